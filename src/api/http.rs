@@ -0,0 +1,178 @@
+// Minimal HTTP/1.1 for the API server
+//
+// Just enough of the protocol for read-only JSON endpoints: one request
+// per connection, no bodies, `Connection: close` on every response.
+
+use std::io::{self, BufRead, Read, Write};
+
+/// Longest request line or header line accepted
+const MAX_LINE: usize = 8 * 1024;
+
+/// Most header lines accepted
+const MAX_HEADERS: usize = 64;
+
+/// A parsed request head
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Request {
+    pub method: String,
+    /// Path without the query string ("/processes/812")
+    pub path: String,
+    /// Query string without the "?" ("" when absent)
+    pub query: String,
+    /// Header names are lowercased
+    pub headers: Vec<(String, String)>,
+}
+
+impl Request {
+    /// Value of header `name` (lowercase)
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Value of query parameter `name`, undecoded
+    pub fn query_param(&self, name: &str) -> Option<&str> {
+        self.query.split('&').find_map(|pair| {
+            pair.split_once('=')
+                .filter(|(key, _)| *key == name)
+                .map(|(_, value)| value)
+        })
+    }
+}
+
+/// Read a request head from `reader`
+pub fn read_request(reader: &mut impl BufRead) -> io::Result<Request> {
+    let invalid = |message: &str| io::Error::new(io::ErrorKind::InvalidData, message.to_string());
+    let request_line = read_line(reader)?;
+    let mut parts = request_line.split_whitespace();
+    let (Some(method), Some(target), Some(version)) = (parts.next(), parts.next(), parts.next())
+    else {
+        return Err(invalid("malformed request line"));
+    };
+    if !version.starts_with("HTTP/1.") {
+        return Err(invalid("unsupported HTTP version"));
+    }
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+
+    let mut headers = Vec::new();
+    loop {
+        let line = read_line(reader)?;
+        if line.is_empty() {
+            break;
+        }
+        if headers.len() == MAX_HEADERS {
+            return Err(invalid("too many headers"));
+        }
+        let (name, value) = line
+            .split_once(':')
+            .ok_or_else(|| invalid("malformed header"))?;
+        headers.push((name.trim().to_ascii_lowercase(), value.trim().to_string()));
+    }
+    Ok(Request {
+        method: method.to_string(),
+        path: path.to_string(),
+        query: query.to_string(),
+        headers,
+    })
+}
+
+/// One CRLF-terminated line, without the line ending
+fn read_line(reader: &mut impl BufRead) -> io::Result<String> {
+    let mut line = Vec::new();
+    (&mut *reader)
+        .take(MAX_LINE as u64 + 2)
+        .read_until(b'\n', &mut line)?;
+    if !line.ends_with(b"\n") {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "request line too long or truncated",
+        ));
+    }
+    let line = String::from_utf8(line)
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "request is not UTF-8"))?;
+    Ok(line.trim_end_matches(['\r', '\n']).to_string())
+}
+
+/// A complete response
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Response {
+    pub status: u16,
+    pub body: String,
+}
+
+impl Response {
+    /// 200 with a JSON body
+    pub fn json(body: String) -> Self {
+        Self { status: 200, body }
+    }
+
+    /// Error status with a `{"error": ...}` body
+    pub fn error(status: u16, message: &str) -> Self {
+        Self {
+            status,
+            body: format!("{{\"error\":{}}}", crate::app::stream::json_string(message)),
+        }
+    }
+}
+
+/// Write `response` as JSON and close the exchange
+pub fn write_response(writer: &mut impl Write, response: &Response) -> io::Result<()> {
+    write!(
+        writer,
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\
+         Cache-Control: no-store\r\nConnection: close\r\n\r\n{}",
+        response.status,
+        reason(response.status),
+        response.body.len(),
+        response.body
+    )?;
+    writer.flush()
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        503 => "Service Unavailable",
+        _ => "Internal Server Error",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_request() {
+        let mut input: &[u8] =
+            b"GET /processes/812?pretty=1 HTTP/1.1\r\nHost: localhost\r\nX-Token: abc\r\n\r\n";
+        let request = read_request(&mut input).unwrap();
+        assert_eq!(request.method, "GET");
+        assert_eq!(request.path, "/processes/812");
+        assert_eq!(request.query, "pretty=1");
+        assert_eq!(
+            request.headers[1],
+            ("x-token".to_string(), "abc".to_string())
+        );
+
+        assert!(read_request(&mut &b"GET /\r\n\r\n"[..]).is_err());
+        assert!(read_request(&mut &b"GET / HTTP/1.1\r\nHost"[..]).is_err());
+    }
+
+    #[test]
+    fn test_write_response() {
+        let mut out = Vec::new();
+        write_response(&mut out, &Response::error(404, "no such path")).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "HTTP/1.1 404 Not Found\r\nContent-Type: application/json\r\nContent-Length: 24\r\n\
+             Cache-Control: no-store\r\nConnection: close\r\n\r\n{\"error\":\"no such path\"}"
+        );
+    }
+}
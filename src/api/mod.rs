@@ -0,0 +1,485 @@
+// Read-only HTTP API (`ntomb serve`)
+//
+// Serves the same collector the TUI uses as JSON, for dashboards and
+// automation:
+//
+// - `GET /connections`     every current connection
+// - `GET /processes/<pid>` one process and its connections
+// - `GET /events`          the Grimoire event log, oldest first
+// - `GET /snapshot`        collection time, backend, connections and events
+// - `GET /ws`              WebSocket feed of snapshot diffs (see ws.rs)
+//
+// The main thread keeps refreshing on the data cadence and publishes each
+// snapshot; every client is answered from the latest one on its own thread,
+// up to `MAX_CLIENTS` at once (more are turned away with a 503).
+// Nothing can be changed through the API. It binds to loopback unless told
+// otherwise, and other addresses need `--token` (or NTOMB_API_TOKEN, kept
+// out of the process list): requests must then carry
+// `Authorization: Bearer <token>` or, for browsers opening a WebSocket,
+// `?token=<token>`. Without a token, only requests naming a loopback host
+// are answered, so a DNS-rebinding page cannot read the API through its
+// own domain name. Browsers let any page open a WebSocket to loopback, so
+// an upgrade from a page (one with an `Origin`) is refused unless the page
+// is served from the listen address or an origin allowed with
+// `--allow-origin`.
+
+mod http;
+mod ws;
+
+use crate::app::events::GrimoireEvent;
+use crate::app::history::event_json;
+use crate::app::stream::{connection_json, json_option, json_string};
+use crate::app::AppState;
+use crate::net::Connection;
+use anyhow::{bail, Context, Result};
+use http::{read_request, write_response, Request, Response};
+use std::io::BufReader;
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Condvar, Mutex, PoisonError};
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// How long a client may take to send its request
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// How long a write may wait on a client that stopped reading; a feed
+/// subscriber that times out is dropped
+const WRITE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Clients served at once, WebSocket subscribers included
+const MAX_CLIENTS: usize = 64;
+
+/// What the API serves: a copy of the latest refresh
+#[derive(Debug, Clone)]
+pub struct Published {
+    pub taken_at: SystemTime,
+    pub backend: &'static str,
+    pub connections: Vec<Connection>,
+    pub events: Vec<GrimoireEvent>,
+    /// Events ever pushed, to tell which of `events` are new
+    pub events_pushed: u64,
+}
+
+impl Published {
+    /// Copy the current state of `app`
+    pub fn capture(app: &AppState) -> Self {
+        Self {
+            taken_at: SystemTime::now(),
+            backend: app.backend_health.backend,
+            connections: app.connections.clone(),
+            events: app.events.iter().cloned().collect(),
+            events_pushed: app.events.pushed(),
+        }
+    }
+}
+
+/// The latest snapshot, with a way to wait for the next one
+#[derive(Debug)]
+pub struct Board {
+    latest: Mutex<Arc<Published>>,
+    updated: Condvar,
+}
+
+impl Board {
+    fn new(published: Published) -> Self {
+        Self {
+            latest: Mutex::new(Arc::new(published)),
+            updated: Condvar::new(),
+        }
+    }
+
+    /// Replace the snapshot and wake everyone waiting for it
+    fn publish(&self, published: Published) {
+        *self.latest.lock().unwrap_or_else(PoisonError::into_inner) = Arc::new(published);
+        self.updated.notify_all();
+    }
+
+    pub fn latest(&self) -> Arc<Published> {
+        Arc::clone(&self.latest.lock().unwrap_or_else(PoisonError::into_inner))
+    }
+
+    /// Block until a snapshot other than `seen` is published
+    pub fn wait_newer(&self, seen: &Arc<Published>) -> Arc<Published> {
+        let mut latest = self.latest.lock().unwrap_or_else(PoisonError::into_inner);
+        while Arc::ptr_eq(&latest, seen) {
+            latest = self
+                .updated
+                .wait(latest)
+                .unwrap_or_else(PoisonError::into_inner);
+        }
+        Arc::clone(&latest)
+    }
+}
+
+/// Client threads running, and how many may
+#[derive(Debug)]
+struct ClientSlots {
+    in_use: AtomicUsize,
+    max: usize,
+}
+
+/// A client thread's place, freed when dropped
+#[derive(Debug)]
+struct ClientSlot(Arc<ClientSlots>);
+
+impl ClientSlots {
+    fn new(max: usize) -> Arc<Self> {
+        Arc::new(Self {
+            in_use: AtomicUsize::new(0),
+            max,
+        })
+    }
+
+    /// A free slot, or None when `max` clients are being served
+    fn take(self: &Arc<Self>) -> Option<ClientSlot> {
+        self.in_use
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |n| {
+                (n < self.max).then_some(n + 1)
+            })
+            .ok()
+            .map(|_| ClientSlot(Arc::clone(self)))
+    }
+}
+
+impl Drop for ClientSlot {
+    fn drop(&mut self) {
+        self.0.in_use.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+/// Who may use the API
+#[derive(Debug)]
+pub struct Access {
+    pub listen: SocketAddr,
+    /// Bearer token every request must present
+    pub token: Option<String>,
+    /// Page origins allowed to open the WebSocket, besides the listen
+    /// address itself
+    pub origins: Vec<String>,
+}
+
+/// Serve `app`'s snapshots on `listen` until the process is stopped;
+/// with a `token`, every request must present it
+pub fn serve(
+    app: &mut AppState,
+    listen: SocketAddr,
+    token: Option<String>,
+    origins: Vec<String>,
+) -> Result<()> {
+    if !listen.ip().is_loopback() && token.is_none() {
+        bail!(
+            "Refusing to serve on {} without --token or NTOMB_API_TOKEN; the API exposes every connection on this host",
+            listen
+        );
+    }
+    let listener =
+        TcpListener::bind(listen).with_context(|| format!("Cannot listen on {}", listen))?;
+    eprintln!("ntomb: serving on http://{}", listener.local_addr()?);
+
+    let board = Arc::new(Board::new(Published::capture(app)));
+    let shared = Arc::clone(&board);
+    let access = Arc::new(Access {
+        listen,
+        token,
+        origins,
+    });
+    let slots = ClientSlots::new(MAX_CLIENTS);
+    thread::spawn(move || {
+        for mut stream in listener.incoming().flatten() {
+            let Some(slot) = slots.take() else {
+                // Answered right away, before reading anything, so idle
+                // clients cannot hold up the accept loop
+                let _ = stream.set_write_timeout(Some(Duration::from_secs(1)));
+                let busy = Response::error(503, "too many clients; try again later");
+                let _ = write_response(&mut stream, &busy);
+                continue;
+            };
+            let board = Arc::clone(&shared);
+            let access = Arc::clone(&access);
+            thread::spawn(move || {
+                let _slot = slot;
+                handle_client(stream, &board, &access);
+            });
+        }
+    });
+
+    loop {
+        thread::sleep(app.refresh_config.data_interval());
+        app.refresh_connections();
+        board.publish(Published::capture(app));
+    }
+}
+
+/// Answer one request; I/O errors just drop the client
+fn handle_client(stream: TcpStream, board: &Board, access: &Access) {
+    let _ = stream.set_read_timeout(Some(REQUEST_TIMEOUT));
+    let Ok(mut writer) = stream.try_clone() else {
+        return;
+    };
+    if writer.set_write_timeout(Some(WRITE_TIMEOUT)).is_err() {
+        return;
+    }
+    let response = match read_request(&mut BufReader::new(stream)) {
+        Ok(request) if access.token.is_none() && !loopback_host(&request, access.listen) => {
+            Response::error(403, "without a token, only loopback hosts are served")
+        }
+        Ok(request) if !authorized(&request, access.token.as_deref()) => {
+            Response::error(401, "missing or wrong token")
+        }
+        Ok(request) if request.path == "/ws" => match ws::accept(&request, access) {
+            Ok(accept) => {
+                let _ = ws::feed(&mut writer, &accept, board);
+                return;
+            }
+            Err(response) => response,
+        },
+        Ok(request) => route(&request, &board.latest()),
+        Err(e) => Response::error(400, &e.to_string()),
+    };
+    let _ = write_response(&mut writer, &response);
+}
+
+/// Whether `request` carries `token` (always, when there is none) as a
+/// bearer token or a `token` query parameter
+fn authorized(request: &Request, token: Option<&str>) -> bool {
+    let Some(token) = token else {
+        return true;
+    };
+    let bearer = request
+        .header("authorization")
+        .and_then(|value| value.strip_prefix("Bearer "));
+    [bearer, request.query_param("token")]
+        .into_iter()
+        .flatten()
+        .any(|given| same_token(given.trim(), token))
+}
+
+/// Whether the `Host` of `request` is a loopback name or IP, or the
+/// exact `listen` address
+fn loopback_host(request: &Request, listen: SocketAddr) -> bool {
+    let Some(host) = request.header("host") else {
+        return false;
+    };
+    if host == listen.to_string() {
+        return true;
+    }
+    // "[::1]:8686", "127.0.0.1:8686", "localhost"
+    let name = match host.strip_prefix('[') {
+        Some(rest) => rest.split(']').next().unwrap_or_default(),
+        None => host.rsplit_once(':').map_or(host, |(name, _)| name),
+    };
+    name.eq_ignore_ascii_case("localhost")
+        || name
+            .parse::<std::net::IpAddr>()
+            .is_ok_and(|ip| ip.is_loopback())
+}
+
+/// Compare without stopping at the first difference
+fn same_token(given: &str, expected: &str) -> bool {
+    given.len() == expected.len()
+        && given
+            .bytes()
+            .zip(expected.bytes())
+            .fold(0, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+/// Response to `request` from `published`
+fn route(request: &Request, published: &Published) -> Response {
+    if request.method != "GET" {
+        return Response::error(405, "the API is read-only; use GET");
+    }
+    let path = request.path.trim_end_matches('/');
+    match path.split('/').collect::<Vec<_>>().as_slice() {
+        ["", "connections"] => Response::json(connections_json(&published.connections)),
+        ["", "processes", pid] => match pid.parse::<i32>() {
+            Ok(pid) => process_json(published, pid)
+                .map(Response::json)
+                .unwrap_or_else(|| Response::error(404, "no connections for that PID")),
+            Err(_) => Response::error(400, "PID must be a number"),
+        },
+        ["", "events"] => Response::json(events_json(&published.events)),
+        ["", "snapshot"] => Response::json(format!(
+            "{{\"taken_at\":{},\"backend\":{},\"connections\":{},\"events\":{}}}",
+            unix_millis(published.taken_at),
+            json_string(published.backend),
+            connections_json(&published.connections),
+            events_json(&published.events)
+        )),
+        _ => Response::error(
+            404,
+            "unknown path; try /connections, /processes/<pid>, /events, /snapshot or /ws",
+        ),
+    }
+}
+
+fn connections_json<'a>(conns: impl IntoIterator<Item = &'a Connection>) -> String {
+    let items: Vec<String> = conns.into_iter().map(connection_json).collect();
+    format!("[{}]", items.join(","))
+}
+
+/// `{"pid":..,"name":..,"connections":[..]}`, or None when `pid` owns no
+/// connection
+fn process_json(published: &Published, pid: i32) -> Option<String> {
+    let conns: Vec<&Connection> = published
+        .connections
+        .iter()
+        .filter(|c| c.pid == Some(pid))
+        .collect();
+    let name = conns.first()?.process_name.as_deref();
+    Some(format!(
+        "{{\"pid\":{},\"name\":{},\"connections\":{}}}",
+        pid,
+        json_option(name),
+        connections_json(conns)
+    ))
+}
+
+fn events_json(events: &[GrimoireEvent]) -> String {
+    let items: Vec<String> = events.iter().map(event_json).collect();
+    format!("[{}]", items.join(","))
+}
+
+fn unix_millis(time: SystemTime) -> u128 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::events::EventSeverity;
+    use crate::net::ConnectionState;
+
+    fn published() -> Published {
+        let curl = Connection {
+            local_addr: "10.0.0.5".into(),
+            local_port: 51000,
+            remote_addr: "93.184.216.34".into(),
+            remote_port: 443,
+            state: ConnectionState::Established,
+            pid: Some(812),
+            process_name: Some("curl".into()),
+            ..Default::default()
+        };
+        let mut event = GrimoireEvent::new(EventSeverity::Warning, "Haunted").with_pid(Some(812));
+        event.timestamp = UNIX_EPOCH + Duration::from_millis(1_500);
+        Published {
+            taken_at: UNIX_EPOCH + Duration::from_millis(2_000),
+            backend: "netstat2",
+            connections: vec![curl, Connection::default()],
+            events: vec![event],
+            events_pushed: 1,
+        }
+    }
+
+    fn get(path: &str) -> Response {
+        let request = Request {
+            method: "GET".to_string(),
+            path: path.to_string(),
+            query: String::new(),
+            headers: Vec::new(),
+        };
+        route(&request, &published())
+    }
+
+    #[test]
+    fn test_routes() {
+        let response = get("/connections");
+        assert_eq!(response.status, 200);
+        assert!(response.body.starts_with("[{\"local_addr\":\"10.0.0.5\""));
+
+        let response = get("/processes/812/");
+        assert_eq!(response.status, 200);
+        assert!(response
+            .body
+            .starts_with("{\"pid\":812,\"name\":\"curl\",\"connections\":[{"));
+        assert_eq!(get("/processes/1").status, 404);
+        assert_eq!(get("/processes/curl").status, 400);
+
+        assert_eq!(
+            get("/events").body,
+            "[{\"ts\":1500,\"severity\":\"WARN\",\"message\":\"Haunted\",\"pid\":812,\"remote_addr\":null}]"
+        );
+        assert!(get("/snapshot")
+            .body
+            .starts_with("{\"taken_at\":2000,\"backend\":\"netstat2\",\"connections\":[{"));
+        assert_eq!(get("/").status, 404);
+    }
+
+    #[test]
+    fn test_read_only() {
+        let request = Request {
+            method: "DELETE".to_string(),
+            path: "/connections".to_string(),
+            query: String::new(),
+            headers: Vec::new(),
+        };
+        assert_eq!(route(&request, &published()).status, 405);
+    }
+
+    #[test]
+    fn test_authorized() {
+        let request = |header: Option<&str>, query: &str| Request {
+            method: "GET".to_string(),
+            path: "/ws".to_string(),
+            query: query.to_string(),
+            headers: header
+                .map(|value| vec![("authorization".to_string(), value.to_string())])
+                .unwrap_or_default(),
+        };
+        assert!(authorized(&request(None, ""), None));
+        assert!(!authorized(&request(None, ""), Some("s3cret")));
+        assert!(authorized(
+            &request(Some("Bearer s3cret"), ""),
+            Some("s3cret")
+        ));
+        assert!(!authorized(
+            &request(Some("Bearer s3cre"), ""),
+            Some("s3cret")
+        ));
+        assert!(authorized(
+            &request(None, "a=1&token=s3cret"),
+            Some("s3cret")
+        ));
+        assert!(!authorized(&request(None, "token=nope"), Some("s3cret")));
+    }
+
+    #[test]
+    fn test_loopback_host() {
+        let listen: SocketAddr = "127.0.0.1:8686".parse().unwrap();
+        let request = |host: Option<&str>| Request {
+            method: "GET".to_string(),
+            path: "/connections".to_string(),
+            query: String::new(),
+            headers: host
+                .map(|value| vec![("host".to_string(), value.to_string())])
+                .unwrap_or_default(),
+        };
+        for host in [
+            "127.0.0.1:8686",
+            "localhost:8686",
+            "LOCALHOST",
+            "[::1]:8686",
+            "127.0.0.2",
+        ] {
+            assert!(loopback_host(&request(Some(host)), listen), "{}", host);
+        }
+        // A rebound name still carries the attacker's domain
+        assert!(!loopback_host(&request(Some("evil.example:8686")), listen));
+        assert!(!loopback_host(&request(Some("10.0.0.5:8686")), listen));
+        assert!(!loopback_host(&request(None), listen));
+    }
+
+    #[test]
+    fn test_client_slots() {
+        let slots = ClientSlots::new(2);
+        let first = slots.take().unwrap();
+        let _second = slots.take().unwrap();
+        assert!(slots.take().is_none());
+        drop(first);
+        assert!(slots.take().is_some());
+    }
+}
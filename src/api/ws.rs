@@ -0,0 +1,330 @@
+// WebSocket live feed (`GET /ws`)
+//
+// A subscriber first gets the full picture, then one message per refresh
+// that changed something:
+//
+// ```text
+// {"type":"snapshot","taken_at":1760000000123,"backend":"netstat2","connections":[..],"events":[..]}
+// {"type":"diff","taken_at":1760000002130,"changes":[{"event":"open",...}],"events":[..]}
+// ```
+//
+// `changes` holds the same objects as `--json --watch` and `events` the
+// Grimoire events added since the previous message, so applying each diff
+// to the snapshot mirrors the graveyard. Refreshes without changes send a
+// ping instead, which is how dead subscribers are noticed. Only
+// server-to-client frames are sent (RFC 6455); anything the client sends
+// is ignored and the feed ends when a write fails or times out (a
+// subscriber that stopped reading).
+//
+// Browsers send the page's `Origin` with every upgrade and let any page
+// connect, so an upgrade with an `Origin` other than the listen address or
+// an `--allow-origin` is refused: otherwise any site the user visits could
+// read the feed from a token-less loopback server.
+
+use super::http::{Request, Response};
+use super::{connections_json, events_json, unix_millis, Access, Board, Published};
+use crate::app::clipboard::base64_encode;
+use crate::app::stream::{connection_events, event_json, json_string};
+use std::io::{self, Write};
+
+/// Appended to the client's key before hashing (RFC 6455 section 1.3)
+const ACCEPT_GUID: &str = "258EAFA5-E914-47DA-95CA-C5AB0DC85B11";
+
+const OPCODE_TEXT: u8 = 0x1;
+const OPCODE_PING: u8 = 0x9;
+
+/// The Sec-WebSocket-Accept value for a valid upgrade request, or the
+/// error response to send instead
+pub fn accept(request: &Request, access: &Access) -> Result<String, Response> {
+    if request.method != "GET" {
+        return Err(Response::error(405, "the API is read-only; use GET"));
+    }
+    if let Some(origin) = request.header("origin") {
+        if !origin_allowed(origin, access) {
+            return Err(Response::error(403, "WebSocket origin not allowed"));
+        }
+    }
+    let upgrade = request
+        .header("upgrade")
+        .is_some_and(|value| value.eq_ignore_ascii_case("websocket"));
+    if !upgrade {
+        return Err(Response::error(400, "/ws expects a WebSocket upgrade"));
+    }
+    if request.header("sec-websocket-version") != Some("13") {
+        return Err(Response::error(400, "unsupported WebSocket version"));
+    }
+    let key = request
+        .header("sec-websocket-key")
+        .ok_or_else(|| Response::error(400, "missing Sec-WebSocket-Key"))?;
+    Ok(accept_key(key))
+}
+
+/// Whether a page from `origin` ("http://127.0.0.1:8686") may subscribe:
+/// one served from the listen address (or `localhost` on its port, when
+/// listening on loopback), or one allowed explicitly
+fn origin_allowed(origin: &str, access: &Access) -> bool {
+    let origin = origin.trim_end_matches('/');
+    if access
+        .origins
+        .iter()
+        .any(|allowed| allowed.trim_end_matches('/').eq_ignore_ascii_case(origin))
+    {
+        return true;
+    }
+    let Some(authority) = origin
+        .strip_prefix("http://")
+        .or_else(|| origin.strip_prefix("https://"))
+    else {
+        return false;
+    };
+    let listen = access.listen;
+    authority == listen.to_string()
+        || (listen.ip().is_loopback()
+            && authority.eq_ignore_ascii_case(&format!("localhost:{}", listen.port())))
+}
+
+/// Finish the handshake, then stream `board` until the client goes away
+pub fn feed(writer: &mut impl Write, accept: &str, board: &Board) -> io::Result<()> {
+    write!(
+        writer,
+        "HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\nConnection: Upgrade\r\n\
+         Sec-WebSocket-Accept: {}\r\n\r\n",
+        accept
+    )?;
+    let mut sent = board.latest();
+    write_frame(writer, OPCODE_TEXT, snapshot_message(&sent).as_bytes())?;
+    loop {
+        let next = board.wait_newer(&sent);
+        match diff_message(&sent, &next) {
+            Some(message) => write_frame(writer, OPCODE_TEXT, message.as_bytes())?,
+            None => write_frame(writer, OPCODE_PING, &[])?,
+        }
+        sent = next;
+    }
+}
+
+fn snapshot_message(published: &Published) -> String {
+    format!(
+        "{{\"type\":\"snapshot\",\"taken_at\":{},\"backend\":{},\"connections\":{},\"events\":{}}}",
+        unix_millis(published.taken_at),
+        json_string(published.backend),
+        connections_json(&published.connections),
+        events_json(&published.events)
+    )
+}
+
+/// What changed from `previous` to `next`; None when nothing did
+fn diff_message(previous: &Published, next: &Published) -> Option<String> {
+    let changes: Vec<String> = connection_events(&previous.connections, &next.connections)
+        .into_iter()
+        .map(|(event, conn)| event_json(event, conn))
+        .collect();
+    let added = next.events_pushed.saturating_sub(previous.events_pushed);
+    let added = (added as usize).min(next.events.len());
+    let events = &next.events[next.events.len() - added..];
+    if changes.is_empty() && events.is_empty() {
+        return None;
+    }
+    Some(format!(
+        "{{\"type\":\"diff\",\"taken_at\":{},\"changes\":[{}],\"events\":{}}}",
+        unix_millis(next.taken_at),
+        changes.join(","),
+        events_json(events)
+    ))
+}
+
+/// One unmasked, unfragmented frame
+fn write_frame(writer: &mut impl Write, opcode: u8, payload: &[u8]) -> io::Result<()> {
+    let mut head = vec![0x80 | opcode];
+    match payload.len() {
+        len @ 0..=125 => head.push(len as u8),
+        len @ 126..=0xffff => {
+            head.push(126);
+            head.extend_from_slice(&(len as u16).to_be_bytes());
+        }
+        len => {
+            head.push(127);
+            head.extend_from_slice(&(len as u64).to_be_bytes());
+        }
+    }
+    writer.write_all(&head)?;
+    writer.write_all(payload)?;
+    writer.flush()
+}
+
+/// base64(SHA-1(key + GUID))
+fn accept_key(key: &str) -> String {
+    base64_encode(&sha1(format!("{}{}", key, ACCEPT_GUID).as_bytes()))
+}
+
+/// SHA-1 digest (FIPS 180-4); only used for the handshake
+fn sha1(data: &[u8]) -> [u8; 20] {
+    let mut state: [u32; 5] = [0x67452301, 0xEFCDAB89, 0x98BADCFE, 0x10325476, 0xC3D2E1F0];
+    let mut message = data.to_vec();
+    message.push(0x80);
+    while message.len() % 64 != 56 {
+        message.push(0);
+    }
+    message.extend_from_slice(&(data.len() as u64 * 8).to_be_bytes());
+
+    for block in message.chunks(64) {
+        let mut w = [0u32; 80];
+        for (word, bytes) in w.iter_mut().zip(block.chunks(4)) {
+            *word = u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+        }
+        for i in 16..80 {
+            w[i] = (w[i - 3] ^ w[i - 8] ^ w[i - 14] ^ w[i - 16]).rotate_left(1);
+        }
+        let [mut a, mut b, mut c, mut d, mut e] = state;
+        for (i, word) in w.iter().enumerate() {
+            let (f, k) = match i {
+                0..=19 => ((b & c) | (!b & d), 0x5A827999),
+                20..=39 => (b ^ c ^ d, 0x6ED9EBA1),
+                40..=59 => ((b & c) | (b & d) | (c & d), 0x8F1BBCDC),
+                _ => (b ^ c ^ d, 0xCA62C1D6),
+            };
+            let temp = a
+                .rotate_left(5)
+                .wrapping_add(f)
+                .wrapping_add(e)
+                .wrapping_add(k)
+                .wrapping_add(*word);
+            e = d;
+            d = c;
+            c = b.rotate_left(30);
+            b = a;
+            a = temp;
+        }
+        for (value, add) in state.iter_mut().zip([a, b, c, d, e]) {
+            *value = value.wrapping_add(add);
+        }
+    }
+
+    let mut digest = [0u8; 20];
+    for (bytes, value) in digest.chunks_mut(4).zip(state) {
+        bytes.copy_from_slice(&value.to_be_bytes());
+    }
+    digest
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::events::{EventSeverity, GrimoireEvent};
+    use crate::net::{Connection, ConnectionState};
+    use std::time::{Duration, UNIX_EPOCH};
+
+    fn access() -> Access {
+        Access {
+            listen: "127.0.0.1:8686".parse().unwrap(),
+            token: None,
+            origins: vec!["https://dash.example".to_string()],
+        }
+    }
+
+    fn upgrade_request(key: Option<&str>) -> Request {
+        let mut headers = vec![
+            ("upgrade".to_string(), "websocket".to_string()),
+            ("sec-websocket-version".to_string(), "13".to_string()),
+        ];
+        if let Some(key) = key {
+            headers.push(("sec-websocket-key".to_string(), key.to_string()));
+        }
+        Request {
+            method: "GET".to_string(),
+            path: "/ws".to_string(),
+            query: String::new(),
+            headers,
+        }
+    }
+
+    #[test]
+    fn test_accept() {
+        let hex: String = sha1(b"abc").iter().map(|b| format!("{:02x}", b)).collect();
+        assert_eq!(hex, "a9993e364706816aba3e25717850c26c9cd0d89d");
+        // Example handshake from RFC 6455 section 1.3
+        assert_eq!(
+            accept(
+                &upgrade_request(Some("dGhlIHNhbXBsZSBub25jZQ==")),
+                &access()
+            ),
+            Ok("s3pPLMBiTxaQ9kYGzzhZRbK+xOo=".to_string())
+        );
+        assert_eq!(
+            accept(&upgrade_request(None), &access())
+                .unwrap_err()
+                .status,
+            400
+        );
+        let mut plain = upgrade_request(Some("x"));
+        plain.headers.clear();
+        assert_eq!(accept(&plain, &access()).unwrap_err().status, 400);
+    }
+
+    #[test]
+    fn test_foreign_origin_is_refused() {
+        let from = |origin: &str| {
+            let mut request = upgrade_request(Some("dGhlIHNhbXBsZSBub25jZQ=="));
+            request
+                .headers
+                .push(("origin".to_string(), origin.to_string()));
+            accept(&request, &access()).map_err(|response| response.status)
+        };
+        assert_eq!(from("https://evil.example"), Err(403));
+        assert_eq!(from("null"), Err(403));
+        assert_eq!(from("http://127.0.0.1:9999"), Err(403));
+        assert!(from("http://127.0.0.1:8686").is_ok());
+        assert!(from("http://localhost:8686").is_ok());
+        assert!(from("https://dash.example/").is_ok());
+    }
+
+    #[test]
+    fn test_write_frame() {
+        let mut out = Vec::new();
+        write_frame(&mut out, OPCODE_TEXT, b"hi").unwrap();
+        assert_eq!(out, b"\x81\x02hi");
+        let mut out = Vec::new();
+        write_frame(&mut out, OPCODE_TEXT, &[b'x'; 300]).unwrap();
+        assert_eq!(&out[..4], &[0x81, 126, 0x01, 0x2c]);
+        assert_eq!(out.len(), 304);
+    }
+
+    #[test]
+    fn test_diff_message() {
+        let conn = |remote_port| Connection {
+            remote_addr: "93.184.216.34".into(),
+            remote_port,
+            state: ConnectionState::Established,
+            ..Default::default()
+        };
+        let event = |message: &str| {
+            let mut event = GrimoireEvent::new(EventSeverity::Info, message);
+            event.timestamp = UNIX_EPOCH;
+            event
+        };
+        let previous = Published {
+            taken_at: UNIX_EPOCH,
+            backend: "netstat2",
+            connections: vec![conn(443)],
+            events: vec![event("a")],
+            events_pushed: 1,
+        };
+        assert_eq!(diff_message(&previous, &previous), None);
+
+        let next = Published {
+            taken_at: UNIX_EPOCH + Duration::from_millis(2_000),
+            connections: vec![conn(80)],
+            events: vec![event("a"), event("b")],
+            events_pushed: 2,
+            ..previous.clone()
+        };
+        let message = diff_message(&previous, &next).unwrap();
+        assert!(message.starts_with(
+            "{\"type\":\"diff\",\"taken_at\":2000,\"changes\":[{\"event\":\"open\",\"local_addr\""
+        ));
+        assert!(message.contains("},{\"event\":\"close\","));
+        assert!(message.ends_with(
+            "\"events\":[{\"ts\":0,\"severity\":\"INFO\",\"message\":\"b\",\"pid\":null,\"remote_addr\":null}]}"
+        ));
+    }
+}
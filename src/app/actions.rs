@@ -0,0 +1,170 @@
+// Investigation commands for the selected connection
+//
+// ntomb never acts on the system itself; instead the actions popup (`x`)
+// offers ready-made shell commands for the usual next step, which are
+// copied to the clipboard for the user to run.
+
+use crate::net::scope::{address_scope, AddressScope};
+use crate::net::{Connection, ConnectionState};
+
+/// A suggested shell command
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvestigationAction {
+    /// What the command does ("Capture packets")
+    pub label: &'static str,
+    /// Command line to copy
+    pub command: String,
+}
+
+/// State of the open actions popup
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ActionsPopup {
+    /// Connection the commands were built for ("1.2.3.4:443")
+    pub target: String,
+    pub actions: Vec<InvestigationAction>,
+    pub selected: usize,
+}
+
+impl ActionsPopup {
+    /// Popup listing the commands for `conn`
+    pub fn for_connection(conn: &Connection) -> Self {
+        let target = if conn.state == ConnectionState::Listen {
+            format!("{}:{}", conn.local_addr, conn.local_port)
+        } else {
+            format!("{}:{}", conn.remote_addr, conn.remote_port)
+        };
+        Self {
+            target,
+            actions: actions_for(conn),
+            selected: 0,
+        }
+    }
+
+    pub fn select_previous(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+
+    pub fn select_next(&mut self) {
+        if self.selected + 1 < self.actions.len() {
+            self.selected += 1;
+        }
+    }
+
+    /// Currently highlighted action
+    pub fn current(&self) -> Option<&InvestigationAction> {
+        self.actions.get(self.selected)
+    }
+}
+
+/// Commands worth running next for `conn`
+///
+/// `kill` needs a known owner and `whois` a public remote address, so
+/// those are only offered when they make sense.
+pub fn actions_for(conn: &Connection) -> Vec<InvestigationAction> {
+    let listening = conn.state == ConnectionState::Listen;
+    let mut actions = Vec::new();
+
+    let ss = if listening {
+        format!("ss -tlnp 'sport = :{}'", conn.local_port)
+    } else {
+        format!(
+            "ss -tnp 'src {} and dst {}'",
+            ss_endpoint(&conn.local_addr, conn.local_port),
+            ss_endpoint(&conn.remote_addr, conn.remote_port)
+        )
+    };
+    actions.push(InvestigationAction {
+        label: "Show socket (ss)",
+        command: ss,
+    });
+
+    let tcpdump = if listening {
+        format!("tcpdump -nn -i any 'tcp port {}'", conn.local_port)
+    } else {
+        format!(
+            "tcpdump -nn -i any 'host {} and tcp port {} and tcp port {}'",
+            conn.remote_addr, conn.remote_port, conn.local_port
+        )
+    };
+    actions.push(InvestigationAction {
+        label: "Capture packets (tcpdump)",
+        command: tcpdump,
+    });
+
+    if let Some(pid) = conn.pid {
+        actions.push(InvestigationAction {
+            label: "Terminate owner (kill)",
+            command: format!("kill {}", pid),
+        });
+    }
+
+    if !listening && is_public(&conn.remote_addr) {
+        actions.push(InvestigationAction {
+            label: "Look up owner (whois)",
+            command: format!("whois {}", conn.remote_addr),
+        });
+    }
+
+    actions
+}
+
+/// `addr:port` as ss expects it (IPv6 in brackets)
+pub(crate) fn ss_endpoint(addr: &str, port: u16) -> String {
+    if addr.contains(':') {
+        format!("[{}]:{}", addr, port)
+    } else {
+        format!("{}:{}", addr, port)
+    }
+}
+
+/// Whether `addr` is a routable address worth a whois query
+fn is_public(addr: &str) -> bool {
+    address_scope(addr) == Some(AddressScope::Public)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_actions_for_established() {
+        let conn = Connection {
+            local_addr: "10.0.0.5".into(),
+            local_port: 51234,
+            remote_addr: "93.184.216.34".into(),
+            remote_port: 443,
+            state: ConnectionState::Established,
+            pid: Some(812),
+            ..Default::default()
+        };
+        let commands: Vec<_> = actions_for(&conn).into_iter().map(|a| a.command).collect();
+        assert_eq!(
+            commands,
+            vec![
+                "ss -tnp 'src 10.0.0.5:51234 and dst 93.184.216.34:443'",
+                "tcpdump -nn -i any 'host 93.184.216.34 and tcp port 443 and tcp port 51234'",
+                "kill 812",
+                "whois 93.184.216.34",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_actions_for_private_listener() {
+        let conn = Connection {
+            local_addr: "::".into(),
+            local_port: 22,
+            remote_addr: "::".into(),
+            state: ConnectionState::Listen,
+            ..Default::default()
+        };
+        let popup = ActionsPopup::for_connection(&conn);
+        assert_eq!(popup.target, ":::22");
+        let commands: Vec<_> = popup.actions.iter().map(|a| a.command.as_str()).collect();
+        assert_eq!(
+            commands,
+            vec!["ss -tlnp 'sport = :22'", "tcpdump -nn -i any 'tcp port 22'"]
+        );
+        assert_eq!(ss_endpoint("fe80::1", 80), "[fe80::1]:80");
+    }
+}
@@ -0,0 +1,178 @@
+// Activity history per scope
+//
+// The Soul Inspector's activity sparkline follows the current selection:
+// the focused process in Process mode, else the selected connection's
+// endpoint, else the whole host. Each scope keeps its own history, so
+// moving the selection shows that scope's past instead of one line that
+// mixes them all. The host, every process and every remote address with
+// live connections, and every pinned endpoint are sampled on each tick;
+// a process or unpinned endpoint is forgotten once its connections are
+// gone.
+//
+// The activity score tracks connection activity with natural variation:
+// - Number of ESTABLISHED connections (weighted heavily)
+// - Number of LISTEN sockets (weighted moderately)
+// - Active state connections (SYN, FIN, etc.)
+// - Adds subtle pulse variation for visual interest
+//
+// This provides meaningful visualization without requiring BPF/eBPF
+// infrastructure for actual byte-level traffic monitoring.
+
+use crate::net::{Connection, ConnectionState};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
+/// Samples kept per scope (one per tick)
+pub const ACTIVITY_SAMPLES: usize = 60;
+
+/// What the activity sparkline shows
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ActivityScope {
+    Host,
+    /// A process (with its children while they are included)
+    Process(i32),
+    /// A remote address
+    Endpoint(Arc<str>),
+}
+
+impl fmt::Display for ActivityScope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ActivityScope::Host => f.write_str("Host"),
+            ActivityScope::Process(pid) => write!(f, "PID {}", pid),
+            ActivityScope::Endpoint(addr) => f.write_str(addr),
+        }
+    }
+}
+
+/// Activity samples of every scope, oldest first
+#[derive(Debug, Clone)]
+pub struct ActivityHistory {
+    scopes: HashMap<ActivityScope, Vec<u64>>,
+}
+
+impl Default for ActivityHistory {
+    fn default() -> Self {
+        let mut scopes = HashMap::new();
+        scopes.insert(ActivityScope::Host, vec![0; ACTIVITY_SAMPLES]);
+        Self { scopes }
+    }
+}
+
+impl ActivityHistory {
+    /// Samples of `scope`; empty for a scope not sampled
+    pub fn samples(&self, scope: &ActivityScope) -> &[u64] {
+        self.scopes.get(scope).map_or(&[], Vec::as_slice)
+    }
+
+    /// Add one sample per scope in `samples`; scopes left out are
+    /// forgotten, except the host
+    pub fn record(&mut self, samples: HashMap<ActivityScope, u64>) {
+        let mut scopes = HashMap::with_capacity(samples.len() + 1);
+        if !samples.contains_key(&ActivityScope::Host) {
+            if let Some(host) = self.scopes.remove(&ActivityScope::Host) {
+                scopes.insert(ActivityScope::Host, host);
+            }
+        }
+        for (scope, value) in samples {
+            // A new scope starts flat, so every sparkline spans the window
+            let mut history = self
+                .scopes
+                .remove(&scope)
+                .unwrap_or_else(|| vec![0; ACTIVITY_SAMPLES]);
+            history.remove(0);
+            history.push(value);
+            scopes.insert(scope, history);
+        }
+        self.scopes = scopes;
+    }
+}
+
+/// Activity score (5-100) of `conns` at tick `tick`
+pub fn activity_score(conns: &[&Connection], tick: u64) -> u64 {
+    // Calculate activity score based on real connection data
+    let established_count = conns
+        .iter()
+        .filter(|c| c.state == ConnectionState::Established)
+        .count();
+
+    let listen_count = conns
+        .iter()
+        .filter(|c| c.state == ConnectionState::Listen)
+        .count();
+
+    let active_states = conns
+        .iter()
+        .filter(|c| {
+            matches!(
+                c.state,
+                ConnectionState::SynSent
+                    | ConnectionState::SynRecv
+                    | ConnectionState::FinWait1
+                    | ConnectionState::FinWait2
+                    | ConnectionState::Closing
+            )
+        })
+        .count();
+
+    // Calculate base activity score (0-100 scale)
+    // - Each ESTABLISHED connection contributes 5 points (max 50)
+    // - Each LISTEN socket contributes 2 points (max 20)
+    // - Each active state connection contributes 10 points (max 30)
+    let established_score = (established_count * 5).min(50) as i64;
+    let listen_score = (listen_count * 2).min(20) as i64;
+    let active_score = (active_states * 10).min(30) as i64;
+
+    // Base activity level (minimum visibility)
+    let base_activity: i64 = if conns.is_empty() { 5 } else { 10 };
+
+    // Calculate base value
+    let base_value = base_activity + established_score + listen_score + active_score;
+
+    // Add natural variation using the tick for visual interest
+    // This creates a subtle "heartbeat" effect even when connections are stable
+    let t = tick as f32 * 0.15;
+    let variation = ((t.sin() * 8.0) + (t * 1.7).cos() * 4.0) as i64;
+
+    // Total score clamped to 5-100 (never fully empty for visibility)
+    (base_value + variation).clamp(5, 100) as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scopes_keep_their_own_history() {
+        let mut history = ActivityHistory::default();
+        let endpoint = ActivityScope::Endpoint("93.184.216.34".into());
+        history.record(HashMap::from([
+            (ActivityScope::Host, 40),
+            (ActivityScope::Process(812), 30),
+            (endpoint.clone(), 20),
+        ]));
+        history.record(HashMap::from([
+            (ActivityScope::Host, 45),
+            (ActivityScope::Process(812), 35),
+        ]));
+
+        let process = history.samples(&ActivityScope::Process(812));
+        assert_eq!(process.len(), ACTIVITY_SAMPLES);
+        assert_eq!(process[ACTIVITY_SAMPLES - 2..], [30, 35]);
+        assert_eq!(
+            history.samples(&ActivityScope::Host)[ACTIVITY_SAMPLES - 2..],
+            [40, 45]
+        );
+        // The endpoint was not sampled again: forgotten
+        assert!(history.samples(&endpoint).is_empty());
+
+        // The host is never forgotten
+        history.record(HashMap::new());
+        assert_eq!(
+            history.samples(&ActivityScope::Host).len(),
+            ACTIVITY_SAMPLES
+        );
+        assert_eq!(ActivityScope::Process(812).to_string(), "PID 812");
+    }
+}
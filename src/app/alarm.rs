@@ -0,0 +1,169 @@
+// Alert alarms
+//
+// A backgrounded tmux pane or a terminal on another screen hides new
+// Grimoire events. Alarms make them noticeable: the terminal bell (tmux
+// flags the window, most terminals flash or beep) and/or the banner drawn
+// in inverted colors for a second. Each is enabled per severity:
+//
+// ```toml
+// [alarm]
+// bell = ["critical"]               # default: no bell
+// flash = ["warning", "critical"]   # default: no flash
+// ```
+
+use super::config::ALARM_FLASH_DURATION;
+use super::events::{EventLog, EventSeverity};
+use serde::Deserialize;
+use std::time::Instant;
+
+/// `[alarm]` section of config.toml
+#[derive(Debug, Clone, Default, Deserialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct AlarmConfig {
+    /// Severities that ring the terminal bell
+    pub bell: Vec<String>,
+    /// Severities that invert the banner colors
+    pub flash: Vec<String>,
+}
+
+/// Watches the event log and raises the configured alarms
+#[derive(Debug, Clone, Default)]
+pub struct Alarm {
+    bell: Vec<EventSeverity>,
+    flash: Vec<EventSeverity>,
+    /// EventLog::pushed() at the last check
+    seen_events: u64,
+    flash_until: Option<Instant>,
+    /// A bell is due with the next frame
+    bell_pending: bool,
+}
+
+impl Alarm {
+    /// Alarm for `config`; fails on an unknown severity name
+    pub fn from_config(config: &AlarmConfig) -> Result<Self, String> {
+        let severities = |names: &[String], key: &str| {
+            names
+                .iter()
+                .map(|name| {
+                    EventSeverity::from_name(name)
+                        .ok_or_else(|| format!("{}: unknown severity {:?}", key, name))
+                })
+                .collect::<Result<Vec<_>, _>>()
+        };
+        Ok(Self {
+            bell: severities(&config.bell, "bell")?,
+            flash: severities(&config.flash, "flash")?,
+            ..Default::default()
+        })
+    }
+
+    /// Take over the severities of `other` (a reloaded config) without
+    /// treating the events already seen as new
+    pub fn reconfigure(&mut self, other: Alarm) {
+        self.bell = other.bell;
+        self.flash = other.flash;
+    }
+
+    /// Check the events added since the last call: ring the bell or start
+    /// the flash for those of an alarmed severity; returns true when the
+    /// flash started or ended (a redraw is due)
+    pub fn observe(&mut self, events: &EventLog, now: Instant) -> bool {
+        let added = events.pushed().saturating_sub(self.seen_events) as usize;
+        self.seen_events = events.pushed();
+        let mut flash = false;
+        for event in events.iter().rev().take(added) {
+            self.bell_pending |= self.bell.contains(&event.severity);
+            flash |= self.flash.contains(&event.severity);
+        }
+        if flash {
+            self.flash_until = Some(now + ALARM_FLASH_DURATION);
+            return true;
+        }
+        if self.flash_until.is_some_and(|until| now >= until) {
+            self.flash_until = None;
+            return true;
+        }
+        false
+    }
+
+    /// Whether the banner is drawn inverted
+    pub fn flashing(&self) -> bool {
+        self.flash_until.is_some()
+    }
+
+    /// Whether the bell should ring now; clears the request
+    pub fn take_bell(&mut self) -> bool {
+        std::mem::take(&mut self.bell_pending)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::events::GrimoireEvent;
+
+    fn alarm(bell: &[&str], flash: &[&str]) -> Alarm {
+        Alarm::from_config(&AlarmConfig {
+            bell: bell.iter().map(|s| s.to_string()).collect(),
+            flash: flash.iter().map(|s| s.to_string()).collect(),
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn test_bell_per_severity() {
+        let mut alarm = alarm(&["critical"], &[]);
+        let mut events = EventLog::default();
+        let now = Instant::now();
+
+        events.push(GrimoireEvent::new(EventSeverity::Warning, "storm brewing"));
+        assert!(!alarm.observe(&events, now));
+        assert!(!alarm.take_bell());
+
+        events.push(GrimoireEvent::new(EventSeverity::Critical, "haunted"));
+        events.push(GrimoireEvent::new(EventSeverity::Info, "noise"));
+        alarm.observe(&events, now);
+        assert!(alarm.take_bell());
+        // One bell per alarm, not per frame
+        assert!(!alarm.take_bell());
+        assert!(!alarm.flashing());
+    }
+
+    #[test]
+    fn test_flash_lasts_a_while() {
+        let mut alarm = alarm(&[], &["warning", "crit"]);
+        let mut events = EventLog::default();
+        let now = Instant::now();
+
+        events.push(GrimoireEvent::new(EventSeverity::Warning, "exposed"));
+        assert!(alarm.observe(&events, now));
+        assert!(alarm.flashing());
+        assert!(!alarm.take_bell());
+
+        assert!(!alarm.observe(&events, now + ALARM_FLASH_DURATION / 2));
+        assert!(alarm.flashing());
+        assert!(alarm.observe(&events, now + ALARM_FLASH_DURATION));
+        assert!(!alarm.flashing());
+    }
+
+    #[test]
+    fn test_reconfigure_keeps_seen_events() {
+        let mut alarm = alarm(&[], &[]);
+        let mut events = EventLog::default();
+        events.push(GrimoireEvent::new(EventSeverity::Critical, "old news"));
+        alarm.observe(&events, Instant::now());
+
+        alarm.reconfigure(self::alarm(&["critical"], &[]));
+        alarm.observe(&events, Instant::now());
+        assert!(!alarm.take_bell());
+    }
+
+    #[test]
+    fn test_unknown_severity() {
+        let config = AlarmConfig {
+            bell: vec!["loud".to_string()],
+            ..Default::default()
+        };
+        assert!(Alarm::from_config(&config).is_err());
+    }
+}
@@ -0,0 +1,140 @@
+// Overdrive ambiance
+//
+// Kiroween Overdrive can add a layer of atmosphere behind the map: fog dots
+// drifting across the canvas, a bat crossing now and then, and a lightning
+// flash when an alert lands in the Grimoire. Positions are pure functions of
+// the tick counter, so the layer keeps no per-particle state; only the
+// flash needs remembering. The animation quality tier decides how much of
+// it is drawn (see `fog_dots`), and `[animation] ambiance = false`
+// turns it off entirely.
+
+use super::config::{BAT_FLIGHT_TICKS, BAT_PERIOD_TICKS, LIGHTNING_DURATION};
+use super::events::{EventLog, EventSeverity};
+use super::quality::AnimationQuality;
+use std::time::Instant;
+
+/// Fog dots drawn per tier; bats only fly from Normal up
+fn fog_dots(quality: AnimationQuality) -> usize {
+    match quality {
+        AnimationQuality::Off => 0,
+        AnimationQuality::Low => 6,
+        AnimationQuality::Normal => 14,
+        AnimationQuality::Lush => 28,
+    }
+}
+
+/// Flash state for alerts
+#[derive(Debug, Clone, Default)]
+pub struct Ambiance {
+    /// EventLog::pushed() at the last check
+    seen_events: u64,
+    flash_until: Option<Instant>,
+}
+
+impl Ambiance {
+    /// Start a flash when an event of Warning or worse arrived since the
+    /// last call; returns true when the flash started or ended (a redraw
+    /// is due)
+    pub fn observe(&mut self, events: &EventLog, now: Instant) -> bool {
+        let added = events.pushed().saturating_sub(self.seen_events) as usize;
+        self.seen_events = events.pushed();
+        let alert = events
+            .iter()
+            .rev()
+            .take(added)
+            .any(|event| event.severity >= EventSeverity::Warning);
+        if alert {
+            self.flash_until = Some(now + LIGHTNING_DURATION);
+            return true;
+        }
+        if self.flash_until.is_some_and(|until| now >= until) {
+            self.flash_until = None;
+            return true;
+        }
+        false
+    }
+
+    /// Whether the lightning flash is on
+    pub fn flashing(&self) -> bool {
+        self.flash_until.is_some()
+    }
+}
+
+/// Fog dot positions at `tick` as fractions of the canvas (0.0-1.0): each
+/// dot drifts right at its own speed and bobs gently, wrapping around
+pub fn fog_positions(tick: u64, quality: AnimationQuality) -> Vec<(f64, f64)> {
+    (0..fog_dots(quality))
+        .map(|i| {
+            // Spread start points and speeds with small co-prime strides
+            let start_x = (i * 37 % 100) as f64 / 100.0;
+            let y = (i * 61 % 90 + 5) as f64 / 100.0;
+            let speed = 0.002 + (i % 5) as f64 * 0.0007;
+            let x = (start_x + tick as f64 * speed).fract();
+            let bob = (tick as f64 / 20.0 + i as f64).sin() * 0.01;
+            (x, y + bob)
+        })
+        .collect()
+}
+
+/// Where the bat is at `tick`, if one is crossing: once every
+/// BAT_PERIOD_TICKS a bat flies left to right in BAT_FLIGHT_TICKS, each
+/// flight at a different height, flapping up and down
+pub fn bat_position(tick: u64, quality: AnimationQuality) -> Option<(f64, f64)> {
+    if quality < AnimationQuality::Normal {
+        return None;
+    }
+    let flight = tick / BAT_PERIOD_TICKS;
+    let progress = tick % BAT_PERIOD_TICKS;
+    if progress >= BAT_FLIGHT_TICKS {
+        return None;
+    }
+    let x = progress as f64 / BAT_FLIGHT_TICKS as f64;
+    let height = 0.6 + (flight * 13 % 30) as f64 / 100.0;
+    let flap = if progress % 4 < 2 { 0.02 } else { -0.02 };
+    Some((x, height + flap))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::events::GrimoireEvent;
+
+    #[test]
+    fn test_lightning_on_alert() {
+        let now = Instant::now();
+        let mut events = EventLog::default();
+        let mut ambiance = Ambiance::default();
+
+        events.push(GrimoireEvent::new(EventSeverity::Info, "opened"));
+        assert!(!ambiance.observe(&events, now));
+        assert!(!ambiance.flashing());
+
+        events.push(GrimoireEvent::new(EventSeverity::Critical, "haunted"));
+        events.push(GrimoireEvent::new(EventSeverity::Info, "opened"));
+        assert!(ambiance.observe(&events, now));
+        assert!(ambiance.flashing());
+
+        // The flash ends on its own, and the same alert doesn't restart it
+        assert!(ambiance.observe(&events, now + LIGHTNING_DURATION));
+        assert!(!ambiance.flashing());
+        assert!(!ambiance.observe(&events, now + LIGHTNING_DURATION * 2));
+    }
+
+    #[test]
+    fn test_ambient_layer() {
+        assert!(fog_positions(0, AnimationQuality::Off).is_empty());
+        let fog = fog_positions(12_345, AnimationQuality::Lush);
+        assert_eq!(fog.len(), 28);
+        assert!(fog
+            .iter()
+            .all(|&(x, y)| (0.0..1.0).contains(&x) && (0.0..=1.0).contains(&y)));
+
+        // Bats cross at the start of each period, and only from Normal up
+        assert!(bat_position(1, AnimationQuality::Normal).is_some());
+        assert!(bat_position(BAT_FLIGHT_TICKS, AnimationQuality::Normal).is_none());
+        assert!(bat_position(1, AnimationQuality::Low).is_none());
+        let (start, _) = bat_position(BAT_PERIOD_TICKS, AnimationQuality::Lush).unwrap();
+        let (later, _) = bat_position(BAT_PERIOD_TICKS + 10, AnimationQuality::Lush).unwrap();
+        assert!(later > start);
+    }
+}
@@ -0,0 +1,247 @@
+// Per-process behaviour baseline and anomaly scores
+//
+// For a learning period after start, ntomb only watches: for every process
+// name it records the ports it talks on (the remote port of outbound
+// connections, the local one of inbound), the autonomous systems of its
+// peers (with an `[asn]` database) and the hours of day (UTC) it is active.
+// After that, each new connection is scored against its process's
+// baseline, and what it added is learned so the same deviation counts once:
+//
+//   new port         +40
+//   new AS           +40
+//   unusual hour     +20   (no connection of the process in that hour yet)
+//
+// The Soul Inspector shows the highest score among the target process's
+// live connections as a meter, and a connection scoring `alert_score` or
+// more raises a Grimoire warning:
+//
+// ```toml
+// [anomaly]
+// learn_minutes = 60   # default 60
+// alert_score = 60     # 0-100, default 60
+// ```
+
+use super::snapshot::{connection_key, ConnectionKey};
+use crate::net::{Connection, ConnectionDirection, ConnectionState};
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Learning period unless `learn_minutes` says otherwise
+const DEFAULT_LEARN_MINUTES: u64 = 60;
+
+/// Event threshold unless `alert_score` says otherwise
+const DEFAULT_ALERT_SCORE: u32 = 60;
+
+const NEW_PORT_SCORE: u32 = 40;
+const NEW_AS_SCORE: u32 = 40;
+const UNUSUAL_HOUR_SCORE: u32 = 20;
+
+/// `[anomaly]` section of config.toml
+#[derive(Debug, Clone, Default, Deserialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct AnomalyConfig {
+    /// Minutes spent learning before anything is scored (default 60)
+    pub learn_minutes: Option<u64>,
+    /// Score (0-100) at which a Grimoire event is raised (default 60)
+    pub alert_score: Option<u32>,
+}
+
+/// How far a connection strays from its process's baseline
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Anomaly {
+    /// 0-100
+    pub score: u32,
+    /// What was new ("new port 4444", "new AS13335", "unusual hour 03h")
+    pub reasons: Vec<String>,
+}
+
+/// What one process normally does
+#[derive(Debug, Clone, Default)]
+struct ProcessBaseline {
+    ports: HashSet<u16>,
+    asns: HashSet<u32>,
+    /// Bit n set: active during hour n (UTC)
+    hours: u32,
+}
+
+/// Learned baselines and the scores of live connections
+#[derive(Debug, Clone)]
+pub struct AnomalyDetector {
+    learn_for: Duration,
+    alert_score: u32,
+    started: Option<SystemTime>,
+    baselines: HashMap<Arc<str>, ProcessBaseline>,
+    /// Scores of the current snapshot's connections, by process and key
+    scores: HashMap<ConnectionKey, (Arc<str>, Anomaly)>,
+}
+
+impl Default for AnomalyDetector {
+    fn default() -> Self {
+        Self::from_config(&AnomalyConfig::default())
+    }
+}
+
+impl AnomalyDetector {
+    pub fn from_config(config: &AnomalyConfig) -> Self {
+        Self {
+            learn_for: Duration::from_secs(
+                config.learn_minutes.unwrap_or(DEFAULT_LEARN_MINUTES) * 60,
+            ),
+            alert_score: config.alert_score.unwrap_or(DEFAULT_ALERT_SCORE).min(100),
+            started: None,
+            baselines: HashMap::new(),
+            scores: HashMap::new(),
+        }
+    }
+
+    /// Time left to learn at `now`; None once learning is over
+    pub fn learning_left(&self, now: SystemTime) -> Option<Duration> {
+        let elapsed = self.started.map_or(Duration::ZERO, |at| {
+            now.duration_since(at).unwrap_or_default()
+        });
+        self.learn_for
+            .checked_sub(elapsed)
+            .filter(|left| !left.is_zero())
+    }
+
+    /// Learn from or score the connections of a snapshot taken at `now`,
+    /// `asn` giving the AS number of a remote address; returns the newly
+    /// seen connections scoring at least `alert_score`
+    pub fn observe<'a>(
+        &mut self,
+        conns: &'a [Connection],
+        asn: impl Fn(&str) -> Option<u32>,
+        now: SystemTime,
+    ) -> Vec<(&'a Connection, Anomaly)> {
+        self.started.get_or_insert(now);
+        let learning = self.learning_left(now).is_some();
+        let hour =
+            (now.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs() / 3600 % 24) as u32;
+
+        let mut scores = HashMap::new();
+        let mut alerts = Vec::new();
+        for conn in conns {
+            let Some(ref process) = conn.process_name else {
+                continue;
+            };
+            if conn.state == ConnectionState::Listen {
+                continue;
+            }
+            let key = connection_key(conn);
+            if let Some(known) = self.scores.remove(&key) {
+                scores.insert(key, known);
+                continue;
+            }
+
+            let port = if conn.direction == Some(ConnectionDirection::Inbound) {
+                conn.local_port
+            } else {
+                conn.remote_port
+            };
+            let as_number = asn(&conn.remote_addr);
+            let baseline = self.baselines.entry(process.clone()).or_default();
+            let mut anomaly = Anomaly::default();
+            if !learning {
+                if !baseline.ports.contains(&port) {
+                    anomaly.score += NEW_PORT_SCORE;
+                    anomaly.reasons.push(format!("new port {}", port));
+                }
+                if let Some(number) = as_number.filter(|n| !baseline.asns.contains(n)) {
+                    anomaly.score += NEW_AS_SCORE;
+                    anomaly.reasons.push(format!("new AS{}", number));
+                }
+                if baseline.hours & (1 << hour) == 0 {
+                    anomaly.score += UNUSUAL_HOUR_SCORE;
+                    anomaly.reasons.push(format!("unusual hour {:02}h", hour));
+                }
+                anomaly.score = anomaly.score.min(100);
+            }
+            baseline.ports.insert(port);
+            baseline.asns.extend(as_number);
+            baseline.hours |= 1 << hour;
+
+            if anomaly.score > 0 && anomaly.score >= self.alert_score {
+                alerts.push((conn, anomaly.clone()));
+            }
+            scores.insert(key, (process.clone(), anomaly));
+        }
+        self.scores = scores;
+        alerts
+    }
+
+    /// The highest-scoring live connection of `process`
+    pub fn process_anomaly(&self, process: &str) -> Option<&Anomaly> {
+        self.scores
+            .values()
+            .filter(|(name, _)| &**name == process)
+            .map(|(_, anomaly)| anomaly)
+            .max_by_key(|anomaly| anomaly.score)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn conn(local_port: u16, remote_addr: &str, remote_port: u16) -> Connection {
+        Connection {
+            local_addr: "10.0.0.5".into(),
+            local_port,
+            remote_addr: remote_addr.into(),
+            remote_port,
+            state: ConnectionState::Established,
+            process_name: Some("curl".into()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_scores_after_learning() {
+        let mut detector = AnomalyDetector::from_config(&AnomalyConfig {
+            learn_minutes: Some(10),
+            alert_score: Some(60),
+        });
+        let asn = |addr: &str| {
+            Some(if addr.starts_with("93.") {
+                15133
+            } else {
+                13335
+            })
+        };
+        // 12:00 UTC
+        let start = UNIX_EPOCH + Duration::from_secs(1_760_000_000 / 86_400 * 86_400 + 12 * 3600);
+
+        let learned = [conn(50000, "93.184.216.34", 443)];
+        assert!(detector.observe(&learned, asn, start).is_empty());
+        assert_eq!(detector.process_anomaly("curl").unwrap().score, 0);
+        assert!(detector.learning_left(start).is_some());
+
+        // Known port and AS at a known hour: nothing unusual
+        let later = start + Duration::from_secs(11 * 60);
+        assert_eq!(detector.learning_left(later), None);
+        let usual = [conn(50001, "93.184.216.35", 443)];
+        assert!(detector.observe(&usual, asn, later).is_empty());
+        assert_eq!(detector.process_anomaly("curl").unwrap().score, 0);
+
+        // New port and AS: alert once, and the meter keeps the score
+        let odd = [conn(50002, "104.16.0.1", 4444)];
+        let alerts = detector.observe(&odd, asn, later);
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].1.score, 80);
+        assert_eq!(alerts[0].1.reasons, ["new port 4444", "new AS13335"]);
+        assert!(detector.observe(&odd, asn, later).is_empty());
+        assert_eq!(detector.process_anomaly("curl").unwrap().score, 80);
+
+        // Learned now; only the hour is new at 03:00 the next day
+        let night = start + Duration::from_secs(15 * 3600);
+        let again = [conn(50003, "104.16.0.2", 4444)];
+        assert!(detector.observe(&again, asn, night).is_empty());
+        let anomaly = detector.process_anomaly("curl").unwrap();
+        assert_eq!(
+            (anomaly.score, anomaly.reasons.clone()),
+            (20, vec!["unusual hour 03h".to_string()])
+        );
+    }
+}
@@ -0,0 +1,192 @@
+// Tor exit node and VPN detection
+//
+// Flags connections whose remote end is a known anonymizer, which is often
+// how data leaves a network unnoticed or how a policy gets bypassed:
+//
+// ```toml
+// [anonymizers]
+// tor_exits = "/var/lib/tor/cached-microdesc-consensus"
+// vpn_ranges = ["/etc/ntomb/vpn-ranges.txt"]
+// ```
+//
+// `tor_exits` accepts a local consensus snapshot (relays with the Exit flag
+// and without BadExit), TorDNSEL's exit-addresses file, or a plain address
+// list such as the bulk exit list. `vpn_ranges` are range lists whose note
+// names the provider ("185.159.156.0/22 ProtonVPN"). Nothing is fetched;
+// the files are read once at startup.
+
+use crate::net::cidr::{parse_range_list, Cidr};
+use serde::Deserialize;
+use std::collections::HashSet;
+use std::fs;
+use std::net::IpAddr;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// `[anonymizers]` section of config.toml
+#[derive(Debug, Clone, Default, Deserialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct AnonymizersConfig {
+    /// Tor consensus, exit-addresses file, or plain exit address list
+    pub tor_exits: Option<PathBuf>,
+    /// Range lists of VPN providers ("CIDR provider" per line)
+    pub vpn_ranges: Vec<PathBuf>,
+}
+
+/// Kind of anonymizer behind a remote address
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Anonymizer {
+    TorExit,
+    /// VPN endpoint, with the provider named by the range list
+    Vpn(Arc<str>),
+}
+
+impl Anonymizer {
+    /// Badge shown next to flagged endpoints
+    pub fn icon(&self) -> &'static str {
+        match self {
+            Anonymizer::TorExit => "🧅",
+            Anonymizer::Vpn(_) => "🎭",
+        }
+    }
+
+    /// Short description ("tor exit", "vpn: Mullvad")
+    pub fn label(&self) -> String {
+        match self {
+            Anonymizer::TorExit => "tor exit".to_string(),
+            Anonymizer::Vpn(provider) if provider.is_empty() => "vpn".to_string(),
+            Anonymizer::Vpn(provider) => format!("vpn: {}", provider),
+        }
+    }
+}
+
+/// Loaded Tor exits and VPN ranges
+#[derive(Debug, Clone, Default)]
+pub struct Anonymizers {
+    tor_exits: HashSet<IpAddr>,
+    vpn_ranges: Vec<(Cidr, Arc<str>)>,
+}
+
+impl Anonymizers {
+    /// Read every configured file
+    pub fn load(config: &AnonymizersConfig) -> Result<Self, String> {
+        let mut anonymizers = Self::default();
+        if let Some(ref path) = config.tor_exits {
+            anonymizers.tor_exits = parse_tor_exits(&read(path)?);
+            if anonymizers.tor_exits.is_empty() {
+                return Err(format!("no Tor exit addresses found in {}", path.display()));
+            }
+        }
+        for path in &config.vpn_ranges {
+            let (entries, _) = parse_range_list(&read(path)?);
+            if entries.is_empty() {
+                return Err(format!("no IP ranges found in {}", path.display()));
+            }
+            anonymizers.vpn_ranges.extend(
+                entries
+                    .into_iter()
+                    .map(|(cidr, provider)| (cidr, Arc::from(provider))),
+            );
+        }
+        Ok(anonymizers)
+    }
+
+    /// Whether nothing is loaded
+    pub fn is_empty(&self) -> bool {
+        self.tor_exits.is_empty() && self.vpn_ranges.is_empty()
+    }
+
+    /// Anonymizer behind `ip`; Tor exits win over VPN ranges
+    pub fn lookup(&self, ip: &str) -> Option<Anonymizer> {
+        let ip: IpAddr = ip.parse().ok()?;
+        let ip = match ip {
+            IpAddr::V6(v6) => v6.to_ipv4_mapped().map(IpAddr::V4).unwrap_or(ip),
+            v4 => v4,
+        };
+        if self.tor_exits.contains(&ip) {
+            return Some(Anonymizer::TorExit);
+        }
+        self.vpn_ranges
+            .iter()
+            .find(|(cidr, _)| cidr.contains(&ip))
+            .map(|(_, provider)| Anonymizer::Vpn(provider.clone()))
+    }
+}
+
+fn read(path: &Path) -> Result<String, String> {
+    fs::read_to_string(path).map_err(|e| format!("cannot read {}: {}", path.display(), e))
+}
+
+/// Exit addresses from a consensus, exit-addresses file, or address list
+fn parse_tor_exits(content: &str) -> HashSet<IpAddr> {
+    let mut exits = HashSet::new();
+    // Consensus: "r <nick> <id> [<digest>] <date> <time> <ip> <orport> <dirport>"
+    // followed by "s <flags>"; the relay is an exit when its flags say so
+    let mut relay: Option<IpAddr> = None;
+    for line in content.lines() {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        match fields.as_slice() {
+            ["r", .., ip, _, _] => relay = ip.parse().ok(),
+            ["s", flags @ ..] => {
+                if let Some(ip) = relay.take() {
+                    if flags.contains(&"Exit") && !flags.contains(&"BadExit") {
+                        exits.insert(ip);
+                    }
+                }
+            }
+            // TorDNSEL exit-addresses: "ExitAddress <ip> <date> <time>"
+            ["ExitAddress", ip, ..] => exits.extend(ip.parse::<IpAddr>()),
+            // Bulk exit list: one address per line
+            [ip] => exits.extend(ip.parse::<IpAddr>()),
+            _ => {}
+        }
+    }
+    exits
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_consensus() {
+        let consensus = "\
+network-status-version 3 microdesc
+r relayA AAAA 2024-05-01 12:00:00 198.51.100.7 9001 0
+m abc
+s Exit Fast Running Stable Valid
+r relayB BBBB 2024-05-01 12:00:00 198.51.100.8 9001 0
+s Fast Guard Running Valid
+r relayC CCCC DDDD 2024-05-01 12:00:00 198.51.100.9 443 80
+s BadExit Exit Running Valid
+";
+        let exits = parse_tor_exits(consensus);
+        assert_eq!(exits.len(), 1);
+        assert!(exits.contains(&"198.51.100.7".parse().unwrap()));
+    }
+
+    #[test]
+    fn test_parse_exit_lists() {
+        let exit_addresses = "ExitNode 0011\nPublished 2024-05-01 10:00:00\nExitAddress 203.0.113.5 2024-05-01 10:10:00\n";
+        assert!(parse_tor_exits(exit_addresses).contains(&"203.0.113.5".parse().unwrap()));
+        assert_eq!(parse_tor_exits("203.0.113.6\n203.0.113.7\n").len(), 2);
+    }
+
+    #[test]
+    fn test_lookup() {
+        let anonymizers = Anonymizers {
+            tor_exits: parse_tor_exits("203.0.113.6\n"),
+            vpn_ranges: vec![("185.159.156.0/22".parse().unwrap(), Arc::from("ProtonVPN"))],
+        };
+        assert_eq!(anonymizers.lookup("203.0.113.6"), Some(Anonymizer::TorExit));
+        assert_eq!(
+            anonymizers.lookup("::ffff:203.0.113.6"),
+            Some(Anonymizer::TorExit)
+        );
+        assert_eq!(
+            anonymizers.lookup("185.159.157.1").unwrap().label(),
+            "vpn: ProtonVPN"
+        );
+        assert_eq!(anonymizers.lookup("8.8.8.8"), None);
+    }
+}
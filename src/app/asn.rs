@@ -0,0 +1,180 @@
+// Autonomous system (ASN) lookups
+//
+// Maps public endpoints to the AS and organization announcing them, so
+// dozens of CDN addresses can be read as "Google LLC: 14 conns". The
+// database is the iptoasn.com TSV dump (ip2asn-combined.tsv or the v4/v6
+// files), read once at startup:
+//
+// ```toml
+// [asn]
+// database = "/var/lib/ntomb/ip2asn-combined.tsv"
+// ```
+//
+// Each line is "range_start range_end AS_number country AS_description",
+// tab separated. Unrouted ranges (AS 0) are skipped.
+
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::net::IpAddr;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// `[asn]` section of config.toml
+#[derive(Debug, Clone, Default, Deserialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct AsnConfig {
+    /// iptoasn.com TSV database
+    pub database: Option<PathBuf>,
+}
+
+/// Owner of an address range
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct AsInfo {
+    pub number: u32,
+    /// Two-letter registration country ("US"), empty when unknown
+    pub country: Arc<str>,
+    /// Organization name as registered ("GOOGLE")
+    pub org: Arc<str>,
+}
+
+impl fmt::Display for AsInfo {
+    /// "AS15169 GOOGLE (US)"
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "AS{} {}", self.number, self.org)?;
+        if !self.country.is_empty() {
+            write!(f, " ({})", self.country)?;
+        }
+        Ok(())
+    }
+}
+
+/// Sorted, non-overlapping address ranges and their owners
+#[derive(Debug, Clone, Default)]
+pub struct AsnDatabase {
+    v4: Vec<(u32, u32, Arc<AsInfo>)>,
+    v6: Vec<(u128, u128, Arc<AsInfo>)>,
+}
+
+impl AsnDatabase {
+    /// Read the configured database, if any
+    pub fn load(config: &AsnConfig) -> Result<Self, String> {
+        let Some(ref path) = config.database else {
+            return Ok(Self::default());
+        };
+        let content = fs::read_to_string(path)
+            .map_err(|e| format!("cannot read {}: {}", path.display(), e))?;
+        let database = Self::parse(&content);
+        if database.is_empty() {
+            return Err(format!("no AS ranges found in {}", path.display()));
+        }
+        Ok(database)
+    }
+
+    /// Parse iptoasn.com TSV lines; malformed lines are skipped
+    pub fn parse(content: &str) -> Self {
+        let mut database = Self::default();
+        // Organization names repeat for every range they announce
+        let mut names: HashMap<&str, Arc<str>> = HashMap::new();
+        let mut infos: HashMap<(u32, &str, &str), Arc<AsInfo>> = HashMap::new();
+
+        for line in content.lines() {
+            let fields: Vec<&str> = line.split('\t').map(str::trim).collect();
+            let [start, end, number, country, org, ..] = fields.as_slice() else {
+                continue;
+            };
+            let Ok(number) = number.parse::<u32>() else {
+                continue;
+            };
+            if number == 0 {
+                continue;
+            }
+            let info = infos
+                .entry((number, country, org))
+                .or_insert_with(|| {
+                    let country = if *country == "None" { "" } else { country };
+                    Arc::new(AsInfo {
+                        number,
+                        country: intern(&mut names, country),
+                        org: intern(&mut names, org),
+                    })
+                })
+                .clone();
+            match (start.parse::<IpAddr>(), end.parse::<IpAddr>()) {
+                (Ok(IpAddr::V4(start)), Ok(IpAddr::V4(end))) => {
+                    database.v4.push((start.into(), end.into(), info))
+                }
+                (Ok(IpAddr::V6(start)), Ok(IpAddr::V6(end))) => {
+                    database.v6.push((start.into(), end.into(), info))
+                }
+                _ => {}
+            }
+        }
+        database.v4.sort_by_key(|(start, _, _)| *start);
+        database.v6.sort_by_key(|(start, _, _)| *start);
+        database
+    }
+
+    /// Whether no ranges are loaded
+    pub fn is_empty(&self) -> bool {
+        self.v4.is_empty() && self.v6.is_empty()
+    }
+
+    /// AS announcing `ip`
+    pub fn lookup(&self, ip: &str) -> Option<&Arc<AsInfo>> {
+        match ip.parse::<IpAddr>().ok()? {
+            IpAddr::V4(v4) => find(&self.v4, u32::from(v4)),
+            IpAddr::V6(v6) => match v6.to_ipv4_mapped() {
+                Some(v4) => find(&self.v4, u32::from(v4)),
+                None => find(&self.v6, u128::from(v6)),
+            },
+        }
+    }
+}
+
+/// Shared copy of `name`
+fn intern<'a>(names: &mut HashMap<&'a str, Arc<str>>, name: &'a str) -> Arc<str> {
+    names.entry(name).or_insert_with(|| Arc::from(name)).clone()
+}
+
+/// Range containing `ip` in a list sorted by start
+fn find<T: Ord + Copy>(ranges: &[(T, T, Arc<AsInfo>)], ip: T) -> Option<&Arc<AsInfo>> {
+    let idx = ranges.partition_point(|(start, _, _)| *start <= ip);
+    let (_, end, info) = ranges.get(idx.checked_sub(1)?)?;
+    (ip <= *end).then_some(info)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "\
+1.0.0.0\t1.0.0.255\t13335\tUS\tCLOUDFLARENET
+1.0.1.0\t1.0.3.255\t0\tNone\tNot routed
+8.8.8.0\t8.8.8.255\t15169\tUS\tGOOGLE
+34.64.0.0\t34.127.255.255\t396982\tUS\tGOOGLE
+2001:4860::\t2001:4860:ffff:ffff:ffff:ffff:ffff:ffff\t15169\tUS\tGOOGLE
+garbage line
+";
+
+    #[test]
+    fn test_parse_and_lookup() {
+        let db = AsnDatabase::parse(SAMPLE);
+        assert_eq!(db.v4.len(), 3);
+        assert_eq!(db.v6.len(), 1);
+
+        let google = db.lookup("8.8.8.8").unwrap();
+        assert_eq!(google.to_string(), "AS15169 GOOGLE (US)");
+        assert_eq!(db.lookup("1.0.0.1").unwrap().number, 13335);
+        assert_eq!(db.lookup("2001:4860:4860::8888").unwrap().number, 15169);
+        assert_eq!(db.lookup("::ffff:34.80.1.1").unwrap().number, 396982);
+        assert!(db.lookup("1.0.2.1").is_none());
+        assert!(db.lookup("9.9.9.9").is_none());
+        assert!(db.lookup("0.0.0.1").is_none());
+
+        // Both Google ASes share one organization name
+        let other = db.lookup("34.80.1.1").unwrap();
+        assert!(Arc::ptr_eq(&google.org, &other.org));
+    }
+}
@@ -0,0 +1,193 @@
+// Scheduled snapshot autosave
+//
+// Without the API server running, nothing records what the connection
+// table looked like an hour ago. With `[autosave]`, ntomb writes the whole
+// snapshot (unfiltered) to a directory at a fixed interval and keeps only
+// the newest files, a forensic trail that costs a few KiB per save:
+//
+// ```toml
+// [autosave]
+// dir = "/var/lib/ntomb/snapshots"
+// every = "5m"    # s, m, h, d or w (default 5m)
+// keep = 288      # newest files kept (default 288, a day at 5m)
+// ```
+//
+// Each file, snapshot-<unix time>.ndjson, holds one connection per line
+// in the format of `--json`. Writing is best effort: a failure is logged
+// to the diagnostics panel and retried at the next interval.
+
+use super::history::parse_duration;
+use super::stream::connection_json;
+use crate::net::Connection;
+use serde::Deserialize;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// Save interval unless `every` says otherwise
+const DEFAULT_EVERY: Duration = Duration::from_secs(5 * 60);
+
+/// Files kept unless `keep` says otherwise
+const DEFAULT_KEEP: usize = 288;
+
+const FILE_PREFIX: &str = "snapshot-";
+const FILE_SUFFIX: &str = ".ndjson";
+
+/// `[autosave]` section of config.toml
+#[derive(Debug, Clone, Default, Deserialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct AutosaveConfig {
+    /// Directory snapshots are written to; autosave is off when unset
+    pub dir: Option<PathBuf>,
+    /// Time between saves: "30s", "5m", "1h" (default 5m)
+    pub every: Option<String>,
+    /// Newest files kept; older ones are deleted (default 288)
+    pub keep: Option<usize>,
+}
+
+/// Writes snapshots to the autosave directory on schedule
+#[derive(Debug)]
+pub struct SnapshotAutosave {
+    dir: PathBuf,
+    every: Duration,
+    keep: usize,
+    last: Option<Instant>,
+}
+
+impl SnapshotAutosave {
+    /// Autosave for `config`; None when no directory is configured
+    pub fn from_config(config: &AutosaveConfig) -> Result<Option<Self>, String> {
+        let Some(ref dir) = config.dir else {
+            return Ok(None);
+        };
+        let every = match config.every.as_deref() {
+            Some(text) => parse_duration(text).map_err(|e| format!("every: {}", e))?,
+            None => DEFAULT_EVERY,
+        };
+        if every.is_zero() {
+            return Err("every must be at least 1s".to_string());
+        }
+        let keep = match config.keep.unwrap_or(DEFAULT_KEEP) {
+            0 => return Err("keep must be at least 1".to_string()),
+            keep => keep,
+        };
+        fs::create_dir_all(dir).map_err(|e| format!("cannot create {}: {}", dir.display(), e))?;
+        Ok(Some(Self {
+            dir: dir.clone(),
+            every,
+            keep,
+            last: None,
+        }))
+    }
+
+    /// Whether a save is due at `now` (the first one right away); a due
+    /// save counts as done, failed or not
+    pub fn due(&mut self, now: Instant) -> bool {
+        if self
+            .last
+            .is_some_and(|last| now.duration_since(last) < self.every)
+        {
+            return false;
+        }
+        self.last = Some(now);
+        true
+    }
+
+    /// Write `conns` as taken at `taken_at`, then delete the oldest files
+    /// beyond `keep`; returns the file written
+    pub fn save(&self, conns: &[Connection], taken_at: SystemTime) -> io::Result<PathBuf> {
+        let secs = taken_at
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        let path = self
+            .dir
+            .join(format!("{}{}{}", FILE_PREFIX, secs, FILE_SUFFIX));
+        let mut content = String::new();
+        for conn in conns {
+            content.push_str(&connection_json(conn));
+            content.push('\n');
+        }
+        fs::write(&path, content)?;
+        self.rotate()?;
+        Ok(path)
+    }
+
+    /// Delete the oldest snapshot files beyond `keep`
+    fn rotate(&self) -> io::Result<()> {
+        let mut saved: Vec<(u64, PathBuf)> = fs::read_dir(&self.dir)?
+            .filter_map(|entry| {
+                let path = entry.ok()?.path();
+                let secs = path
+                    .file_name()?
+                    .to_str()?
+                    .strip_prefix(FILE_PREFIX)?
+                    .strip_suffix(FILE_SUFFIX)?
+                    .parse()
+                    .ok()?;
+                Some((secs, path))
+            })
+            .collect();
+        if saved.len() <= self.keep {
+            return Ok(());
+        }
+        saved.sort();
+        for (_, path) in &saved[..saved.len() - self.keep] {
+            fs::remove_file(path)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_saves_on_schedule_and_rotates() {
+        let dir = std::env::temp_dir().join(format!("ntomb-autosave-{}", std::process::id()));
+        let config = AutosaveConfig {
+            dir: Some(dir.clone()),
+            every: Some("1m".to_string()),
+            keep: Some(2),
+        };
+        let mut autosave = SnapshotAutosave::from_config(&config).unwrap().unwrap();
+
+        let now = Instant::now();
+        assert!(autosave.due(now));
+        assert!(!autosave.due(now + Duration::from_secs(59)));
+        assert!(autosave.due(now + Duration::from_secs(60)));
+
+        let conns = vec![Connection {
+            local_addr: "10.0.0.5".into(),
+            local_port: 50000,
+            remote_addr: "10.1.0.1".into(),
+            remote_port: 5432,
+            ..Default::default()
+        }];
+        for secs in [1_000, 1_060, 1_120] {
+            autosave
+                .save(&conns, UNIX_EPOCH + Duration::from_secs(secs))
+                .unwrap();
+        }
+        let mut names: Vec<String> = fs::read_dir(&dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        names.sort();
+        assert_eq!(names, ["snapshot-1060.ndjson", "snapshot-1120.ndjson"]);
+        let content = fs::read_to_string(dir.join("snapshot-1120.ndjson")).unwrap();
+        assert_eq!(content, format!("{}\n", connection_json(&conns[0])));
+        let _ = fs::remove_dir_all(&dir);
+
+        config_error("every = \"0s\"");
+        config_error("keep = 0");
+    }
+
+    fn config_error(toml: &str) {
+        let mut config: AutosaveConfig = toml::from_str(toml).unwrap();
+        config.dir = Some(std::env::temp_dir());
+        assert!(SnapshotAutosave::from_config(&config).is_err());
+    }
+}
@@ -0,0 +1,144 @@
+// System clipboard access
+//
+// Text is piped to the platform's clipboard tool (pbcopy, wl-copy, xclip or
+// xsel). When none is available, e.g. over SSH, an OSC 52 escape sequence
+// asks the terminal itself to set the clipboard.
+
+use crate::net::Connection;
+use std::io::{self, Write};
+use std::process::{Command, Stdio};
+
+/// Clipboard tools in order of preference: (program, args)
+const CLIPBOARD_COMMANDS: &[(&str, &[&str])] = &[
+    ("pbcopy", &[]),
+    ("wl-copy", &[]),
+    ("xclip", &["-selection", "clipboard"]),
+    ("xsel", &["--clipboard", "--input"]),
+];
+
+/// Copy `text` to the clipboard, returning the method that was used
+pub fn copy(text: &str) -> Result<&'static str, String> {
+    let wayland = std::env::var_os("WAYLAND_DISPLAY").is_some();
+    let x11 = std::env::var_os("DISPLAY").is_some();
+    for (program, args) in CLIPBOARD_COMMANDS {
+        let usable = match *program {
+            "pbcopy" => cfg!(target_os = "macos"),
+            "wl-copy" => wayland,
+            _ => x11,
+        };
+        if usable && pipe_to(program, args, text).is_ok() {
+            return Ok(program);
+        }
+    }
+    copy_osc52(text).map_err(|e| format!("clipboard unavailable: {}", e))?;
+    Ok("OSC 52")
+}
+
+/// Run `program` with `text` on stdin
+fn pipe_to(program: &str, args: &[&str], text: &str) -> io::Result<()> {
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(text.as_bytes())?;
+    }
+    let status = child.wait()?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(io::Error::other(status.to_string()))
+    }
+}
+
+/// Ask the terminal to set the clipboard (xterm's OSC 52)
+fn copy_osc52(text: &str) -> io::Result<()> {
+    let mut stdout = io::stdout();
+    write!(stdout, "\x1b]52;c;{}\x07", base64_encode(text.as_bytes()))?;
+    stdout.flush()
+}
+
+/// Standard base64 with padding
+pub fn base64_encode(data: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let b = [
+            chunk[0],
+            *chunk.get(1).unwrap_or(&0),
+            *chunk.get(2).unwrap_or(&0),
+        ];
+        let n = (b[0] as u32) << 16 | (b[1] as u32) << 8 | b[2] as u32;
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(ALPHABET[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+/// One-line description of a connection for pasting into tickets
+///
+/// "tcp 10.0.0.5:51234 -> 93.184.216.34:443 ESTABLISHED pid=812 process=curl user=alice"
+pub fn format_connection(conn: &Connection) -> String {
+    let mut text = format!(
+        "tcp {}:{} -> {}:{} {}",
+        conn.local_addr,
+        conn.local_port,
+        conn.remote_addr,
+        conn.remote_port,
+        conn.state.name()
+    );
+    if let Some(pid) = conn.pid {
+        text.push_str(&format!(" pid={}", pid));
+    }
+    if let Some(ref name) = conn.process_name {
+        text.push_str(&format!(" process={}", name));
+    }
+    if let Some(ref user) = conn.user {
+        text.push_str(&format!(" user={}", user));
+    }
+    if let Some(ref container) = conn.container {
+        text.push_str(&format!(" container={}", container));
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::net::ConnectionState;
+
+    #[test]
+    fn test_base64_encode() {
+        assert_eq!(base64_encode(b""), "");
+        assert_eq!(base64_encode(b"f"), "Zg==");
+        assert_eq!(base64_encode(b"fo"), "Zm8=");
+        assert_eq!(base64_encode(b"foo"), "Zm9v");
+        assert_eq!(base64_encode(b"foobar"), "Zm9vYmFy");
+    }
+
+    #[test]
+    fn test_format_connection() {
+        let conn = Connection {
+            local_addr: "10.0.0.5".into(),
+            local_port: 51234,
+            remote_addr: "93.184.216.34".into(),
+            remote_port: 443,
+            state: ConnectionState::Established,
+            pid: Some(812),
+            process_name: Some("curl".into()),
+            user: Some("alice".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            format_connection(&conn),
+            "tcp 10.0.0.5:51234 -> 93.184.216.34:443 ESTABLISHED pid=812 process=curl user=alice"
+        );
+    }
+}
@@ -0,0 +1,380 @@
+// Cloud provider IP ranges
+//
+// Tags public endpoints with the provider, region and service that own the
+// address ("AWS us-east-1 / S3"), which usually says more than reverse DNS.
+// Cloudflare's ranges are small and stable, so they ship with ntomb; the
+// large AWS/GCP/Azure lists are loaded from the files the providers publish:
+//
+// ```toml
+// [cloud_ranges]
+// files = [
+//     "/var/lib/ntomb/ip-ranges.json",     # AWS  ip-ranges.json
+//     "/var/lib/ntomb/cloud.json",         # GCP  cloud.json
+//     "/var/lib/ntomb/ServiceTags.json",   # Azure Service Tags
+// ]
+// ```
+//
+// The format is detected per file: the three JSON layouts above, or a plain
+// range list ("1.2.3.0/24 Provider") for anything else. Lookups use the
+// longest matching prefix, preferring entries that name a service.
+
+use crate::net::cidr::{parse_range_list, Cidr};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::net::IpAddr;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Cloudflare's published ranges (cloudflare.com/ips)
+const CLOUDFLARE_RANGES: &str = "\
+173.245.48.0/20
+103.21.244.0/22
+103.22.200.0/22
+103.31.4.0/22
+141.101.64.0/18
+108.162.192.0/18
+190.93.240.0/20
+188.114.96.0/20
+197.234.240.0/22
+198.41.128.0/17
+162.158.0.0/15
+104.16.0.0/13
+104.24.0.0/14
+172.64.0.0/13
+131.0.72.0/22
+2400:cb00::/32
+2606:4700::/32
+2803:f800::/32
+2405:b500::/32
+2405:8100::/32
+2a06:98c0::/29
+2c0f:f248::/32
+";
+
+/// `[cloud_ranges]` section of config.toml
+#[derive(Debug, Clone, Default, Deserialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct CloudRangesConfig {
+    /// Provider range files (AWS/GCP/Azure JSON or plain range lists)
+    pub files: Vec<PathBuf>,
+    /// Include the bundled Cloudflare ranges (default true)
+    pub bundled: Option<bool>,
+}
+
+/// Owner of a cloud address range
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CloudRange {
+    pub provider: String,
+    pub region: Option<String>,
+    pub service: Option<String>,
+}
+
+impl CloudRange {
+    fn new(provider: &str, region: Option<&str>, service: Option<&str>) -> Self {
+        let clean = |s: Option<&str>| {
+            s.map(str::trim)
+                .filter(|s| !s.is_empty())
+                .map(str::to_string)
+        };
+        Self {
+            provider: provider.to_string(),
+            region: clean(region),
+            service: clean(service),
+        }
+    }
+
+    /// Compact name for Graveyard labels ("AWS S3", "GCP us-east1")
+    pub fn short_label(&self) -> String {
+        match (&self.service, &self.region) {
+            (Some(service), _) => format!("{} {}", self.provider, service),
+            (None, Some(region)) => format!("{} {}", self.provider, region),
+            (None, None) => self.provider.clone(),
+        }
+    }
+}
+
+impl fmt::Display for CloudRange {
+    /// "AWS us-east-1 / S3"
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.provider)?;
+        if let Some(ref region) = self.region {
+            write!(f, " {}", region)?;
+        }
+        if let Some(ref service) = self.service {
+            write!(f, " / {}", service)?;
+        }
+        Ok(())
+    }
+}
+
+/// All loaded ranges, most specific first
+#[derive(Debug, Clone, Default)]
+pub struct CloudRanges {
+    entries: Vec<(Cidr, Arc<CloudRange>)>,
+}
+
+impl CloudRanges {
+    /// Load the bundled ranges and every configured file
+    pub fn load(config: &CloudRangesConfig) -> Result<Self, String> {
+        let mut ranges = Self::default();
+        if config.bundled.unwrap_or(true) {
+            let cloudflare = Arc::new(CloudRange::new("Cloudflare", None, None));
+            let (entries, _) = parse_range_list(CLOUDFLARE_RANGES);
+            ranges.entries.extend(
+                entries
+                    .into_iter()
+                    .map(|(cidr, _)| (cidr, cloudflare.clone())),
+            );
+        }
+        for path in &config.files {
+            let content = fs::read_to_string(path)
+                .map_err(|e| format!("cannot read {}: {}", path.display(), e))?;
+            let entries = parse_ranges(&content, path);
+            if entries.is_empty() {
+                return Err(format!("no IP ranges found in {}", path.display()));
+            }
+            ranges.entries.extend(entries);
+        }
+        ranges.sort();
+        Ok(ranges)
+    }
+
+    /// Whether no ranges are loaded
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Owner of the most specific range containing `ip`
+    pub fn lookup(&self, ip: &str) -> Option<&Arc<CloudRange>> {
+        let ip: IpAddr = ip.parse().ok()?;
+        self.entries
+            .iter()
+            .find(|(cidr, _)| cidr.contains(&ip))
+            .map(|(_, range)| range)
+    }
+
+    /// Longest prefix first; on equal prefixes, entries naming a service
+    /// win over catch-alls like AWS's "AMAZON"
+    fn sort(&mut self) {
+        self.entries.sort_by_key(|(cidr, range)| {
+            (
+                std::cmp::Reverse(cidr.prefix_len()),
+                range.service.is_none(),
+            )
+        });
+    }
+}
+
+/// Parse one provider file, detecting its format
+fn parse_ranges(content: &str, path: &Path) -> Vec<(Cidr, Arc<CloudRange>)> {
+    if !content.trim_start().starts_with('{') {
+        let fallback = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        return parse_range_list(content)
+            .0
+            .into_iter()
+            .map(|(cidr, note)| {
+                let provider = if note.is_empty() { &fallback } else { &note };
+                (cidr, Arc::new(CloudRange::new(provider, None, None)))
+            })
+            .collect();
+    }
+
+    // Ranges sharing an owner share one allocation
+    let mut owners: HashMap<CloudRange, Arc<CloudRange>> = HashMap::new();
+    let mut entries = Vec::new();
+    for object in json_objects(content) {
+        let (range, prefixes) = if let Some(prefix) =
+            json_str(object, "ip_prefix").or_else(|| json_str(object, "ipv6_prefix"))
+        {
+            // AWS ip-ranges.json: "AMAZON" is the catch-all for every service
+            let service = json_str(object, "service").filter(|s| *s != "AMAZON");
+            let region = json_str(object, "region").filter(|r| *r != "GLOBAL");
+            (CloudRange::new("AWS", region, service), vec![prefix])
+        } else if let Some(prefix) =
+            json_str(object, "ipv4Prefix").or_else(|| json_str(object, "ipv6Prefix"))
+        {
+            // GCP cloud.json (with scope) or goog.json (Google-wide)
+            let range = match json_str(object, "scope") {
+                Some(scope) => CloudRange::new("GCP", Some(scope), None),
+                None => CloudRange::new("Google", None, None),
+            };
+            (range, vec![prefix])
+        } else if object.contains("\"addressPrefixes\"") {
+            // Azure Service Tags: the innermost object is "properties"
+            let region = json_str(object, "region");
+            let service = json_str(object, "systemService");
+            (
+                CloudRange::new("Azure", region, service),
+                json_str_array(object, "addressPrefixes"),
+            )
+        } else {
+            continue;
+        };
+
+        let owner = owners
+            .entry(range.clone())
+            .or_insert_with(|| Arc::new(range))
+            .clone();
+        entries.extend(
+            prefixes
+                .into_iter()
+                .filter_map(|prefix| prefix.parse::<Cidr>().ok())
+                .map(|cidr| (cidr, owner.clone())),
+        );
+    }
+    entries
+}
+
+/// Innermost `{...}` objects of a JSON document
+///
+/// The provider files are flat lists of small objects, so this is all the
+/// JSON structure needed; braces inside strings are skipped.
+fn json_objects(content: &str) -> Vec<&str> {
+    let mut objects = Vec::new();
+    let mut start = None;
+    let mut in_string = false;
+    let mut escaped = false;
+    for (idx, c) in content.char_indices() {
+        if in_string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match c {
+            '"' => in_string = true,
+            '{' => start = Some(idx),
+            '}' => {
+                if let Some(begin) = start.take() {
+                    objects.push(&content[begin..=idx]);
+                }
+            }
+            _ => {}
+        }
+    }
+    objects
+}
+
+/// Text after `"key":` in `object`, with leading whitespace removed
+fn json_value<'a>(object: &'a str, key: &str) -> Option<&'a str> {
+    let needle = format!("\"{}\"", key);
+    let rest = &object[object.find(&needle)? + needle.len()..];
+    rest.trim_start().strip_prefix(':').map(str::trim_start)
+}
+
+/// String value of `key` (provider files never escape these values)
+fn json_str<'a>(object: &'a str, key: &str) -> Option<&'a str> {
+    let value = json_value(object, key)?.strip_prefix('"')?;
+    Some(&value[..value.find('"')?])
+}
+
+/// String array value of `key`
+fn json_str_array<'a>(object: &'a str, key: &str) -> Vec<&'a str> {
+    let Some(value) = json_value(object, key).and_then(|v| v.strip_prefix('[')) else {
+        return Vec::new();
+    };
+    let body = &value[..value.find(']').unwrap_or(value.len())];
+    body.split(',')
+        .map(|item| item.trim().trim_matches('"'))
+        .filter(|item| !item.is_empty())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const AWS: &str = r#"{
+  "syncToken": "1700000000",
+  "prefixes": [
+    {
+      "ip_prefix": "52.216.0.0/15",
+      "region": "us-east-1",
+      "service": "AMAZON",
+      "network_border_group": "us-east-1"
+    },
+    {
+      "ip_prefix": "52.216.0.0/15",
+      "region": "us-east-1",
+      "service": "S3",
+      "network_border_group": "us-east-1"
+    },
+    {
+      "ip_prefix": "3.0.0.0/9",
+      "region": "GLOBAL",
+      "service": "AMAZON",
+      "network_border_group": "GLOBAL"
+    }
+  ],
+  "ipv6_prefixes": [
+    { "ipv6_prefix": "2600:1f00::/24", "region": "us-west-2", "service": "EC2" }
+  ]
+}"#;
+
+    fn ranges(files: &[(&str, &str)]) -> CloudRanges {
+        let mut ranges = CloudRanges::default();
+        for (name, content) in files {
+            ranges
+                .entries
+                .extend(parse_ranges(content, Path::new(name)));
+        }
+        ranges.sort();
+        ranges
+    }
+
+    #[test]
+    fn test_aws_prefers_named_service() {
+        let ranges = ranges(&[("ip-ranges.json", AWS)]);
+        let s3 = ranges.lookup("52.217.1.2").unwrap();
+        assert_eq!(s3.to_string(), "AWS us-east-1 / S3");
+        assert_eq!(s3.short_label(), "AWS S3");
+        assert_eq!(ranges.lookup("3.5.0.1").unwrap().to_string(), "AWS");
+        assert_eq!(
+            ranges.lookup("2600:1f00::1").unwrap().to_string(),
+            "AWS us-west-2 / EC2"
+        );
+        assert!(ranges.lookup("8.8.8.8").is_none());
+    }
+
+    #[test]
+    fn test_gcp_and_azure() {
+        let gcp = r#"{"prefixes": [{"ipv4Prefix": "34.80.0.0/15", "service": "Google Cloud", "scope": "asia-east1"}]}"#;
+        let azure = r#"{"values": [{"name": "Storage.WestEurope", "id": "Storage.WestEurope",
+            "properties": {"changeNumber": 3, "region": "westeurope", "systemService": "AzureStorage",
+            "addressPrefixes": ["13.69.40.0/24", "2a01:111:f100::/48"]}}]}"#;
+        let ranges = ranges(&[("cloud.json", gcp), ("ServiceTags.json", azure)]);
+        assert_eq!(
+            ranges.lookup("34.81.0.1").unwrap().to_string(),
+            "GCP asia-east1"
+        );
+        assert_eq!(
+            ranges.lookup("13.69.40.9").unwrap().to_string(),
+            "Azure westeurope / AzureStorage"
+        );
+        assert!(ranges.lookup("2a01:111:f100::5").is_some());
+    }
+
+    #[test]
+    fn test_plain_list_and_bundled() {
+        let ranges = ranges(&[("hetzner.txt", "5.9.0.0/16 Hetzner\n88.198.0.0/16\n")]);
+        assert_eq!(ranges.lookup("5.9.1.1").unwrap().provider, "Hetzner");
+        assert_eq!(ranges.lookup("88.198.1.1").unwrap().provider, "hetzner");
+
+        let bundled = CloudRanges::load(&CloudRangesConfig::default()).unwrap();
+        assert_eq!(bundled.lookup("104.16.1.1").unwrap().provider, "Cloudflare");
+        assert!(CloudRanges::load(&CloudRangesConfig {
+            bundled: Some(false),
+            ..Default::default()
+        })
+        .unwrap()
+        .is_empty());
+    }
+}
@@ -0,0 +1,191 @@
+// Collection worker and watchdog
+//
+// Snapshots are collected on a worker thread, so a backend that hangs (an
+// NFS-backed /proc, a stuck netlink socket, lsof blocked on a dead mount)
+// cannot freeze the UI with it. A refresh waits at most the collection
+// timeout for its snapshot:
+//
+// ```toml
+// [refresh]
+// collect_timeout_ms = 3000   # default 3000
+// ```
+//
+// When the snapshot doesn't come in time, the last one stays on screen,
+// the banner marks it stale and the watchdog logs a Grimoire event. No new
+// collection starts while the hung one runs; its snapshot is used as soon
+// as it returns.
+
+use crate::net::{Connection, DataSource};
+use std::io;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// One collection, as the worker returns it
+#[derive(Debug)]
+pub struct Collected {
+    /// Backend that produced (or failed to produce) the snapshot
+    pub backend: &'static str,
+    pub result: io::Result<Vec<Connection>>,
+    /// Failures that did not make the collection fail, as (backend,
+    /// message) pairs
+    pub errors: Vec<(&'static str, String)>,
+    /// Why processes could not be attached to the snapshot (Linux)
+    pub process_error: Option<io::Error>,
+    /// How long the collection took
+    pub took: Duration,
+}
+
+/// Runs the data source on its own thread, started by the first collection
+pub struct Collector {
+    /// The source until the worker takes it
+    source: Option<Box<dyn DataSource>>,
+    worker: Option<(Sender<()>, Receiver<Collected>)>,
+    /// Backend of the last collection
+    backend: &'static str,
+    /// When the running collection started
+    busy_since: Option<Instant>,
+}
+
+impl Collector {
+    pub fn new(source: Box<dyn DataSource>) -> Self {
+        Self {
+            backend: source.name(),
+            source: Some(source),
+            worker: None,
+            busy_since: None,
+        }
+    }
+
+    /// Backend of the last collection
+    pub fn backend(&self) -> &'static str {
+        self.backend
+    }
+
+    /// Start a collection, unless one is still running, and wait until
+    /// `timeout` after its start; None when it is still running by then
+    pub fn collect(&mut self, timeout: Duration) -> Option<Collected> {
+        let now = Instant::now();
+        let (requests, results) = self.worker.get_or_insert_with(|| {
+            spawn(
+                self.source
+                    .take()
+                    .expect("data source taken without a worker"),
+            )
+        });
+        let since = match self.busy_since {
+            Some(since) => since,
+            None => {
+                if requests.send(()).is_err() {
+                    return Some(self.gone());
+                }
+                *self.busy_since.insert(now)
+            }
+        };
+        let wait = (since + timeout).saturating_duration_since(now);
+        match results.recv_timeout(wait) {
+            Ok(collected) => {
+                self.busy_since = None;
+                self.backend = collected.backend;
+                Some(collected)
+            }
+            Err(RecvTimeoutError::Timeout) => None,
+            Err(RecvTimeoutError::Disconnected) => Some(self.gone()),
+        }
+    }
+
+    /// How long the running collection has been going, once past
+    /// `timeout`
+    pub fn overdue(&self, now: Instant, timeout: Duration) -> Option<Duration> {
+        let running = now.saturating_duration_since(self.busy_since?);
+        (running >= timeout).then_some(running)
+    }
+
+    /// The collection a dead worker thread would have returned
+    fn gone(&mut self) -> Collected {
+        self.busy_since = None;
+        Collected {
+            backend: self.backend,
+            result: Err(io::Error::other("collection thread is gone")),
+            errors: Vec::new(),
+            process_error: None,
+            took: Duration::ZERO,
+        }
+    }
+}
+
+/// Start the worker thread; it collects once per request and exits when
+/// the collector is dropped
+fn spawn(mut source: Box<dyn DataSource>) -> (Sender<()>, Receiver<Collected>) {
+    let (requests, queue) = mpsc::channel::<()>();
+    let (answers, results) = mpsc::channel();
+    thread::spawn(move || {
+        for () in queue {
+            let started = Instant::now();
+            let result = source.collect();
+            let errors = source.take_errors();
+            let mut process_error = None;
+            // On Linux, attach process information to connections; this
+            // is best effort, so a failure only gets reported
+            #[cfg(target_os = "linux")]
+            let result = result.map(|mut conns| {
+                // QUIC candidates; the TCP backends never see UDP
+                conns.extend(crate::net::udp::read_udp_flows());
+                process_error = crate::procfs::attach_process_info(&mut conns).err();
+                conns
+            });
+            let collected = Collected {
+                backend: source.name(),
+                result,
+                errors,
+                process_error,
+                took: started.elapsed(),
+            };
+            if answers.send(collected).is_err() {
+                break;
+            }
+        }
+    });
+    (requests, results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    /// Source that blocks while the test holds the lock
+    struct StuckSource(Arc<Mutex<()>>);
+
+    impl DataSource for StuckSource {
+        fn name(&self) -> &'static str {
+            "stuck"
+        }
+
+        fn collect(&mut self) -> io::Result<Vec<Connection>> {
+            let _wait = self.0.lock().unwrap();
+            Ok(Vec::new())
+        }
+    }
+
+    #[test]
+    fn test_hung_collection_times_out_and_comes_back() {
+        let lock = Arc::new(Mutex::new(()));
+        let mut collector = Collector::new(Box::new(StuckSource(lock.clone())));
+        let timeout = Duration::from_millis(50);
+
+        let held = lock.lock().unwrap();
+        assert!(collector.collect(timeout).is_none());
+        assert!(collector.overdue(Instant::now(), timeout).is_some());
+        // Still running: the next refresh doesn't wait again
+        let started = Instant::now();
+        assert!(collector.collect(timeout).is_none());
+        assert!(started.elapsed() < timeout);
+
+        drop(held);
+        let collected = collector.collect(Duration::from_secs(5)).unwrap();
+        assert_eq!(collected.backend, "stuck");
+        assert!(collected.result.is_ok());
+        assert_eq!(collector.overdue(Instant::now(), timeout), None);
+    }
+}
@@ -6,6 +6,8 @@
 // - Refresh intervals
 // - View modes
 
+use super::heavy_talkers::HeavyTalkerThreshold;
+use super::quality::AnimationQuality;
 use std::time::{Duration, Instant};
 
 // ============================================================================
@@ -21,25 +23,105 @@ pub const MAX_REFRESH_MS: u64 = 10000;
 /// Refresh interval adjustment step in milliseconds
 pub const REFRESH_STEP: u64 = 50;
 
-/// Data refresh multiplier (data refreshes at N times the UI interval)
+/// Default data refresh multiplier (data refreshes at N times the UI interval)
 pub const DATA_REFRESH_MULTIPLIER: u64 = 10;
 
+/// Minimum data refresh multiplier (data refreshes on every UI tick)
+pub const MIN_DATA_MULTIPLIER: u64 = 1;
+
+/// Maximum data refresh multiplier
+pub const MAX_DATA_MULTIPLIER: u64 = 100;
+
+/// Default time a refresh waits for the backend before the watchdog
+/// keeps the last snapshot
+pub const DEFAULT_COLLECT_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Shortest collection timeout accepted
+pub const MIN_COLLECT_TIMEOUT_MS: u64 = 100;
+
 /// Duration to highlight recently changed refresh intervals
 pub const CHANGE_HIGHLIGHT_DURATION: Duration = Duration::from_millis(500);
 
+/// Data refreshes kept in memory for the timeline
+pub const HISTORY_SNAPSHOTS: usize = 120;
+
+/// Snapshots (oldest to the one on screen) a trend arrow looks at
+pub const TREND_WINDOW: usize = 6;
+
+/// Least change in connections across the trend window that is a trend
+pub const TREND_MIN_CHANGE: usize = 2;
+
+/// ...and least change relative to the mean count (10%), so busy counts
+/// don't trend on noise
+pub const TREND_MIN_CHANGE_RATIO: f64 = 0.1;
+
+/// View-state changes `u` can undo
+pub const UNDO_DEPTH: usize = 20;
+
+/// How long a toast (e.g. "Copied to clipboard") stays visible
+pub const TOAST_DURATION: Duration = Duration::from_secs(3);
+
+/// How long an error toast stays visible
+pub const ERROR_TOAST_DURATION: Duration = Duration::from_secs(6);
+
+/// Toasts stacked over the status bar at once
+pub const MAX_TOASTS: usize = 3;
+
 /// Tick interval for pulse animation (100ms)
 pub const TICK_INTERVAL_MS: u128 = 100;
 
+/// Data older than this many data intervals is shown late (Pumpkin
+/// Orange) in the status bar...
+pub const DATA_LATE_INTERVALS: f64 = 1.5;
+
+/// ...and this many stale (Blood Red)
+pub const DATA_STALE_INTERVALS: f64 = 3.0;
+
 /// Blink interval for zombie animation (500ms)
 pub const BLINK_INTERVAL_MS: u128 = 500;
 
 /// Frame time threshold for auto-reducing animation complexity (100ms)
-/// If frame time consistently exceeds this, particle count is reduced
+/// If frame time consistently exceeds this, the animation quality drops a tier
 pub const FRAME_TIME_THRESHOLD_MS: u128 = 100;
 
-/// Number of consecutive slow frames before triggering complexity reduction
+/// Number of consecutive slow frames before each one-tier quality reduction
 pub const SLOW_FRAME_COUNT_THRESHOLD: u32 = 5;
 
+/// Default number of endpoints shown in the Graveyard canvas at once
+/// (8 keeps the rings around the central HOST readable)
+pub const MAX_VISIBLE_ENDPOINTS: usize = 8;
+
+/// Séance view: processes tiled into mini-graveyards
+pub const SEANCE_PROCESSES: usize = 6;
+
+/// Séance view: endpoints drawn around each process coffin
+pub const SEANCE_ENDPOINTS: usize = 6;
+
+/// Port heatmap: share of the ephemeral range in use (percent) shown as
+/// a port exhaustion warning
+pub const PORT_EXHAUSTION_PERCENT: f64 = 50.0;
+
+/// Overdrive ambiance: ticks between bat flights (~30s at 100ms ticks)
+pub const BAT_PERIOD_TICKS: u64 = 300;
+
+/// Overdrive ambiance: ticks a bat takes to cross the canvas
+pub const BAT_FLIGHT_TICKS: u64 = 60;
+
+/// Overdrive ambiance: how long the lightning flash lasts after an alert
+pub const LIGHTNING_DURATION: Duration = Duration::from_millis(250);
+
+/// Alarm: how long the banner stays inverted after an alarmed alert
+pub const ALARM_FLASH_DURATION: Duration = Duration::from_secs(1);
+
+/// Screensaver: ticks between frames (the orbit is slow, so ~2 fps will do)
+pub const SCREENSAVER_FRAME_TICKS: u64 = 5;
+
+/// How often the theme schedule re-reads the clock
+pub const THEME_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// How far back the performance overlay graphs frame and collection times
+pub const PERF_WINDOW: Duration = Duration::from_secs(60);
+
 /// Number of log entries in the grimoire (for bounds checking)
 #[allow(dead_code)]
 pub const LOG_ENTRY_COUNT: usize = 6;
@@ -93,6 +175,19 @@ pub struct GraveyardSettings {
     /// Negative: emoji renders narrower than expected
     /// Adjust with '[' and ']' keys
     pub emoji_width_offset: i32,
+
+    /// Particle, ring and pulse detail (cycle with '*' key)
+    pub animation_quality: AnimationQuality,
+
+    /// Fog, bats and lightning behind the map in overdrive mode
+    pub ambiance_enabled: bool,
+
+    /// Endpoints drawn on the canvas at once; the rest are reached by
+    /// paging with '{' and '}'
+    pub max_visible_endpoints: usize,
+
+    /// Endpoints crowned as heavy talkers (adjust in the ',' popup)
+    pub heavy_talkers: HeavyTalkerThreshold,
 }
 
 impl Default for GraveyardSettings {
@@ -102,6 +197,10 @@ impl Default for GraveyardSettings {
             labels_enabled: true,
             overdrive_enabled: false, // Off by default per requirements
             emoji_width_offset: 0,    // Will be set from detection at startup
+            animation_quality: AnimationQuality::Normal,
+            ambiance_enabled: true,
+            max_visible_endpoints: MAX_VISIBLE_ENDPOINTS,
+            heavy_talkers: HeavyTalkerThreshold::default(),
         }
     }
 }
@@ -128,10 +227,16 @@ impl Default for LatencyConfig {
 /// Configuration for refresh intervals (unified)
 #[derive(Debug, Clone)]
 pub struct RefreshConfig {
-    /// Refresh interval in milliseconds (50-1000ms)
-    /// Data collection uses this * DATA_REFRESH_MULTIPLIER
+    /// Refresh interval in milliseconds (50-10000ms)
+    /// Data collection uses this * data_multiplier
     pub refresh_ms: u64,
 
+    /// Data refreshes every N UI intervals (1-100)
+    pub data_multiplier: u64,
+
+    /// Longest wait for the backend before the last snapshot is kept
+    pub collect_timeout: Duration,
+
     /// Timestamp of last interval change (for visual feedback)
     pub last_change: Option<Instant>,
 }
@@ -141,6 +246,23 @@ impl RefreshConfig {
     pub fn new() -> Self {
         Self {
             refresh_ms: 500,
+            data_multiplier: DATA_REFRESH_MULTIPLIER,
+            collect_timeout: DEFAULT_COLLECT_TIMEOUT,
+            last_change: None,
+        }
+    }
+
+    /// Create a RefreshConfig from user settings, clamping to valid ranges
+    pub fn with_settings(refresh_ms: Option<u64>, data_multiplier: Option<u64>) -> Self {
+        let defaults = Self::new();
+        Self {
+            refresh_ms: refresh_ms
+                .unwrap_or(defaults.refresh_ms)
+                .clamp(MIN_REFRESH_MS, MAX_REFRESH_MS),
+            data_multiplier: data_multiplier
+                .unwrap_or(defaults.data_multiplier)
+                .clamp(MIN_DATA_MULTIPLIER, MAX_DATA_MULTIPLIER),
+            collect_timeout: defaults.collect_timeout,
             last_change: None,
         }
     }
@@ -150,9 +272,9 @@ impl RefreshConfig {
         Duration::from_millis(self.refresh_ms)
     }
 
-    /// Get data refresh interval as Duration (10x UI interval)
+    /// Get data refresh interval as Duration (data_multiplier x UI interval)
     pub fn data_interval(&self) -> Duration {
-        Duration::from_millis(self.refresh_ms * DATA_REFRESH_MULTIPLIER)
+        Duration::from_millis(self.refresh_ms * self.data_multiplier)
     }
 }
 
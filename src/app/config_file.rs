@@ -0,0 +1,482 @@
+// User configuration file
+//
+// Optional TOML file loaded at startup from `--config <path>` or the
+// default location ($XDG_CONFIG_HOME/ntomb/config.toml, falling back to
+// ~/.config/ntomb/config.toml). Every section and key is optional; missing
+// keys keep the built-in defaults, and CLI flags override file values.
+// Edits made while running are picked up by config_watch.rs.
+//
+// Example:
+//
+// ```toml
+// [refresh]
+// ui_interval_ms = 250
+// data_multiplier = 8
+//
+// [[endpoint_rules]]
+// cidr = "10.1.0.0/16"
+// label = "prod-db"
+// color = "blood_red"
+// environment = "prod"
+//
+// [[environment_alerts]]
+// environment = "prod"
+// filter = "proc ~ staging"
+//
+// [threat_intel]
+// blocklist = "/etc/ntomb/drop.txt"
+//
+// [cloud_ranges]
+// files = ["/var/lib/ntomb/ip-ranges.json"]
+//
+// [asn]
+// database = "/var/lib/ntomb/ip2asn-combined.tsv"
+//
+// [dns]
+// reverse = true
+//
+// [cache]
+// ttl = "3d"
+//
+// [probes]
+// rate = 2
+//
+// [peers]
+// file = "/var/lib/ntomb/peers.tsv"
+//
+// [anomaly]
+// learn_minutes = 120
+//
+// [autosave]
+// dir = "/var/lib/ntomb/snapshots"
+//
+// [tombstones]
+// enabled = true
+//
+// [anonymizers]
+// tor_exits = "/var/lib/tor/cached-microdesc-consensus"
+//
+// [inventory]
+// file = "/etc/ntomb/inventory.csv"
+//
+// [syn_storm]
+// rate_per_sec = 50
+//
+// [[watches]]
+// name = "pg-conns"
+// expr = 'count(proc == "postgres") > 90'
+//
+// [alarm]
+// bell = ["critical"]
+//
+// [forward]
+// target = "journald"
+//
+// [otlp]
+// endpoint = "http://localhost:4318/v1/metrics"
+//
+// [history]
+// file = "/var/lib/ntomb/events.ndjson"
+// retention = "7d"
+//
+// [scripting]
+// script = "/etc/ntomb/hooks.rhai"
+//
+// [keys]
+// profile = "vim"
+//
+// [animation]
+// quality = "lush"
+// ambiance = true
+//
+// [graveyard]
+// max_endpoints = 12
+// heavy_talkers = { top = 3 }
+// host_name = "db-primary"
+//
+// [theme]
+// season = "auto"
+// night = "auto"
+// night_start = 20
+// night_end = 7
+// utc_offset_minutes = 540
+//
+// [screensaver]
+// idle_secs = 600
+//
+// [glyphs]
+// preset = "nerd-font"
+// crown = "*"
+// flags = false
+// ```
+
+use super::alarm::AlarmConfig;
+use super::anomaly::AnomalyConfig;
+use super::anonymizers::AnonymizersConfig;
+use super::asn::AsnConfig;
+use super::autosave::AutosaveConfig;
+use super::cloud_ranges::CloudRangesConfig;
+use super::dns::DnsConfig;
+use super::endpoint_rules::{EndpointRuleConfig, EnvironmentAlertConfig};
+use super::enrich_cache::CacheConfig;
+use super::forward::ForwardConfig;
+use super::heavy_talkers::HeavyTalkerThreshold;
+use super::history::HistoryConfig;
+use super::inventory::InventoryConfig;
+use super::keymap::KeysConfig;
+use super::otlp::OtlpConfig;
+use super::peers::PeersConfig;
+use super::probes::ProbesConfig;
+use super::quality::AnimationConfig;
+use super::scripting::ScriptingConfig;
+use super::syn_storm::SynStormConfig;
+use super::theme_schedule::ThemeConfig;
+use super::threat_intel::ThreatIntelConfig;
+use super::tombstones::TombstonesConfig;
+use super::watch::WatchConfig;
+use crate::theme::glyphs::GlyphsConfig;
+use serde::Deserialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+/// Errors raised while loading the config file
+#[derive(Debug, Error)]
+pub enum ConfigError {
+    #[error("cannot read config file {path}: {source}")]
+    Read {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[error("invalid config file {path}: {source}")]
+    Parse {
+        path: PathBuf,
+        source: toml::de::Error,
+    },
+}
+
+/// Contents of config.toml
+#[derive(Debug, Clone, Default, Deserialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct ConfigFile {
+    /// `[refresh]` section
+    pub refresh: RefreshSection,
+
+    /// `[[endpoint_rules]]` tables: CIDR → label/color/icon
+    pub endpoint_rules: Vec<EndpointRuleConfig>,
+
+    /// `[[environment_alerts]]` tables: alert when processes reach an environment
+    pub environment_alerts: Vec<EnvironmentAlertConfig>,
+
+    /// `[threat_intel]` section: blocklist file and optional feed
+    pub threat_intel: ThreatIntelConfig,
+
+    /// `[cloud_ranges]` section: provider IP range files
+    pub cloud_ranges: CloudRangesConfig,
+
+    /// `[asn]` section: ASN database for per-organization grouping
+    pub asn: AsnConfig,
+
+    /// `[dns]` section: reverse DNS names for endpoints
+    pub dns: DnsConfig,
+
+    /// `[cache]` section: DNS, RDAP and package lookups kept on disk
+    pub cache: CacheConfig,
+
+    /// `[probes]` section: rate limit for everything that sends packets
+    pub probes: ProbesConfig,
+
+    /// `[peers]` section: first/last-seen database of remote addresses
+    pub peers: PeersConfig,
+
+    /// `[anomaly]` section: learning period and alert score of the
+    /// per-process baselines
+    pub anomaly: AnomalyConfig,
+
+    /// `[autosave]` section: periodic snapshot files and their rotation
+    pub autosave: AutosaveConfig,
+
+    /// `[tombstones]` section: connections closed between two snapshots
+    pub tombstones: TombstonesConfig,
+
+    /// `[anonymizers]` section: Tor exit list and VPN range files
+    pub anonymizers: AnonymizersConfig,
+
+    /// `[inventory]` section: CMDB asset names, owners and criticality
+    pub inventory: InventoryConfig,
+
+    /// `[syn_storm]` section: half-open socket thresholds
+    pub syn_storm: SynStormConfig,
+
+    /// `[[watches]]` tables: connection count thresholds
+    pub watches: Vec<WatchConfig>,
+
+    /// `[alarm]` section: terminal bell and banner flash per severity
+    pub alarm: AlarmConfig,
+
+    /// `[forward]` section: copy events to syslog or journald
+    pub forward: ForwardConfig,
+
+    /// `[otlp]` section: OpenTelemetry metrics collector
+    pub otlp: OtlpConfig,
+
+    /// `[history]` section: Grimoire events kept on disk
+    pub history: HistoryConfig,
+
+    /// `[scripting]` section: Rhai event hooks
+    pub scripting: ScriptingConfig,
+
+    /// `[keys]` section: keymap profile
+    pub keys: KeysConfig,
+
+    /// `[animation]` section: starting animation quality tier and the
+    /// overdrive ambiance switch
+    pub animation: AnimationConfig,
+
+    /// `[graveyard]` section: network map layout
+    pub graveyard: GraveyardSection,
+
+    /// `[theme]` section: seasonal and night-time theme schedule
+    pub theme: ThemeConfig,
+
+    /// `[screensaver]` section: idle time before the ambient display
+    pub screensaver: ScreensaverSection,
+
+    /// `[glyphs]` section: symbol preset and single-glyph overrides
+    pub glyphs: GlyphsConfig,
+}
+
+/// `[refresh]` section: UI and data refresh cadence
+#[derive(Debug, Clone, Default, Deserialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct RefreshSection {
+    /// UI refresh interval in milliseconds
+    pub ui_interval_ms: Option<u64>,
+    /// Data refreshes every N UI intervals
+    pub data_multiplier: Option<u64>,
+    /// Longest wait for a snapshot before the watchdog keeps the last one
+    pub collect_timeout_ms: Option<u64>,
+}
+
+/// `[graveyard]` section: network map layout
+#[derive(Debug, Clone, Default, Deserialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct GraveyardSection {
+    /// Endpoints drawn at once before paging
+    pub max_endpoints: Option<usize>,
+    /// Endpoints crowned as heavy talkers: `{ top = N }` or `{ min = N }`
+    pub heavy_talkers: Option<HeavyTalkerThreshold>,
+    /// Name in the coffin instead of the machine's hostname
+    pub host_name: Option<String>,
+}
+
+/// `[screensaver]` section: ambient display for unattended screens
+#[derive(Debug, Clone, Default, Deserialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct ScreensaverSection {
+    /// Seconds without a keypress before the screensaver starts; unset
+    /// never starts it
+    pub idle_secs: Option<u64>,
+}
+
+impl ConfigFile {
+    /// Load the config from `path`, or from the default location when None
+    ///
+    /// A missing file at the default location is not an error; a missing
+    /// explicitly requested file is.
+    pub fn load(path: Option<&Path>) -> Result<Self, ConfigError> {
+        let (path, required) = match path {
+            Some(path) => (path.to_path_buf(), true),
+            None => match default_path() {
+                Some(path) => (path, false),
+                None => return Ok(Self::default()),
+            },
+        };
+
+        let content = match fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(e) if !required && e.kind() == std::io::ErrorKind::NotFound => {
+                return Ok(Self::default());
+            }
+            Err(source) => return Err(ConfigError::Read { path, source }),
+        };
+
+        Self::parse(&content).map_err(|source| ConfigError::Parse { path, source })
+    }
+
+    /// Parse config TOML
+    pub fn parse(content: &str) -> Result<Self, toml::de::Error> {
+        toml::from_str(content)
+    }
+}
+
+/// Default config location: $XDG_CONFIG_HOME/ntomb/config.toml or
+/// ~/.config/ntomb/config.toml
+pub fn default_path() -> Option<PathBuf> {
+    let base = std::env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    Some(base.join("ntomb").join("config.toml"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_refresh_section() {
+        let config =
+            ConfigFile::parse("[refresh]\nui_interval_ms = 250\ndata_multiplier = 4\n").unwrap();
+        assert_eq!(config.refresh.ui_interval_ms, Some(250));
+        assert_eq!(config.refresh.data_multiplier, Some(4));
+    }
+
+    #[test]
+    fn test_parse_empty_and_unknown_keys() {
+        assert_eq!(ConfigFile::parse("").unwrap(), ConfigFile::default());
+        assert!(ConfigFile::parse("[refresh]\nui_intervl_ms = 5\n").is_err());
+    }
+
+    #[test]
+    fn test_parse_endpoint_rules() {
+        let config = ConfigFile::parse(
+            "[[endpoint_rules]]\ncidr = \"10.1.0.0/16\"\nlabel = \"prod-db\"\ncolor = \"blood_red\"\n\n\
+             [[endpoint_rules]]\ncidr = \"192.168.0.0/16\"\nlabel = \"office\"\nicon = \"🏢\"\n",
+        )
+        .unwrap();
+        assert_eq!(config.endpoint_rules.len(), 2);
+        assert_eq!(config.endpoint_rules[0].label, "prod-db");
+        assert_eq!(config.endpoint_rules[1].icon.as_deref(), Some("🏢"));
+        assert!(
+            ConfigFile::parse("[[endpoint_rules]]\ncidr = \"10.0.0.0/8\"\nlable = \"x\"\n")
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_parse_threat_intel_section() {
+        let config = ConfigFile::parse(
+            "[threat_intel]\nfeed_url = \"https://example.com/drop.txt\"\nrefresh_hours = 6\n",
+        )
+        .unwrap();
+        assert!(config.threat_intel.is_enabled());
+        assert_eq!(config.threat_intel.refresh_hours, Some(6));
+        assert!(!ConfigFile::default().threat_intel.is_enabled());
+    }
+
+    #[test]
+    fn test_parse_cloud_ranges_section() {
+        let config =
+            ConfigFile::parse("[cloud_ranges]\nfiles = [\"ip-ranges.json\"]\nbundled = false\n")
+                .unwrap();
+        assert_eq!(
+            config.cloud_ranges.files,
+            vec![PathBuf::from("ip-ranges.json")]
+        );
+        assert_eq!(config.cloud_ranges.bundled, Some(false));
+    }
+
+    #[test]
+    fn test_parse_watches() {
+        let config = ConfigFile::parse(
+            "[[watches]]\nname = \"pg\"\nexpr = 'count(proc == \"postgres\") > 90'\nnotify = true\n",
+        )
+        .unwrap();
+        assert_eq!(config.watches.len(), 1);
+        assert_eq!(config.watches[0].name.as_deref(), Some("pg"));
+        assert_eq!(config.watches[0].expr, "count(proc == \"postgres\") > 90");
+        assert!(config.watches[0].notify);
+    }
+
+    #[test]
+    fn test_parse_forward() {
+        let config =
+            ConfigFile::parse("[forward]\ntarget = \"syslog\"\naddress = \"udp://loghost:514\"\n")
+                .unwrap();
+        assert_eq!(config.forward.target.as_deref(), Some("syslog"));
+        assert_eq!(config.forward.address.as_deref(), Some("udp://loghost:514"));
+        assert!(ConfigFile::parse("[forward]\nfacility = \"daemon\"\n").is_err());
+    }
+
+    #[test]
+    fn test_parse_keys() {
+        use crate::app::keymap::KeymapProfile;
+        let config = ConfigFile::parse("[keys]\nprofile = \"vim\"\n").unwrap();
+        assert_eq!(config.keys.profile, KeymapProfile::Vim);
+        assert_eq!(
+            ConfigFile::parse("").unwrap().keys.profile,
+            KeymapProfile::Default
+        );
+        assert!(ConfigFile::parse("[keys]\nprofile = \"emacs\"\n").is_err());
+    }
+
+    #[test]
+    fn test_parse_animation() {
+        use crate::app::quality::AnimationQuality;
+        let config = ConfigFile::parse("[animation]\nquality = \"low\"\n").unwrap();
+        assert_eq!(config.animation.quality, AnimationQuality::Low);
+        assert_eq!(
+            ConfigFile::parse("").unwrap().animation.quality,
+            AnimationQuality::Normal
+        );
+        assert!(ConfigFile::parse("[animation]\nquality = \"max\"\n").is_err());
+        assert!(ConfigFile::parse("").unwrap().animation.ambiance);
+        let config = ConfigFile::parse("[animation]\nambiance = false\n").unwrap();
+        assert!(!config.animation.ambiance);
+    }
+
+    #[test]
+    fn test_parse_graveyard() {
+        let config = ConfigFile::parse("[graveyard]\nmax_endpoints = 12\n").unwrap();
+        assert_eq!(config.graveyard.max_endpoints, Some(12));
+        assert!(ConfigFile::parse("[graveyard]\nmax_endpoints = -1\n").is_err());
+
+        let config = ConfigFile::parse("[graveyard]\nheavy_talkers = { min = 40 }\n").unwrap();
+        assert_eq!(
+            config.graveyard.heavy_talkers,
+            Some(HeavyTalkerThreshold::Min(40))
+        );
+        assert!(ConfigFile::parse("[graveyard]\nheavy_talkers = { top = 3, min = 4 }\n").is_err());
+    }
+
+    #[test]
+    fn test_parse_screensaver() {
+        let config = ConfigFile::parse("[screensaver]\nidle_secs = 600\n").unwrap();
+        assert_eq!(config.screensaver.idle_secs, Some(600));
+        assert_eq!(ConfigFile::parse("").unwrap().screensaver.idle_secs, None);
+    }
+
+    #[test]
+    fn test_parse_glyphs() {
+        use crate::theme::glyphs::GlyphPreset;
+
+        let config = ConfigFile::parse("[glyphs]\npreset = \"ascii\"\ncrown = \"^\"\n").unwrap();
+        assert_eq!(config.glyphs.preset, Some(GlyphPreset::Ascii));
+        assert_eq!(config.glyphs.build(false).crown, "^");
+        let config = ConfigFile::parse("[glyphs]\npreset = \"nerd-font\"\n").unwrap();
+        assert_eq!(config.glyphs.preset, Some(GlyphPreset::NerdFont));
+        assert!(ConfigFile::parse("[glyphs]\nskull = \"x\"\n").is_err());
+    }
+
+    #[test]
+    fn test_parse_theme() {
+        use crate::app::theme_schedule::{Night, Season};
+        let config = ConfigFile::parse(
+            "[theme]\nseason = \"plain\"\nnight = \"dim\"\nutc_offset_minutes = -300\n",
+        )
+        .unwrap();
+        assert_eq!(config.theme.season, Season::Plain);
+        assert_eq!(config.theme.night, Night::Dim);
+        assert_eq!(config.theme.night_start, 20);
+        assert_eq!(config.theme.utc_offset_minutes, -300);
+        assert_eq!(ConfigFile::parse("").unwrap().theme, ThemeConfig::default());
+        assert!(ConfigFile::parse("[theme]\nseason = \"spooky\"\n").is_err());
+    }
+
+    #[test]
+    fn test_load_missing_explicit_path_fails() {
+        let result = ConfigFile::load(Some(Path::new("/nonexistent/ntomb/config.toml")));
+        assert!(matches!(result, Err(ConfigError::Read { .. })));
+    }
+}
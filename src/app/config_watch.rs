@@ -0,0 +1,216 @@
+// Config file hot-reload
+//
+// Watches config.toml for edits so thresholds, themes, rules and
+// classification ranges can be tuned without restarting. The parent
+// directory is watched rather than the file itself: most editors save by
+// writing a new file and renaming it over the old one, which would end a
+// watch on the original inode. Bursts of events from a single save are
+// debounced into one reload.
+
+use super::config_file::ConfigFile;
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver};
+use std::time::{Duration, Instant};
+
+/// Quiet time after the last file event before the config is re-read,
+/// so a save written in several steps is only parsed once it is complete
+const SETTLE_TIME: Duration = Duration::from_millis(250);
+
+/// Watches the config file and reports when it has settled after a change
+pub struct ConfigWatcher {
+    path: PathBuf,
+    file_name: OsString,
+    /// Kept alive for as long as events should be delivered
+    _watcher: RecommendedWatcher,
+    events: Receiver<notify::Result<Event>>,
+    /// Time of the last relevant event not yet reported
+    pending: Option<Instant>,
+}
+
+impl ConfigWatcher {
+    /// Start watching `path`; the file itself does not need to exist yet
+    pub fn spawn(path: &Path) -> Result<Self, String> {
+        let file_name = path
+            .file_name()
+            .ok_or_else(|| format!("{} is not a file path", path.display()))?
+            .to_os_string();
+        let dir = match path.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir,
+            _ => Path::new("."),
+        };
+
+        let (tx, events) = mpsc::channel();
+        let mut watcher = notify::recommended_watcher(move |event| {
+            let _ = tx.send(event);
+        })
+        .map_err(|e| format!("cannot watch {}: {}", dir.display(), e))?;
+        watcher
+            .watch(dir, RecursiveMode::NonRecursive)
+            .map_err(|e| format!("cannot watch {}: {}", dir.display(), e))?;
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            file_name,
+            _watcher: watcher,
+            events,
+            pending: None,
+        })
+    }
+
+    /// Path of the watched config file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Drain pending file events; true once the file changed and has
+    /// been quiet for `SETTLE_TIME`
+    pub fn poll(&mut self, now: Instant) -> bool {
+        while let Ok(event) = self.events.try_recv() {
+            if let Ok(event) = event {
+                if touches(&event, &self.file_name) {
+                    self.pending = Some(now);
+                }
+            }
+        }
+        settled(&mut self.pending, now)
+    }
+}
+
+/// Whether `event` changed the file called `file_name`
+fn touches(event: &Event, file_name: &OsString) -> bool {
+    matches!(
+        event.kind,
+        EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)
+    ) && event
+        .paths
+        .iter()
+        .any(|path| path.file_name() == Some(file_name.as_os_str()))
+}
+
+/// Report and clear a pending change once it is `SETTLE_TIME` old
+fn settled(pending: &mut Option<Instant>, now: Instant) -> bool {
+    match *pending {
+        Some(since) if now.duration_since(since) >= SETTLE_TIME => {
+            *pending = None;
+            true
+        }
+        _ => false,
+    }
+}
+
+/// Config sections that can be applied without a restart
+pub const LIVE_SECTIONS: &[&str] = &[
+    "endpoint_rules",
+    "environment_alerts",
+    "threat_intel",
+    "cloud_ranges",
+    "anonymizers",
+    "inventory",
+    "syn_storm",
+    "watches",
+    "alarm",
+    "probes",
+    "keys",
+    "animation",
+    "graveyard",
+    "theme",
+    "screensaver",
+];
+
+/// Names of the top-level sections that differ between `old` and `new`
+pub fn changed_sections(old: &ConfigFile, new: &ConfigFile) -> Vec<&'static str> {
+    let sections = [
+        ("refresh", old.refresh != new.refresh),
+        ("endpoint_rules", old.endpoint_rules != new.endpoint_rules),
+        (
+            "environment_alerts",
+            old.environment_alerts != new.environment_alerts,
+        ),
+        ("threat_intel", old.threat_intel != new.threat_intel),
+        ("cloud_ranges", old.cloud_ranges != new.cloud_ranges),
+        ("asn", old.asn != new.asn),
+        ("dns", old.dns != new.dns),
+        ("cache", old.cache != new.cache),
+        ("probes", old.probes != new.probes),
+        ("peers", old.peers != new.peers),
+        ("anomaly", old.anomaly != new.anomaly),
+        ("autosave", old.autosave != new.autosave),
+        ("tombstones", old.tombstones != new.tombstones),
+        ("anonymizers", old.anonymizers != new.anonymizers),
+        ("inventory", old.inventory != new.inventory),
+        ("syn_storm", old.syn_storm != new.syn_storm),
+        ("watches", old.watches != new.watches),
+        ("alarm", old.alarm != new.alarm),
+        ("forward", old.forward != new.forward),
+        ("otlp", old.otlp != new.otlp),
+        ("history", old.history != new.history),
+        ("scripting", old.scripting != new.scripting),
+        ("keys", old.keys != new.keys),
+        ("animation", old.animation != new.animation),
+        ("graveyard", old.graveyard != new.graveyard),
+        ("theme", old.theme != new.theme),
+        ("screensaver", old.screensaver != new.screensaver),
+        ("glyphs", old.glyphs != new.glyphs),
+    ];
+    sections
+        .into_iter()
+        .filter(|(_, changed)| *changed)
+        .map(|(name, _)| name)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use notify::event::{AccessKind, CreateKind, ModifyKind};
+
+    fn event(kind: EventKind, path: &str) -> Event {
+        Event::new(kind).add_path(PathBuf::from(path))
+    }
+
+    #[test]
+    fn test_touches_only_the_config_file() {
+        let name = OsString::from("config.toml");
+        let modify = EventKind::Modify(ModifyKind::Any);
+        assert!(touches(&event(modify, "/etc/ntomb/config.toml"), &name));
+        assert!(touches(
+            &event(
+                EventKind::Create(CreateKind::File),
+                "/etc/ntomb/config.toml"
+            ),
+            &name
+        ));
+        assert!(!touches(
+            &event(modify, "/etc/ntomb/.config.toml.swp"),
+            &name
+        ));
+        assert!(!touches(
+            &event(EventKind::Access(AccessKind::Any), "/etc/ntomb/config.toml"),
+            &name
+        ));
+    }
+
+    #[test]
+    fn test_settled_waits_for_quiet_time() {
+        let start = Instant::now();
+        let mut pending = None;
+        assert!(!settled(&mut pending, start));
+
+        pending = Some(start);
+        assert!(!settled(&mut pending, start + SETTLE_TIME / 2));
+        assert!(settled(&mut pending, start + SETTLE_TIME));
+        assert_eq!(pending, None);
+        assert!(!settled(&mut pending, start + SETTLE_TIME * 2));
+    }
+
+    #[test]
+    fn test_changed_sections() {
+        let old = ConfigFile::parse("[graveyard]\nmax_endpoints = 12\n").unwrap();
+        let new =
+            ConfigFile::parse("[graveyard]\nmax_endpoints = 8\n\n[dns]\nreverse = true\n").unwrap();
+        assert_eq!(changed_sections(&old, &new), vec!["dns", "graveyard"]);
+        assert!(changed_sections(&new, &new).is_empty());
+    }
+}
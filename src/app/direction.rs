@@ -0,0 +1,234 @@
+// Inbound vs outbound connections
+//
+// A socket is inbound when its local end is one of our LISTEN ports: a
+// peer connected to a service we offer. Everything else was opened by us.
+// Half-open sockets give it away too (SYN_RECV is inbound, SYN_SENT
+// outbound). A socket keeps the direction it was first seen with, so an
+// accepted connection stays inbound after its listener closes.
+// The split view (`s`) shows the two sides next to each other.
+
+use super::snapshot::{connection_key, ConnectionKey};
+use crate::net::{Connection, ConnectionDirection, ConnectionState};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// LISTEN sockets by local port, with the addresses they are bound to
+#[derive(Debug, Clone, Default)]
+pub struct Listeners {
+    ports: HashMap<u16, Vec<Arc<str>>>,
+}
+
+impl Listeners {
+    /// Collect the LISTEN sockets of `conns`
+    pub fn from_connections<'a>(conns: impl IntoIterator<Item = &'a Connection>) -> Self {
+        let mut ports: HashMap<u16, Vec<Arc<str>>> = HashMap::new();
+        for conn in conns {
+            if conn.state == ConnectionState::Listen {
+                ports
+                    .entry(conn.local_port)
+                    .or_default()
+                    .push(conn.local_addr.clone());
+            }
+        }
+        Self { ports }
+    }
+
+    /// Whether `conn` was accepted by one of the listeners
+    pub fn accepted(&self, conn: &Connection) -> bool {
+        if conn.state == ConnectionState::Listen {
+            return false;
+        }
+        self.ports.get(&conn.local_port).is_some_and(|addrs| {
+            addrs
+                .iter()
+                .any(|addr| matches!(&**addr, "0.0.0.0" | "::") || *addr == conn.local_addr)
+        })
+    }
+}
+
+/// Directions of the sockets seen so far
+#[derive(Debug, Clone, Default)]
+pub struct DirectionHistory {
+    known: HashMap<ConnectionKey, ConnectionDirection>,
+}
+
+impl DirectionHistory {
+    /// Set `direction` on every connection of a fresh snapshot
+    ///
+    /// Sockets that are gone are forgotten.
+    pub fn classify(&mut self, conns: &mut [Connection]) {
+        let listeners = Listeners::from_connections(conns.iter());
+        let mut known = HashMap::with_capacity(conns.len());
+        for conn in conns.iter_mut() {
+            if conn.state == ConnectionState::Listen {
+                conn.direction = None;
+                continue;
+            }
+            let key = connection_key(conn);
+            let direction = match self.known.get(&key) {
+                Some(direction) => *direction,
+                None => infer(&listeners, conn),
+            };
+            conn.direction = Some(direction);
+            known.insert(key, direction);
+        }
+        self.known = known;
+    }
+}
+
+/// Direction of a socket seen for the first time
+fn infer(listeners: &Listeners, conn: &Connection) -> ConnectionDirection {
+    match conn.state {
+        ConnectionState::SynRecv => ConnectionDirection::Inbound,
+        ConnectionState::SynSent => ConnectionDirection::Outbound,
+        _ if listeners.accepted(conn) => ConnectionDirection::Inbound,
+        _ => ConnectionDirection::Outbound,
+    }
+}
+
+/// A LISTEN socket and the peers connected to it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Service {
+    pub listener: Connection,
+    pub peers: Vec<Connection>,
+}
+
+/// Connections split into services we offer and connections we opened
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SplitConnections {
+    /// "Souls we summon": listeners with their inbound peers
+    pub services: Vec<Service>,
+    /// "Souls we seek": outbound connections
+    pub outbound: Vec<Connection>,
+}
+
+/// Split `conns` into services and outbound connections
+///
+/// Listeners are ordered by port; a peer is listed under the first
+/// listener on its local port, or under a stand-in for a listener that
+/// has since closed. Connections not yet classified are judged by the
+/// listeners present.
+pub fn split(conns: &[Connection]) -> SplitConnections {
+    let listeners = Listeners::from_connections(conns);
+    let mut services: Vec<Service> = conns
+        .iter()
+        .filter(|c| c.state == ConnectionState::Listen)
+        .map(|c| Service {
+            listener: c.clone(),
+            peers: Vec::new(),
+        })
+        .collect();
+
+    let mut outbound = Vec::new();
+    for conn in conns.iter().filter(|c| c.state != ConnectionState::Listen) {
+        let direction = conn.direction.unwrap_or_else(|| infer(&listeners, conn));
+        if direction == ConnectionDirection::Outbound {
+            outbound.push(conn.clone());
+            continue;
+        }
+        let position = services.iter().position(|s| {
+            s.listener.local_port == conn.local_port
+                && (matches!(&*s.listener.local_addr, "0.0.0.0" | "::")
+                    || s.listener.local_addr == conn.local_addr)
+        });
+        let index = position.unwrap_or_else(|| {
+            services.push(Service {
+                listener: Connection {
+                    local_addr: conn.local_addr.clone(),
+                    local_port: conn.local_port,
+                    remote_addr: "0.0.0.0".into(),
+                    state: ConnectionState::Listen,
+                    pid: conn.pid,
+                    process_name: conn.process_name.clone(),
+                    ..Default::default()
+                },
+                peers: Vec::new(),
+            });
+            services.len() - 1
+        });
+        services[index].peers.push(conn.clone());
+    }
+    services.sort_by(|a, b| {
+        (a.listener.local_port, &a.listener.local_addr)
+            .cmp(&(b.listener.local_port, &b.listener.local_addr))
+    });
+    SplitConnections { services, outbound }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn conn(
+        local: &str,
+        lport: u16,
+        remote: &str,
+        rport: u16,
+        state: ConnectionState,
+    ) -> Connection {
+        Connection {
+            local_addr: local.into(),
+            local_port: lport,
+            remote_addr: remote.into(),
+            remote_port: rport,
+            state,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_split_inbound_and_outbound() {
+        use ConnectionState::*;
+        let conns = vec![
+            conn("0.0.0.0", 22, "0.0.0.0", 0, Listen),
+            conn("127.0.0.1", 5432, "0.0.0.0", 0, Listen),
+            conn("10.0.0.5", 22, "203.0.113.9", 51234, Established),
+            conn("10.0.0.5", 5432, "10.0.0.7", 40000, Established),
+            conn("127.0.0.1", 5432, "127.0.0.1", 40001, Established),
+            conn("10.0.0.5", 51000, "93.184.216.34", 443, Established),
+        ];
+        let split = split(&conns);
+        assert_eq!(split.services.len(), 2);
+        assert_eq!(split.services[0].listener.local_port, 22);
+        assert_eq!(split.services[0].peers.len(), 1);
+        // 5432 only listens on loopback, so the 10.0.0.5:5432 socket is ours
+        assert_eq!(split.services[1].peers.len(), 1);
+        assert_eq!(split.outbound.len(), 2);
+        assert_eq!(split.outbound[1].remote_port, 443);
+    }
+
+    #[test]
+    fn test_direction_history() {
+        use ConnectionState::*;
+        let listener = conn("0.0.0.0", 22, "0.0.0.0", 0, Listen);
+        let accepted = conn("10.0.0.5", 22, "203.0.113.9", 51234, Established);
+        let mut conns = vec![
+            listener,
+            accepted.clone(),
+            conn("10.0.0.5", 51000, "93.184.216.34", 443, Established),
+            conn("10.0.0.5", 8080, "203.0.113.9", 40000, SynRecv),
+        ];
+        let mut history = DirectionHistory::default();
+        history.classify(&mut conns);
+        let directions: Vec<_> = conns.iter().map(|c| c.direction).collect();
+        assert_eq!(
+            directions,
+            vec![
+                None,
+                Some(ConnectionDirection::Inbound),
+                Some(ConnectionDirection::Outbound),
+                Some(ConnectionDirection::Inbound),
+            ]
+        );
+
+        // The listener closed; the accepted socket is still inbound and
+        // gets a stand-in listener in the split
+        let mut conns = vec![accepted];
+        history.classify(&mut conns);
+        assert_eq!(conns[0].direction, Some(ConnectionDirection::Inbound));
+        let split = split(&conns);
+        assert_eq!(split.services.len(), 1);
+        assert_eq!(split.services[0].listener.local_port, 22);
+        assert!(split.outbound.is_empty());
+    }
+}
@@ -0,0 +1,222 @@
+// Reverse DNS names for remote endpoints
+//
+// Opt-in, because every lookup is a query the network can see:
+//
+// ```toml
+// [dns]
+// reverse = true
+// ```
+//
+// Addresses are resolved one at a time on a background thread through the
+// system resolver (getent(1), falling back to host(1)), so /etc/hosts and
+// the configured DNS servers apply. Results, including addresses without a
+// PTR record, are cached for the rest of the session, and across sessions
+// by the `[cache]` (see enrich_cache.rs). Each lookup counts against the
+// `[probes]` budget (see probes.rs).
+
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+use std::net::IpAddr;
+use std::process::{Command, Stdio};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::Arc;
+use std::thread;
+
+/// Second-level labels under which country TLDs register names
+/// ("example.co.uk", "example.com.au")
+const GENERIC_SLDS: &[&str] = &[
+    "ac", "co", "com", "edu", "go", "gov", "ne", "net", "or", "org",
+];
+
+/// `[dns]` section of config.toml
+#[derive(Debug, Clone, Default, Deserialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct DnsConfig {
+    /// Resolve remote addresses to host names
+    pub reverse: bool,
+}
+
+/// Resolved name of an address
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReverseName {
+    /// Full PTR name ("ec2-3-5-1-9.compute-1.amazonaws.com")
+    pub name: Arc<str>,
+    /// Registrable domain ("amazonaws.com")
+    pub domain: Arc<str>,
+}
+
+/// Background reverse resolver with a session cache
+#[derive(Debug)]
+pub struct ReverseDns {
+    requests: Sender<Arc<str>>,
+    results: Receiver<(Arc<str>, Option<String>)>,
+    /// Finished lookups; None when the address has no name
+    names: HashMap<Arc<str>, Option<ReverseName>>,
+    pending: HashSet<Arc<str>>,
+    /// Names from an old session's cache, to be looked up again
+    stale: HashSet<Arc<str>>,
+}
+
+impl ReverseDns {
+    /// Start the resolver thread; it exits when the resolver is dropped
+    pub fn spawn() -> Self {
+        let (requests, queue) = mpsc::channel::<Arc<str>>();
+        let (answers, results) = mpsc::channel();
+        thread::spawn(move || {
+            for addr in queue {
+                let name = resolve(&addr);
+                if answers.send((addr, name)).is_err() {
+                    break;
+                }
+            }
+        });
+        Self {
+            requests,
+            results,
+            names: HashMap::new(),
+            pending: HashSet::new(),
+            stale: HashSet::new(),
+        }
+    }
+
+    /// Take the name of `addr` from the on-disk cache; a `stale` one is
+    /// shown until a fresh lookup succeeds
+    pub fn seed(&mut self, addr: Arc<str>, name: Option<&str>, stale: bool) {
+        let name = name.map(|name| ReverseName {
+            domain: Arc::from(registrable_domain(name)),
+            name: Arc::from(name),
+        });
+        if stale {
+            self.stale.insert(addr.clone());
+        }
+        self.names.insert(addr, name);
+    }
+
+    /// Whether `addr` still needs a lookup: not looked up yet (or only
+    /// known from a stale cache entry), and neither unspecified nor loopback
+    pub fn wants(&self, addr: &Arc<str>) -> bool {
+        if (self.names.contains_key(addr) && !self.stale.contains(addr))
+            || self.pending.contains(addr)
+        {
+            return false;
+        }
+        matches!(addr.parse::<IpAddr>(), Ok(ip) if !ip.is_unspecified() && !ip.is_loopback())
+    }
+
+    /// Queue `addr` if it `wants` a lookup
+    pub fn request(&mut self, addr: &Arc<str>) {
+        if !self.wants(addr) {
+            return;
+        }
+        if self.requests.send(addr.clone()).is_ok() {
+            self.pending.insert(addr.clone());
+        }
+    }
+
+    /// Collect finished lookups; returns the addresses answered. A stale
+    /// name is kept when the new lookup finds none (offline, most likely)
+    pub fn poll(&mut self) -> Vec<Arc<str>> {
+        let mut answered = Vec::new();
+        while let Ok((addr, name)) = self.results.try_recv() {
+            self.pending.remove(&addr);
+            let was_stale = self.stale.remove(&addr);
+            if name.is_none() && was_stale && self.name(&addr).is_some() {
+                continue;
+            }
+            let name = name.map(|name| ReverseName {
+                domain: Arc::from(registrable_domain(&name)),
+                name: Arc::from(name),
+            });
+            self.names.insert(addr.clone(), name);
+            answered.push(addr);
+        }
+        answered
+    }
+
+    /// Cached name of `addr`
+    pub fn name(&self, addr: &str) -> Option<&ReverseName> {
+        self.names.get(addr)?.as_ref()
+    }
+}
+
+/// PTR name of `addr` from the system resolver
+fn resolve(addr: &str) -> Option<String> {
+    if let Some(output) = run("getent", &["hosts", addr]) {
+        return parse_getent(&output);
+    }
+    run("host", &["-W", "2", addr]).and_then(|output| parse_host(&output))
+}
+
+/// Stdout of a command that ran; None when it could not be started
+fn run(program: &str, args: &[&str]) -> Option<String> {
+    let output = Command::new(program)
+        .args(args)
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+    Some(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// "8.8.8.8         dns.google" → "dns.google"
+fn parse_getent(output: &str) -> Option<String> {
+    let name = output.lines().next()?.split_whitespace().nth(1)?;
+    Some(name.trim_end_matches('.').to_string())
+}
+
+/// "8.8.8.8.in-addr.arpa domain name pointer dns.google." → "dns.google"
+fn parse_host(output: &str) -> Option<String> {
+    output.lines().find_map(|line| {
+        let (_, name) = line.split_once("domain name pointer ")?;
+        Some(name.trim().trim_end_matches('.').to_string())
+    })
+}
+
+/// Registrable part of a host name: the last two labels, or three under a
+/// country TLD's generic second level ("bbc.co.uk")
+///
+/// A heuristic rather than the full public suffix list, which is enough
+/// to fold a provider's per-host names into one group.
+pub fn registrable_domain(name: &str) -> String {
+    let name = name.trim_end_matches('.').to_ascii_lowercase();
+    let labels: Vec<&str> = name.split('.').collect();
+    let keep = match labels.as_slice() {
+        [.., sld, tld] if tld.len() == 2 && GENERIC_SLDS.contains(sld) => 3,
+        _ => 2,
+    };
+    labels[labels.len().saturating_sub(keep)..].join(".")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_resolver_output() {
+        assert_eq!(
+            parse_getent("8.8.8.8         dns.google\n").as_deref(),
+            Some("dns.google")
+        );
+        assert_eq!(parse_getent(""), None);
+        assert_eq!(
+            parse_host("8.8.8.8.in-addr.arpa domain name pointer dns.google.\n").as_deref(),
+            Some("dns.google")
+        );
+        assert_eq!(
+            parse_host("Host 5.113.0.203.in-addr.arpa. not found: 3(NXDOMAIN)\n"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_registrable_domain() {
+        assert_eq!(
+            registrable_domain("ec2-3-5-1-9.compute-1.amazonaws.com."),
+            "amazonaws.com"
+        );
+        assert_eq!(registrable_domain("lhr25s34-in-f14.1e100.net"), "1e100.net");
+        assert_eq!(registrable_domain("www.bbc.co.uk"), "bbc.co.uk");
+        assert_eq!(registrable_domain("Example.COM"), "example.com");
+        assert_eq!(registrable_domain("localhost"), "localhost");
+    }
+}
@@ -0,0 +1,206 @@
+// Endpoint detail popup
+//
+// Enter on the selected connection opens everything known about the
+// Graveyard node it is drawn under: every connection to that endpoint (or
+// group) with its ports, state, owning process and how long it has been
+// seen, plus the TCP states it went through. Classification, heavy-talker status and latency are worked out by
+// the renderer from the same helpers the map uses, so the two agree.
+
+use super::events::time_of_day_label;
+use super::snapshot::{connection_key, ConnectionKey, SnapshotHistory};
+use super::state_timeline::{StateStep, StateTimelines};
+use crate::net::{Connection, ConnectionState};
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+/// One connection to the endpoint
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DetailRow {
+    pub conn: Connection,
+    /// First snapshot of the unbroken run that ends at the newest one
+    pub first_seen: Option<SystemTime>,
+    /// Whether that run starts at the oldest recorded snapshot, so the
+    /// connection may well be older
+    pub before_history: bool,
+    /// States the connection went through, oldest first
+    pub steps: Vec<StateStep>,
+}
+
+/// State of the open endpoint detail popup
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EndpointDetail {
+    /// Graveyard node key: a remote address, or a group name
+    pub key: Arc<str>,
+    /// Whether the node folds several addresses into a group
+    pub is_group: bool,
+    pub rows: Vec<DetailRow>,
+}
+
+impl EndpointDetail {
+    /// Detail for node `key` from `connections` (already narrowed to the
+    /// node), aged against `history`, with each one's states from
+    /// `timelines`
+    pub fn new(
+        key: Arc<str>,
+        is_group: bool,
+        connections: Vec<Connection>,
+        history: &SnapshotHistory,
+        timelines: &StateTimelines,
+    ) -> Self {
+        let keys: Vec<ConnectionKey> = connections.iter().map(connection_key).collect();
+        let seen = first_seen(history, &keys);
+        let rows = connections
+            .into_iter()
+            .zip(seen)
+            .map(|(conn, seen)| DetailRow {
+                steps: timelines.steps(&conn).to_vec(),
+                conn,
+                first_seen: seen.map(|(at, _)| at),
+                before_history: seen.is_some_and(|(_, oldest)| oldest),
+            })
+            .collect();
+        Self {
+            key,
+            is_group,
+            rows,
+        }
+    }
+}
+
+/// Compact age: "42s", "5m03s", "2h10m", "3d04h"
+pub fn age_label(age: Duration) -> String {
+    let secs = age.as_secs();
+    match secs {
+        0..=59 => format!("{}s", secs),
+        60..=3599 => format!("{}m{:02}s", secs / 60, secs % 60),
+        3600..=86_399 => format!("{}h{:02}m", secs / 3600, secs % 3600 / 60),
+        _ => format!("{}d{:02}h", secs / 86_400, secs % 86_400 / 3600),
+    }
+}
+
+/// Timeline strip of a connection's `steps`: each state with the time it
+/// was first seen, the first as a time of day ("12:00:01") and the rest
+/// relative to the one before ("+2s")
+pub fn timeline_strip(steps: &[StateStep]) -> Vec<(ConnectionState, String)> {
+    let mut previous: Option<SystemTime> = None;
+    steps
+        .iter()
+        .map(|step| {
+            let when = match previous {
+                None => time_of_day_label(step.at),
+                Some(before) => format!(
+                    "+{}",
+                    age_label(step.at.duration_since(before).unwrap_or_default())
+                ),
+            };
+            previous = Some(step.at);
+            (step.state, when)
+        })
+        .collect()
+}
+
+/// For each key, when its unbroken run of snapshots up to the newest one
+/// began, and whether that run reaches back to the oldest snapshot
+fn first_seen(
+    history: &SnapshotHistory,
+    keys: &[ConnectionKey],
+) -> Vec<Option<(SystemTime, bool)>> {
+    let mut seen = vec![None; keys.len()];
+    // Keys still present in every snapshot walked so far
+    let mut running: Vec<usize> = (0..keys.len()).collect();
+    for index in (0..history.len()).rev() {
+        let Some(snapshot) = history.get(index) else {
+            break;
+        };
+        let present: HashSet<ConnectionKey> =
+            snapshot.connections.iter().map(connection_key).collect();
+        running.retain(|&i| present.contains(&keys[i]));
+        if running.is_empty() {
+            break;
+        }
+        for &i in &running {
+            seen[i] = Some((snapshot.taken_at, index == 0));
+        }
+    }
+    seen
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::snapshot::Snapshot;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    #[test]
+    fn test_first_seen() {
+        let conn = |remote_port| Connection {
+            remote_addr: "93.184.216.34".into(),
+            remote_port,
+            ..Default::default()
+        };
+        let at = |secs| UNIX_EPOCH + Duration::from_secs(secs);
+        let mut history = SnapshotHistory::new(4);
+        for (secs, conns) in [
+            (10, vec![conn(443), conn(80)]),
+            (20, vec![conn(443)]),
+            (30, vec![conn(443), conn(80)]),
+            (40, vec![conn(443), conn(80)]),
+        ] {
+            history.push(Snapshot {
+                taken_at: at(secs),
+                connections: conns,
+            });
+        }
+
+        let detail = EndpointDetail::new(
+            "93.184.216.34".into(),
+            false,
+            vec![conn(443), conn(80), conn(22)],
+            &history,
+            &StateTimelines::default(),
+        );
+        // Present since the oldest snapshot: possibly older still
+        assert_eq!(detail.rows[0].first_seen, Some(at(10)));
+        assert!(detail.rows[0].before_history);
+        // Gone at 20s, so only counted from its return
+        assert_eq!(detail.rows[1].first_seen, Some(at(30)));
+        assert!(!detail.rows[1].before_history);
+        assert_eq!(detail.rows[2].first_seen, None);
+    }
+
+    #[test]
+    fn test_timeline_strip() {
+        let at = |secs| UNIX_EPOCH + Duration::from_secs(secs);
+        let steps = [
+            StateStep {
+                at: at(43_201),
+                state: ConnectionState::SynSent,
+            },
+            StateStep {
+                at: at(43_203),
+                state: ConnectionState::Established,
+            },
+            StateStep {
+                at: at(43_506),
+                state: ConnectionState::FinWait1,
+            },
+        ];
+        assert_eq!(
+            timeline_strip(&steps),
+            [
+                (ConnectionState::SynSent, "12:00:01".to_string()),
+                (ConnectionState::Established, "+2s".to_string()),
+                (ConnectionState::FinWait1, "+5m03s".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_age_label() {
+        assert_eq!(age_label(Duration::from_secs(42)), "42s");
+        assert_eq!(age_label(Duration::from_secs(303)), "5m03s");
+        assert_eq!(age_label(Duration::from_secs(7_800)), "2h10m");
+        assert_eq!(age_label(Duration::from_secs(273_600)), "3d04h");
+    }
+}
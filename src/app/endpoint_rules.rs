@@ -0,0 +1,283 @@
+// Custom endpoint classification rules
+//
+// Users map CIDR ranges of their own network to labels, colors and icons
+// in the config file:
+//
+// ```toml
+// [[endpoint_rules]]
+// cidr = "10.1.0.0/16"
+// label = "prod-db"
+// color = "blood_red"
+// icon = "🗄"
+// environment = "prod"
+//
+// [[environment_alerts]]
+// environment = "prod"
+// filter = "proc ~ staging"
+// severity = "critical"
+// ```
+//
+// Rules are checked in file order by `classify_endpoint` before the built-in
+// localhost/RFC1918/public logic; the first matching rule wins. A rule's
+// optional environment (prod/staging/dev/...) is shown as a badge and can be
+// watched by environment alerts, which log a Grimoire event the first time
+// a matching process talks to that environment.
+
+use super::events::EventSeverity;
+use super::filter_expr::FilterExpr;
+use crate::net::cidr::Cidr;
+use crate::net::Connection;
+use crate::theme::{
+    color_by_name, BLOOD_RED, BONE_WHITE, NEON_PURPLE, PUMPKIN_ORANGE, TOXIC_GREEN,
+};
+use ratatui::style::Color;
+use serde::Deserialize;
+use std::sync::Arc;
+
+/// Icon used when a rule does not set one
+pub const DEFAULT_RULE_ICON: &str = "🪦";
+
+/// `[[endpoint_rules]]` entry as written in config.toml
+#[derive(Debug, Clone, Default, Deserialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct EndpointRuleConfig {
+    /// Network the rule applies to, e.g. "10.1.0.0/16"
+    pub cidr: String,
+    /// Name shown instead of the address in the Graveyard
+    pub label: String,
+    /// Palette name (blood_red, toxic_green, ...), color name, or #rrggbb
+    pub color: Option<String>,
+    /// Icon replacing the built-in endpoint icon
+    pub icon: Option<String>,
+    /// Environment tag (prod, staging, dev, ...)
+    pub environment: Option<String>,
+}
+
+/// A validated classification rule
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct EndpointRule {
+    pub cidr: Cidr,
+    pub label: String,
+    pub color: Color,
+    pub icon: String,
+    pub environment: Option<String>,
+}
+
+impl EndpointRule {
+    /// Validate a config entry, describing the first problem found
+    pub fn from_config(config: &EndpointRuleConfig) -> Result<Self, String> {
+        let cidr = config.cidr.parse::<Cidr>()?;
+        if config.label.trim().is_empty() {
+            return Err(format!("rule for {} has no label", cidr));
+        }
+        let color = match config.color {
+            Some(ref name) => color_by_name(name)
+                .ok_or_else(|| format!("rule '{}': unknown color '{}'", config.label, name))?,
+            None => BONE_WHITE,
+        };
+        Ok(Self {
+            cidr,
+            label: config.label.trim().to_string(),
+            color,
+            icon: config
+                .icon
+                .clone()
+                .unwrap_or_else(|| DEFAULT_RULE_ICON.to_string()),
+            environment: config
+                .environment
+                .as_deref()
+                .map(str::trim)
+                .filter(|env| !env.is_empty())
+                .map(str::to_string),
+        })
+    }
+}
+
+/// Validate all configured rules, keeping file order
+pub fn build_rules(configs: &[EndpointRuleConfig]) -> Result<Vec<Arc<EndpointRule>>, String> {
+    configs
+        .iter()
+        .map(|config| EndpointRule::from_config(config).map(Arc::new))
+        .collect()
+}
+
+/// First rule whose range contains `ip`
+pub fn match_rule<'a>(rules: &'a [Arc<EndpointRule>], ip: &str) -> Option<&'a Arc<EndpointRule>> {
+    if rules.is_empty() {
+        return None;
+    }
+    let ip = ip.parse().ok()?;
+    rules.iter().find(|rule| rule.cidr.contains(&ip))
+}
+
+/// Environment of the first rule containing `ip`, if it has one
+pub fn environment_of<'a>(rules: &'a [Arc<EndpointRule>], ip: &str) -> Option<&'a str> {
+    match_rule(rules, ip)?.environment.as_deref()
+}
+
+/// Badge color for an environment: production stands out the most
+pub fn environment_color(environment: &str) -> Color {
+    match environment.to_ascii_lowercase().as_str() {
+        "prod" | "production" => BLOOD_RED,
+        "staging" | "stage" | "qa" => PUMPKIN_ORANGE,
+        "dev" | "development" | "test" => TOXIC_GREEN,
+        _ => NEON_PURPLE,
+    }
+}
+
+/// `[[environment_alerts]]` entry as written in config.toml
+#[derive(Debug, Clone, Default, Deserialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct EnvironmentAlertConfig {
+    /// Environment whose endpoints trigger the alert
+    pub environment: String,
+    /// Only connections matching this filter expression (default: all)
+    pub filter: Option<String>,
+    /// info, warning (default) or critical
+    pub severity: Option<String>,
+}
+
+/// "process X talked to env=Y" alert template
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnvironmentAlert {
+    pub environment: String,
+    pub filter: Option<FilterExpr>,
+    pub severity: EventSeverity,
+}
+
+impl EnvironmentAlert {
+    /// Validate a config entry, describing the first problem found
+    pub fn from_config(config: &EnvironmentAlertConfig) -> Result<Self, String> {
+        let environment = config.environment.trim();
+        if environment.is_empty() {
+            return Err("environment alert has no environment".to_string());
+        }
+        let filter = match config.filter {
+            Some(ref filter) => Some(
+                FilterExpr::parse(filter)
+                    .map_err(|e| format!("environment alert '{}': filter: {}", environment, e))?,
+            ),
+            None => None,
+        };
+        let severity = match config.severity {
+            Some(ref name) => EventSeverity::from_name(name).ok_or_else(|| {
+                format!(
+                    "environment alert '{}': unknown severity '{}'",
+                    environment, name
+                )
+            })?,
+            None => EventSeverity::Warning,
+        };
+        Ok(Self {
+            environment: environment.to_string(),
+            filter,
+            severity,
+        })
+    }
+
+    /// Whether `conn`, whose remote end is in `environment`, fires this alert
+    pub fn matches(&self, conn: &Connection, environment: &str) -> bool {
+        self.environment.eq_ignore_ascii_case(environment)
+            && self.filter.as_ref().map_or(true, |f| f.matches(conn))
+    }
+}
+
+/// Validate all configured environment alerts
+pub fn build_environment_alerts(
+    configs: &[EnvironmentAlertConfig],
+) -> Result<Vec<EnvironmentAlert>, String> {
+    configs.iter().map(EnvironmentAlert::from_config).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(cidr: &str, label: &str, color: Option<&str>) -> EndpointRuleConfig {
+        EndpointRuleConfig {
+            cidr: cidr.to_string(),
+            label: label.to_string(),
+            color: color.map(|c| c.to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_build_rules() {
+        let rules = build_rules(&[
+            rule("10.1.0.0/16", "prod-db", Some("blood_red")),
+            rule("10.0.0.0/8", "corp", Some("#336699")),
+        ])
+        .unwrap();
+        assert_eq!(rules[0].color, BLOOD_RED);
+        assert_eq!(rules[0].icon, DEFAULT_RULE_ICON);
+        assert_eq!(rules[1].color, Color::Rgb(0x33, 0x66, 0x99));
+    }
+
+    #[test]
+    fn test_first_matching_rule_wins() {
+        let rules = build_rules(&[
+            rule("10.1.0.0/16", "prod-db", None),
+            rule("10.0.0.0/8", "corp", None),
+        ])
+        .unwrap();
+        assert_eq!(match_rule(&rules, "10.1.4.2").unwrap().label, "prod-db");
+        assert_eq!(match_rule(&rules, "10.2.0.1").unwrap().label, "corp");
+        assert!(match_rule(&rules, "8.8.8.8").is_none());
+        assert!(match_rule(&rules, "not-an-ip").is_none());
+    }
+
+    #[test]
+    fn test_invalid_rules() {
+        assert!(build_rules(&[rule("10.1.0.0/99", "x", None)]).is_err());
+        assert!(build_rules(&[rule("10.1.0.0/16", " ", None)]).is_err());
+        let err = build_rules(&[rule("10.1.0.0/16", "db", Some("ghostly"))]).unwrap_err();
+        assert!(err.contains("ghostly"));
+    }
+
+    #[test]
+    fn test_environment_of() {
+        let rules = build_rules(&[
+            EndpointRuleConfig {
+                environment: Some("prod".to_string()),
+                ..rule("10.1.0.0/16", "prod-db", None)
+            },
+            rule("10.0.0.0/8", "corp", None),
+        ])
+        .unwrap();
+        assert_eq!(environment_of(&rules, "10.1.0.7"), Some("prod"));
+        assert_eq!(environment_of(&rules, "10.9.0.7"), None);
+        assert_eq!(environment_color("PROD"), BLOOD_RED);
+    }
+
+    #[test]
+    fn test_environment_alert() {
+        let alert = EnvironmentAlert::from_config(&EnvironmentAlertConfig {
+            environment: "prod".to_string(),
+            filter: Some("proc ~ staging".to_string()),
+            severity: Some("critical".to_string()),
+        })
+        .unwrap();
+        assert_eq!(alert.severity, EventSeverity::Critical);
+
+        let staging = Connection {
+            process_name: Some("staging-api".into()),
+            ..Default::default()
+        };
+        let prod = Connection {
+            process_name: Some("billing".into()),
+            ..Default::default()
+        };
+        assert!(alert.matches(&staging, "prod"));
+        assert!(!alert.matches(&staging, "dev"));
+        assert!(!alert.matches(&prod, "prod"));
+
+        assert!(EnvironmentAlert::from_config(&EnvironmentAlertConfig {
+            environment: "prod".to_string(),
+            filter: Some("proc ==".to_string()),
+            ..Default::default()
+        })
+        .is_err());
+        assert!(EnvironmentAlert::from_config(&EnvironmentAlertConfig::default()).is_err());
+    }
+}
@@ -0,0 +1,436 @@
+// Connection enrichment pipeline
+//
+// Enrichers add information to each collected snapshot before it is
+// stored: the owning container, the local interface, or free-form labels
+// such as a service name, a country or the endpoint rule that matched.
+// Each source is an isolated `Enricher`; the pipeline runs them in
+// registration order, so a new source is one module plus one `register`
+// call in `AppState::rebuild_enrichers`.
+//
+// Labels are keyed by short static names ("service", "country", "rule",
+// "env", "asset") and show up in the Soul Inspector, the JSON stream and bare-word
+// filter searches.
+
+use super::asn::AsnDatabase;
+use super::endpoint_rules::{self, EndpointRule};
+use super::interfaces::InterfaceMap;
+use super::inventory::Inventory;
+use crate::net::transport;
+use crate::net::{Connection, ConnectionDirection, ConnectionState};
+use crate::procfs::container::ContainerResolver;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
+/// A source of extra information about connections
+pub trait Enricher {
+    /// Short name for logs and diagnostics
+    fn name(&self) -> &'static str;
+
+    /// Add information to every connection of the snapshot
+    fn enrich(&mut self, conns: &mut [Connection]);
+}
+
+/// Enrichers applied to every snapshot, in registration order
+#[derive(Default)]
+pub struct EnrichmentPipeline {
+    enrichers: Vec<Box<dyn Enricher>>,
+}
+
+impl fmt::Debug for EnrichmentPipeline {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.names()).finish()
+    }
+}
+
+impl EnrichmentPipeline {
+    /// Add an enricher after the ones already registered
+    pub fn register(&mut self, enricher: impl Enricher + 'static) {
+        self.enrichers.push(Box::new(enricher));
+    }
+
+    /// Names of the registered enrichers, in order
+    pub fn names(&self) -> Vec<&'static str> {
+        self.enrichers.iter().map(|e| e.name()).collect()
+    }
+
+    /// Run every enricher over the snapshot
+    pub fn run(&mut self, conns: &mut [Connection]) {
+        for enricher in &mut self.enrichers {
+            enricher.enrich(conns);
+        }
+    }
+}
+
+/// Docker/Podman container of the owning process
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+pub struct ContainerEnricher {
+    resolver: ContainerResolver,
+}
+
+impl Default for ContainerEnricher {
+    fn default() -> Self {
+        Self {
+            resolver: ContainerResolver::new(),
+        }
+    }
+}
+
+impl Enricher for ContainerEnricher {
+    fn name(&self) -> &'static str {
+        "containers"
+    }
+
+    /// Each distinct PID is resolved once per snapshot; container names
+    /// themselves are cached by the resolver for the whole session
+    fn enrich(&mut self, conns: &mut [Connection]) {
+        let mut by_pid: HashMap<i32, Option<String>> = HashMap::new();
+        for conn in conns.iter_mut() {
+            if let Some(pid) = conn.pid {
+                conn.container = by_pid
+                    .entry(pid)
+                    .or_insert_with(|| self.resolver.name_for_pid(pid))
+                    .clone();
+            }
+        }
+    }
+}
+
+/// Local network interface each socket is bound to
+#[derive(Default)]
+pub struct InterfaceEnricher;
+
+impl Enricher for InterfaceEnricher {
+    fn name(&self) -> &'static str {
+        "interfaces"
+    }
+
+    /// Interfaces come and go (VPNs, hotplug), so they are re-read for
+    /// every snapshot
+    fn enrich(&mut self, conns: &mut [Connection]) {
+        InterfaceMap::from_system().attach(conns);
+    }
+}
+
+/// MPTCP subflow tokens and TCP Fast Open, read with `ss -tin`
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+pub struct TransportEnricher {
+    /// Cleared once ss cannot be run, so it is not retried every snapshot
+    available: bool,
+}
+
+impl Default for TransportEnricher {
+    fn default() -> Self {
+        Self { available: true }
+    }
+}
+
+impl Enricher for TransportEnricher {
+    fn name(&self) -> &'static str {
+        "transport"
+    }
+
+    fn enrich(&mut self, conns: &mut [Connection]) {
+        if !self.available {
+            return;
+        }
+        match transport::read_transport_features() {
+            Ok(features) => transport::attach_transport_features(conns, &features),
+            Err(e) => {
+                tracing::debug!(error = %e, "Cannot run ss; MPTCP and Fast Open not detected");
+                self.available = false;
+            }
+        }
+    }
+}
+
+/// Well-known service names by TCP port
+const SERVICES: &[(u16, &str)] = &[
+    (21, "ftp"),
+    (22, "ssh"),
+    (23, "telnet"),
+    (25, "smtp"),
+    (53, "dns"),
+    (80, "http"),
+    (110, "pop3"),
+    (143, "imap"),
+    (389, "ldap"),
+    (443, "https"),
+    (445, "smb"),
+    (465, "smtps"),
+    (587, "submission"),
+    (636, "ldaps"),
+    (993, "imaps"),
+    (995, "pop3s"),
+    (1433, "mssql"),
+    (1521, "oracle"),
+    (2049, "nfs"),
+    (2379, "etcd"),
+    (3306, "mysql"),
+    (3389, "rdp"),
+    (5432, "postgres"),
+    (5672, "amqp"),
+    (6379, "redis"),
+    (6443, "kube-api"),
+    (8080, "http-alt"),
+    (8443, "https-alt"),
+    (9092, "kafka"),
+    (9200, "elasticsearch"),
+    (11211, "memcached"),
+    (27017, "mongodb"),
+];
+
+/// Service name label from the port on the server side of the connection
+#[derive(Default)]
+pub struct ServiceEnricher;
+
+impl ServiceEnricher {
+    /// Service listening on `port`, if it is a well-known one
+    pub fn service_name(port: u16) -> Option<&'static str> {
+        SERVICES
+            .binary_search_by_key(&port, |(p, _)| *p)
+            .ok()
+            .map(|idx| SERVICES[idx].1)
+    }
+}
+
+impl Enricher for ServiceEnricher {
+    fn name(&self) -> &'static str {
+        "services"
+    }
+
+    fn enrich(&mut self, conns: &mut [Connection]) {
+        for conn in conns {
+            // Listeners and accepted connections serve on the local port
+            let server_port = if conn.state == ConnectionState::Listen
+                || conn.direction == Some(ConnectionDirection::Inbound)
+            {
+                conn.local_port
+            } else {
+                conn.remote_port
+            };
+            if let Some(service) = Self::service_name(server_port) {
+                conn.labels.insert("service", service.into());
+            }
+        }
+    }
+}
+
+/// `[[endpoint_rules]]` label and environment of the remote address
+pub struct CidrRuleEnricher {
+    rules: Vec<Arc<EndpointRule>>,
+}
+
+impl CidrRuleEnricher {
+    pub fn new(rules: Vec<Arc<EndpointRule>>) -> Self {
+        Self { rules }
+    }
+}
+
+impl Enricher for CidrRuleEnricher {
+    fn name(&self) -> &'static str {
+        "cidr-rules"
+    }
+
+    fn enrich(&mut self, conns: &mut [Connection]) {
+        for conn in conns {
+            let Some(rule) = endpoint_rules::match_rule(&self.rules, &conn.remote_addr) else {
+                continue;
+            };
+            conn.labels.insert("rule", rule.label.as_str().into());
+            if let Some(ref environment) = rule.environment {
+                conn.labels.insert("env", environment.as_str().into());
+            }
+        }
+    }
+}
+
+/// Registration country of the remote address, from the `[asn]` database
+pub struct GeoIpEnricher {
+    database: Arc<AsnDatabase>,
+}
+
+impl GeoIpEnricher {
+    pub fn new(database: Arc<AsnDatabase>) -> Self {
+        Self { database }
+    }
+}
+
+impl Enricher for GeoIpEnricher {
+    fn name(&self) -> &'static str {
+        "geoip"
+    }
+
+    fn enrich(&mut self, conns: &mut [Connection]) {
+        for conn in conns {
+            let Some(info) = self.database.lookup(&conn.remote_addr) else {
+                continue;
+            };
+            if !info.country.is_empty() {
+                conn.labels.insert("country", info.country.clone());
+            }
+        }
+    }
+}
+
+/// Connections per `country` label, busiest first (ties by code)
+pub fn country_counts<'a>(
+    conns: impl IntoIterator<Item = &'a Connection>,
+) -> Vec<(Arc<str>, usize)> {
+    let mut counts: HashMap<Arc<str>, usize> = HashMap::new();
+    for conn in conns {
+        if let Some(country) = conn.labels.get("country") {
+            *counts.entry(country.clone()).or_insert(0) += 1;
+        }
+    }
+    let mut counts: Vec<_> = counts.into_iter().collect();
+    counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    counts
+}
+
+/// `[inventory]` asset name, owner and criticality of the remote address
+pub struct InventoryEnricher {
+    inventory: Arc<Inventory>,
+}
+
+impl InventoryEnricher {
+    pub fn new(inventory: Arc<Inventory>) -> Self {
+        Self { inventory }
+    }
+}
+
+impl Enricher for InventoryEnricher {
+    fn name(&self) -> &'static str {
+        "inventory"
+    }
+
+    fn enrich(&mut self, conns: &mut [Connection]) {
+        for conn in conns {
+            let Some(entry) = self.inventory.lookup(&conn.remote_addr) else {
+                continue;
+            };
+            conn.labels.insert("asset", entry.name.as_str().into());
+            if let Some(ref owner) = entry.owner {
+                conn.labels.insert("owner", owner.as_str().into());
+            }
+            if let Some(criticality) = entry.criticality {
+                conn.labels
+                    .insert("criticality", criticality.label().into());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::endpoint_rules::EndpointRuleConfig;
+
+    fn conn(remote: &str, local_port: u16, remote_port: u16) -> Connection {
+        Connection {
+            local_addr: "10.0.0.5".into(),
+            local_port,
+            remote_addr: remote.into(),
+            remote_port,
+            state: ConnectionState::Established,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_service_table_is_sorted() {
+        assert!(SERVICES.windows(2).all(|w| w[0].0 < w[1].0));
+        assert_eq!(ServiceEnricher::service_name(5432), Some("postgres"));
+        assert_eq!(ServiceEnricher::service_name(5433), None);
+    }
+
+    #[test]
+    fn test_service_from_server_side() {
+        let listener = Connection {
+            state: ConnectionState::Listen,
+            ..conn("0.0.0.0", 22, 0)
+        };
+        let inbound = Connection {
+            direction: Some(ConnectionDirection::Inbound),
+            ..conn("203.0.113.9", 443, 51000)
+        };
+        let mut conns = vec![listener, inbound, conn("203.0.113.9", 51000, 5432)];
+        ServiceEnricher.enrich(&mut conns);
+        let services: Vec<Option<&str>> = conns
+            .iter()
+            .map(|c| c.labels.get("service").map(|s| &**s))
+            .collect();
+        assert_eq!(services, [Some("ssh"), Some("https"), Some("postgres")]);
+    }
+
+    #[test]
+    fn test_pipeline_runs_in_order() {
+        let rules = endpoint_rules::build_rules(&[EndpointRuleConfig {
+            cidr: "10.1.0.0/16".to_string(),
+            label: "prod-db".to_string(),
+            environment: Some("prod".to_string()),
+            ..Default::default()
+        }])
+        .unwrap();
+        let database = AsnDatabase::parse("198.51.100.0\t198.51.100.255\t64500\tNL\tEXAMPLE\n");
+        let inventory =
+            Inventory::parse("ip,name,owner,criticality\n10.1.2.3,pg-01,dba,high\n").unwrap();
+
+        let mut pipeline = EnrichmentPipeline::default();
+        pipeline.register(ServiceEnricher);
+        pipeline.register(CidrRuleEnricher::new(rules));
+        pipeline.register(GeoIpEnricher::new(Arc::new(database)));
+        pipeline.register(InventoryEnricher::new(Arc::new(inventory)));
+        assert_eq!(
+            pipeline.names(),
+            ["services", "cidr-rules", "geoip", "inventory"]
+        );
+
+        let mut conns = vec![
+            conn("10.1.2.3", 50000, 5432),
+            conn("198.51.100.7", 50001, 8443),
+        ];
+        pipeline.run(&mut conns);
+        let labels = |c: &Connection| -> Vec<(&str, String)> {
+            c.labels.iter().map(|(k, v)| (*k, v.to_string())).collect()
+        };
+        assert_eq!(
+            labels(&conns[0]),
+            [
+                ("asset", "pg-01".to_string()),
+                ("criticality", "high".to_string()),
+                ("env", "prod".to_string()),
+                ("owner", "dba".to_string()),
+                ("rule", "prod-db".to_string()),
+                ("service", "postgres".to_string())
+            ]
+        );
+        assert_eq!(
+            labels(&conns[1]),
+            [
+                ("country", "NL".to_string()),
+                ("service", "https-alt".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_country_counts() {
+        let database = AsnDatabase::parse(
+            "198.51.100.0\t198.51.100.255\t64500\tNL\tEXAMPLE\n\
+             203.0.113.0\t203.0.113.255\t64501\tDE\tOTHER\n",
+        );
+        let mut conns = vec![
+            conn("203.0.113.1", 50000, 443),
+            conn("198.51.100.7", 50001, 443),
+            conn("198.51.100.8", 50002, 443),
+            conn("10.1.2.3", 50003, 5432),
+        ];
+        GeoIpEnricher::new(Arc::new(database)).enrich(&mut conns);
+        let counts: Vec<(String, usize)> = country_counts(&conns)
+            .into_iter()
+            .map(|(country, n)| (country.to_string(), n))
+            .collect();
+        assert_eq!(counts, [("NL".to_string(), 2), ("DE".to_string(), 1)]);
+    }
+}
@@ -0,0 +1,348 @@
+// On-disk enrichment cache
+//
+// Reverse DNS names, RDAP records and package owners are remembered across
+// restarts, so a new session doesn't re-resolve every address it already
+// knew, and a run without network (or with `passive_only`) still shows what
+// was learned last time:
+//
+// ```toml
+// [cache]
+// enabled = true                             # default
+// file = "/var/cache/ntomb/enrichment.tsv"   # default $XDG_CACHE_HOME/ntomb/enrichment.tsv
+// ttl = "7d"                                 # s, m, h, d or w (default 7d)
+// ```
+//
+// Entries older than `ttl` are stale: DNS names and RDAP records are still
+// shown, marked with their age, and looked up again as the probe budget
+// allows; stale package owners are simply queried again. Entries older
+// than four times `ttl` are dropped when the cache is written. ASN and
+// country data come from local databases and need no cache.
+//
+// Each line is "kind<TAB>key<TAB>stored<TAB>fields...", stored in Unix
+// seconds, with tabs, newlines and backslashes escaped. Like the peer
+// database, the file is rewritten at most once a minute while it changes,
+// and when ntomb exits.
+
+use super::history::parse_duration;
+use super::peers::unix_secs;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime};
+
+/// Entry lifetime unless `ttl` says otherwise
+const DEFAULT_TTL: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// Minimum time between two writes of the cache
+const SAVE_EVERY: Duration = Duration::from_secs(60);
+
+/// Entries this many TTLs old are dropped when the cache is written
+const EVICT_AFTER_TTLS: u64 = 4;
+
+const HEADER: &str = "# ntomb enrichment cache: kind, key, stored, fields";
+
+/// `[cache]` section of config.toml
+#[derive(Debug, Clone, Default, Deserialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct CacheConfig {
+    /// Keep the cache (default true)
+    pub enabled: Option<bool>,
+    /// Where it is kept (default $XDG_CACHE_HOME/ntomb/enrichment.tsv)
+    pub file: Option<PathBuf>,
+    /// Age at which an entry is looked up again: "12h", "7d" (default 7d)
+    pub ttl: Option<String>,
+}
+
+/// Which enricher an entry belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CacheKind {
+    /// Reverse DNS name by address; no fields when there is no PTR record
+    Dns,
+    /// RDAP record by address
+    Rdap,
+    /// Owning package by executable path
+    Package,
+}
+
+impl CacheKind {
+    const ALL: [CacheKind; 3] = [CacheKind::Dns, CacheKind::Rdap, CacheKind::Package];
+
+    fn as_str(self) -> &'static str {
+        match self {
+            CacheKind::Dns => "dns",
+            CacheKind::Rdap => "rdap",
+            CacheKind::Package => "pkg",
+        }
+    }
+}
+
+/// One cached lookup
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CacheEntry {
+    /// Unix seconds of the lookup
+    pub stored: u64,
+    pub fields: Vec<String>,
+}
+
+/// Enrichment lookups kept in a file
+#[derive(Debug)]
+pub struct EnrichCache {
+    path: PathBuf,
+    ttl: Duration,
+    entries: HashMap<(CacheKind, Arc<str>), CacheEntry>,
+    /// Changed since the last write
+    changed: bool,
+    saved_at: Option<Instant>,
+}
+
+impl EnrichCache {
+    /// The configured cache, read from disk; None when disabled or there
+    /// is nowhere to keep it
+    pub fn from_config(config: &CacheConfig) -> Result<Option<Self>, String> {
+        if !config.enabled.unwrap_or(true) {
+            return Ok(None);
+        }
+        let ttl = match config.ttl.as_deref() {
+            Some(text) => parse_duration(text).map_err(|e| format!("ttl: {}", e))?,
+            None => DEFAULT_TTL,
+        };
+        if ttl.is_zero() {
+            return Err("ttl must be at least 1s".to_string());
+        }
+        let Some(path) = config.file.clone().or_else(default_path) else {
+            return Ok(None);
+        };
+        let content = match fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(e) => {
+                if e.kind() != io::ErrorKind::NotFound {
+                    tracing::warn!(path = %path.display(), error = %e, "Cannot read enrichment cache, starting over");
+                }
+                String::new()
+            }
+        };
+        Ok(Some(Self::parse(path, ttl, &content)))
+    }
+
+    /// A cache holding the lines of `content`; malformed lines are skipped
+    fn parse(path: PathBuf, ttl: Duration, content: &str) -> Self {
+        let mut entries = HashMap::new();
+        for line in content.lines().filter(|line| !line.starts_with('#')) {
+            let mut fields = line.split('\t').map(unescape);
+            let (Some(kind), Some(key), Some(stored)) =
+                (fields.next(), fields.next(), fields.next())
+            else {
+                continue;
+            };
+            let Some(kind) = CacheKind::ALL.into_iter().find(|k| k.as_str() == kind) else {
+                continue;
+            };
+            if let Ok(stored) = stored.parse() {
+                let fields = fields.collect();
+                entries.insert((kind, key.into()), CacheEntry { stored, fields });
+            }
+        }
+        Self {
+            path,
+            ttl,
+            entries,
+            changed: false,
+            saved_at: None,
+        }
+    }
+
+    /// Every entry of `kind`
+    pub fn entries(&self, kind: CacheKind) -> impl Iterator<Item = (&Arc<str>, &CacheEntry)> {
+        self.entries
+            .iter()
+            .filter(move |((k, _), _)| *k == kind)
+            .map(|((_, key), entry)| (key, entry))
+    }
+
+    /// Remember a lookup of `key` made at `now`
+    pub fn put(&mut self, kind: CacheKind, key: &Arc<str>, fields: Vec<String>, now: SystemTime) {
+        let entry = CacheEntry {
+            stored: unix_secs(now),
+            fields,
+        };
+        self.entries.insert((kind, key.clone()), entry);
+        self.changed = true;
+    }
+
+    /// Age of the entry for `key` when it is older than the TTL at `now`
+    pub fn stale_age(&self, kind: CacheKind, key: &str, now: SystemTime) -> Option<Duration> {
+        let entry = self.entries.get(&(kind, Arc::from(key)))?;
+        self.age_if_stale(entry, now)
+    }
+
+    /// Age of `entry` when it is older than the TTL at `now`
+    pub fn age_if_stale(&self, entry: &CacheEntry, now: SystemTime) -> Option<Duration> {
+        let age = Duration::from_secs(unix_secs(now).saturating_sub(entry.stored));
+        (age >= self.ttl).then_some(age)
+    }
+
+    /// Write the cache if it changed and the last write is a while ago
+    pub fn save_if_due(&mut self, now: Instant) {
+        let due = self
+            .saved_at
+            .map_or(true, |at| now.duration_since(at) >= SAVE_EVERY);
+        if self.changed && due {
+            self.saved_at = Some(now);
+            self.save();
+        }
+    }
+
+    /// Write the cache now if it changed; failures are logged
+    pub fn save(&mut self) {
+        if !self.changed {
+            return;
+        }
+        self.evict(SystemTime::now());
+        match write_entries(&self.path, &self.entries) {
+            Ok(()) => self.changed = false,
+            Err(e) => {
+                tracing::warn!(path = %self.path.display(), error = %e, "Cannot save enrichment cache")
+            }
+        }
+    }
+
+    /// Drop entries `EVICT_AFTER_TTLS` times the TTL old at `now`, so
+    /// addresses seen once do not pile up forever
+    fn evict(&mut self, now: SystemTime) {
+        let cap = self.ttl.as_secs().saturating_mul(EVICT_AFTER_TTLS);
+        let cutoff = unix_secs(now).saturating_sub(cap);
+        self.entries.retain(|_, entry| entry.stored > cutoff);
+    }
+}
+
+/// Write `entries` to `path` via a temporary file, so a crash never leaves
+/// half a cache
+fn write_entries(
+    path: &Path,
+    entries: &HashMap<(CacheKind, Arc<str>), CacheEntry>,
+) -> io::Result<()> {
+    if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+        fs::create_dir_all(dir)?;
+    }
+    let mut sorted: Vec<_> = entries.iter().collect();
+    sorted.sort_by(|a, b| (a.0 .0.as_str(), &a.0 .1).cmp(&(b.0 .0.as_str(), &b.0 .1)));
+    let mut content = format!("{}\n", HEADER);
+    for ((kind, key), entry) in sorted {
+        content.push_str(&format!(
+            "{}\t{}\t{}",
+            kind.as_str(),
+            escape(key),
+            entry.stored
+        ));
+        for field in &entry.fields {
+            content.push('\t');
+            content.push_str(&escape(field));
+        }
+        content.push('\n');
+    }
+    let tmp = path.with_extension("tmp");
+    fs::write(&tmp, content)?;
+    fs::rename(&tmp, path)
+}
+
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('\t', "\\t")
+        .replace('\n', "\\n")
+}
+
+fn unescape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('t') => out.push('\t'),
+            Some('n') => out.push('\n'),
+            Some(other) => out.push(other),
+            None => out.push('\\'),
+        }
+    }
+    out
+}
+
+/// $XDG_CACHE_HOME/ntomb/enrichment.tsv or ~/.cache/ntomb/enrichment.tsv
+fn default_path() -> Option<PathBuf> {
+    let base = std::env::var_os("XDG_CACHE_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".cache")))?;
+    Some(base.join("ntomb").join("enrichment.tsv"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_entries_survive_a_restart_and_go_stale() {
+        let dir = std::env::temp_dir().join(format!("ntomb-cache-{}", std::process::id()));
+        let config = CacheConfig {
+            file: Some(dir.join("enrichment.tsv")),
+            ttl: Some("1d".to_string()),
+            ..Default::default()
+        };
+        let start = SystemTime::now();
+        let day = Duration::from_secs(86_400);
+
+        let mut cache = EnrichCache::from_config(&config).unwrap().unwrap();
+        let addr: Arc<str> = Arc::from("93.184.216.34");
+        cache.put(CacheKind::Dns, &addr, vec!["example.com".into()], start);
+        let org = vec!["EDGECAST".to_string(), "Edgio\tInc.\\".to_string()];
+        cache.put(CacheKind::Rdap, &addr, org.clone(), start);
+        cache.put(CacheKind::Dns, &Arc::from("10.1.0.1"), Vec::new(), start);
+        // Past the eviction age: dropped on write
+        let old = vec!["coreutils".to_string()];
+        cache.put(
+            CacheKind::Package,
+            &Arc::from("/bin/ls"),
+            old,
+            start - day * 5,
+        );
+        cache.save();
+
+        let cache = EnrichCache::from_config(&config).unwrap().unwrap();
+        let mut dns: Vec<_> = cache
+            .entries(CacheKind::Dns)
+            .map(|(key, entry)| (key.to_string(), entry.fields.clone()))
+            .collect();
+        dns.sort();
+        assert_eq!(
+            dns,
+            [
+                ("10.1.0.1".to_string(), vec![]),
+                ("93.184.216.34".to_string(), vec!["example.com".to_string()])
+            ]
+        );
+        let (_, rdap) = cache.entries(CacheKind::Rdap).next().unwrap();
+        assert_eq!(rdap.fields, org);
+        assert_eq!(cache.entries(CacheKind::Package).count(), 0);
+
+        assert_eq!(
+            cache.stale_age(CacheKind::Dns, &addr, start + day / 2),
+            None
+        );
+        assert_eq!(
+            cache.stale_age(CacheKind::Dns, &addr, start + day * 3),
+            Some(day * 3)
+        );
+        let _ = fs::remove_dir_all(&dir);
+
+        let bad = CacheConfig {
+            ttl: Some("0s".to_string()),
+            ..config
+        };
+        assert!(EnrichCache::from_config(&bad).is_err());
+    }
+}
@@ -3,8 +3,26 @@
 // This module contains the keyboard event handler that processes
 // user input and updates the application state accordingly.
 
+use super::tags::Tag;
+use super::toast::ToastSeverity;
 use super::AppState;
-use crossterm::event::KeyCode;
+use crossterm::event::{KeyCode, KeyEvent};
+
+/// Rows moved by `PageUp` / `PageDown` (`Ctrl-u` / `Ctrl-d` in the vim keymap)
+const PAGE_ROWS: isize = 10;
+
+/// Handle a key press as bound by the active keymap profile
+///
+/// Vim keys are translated (see `keymap.rs`) except while the filter
+/// prompt is open, where every character is text.
+pub fn handle_key(app: &mut AppState, key: KeyEvent) -> bool {
+    let code = if app.filter_prompt.is_some() {
+        key.code
+    } else {
+        app.keymap.translate(key)
+    };
+    handle_key_event(app, code)
+}
 
 /// Handle keyboard events and update application state
 ///
@@ -16,18 +34,139 @@ use crossterm::event::KeyCode;
 /// * `key` - The key code that was pressed
 ///
 /// # Key Bindings
+/// - Any key while the screensaver shows - Back to the normal UI (the key does nothing else)
 /// - `q`, `Q`, `Esc` - Quit the application
 /// - `Up` - Select previous connection
 /// - `Down` - Select next connection
+/// - `Home` / `End` - Select the first / last connection
+/// - `PageUp` / `PageDown` - Move the selection ten rows
 /// - `p`, `P` - Toggle graveyard mode (Host/Process)
-/// - `Tab` - Switch panel (placeholder)
-/// - `+`, `=` - Increase refresh rate
-/// - `-`, `_` - Decrease refresh rate
+/// - `Backspace`, `(` / `)` - Back / forward through the Process-mode focus history
+/// - `c`, `C` - Include child processes in Process mode
+/// - `u` - Undo the last view change (filter, mode, grouping, split/Séance/diff view, page)
+/// - `U` - Cycle user filter
+/// - `d`, `D` - Cycle container filter
+/// - `I` - Cycle local interface filter (wg0, eth1, ...)
+/// - `i` - Cycle the inspector tabs (Overview / Sockets / Forensics / History)
+/// - `b`, `B` - Toggle backend debug popup (`Esc` also closes it)
+/// - `!` - Toggle the diagnostics panel (`Esc` also closes it)
+/// - `%` - Toggle the frame/collection time overlay (`Esc` also closes it)
+/// - `?` - Toggle the Graveyard legend (`Esc` also closes it)
+/// - `r`, `R` - Toggle the local port heatmap below the Graveyard
+/// - `L` - Cycle the log file verbosity (error → warn → info → debug → trace)
+/// - `/` - Open the filter expression prompt (see `handle_prompt_key`)
+/// - `g`, `G` - Cycle endpoint grouping (off / subnet / AS organization / process / country / domain)
+/// - `Enter` - Detail popup for the selected connection's endpoint (`handle_endpoint_detail_key`),
+///   and narrow the Grimoire to the events of its process and remote address
+/// - `e`, `E` - Expand or collapse the selected connection pool
+/// - `s`, `S` - Toggle the split view (services we offer / connections we open)
+/// - `O` - Toggle the Séance view (a mini-graveyard per busy process)
+/// - `w` - Add a watch expression via the prompt
+/// - `#` - Jump to the first connection of a PID or on a port via the prompt
+/// - `W` - Remove all watches
+/// - `Space` - Pause on the latest snapshot / resume live data
+/// - `Left` / `Right` - Step through recorded snapshots while paused
+/// - `m` - Mark the current snapshot as the diff baseline
+/// - `M` - Freeze a second snapshot and diff it against the baseline
+/// - `v`, `V` - Toggle the diff view (only connections added/removed since the baseline)
+/// - `y`, `Y` - Yank the selected connection to the clipboard
+/// - `f` - Copy the connections on screen as a Mermaid flowchart
+/// - `F` - Write the Mermaid flowchart to ntomb-<unix time>.mmd
+/// - `>` - Save the next frame to ntomb-<unix time>.ans and .html
+/// - `K` - RDAP lookup of who owns the selected connection's remote address
+/// - `x`, `X` - Investigation commands for the selected connection (see `handle_actions_key`)
+/// - `n` - Tag the selected connection (see `handle_tag_key`)
+/// - `N` - Cycle the tag filter (!suspicious / ok / follow-up / off)
+/// - `o` - Pin the selected connection's endpoint to the watchlist (or unpin it)
+/// - `,` - Settings popup: heavy-talker threshold (see `handle_settings_key`)
+/// - `.` - Mark the selected row for bulk actions (or unmark it); `Esc` clears all marks
+/// - `Tab`, `Shift-Tab` - Focus the Grimoire event feed (see `handle_event_feed_key`)
+/// - `=` - Slower UI refresh (increase interval)
+/// - `-` - Faster UI refresh (decrease interval)
+/// - `+` (Shift+`=`) - Collect data less often (data multiplier +1)
+/// - `_` (Shift+`-`) - Collect data more often (data multiplier -1)
 /// - `a`, `A` - Toggle animations
+/// - `*` - Cycle animation quality (Off, Low, Normal, Lush)
 /// - `h`, `H` - Toggle Kiroween Overdrive mode
 /// - `t`, `T` - Toggle endpoint labels
+/// - `{` / `}` - Previous / next page of Graveyard endpoints beyond the canvas cap
+/// - `[` / `]` / `|` - Lower / raise / cycle the emoji width offset, saved per $TERM
+/// - `\\` - Reset the emoji width offset to the detected value
+///
+/// With connections marked, `n`, `o`, `y`, `f` and `F` act on the marked
+/// connections instead of the selection or the whole list.
 pub fn handle_key_event(app: &mut AppState, key: KeyCode) -> bool {
+    // Any key ends the screensaver without doing anything else
+    if app.note_input() {
+        return true;
+    }
+    // Remember the view this key changes, unless the key was the undo
+    // itself (which shortens the stack)
+    let before = app.view_state();
+    let undo_depth = app.undo_stack.len();
+    let running = dispatch_key(app, key);
+    if app.undo_stack.len() >= undo_depth {
+        app.record_view_change(before);
+    }
+    running
+}
+
+/// Run the action bound to `key` (see `handle_key_event`)
+fn dispatch_key(app: &mut AppState, key: KeyCode) -> bool {
+    // While the filter prompt is open every key edits the expression
+    if app.filter_prompt.is_some() {
+        handle_prompt_key(app, key);
+        return true;
+    }
+    if app.actions_popup.is_some() {
+        handle_actions_key(app, key);
+        return true;
+    }
+    if app.endpoint_detail.is_some() {
+        handle_endpoint_detail_key(app, key);
+        return true;
+    }
+    if app.tag_popup.is_some() {
+        handle_tag_key(app, key);
+        return true;
+    }
+    if app.settings_popup.is_some() {
+        handle_settings_key(app, key);
+        return true;
+    }
+    if app.event_feed.focused && handle_event_feed_key(app, key) {
+        return true;
+    }
+    if app.inspector_sockets.focused && handle_inspector_key(app, key) {
+        return true;
+    }
+
     match key {
+        // Esc closes an open popup before it quits
+        KeyCode::Esc if app.show_backend_popup => {
+            app.show_backend_popup = false;
+            true
+        }
+        KeyCode::Esc if app.show_diagnostics => {
+            app.show_diagnostics = false;
+            true
+        }
+        KeyCode::Esc if app.show_legend => {
+            app.show_legend = false;
+            true
+        }
+        KeyCode::Esc if app.show_perf_overlay => {
+            app.show_perf_overlay = false;
+            true
+        }
+        KeyCode::Esc if app.rdap_popup.is_some() => {
+            app.rdap_popup = None;
+            true
+        }
+        KeyCode::Esc if !app.marked.is_empty() => {
+            app.clear_marks();
+            true
+        }
         // Quit on 'q', 'Q', or Esc
         KeyCode::Char('q') | KeyCode::Char('Q') | KeyCode::Esc => {
             app.running = false;
@@ -42,31 +181,282 @@ pub fn handle_key_event(app: &mut AppState, key: KeyCode) -> bool {
             app.select_next_connection();
             true
         }
+        KeyCode::Home => {
+            app.select_edge_connection(false);
+            true
+        }
+        KeyCode::End => {
+            app.select_edge_connection(true);
+            true
+        }
+        KeyCode::PageUp => {
+            app.move_connection_selection(-PAGE_ROWS);
+            true
+        }
+        KeyCode::PageDown => {
+            app.move_connection_selection(PAGE_ROWS);
+            true
+        }
         // Toggle graveyard mode with 'p' key
         KeyCode::Char('p') | KeyCode::Char('P') => {
             app.toggle_graveyard_mode();
             true
         }
 
+        // Back / forward through the Process-mode focus history
+        KeyCode::Backspace | KeyCode::Char('(') => {
+            app.navigate_focus(true);
+            true
+        }
+        KeyCode::Char(')') => {
+            app.navigate_focus(false);
+            true
+        }
+
+        // Aggregate descendant processes under the focused process
+        KeyCode::Char('c') | KeyCode::Char('C') => {
+            app.toggle_child_processes();
+            true
+        }
+
+        // Cycle through socket owners to show only one user's connections
+        KeyCode::Char('U') => {
+            app.cycle_user_filter();
+            true
+        }
+
+        // Step back to the view before the last change
+        KeyCode::Char('u') => {
+            app.undo_view_change();
+            true
+        }
+
+        // Cycle through containers to show only one container's connections
+        KeyCode::Char('d') | KeyCode::Char('D') => {
+            app.cycle_container_filter();
+            true
+        }
+
+        // Isolate traffic on one local interface
+        KeyCode::Char('I') => {
+            app.cycle_interface_filter();
+            true
+        }
+
+        // Overview / Sockets / Forensics / History
+        KeyCode::Char('i') => {
+            app.cycle_inspector_tab();
+            true
+        }
+
+        // Focus the Grimoire event feed, then the inspector's socket list
+        KeyCode::Tab => {
+            app.cycle_focus(true);
+            true
+        }
+        KeyCode::BackTab => {
+            app.cycle_focus(false);
+            true
+        }
+
+        // Filter expression prompt
+        KeyCode::Char('/') => {
+            app.open_filter_prompt();
+            true
+        }
+
+        // Aggregate CDN/cloud addresses into one node per organization/domain
+        KeyCode::Char('g') | KeyCode::Char('G') => {
+            app.cycle_grouping();
+            true
+        }
+        KeyCode::Enter => {
+            app.narrow_events_to_selected();
+            app.open_endpoint_detail();
+            true
+        }
+
+        // Sockets differing only in local port are folded into one row
+        KeyCode::Char('e') | KeyCode::Char('E') => {
+            app.toggle_pool_expansion();
+            true
+        }
+
+        // Inbound vs outbound at a glance
+        KeyCode::Char('s') | KeyCode::Char('S') => {
+            app.toggle_split_view();
+            true
+        }
+
+        // Who talks to what, one process at a time
+        KeyCode::Char('O') => {
+            app.toggle_seance();
+            true
+        }
+
+        // Connection count watches: add via the prompt, or clear them all
+        KeyCode::Char('w') => {
+            app.open_watch_prompt();
+            true
+        }
+        KeyCode::Char('W') => {
+            app.clear_watches();
+            true
+        }
+
+        // Who owns the selected remote address (RDAP)
+        KeyCode::Char('K') => {
+            app.open_rdap_lookup();
+            true
+        }
+
+        // Quick navigation without building a filter
+        KeyCode::Char('#') => {
+            app.open_jump_prompt();
+            true
+        }
+
+        // Timeline: freeze the view and step through recorded refreshes
+        KeyCode::Char(' ') => {
+            app.toggle_pause();
+            true
+        }
+        KeyCode::Left => {
+            app.step_timeline(-1);
+            true
+        }
+        KeyCode::Right => {
+            app.step_timeline(1);
+            true
+        }
+
+        // Snapshot diff: baseline, second snapshot, diff view
+        KeyCode::Char('m') => {
+            app.mark_baseline();
+            true
+        }
+        KeyCode::Char('M') => {
+            app.mark_comparison();
+            true
+        }
+        KeyCode::Char('v') | KeyCode::Char('V') => {
+            app.toggle_diff_mode();
+            true
+        }
+
+        // Copy the selected connection for pasting into tickets
+        KeyCode::Char('y') | KeyCode::Char('Y') => {
+            app.yank_selected_connection();
+            true
+        }
+
+        // Topology as a Mermaid flowchart for incident docs
+        KeyCode::Char('f') => {
+            app.copy_mermaid();
+            true
+        }
+        KeyCode::Char('F') => {
+            app.export_mermaid();
+            true
+        }
+        KeyCode::Char('>') => {
+            app.request_screenshot();
+            true
+        }
+
+        // Ready-made ss/tcpdump/kill/whois commands for the selection
+        KeyCode::Char('x') | KeyCode::Char('X') => {
+            app.open_actions_popup();
+            true
+        }
+
+        // Triage labels during incidents
+        KeyCode::Char('n') => {
+            app.open_tag_popup();
+            true
+        }
+        KeyCode::Char('N') => {
+            app.cycle_tag_filter();
+            true
+        }
+        KeyCode::Char('o') => {
+            app.toggle_pin();
+            true
+        }
+
+        // Live display settings (heavy-talker threshold)
+        KeyCode::Char(',') => {
+            app.toggle_settings_popup();
+            true
+        }
+
+        // Multi-select for bulk tagging, pinning and export
+        KeyCode::Char('.') => {
+            app.toggle_mark();
+            true
+        }
+
+        // Backend health details (errors from recent refreshes)
+        KeyCode::Char('L') => {
+            app.cycle_log_level();
+            true
+        }
+        KeyCode::Char('!') => {
+            app.toggle_diagnostics();
+            true
+        }
+        KeyCode::Char('%') => {
+            app.toggle_perf_overlay();
+            true
+        }
+        KeyCode::Char('?') => {
+            app.show_legend = !app.show_legend;
+            true
+        }
+        KeyCode::Char('r') | KeyCode::Char('R') => {
+            app.toggle_port_heatmap();
+            true
+        }
+        KeyCode::Char('b') | KeyCode::Char('B') => {
+            app.toggle_backend_popup();
+            true
+        }
+
         // Refresh rate controls (unified)
-        // + = slower refresh (increase interval)
-        // - = faster refresh (decrease interval)
-        KeyCode::Char('+') | KeyCode::Char('=') => {
+        // = slower refresh (increase interval)
+        // - faster refresh (decrease interval)
+        KeyCode::Char('=') => {
             app.decrease_refresh_rate();
             true
         }
-        KeyCode::Char('-') | KeyCode::Char('_') => {
+        KeyCode::Char('-') => {
             app.increase_refresh_rate();
             true
         }
+        // Shift variants adjust the data/UI ratio
+        // + (Shift+=) = collect data less often
+        // _ (Shift+-) = collect data more often
+        KeyCode::Char('+') => {
+            app.increase_data_multiplier();
+            true
+        }
+        KeyCode::Char('_') => {
+            app.decrease_data_multiplier();
+            true
+        }
         // Toggle animations (Requirements 2.4, 5.1)
         KeyCode::Char('a') | KeyCode::Char('A') => {
             app.graveyard_settings.animations_enabled = !app.graveyard_settings.animations_enabled;
             // Reset animation reduction when user manually toggles animations
-            // This allows the system to try full animation complexity again
+            // This restores the quality tier in use before slow frames
             app.reset_animation_reduction();
             true
         }
+        // Cycle animation quality tiers
+        KeyCode::Char('*') => {
+            app.cycle_animation_quality();
+            true
+        }
         // Toggle Kiroween Overdrive mode (Requirements 4.1, 5.2)
         KeyCode::Char('h') | KeyCode::Char('H') => {
             app.graveyard_settings.overdrive_enabled = !app.graveyard_settings.overdrive_enabled;
@@ -80,27 +470,261 @@ pub fn handle_key_event(app: &mut AppState, key: KeyCode) -> bool {
         // Adjust emoji width offset for cross-platform rendering
         // '[' = decrease offset (emoji renders narrower)
         // ']' = increase offset (emoji renders wider)
+        // '|' = cycle -1 → 0 → +1, the values terminals actually need
+        // Each choice is saved for the current $TERM
         KeyCode::Char('[') => {
-            app.graveyard_settings.emoji_width_offset -= 1;
+            app.set_emoji_width_offset(app.graveyard_settings.emoji_width_offset - 1);
             true
         }
         KeyCode::Char(']') => {
-            app.graveyard_settings.emoji_width_offset += 1;
+            app.set_emoji_width_offset(app.graveyard_settings.emoji_width_offset + 1);
+            true
+        }
+        KeyCode::Char('|') => {
+            app.cycle_emoji_width_offset();
+            true
+        }
+        // Page through endpoints beyond the canvas cap
+        KeyCode::Char('{') => {
+            app.endpoint_page -= 1;
+            true
+        }
+        KeyCode::Char('}') => {
+            app.endpoint_page += 1;
             true
         }
-        // Reset emoji width offset to detected value
+        // Reset emoji width offset to detected value (and forget the saved one)
         KeyCode::Char('\\') => {
-            app.graveyard_settings.emoji_width_offset =
-                crate::ui::emoji_width::get_detected_offset();
+            app.reset_emoji_width_offset();
             true
         }
         _ => true,
     }
 }
 
+/// Handle a key while the '/' filter, 'w' watch or '#' jump prompt is open
+///
+/// # Key Bindings
+/// - Printable characters - Append to the expression
+/// - `Backspace` - Delete the last character
+/// - `Enter` - Apply the filter (an empty one clears it), add the watch or jump
+/// - `Esc` - Close the prompt, keeping the previous filter
+fn handle_prompt_key(app: &mut AppState, key: KeyCode) {
+    match key {
+        KeyCode::Enter => app.submit_filter_prompt(),
+        KeyCode::Esc => app.cancel_filter_prompt(),
+        KeyCode::Backspace => {
+            if let Some(input) = app.filter_prompt.as_mut() {
+                input.pop();
+            }
+        }
+        KeyCode::Char(c) => {
+            if let Some(input) = app.filter_prompt.as_mut() {
+                input.push(c);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Handle a key while the Grimoire event feed has focus; other keys keep
+/// their usual meaning (returns false for them)
+///
+/// # Key Bindings
+/// - `Up` / `Down` - Scroll one event (scrolling back stops the live tail)
+/// - `PageUp` / `PageDown` - Scroll one panel height
+/// - `Home` / `End` - Oldest events / back to the live tail
+/// - `/` - Search event messages at the prompt
+/// - `n` / `N` - Older / newer match
+/// - `Esc` - Clear the search, then show all events again, then return to
+///   the connection list
+/// - `Tab`, `Shift-Tab` - Focus the inspector's socket list / the
+///   connection list
+fn handle_event_feed_key(app: &mut AppState, key: KeyCode) -> bool {
+    let page = app.event_feed.rows.max(1) as i64;
+    let feed = &mut app.event_feed;
+    match key {
+        KeyCode::Up => feed.scroll(&app.events, -1),
+        KeyCode::Down => feed.scroll(&app.events, 1),
+        KeyCode::PageUp => feed.scroll(&app.events, -page),
+        KeyCode::PageDown => feed.scroll(&app.events, page),
+        KeyCode::Home => feed.scroll_to_top(&app.events),
+        KeyCode::End => feed.tail(),
+        KeyCode::Char('/') => app.open_event_search_prompt(),
+        KeyCode::Char('n') => {
+            if !feed.step_match(&app.events, true) && feed.search().is_some() {
+                app.toast("No older match", ToastSeverity::Info);
+            }
+        }
+        KeyCode::Char('N') => {
+            if !feed.step_match(&app.events, false) && feed.search().is_some() {
+                app.toast("No newer match", ToastSeverity::Info);
+            }
+        }
+        KeyCode::Esc if feed.search().is_some() => feed.clear_search(),
+        KeyCode::Esc if feed.subject().is_some() => feed.set_subject(None),
+        KeyCode::Esc => feed.focused = false,
+        KeyCode::Tab => app.cycle_focus(true),
+        KeyCode::BackTab => app.cycle_focus(false),
+        _ => return false,
+    }
+    true
+}
+
+/// Handle a key while the Soul Inspector's socket list is focused; false
+/// for keys it leaves to the main bindings
+///
+/// # Key Bindings
+/// - `Up` / `Down` - Select the previous / next socket
+/// - `PageUp` / `PageDown` - Move one panel height
+/// - `Home` / `End` - First / last socket
+/// - `Enter` - Select the socket in the connection list
+/// - `Esc` - Return to the connection list
+/// - `Tab`, `Shift-Tab` - Focus the connection list / the event feed
+fn handle_inspector_key(app: &mut AppState, key: KeyCode) -> bool {
+    let len = app.inspected_connections().len();
+    let page = app.inspector_sockets.rows.max(1) as isize;
+    let list = &mut app.inspector_sockets;
+    match key {
+        KeyCode::Up => list.move_by(-1, len),
+        KeyCode::Down => list.move_by(1, len),
+        KeyCode::PageUp => list.move_by(-page, len),
+        KeyCode::PageDown => list.move_by(page, len),
+        KeyCode::Home => list.select_edge(false, len),
+        KeyCode::End => list.select_edge(true, len),
+        KeyCode::Enter => app.select_inspected_socket(),
+        KeyCode::Esc => list.focused = false,
+        KeyCode::Tab => app.cycle_focus(true),
+        KeyCode::BackTab => app.cycle_focus(false),
+        _ => return false,
+    }
+    true
+}
+
+/// Handle a key while the actions popup is open
+///
+/// # Key Bindings
+/// - `Up` / `Down` - Highlight a command
+/// - `1`-`9` - Copy that command directly
+/// - `Enter`, `y` - Copy the highlighted command
+/// - `Esc`, `x` - Close the popup
+fn handle_actions_key(app: &mut AppState, key: KeyCode) {
+    match key {
+        KeyCode::Up => {
+            if let Some(popup) = app.actions_popup.as_mut() {
+                popup.select_previous();
+            }
+        }
+        KeyCode::Down => {
+            if let Some(popup) = app.actions_popup.as_mut() {
+                popup.select_next();
+            }
+        }
+        KeyCode::Char(c @ '1'..='9') => {
+            let idx = c as usize - '1' as usize;
+            if let Some(popup) = app.actions_popup.as_mut() {
+                if idx < popup.actions.len() {
+                    popup.selected = idx;
+                    app.copy_selected_action();
+                }
+            }
+        }
+        KeyCode::Enter | KeyCode::Char('y') | KeyCode::Char('Y') => app.copy_selected_action(),
+        KeyCode::Esc | KeyCode::Char('x') | KeyCode::Char('X') => app.close_actions_popup(),
+        _ => {}
+    }
+}
+
+/// Handle a key while the settings popup is open
+///
+/// # Key Bindings
+/// - `Up` / `Down` - Highlight a setting
+/// - `Left` / `Right` - Change it by one step
+/// - `PageUp` / `PageDown` - Change a count by ten
+/// - `Esc`, `,` - Close the popup
+fn handle_settings_key(app: &mut AppState, key: KeyCode) {
+    match key {
+        KeyCode::Up => {
+            if let Some(popup) = app.settings_popup.as_mut() {
+                popup.select_previous();
+                app.dirty = true;
+            }
+        }
+        KeyCode::Down => {
+            if let Some(popup) = app.settings_popup.as_mut() {
+                popup.select_next();
+                app.dirty = true;
+            }
+        }
+        KeyCode::Left => app.adjust_setting(-1),
+        KeyCode::Right => app.adjust_setting(1),
+        KeyCode::PageDown => app.adjust_setting(-10),
+        KeyCode::PageUp => app.adjust_setting(10),
+        KeyCode::Esc | KeyCode::Char(',') => app.toggle_settings_popup(),
+        _ => {}
+    }
+}
+
+/// Handle a key while the tag popup is open
+///
+/// # Key Bindings
+/// - `Up` / `Down` - Highlight a tag
+/// - `1`-`3` - Toggle that tag directly
+/// - `Enter` - Toggle the highlighted tag
+/// - `Esc`, `n` - Close the popup
+fn handle_tag_key(app: &mut AppState, key: KeyCode) {
+    match key {
+        KeyCode::Up => {
+            if let Some(popup) = app.tag_popup.as_mut() {
+                popup.select_previous();
+            }
+        }
+        KeyCode::Down => {
+            if let Some(popup) = app.tag_popup.as_mut() {
+                popup.select_next();
+            }
+        }
+        KeyCode::Char(c @ '1'..='9') => {
+            let idx = c as usize - '1' as usize;
+            if let Some(popup) = app.tag_popup.as_mut() {
+                if idx < Tag::ALL.len() {
+                    popup.selected = idx;
+                    app.toggle_selected_tag();
+                }
+            }
+        }
+        KeyCode::Enter => app.toggle_selected_tag(),
+        KeyCode::Esc | KeyCode::Char('n') => app.close_tag_popup(),
+        _ => {}
+    }
+}
+
+/// Handle keys while the endpoint detail popup is open
+///
+/// - `Enter` - Expand or collapse the endpoint's group and close
+/// - `Esc`, `q` - Close
+fn handle_endpoint_detail_key(app: &mut AppState, key: KeyCode) {
+    match key {
+        KeyCode::Enter => {
+            let grouped = app
+                .endpoint_detail
+                .as_ref()
+                .is_some_and(|detail| app.detail_in_group(detail));
+            app.close_endpoint_detail();
+            if grouped {
+                app.toggle_group_expansion();
+            }
+        }
+        KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('Q') => app.close_endpoint_detail(),
+        _ => {}
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::app::inspector::InspectorTab;
+    use crate::net::Connection;
 
     #[test]
     fn test_quit_keys() {
@@ -173,17 +797,943 @@ mod tests {
         assert!(app.graveyard_settings.labels_enabled);
     }
 
+    #[test]
+    fn test_toggle_child_processes() {
+        let mut app = AppState::new();
+
+        // Default: only the selected process itself
+        assert!(!app.include_child_processes);
+
+        handle_key_event(&mut app, KeyCode::Char('c'));
+        assert!(app.include_child_processes);
+
+        handle_key_event(&mut app, KeyCode::Char('C'));
+        assert!(!app.include_child_processes);
+    }
+
+    #[test]
+    fn test_container_filter_key() {
+        let mut app = AppState::new();
+        app.raw_connections = vec![Connection {
+            container: Some("cache-1".to_string()),
+            ..Default::default()
+        }];
+
+        handle_key_event(&mut app, KeyCode::Char('d'));
+        assert_eq!(app.connection_filter.container.as_deref(), Some("cache-1"));
+
+        handle_key_event(&mut app, KeyCode::Char('D'));
+        assert_eq!(app.connection_filter.container, None);
+    }
+
+    #[test]
+    fn test_interface_filter_key() {
+        let mut app = AppState::new();
+        app.raw_connections = vec![Connection {
+            interface: Some("wg0".into()),
+            ..Default::default()
+        }];
+
+        handle_key_event(&mut app, KeyCode::Char('I'));
+        assert_eq!(app.connection_filter.interface.as_deref(), Some("wg0"));
+
+        handle_key_event(&mut app, KeyCode::Char('I'));
+        assert_eq!(app.connection_filter.interface, None);
+    }
+
+    #[test]
+    fn test_inspector_socket_list_focus() {
+        let mut app = AppState::new();
+        app.raw_connections = (0..4)
+            .map(|i| Connection {
+                remote_addr: format!("10.1.0.{}", i).into(),
+                remote_port: 443,
+                ..Default::default()
+            })
+            .collect();
+        app.apply_filters();
+
+        // Connection list → event feed → inspector → connection list
+        handle_key_event(&mut app, KeyCode::Tab);
+        assert!(app.event_feed.focused);
+        handle_key_event(&mut app, KeyCode::Tab);
+        assert!(!app.event_feed.focused);
+        assert!(app.inspector_sockets.focused);
+
+        // Arrow keys move the inspector selection, which the Graveyard
+        // highlights, and leave the connection list alone
+        handle_key_event(&mut app, KeyCode::End);
+        handle_key_event(&mut app, KeyCode::Up);
+        assert_eq!(app.selected_connection, None);
+        let highlighted = app.highlighted_connection().unwrap();
+        assert_eq!(&*highlighted.remote_addr, "10.1.0.2");
+
+        // Enter selects that socket in the connection list
+        handle_key_event(&mut app, KeyCode::Enter);
+        assert!(!app.inspector_sockets.focused);
+        let selected = &app.connections[app.selected_connection.unwrap()];
+        assert_eq!(&*selected.remote_addr, "10.1.0.2");
+
+        // The Forensics tab has no socket list to focus
+        handle_key_event(&mut app, KeyCode::Char('i'));
+        handle_key_event(&mut app, KeyCode::Char('i'));
+        handle_key_event(&mut app, KeyCode::BackTab);
+        assert!(app.event_feed.focused);
+        assert!(!app.inspector_sockets.focused);
+    }
+
+    #[test]
+    fn test_inspector_tab_key() {
+        let mut app = AppState::new();
+        handle_key_event(&mut app, KeyCode::Char('i'));
+        assert_eq!(app.inspector_tab, InspectorTab::Sockets);
+        handle_key_event(&mut app, KeyCode::Char('i'));
+        assert_eq!(app.inspector_tab, InspectorTab::Forensics);
+        // Nothing is inspected in Host mode without a selection
+        assert_eq!(app.forensics, None);
+    }
+
+    #[test]
+    fn test_toggle_backend_popup() {
+        let mut app = AppState::new();
+        assert!(!app.show_backend_popup);
+
+        handle_key_event(&mut app, KeyCode::Char('b'));
+        assert!(app.show_backend_popup);
+
+        handle_key_event(&mut app, KeyCode::Char('B'));
+        assert!(!app.show_backend_popup);
+
+        // Esc closes the popup without quitting
+        app.toggle_backend_popup();
+        handle_key_event(&mut app, KeyCode::Esc);
+        assert!(!app.show_backend_popup);
+        assert!(app.running);
+    }
+
     #[test]
     fn test_refresh_rate_controls() {
         let mut app = AppState::new();
         let initial_rate = app.refresh_config.refresh_ms;
 
-        // + = slower refresh (increase interval)
-        handle_key_event(&mut app, KeyCode::Char('+'));
+        // = slower refresh (increase interval)
+        handle_key_event(&mut app, KeyCode::Char('='));
         assert!(app.refresh_config.refresh_ms > initial_rate);
 
         // - = faster refresh (decrease interval back to initial)
         handle_key_event(&mut app, KeyCode::Char('-'));
         assert_eq!(app.refresh_config.refresh_ms, initial_rate);
     }
+
+    #[test]
+    fn test_data_multiplier_controls() {
+        let mut app = AppState::new();
+        let initial = app.refresh_config.data_multiplier;
+        let ui_interval = app.refresh_config.ui_interval();
+
+        // Shift++ = collect data less often, UI cadence unchanged
+        handle_key_event(&mut app, KeyCode::Char('+'));
+        assert_eq!(app.refresh_config.data_multiplier, initial + 1);
+        assert_eq!(app.refresh_config.ui_interval(), ui_interval);
+
+        handle_key_event(&mut app, KeyCode::Char('_'));
+        assert_eq!(app.refresh_config.data_multiplier, initial);
+
+        // Never drops below one data refresh per UI tick
+        for _ in 0..200 {
+            handle_key_event(&mut app, KeyCode::Char('_'));
+        }
+        assert_eq!(app.refresh_config.data_multiplier, 1);
+    }
+
+    fn type_text(app: &mut AppState, text: &str) {
+        for c in text.chars() {
+            handle_key_event(app, KeyCode::Char(c));
+        }
+    }
+
+    #[test]
+    fn test_filter_prompt() {
+        let mut app = AppState::new();
+        app.raw_connections = vec![
+            Connection {
+                local_port: 22,
+                ..Default::default()
+            },
+            Connection {
+                local_port: 443,
+                ..Default::default()
+            },
+        ];
+
+        handle_key_event(&mut app, KeyCode::Char('/'));
+        assert_eq!(app.filter_prompt.as_deref(), Some(""));
+
+        // Keys are typed into the prompt instead of triggering actions
+        type_text(&mut app, "port == 2q");
+        assert!(app.running);
+        handle_key_event(&mut app, KeyCode::Backspace);
+        type_text(&mut app, "2");
+        handle_key_event(&mut app, KeyCode::Enter);
+
+        assert_eq!(app.filter_prompt, None);
+        assert_eq!(app.connections.len(), 1);
+        assert_eq!(app.connections[0].local_port, 22);
+
+        // Reopening pre-fills the active expression; Esc keeps it
+        handle_key_event(&mut app, KeyCode::Char('/'));
+        assert_eq!(app.filter_prompt.as_deref(), Some("port == 22"));
+        handle_key_event(&mut app, KeyCode::Esc);
+        assert!(app.running);
+        assert!(app.connection_filter.expression.is_some());
+    }
+
+    #[test]
+    fn test_filter_prompt_parse_error() {
+        let mut app = AppState::new();
+
+        handle_key_event(&mut app, KeyCode::Char('/'));
+        type_text(&mut app, "state == nope");
+        handle_key_event(&mut app, KeyCode::Enter);
+
+        // The prompt stays open with the error for correction
+        assert_eq!(app.filter_prompt.as_deref(), Some("state == nope"));
+        assert!(app
+            .filter_error
+            .as_deref()
+            .unwrap()
+            .contains("unknown state"));
+        assert_eq!(app.connection_filter.expression, None);
+
+        // Submitting an empty prompt clears the filter
+        app.filter_prompt = Some(String::new());
+        handle_key_event(&mut app, KeyCode::Enter);
+        assert_eq!(app.filter_prompt, None);
+        assert_eq!(app.filter_error, None);
+    }
+
+    #[test]
+    fn test_emoji_width_keys_save_per_terminal() {
+        use crate::app::settings::{terminal_name, Settings};
+
+        let dir = std::env::temp_dir().join(format!("ntomb-emoji-{}", std::process::id()));
+        let path = dir.join("settings.toml");
+        let mut app = AppState::new();
+        app.load_settings(path.clone());
+        app.graveyard_settings.emoji_width_offset = 0;
+
+        handle_key_event(&mut app, KeyCode::Char('|'));
+        assert_eq!(app.graveyard_settings.emoji_width_offset, 1);
+        handle_key_event(&mut app, KeyCode::Char('|'));
+        assert_eq!(app.graveyard_settings.emoji_width_offset, -1);
+        handle_key_event(&mut app, KeyCode::Char('['));
+        assert_eq!(app.graveyard_settings.emoji_width_offset, -2);
+        assert_eq!(
+            Settings::load(&path).emoji_width.get(&terminal_name()),
+            Some(&-2)
+        );
+
+        // A fresh session starts from the saved value
+        let mut next = AppState::new();
+        next.load_settings(path.clone());
+        assert_eq!(next.graveyard_settings.emoji_width_offset, -2);
+
+        // Reset forgets it
+        handle_key_event(&mut app, KeyCode::Char('\\'));
+        assert!(Settings::load(&path).emoji_width.is_empty());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_screensaver_wakes_on_any_key() {
+        use std::time::{Duration, Instant};
+
+        let mut app = AppState::new();
+        app.on_tick();
+        assert!(!app.screensaver);
+
+        // Idle past the configured time: the next tick starts it
+        app.screensaver_after = Some(Duration::from_secs(60));
+        app.last_input = Instant::now() - Duration::from_secs(61);
+        app.last_tick = Instant::now() - Duration::from_millis(200);
+        app.on_tick();
+        assert!(app.screensaver);
+
+        // The waking key is swallowed, even q
+        assert!(handle_key_event(&mut app, KeyCode::Char('q')));
+        assert!(!app.screensaver);
+        assert!(app.running);
+        app.last_tick = Instant::now() - Duration::from_millis(200);
+        app.on_tick();
+        assert!(!app.screensaver);
+    }
+
+    #[test]
+    fn test_endpoint_detail_keys() {
+        use crate::net::ConnectionState;
+
+        let mut app = AppState::new();
+        let conn = |remote_port| Connection {
+            remote_addr: "93.184.216.34".into(),
+            remote_port,
+            state: ConnectionState::Established,
+            ..Default::default()
+        };
+        app.connections = vec![
+            conn(443),
+            conn(80),
+            Connection {
+                state: ConnectionState::Listen,
+                local_port: 22,
+                ..Default::default()
+            },
+        ];
+
+        // Listeners have no Graveyard node
+        app.selected_connection = Some(2);
+        handle_key_event(&mut app, KeyCode::Enter);
+        assert!(app.endpoint_detail.is_none());
+
+        app.selected_connection = Some(0);
+        handle_key_event(&mut app, KeyCode::Enter);
+        let detail = app.endpoint_detail.as_ref().unwrap();
+        assert_eq!(&*detail.key, "93.184.216.34");
+        assert_eq!(detail.rows.len(), 2);
+
+        // The popup is modal, and q closes it instead of quitting
+        handle_key_event(&mut app, KeyCode::Char('p'));
+        assert!(app.endpoint_detail.is_some());
+        handle_key_event(&mut app, KeyCode::Char('q'));
+        assert!(app.endpoint_detail.is_none());
+        assert!(app.running);
+    }
+
+    #[test]
+    fn test_focus_history_keys() {
+        let mut app = AppState::new();
+        let conn = |pid: i32, name: &str| Connection {
+            remote_addr: "10.1.0.1".into(),
+            pid: Some(pid),
+            process_name: Some(name.into()),
+            ..Default::default()
+        };
+        app.raw_connections = vec![conn(1234, "nginx"), conn(99, "sshd")];
+        app.apply_filters();
+
+        app.selected_connection = Some(0);
+        handle_key_event(&mut app, KeyCode::Char('P'));
+        assert_eq!(app.selected_process_pid, Some(1234));
+        let trail: Vec<String> = app
+            .focus_history
+            .breadcrumb()
+            .iter()
+            .map(|step| step.label())
+            .collect();
+        assert_eq!(trail, ["HOST", "nginx (1234)"]);
+
+        handle_key_event(&mut app, KeyCode::Backspace);
+        assert_eq!(app.graveyard_mode, crate::app::GraveyardMode::Host);
+        handle_key_event(&mut app, KeyCode::Char(')'));
+        assert_eq!(app.selected_process_pid, Some(1234));
+
+        // sshd is not a child of nginx: 'P' returns to Host
+        app.selected_connection = Some(1);
+        handle_key_event(&mut app, KeyCode::Char('P'));
+        assert_eq!(app.graveyard_mode, crate::app::GraveyardMode::Host);
+        handle_key_event(&mut app, KeyCode::Char('P'));
+        assert_eq!(app.selected_process_pid, Some(99));
+        handle_key_event(&mut app, KeyCode::Char('('));
+        handle_key_event(&mut app, KeyCode::Char('('));
+        assert_eq!(app.selected_process_pid, Some(1234));
+    }
+
+    #[test]
+    fn test_actions_popup_keys() {
+        let mut app = AppState::new();
+        app.connections = vec![Connection {
+            remote_addr: "93.184.216.34".into(),
+            remote_port: 443,
+            pid: Some(812),
+            ..Default::default()
+        }];
+
+        // Nothing selected: no popup, just a hint
+        app.selected_connection = None;
+        handle_key_event(&mut app, KeyCode::Char('x'));
+        assert!(app.actions_popup.is_none());
+        assert_eq!(
+            app.toasts.newest_first().next().unwrap().severity,
+            crate::app::toast::ToastSeverity::Warning
+        );
+
+        app.selected_connection = Some(0);
+        handle_key_event(&mut app, KeyCode::Char('x'));
+        handle_key_event(&mut app, KeyCode::Down);
+        assert_eq!(app.actions_popup.as_ref().unwrap().selected, 1);
+
+        // 'q' does not quit while the popup is open; Esc closes it
+        assert!(handle_key_event(&mut app, KeyCode::Char('q')));
+        handle_key_event(&mut app, KeyCode::Esc);
+        assert!(app.actions_popup.is_none());
+        assert!(app.running);
+    }
+
+    #[test]
+    fn test_tag_keys() {
+        let mut app = AppState::new();
+        let conn = |remote_port| Connection {
+            remote_addr: "93.184.216.34".into(),
+            remote_port,
+            ..Default::default()
+        };
+        app.raw_connections = vec![conn(443), conn(80)];
+        app.apply_filters();
+
+        // '2' tags the selected connection ok and closes the popup
+        app.selected_connection = Some(0);
+        handle_key_event(&mut app, KeyCode::Char('n'));
+        assert_eq!(app.tag_popup.as_ref().unwrap().target, "93.184.216.34:443");
+        handle_key_event(&mut app, KeyCode::Char('2'));
+        assert!(app.tag_popup.is_none());
+        assert_eq!(app.tags.get(&conn(443)), vec![Tag::Ok]);
+
+        // Enter toggles the highlighted tag
+        handle_key_event(&mut app, KeyCode::Char('n'));
+        handle_key_event(&mut app, KeyCode::Enter);
+        assert_eq!(
+            app.tags.labels(&conn(443)).as_deref(),
+            Some("!suspicious,ok")
+        );
+
+        // Esc closes without tagging, and doesn't quit
+        handle_key_event(&mut app, KeyCode::Char('n'));
+        handle_key_event(&mut app, KeyCode::Down);
+        handle_key_event(&mut app, KeyCode::Esc);
+        assert!(app.tag_popup.is_none());
+        assert!(app.running);
+        assert!(!app.tags.has(&conn(443), Tag::FollowUp));
+
+        // 'N' narrows the list to one tag at a time
+        handle_key_event(&mut app, KeyCode::Char('N'));
+        assert_eq!(app.tag_filter, Some(Tag::Suspicious));
+        assert_eq!(app.connections, vec![conn(443)]);
+        handle_key_event(&mut app, KeyCode::Char('N'));
+        handle_key_event(&mut app, KeyCode::Char('N'));
+        assert_eq!(app.tag_filter, Some(Tag::FollowUp));
+        assert!(app.connections.is_empty());
+        handle_key_event(&mut app, KeyCode::Char('N'));
+        assert_eq!(app.tag_filter, None);
+        assert_eq!(app.connections.len(), 2);
+    }
+
+    #[test]
+    fn test_settings_keys() {
+        use crate::app::heavy_talkers::HeavyTalkerThreshold;
+        use crate::app::profiles::Profile;
+
+        let mut app = AppState::new();
+        handle_key_event(&mut app, KeyCode::Char(','));
+        assert!(app.settings_popup.is_some());
+
+        // Left/Right on the rule row switch between top N and at least N
+        handle_key_event(&mut app, KeyCode::Right);
+        assert_eq!(
+            app.graveyard_settings.heavy_talkers,
+            HeavyTalkerThreshold::Min(5)
+        );
+        handle_key_event(&mut app, KeyCode::Down);
+        handle_key_event(&mut app, KeyCode::PageUp);
+        handle_key_event(&mut app, KeyCode::Left);
+        assert_eq!(
+            app.graveyard_settings.heavy_talkers,
+            HeavyTalkerThreshold::Min(14)
+        );
+
+        // The profile row applies the picked preset right away
+        handle_key_event(&mut app, KeyCode::Down);
+        handle_key_event(&mut app, KeyCode::Right);
+        assert_eq!(app.profile, Some(Profile::SecurityAudit));
+        assert!(app.show_port_heatmap);
+        assert!(app.connection_filter.expression.is_some());
+        handle_key_event(&mut app, KeyCode::Left);
+        assert_eq!(app.profile, Some(Profile::Demo));
+        assert!(app.connection_filter.expression.is_none());
+
+        // Esc closes the popup without quitting
+        handle_key_event(&mut app, KeyCode::Esc);
+        assert!(app.settings_popup.is_none());
+        assert!(app.running);
+    }
+
+    #[test]
+    fn test_pin_key() {
+        let mut app = AppState::new();
+        app.raw_connections = vec![
+            Connection {
+                remote_addr: "93.184.216.34".into(),
+                remote_port: 443,
+                ..Default::default()
+            },
+            Connection {
+                state: crate::net::ConnectionState::Listen,
+                local_port: 22,
+                ..Default::default()
+            },
+        ];
+        app.apply_filters();
+
+        // Listeners have no endpoint to pin
+        app.selected_connection = Some(1);
+        handle_key_event(&mut app, KeyCode::Char('o'));
+        assert!(app.pinned_endpoints.is_empty());
+
+        app.selected_connection = Some(0);
+        handle_key_event(&mut app, KeyCode::Char('o'));
+        assert!(app.is_pinned("93.184.216.34"));
+        assert_eq!(app.watchlist().len(), 1);
+        handle_key_event(&mut app, KeyCode::Char('o'));
+        assert!(app.watchlist().is_empty());
+    }
+
+    #[test]
+    fn test_undo_key() {
+        let mut app = AppState::new();
+        app.raw_connections = vec![Connection {
+            user: Some("postgres".to_string()),
+            container: Some("db-1".to_string()),
+            ..Default::default()
+        }];
+        app.apply_filters();
+
+        // Nothing changed yet
+        handle_key_event(&mut app, KeyCode::Char('u'));
+        assert_eq!(app.undo_stack.len(), 0);
+
+        handle_key_event(&mut app, KeyCode::Char('U'));
+        handle_key_event(&mut app, KeyCode::Char('d'));
+        handle_key_event(&mut app, KeyCode::Char('s'));
+        // Keys that leave the view alone are not recorded
+        handle_key_event(&mut app, KeyCode::Char('?'));
+        assert_eq!(app.undo_stack.len(), 3);
+
+        handle_key_event(&mut app, KeyCode::Char('u'));
+        assert!(!app.split_view);
+        assert_eq!(app.connection_filter.container.as_deref(), Some("db-1"));
+        handle_key_event(&mut app, KeyCode::Char('u'));
+        assert_eq!(app.connection_filter.container, None);
+        assert_eq!(app.connection_filter.user.as_deref(), Some("postgres"));
+        handle_key_event(&mut app, KeyCode::Char('u'));
+        assert!(!app.connection_filter.is_active());
+        assert_eq!(app.undo_stack.len(), 0);
+
+        // Filter expressions submitted at the prompt are undone too
+        handle_key_event(&mut app, KeyCode::Char('/'));
+        for c in "port == 22".chars() {
+            handle_key_event(&mut app, KeyCode::Char(c));
+        }
+        handle_key_event(&mut app, KeyCode::Enter);
+        assert!(app.connection_filter.expression.is_some());
+        handle_key_event(&mut app, KeyCode::Char('u'));
+        assert!(app.connection_filter.expression.is_none());
+    }
+
+    #[test]
+    fn test_mark_keys() {
+        use crate::app::tags::Tag;
+
+        let mut app = AppState::new();
+        let conn = |remote_addr: &str| Connection {
+            remote_addr: remote_addr.into(),
+            remote_port: 443,
+            ..Default::default()
+        };
+        app.raw_connections = vec![
+            conn("93.184.216.34"),
+            conn("203.0.113.9"),
+            conn("198.51.100.7"),
+        ];
+        app.apply_filters();
+
+        // Marking moves down, so two presses mark the first two rows
+        app.selected_connection = Some(0);
+        handle_key_event(&mut app, KeyCode::Char('.'));
+        handle_key_event(&mut app, KeyCode::Char('.'));
+        assert_eq!(app.selected_connection, Some(2));
+        assert_eq!(app.marked_connections().len(), 2);
+
+        // Tagging and pinning apply to every marked connection
+        handle_key_event(&mut app, KeyCode::Char('n'));
+        assert_eq!(
+            app.tag_popup.as_ref().unwrap().target,
+            "2 marked connections"
+        );
+        handle_key_event(&mut app, KeyCode::Char('1'));
+        assert!(app.tags.has(&conn("93.184.216.34"), Tag::Suspicious));
+        assert!(app.tags.has(&conn("203.0.113.9"), Tag::Suspicious));
+        assert!(!app.tags.has(&conn("198.51.100.7"), Tag::Suspicious));
+        handle_key_event(&mut app, KeyCode::Char('o'));
+        assert!(app.is_pinned("93.184.216.34") && app.is_pinned("203.0.113.9"));
+        assert!(!app.is_pinned("198.51.100.7"));
+
+        // Esc clears the marks before it quits
+        handle_key_event(&mut app, KeyCode::Esc);
+        assert!(app.marked.is_empty());
+        assert!(app.running);
+    }
+
+    #[test]
+    fn test_snapshot_diff_keys() {
+        let mut app = AppState::new();
+        app.raw_connections = vec![Connection {
+            remote_addr: "10.1.0.1".into(),
+            remote_port: 5432,
+            ..Default::default()
+        }];
+
+        // The diff view needs a baseline
+        handle_key_event(&mut app, KeyCode::Char('v'));
+        assert!(!app.diff_mode);
+
+        handle_key_event(&mut app, KeyCode::Char('m'));
+        app.raw_connections.push(Connection {
+            remote_addr: "10.1.0.2".into(),
+            remote_port: 443,
+            ..Default::default()
+        });
+        handle_key_event(&mut app, KeyCode::Char('v'));
+        assert!(app.diff_mode);
+        assert_eq!(app.connections.len(), 1);
+        assert_eq!(&*app.connections[0].remote_addr, "10.1.0.2");
+        assert_eq!(app.diff_counts(), (1, 0));
+
+        handle_key_event(&mut app, KeyCode::Char('V'));
+        assert_eq!(app.connections.len(), 2);
+        assert_eq!(app.diff_change(&app.connections[0]), None);
+    }
+
+    #[test]
+    fn test_timeline_keys() {
+        let mut app = AppState::new();
+        let recorded = app.history.len();
+        assert!(recorded > 0);
+        app.history
+            .push(crate::app::snapshot::Snapshot::capture(&[Connection {
+                remote_addr: "10.1.0.9".into(),
+                ..Default::default()
+            }]));
+
+        // Arrows do nothing while live
+        handle_key_event(&mut app, KeyCode::Left);
+        assert_eq!(app.timeline, None);
+
+        handle_key_event(&mut app, KeyCode::Char(' '));
+        assert_eq!(app.timeline, Some(recorded));
+        assert_eq!(app.connections.len(), 1);
+
+        handle_key_event(&mut app, KeyCode::Left);
+        assert_eq!(app.timeline, Some(recorded - 1));
+        handle_key_event(&mut app, KeyCode::Right);
+        handle_key_event(&mut app, KeyCode::Right);
+        assert_eq!(app.timeline, Some(recorded));
+
+        handle_key_event(&mut app, KeyCode::Char(' '));
+        assert_eq!(app.timeline, None);
+    }
+
+    #[test]
+    fn test_watch_prompt() {
+        let mut app = AppState::new();
+        app.raw_connections = vec![Connection::default(), Connection::default()];
+
+        handle_key_event(&mut app, KeyCode::Char('w'));
+        assert_eq!(app.filter_prompt.as_deref(), Some("count("));
+        for c in ") > 1".chars() {
+            handle_key_event(&mut app, KeyCode::Char(c));
+        }
+        handle_key_event(&mut app, KeyCode::Enter);
+        assert!(app.filter_prompt.is_none());
+        assert_eq!(app.watches.len(), 1);
+        assert!(app.watches[0].firing);
+        // The filter itself is untouched
+        assert!(app.connection_filter.expression.is_none());
+
+        // Invalid watches keep the prompt open with the error
+        handle_key_event(&mut app, KeyCode::Char('w'));
+        handle_key_event(&mut app, KeyCode::Enter);
+        assert!(app.filter_prompt.is_some());
+        assert!(app.filter_error.is_some());
+        handle_key_event(&mut app, KeyCode::Esc);
+
+        handle_key_event(&mut app, KeyCode::Char('W'));
+        assert!(app.watches.is_empty());
+    }
+
+    #[test]
+    fn test_event_feed_focus_and_search() {
+        use crate::app::events::{EventSeverity, GrimoireEvent};
+
+        let mut app = AppState::new();
+        for i in 0..10 {
+            app.events.push(GrimoireEvent::new(
+                EventSeverity::Info,
+                format!("event {}", i),
+            ));
+        }
+        app.event_feed.rows = 3;
+
+        handle_key_event(&mut app, KeyCode::Tab);
+        assert!(app.event_feed.focused);
+
+        // Arrow keys scroll the feed, not the connection list
+        handle_key_event(&mut app, KeyCode::Up);
+        assert!(!app.event_feed.is_tailing());
+        handle_key_event(&mut app, KeyCode::End);
+        assert!(app.event_feed.is_tailing());
+
+        // '/' searches events instead of filtering connections
+        handle_key_event(&mut app, KeyCode::Char('/'));
+        type_text(&mut app, "EVENT 2");
+        handle_key_event(&mut app, KeyCode::Enter);
+        assert!(app.filter_prompt.is_none());
+        assert!(app.connection_filter.expression.is_none());
+        assert_eq!(app.event_feed.search(), Some("event 2"));
+        assert!(!app.event_feed.is_tailing());
+
+        // Esc clears the search first, then leaves the feed
+        handle_key_event(&mut app, KeyCode::Esc);
+        assert_eq!(app.event_feed.search(), None);
+        assert!(app.event_feed.focused);
+        handle_key_event(&mut app, KeyCode::Esc);
+        assert!(!app.event_feed.focused);
+        assert!(app.running);
+    }
+
+    #[test]
+    fn test_enter_narrows_the_event_feed() {
+        use crate::app::events::{EventSeverity, GrimoireEvent};
+        use crate::net::ConnectionState;
+
+        let mut app = AppState::new();
+        app.connections = vec![Connection {
+            remote_addr: "93.184.216.34".into(),
+            remote_port: 443,
+            state: ConnectionState::Established,
+            pid: Some(812),
+            ..Default::default()
+        }];
+        app.selected_connection = Some(0);
+        app.events
+            .push(GrimoireEvent::new(EventSeverity::Info, "curl").with_pid(Some(812)));
+        app.events
+            .push(GrimoireEvent::new(EventSeverity::Info, "other").with_pid(Some(9)));
+
+        handle_key_event(&mut app, KeyCode::Enter);
+        assert!(app.endpoint_detail.is_some());
+        let subject = app.event_feed.subject().unwrap();
+        assert_eq!(subject.label(), "pid 812 · 93.184.216.34");
+        assert_eq!(app.event_feed.window(&app.events).len(), 1);
+
+        // Esc in the feed shows every event again
+        handle_key_event(&mut app, KeyCode::Esc);
+        handle_key_event(&mut app, KeyCode::Tab);
+        handle_key_event(&mut app, KeyCode::Esc);
+        assert!(app.event_feed.subject().is_none());
+        assert!(app.event_feed.focused);
+    }
+
+    #[test]
+    fn test_jump_prompt() {
+        let mut app = AppState::new();
+        app.raw_connections = vec![
+            Connection {
+                local_port: 22,
+                state: crate::net::ConnectionState::Listen,
+                pid: Some(1),
+                ..Default::default()
+            },
+            Connection {
+                remote_addr: "10.1.0.1".into(),
+                remote_port: 5432,
+                pid: Some(812),
+                ..Default::default()
+            },
+        ];
+        app.apply_filters();
+        let type_in = |app: &mut AppState, text: &str| {
+            handle_key_event(app, KeyCode::Char('#'));
+            for c in text.chars() {
+                handle_key_event(app, KeyCode::Char(c));
+            }
+            handle_key_event(app, KeyCode::Enter);
+        };
+
+        type_in(&mut app, ":5432");
+        assert!(app.filter_prompt.is_none());
+        assert_eq!(app.selected_connection, Some(1));
+
+        // Unknown targets keep the prompt open with the error
+        type_in(&mut app, "4242");
+        assert!(app.filter_prompt.is_some());
+        assert!(app.filter_error.is_some());
+        handle_key_event(&mut app, KeyCode::Esc);
+
+        // '!' also focuses the process
+        type_in(&mut app, "1!");
+        assert_eq!(app.selected_connection, Some(0));
+        assert_eq!(app.graveyard_mode, crate::app::GraveyardMode::Process);
+        assert_eq!(app.selected_process_pid, Some(1));
+    }
+
+    #[test]
+    fn test_pool_expansion_key() {
+        let mut app = AppState::new();
+        let pooled = |local_port: u16| Connection {
+            local_addr: "10.0.0.5".into(),
+            local_port,
+            remote_addr: "10.1.0.1".into(),
+            remote_port: 5432,
+            pid: Some(42),
+            ..Default::default()
+        };
+        app.connections = vec![pooled(51000), pooled(51001), pooled(51002)];
+
+        // One pooled row: Down stays on it
+        handle_key_event(&mut app, KeyCode::Down);
+        handle_key_event(&mut app, KeyCode::Down);
+        assert_eq!(app.connection_list_state.selected(), Some(0));
+
+        // Expanded: pool row plus three members
+        handle_key_event(&mut app, KeyCode::Char('e'));
+        assert_eq!(app.connection_rows().len(), 4);
+        handle_key_event(&mut app, KeyCode::Down);
+        handle_key_event(&mut app, KeyCode::Down);
+        assert_eq!(app.selected_connection, Some(1));
+
+        // Collapsing from a member returns to the pool row
+        handle_key_event(&mut app, KeyCode::Char('E'));
+        assert_eq!(app.connection_rows().len(), 1);
+        assert_eq!(app.connection_list_state.selected(), Some(0));
+        assert_eq!(app.selected_connection, Some(0));
+    }
+
+    #[test]
+    fn test_split_view_toggle() {
+        let mut app = AppState::new();
+        assert!(!app.split_view);
+        handle_key_event(&mut app, KeyCode::Char('s'));
+        assert!(app.split_view);
+        handle_key_event(&mut app, KeyCode::Char('S'));
+        assert!(!app.split_view);
+    }
+
+    #[test]
+    fn test_port_heatmap_toggle() {
+        let mut app = AppState::new();
+        assert!(!app.show_port_heatmap);
+        handle_key_event(&mut app, KeyCode::Char('r'));
+        assert!(app.show_port_heatmap);
+        handle_key_event(&mut app, KeyCode::Char('R'));
+        assert!(!app.show_port_heatmap);
+    }
+
+    #[test]
+    fn test_seance_toggle() {
+        let mut app = AppState::new();
+        handle_key_event(&mut app, KeyCode::Char('S'));
+        handle_key_event(&mut app, KeyCode::Char('O'));
+        // The Séance grid and the split view replace the same panel
+        assert!(app.seance);
+        assert!(!app.split_view);
+        handle_key_event(&mut app, KeyCode::Char('u'));
+        assert!(!app.seance);
+        assert!(app.split_view);
+    }
+
+    #[test]
+    fn test_vim_keymap() {
+        use crate::app::keymap::KeymapProfile;
+        use crossterm::event::KeyModifiers;
+
+        let mut app = AppState::new();
+        app.keymap = KeymapProfile::Vim;
+        app.connections = (0..30)
+            .map(|i| Connection {
+                remote_addr: format!("10.1.0.{}", i).into(),
+                remote_port: 443,
+                ..Default::default()
+            })
+            .collect();
+        let press = |app: &mut AppState, c, modifiers| {
+            handle_key(app, KeyEvent::new(KeyCode::Char(c), modifiers));
+        };
+
+        press(&mut app, 'j', KeyModifiers::NONE);
+        press(&mut app, 'j', KeyModifiers::NONE);
+        assert_eq!(app.connection_list_state.selected(), Some(1));
+        press(&mut app, 'd', KeyModifiers::CONTROL);
+        assert_eq!(app.connection_list_state.selected(), Some(11));
+        press(&mut app, 'k', KeyModifiers::NONE);
+        assert_eq!(app.connection_list_state.selected(), Some(10));
+        press(&mut app, 'G', KeyModifiers::NONE);
+        assert_eq!(app.connection_list_state.selected(), Some(29));
+        press(&mut app, 'g', KeyModifiers::NONE);
+        assert_eq!(app.connection_list_state.selected(), Some(0));
+        press(&mut app, 'u', KeyModifiers::CONTROL);
+        assert_eq!(app.connection_list_state.selected(), Some(0));
+
+        // Typed into the filter prompt, not translated
+        press(&mut app, '/', KeyModifiers::NONE);
+        press(&mut app, 'j', KeyModifiers::NONE);
+        assert!(app
+            .filter_prompt
+            .as_deref()
+            .is_some_and(|p| p.ends_with('j')));
+    }
+
+    #[test]
+    fn test_diagnostics_panel() {
+        use crate::app::health::DiagnosticKind;
+
+        let mut app = AppState::new();
+        app.backend_health.mark_seen();
+        app.backend_health
+            .record(DiagnosticKind::Error, "ss", "ss: command not found");
+        assert_eq!(app.backend_health.unseen_errors(), 1);
+
+        handle_key_event(&mut app, KeyCode::Char('!'));
+        assert!(app.show_diagnostics);
+        assert_eq!(app.backend_health.unseen_errors(), 0);
+
+        // Esc closes the panel instead of quitting
+        assert!(handle_key_event(&mut app, KeyCode::Esc));
+        assert!(!app.show_diagnostics);
+        assert!(app.running);
+    }
+
+    #[test]
+    fn test_rdap_lookup_needs_a_public_address() {
+        use crate::app::probes::{ProbeLimiter, ProbesConfig};
+        use crate::net::ConnectionState;
+
+        let mut app = AppState::new();
+        app.connections = vec![Connection {
+            remote_addr: "10.1.0.1".into(),
+            remote_port: 5432,
+            state: ConnectionState::Established,
+            ..Default::default()
+        }];
+        app.selected_connection = Some(0);
+        handle_key_event(&mut app, KeyCode::Char('K'));
+        assert_eq!(app.rdap_popup, None);
+
+        // Passive-only mode never queries the registry
+        app.connections[0].remote_addr = "93.184.216.34".into();
+        app.probes = ProbeLimiter::from_config(&ProbesConfig {
+            passive_only: true,
+            ..Default::default()
+        });
+        handle_key_event(&mut app, KeyCode::Char('K'));
+        assert_eq!(app.rdap_popup, None);
+        assert_eq!(app.rdap_lookup("93.184.216.34"), None);
+    }
 }
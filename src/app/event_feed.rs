@@ -0,0 +1,382 @@
+// Grimoire event feed scrolling and search
+//
+// The feed tails the newest events until it is scrolled back (`Tab` focuses
+// it, then the arrow and page keys scroll). While scrolled back it stays on
+// the events being read as new ones arrive below, counting them for a
+// "↓ N new" indicator, and tails again once scrolled to the end. `/` searches
+// event messages and `n` / `N` step to the older / newer match.
+//
+// `Enter` on a connection narrows the feed to the events involving its
+// process or remote address, so the list, the endpoint detail and the
+// Grimoire tell the same story; `Esc` in the feed widens it again.
+//
+// Events are addressed by sequence number (their position in the order they
+// were recorded, see `EventLog::first_seq`), so the view does not shift when
+// the oldest events are evicted.
+
+use super::events::{EventLog, GrimoireEvent};
+
+/// Process and remote address the feed is narrowed to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EventSubject {
+    pub pid: Option<i32>,
+    pub remote_addr: Option<String>,
+}
+
+impl EventSubject {
+    /// Whether `event` involves the process or the remote address
+    pub fn involves(&self, event: &GrimoireEvent) -> bool {
+        (self.pid.is_some() && event.pid == self.pid)
+            || (self.remote_addr.is_some() && event.remote_addr == self.remote_addr)
+    }
+
+    /// "pid 1234 · 93.184.216.34"
+    pub fn label(&self) -> String {
+        let pid = self.pid.map(|pid| format!("pid {}", pid));
+        let parts: Vec<String> = pid.into_iter().chain(self.remote_addr.clone()).collect();
+        parts.join(" · ")
+    }
+}
+
+/// Scroll position, focus and search of the event feed
+#[derive(Debug, Clone, Default)]
+pub struct EventFeed {
+    /// Whether navigation keys scroll the feed instead of the connection list
+    pub focused: bool,
+    /// Sequence number of the bottom event while scrolled back; None while
+    /// tailing
+    anchor: Option<u64>,
+    /// `EventLog::pushed` when tailing stopped
+    pushed_at_scroll: u64,
+    /// Event rows visible at the last render
+    pub rows: usize,
+    /// Lowercased text searched for in event messages
+    search: Option<String>,
+    /// Sequence number of the match last jumped to
+    current_match: Option<u64>,
+    /// Only events involving this are shown
+    subject: Option<EventSubject>,
+}
+
+impl EventFeed {
+    /// Whether the newest events are shown as they arrive
+    pub fn is_tailing(&self) -> bool {
+        self.anchor.is_none()
+    }
+
+    /// Events recorded since the feed was scrolled back
+    pub fn new_events(&self, log: &EventLog) -> u64 {
+        if self.is_tailing() {
+            0
+        } else {
+            let seqs = self.seqs(log);
+            seqs.iter()
+                .filter(|&&seq| seq >= self.pushed_at_scroll)
+                .count() as u64
+        }
+    }
+
+    /// Sequence numbers of the events to show, oldest first
+    pub fn window(&self, log: &EventLog) -> Vec<u64> {
+        let seqs = self.seqs(log);
+        let rows = self.rows.max(1).min(seqs.len());
+        let end = self.end_index(&seqs);
+        seqs[end - rows..end].to_vec()
+    }
+
+    /// Sequence numbers of the retained events involving the subject (all
+    /// of them without one), oldest first
+    fn seqs(&self, log: &EventLog) -> Vec<u64> {
+        let first = log.first_seq();
+        log.iter()
+            .enumerate()
+            .filter(|(_, event)| self.involves(event))
+            .map(|(idx, _)| first + idx as u64)
+            .collect()
+    }
+
+    /// Index into `seqs` just past the bottom event of the view
+    fn end_index(&self, seqs: &[u64]) -> usize {
+        let rows = self.rows.max(1).min(seqs.len());
+        match self.anchor {
+            Some(anchor) => seqs
+                .partition_point(|&seq| seq <= anchor)
+                .clamp(rows, seqs.len()),
+            None => seqs.len(),
+        }
+    }
+
+    /// Move the view by `delta` events (negative is older)
+    pub fn scroll(&mut self, log: &EventLog, delta: i64) {
+        let seqs = self.seqs(log);
+        let end = self.end_index(&seqs) as i64;
+        self.show_until_index(log, &seqs, end.saturating_add(delta).max(0) as usize);
+    }
+
+    /// Show the oldest retained events
+    pub fn scroll_to_top(&mut self, log: &EventLog) {
+        let seqs = self.seqs(log);
+        self.show_until_index(log, &seqs, 0);
+    }
+
+    /// Follow the newest events again
+    pub fn tail(&mut self) {
+        self.anchor = None;
+    }
+
+    /// Put the view's bottom edge just before sequence number `end`,
+    /// tailing when that is past the newest event
+    fn show_until(&mut self, log: &EventLog, end: u64) {
+        let seqs = self.seqs(log);
+        let index = seqs.partition_point(|&seq| seq < end);
+        self.show_until_index(log, &seqs, index);
+    }
+
+    /// Put the view's bottom edge just before `seqs[end]`, tailing when
+    /// that is past the newest event
+    fn show_until_index(&mut self, log: &EventLog, seqs: &[u64], end: usize) {
+        let rows = self.rows.max(1).min(seqs.len());
+        let end = end.clamp(rows, seqs.len());
+        if end >= seqs.len() {
+            self.anchor = None;
+            return;
+        }
+        if self.anchor.is_none() {
+            self.pushed_at_scroll = log.pushed();
+        }
+        self.anchor = Some(seqs[end - 1]);
+    }
+
+    /// What the feed is narrowed to, if anything
+    pub fn subject(&self) -> Option<&EventSubject> {
+        self.subject.as_ref()
+    }
+
+    /// Show only the events involving `subject` (all events for None),
+    /// tailing the newest ones
+    pub fn set_subject(&mut self, subject: Option<EventSubject>) {
+        self.subject = subject;
+        self.anchor = None;
+        self.current_match = None;
+    }
+
+    /// Whether `event` is shown under the current subject
+    fn involves(&self, event: &GrimoireEvent) -> bool {
+        self.subject
+            .as_ref()
+            .map_or(true, |subject| subject.involves(event))
+    }
+
+    /// Text being searched for, if any
+    pub fn search(&self) -> Option<&str> {
+        self.search.as_deref()
+    }
+
+    /// Whether `event` contains the search text
+    pub fn matches(&self, event: &GrimoireEvent) -> bool {
+        self.search
+            .as_ref()
+            .is_some_and(|text| event.message.to_lowercase().contains(text.as_str()))
+    }
+
+    /// Whether sequence number `seq` is the match last jumped to
+    pub fn is_current_match(&self, seq: u64) -> bool {
+        self.current_match == Some(seq)
+    }
+
+    /// Search event messages for `text` (case-insensitive) and show the
+    /// newest match; an empty text clears the search
+    ///
+    /// Fails, keeping the previous search, when no event matches.
+    pub fn set_search(&mut self, log: &EventLog, text: &str) -> Result<(), String> {
+        let text = text.trim().to_lowercase();
+        if text.is_empty() {
+            self.clear_search();
+            return Ok(());
+        }
+        let previous = self.search.replace(text);
+        let previous_match = self.current_match.take();
+        if self.step_match(log, true) {
+            return Ok(());
+        }
+        let text = std::mem::replace(&mut self.search, previous).unwrap_or_default();
+        self.current_match = previous_match;
+        Err(format!("no event matches '{}'", text))
+    }
+
+    /// Stop searching
+    pub fn clear_search(&mut self) {
+        self.search = None;
+        self.current_match = None;
+    }
+
+    /// Jump to the next older (or newer) match and scroll it into view;
+    /// false when there is none
+    pub fn step_match(&mut self, log: &EventLog, older: bool) -> bool {
+        if self.search.is_none() {
+            return false;
+        }
+        let first = log.first_seq();
+        let mut seqs = log
+            .iter()
+            .enumerate()
+            .map(|(idx, event)| (first + idx as u64, event));
+        let found = if older {
+            let before = self.current_match.unwrap_or(log.pushed());
+            seqs.rev()
+                .find(|(seq, event)| *seq < before && self.matches(event) && self.involves(event))
+        } else {
+            let Some(after) = self.current_match else {
+                return false;
+            };
+            seqs.find(|(seq, event)| *seq > after && self.matches(event) && self.involves(event))
+        };
+        let Some((seq, _)) = found else {
+            return false;
+        };
+        self.current_match = Some(seq);
+        if !self.window(log).contains(&seq) {
+            self.show_until(log, seq + 1);
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::events::EventSeverity;
+
+    fn log_with(messages: &[&str], capacity: usize) -> EventLog {
+        let mut log = EventLog::new(capacity);
+        for message in messages {
+            log.push(GrimoireEvent::new(EventSeverity::Info, *message));
+        }
+        log
+    }
+
+    fn shown<'a>(feed: &EventFeed, log: &'a EventLog) -> Vec<&'a str> {
+        let first = log.first_seq();
+        let window = feed.window(log);
+        log.iter()
+            .enumerate()
+            .filter(|(idx, _)| window.contains(&(first + *idx as u64)))
+            .map(|(_, e)| e.message.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_tails_until_scrolled_back() {
+        let mut log = log_with(&["a", "b", "c", "d", "e"], 100);
+        let mut feed = EventFeed {
+            rows: 2,
+            ..Default::default()
+        };
+        assert_eq!(shown(&feed, &log), ["d", "e"]);
+
+        feed.scroll(&log, -1);
+        assert!(!feed.is_tailing());
+        assert_eq!(shown(&feed, &log), ["c", "d"]);
+
+        // New events arrive below without moving the view
+        log.push(GrimoireEvent::new(EventSeverity::Info, "f"));
+        log.push(GrimoireEvent::new(EventSeverity::Info, "g"));
+        assert_eq!(shown(&feed, &log), ["c", "d"]);
+        assert_eq!(feed.new_events(&log), 2);
+
+        // Scrolling past the top stops at the oldest event
+        feed.scroll(&log, -10);
+        assert_eq!(shown(&feed, &log), ["a", "b"]);
+
+        // Scrolling back to the end tails again
+        feed.scroll(&log, 10);
+        assert!(feed.is_tailing());
+        assert_eq!(feed.new_events(&log), 0);
+        assert_eq!(shown(&feed, &log), ["f", "g"]);
+    }
+
+    #[test]
+    fn test_view_survives_eviction() {
+        let mut log = log_with(&["a", "b", "c", "d"], 4);
+        let mut feed = EventFeed {
+            rows: 2,
+            ..Default::default()
+        };
+        feed.scroll_to_top(&log);
+        assert_eq!(shown(&feed, &log), ["a", "b"]);
+
+        // "a" is evicted; the view keeps "b" and fills in below it
+        log.push(GrimoireEvent::new(EventSeverity::Info, "e"));
+        assert_eq!(shown(&feed, &log), ["b", "c"]);
+    }
+
+    #[test]
+    fn test_search_steps_through_matches() {
+        let log = log_with(&["SYN storm", "x", "y", "syn storm over", "z", "w"], 100);
+        let mut feed = EventFeed {
+            rows: 2,
+            ..Default::default()
+        };
+        feed.set_search(&log, "Storm").unwrap();
+        assert!(feed.is_current_match(3));
+        assert_eq!(shown(&feed, &log), ["y", "syn storm over"]);
+
+        assert!(feed.step_match(&log, true));
+        assert!(feed.is_current_match(0));
+        assert_eq!(shown(&feed, &log), ["SYN storm", "x"]);
+        assert!(!feed.step_match(&log, true));
+
+        assert!(feed.step_match(&log, false));
+        assert!(feed.is_current_match(3));
+
+        // A failed search keeps the previous one
+        assert!(feed.set_search(&log, "nothing").is_err());
+        assert_eq!(feed.search(), Some("storm"));
+
+        feed.set_search(&log, "").unwrap();
+        assert_eq!(feed.search(), None);
+    }
+
+    #[test]
+    fn test_subject_narrows_the_feed() {
+        let mut log = EventLog::new(100);
+        for (message, pid, remote) in [
+            ("curl connects", Some(42), Some("93.184.216.34")),
+            ("sshd listens", Some(7), None),
+            ("SYN storm", None, Some("93.184.216.34")),
+            ("curl exits", Some(42), None),
+            ("nginx connects", Some(8), Some("10.1.0.1")),
+        ] {
+            let mut event = GrimoireEvent::new(EventSeverity::Info, message).with_pid(pid);
+            if let Some(remote) = remote {
+                event = event.with_remote(remote);
+            }
+            log.push(event);
+        }
+        let mut feed = EventFeed {
+            rows: 2,
+            ..Default::default()
+        };
+        feed.set_subject(Some(EventSubject {
+            pid: Some(42),
+            remote_addr: Some("93.184.216.34".to_string()),
+        }));
+        assert_eq!(shown(&feed, &log), ["SYN storm", "curl exits"]);
+        feed.scroll(&log, -1);
+        assert_eq!(shown(&feed, &log), ["curl connects", "SYN storm"]);
+
+        // Search only steps through the subject's events
+        feed.set_search(&log, "connects").unwrap();
+        assert!(feed.is_current_match(0));
+        assert!(!feed.step_match(&log, false));
+
+        // New events of the subject count, others don't
+        log.push(GrimoireEvent::new(EventSeverity::Info, "other"));
+        log.push(GrimoireEvent::new(EventSeverity::Info, "curl again").with_pid(Some(42)));
+        assert_eq!(feed.new_events(&log), 1);
+
+        feed.set_subject(None);
+        assert!(feed.is_tailing());
+        assert_eq!(shown(&feed, &log), ["other", "curl again"]);
+    }
+}
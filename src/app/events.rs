@@ -0,0 +1,366 @@
+// Grimoire event log
+//
+// This module contains the bounded event log shown in the Grimoire panel.
+// Events record notable observations (exposed listeners, alerts, lifecycle
+// changes) with a timestamp and severity, newest last.
+
+use super::forward::EventForwarder;
+use super::history::EventHistory;
+#[cfg(feature = "scripting")]
+use super::scripting::ScriptHooks;
+use std::collections::VecDeque;
+#[cfg(feature = "scripting")]
+use std::rc::Rc;
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Maximum number of events retained in memory
+pub const EVENT_LOG_CAPACITY: usize = 200;
+
+/// Severity of a Grimoire event
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum EventSeverity {
+    /// Informational lifecycle events
+    Info,
+    /// Notable findings worth a look
+    Warning,
+    /// High-signal findings that need attention
+    Critical,
+}
+
+impl EventSeverity {
+    /// Parse a severity name from the config file (info, warning, critical)
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "info" => Some(Self::Info),
+            "warn" | "warning" => Some(Self::Warning),
+            "crit" | "critical" => Some(Self::Critical),
+            _ => None,
+        }
+    }
+
+    /// Short label used in the event feed
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Info => "INFO",
+            Self::Warning => "WARN",
+            Self::Critical => "CRIT",
+        }
+    }
+}
+
+/// A single entry in the Grimoire event feed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GrimoireEvent {
+    /// Wall-clock time the event was recorded
+    pub timestamp: SystemTime,
+    /// Event severity
+    pub severity: EventSeverity,
+    /// Human-readable description
+    pub message: String,
+    /// Process involved in the event, if any
+    pub pid: Option<i32>,
+    /// Remote address involved in the event, if any
+    pub remote_addr: Option<String>,
+}
+
+impl GrimoireEvent {
+    /// Create an event stamped with the current time
+    pub fn new(severity: EventSeverity, message: impl Into<String>) -> Self {
+        Self {
+            timestamp: SystemTime::now(),
+            severity,
+            message: message.into(),
+            pid: None,
+            remote_addr: None,
+        }
+    }
+
+    /// Attach the process involved in the event
+    pub fn with_pid(mut self, pid: Option<i32>) -> Self {
+        self.pid = pid;
+        self
+    }
+
+    /// Attach the remote address involved in the event
+    pub fn with_remote(mut self, remote_addr: impl Into<String>) -> Self {
+        self.remote_addr = Some(remote_addr.into());
+        self
+    }
+
+    /// Format the timestamp as "HH:MM:SS" (UTC)
+    pub fn time_label(&self) -> String {
+        time_of_day_label(self.timestamp)
+    }
+}
+
+/// Format a wall-clock time as "HH:MM:SS" (UTC)
+pub fn time_of_day_label(time: SystemTime) -> String {
+    let secs = time
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let day_secs = secs % 86_400;
+    format!(
+        "{:02}:{:02}:{:02}",
+        day_secs / 3600,
+        (day_secs % 3600) / 60,
+        day_secs % 60
+    )
+}
+
+/// Days since the epoch to a civil (year, month, day), using Howard
+/// Hinnant's algorithm
+pub fn civil_date(days: i64) -> (i64, i64, i64) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let doe = days.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+/// Format a wall-clock time as "2025-10-31T23:59:59Z" (RFC 3339, UTC)
+pub fn rfc3339(time: SystemTime) -> String {
+    let secs = time
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let (year, month, day) = civil_date((secs / 86_400) as i64);
+    format!(
+        "{:04}-{:02}-{:02}T{}Z",
+        year,
+        month,
+        day,
+        time_of_day_label(time)
+    )
+}
+
+/// Bounded, append-only event log (oldest events are dropped first)
+#[derive(Debug, Clone)]
+pub struct EventLog {
+    events: VecDeque<GrimoireEvent>,
+    capacity: usize,
+    /// Events pushed since creation, including evicted ones
+    pushed: u64,
+    /// Copies events to syslog/journald (`[forward]`)
+    forwarder: Option<Arc<EventForwarder>>,
+    /// Appends events to the history file (`[history]`)
+    history: Option<Arc<Mutex<EventHistory>>>,
+    /// User script that can re-rate or suppress events (`[scripting]`)
+    #[cfg(feature = "scripting")]
+    hooks: Option<Rc<ScriptHooks>>,
+    /// Last script failure logged, so a broken hook is reported once
+    #[cfg(feature = "scripting")]
+    last_script_error: Option<String>,
+}
+
+impl EventLog {
+    /// Create an empty log holding at most `capacity` events
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            events: VecDeque::with_capacity(capacity),
+            capacity,
+            pushed: 0,
+            forwarder: None,
+            history: None,
+            #[cfg(feature = "scripting")]
+            hooks: None,
+            #[cfg(feature = "scripting")]
+            last_script_error: None,
+        }
+    }
+
+    /// Forward every event from now on, starting with the ones already
+    /// retained (startup events are recorded before the config is applied)
+    pub fn set_forwarder(&mut self, forwarder: EventForwarder) {
+        for event in &self.events {
+            forwarder.forward(event);
+        }
+        self.forwarder = Some(Arc::new(forwarder));
+    }
+
+    /// Write every event from now on to the history file, starting with
+    /// the ones already retained
+    pub fn set_history(&mut self, mut history: EventHistory) {
+        for event in &self.events {
+            history.record(event);
+        }
+        self.history = Some(Arc::new(Mutex::new(history)));
+    }
+
+    /// Run every event from now on through the user script's hook
+    #[cfg(feature = "scripting")]
+    pub fn set_script_hooks(&mut self, hooks: ScriptHooks) {
+        self.hooks = Some(Rc::new(hooks));
+    }
+
+    /// The user script, if one is loaded
+    #[cfg(feature = "scripting")]
+    pub fn script_hooks(&self) -> Option<Rc<ScriptHooks>> {
+        self.hooks.clone()
+    }
+
+    /// Log a script failure unless it repeats the previous one
+    #[cfg(feature = "scripting")]
+    pub fn script_error(&mut self, error: String) {
+        if self.last_script_error.as_ref() != Some(&error) {
+            self.record(GrimoireEvent::new(
+                EventSeverity::Warning,
+                format!("Script error: {}", error),
+            ));
+            self.last_script_error = Some(error);
+        }
+    }
+
+    /// Append an event after the user script has seen it
+    ///
+    /// A failing hook logs the event unchanged.
+    #[cfg(feature = "scripting")]
+    pub fn push(&mut self, event: GrimoireEvent) {
+        let Some(hooks) = self.hooks.clone() else {
+            return self.record(event);
+        };
+        match hooks.on_alert(&event) {
+            Ok(verdict) => {
+                if let Some(event) = verdict.apply(event) {
+                    self.record(event);
+                }
+            }
+            Err(e) => {
+                self.script_error(e);
+                self.record(event);
+            }
+        }
+    }
+
+    /// Append an event
+    #[cfg(not(feature = "scripting"))]
+    pub fn push(&mut self, event: GrimoireEvent) {
+        self.record(event);
+    }
+
+    /// Append an event without running hooks, evicting the oldest when full
+    pub fn record(&mut self, event: GrimoireEvent) {
+        if self.events.len() >= self.capacity {
+            self.events.pop_front();
+        }
+        tracing::debug!(
+            severity = event.severity.label(),
+            text = ?event.message,
+            "Grimoire event"
+        );
+        if let Some(forwarder) = &self.forwarder {
+            forwarder.forward(&event);
+        }
+        if let Some(history) = &self.history {
+            if let Ok(mut history) = history.lock() {
+                history.record(&event);
+            }
+        }
+        self.events.push_back(event);
+        self.pushed += 1;
+    }
+
+    /// Number of events ever pushed; the difference between two readings
+    /// tells how many of the newest events arrived in between
+    pub fn pushed(&self) -> u64 {
+        self.pushed
+    }
+
+    /// Sequence number of the oldest retained event; events are numbered
+    /// 0, 1, 2, ... in the order they were recorded
+    pub fn first_seq(&self) -> u64 {
+        self.pushed - self.events.len() as u64
+    }
+
+    /// Iterate events from oldest to newest
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &GrimoireEvent> + ExactSizeIterator {
+        self.events.iter()
+    }
+
+    /// Number of retained events
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    /// Whether the log is empty
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+}
+
+impl Default for EventLog {
+    fn default() -> Self {
+        Self::new(EVENT_LOG_CAPACITY)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_event_log_evicts_oldest() {
+        let mut log = EventLog::new(2);
+        log.push(GrimoireEvent::new(EventSeverity::Info, "one"));
+        log.push(GrimoireEvent::new(EventSeverity::Warning, "two"));
+        log.push(GrimoireEvent::new(EventSeverity::Critical, "three"));
+
+        let messages: Vec<&str> = log.iter().map(|e| e.message.as_str()).collect();
+        assert_eq!(messages, vec!["two", "three"]);
+        assert_eq!(log.len(), 2);
+    }
+
+    #[test]
+    fn test_severity_from_name() {
+        assert_eq!(
+            EventSeverity::from_name("Critical"),
+            Some(EventSeverity::Critical)
+        );
+        assert_eq!(
+            EventSeverity::from_name("warn"),
+            Some(EventSeverity::Warning)
+        );
+        assert_eq!(EventSeverity::from_name("loud"), None);
+    }
+
+    #[test]
+    fn test_time_label() {
+        let mut event = GrimoireEvent::new(EventSeverity::Info, "tick");
+        // 1970-01-02 01:02:03 UTC
+        event.timestamp = UNIX_EPOCH + Duration::from_secs(86_400 + 3_723);
+        assert_eq!(event.time_label(), "01:02:03");
+    }
+
+    #[cfg(feature = "scripting")]
+    #[test]
+    fn test_script_hooks_filter_events() {
+        let mut log = EventLog::new(10);
+        log.set_script_hooks(
+            ScriptHooks::compile(
+                r#"fn on_event(ev) {
+                    if ev.message == "noise" { return #{ suppress: true }; }
+                    if ev.message == "boom" { throw "broken"; }
+                }"#,
+            )
+            .unwrap(),
+        );
+        log.push(GrimoireEvent::new(EventSeverity::Info, "noise"));
+        log.push(GrimoireEvent::new(EventSeverity::Info, "signal"));
+        log.push(GrimoireEvent::new(EventSeverity::Info, "boom"));
+        log.push(GrimoireEvent::new(EventSeverity::Info, "boom"));
+
+        // The failing hook is reported once and its events are kept
+        let messages: Vec<&str> = log.iter().map(|e| e.message.as_str()).collect();
+        assert_eq!(messages.len(), 4);
+        assert_eq!(messages[0], "signal");
+        assert!(messages[1].starts_with("Script error: on_event"));
+        assert_eq!(&messages[2..], ["boom", "boom"]);
+    }
+}
@@ -0,0 +1,225 @@
+// Connection filtering
+//
+// This module contains the ConnectionFilter applied to each collected
+// snapshot before it reaches the UI panels. Filters narrow the visible
+// connection list without touching the underlying collected data.
+
+use super::filter_expr::FilterExpr;
+use crate::net::Connection;
+
+/// Active connection filters
+///
+/// All configured criteria must match for a connection to be visible.
+/// An empty filter (the default) shows every connection.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConnectionFilter {
+    /// Only show sockets owned by this user (cycle with 'U' key)
+    pub user: Option<String>,
+
+    /// Only show connections from this container (cycle with 'd' key)
+    pub container: Option<String>,
+
+    /// Only show connections bound to this local interface (cycle with 'I' key)
+    pub interface: Option<String>,
+
+    /// Filter expression entered at the '/' prompt or via `--filter`
+    pub expression: Option<FilterExpr>,
+}
+
+impl ConnectionFilter {
+    /// Check whether a connection passes every active filter
+    pub fn matches(&self, conn: &Connection) -> bool {
+        if let Some(ref user) = self.user {
+            if conn.user.as_deref() != Some(user.as_str()) {
+                return false;
+            }
+        }
+        if let Some(ref container) = self.container {
+            if conn.container.as_deref() != Some(container.as_str()) {
+                return false;
+            }
+        }
+        if let Some(ref interface) = self.interface {
+            if conn.interface.as_deref() != Some(interface.as_str()) {
+                return false;
+            }
+        }
+        if let Some(ref expression) = self.expression {
+            if !expression.matches(conn) {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Whether any filter criterion is set
+    pub fn is_active(&self) -> bool {
+        self.user.is_some()
+            || self.container.is_some()
+            || self.interface.is_some()
+            || self.expression.is_some()
+    }
+}
+
+/// Pick the next user filter value when cycling with the 'U' key
+pub fn next_user_filter(current: Option<&str>, connections: &[Connection]) -> Option<String> {
+    next_filter_value(
+        current,
+        connections.iter().filter_map(|c| c.user.as_deref()),
+    )
+}
+
+/// Pick the next container filter value when cycling with the 'd' key
+pub fn next_container_filter(current: Option<&str>, connections: &[Connection]) -> Option<String> {
+    next_filter_value(
+        current,
+        connections.iter().filter_map(|c| c.container.as_deref()),
+    )
+}
+
+/// Pick the next interface filter value when cycling with the 'I' key
+pub fn next_interface_filter(current: Option<&str>, connections: &[Connection]) -> Option<String> {
+    next_filter_value(
+        current,
+        connections.iter().filter_map(|c| c.interface.as_deref()),
+    )
+}
+
+/// Cycle through the sorted, distinct `values`:
+/// no filter → first value → ... → last value → no filter.
+///
+/// A current value that no longer exists resets the filter.
+fn next_filter_value<'a>(
+    current: Option<&str>,
+    values: impl Iterator<Item = &'a str>,
+) -> Option<String> {
+    let mut values: Vec<&str> = values.collect();
+    values.sort_unstable();
+    values.dedup();
+
+    let next = match current {
+        None => values.first(),
+        Some(current) => values
+            .iter()
+            .position(|v| *v == current)
+            .and_then(|idx| values.get(idx + 1)),
+    };
+    next.map(|v| v.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn conn_for_user(user: Option<&str>) -> Connection {
+        Connection {
+            local_addr: "127.0.0.1".into(),
+            local_port: 8080,
+            user: user.map(|u| u.to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_empty_filter_matches_everything() {
+        let filter = ConnectionFilter::default();
+        assert!(!filter.is_active());
+        assert!(filter.matches(&conn_for_user(None)));
+        assert!(filter.matches(&conn_for_user(Some("root"))));
+    }
+
+    #[test]
+    fn test_user_filter() {
+        let filter = ConnectionFilter {
+            user: Some("postgres".to_string()),
+            ..Default::default()
+        };
+        assert!(filter.matches(&conn_for_user(Some("postgres"))));
+        assert!(!filter.matches(&conn_for_user(Some("root"))));
+        assert!(!filter.matches(&conn_for_user(None)));
+    }
+
+    #[test]
+    fn test_container_filter() {
+        let in_container = Connection {
+            container: Some("cache-1".to_string()),
+            ..Default::default()
+        };
+        let filter = ConnectionFilter {
+            container: Some("cache-1".to_string()),
+            ..Default::default()
+        };
+        assert!(filter.is_active());
+        assert!(filter.matches(&in_container));
+        assert!(!filter.matches(&conn_for_user(Some("root"))));
+        assert_eq!(
+            next_container_filter(None, &[in_container]).as_deref(),
+            Some("cache-1")
+        );
+    }
+
+    #[test]
+    fn test_interface_filter() {
+        let on_wg = Connection {
+            interface: Some("wg0".into()),
+            ..Default::default()
+        };
+        let on_eth = Connection {
+            interface: Some("eth1".into()),
+            ..Default::default()
+        };
+        let filter = ConnectionFilter {
+            interface: Some("wg0".into()),
+            ..Default::default()
+        };
+        assert!(filter.is_active());
+        assert!(filter.matches(&on_wg));
+        assert!(!filter.matches(&on_eth));
+        assert!(!filter.matches(&conn_for_user(None)));
+
+        let conns = [on_wg, on_eth];
+        assert_eq!(next_interface_filter(None, &conns).as_deref(), Some("eth1"));
+        assert_eq!(
+            next_interface_filter(Some("eth1"), &conns).as_deref(),
+            Some("wg0")
+        );
+        assert_eq!(next_interface_filter(Some("wg0"), &conns), None);
+    }
+
+    #[test]
+    fn test_expression_combines_with_user_filter() {
+        let filter = ConnectionFilter {
+            user: Some("root".to_string()),
+            expression: Some("port == 8080".parse().unwrap()),
+            ..Default::default()
+        };
+        assert!(filter.is_active());
+        assert!(filter.matches(&conn_for_user(Some("root"))));
+        assert!(!filter.matches(&conn_for_user(Some("postgres"))));
+
+        let filter = ConnectionFilter {
+            expression: Some("port == 22".parse().unwrap()),
+            ..Default::default()
+        };
+        assert!(!filter.matches(&conn_for_user(Some("root"))));
+    }
+
+    #[test]
+    fn test_next_user_filter_cycles() {
+        let conns = vec![
+            conn_for_user(Some("root")),
+            conn_for_user(Some("postgres")),
+            conn_for_user(Some("root")),
+            conn_for_user(None),
+        ];
+
+        let first = next_user_filter(None, &conns);
+        assert_eq!(first.as_deref(), Some("postgres"));
+        let second = next_user_filter(first.as_deref(), &conns);
+        assert_eq!(second.as_deref(), Some("root"));
+        // Wraps back to "no filter"
+        assert_eq!(next_user_filter(second.as_deref(), &conns), None);
+        // A user that disappeared resets the filter
+        assert_eq!(next_user_filter(Some("gone"), &conns), None);
+    }
+}
@@ -0,0 +1,789 @@
+// Filter expression language
+//
+// A small boolean language for selecting connections, used by the `/`
+// prompt and `--filter`:
+//
+//   state == established && remote in 10.0.0.0/8 && port != 22
+//   proc ~ "postgres" || (user == root && !state == listen)
+//
+// Grammar (lowest to highest precedence):
+//
+//   expr    := and ( ("||" | "or") and )*
+//   and     := unary ( ("&&" | "and") unary )*
+//   unary   := ("!" | "not") unary | "(" expr ")" | term
+//   term    := FIELD OP VALUE | VALUE
+//
+// A bare VALUE is a case-insensitive substring search over the process
+// name, addresses, user, container, interface and enricher labels.
+// Expressions are parsed once into a tree and evaluated against every
+// connection of each snapshot.
+
+use crate::net::cidr::Cidr;
+use crate::net::{Connection, ConnectionState};
+use std::fmt;
+use std::str::FromStr;
+use thiserror::Error;
+
+/// A filter expression that failed to parse
+///
+/// `column` is 1-based so it can be pointed at directly in the prompt.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("{message} (column {column})")]
+pub struct FilterParseError {
+    pub column: usize,
+    pub message: String,
+}
+
+/// A parsed filter expression together with its source text
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FilterExpr {
+    source: String,
+    root: Node,
+}
+
+impl FilterExpr {
+    /// Parse `input` into an expression tree
+    pub fn parse(input: &str) -> Result<Self, FilterParseError> {
+        let tokens = tokenize(input)?;
+        let mut parser = Parser {
+            tokens,
+            pos: 0,
+            end_column: input.chars().count() + 1,
+        };
+        if parser.peek().is_none() {
+            return Err(FilterParseError {
+                column: 1,
+                message: "empty filter expression".to_string(),
+            });
+        }
+        let root = parser.parse_or()?;
+        if let Some(token) = parser.peek() {
+            return Err(parser.error_at(
+                token.column,
+                format!("unexpected '{}' (missing && or ||?)", token.kind),
+            ));
+        }
+        Ok(Self {
+            source: input.trim().to_string(),
+            root,
+        })
+    }
+
+    /// The expression as typed by the user
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// Check whether `conn` satisfies the expression
+    pub fn matches(&self, conn: &Connection) -> bool {
+        self.root.eval(conn)
+    }
+}
+
+impl FromStr for FilterExpr {
+    type Err = FilterParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl fmt::Display for FilterExpr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Expression tree
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Node {
+    And(Box<Node>, Box<Node>),
+    Or(Box<Node>, Box<Node>),
+    Not(Box<Node>),
+    /// `state == listen`
+    State {
+        negated: bool,
+        state: ConnectionState,
+    },
+    /// `process == nginx`, `remote ~ 10.0.`
+    Text {
+        field: TextField,
+        op: TextOp,
+        value: String,
+    },
+    /// `remote in 10.0.0.0/8`
+    InCidr {
+        field: TextField,
+        cidr: Cidr,
+    },
+    /// `pid > 1000`, `port == 443`
+    Number {
+        field: NumberField,
+        op: NumberOp,
+        value: u64,
+    },
+    /// Bare word: substring search over the text fields
+    Search(String),
+}
+
+impl Node {
+    fn eval(&self, conn: &Connection) -> bool {
+        match self {
+            Node::And(a, b) => a.eval(conn) && b.eval(conn),
+            Node::Or(a, b) => a.eval(conn) || b.eval(conn),
+            Node::Not(inner) => !inner.eval(conn),
+            Node::State { negated, state } => (conn.state == *state) != *negated,
+            Node::Text { field, op, value } => {
+                let actual = field.get(conn);
+                match op {
+                    TextOp::Eq => actual == Some(value.as_str()),
+                    TextOp::Ne => actual != Some(value.as_str()),
+                    TextOp::Contains => actual.is_some_and(|a| contains_ignore_case(a, value)),
+                    TextOp::NotContains => !actual.is_some_and(|a| contains_ignore_case(a, value)),
+                }
+            }
+            Node::InCidr { field, cidr } => field.get(conn).is_some_and(|a| cidr.contains_str(a)),
+            Node::Number { field, op, value } => {
+                let values = field.get(conn);
+                match op {
+                    // "port != 22" means neither port is 22
+                    NumberOp::Ne => !values.iter().flatten().any(|v| v == value),
+                    _ => values.iter().flatten().any(|v| op.compare(*v, *value)),
+                }
+            }
+            Node::Search(needle) => {
+                TextField::ALL
+                    .iter()
+                    .filter_map(|field| field.get(conn))
+                    .any(|text| contains_ignore_case(text, needle))
+                    || conn
+                        .labels
+                        .values()
+                        .any(|label| contains_ignore_case(label, needle))
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TextField {
+    Local,
+    Remote,
+    Process,
+    User,
+    Container,
+    Interface,
+}
+
+impl TextField {
+    const ALL: [TextField; 6] = [
+        TextField::Local,
+        TextField::Remote,
+        TextField::Process,
+        TextField::User,
+        TextField::Container,
+        TextField::Interface,
+    ];
+
+    fn get<'a>(&self, conn: &'a Connection) -> Option<&'a str> {
+        match self {
+            TextField::Local => Some(&conn.local_addr),
+            TextField::Remote => Some(&conn.remote_addr),
+            TextField::Process => conn.process_name.as_deref(),
+            TextField::User => conn.user.as_deref(),
+            TextField::Container => conn.container.as_deref(),
+            TextField::Interface => conn.interface.as_deref(),
+        }
+    }
+
+    fn is_address(&self) -> bool {
+        matches!(self, TextField::Local | TextField::Remote)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum NumberField {
+    /// Either the local or the remote port
+    Port,
+    LocalPort,
+    RemotePort,
+    Pid,
+    Uid,
+}
+
+impl NumberField {
+    fn get(&self, conn: &Connection) -> [Option<u64>; 2] {
+        match self {
+            NumberField::Port => [Some(conn.local_port as u64), Some(conn.remote_port as u64)],
+            NumberField::LocalPort => [Some(conn.local_port as u64), None],
+            NumberField::RemotePort => [Some(conn.remote_port as u64), None],
+            NumberField::Pid => [conn.pid.map(|p| p as u64), None],
+            NumberField::Uid => [conn.uid.map(|u| u as u64), None],
+        }
+    }
+
+    fn max(&self) -> u64 {
+        match self {
+            NumberField::Port | NumberField::LocalPort | NumberField::RemotePort => u16::MAX as u64,
+            NumberField::Pid => i32::MAX as u64,
+            NumberField::Uid => u32::MAX as u64,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TextOp {
+    Eq,
+    Ne,
+    Contains,
+    NotContains,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum NumberOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl NumberOp {
+    fn compare(&self, actual: u64, expected: u64) -> bool {
+        match self {
+            NumberOp::Eq => actual == expected,
+            NumberOp::Ne => actual != expected,
+            NumberOp::Lt => actual < expected,
+            NumberOp::Le => actual <= expected,
+            NumberOp::Gt => actual > expected,
+            NumberOp::Ge => actual >= expected,
+        }
+    }
+}
+
+enum Field {
+    State,
+    Text(TextField),
+    Number(NumberField),
+}
+
+/// Field names accepted on the left of a comparison
+const FIELD_NAMES: &str =
+    "state, local, remote, port, lport, rport, pid, uid, proc, user, container, iface";
+
+fn lookup_field(name: &str) -> Option<Field> {
+    let field = match name.to_ascii_lowercase().as_str() {
+        "state" => Field::State,
+        "local" | "laddr" => Field::Text(TextField::Local),
+        "remote" | "raddr" => Field::Text(TextField::Remote),
+        "proc" | "process" | "name" => Field::Text(TextField::Process),
+        "user" => Field::Text(TextField::User),
+        "container" => Field::Text(TextField::Container),
+        "iface" | "interface" => Field::Text(TextField::Interface),
+        "port" => Field::Number(NumberField::Port),
+        "lport" => Field::Number(NumberField::LocalPort),
+        "rport" => Field::Number(NumberField::RemotePort),
+        "pid" => Field::Number(NumberField::Pid),
+        "uid" => Field::Number(NumberField::Uid),
+        _ => return None,
+    };
+    Some(field)
+}
+
+/// Accept ss/lsof/netstat spellings: established, ESTAB, syn-sent, time_wait
+fn parse_state_name(name: &str) -> Option<ConnectionState> {
+    let state = match name.to_ascii_uppercase().replace('-', "_").as_str() {
+        "ESTABLISHED" | "ESTAB" => ConnectionState::Established,
+        "SYN_SENT" => ConnectionState::SynSent,
+        "SYN_RECV" | "SYN_RECEIVED" => ConnectionState::SynRecv,
+        "FIN_WAIT1" | "FIN_WAIT_1" => ConnectionState::FinWait1,
+        "FIN_WAIT2" | "FIN_WAIT_2" => ConnectionState::FinWait2,
+        "TIME_WAIT" => ConnectionState::TimeWait,
+        "CLOSE" | "CLOSED" => ConnectionState::Close,
+        "CLOSE_WAIT" => ConnectionState::CloseWait,
+        "LAST_ACK" => ConnectionState::LastAck,
+        "LISTEN" => ConnectionState::Listen,
+        "CLOSING" => ConnectionState::Closing,
+        "UNKNOWN" => ConnectionState::Unknown,
+        _ => return None,
+    };
+    Some(state)
+}
+
+fn contains_ignore_case(haystack: &str, needle: &str) -> bool {
+    haystack.to_lowercase().contains(&needle.to_lowercase())
+}
+
+// ---------------------------------------------------------------------------
+// Tokenizer
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum TokenKind {
+    Word(String),
+    Quoted(String),
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Tilde,
+    NotTilde,
+    And,
+    Or,
+    Not,
+    In,
+    LParen,
+    RParen,
+}
+
+impl fmt::Display for TokenKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TokenKind::Word(w) => f.write_str(w),
+            TokenKind::Quoted(s) => write!(f, "\"{}\"", s),
+            TokenKind::Eq => f.write_str("=="),
+            TokenKind::Ne => f.write_str("!="),
+            TokenKind::Lt => f.write_str("<"),
+            TokenKind::Le => f.write_str("<="),
+            TokenKind::Gt => f.write_str(">"),
+            TokenKind::Ge => f.write_str(">="),
+            TokenKind::Tilde => f.write_str("~"),
+            TokenKind::NotTilde => f.write_str("!~"),
+            TokenKind::And => f.write_str("&&"),
+            TokenKind::Or => f.write_str("||"),
+            TokenKind::Not => f.write_str("!"),
+            TokenKind::In => f.write_str("in"),
+            TokenKind::LParen => f.write_str("("),
+            TokenKind::RParen => f.write_str(")"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Token {
+    kind: TokenKind,
+    /// 1-based column of the first character
+    column: usize,
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || matches!(c, '.' | ':' | '/' | '-' | '_' | '*' | '@' | '[' | ']')
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>, FilterParseError> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let column = i + 1;
+        if c.is_whitespace() {
+            i += 1;
+            continue;
+        }
+
+        let next = chars.get(i + 1).copied();
+        let (kind, len) = match (c, next) {
+            ('=', Some('=')) => (TokenKind::Eq, 2),
+            ('=', _) => (TokenKind::Eq, 1),
+            ('!', Some('=')) => (TokenKind::Ne, 2),
+            ('!', Some('~')) => (TokenKind::NotTilde, 2),
+            ('!', _) => (TokenKind::Not, 1),
+            ('<', Some('=')) => (TokenKind::Le, 2),
+            ('<', _) => (TokenKind::Lt, 1),
+            ('>', Some('=')) => (TokenKind::Ge, 2),
+            ('>', _) => (TokenKind::Gt, 1),
+            ('~', _) => (TokenKind::Tilde, 1),
+            ('&', Some('&')) => (TokenKind::And, 2),
+            ('|', Some('|')) => (TokenKind::Or, 2),
+            ('(', _) => (TokenKind::LParen, 1),
+            (')', _) => (TokenKind::RParen, 1),
+            ('"', _) | ('\'', _) => {
+                let end = chars[i + 1..]
+                    .iter()
+                    .position(|&ch| ch == c)
+                    .ok_or_else(|| FilterParseError {
+                        column,
+                        message: "unterminated string".to_string(),
+                    })?;
+                let text: String = chars[i + 1..i + 1 + end].iter().collect();
+                (TokenKind::Quoted(text), end + 2)
+            }
+            ('&', _) | ('|', _) => {
+                return Err(FilterParseError {
+                    column,
+                    message: format!("use '{0}{0}' instead of '{0}'", c),
+                });
+            }
+            _ if is_word_char(c) => {
+                let len = chars[i..]
+                    .iter()
+                    .take_while(|&&ch| is_word_char(ch))
+                    .count();
+                let word: String = chars[i..i + len].iter().collect();
+                let kind = match word.to_ascii_lowercase().as_str() {
+                    "and" => TokenKind::And,
+                    "or" => TokenKind::Or,
+                    "not" => TokenKind::Not,
+                    "in" => TokenKind::In,
+                    _ => TokenKind::Word(word),
+                };
+                (kind, len)
+            }
+            _ => {
+                return Err(FilterParseError {
+                    column,
+                    message: format!("unexpected character '{}'", c),
+                });
+            }
+        };
+
+        tokens.push(Token { kind, column });
+        i += len;
+    }
+
+    Ok(tokens)
+}
+
+// ---------------------------------------------------------------------------
+// Parser
+// ---------------------------------------------------------------------------
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+    /// Column just past the input, for "expected ... at end" errors
+    end_column: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn error_at(&self, column: usize, message: impl Into<String>) -> FilterParseError {
+        FilterParseError {
+            column,
+            message: message.into(),
+        }
+    }
+
+    /// Error for a missing token at the current position
+    fn expected(&self, what: &str) -> FilterParseError {
+        match self.peek() {
+            Some(token) => self.error_at(
+                token.column,
+                format!("expected {}, found '{}'", what, token.kind),
+            ),
+            None => self.error_at(self.end_column, format!("expected {} at end", what)),
+        }
+    }
+
+    fn parse_or(&mut self) -> Result<Node, FilterParseError> {
+        let mut node = self.parse_and()?;
+        while self.peek().is_some_and(|t| t.kind == TokenKind::Or) {
+            self.pos += 1;
+            node = Node::Or(Box::new(node), Box::new(self.parse_and()?));
+        }
+        Ok(node)
+    }
+
+    fn parse_and(&mut self) -> Result<Node, FilterParseError> {
+        let mut node = self.parse_unary()?;
+        while self.peek().is_some_and(|t| t.kind == TokenKind::And) {
+            self.pos += 1;
+            node = Node::And(Box::new(node), Box::new(self.parse_unary()?));
+        }
+        Ok(node)
+    }
+
+    fn parse_unary(&mut self) -> Result<Node, FilterParseError> {
+        match self.peek().map(|t| &t.kind) {
+            Some(TokenKind::Not) => {
+                self.pos += 1;
+                Ok(Node::Not(Box::new(self.parse_unary()?)))
+            }
+            Some(TokenKind::LParen) => {
+                let open = self.next().map(|t| t.column).unwrap_or(1);
+                let node = self.parse_or()?;
+                match self.next() {
+                    Some(Token {
+                        kind: TokenKind::RParen,
+                        ..
+                    }) => Ok(node),
+                    _ => Err(self.error_at(open, "unclosed '('")),
+                }
+            }
+            _ => self.parse_term(),
+        }
+    }
+
+    fn parse_term(&mut self) -> Result<Node, FilterParseError> {
+        let (name, column) = match self.next() {
+            Some(Token {
+                kind: TokenKind::Word(word) | TokenKind::Quoted(word),
+                column,
+            }) => (word, column),
+            Some(token) => {
+                return Err(self.error_at(
+                    token.column,
+                    format!("expected a field or search term, found '{}'", token.kind),
+                ));
+            }
+            None => return Err(self.expected("a field or search term")),
+        };
+
+        let Some(op) = self.peek().filter(|t| is_comparison(&t.kind)).cloned() else {
+            return Ok(Node::Search(name));
+        };
+        self.pos += 1;
+
+        let field = lookup_field(&name).ok_or_else(|| {
+            self.error_at(
+                column,
+                format!("unknown field '{}' (fields: {})", name, FIELD_NAMES),
+            )
+        })?;
+        let (value, value_column) = match self.next() {
+            Some(Token {
+                kind: TokenKind::Word(value) | TokenKind::Quoted(value),
+                column,
+            }) => (value, column),
+            _ => {
+                self.pos -= 1;
+                return Err(self.expected(&format!("a value after '{}'", op.kind)));
+            }
+        };
+
+        match field {
+            Field::State => {
+                let negated = match op.kind {
+                    TokenKind::Eq => false,
+                    TokenKind::Ne => true,
+                    _ => {
+                        return Err(self.error_at(op.column, "state only supports == and !="));
+                    }
+                };
+                let state = parse_state_name(&value).ok_or_else(|| {
+                    self.error_at(
+                        value_column,
+                        format!(
+                            "unknown state '{}' (e.g. established, listen, time_wait)",
+                            value
+                        ),
+                    )
+                })?;
+                Ok(Node::State { negated, state })
+            }
+            Field::Text(field) => {
+                let op = match op.kind {
+                    TokenKind::Eq => TextOp::Eq,
+                    TokenKind::Ne => TextOp::Ne,
+                    TokenKind::Tilde => TextOp::Contains,
+                    TokenKind::NotTilde => TextOp::NotContains,
+                    TokenKind::In if field.is_address() => {
+                        let cidr = value
+                            .parse::<Cidr>()
+                            .map_err(|e| self.error_at(value_column, e))?;
+                        return Ok(Node::InCidr { field, cidr });
+                    }
+                    TokenKind::In => {
+                        return Err(
+                            self.error_at(op.column, "'in' needs an address field (local, remote)")
+                        );
+                    }
+                    _ => {
+                        return Err(self
+                            .error_at(op.column, format!("'{}' needs a numeric field", op.kind)));
+                    }
+                };
+                Ok(Node::Text { field, op, value })
+            }
+            Field::Number(field) => {
+                let op = match op.kind {
+                    TokenKind::Eq => NumberOp::Eq,
+                    TokenKind::Ne => NumberOp::Ne,
+                    TokenKind::Lt => NumberOp::Lt,
+                    TokenKind::Le => NumberOp::Le,
+                    TokenKind::Gt => NumberOp::Gt,
+                    TokenKind::Ge => NumberOp::Ge,
+                    _ => {
+                        return Err(self.error_at(
+                            op.column,
+                            format!("'{}' is not supported on '{}'", op.kind, name),
+                        ));
+                    }
+                };
+                let value = value
+                    .parse::<u64>()
+                    .ok()
+                    .filter(|v| *v <= field.max())
+                    .ok_or_else(|| {
+                        self.error_at(
+                            value_column,
+                            format!("'{}' is not a valid {} (0-{})", value, name, field.max()),
+                        )
+                    })?;
+                Ok(Node::Number { field, op, value })
+            }
+        }
+    }
+}
+
+fn is_comparison(kind: &TokenKind) -> bool {
+    matches!(
+        kind,
+        TokenKind::Eq
+            | TokenKind::Ne
+            | TokenKind::Lt
+            | TokenKind::Le
+            | TokenKind::Gt
+            | TokenKind::Ge
+            | TokenKind::Tilde
+            | TokenKind::NotTilde
+            | TokenKind::In
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn conn(remote: &str, local_port: u16, remote_port: u16, state: ConnectionState) -> Connection {
+        Connection {
+            local_addr: "192.168.1.5".into(),
+            local_port,
+            remote_addr: remote.into(),
+            remote_port,
+            state,
+            pid: Some(4242),
+            process_name: Some("postgres".into()),
+            user: Some("postgres".to_string()),
+            ..Default::default()
+        }
+    }
+
+    fn matches(expr: &str, conn: &Connection) -> bool {
+        FilterExpr::parse(expr).unwrap().matches(conn)
+    }
+
+    #[test]
+    fn test_example_expression() {
+        let expr = "state == established && remote in 10.0.0.0/8 && port != 22";
+        let ssh = conn("10.1.2.3", 50000, 22, ConnectionState::Established);
+        let db = conn("10.1.2.3", 50001, 5432, ConnectionState::Established);
+        let public = conn("203.0.113.9", 50002, 443, ConnectionState::Established);
+        let waiting = conn("10.1.2.3", 50003, 5432, ConnectionState::TimeWait);
+
+        assert!(!matches(expr, &ssh));
+        assert!(matches(expr, &db));
+        assert!(!matches(expr, &public));
+        assert!(!matches(expr, &waiting));
+    }
+
+    #[test]
+    fn test_precedence_and_grouping() {
+        let c = conn("10.0.0.1", 5432, 40000, ConnectionState::Listen);
+        // && binds tighter than ||
+        assert!(matches("pid == 1 && port == 1 || state == listen", &c));
+        assert!(!matches("pid == 1 && (port == 1 || state == listen)", &c));
+        assert!(matches(
+            "not (state == established) and proc == postgres",
+            &c
+        ));
+        assert!(matches("!state == established", &c));
+    }
+
+    #[test]
+    fn test_text_and_numeric_operators() {
+        let c = conn("10.0.0.1", 5432, 40000, ConnectionState::Established);
+        assert!(matches("proc ~ GRES", &c));
+        assert!(matches("proc !~ nginx", &c));
+        assert!(matches("user == \"postgres\"", &c));
+        assert!(matches("container != web", &c));
+        assert!(!matches("container == web", &c));
+        assert!(matches("lport < 6000 && rport >= 40000 && pid > 1000", &c));
+        assert!(matches("state == ESTAB", &c));
+        assert!(matches("state == syn-sent || state == established", &c));
+    }
+
+    #[test]
+    fn test_bare_word_searches_text_fields() {
+        let c = conn("10.0.0.1", 5432, 40000, ConnectionState::Established);
+        assert!(matches("postgres", &c));
+        assert!(matches("10.0.0", &c));
+        assert!(!matches("nginx", &c));
+        assert!(matches("nginx || 10.0.0", &c));
+    }
+
+    #[test]
+    fn test_parse_errors_point_at_problem() {
+        let err = FilterExpr::parse("state == establishd").unwrap_err();
+        assert_eq!(err.column, 10);
+        assert!(err.message.contains("unknown state"));
+
+        let err = FilterExpr::parse("color == red").unwrap_err();
+        assert_eq!(err.column, 1);
+        assert!(err.message.contains("unknown field"));
+
+        let err = FilterExpr::parse("port == 70000").unwrap_err();
+        assert!(err.message.contains("0-65535"));
+
+        let err = FilterExpr::parse("remote in 10.0.0.0/40").unwrap_err();
+        assert!(err.message.contains("prefix length"));
+
+        let err = FilterExpr::parse("(state == listen").unwrap_err();
+        assert_eq!(err.column, 1);
+        assert!(err.message.contains("unclosed"));
+
+        let err = FilterExpr::parse("port ==").unwrap_err();
+        assert!(err.message.contains("at end"));
+
+        let err = FilterExpr::parse("state == listen port == 22").unwrap_err();
+        assert_eq!(err.column, 17);
+
+        assert!(FilterExpr::parse("proc & x").is_err());
+        assert!(FilterExpr::parse("pid ~ 12").is_err());
+        assert!(FilterExpr::parse("user in 10.0.0.0/8").is_err());
+        assert!(FilterExpr::parse("   ").is_err());
+        assert!(FilterExpr::parse("proc == \"unterminated").is_err());
+    }
+
+    #[test]
+    fn test_source_is_kept() {
+        let expr: FilterExpr = "  port == 22 ".parse().unwrap();
+        assert_eq!(expr.source(), "port == 22");
+        assert_eq!(expr.to_string(), "port == 22");
+    }
+
+    #[test]
+    fn test_interface_field() {
+        let mut c = conn("10.8.0.1", 5432, 40000, ConnectionState::Established);
+        c.interface = Some("wg0".into());
+        assert!(matches("iface == wg0", &c));
+        assert!(matches("interface ~ WG", &c));
+        assert!(!matches("iface == eth1", &c));
+        assert!(matches("wg0", &c));
+    }
+
+    #[test]
+    fn test_bare_word_searches_labels() {
+        let mut c = conn("10.0.0.1", 50000, 5432, ConnectionState::Established);
+        c.labels.insert("service", "postgres-primary".into());
+        assert!(matches("primary", &c));
+        assert!(!matches("replica", &c));
+    }
+}
@@ -0,0 +1,246 @@
+// Event forwarding to syslog or the systemd journal
+//
+// Grimoire events (alerts and lifecycle changes) can be copied into the
+// host's log pipeline, so findings are kept even when nobody is watching
+// the terminal:
+//
+// ```toml
+// [forward]
+// target = "journald"        # or "syslog"
+// min_severity = "warning"   # info (default), warning, critical
+// # syslog only: Unix socket (default /dev/log) or "udp://loghost:514"
+// # address = "udp://loghost:514"
+// ```
+//
+// journald gets its native protocol with NTOMB_* fields; syslog gets
+// RFC 5424 messages carrying the same fields as structured data. Sending
+// is best effort: a log daemon that is down never disturbs the TUI.
+
+use super::events::{rfc3339, EventSeverity, GrimoireEvent};
+use serde::Deserialize;
+use std::net::UdpSocket;
+use std::os::unix::net::UnixDatagram;
+
+/// Native protocol socket of systemd-journald
+const JOURNALD_SOCKET: &str = "/run/systemd/journal/socket";
+
+/// Local syslog socket
+const SYSLOG_SOCKET: &str = "/dev/log";
+
+/// syslog facility "user" (RFC 5424 section 6.2.1)
+const FACILITY_USER: u8 = 1;
+
+/// Private enterprise number used for the structured data ID
+const SD_ID: &str = "ntomb@32473";
+
+/// `[forward]` section of config.toml
+#[derive(Debug, Clone, Default, Deserialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct ForwardConfig {
+    /// "syslog" or "journald"; forwarding is off when unset
+    pub target: Option<String>,
+    /// Least severe event forwarded (default info)
+    pub min_severity: Option<String>,
+    /// syslog destination: a Unix socket path or "udp://host:port"
+    pub address: Option<String>,
+}
+
+/// Where forwarded events go
+#[derive(Debug)]
+enum Sink {
+    Journald(UnixDatagram),
+    SyslogUnix(UnixDatagram, String),
+    SyslogUdp(UdpSocket),
+}
+
+/// Sends Grimoire events to the configured log daemon
+#[derive(Debug)]
+pub struct EventForwarder {
+    sink: Sink,
+    min_severity: EventSeverity,
+    hostname: String,
+}
+
+impl EventForwarder {
+    /// Forwarder for `config`; None when forwarding is not configured
+    pub fn from_config(config: &ForwardConfig) -> Result<Option<Self>, String> {
+        let Some(target) = config.target.as_deref() else {
+            return Ok(None);
+        };
+        let min_severity = match config.min_severity.as_deref() {
+            Some(name) => EventSeverity::from_name(name)
+                .ok_or_else(|| format!("unknown min_severity {:?}", name))?,
+            None => EventSeverity::Info,
+        };
+        let socket_error = |e: std::io::Error| format!("cannot open socket: {}", e);
+        let sink = match target.to_ascii_lowercase().as_str() {
+            "journald" | "journal" => {
+                if config.address.is_some() {
+                    return Err("address is only used with target = \"syslog\"".to_string());
+                }
+                Sink::Journald(UnixDatagram::unbound().map_err(socket_error)?)
+            }
+            "syslog" => match config.address.as_deref() {
+                Some(address) if address.starts_with("udp://") => {
+                    let socket = UdpSocket::bind("0.0.0.0:0").map_err(socket_error)?;
+                    socket
+                        .connect(&address["udp://".len()..])
+                        .map_err(|e| format!("cannot reach {}: {}", address, e))?;
+                    Sink::SyslogUdp(socket)
+                }
+                address => Sink::SyslogUnix(
+                    UnixDatagram::unbound().map_err(socket_error)?,
+                    address.unwrap_or(SYSLOG_SOCKET).to_string(),
+                ),
+            },
+            other => {
+                return Err(format!(
+                    "unknown target {:?} (expected \"syslog\" or \"journald\")",
+                    other
+                ))
+            }
+        };
+        Ok(Some(Self {
+            sink,
+            min_severity,
+            hostname: super::mermaid::local_host_name(),
+        }))
+    }
+
+    /// Send `event` if it is severe enough; failures are only traced
+    pub fn forward(&self, event: &GrimoireEvent) {
+        if event.severity < self.min_severity {
+            return;
+        }
+        let result = match &self.sink {
+            Sink::Journald(socket) => socket.send_to(&journald_message(event), JOURNALD_SOCKET),
+            Sink::SyslogUnix(socket, path) => {
+                socket.send_to(syslog_message(event, &self.hostname).as_bytes(), path)
+            }
+            Sink::SyslogUdp(socket) => {
+                socket.send(syslog_message(event, &self.hostname).as_bytes())
+            }
+        };
+        if let Err(e) = result {
+            tracing::debug!(error = %e, "Cannot forward Grimoire event");
+        }
+    }
+}
+
+/// syslog severity of an event (RFC 5424 section 6.2.1)
+fn syslog_severity(severity: EventSeverity) -> u8 {
+    match severity {
+        EventSeverity::Info => 6,
+        EventSeverity::Warning => 4,
+        EventSeverity::Critical => 2,
+    }
+}
+
+/// journald native protocol datagram: one FIELD=value per line
+fn journald_message(event: &GrimoireEvent) -> Vec<u8> {
+    let mut fields = vec![
+        ("MESSAGE", event.message.clone()),
+        ("PRIORITY", syslog_severity(event.severity).to_string()),
+        ("SYSLOG_IDENTIFIER", "ntomb".to_string()),
+        ("NTOMB_SEVERITY", event.severity.label().to_string()),
+    ];
+    if let Some(pid) = event.pid {
+        fields.push(("NTOMB_PID", pid.to_string()));
+    }
+    if let Some(remote) = &event.remote_addr {
+        fields.push(("NTOMB_REMOTE_ADDR", remote.clone()));
+    }
+    let mut out = Vec::new();
+    for (name, value) in fields {
+        // Newlines would need the protocol's binary framing
+        let value = value.replace('\n', " ");
+        out.extend_from_slice(format!("{}={}\n", name, value).as_bytes());
+    }
+    out
+}
+
+/// RFC 5424 message with the event's fields as structured data
+fn syslog_message(event: &GrimoireEvent, hostname: &str) -> String {
+    let priority = FACILITY_USER * 8 + syslog_severity(event.severity);
+    let mut params = format!("severity=\"{}\"", event.severity.label());
+    if let Some(pid) = event.pid {
+        params.push_str(&format!(" pid=\"{}\"", pid));
+    }
+    if let Some(remote) = &event.remote_addr {
+        params.push_str(&format!(" remote=\"{}\"", sd_escape(remote)));
+    }
+    format!(
+        "<{}>1 {} {} ntomb {} - [{} {}] {}",
+        priority,
+        rfc3339(event.timestamp),
+        hostname,
+        std::process::id(),
+        SD_ID,
+        params,
+        event.message.replace('\n', " ")
+    )
+}
+
+/// Escape a structured data parameter value (RFC 5424 section 6.3.3)
+fn sd_escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace(']', "\\]")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    fn event() -> GrimoireEvent {
+        let mut event = GrimoireEvent::new(EventSeverity::Critical, "Haunted endpoint 1.2.3.4")
+            .with_pid(Some(812))
+            .with_remote("1.2.3.4");
+        // 2025-10-31 23:59:59 UTC
+        event.timestamp = UNIX_EPOCH + Duration::from_secs(1_761_955_199);
+        event
+    }
+
+    #[test]
+    fn test_syslog_message() {
+        assert_eq!(
+            syslog_message(&event(), "web-1"),
+            format!(
+                "<10>1 2025-10-31T23:59:59Z web-1 ntomb {} - \
+                 [ntomb@32473 severity=\"CRIT\" pid=\"812\" remote=\"1.2.3.4\"] \
+                 Haunted endpoint 1.2.3.4",
+                std::process::id()
+            )
+        );
+        assert_eq!(sd_escape("a\"b]c\\"), "a\\\"b\\]c\\\\");
+    }
+
+    #[test]
+    fn test_journald_message() {
+        assert_eq!(
+            String::from_utf8(journald_message(&event())).unwrap(),
+            "MESSAGE=Haunted endpoint 1.2.3.4\nPRIORITY=2\nSYSLOG_IDENTIFIER=ntomb\n\
+             NTOMB_SEVERITY=CRIT\nNTOMB_PID=812\nNTOMB_REMOTE_ADDR=1.2.3.4\n"
+        );
+    }
+
+    #[test]
+    fn test_from_config() {
+        let config = |target: &str, address: Option<&str>| ForwardConfig {
+            target: Some(target.to_string()),
+            min_severity: Some("warning".to_string()),
+            address: address.map(str::to_string),
+        };
+        assert!(EventForwarder::from_config(&ForwardConfig::default())
+            .unwrap()
+            .is_none());
+        let forwarder = EventForwarder::from_config(&config("syslog", None))
+            .unwrap()
+            .unwrap();
+        assert_eq!(forwarder.min_severity, EventSeverity::Warning);
+        assert!(EventForwarder::from_config(&config("journald", Some("/dev/log"))).is_err());
+        assert!(EventForwarder::from_config(&config("splunk", None)).is_err());
+    }
+}
@@ -0,0 +1,186 @@
+// Endpoint grouping
+//
+// Folds many remote addresses into one Graveyard node per subnet
+// ("142.250.74.0/24"), AS organization ("GOOGLE"), owning process, country
+// or registrable domain ("amazonaws.com"). `g` cycles through the modes
+// whose data is available; Enter expands the selected connection's group
+// back into its individual endpoints.
+
+use crate::net::Connection;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::net::IpAddr;
+use std::sync::Arc;
+
+/// How public endpoints are folded in the Graveyard
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EndpointGrouping {
+    /// Every address is its own node (default)
+    #[default]
+    Off,
+    /// One node per /24 (IPv4) or /48 (IPv6) subnet
+    Subnet,
+    /// One node per AS organization (`[asn]`)
+    Asn,
+    /// One node per owning process
+    Process,
+    /// One node per registration country of the AS (`[asn]`)
+    Country,
+    /// One node per registrable domain (`[dns] reverse`)
+    Domain,
+}
+
+impl EndpointGrouping {
+    /// Next mode in `g` order
+    pub fn next(self) -> Self {
+        match self {
+            EndpointGrouping::Off => EndpointGrouping::Subnet,
+            EndpointGrouping::Subnet => EndpointGrouping::Asn,
+            EndpointGrouping::Asn => EndpointGrouping::Process,
+            EndpointGrouping::Process => EndpointGrouping::Country,
+            EndpointGrouping::Country => EndpointGrouping::Domain,
+            EndpointGrouping::Domain => EndpointGrouping::Off,
+        }
+    }
+
+    /// Title of the group summary ("Organizations", "Domains")
+    pub fn title(self) -> &'static str {
+        match self {
+            EndpointGrouping::Off => "Endpoints",
+            EndpointGrouping::Subnet => "Subnets",
+            EndpointGrouping::Asn => "Organizations",
+            EndpointGrouping::Process => "Processes",
+            EndpointGrouping::Country => "Countries",
+            EndpointGrouping::Domain => "Domains",
+        }
+    }
+
+    /// What a Graveyard node stands for, for the panel title
+    pub fn label(self) -> &'static str {
+        match self {
+            EndpointGrouping::Off => "remote IP",
+            EndpointGrouping::Subnet => "subnet",
+            EndpointGrouping::Asn => "AS organization",
+            EndpointGrouping::Process => "process",
+            EndpointGrouping::Country => "country",
+            EndpointGrouping::Domain => "domain",
+        }
+    }
+}
+
+/// "142.250.74.0/24" for an IPv4 address, "2001:db8:1::/48" for IPv6;
+/// None for anything else
+pub fn subnet(addr: &str) -> Option<String> {
+    match addr.parse::<IpAddr>().ok()? {
+        IpAddr::V4(ip) => {
+            let [a, b, c, _] = ip.octets();
+            Some(format!("{}.{}.{}.0/24", a, b, c))
+        }
+        IpAddr::V6(ip) => {
+            let [a, b, c, ..] = ip.segments();
+            Some(format!("{:x}:{:x}:{:x}::/48", a, b, c))
+        }
+    }
+}
+
+/// Connections folded under one group name
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EndpointGroup {
+    pub name: Arc<str>,
+    /// Extra identifiers seen in the group, sorted ("AS15169")
+    pub tags: Vec<String>,
+    pub connections: usize,
+    /// Distinct remote addresses
+    pub endpoints: usize,
+}
+
+/// Per-group tally: (tags, connections, remote addresses)
+type Tally = (BTreeSet<String>, usize, HashSet<Arc<str>>);
+
+/// Group `conns` by the name `key` gives their remote end, busiest first;
+/// connections without a group are left out
+pub fn aggregate<'a>(
+    conns: impl IntoIterator<Item = &'a Connection>,
+    key: impl Fn(&Connection) -> Option<(Arc<str>, Option<String>)>,
+) -> Vec<EndpointGroup> {
+    let mut groups: HashMap<Arc<str>, Tally> = HashMap::new();
+    for conn in conns {
+        let Some((name, tag)) = key(conn) else {
+            continue;
+        };
+        let (tags, connections, endpoints) = groups.entry(name).or_default();
+        tags.extend(tag);
+        *connections += 1;
+        endpoints.insert(conn.remote_addr.clone());
+    }
+
+    let mut groups: Vec<EndpointGroup> = groups
+        .into_iter()
+        .map(|(name, (tags, connections, endpoints))| EndpointGroup {
+            name,
+            tags: tags.into_iter().collect(),
+            connections,
+            endpoints: endpoints.len(),
+        })
+        .collect();
+    groups.sort_by(|a, b| {
+        b.connections
+            .cmp(&a.connections)
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    groups
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_aggregate() {
+        let conn = |remote: &str| Connection {
+            remote_addr: remote.into(),
+            ..Default::default()
+        };
+        let conns = [
+            conn("8.8.8.8"),
+            conn("8.8.8.8"),
+            conn("34.80.1.1"),
+            conn("1.0.0.1"),
+            conn("10.0.0.1"),
+        ];
+        let groups = aggregate(&conns, |c| match &*c.remote_addr {
+            "8.8.8.8" => Some(("GOOGLE".into(), Some("AS15169".to_string()))),
+            "34.80.1.1" => Some(("GOOGLE".into(), Some("AS396982".to_string()))),
+            "1.0.0.1" => Some(("CLOUDFLARENET".into(), None)),
+            _ => None,
+        });
+        assert_eq!(groups.len(), 2);
+        assert_eq!(&*groups[0].name, "GOOGLE");
+        assert_eq!(groups[0].tags, vec!["AS15169", "AS396982"]);
+        assert_eq!(groups[0].connections, 3);
+        assert_eq!(groups[0].endpoints, 2);
+        assert_eq!(&*groups[1].name, "CLOUDFLARENET");
+        assert!(groups[1].tags.is_empty());
+    }
+
+    #[test]
+    fn test_grouping_cycle() {
+        let mut mode = EndpointGrouping::default();
+        mode = mode.next();
+        assert_eq!(mode, EndpointGrouping::Subnet);
+        mode = mode.next();
+        assert_eq!(mode, EndpointGrouping::Asn);
+        mode = mode.next().next().next();
+        assert_eq!(mode.title(), "Domains");
+        assert_eq!(mode.next(), EndpointGrouping::Off);
+    }
+
+    #[test]
+    fn test_subnet() {
+        assert_eq!(subnet("142.250.74.36").as_deref(), Some("142.250.74.0/24"));
+        assert_eq!(
+            subnet("2001:db8:1:2::5").as_deref(),
+            Some("2001:db8:1::/48")
+        );
+        assert_eq!(subnet("not-an-ip"), None);
+    }
+}
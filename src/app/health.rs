@@ -0,0 +1,209 @@
+// Collection backend health
+//
+// Tracks which backend produced the last snapshot, how long the collection
+// took, how many sockets could not be attributed to a process, and a short
+// history of diagnostics (collection errors, permission problems, backend
+// warnings) for the backend popup and the diagnostics panel.
+
+use super::events::time_of_day_label;
+use crate::net::Connection;
+use std::collections::VecDeque;
+use std::time::{Duration, SystemTime};
+
+/// Maximum number of backend errors kept for the debug popup
+pub const BACKEND_ERROR_CAPACITY: usize = 20;
+
+/// What kind of problem a diagnostic reports
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiagnosticKind {
+    /// Collection or process attribution failed
+    Error,
+    /// Something could not be read for lack of privileges
+    Permission,
+    /// A backend failed but another one took over
+    Warning,
+}
+
+impl DiagnosticKind {
+    /// Kind for an I/O error
+    pub fn of(error: &std::io::Error) -> Self {
+        if error.kind() == std::io::ErrorKind::PermissionDenied {
+            DiagnosticKind::Permission
+        } else {
+            DiagnosticKind::Error
+        }
+    }
+
+    /// Short label for lists
+    pub fn label(self) -> &'static str {
+        match self {
+            DiagnosticKind::Error => "ERROR",
+            DiagnosticKind::Permission => "PERM",
+            DiagnosticKind::Warning => "WARN",
+        }
+    }
+}
+
+/// A backend error observed during a refresh
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BackendError {
+    /// When the error occurred
+    pub timestamp: SystemTime,
+    pub kind: DiagnosticKind,
+    /// Backend (or enrichment step) that failed
+    pub source: String,
+    /// Error description
+    pub message: String,
+}
+
+impl BackendError {
+    /// Format the timestamp as "HH:MM:SS" (UTC)
+    pub fn time_label(&self) -> String {
+        time_of_day_label(self.timestamp)
+    }
+}
+
+/// Health of the connection collection pipeline
+#[derive(Debug, Clone, Default)]
+pub struct BackendHealth {
+    /// Backend that produced the last successful snapshot
+    pub backend: &'static str,
+    /// Duration of the last collection (including process attribution)
+    pub last_duration: Option<Duration>,
+    /// Sockets in the last snapshot without an owning PID
+    pub unattributed: usize,
+    /// Total sockets in the last snapshot
+    pub total: usize,
+    /// Recent errors, oldest first
+    errors: VecDeque<BackendError>,
+    /// Errors and permission problems recorded since the diagnostics
+    /// panel was last open
+    unseen: usize,
+}
+
+impl BackendHealth {
+    /// Record a successful collection
+    pub fn record_snapshot(
+        &mut self,
+        backend: &'static str,
+        duration: Duration,
+        connections: &[Connection],
+    ) {
+        let unattributed = connections.iter().filter(|c| c.pid.is_none()).count();
+        // Reported when sockets start going unattributed, not on every refresh
+        if unattributed > 0 && self.unattributed == 0 {
+            self.record(
+                DiagnosticKind::Permission,
+                backend,
+                format!(
+                    "{} of {} sockets have no owning process (other users' sockets need root)",
+                    unattributed,
+                    connections.len()
+                ),
+            );
+        }
+        self.backend = backend;
+        self.last_duration = Some(duration);
+        self.total = connections.len();
+        self.unattributed = unattributed;
+    }
+
+    /// Record a diagnostic of any kind, evicting the oldest when full
+    pub fn record(
+        &mut self,
+        kind: DiagnosticKind,
+        source: impl Into<String>,
+        message: impl Into<String>,
+    ) {
+        if self.errors.len() >= BACKEND_ERROR_CAPACITY {
+            self.errors.pop_front();
+        }
+        self.errors.push_back(BackendError {
+            timestamp: SystemTime::now(),
+            kind,
+            source: source.into(),
+            message: message.into(),
+        });
+        if kind != DiagnosticKind::Warning {
+            self.unseen += 1;
+        }
+    }
+
+    /// Errors and permission problems nobody has looked at yet
+    pub fn unseen_errors(&self) -> usize {
+        self.unseen
+    }
+
+    /// The diagnostics panel is showing everything recorded so far
+    pub fn mark_seen(&mut self) {
+        self.unseen = 0;
+    }
+
+    /// Iterate recent errors from oldest to newest
+    pub fn errors(&self) -> impl DoubleEndedIterator<Item = &BackendError> {
+        self.errors.iter()
+    }
+
+    /// Number of retained errors
+    pub fn error_count(&self) -> usize {
+        self.errors.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_snapshot_counts_unattributed() {
+        let conns = vec![
+            Connection {
+                pid: Some(1),
+                ..Default::default()
+            },
+            Connection::default(),
+            Connection::default(),
+        ];
+        let mut health = BackendHealth::default();
+        health.record_snapshot("netstat2", Duration::from_millis(12), &conns);
+
+        assert_eq!(health.backend, "netstat2");
+        assert_eq!(health.total, 3);
+        assert_eq!(health.unattributed, 2);
+    }
+
+    #[test]
+    fn test_error_history_is_bounded() {
+        let mut health = BackendHealth::default();
+        for i in 0..BACKEND_ERROR_CAPACITY + 5 {
+            health.record(DiagnosticKind::Error, "ss", format!("failure {}", i));
+        }
+        assert_eq!(health.error_count(), BACKEND_ERROR_CAPACITY);
+        assert_eq!(health.errors().next().unwrap().message, "failure 5");
+    }
+
+    #[test]
+    fn test_unseen_diagnostics() {
+        let mut health = BackendHealth::default();
+        health.record(DiagnosticKind::Warning, "ss", "ss not found, using lsof");
+        assert_eq!(health.unseen_errors(), 0);
+
+        // Unattributed sockets are reported once, when they appear
+        let conns = vec![Connection::default()];
+        health.record_snapshot("netstat2", Duration::from_millis(12), &conns);
+        health.record_snapshot("netstat2", Duration::from_millis(12), &conns);
+        assert_eq!(health.unseen_errors(), 1);
+        assert_eq!(
+            health.errors().last().unwrap().kind,
+            DiagnosticKind::Permission
+        );
+
+        health.record(DiagnosticKind::Error, "procfs", "boom");
+        assert_eq!(health.unseen_errors(), 2);
+        health.mark_seen();
+        assert_eq!(health.unseen_errors(), 0);
+
+        let denied = std::io::Error::from(std::io::ErrorKind::PermissionDenied);
+        assert_eq!(DiagnosticKind::of(&denied), DiagnosticKind::Permission);
+    }
+}
@@ -0,0 +1,116 @@
+// Heavy-talker threshold
+//
+// Which Graveyard endpoints wear the crown badge: the N busiest (ties
+// included) or every endpoint with at least N connections. "Top 5"
+// crowns nearly everything on a busy host, so the rule comes from
+// `[graveyard] heavy_talkers` in config.toml and can be changed live in
+// the `,` settings popup:
+//
+// ```toml
+// [graveyard]
+// heavy_talkers = { top = 3 }   # or { min = 40 }
+// ```
+
+use serde::Deserialize;
+
+/// How heavy talkers are picked among the Graveyard's endpoints
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase", deny_unknown_fields)]
+pub enum HeavyTalkerThreshold {
+    /// The N endpoints with the most connections
+    Top(usize),
+    /// Endpoints with at least N connections
+    Min(usize),
+}
+
+impl Default for HeavyTalkerThreshold {
+    fn default() -> Self {
+        HeavyTalkerThreshold::Top(5)
+    }
+}
+
+impl HeavyTalkerThreshold {
+    /// The N of either rule
+    pub fn count(self) -> usize {
+        match self {
+            HeavyTalkerThreshold::Top(n) | HeavyTalkerThreshold::Min(n) => n,
+        }
+    }
+
+    /// The other rule with the same N
+    pub fn toggled(self) -> Self {
+        match self {
+            HeavyTalkerThreshold::Top(n) => HeavyTalkerThreshold::Min(n),
+            HeavyTalkerThreshold::Min(n) => HeavyTalkerThreshold::Top(n),
+        }
+    }
+
+    /// The same rule with N moved by `delta`, never below 1
+    pub fn adjusted(self, delta: isize) -> Self {
+        let n = self.count().saturating_add_signed(delta).max(1);
+        match self {
+            HeavyTalkerThreshold::Top(_) => HeavyTalkerThreshold::Top(n),
+            HeavyTalkerThreshold::Min(_) => HeavyTalkerThreshold::Min(n),
+        }
+    }
+
+    /// "top 5" / "≥ 40 connections"
+    pub fn label(self) -> String {
+        match self {
+            HeavyTalkerThreshold::Top(n) => format!("top {}", n),
+            HeavyTalkerThreshold::Min(n) => format!("≥ {} connections", n),
+        }
+    }
+}
+
+/// Rows of the settings popup
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SettingsRow {
+    HeavyTalkerRule,
+    HeavyTalkerCount,
+    /// Profile preset, applied as soon as it is picked
+    Profile,
+}
+
+impl SettingsRow {
+    pub const ALL: [SettingsRow; 3] = [
+        SettingsRow::HeavyTalkerRule,
+        SettingsRow::HeavyTalkerCount,
+        SettingsRow::Profile,
+    ];
+}
+
+/// The `,` popup: live display settings, changed with ←/→
+#[derive(Debug, Clone, Default)]
+pub struct SettingsPopup {
+    /// Highlighted row of `SettingsRow::ALL`
+    pub selected: usize,
+}
+
+impl SettingsPopup {
+    pub fn select_previous(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+
+    pub fn select_next(&mut self) {
+        self.selected = (self.selected + 1).min(SettingsRow::ALL.len() - 1);
+    }
+
+    pub fn current(&self) -> SettingsRow {
+        SettingsRow::ALL[self.selected]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_adjust_threshold() {
+        let top = HeavyTalkerThreshold::default();
+        assert_eq!(top.label(), "top 5");
+        assert_eq!(top.adjusted(-10), HeavyTalkerThreshold::Top(1));
+        assert_eq!(top.adjusted(3).toggled(), HeavyTalkerThreshold::Min(8));
+        assert_eq!(HeavyTalkerThreshold::Min(40).label(), "≥ 40 connections");
+    }
+}
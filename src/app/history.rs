@@ -0,0 +1,405 @@
+// Event history on disk
+//
+// Grimoire events can be appended to an NDJSON file so alert history
+// survives restarts, and queried offline with `ntomb events --since 2h`:
+//
+// ```toml
+// [history]
+// file = "/var/lib/ntomb/events.ndjson"
+// retention = "7d"     # s, m, h, d or w (default 7d)
+// max_file_mb = 10     # rotate the active file at this size (default 10)
+// ```
+//
+// Each line is one event in the format of the `/events` API:
+//
+// ```json
+// {"ts":1760000000123,"severity":"WARN","message":"SYN storm on :443","pid":812,"remote_addr":null}
+// ```
+//
+// The active file is rotated (events.ndjson → events.ndjson.1 → ...) when
+// it reaches the size limit or holds a day of events, and rotated files
+// last written before the retention period are deleted. Writing is best
+// effort: a full disk never disturbs the TUI.
+
+use super::events::{EventSeverity, GrimoireEvent};
+use super::stream::{json_number, json_option, json_string};
+use serde::Deserialize;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Events older than this are deleted unless `retention` says otherwise
+const DEFAULT_RETENTION: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// Size at which the active file is rotated unless `max_file_mb` says otherwise
+const DEFAULT_MAX_FILE_MB: u64 = 10;
+
+/// Age of the oldest event in the active file at which it is rotated
+const ROTATE_AFTER: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// `[history]` section of config.toml
+#[derive(Debug, Clone, Default, Deserialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct HistoryConfig {
+    /// NDJSON file events are appended to; history is off when unset
+    pub file: Option<PathBuf>,
+    /// How long rotated files are kept: "36h", "7d", "2w" (default 7d)
+    pub retention: Option<String>,
+    /// Rotate the active file at this many MiB (default 10)
+    pub max_file_mb: Option<u64>,
+}
+
+impl HistoryConfig {
+    /// The configured retention period
+    pub fn retention(&self) -> Result<Duration, String> {
+        match self.retention.as_deref() {
+            Some(text) => parse_duration(text).map_err(|e| format!("retention: {}", e)),
+            None => Ok(DEFAULT_RETENTION),
+        }
+    }
+}
+
+/// Appends events to the history file, rotating and pruning it
+#[derive(Debug)]
+pub struct EventHistory {
+    path: PathBuf,
+    file: File,
+    /// Bytes in the active file
+    size: u64,
+    /// Time of the oldest event in the active file
+    started: Option<SystemTime>,
+    max_bytes: u64,
+    retention: Duration,
+}
+
+impl EventHistory {
+    /// History writer for `config`; None when no file is configured
+    pub fn from_config(config: &HistoryConfig) -> Result<Option<Self>, String> {
+        let Some(ref path) = config.file else {
+            return Ok(None);
+        };
+        let retention = config.retention()?;
+        let max_bytes = match config.max_file_mb.unwrap_or(DEFAULT_MAX_FILE_MB) {
+            0 => return Err("max_file_mb must be at least 1".to_string()),
+            mb => mb * 1024 * 1024,
+        };
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            fs::create_dir_all(dir)
+                .map_err(|e| format!("cannot create {}: {}", dir.display(), e))?;
+        }
+        let history = Self::open(path.clone(), max_bytes, retention)
+            .map_err(|e| format!("cannot open {}: {}", path.display(), e))?;
+        history.prune(SystemTime::now());
+        Ok(Some(history))
+    }
+
+    fn open(path: PathBuf, max_bytes: u64, retention: Duration) -> io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let size = file.metadata()?.len();
+        let started = read_lines(&path)?.first().map(|event| event.timestamp);
+        Ok(Self {
+            path,
+            file,
+            size,
+            started,
+            max_bytes,
+            retention,
+        })
+    }
+
+    /// Append `event`; failures are only traced
+    pub fn record(&mut self, event: &GrimoireEvent) {
+        let line = format!("{}\n", event_json(event));
+        if let Err(e) = self.write_line(&line, event.timestamp) {
+            tracing::debug!(path = %self.path.display(), error = %e, "Cannot write event history");
+        }
+    }
+
+    /// Append `line`, rotating first when the file is full or a day old
+    fn write_line(&mut self, line: &str, now: SystemTime) -> io::Result<()> {
+        let full = self.size > 0 && self.size + line.len() as u64 > self.max_bytes;
+        let old = self
+            .started
+            .and_then(|started| now.duration_since(started).ok())
+            .is_some_and(|age| age >= ROTATE_AFTER);
+        if full || old {
+            self.rotate(now)?;
+        }
+        self.file.write_all(line.as_bytes())?;
+        self.size += line.len() as u64;
+        self.started.get_or_insert(now);
+        Ok(())
+    }
+
+    /// events.ndjson.N → .N+1, ..., events.ndjson → .1, then start an
+    /// empty file and delete rotated files past the retention period
+    fn rotate(&mut self, now: SystemTime) -> io::Result<()> {
+        for n in (1..=rotated_count(&self.path)).rev() {
+            fs::rename(rotated(&self.path, n), rotated(&self.path, n + 1))?;
+        }
+        fs::rename(&self.path, rotated(&self.path, 1))?;
+        *self = Self::open(self.path.clone(), self.max_bytes, self.retention)?;
+        self.prune(now);
+        Ok(())
+    }
+
+    /// Delete rotated files last written before the retention period,
+    /// oldest (highest number) first
+    fn prune(&self, now: SystemTime) {
+        let Some(cutoff) = now.checked_sub(self.retention) else {
+            return;
+        };
+        for n in (1..=rotated_count(&self.path)).rev() {
+            let path = rotated(&self.path, n);
+            let expired = fs::metadata(&path)
+                .and_then(|meta| meta.modified())
+                .is_ok_and(|modified| modified < cutoff);
+            if !expired {
+                break;
+            }
+            if let Err(e) = fs::remove_file(&path) {
+                tracing::debug!(path = %path.display(), error = %e, "Cannot prune event history");
+                break;
+            }
+        }
+    }
+}
+
+/// Path of the `n`th rotated file
+fn rotated(path: &Path, n: usize) -> PathBuf {
+    PathBuf::from(format!("{}.{}", path.display(), n))
+}
+
+/// Number of rotated files next to `path` (they are numbered without gaps)
+fn rotated_count(path: &Path) -> usize {
+    (1..).take_while(|&n| rotated(path, n).exists()).count()
+}
+
+/// Events of the history at `path` (rotated files included) recorded at or
+/// after `since`, oldest first; unreadable lines are skipped
+pub fn read_events(path: &Path, since: Option<SystemTime>) -> io::Result<Vec<GrimoireEvent>> {
+    let rotated_files = rotated_count(path);
+    let mut events = Vec::new();
+    for n in (1..=rotated_files).rev() {
+        events.extend(read_lines(&rotated(path, n))?);
+    }
+    match read_lines(path) {
+        Ok(active) => events.extend(active),
+        // The active file is briefly missing while it is being rotated
+        Err(e) if e.kind() == io::ErrorKind::NotFound && rotated_files > 0 => {}
+        Err(e) => return Err(e),
+    }
+    if let Some(since) = since {
+        events.retain(|event| event.timestamp >= since);
+    }
+    Ok(events)
+}
+
+/// Events of one file; lines that are not valid UTF-8 (such as one cut
+/// short by a crash) are decoded lossily and skipped if they do not parse
+fn read_lines(path: &Path) -> io::Result<Vec<GrimoireEvent>> {
+    let mut reader = BufReader::new(File::open(path)?);
+    let mut events = Vec::new();
+    let mut line = Vec::new();
+    while reader.read_until(b'\n', &mut line)? > 0 {
+        if let Some(event) = parse_event(&String::from_utf8_lossy(&line)) {
+            events.push(event);
+        }
+        line.clear();
+    }
+    Ok(events)
+}
+
+/// One event as a JSON object (the `/events` API format)
+pub fn event_json(event: &GrimoireEvent) -> String {
+    let millis = event
+        .timestamp
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or(0);
+    format!(
+        "{{\"ts\":{},\"severity\":\"{}\",\"message\":{},\"pid\":{},\"remote_addr\":{}}}",
+        millis,
+        event.severity.label(),
+        json_string(&event.message),
+        json_number(event.pid),
+        json_option(event.remote_addr.as_deref())
+    )
+}
+
+/// A line written by `event_json`
+#[derive(Debug, Deserialize)]
+struct StoredEvent {
+    ts: u64,
+    severity: String,
+    message: String,
+    pid: Option<i32>,
+    remote_addr: Option<String>,
+}
+
+/// Read back a line written by `event_json`
+pub fn parse_event(line: &str) -> Option<GrimoireEvent> {
+    let stored: StoredEvent = serde_json::from_str(line).ok()?;
+    let mut event = GrimoireEvent::new(EventSeverity::from_name(&stored.severity)?, stored.message);
+    event.timestamp = UNIX_EPOCH + Duration::from_millis(stored.ts);
+    event.pid = stored.pid;
+    event.remote_addr = stored.remote_addr;
+    Some(event)
+}
+
+/// Parse "90s", "30m", "2h", "7d" or "2w"
+pub fn parse_duration(text: &str) -> Result<Duration, String> {
+    let text = text.trim();
+    let unit_at = text
+        .find(|c: char| !c.is_ascii_digit())
+        .ok_or_else(|| format!("{:?} needs a unit: s, m, h, d or w", text))?;
+    let (number, unit) = text.split_at(unit_at);
+    let number: u64 = number
+        .parse()
+        .map_err(|_| format!("{:?} does not start with a number", text))?;
+    let seconds = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 24 * 60 * 60,
+        "w" => 7 * 24 * 60 * 60,
+        _ => {
+            return Err(format!(
+                "unknown unit {:?} (expected s, m, h, d or w)",
+                unit
+            ))
+        }
+    };
+    Ok(Duration::from_secs(number.saturating_mul(seconds)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("ntomb-history-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn event_at(secs: u64, message: &str) -> GrimoireEvent {
+        let mut event = GrimoireEvent::new(EventSeverity::Warning, message)
+            .with_pid(Some(812))
+            .with_remote("203.0.113.9");
+        event.timestamp = UNIX_EPOCH + Duration::from_secs(secs);
+        event
+    }
+
+    #[test]
+    fn test_event_json_round_trip() {
+        let event = event_at(1_760_000_000, "quote \" and\nnewline \u{1}");
+        let line = event_json(&event);
+        assert!(line.starts_with("{\"ts\":1760000000000,\"severity\":\"WARN\","));
+        assert_eq!(parse_event(&line), Some(event));
+
+        let mut bare = GrimoireEvent::new(EventSeverity::Info, "bare");
+        bare.timestamp = UNIX_EPOCH;
+        assert_eq!(parse_event(&event_json(&bare)), Some(bare));
+
+        assert_eq!(parse_event("{\"ts\":1}"), None);
+        assert_eq!(parse_event("not json"), None);
+        assert_eq!(
+            parse_event("{\"ts\":1,\"severity\":\"INFO\",\"message\":\"x\"} trailing"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("2h"), Ok(Duration::from_secs(7200)));
+        assert_eq!(parse_duration(" 7d"), Ok(Duration::from_secs(7 * 86400)));
+        assert_eq!(parse_duration("90s"), Ok(Duration::from_secs(90)));
+        assert!(parse_duration("2").is_err());
+        assert!(parse_duration("h").is_err());
+        assert!(parse_duration("2y").is_err());
+    }
+
+    #[test]
+    fn test_rotation_and_query() {
+        let dir = temp_dir("rotate");
+        let path = dir.join("events.ndjson");
+        let mut history = EventHistory::open(path.clone(), 1024 * 1024, DEFAULT_RETENTION).unwrap();
+
+        // A day after the first event the file is rotated
+        history.record(&event_at(1_000, "one"));
+        history.record(&event_at(2_000, "two"));
+        history.record(&event_at(1_000 + 86_400, "three"));
+        assert!(rotated(&path, 1).exists());
+        assert!(!rotated(&path, 2).exists());
+
+        let all = read_events(&path, None).unwrap();
+        let messages: Vec<&str> = all.iter().map(|e| e.message.as_str()).collect();
+        assert_eq!(messages, ["one", "two", "three"]);
+
+        let since = UNIX_EPOCH + Duration::from_secs(1_500);
+        let recent = read_events(&path, Some(since)).unwrap();
+        assert_eq!(recent.len(), 2);
+
+        // Reopening picks up the age of the active file
+        let reopened = EventHistory::open(path.clone(), 1024 * 1024, DEFAULT_RETENTION).unwrap();
+        assert_eq!(
+            reopened.started,
+            Some(UNIX_EPOCH + Duration::from_secs(1_000 + 86_400))
+        );
+
+        assert!(read_events(&dir.join("missing.ndjson"), None).is_err());
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_truncated_multibyte_line() {
+        let dir = temp_dir("truncated");
+        let path = dir.join("events.ndjson");
+        let cut = event_json(&event_at(2_000, "café"));
+        let cut = &cut.as_bytes()[..cut.find('é').unwrap() + 1];
+        let mut bytes = format!("{}\n", event_json(&event_at(1_000, "before"))).into_bytes();
+        bytes.extend_from_slice(cut);
+        bytes
+            .extend_from_slice(format!("\n{}\n", event_json(&event_at(3_000, "after"))).as_bytes());
+        fs::write(&path, bytes).unwrap();
+
+        // The broken line is skipped instead of failing the whole file
+        let history = EventHistory::open(path.clone(), 1024 * 1024, DEFAULT_RETENTION).unwrap();
+        assert_eq!(
+            history.started,
+            Some(UNIX_EPOCH + Duration::from_secs(1_000))
+        );
+        let messages: Vec<String> = read_events(&path, None)
+            .unwrap()
+            .into_iter()
+            .map(|e| e.message)
+            .collect();
+        assert_eq!(messages, ["before", "after"]);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_size_rotation_and_pruning() {
+        let dir = temp_dir("prune");
+        let path = dir.join("events.ndjson");
+        let mut history = EventHistory::open(path.clone(), 100, Duration::from_secs(60)).unwrap();
+        let now = SystemTime::now();
+        for n in 0..3 {
+            let mut event = GrimoireEvent::new(EventSeverity::Info, format!("{:060}", n));
+            event.timestamp = now;
+            history.record(&event);
+        }
+        // Every line overflows the 100 byte limit together with the next
+        assert_eq!(rotated_count(&path), 2);
+
+        // Rotated files written before the retention period are deleted
+        history.prune(now + Duration::from_secs(3600));
+        assert_eq!(rotated_count(&path), 0);
+        assert!(path.exists());
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
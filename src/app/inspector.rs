@@ -0,0 +1,292 @@
+// Soul Inspector tabs
+//
+// `i` cycles the inspector between Overview (target basics, activity and
+// the first few sockets), Sockets (every socket of the target), Forensics
+// (executable, user, cgroup and hash of the target's process) and History
+// (the target's connections opening, closing and changing state across the
+// recorded refreshes).
+//
+// The socket list of the Overview and Sockets tabs scrolls: `Tab` focuses
+// it (after the event feed), the arrow and page keys move its selection and
+// the Graveyard highlights the selected socket's endpoint meanwhile.
+
+use super::snapshot::{connection_key, ConnectionKey, SnapshotHistory};
+use crate::net::{Connection, ConnectionState};
+use ratatui::widgets::ListState;
+use std::collections::HashMap;
+use std::time::SystemTime;
+
+/// Which Soul Inspector tab is shown
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum InspectorTab {
+    #[default]
+    Overview,
+    Sockets,
+    Forensics,
+    History,
+}
+
+impl InspectorTab {
+    /// Every tab in `i` order
+    pub const ALL: [InspectorTab; 4] = [
+        InspectorTab::Overview,
+        InspectorTab::Sockets,
+        InspectorTab::Forensics,
+        InspectorTab::History,
+    ];
+
+    /// Next tab in `i` order
+    pub fn next(self) -> Self {
+        match self {
+            InspectorTab::Overview => InspectorTab::Sockets,
+            InspectorTab::Sockets => InspectorTab::Forensics,
+            InspectorTab::Forensics => InspectorTab::History,
+            InspectorTab::History => InspectorTab::Overview,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            InspectorTab::Overview => "Overview",
+            InspectorTab::Sockets => "Sockets",
+            InspectorTab::Forensics => "Forensics",
+            InspectorTab::History => "History",
+        }
+    }
+
+    /// Whether the tab shows the (focusable) socket list
+    pub fn lists_sockets(self) -> bool {
+        matches!(self, InspectorTab::Overview | InspectorTab::Sockets)
+    }
+}
+
+/// Focus and selection of the inspector's socket list
+#[derive(Debug, Clone, Default)]
+pub struct SocketList {
+    /// Whether navigation keys move through the inspector's sockets
+    /// instead of the connection list
+    pub focused: bool,
+    /// Selected socket, an index into `AppState::inspected_connections`
+    pub state: ListState,
+    /// Socket rows visible at the last render
+    pub rows: usize,
+}
+
+impl SocketList {
+    /// Selected index among `len` sockets (the last one when the list has
+    /// shrunk below the selection)
+    pub fn selected(&self, len: usize) -> Option<usize> {
+        let last = len.checked_sub(1)?;
+        self.state.selected().map(|i| i.min(last))
+    }
+
+    /// Move the selection by `delta` sockets, stopping at either end;
+    /// without a selection, moving up starts at the last socket
+    pub fn move_by(&mut self, delta: isize, len: usize) {
+        let Some(last) = len.checked_sub(1) else {
+            self.state.select(None);
+            return;
+        };
+        let index = match self.selected(len) {
+            None if delta < 0 => last,
+            None => 0,
+            Some(index) => index.saturating_add_signed(delta).min(last),
+        };
+        self.state.select(Some(index));
+    }
+
+    /// Select the first (or, with `last`, the final) socket
+    pub fn select_edge(&mut self, last: bool, len: usize) {
+        if len > 0 {
+            self.state.select(Some(if last { len - 1 } else { 0 }));
+        }
+    }
+
+    /// Forget the selection (the inspected target changed)
+    pub fn reset(&mut self) {
+        self.state = ListState::default();
+    }
+}
+
+/// What happened to a connection between two refreshes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimelineChange {
+    Opened,
+    Closed,
+    /// Previous state
+    StateChange(ConnectionState),
+}
+
+/// One History tab row
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TimelineEntry {
+    /// Capture time of the refresh that saw the change
+    pub at: SystemTime,
+    pub change: TimelineChange,
+    /// The connection after the change (before it, for Closed)
+    pub conn: Connection,
+}
+
+/// Changes to the connections matching `include` across `history`, newest
+/// first, at most `limit` of them
+pub fn timeline(
+    history: &SnapshotHistory,
+    include: impl Fn(&Connection) -> bool,
+    limit: usize,
+) -> Vec<TimelineEntry> {
+    let states = |index: usize| -> HashMap<ConnectionKey, &Connection> {
+        history
+            .get(index)
+            .map(|snapshot| {
+                snapshot
+                    .connections
+                    .iter()
+                    .filter(|c| include(c))
+                    .map(|c| (connection_key(c), c))
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+
+    let mut entries = Vec::new();
+    let Some(mut after) = history.len().checked_sub(1).map(states) else {
+        return entries;
+    };
+    for index in (1..history.len()).rev() {
+        if entries.len() >= limit {
+            break;
+        }
+        let Some(at) = history.get(index).map(|s| s.taken_at) else {
+            break;
+        };
+        let before = states(index - 1);
+        let mut changes: Vec<TimelineEntry> = Vec::new();
+        for (key, conn) in &after {
+            let change = match before.get(key) {
+                None => TimelineChange::Opened,
+                Some(prev) if prev.state != conn.state => TimelineChange::StateChange(prev.state),
+                Some(_) => continue,
+            };
+            changes.push(TimelineEntry {
+                at,
+                change,
+                conn: (*conn).clone(),
+            });
+        }
+        for (key, conn) in &before {
+            if !after.contains_key(key) {
+                changes.push(TimelineEntry {
+                    at,
+                    change: TimelineChange::Closed,
+                    conn: (*conn).clone(),
+                });
+            }
+        }
+        // Stable order within one refresh
+        changes.sort_by_key(|entry| connection_key(&entry.conn));
+        entries.extend(changes);
+        after = before;
+    }
+    entries.truncate(limit);
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::snapshot::Snapshot;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    fn conn(remote: &str, state: ConnectionState, pid: i32) -> Connection {
+        Connection {
+            local_addr: "10.0.0.5".into(),
+            local_port: 40000,
+            remote_addr: remote.into(),
+            remote_port: 443,
+            state,
+            pid: Some(pid),
+            ..Default::default()
+        }
+    }
+
+    fn at(secs: u64) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(secs)
+    }
+
+    #[test]
+    fn test_tab_cycle() {
+        let mut tab = InspectorTab::default();
+        for expected in InspectorTab::ALL.iter().skip(1) {
+            tab = tab.next();
+            assert_eq!(tab, *expected);
+        }
+        assert_eq!(tab.next(), InspectorTab::Overview);
+    }
+
+    #[test]
+    fn test_socket_list_selection() {
+        let mut list = SocketList::default();
+        list.move_by(-1, 5);
+        assert_eq!(list.selected(5), Some(4));
+        list.move_by(-10, 5);
+        assert_eq!(list.selected(5), Some(0));
+        list.move_by(3, 5);
+        assert_eq!(list.selected(5), Some(3));
+
+        // The list shrank under the selection
+        assert_eq!(list.selected(2), Some(1));
+        list.move_by(-1, 2);
+        assert_eq!(list.selected(2), Some(0));
+
+        list.select_edge(true, 5);
+        assert_eq!(list.selected(5), Some(4));
+        list.move_by(1, 0);
+        assert_eq!(list.selected(0), None);
+    }
+
+    #[test]
+    fn test_timeline_newest_first() {
+        use ConnectionState::*;
+        let mut history = SnapshotHistory::new(10);
+        let snapshots = [
+            vec![
+                conn("10.1.0.1", SynSent, 7),
+                conn("10.9.9.9", Established, 8),
+            ],
+            vec![conn("10.1.0.1", Established, 7)],
+            vec![
+                conn("10.1.0.1", TimeWait, 7),
+                conn("10.1.0.2", Established, 7),
+            ],
+        ];
+        for (i, connections) in snapshots.into_iter().enumerate() {
+            history.push(Snapshot {
+                taken_at: at(10 * i as u64),
+                connections,
+            });
+        }
+
+        // PID 8's connection is not followed
+        let entries = timeline(&history, |c| c.pid == Some(7), 10);
+        let summary: Vec<(u64, &str, TimelineChange)> = entries
+            .iter()
+            .map(|e| {
+                let secs = e.at.duration_since(UNIX_EPOCH).unwrap().as_secs();
+                (secs, &*e.conn.remote_addr, e.change)
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                (20, "10.1.0.1", TimelineChange::StateChange(Established)),
+                (20, "10.1.0.2", TimelineChange::Opened),
+                (10, "10.1.0.1", TimelineChange::StateChange(SynSent)),
+            ]
+        );
+        assert_eq!(entries[0].conn.state, TimeWait);
+
+        assert_eq!(timeline(&history, |_| true, 10).len(), 4);
+        assert_eq!(timeline(&history, |_| true, 2).len(), 2);
+        assert!(timeline(&SnapshotHistory::new(10), |_| true, 10).is_empty());
+    }
+}
@@ -0,0 +1,141 @@
+// Local interface lookup
+//
+// Maps the local address of each connection to the network interface it is
+// bound to (eth0, wg0, lo, ...), so multi-homed hosts can tell which link a
+// connection uses. Addresses are matched exactly first, then against the
+// longest interface network containing them (127.0.0.53 → lo).
+
+use crate::net::cidr::{normalize, Cidr};
+use crate::net::{Connection, StringInterner};
+use std::net::IpAddr;
+use std::sync::Arc;
+use sysinfo::Networks;
+
+/// Interface addresses and networks of this host
+#[derive(Debug, Clone, Default)]
+pub struct InterfaceMap {
+    /// (interface name, assigned address, network it belongs to); names
+    /// are shared by all addresses of an interface and the connections on it
+    entries: Vec<(Arc<str>, IpAddr, Cidr)>,
+}
+
+impl InterfaceMap {
+    /// Read the current interface list from the OS
+    pub fn from_system() -> Self {
+        let networks = Networks::new_with_refreshed_list();
+        Self::from_entries(networks.iter().flat_map(|(name, data)| {
+            data.ip_networks()
+                .iter()
+                .map(move |net| (name.as_str(), net.addr, net.prefix))
+        }))
+    }
+
+    /// Build from (interface, address, prefix length) triples
+    pub fn from_entries<'a>(entries: impl IntoIterator<Item = (&'a str, IpAddr, u8)>) -> Self {
+        let mut names = StringInterner::new();
+        let entries = entries
+            .into_iter()
+            .filter_map(|(name, addr, prefix)| {
+                let network = format!("{}/{}", addr, prefix).parse::<Cidr>().ok()?;
+                Some((names.intern(name), normalize(addr), network))
+            })
+            .collect();
+        Self { entries }
+    }
+
+    /// Interface owning `addr`, if any
+    ///
+    /// Wildcard (0.0.0.0, ::) and unparseable addresses have no interface.
+    pub fn lookup(&self, addr: &str) -> Option<&Arc<str>> {
+        let ip = normalize(addr.parse::<IpAddr>().ok()?);
+        if ip.is_unspecified() {
+            return None;
+        }
+        if let Some((name, _, _)) = self.entries.iter().find(|(_, a, _)| *a == ip) {
+            return Some(name);
+        }
+        self.entries
+            .iter()
+            .filter(|(_, _, network)| network.contains(&ip))
+            .max_by_key(|(_, _, network)| network.prefix_len())
+            .map(|(name, _, _)| name)
+    }
+
+    /// Fill in `Connection::interface` from each local address
+    pub fn attach(&self, conns: &mut [Connection]) {
+        for conn in conns {
+            conn.interface = self.lookup(&conn.local_addr).cloned();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> InterfaceMap {
+        InterfaceMap::from_entries([
+            ("lo", "127.0.0.1".parse().unwrap(), 8),
+            ("eth0", "192.168.1.5".parse().unwrap(), 24),
+            ("wg0", "10.8.0.2".parse().unwrap(), 24),
+            ("eth0", "fe80::1".parse().unwrap(), 64),
+        ])
+    }
+
+    #[test]
+    fn test_exact_and_network_lookup() {
+        let map = sample();
+        assert_eq!(map.lookup("192.168.1.5"), Some(&"eth0".into()));
+        assert_eq!(map.lookup("10.8.0.2"), Some(&"wg0".into()));
+        assert_eq!(map.lookup("127.0.0.53"), Some(&"lo".into()));
+        assert_eq!(map.lookup("::ffff:10.8.0.2"), Some(&"wg0".into()));
+        assert_eq!(map.lookup("fe80::1"), Some(&"eth0".into()));
+    }
+
+    #[test]
+    fn test_unknown_and_wildcard_addresses() {
+        let map = sample();
+        assert_eq!(map.lookup("0.0.0.0"), None);
+        assert_eq!(map.lookup("::"), None);
+        assert_eq!(map.lookup("203.0.113.9"), None);
+        assert_eq!(map.lookup("not-an-ip"), None);
+    }
+
+    #[test]
+    fn test_longest_prefix_wins() {
+        let map = InterfaceMap::from_entries([
+            ("br0", "10.0.0.1".parse().unwrap(), 8),
+            ("wg0", "10.8.0.2".parse().unwrap(), 24),
+        ]);
+        assert_eq!(map.lookup("10.8.0.9"), Some(&"wg0".into()));
+        assert_eq!(map.lookup("10.9.0.9"), Some(&"br0".into()));
+    }
+
+    #[test]
+    fn test_attach_sets_interface() {
+        let mut conns = vec![
+            Connection {
+                local_addr: "10.8.0.2".into(),
+                ..Default::default()
+            },
+            Connection {
+                local_addr: "0.0.0.0".into(),
+                ..Default::default()
+            },
+            Connection {
+                local_addr: "192.168.1.5".into(),
+                ..Default::default()
+            },
+            Connection {
+                local_addr: "fe80::1".into(),
+                ..Default::default()
+            },
+        ];
+        sample().attach(&mut conns);
+        assert_eq!(conns[0].interface.as_deref(), Some("wg0"));
+        assert_eq!(conns[1].interface, None);
+        // Both addresses of eth0 hand out the same shared name
+        let (v4, v6) = (conns[2].interface.as_ref(), conns[3].interface.as_ref());
+        assert!(Arc::ptr_eq(v4.unwrap(), v6.unwrap()));
+    }
+}
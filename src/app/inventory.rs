@@ -0,0 +1,262 @@
+// Host inventory annotations
+//
+// A CSV export from the CMDB maps addresses and networks to asset names,
+// owners and criticality, so the Graveyard speaks the same language as
+// the rest of the organization:
+//
+// ```toml
+// [inventory]
+// file = "/etc/ntomb/inventory.csv"
+// ```
+//
+// ```csv
+// cidr,name,owner,criticality
+// 10.1.4.20,db-primary-01,dba-team,critical
+// 10.1.8.0/24,"build farm",platform,low
+// ```
+//
+// The header row names the columns, in any order: `cidr` (or `ip`,
+// `address`, `network`) and `name` (or `asset`, `hostname`) are required,
+// `owner` (or `team`) and `criticality` (or `tier`) are optional, other
+// columns are ignored. The most specific network containing an address
+// wins. Blank lines and `#` comments are skipped.
+
+use crate::net::cidr::Cidr;
+use serde::Deserialize;
+use std::fmt;
+use std::fs;
+use std::net::IpAddr;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// `[inventory]` section of config.toml
+#[derive(Debug, Clone, Default, Deserialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct InventoryConfig {
+    /// CSV file of asset annotations; none are loaded when unset
+    pub file: Option<PathBuf>,
+}
+
+/// How important an asset is to the organization
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Criticality {
+    Low,
+    Medium,
+    High,
+    Critical,
+}
+
+impl Criticality {
+    /// Parse a criticality column value (also accepts tier numbers 1-4,
+    /// tier 1 being the most critical)
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "low" | "4" => Some(Self::Low),
+            "medium" | "med" | "3" => Some(Self::Medium),
+            "high" | "2" => Some(Self::High),
+            "critical" | "crit" | "1" => Some(Self::Critical),
+            _ => None,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Low => "low",
+            Self::Medium => "medium",
+            Self::High => "high",
+            Self::Critical => "critical",
+        }
+    }
+}
+
+/// One inventory row
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InventoryEntry {
+    pub cidr: Cidr,
+    pub name: String,
+    pub owner: Option<String>,
+    pub criticality: Option<Criticality>,
+}
+
+impl fmt::Display for InventoryEntry {
+    /// "db-primary-01 · dba-team · critical"
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)?;
+        if let Some(ref owner) = self.owner {
+            write!(f, " · {}", owner)?;
+        }
+        if let Some(criticality) = self.criticality {
+            write!(f, " · {}", criticality.label())?;
+        }
+        Ok(())
+    }
+}
+
+/// Inventory rows, most specific network first
+#[derive(Debug, Clone, Default)]
+pub struct Inventory {
+    entries: Vec<Arc<InventoryEntry>>,
+    /// Rows that could not be parsed
+    pub skipped: usize,
+}
+
+impl Inventory {
+    /// Read the configured inventory, if any
+    pub fn load(config: &InventoryConfig) -> Result<Self, String> {
+        let Some(ref path) = config.file else {
+            return Ok(Self::default());
+        };
+        let content = fs::read_to_string(path)
+            .map_err(|e| format!("cannot read {}: {}", path.display(), e))?;
+        let inventory = Self::parse(&content).map_err(|e| format!("{}: {}", path.display(), e))?;
+        if inventory.skipped > 0 {
+            tracing::warn!(
+                path = %path.display(),
+                skipped = inventory.skipped,
+                "Skipped unreadable inventory rows"
+            );
+        }
+        Ok(inventory)
+    }
+
+    /// Parse CSV content; fails only when the header lacks a required column
+    pub fn parse(content: &str) -> Result<Self, String> {
+        let mut rows = content
+            .lines()
+            .filter(|line| !line.trim().is_empty() && !line.trim_start().starts_with('#'))
+            .map(split_csv_line);
+        let header = rows.next().ok_or("empty inventory")?;
+        let column = |names: &[&str]| {
+            header
+                .iter()
+                .position(|h| names.contains(&h.trim().to_ascii_lowercase().as_str()))
+        };
+        let cidr_col =
+            column(&["cidr", "ip", "address", "network"]).ok_or("header has no cidr/ip column")?;
+        let name_col =
+            column(&["name", "asset", "hostname"]).ok_or("header has no name/asset column")?;
+        let owner_col = column(&["owner", "team"]);
+        let criticality_col = column(&["criticality", "tier"]);
+
+        let mut inventory = Self::default();
+        for row in rows {
+            let field = |col: Option<usize>| {
+                col.and_then(|c| row.get(c))
+                    .map(|v| v.trim())
+                    .filter(|v| !v.is_empty())
+            };
+            let cidr = field(Some(cidr_col)).and_then(|v| v.parse::<Cidr>().ok());
+            let name = field(Some(name_col));
+            let criticality = field(criticality_col).map(Criticality::from_name);
+            match (cidr, name, criticality) {
+                (Some(cidr), Some(name), criticality) if criticality != Some(None) => {
+                    inventory.entries.push(Arc::new(InventoryEntry {
+                        cidr,
+                        name: name.to_string(),
+                        owner: field(owner_col).map(str::to_string),
+                        criticality: criticality.flatten(),
+                    }));
+                }
+                _ => inventory.skipped += 1,
+            }
+        }
+        // Stable sort keeps file order among equally specific networks
+        inventory
+            .entries
+            .sort_by_key(|entry| std::cmp::Reverse(entry.cidr.prefix_len()));
+        Ok(inventory)
+    }
+
+    /// Whether no rows are loaded
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// The most specific row containing `ip`
+    pub fn lookup(&self, ip: &str) -> Option<&Arc<InventoryEntry>> {
+        if self.entries.is_empty() {
+            return None;
+        }
+        let ip = ip.parse::<IpAddr>().ok()?;
+        self.entries.iter().find(|entry| entry.cidr.contains(&ip))
+    }
+}
+
+/// Split one CSV line into fields; double quotes group commas and `""`
+/// is a literal quote
+fn split_csv_line(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match (quoted, c) {
+            (true, '"') if chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            (true, '"') => quoted = false,
+            (false, '"') => quoted = true,
+            (false, ',') => fields.push(std::mem::take(&mut field)),
+            (_, c) => field.push(c),
+        }
+    }
+    fields.push(field);
+    fields
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CSV: &str = "\
+# exported from the CMDB
+Hostname,Team,IP,Tier,Notes
+db-primary-01,dba-team,10.1.4.20,1,
+\"build farm, rack 3\",platform,10.1.8.0/24,low,\"says \"\"hi\"\"\"
+prod network,,10.1.0.0/16,,
+broken,,not-an-ip,,
+bad tier,,10.9.9.9,urgent,
+";
+
+    #[test]
+    fn test_parse_with_header_aliases() {
+        let inventory = Inventory::parse(CSV).unwrap();
+        assert_eq!(inventory.entries.len(), 3);
+        assert_eq!(inventory.skipped, 2);
+
+        let db = inventory.lookup("10.1.4.20").unwrap();
+        assert_eq!(db.name, "db-primary-01");
+        assert_eq!(db.owner.as_deref(), Some("dba-team"));
+        assert_eq!(db.criticality, Some(Criticality::Critical));
+        assert_eq!(db.to_string(), "db-primary-01 · dba-team · critical");
+
+        let farm = inventory.lookup("::ffff:10.1.8.7").unwrap();
+        assert_eq!(farm.name, "build farm, rack 3");
+        assert_eq!(farm.criticality, Some(Criticality::Low));
+    }
+
+    #[test]
+    fn test_most_specific_network_wins() {
+        let inventory = Inventory::parse(CSV).unwrap();
+        assert_eq!(inventory.lookup("10.1.4.21").unwrap().name, "prod network");
+        assert_eq!(inventory.lookup("10.1.4.20").unwrap().name, "db-primary-01");
+        assert!(inventory.lookup("192.0.2.1").is_none());
+        assert!(inventory.lookup("not-an-ip").is_none());
+    }
+
+    #[test]
+    fn test_missing_required_columns() {
+        assert!(Inventory::parse("name,owner\nx,y\n").is_err());
+        assert!(Inventory::parse("cidr,owner\n10.0.0.1,y\n").is_err());
+        assert!(Inventory::parse("# nothing\n").is_err());
+    }
+
+    #[test]
+    fn test_split_csv_line() {
+        assert_eq!(
+            split_csv_line("a,\"b,c\",\"d\"\"e\","),
+            ["a", "b,c", "d\"e", ""]
+        );
+    }
+}
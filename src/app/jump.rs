@@ -0,0 +1,115 @@
+// Jump to a PID or port
+//
+// `#` opens a prompt taking a number; Enter selects the first connection
+// of that PID, or failing that the first one using that port (local or
+// remote), without building a filter. A leading ':' only looks at ports
+// and a trailing '!' also focuses the process in Process mode:
+//
+//   #812      first connection of PID 812, else on port 812
+//   #:5432    first connection on port 5432
+//   #812!     same as #812, then Process mode on its owner
+
+use crate::net::Connection;
+
+/// What the jump prompt looks for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JumpTarget {
+    /// A PID, falling back to a port when no process has it
+    PidOrPort(u32),
+    /// A port (`:443`)
+    Port(u16),
+}
+
+/// A parsed jump prompt
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Jump {
+    pub target: JumpTarget,
+    /// Enter Process mode on the connection's process ('!')
+    pub focus: bool,
+}
+
+impl Jump {
+    pub fn parse(input: &str) -> Result<Self, String> {
+        let input = input.trim();
+        let (input, focus) = match input.strip_suffix('!') {
+            Some(rest) => (rest.trim_end(), true),
+            None => (input, false),
+        };
+        let target = match input.strip_prefix(':') {
+            Some(port) => JumpTarget::Port(
+                port.trim()
+                    .parse()
+                    .map_err(|_| format!("'{}' is not a port number", port.trim()))?,
+            ),
+            None => JumpTarget::PidOrPort(
+                input
+                    .parse()
+                    .map_err(|_| format!("'{}' is not a PID or port number", input))?,
+            ),
+        };
+        Ok(Self { target, focus })
+    }
+
+    /// Index of the first connection in `conns` this jump lands on
+    pub fn find(&self, conns: &[Connection]) -> Option<usize> {
+        let on_port = |port: u16| {
+            conns
+                .iter()
+                .position(|c| c.local_port == port || c.remote_port == port)
+        };
+        match self.target {
+            JumpTarget::Port(port) => on_port(port),
+            JumpTarget::PidOrPort(number) => conns
+                .iter()
+                .position(|c| c.pid.is_some_and(|pid| pid as i64 == number as i64))
+                .or_else(|| u16::try_from(number).ok().and_then(on_port)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let jump = |target, focus| Ok(Jump { target, focus });
+        assert_eq!(Jump::parse("812"), jump(JumpTarget::PidOrPort(812), false));
+        assert_eq!(Jump::parse(" :5432 "), jump(JumpTarget::Port(5432), false));
+        assert_eq!(Jump::parse("812!"), jump(JumpTarget::PidOrPort(812), true));
+        assert!(Jump::parse("").is_err());
+        assert!(Jump::parse("nginx").is_err());
+        assert!(Jump::parse(":70000").is_err());
+    }
+
+    #[test]
+    fn test_find() {
+        let conns = [
+            Connection {
+                local_port: 51000,
+                remote_port: 443,
+                pid: Some(443),
+                ..Default::default()
+            },
+            Connection {
+                local_port: 443,
+                pid: Some(1),
+                ..Default::default()
+            },
+            Connection {
+                local_port: 22,
+                pid: Some(812),
+                ..Default::default()
+            },
+        ];
+        let find = |input| Jump::parse(input).unwrap().find(&conns);
+
+        // PIDs win over ports, ports are local or remote
+        assert_eq!(find("443"), Some(0));
+        assert_eq!(find(":443"), Some(0));
+        assert_eq!(find("812"), Some(2));
+        assert_eq!(find("22"), Some(2));
+        assert_eq!(find("9999"), None);
+        assert_eq!(find("100000"), None);
+    }
+}
@@ -0,0 +1,92 @@
+// Keymap profiles
+//
+// The default profile uses arrow keys for navigation. The vim profile adds
+// the bindings vim users reach for without thinking, selected in
+// config.toml:
+//
+// ```toml
+// [keys]
+// profile = "vim"
+// ```
+//
+// | Key        | Acts as                  |
+// |------------|--------------------------|
+// | `j` / `k`  | `Down` / `Up`            |
+// | `g` / `G`  | `Home` / `End`           |
+// | `Ctrl-d/u` | `PageDown` / `PageUp`    |
+// | `h` / `l`  | `Shift-Tab` / `Tab`      |
+// | `z` / `Z`  | `g` / `G` (grouping)     |
+//
+// Vim keys are translated into the default profile's keys before dispatch,
+// so every action has exactly one implementation in event.rs.
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::Deserialize;
+
+/// Which set of key bindings is active
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum KeymapProfile {
+    #[default]
+    Default,
+    Vim,
+}
+
+/// `[keys]` section of config.toml
+#[derive(Debug, Clone, Default, Deserialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct KeysConfig {
+    /// "default" or "vim"
+    pub profile: KeymapProfile,
+}
+
+impl KeymapProfile {
+    /// The default-profile key that `key` stands for
+    pub fn translate(self, key: KeyEvent) -> KeyCode {
+        if self == KeymapProfile::Default {
+            return key.code;
+        }
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        match key.code {
+            KeyCode::Char('d') if ctrl => KeyCode::PageDown,
+            KeyCode::Char('u') if ctrl => KeyCode::PageUp,
+            KeyCode::Char('j') => KeyCode::Down,
+            KeyCode::Char('k') => KeyCode::Up,
+            KeyCode::Char('g') => KeyCode::Home,
+            KeyCode::Char('G') => KeyCode::End,
+            KeyCode::Char('h') => KeyCode::BackTab,
+            KeyCode::Char('l') => KeyCode::Tab,
+            KeyCode::Char('z') => KeyCode::Char('g'),
+            KeyCode::Char('Z') => KeyCode::Char('G'),
+            code => code,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_translate() {
+        let plain = |c| KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE);
+        let ctrl = |c| KeyEvent::new(KeyCode::Char(c), KeyModifiers::CONTROL);
+
+        assert_eq!(
+            KeymapProfile::Default.translate(plain('j')),
+            KeyCode::Char('j')
+        );
+        assert_eq!(
+            KeymapProfile::Default.translate(ctrl('d')),
+            KeyCode::Char('d')
+        );
+
+        let vim = KeymapProfile::Vim;
+        assert_eq!(vim.translate(plain('j')), KeyCode::Down);
+        assert_eq!(vim.translate(plain('G')), KeyCode::End);
+        assert_eq!(vim.translate(ctrl('d')), KeyCode::PageDown);
+        assert_eq!(vim.translate(plain('d')), KeyCode::Char('d'));
+        assert_eq!(vim.translate(plain('Z')), KeyCode::Char('G'));
+        assert_eq!(vim.translate(plain('q')), KeyCode::Char('q'));
+    }
+}
@@ -0,0 +1,194 @@
+// Mermaid flowchart export
+//
+// `f` copies the connections on screen as a Mermaid flowchart, ready to
+// paste into a markdown incident doc or wiki; `F` writes it to
+// ntomb-<unix time>.mmd in the working directory instead:
+//
+// ```mermaid
+// flowchart LR
+//     host["🖥️ web-1"]
+//     p812["curl (812)"]
+//     host --- p812
+//     e0["93.184.216.34"]
+//     p812 -->|"443 ESTABLISHED ×2"| e0
+// ```
+//
+// Edges point from the side that opened the connection, so inbound peers
+// point at the process that accepted them, and carry the triage tags of
+// their connections ("443 ESTABLISHED !suspicious"). Endpoints listed in
+// the `[inventory]` are labeled with their asset name.
+
+use super::tags::{ConnectionTags, Tag};
+use crate::net::{Connection, ConnectionDirection, ConnectionState};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
+use std::process::Command;
+use std::sync::Arc;
+
+/// Connections folded into one edge:
+/// (process node, remote addr, remote port, state name, inbound)
+type EdgeKey = (String, Arc<str>, u16, &'static str, bool);
+
+/// Name of this machine for the center node ("HOST" when unknown)
+pub fn local_host_name() -> String {
+    let name = std::fs::read_to_string("/proc/sys/kernel/hostname")
+        .ok()
+        .or_else(|| {
+            let output = Command::new("hostname").output().ok()?;
+            Some(String::from_utf8_lossy(&output.stdout).into_owned())
+        })
+        .map(|name| name.trim().to_string())
+        .unwrap_or_default();
+    if name.is_empty() {
+        "HOST".to_string()
+    } else {
+        name
+    }
+}
+
+/// Mermaid flowchart of `conns` around a `host` node
+///
+/// Processes hang off the host, their listeners and remote endpoints off
+/// the processes; connections to the same endpoint and port in the same
+/// state share one edge with a count and the union of their tags.
+pub fn flowchart(host: &str, conns: &[Connection], tags: &ConnectionTags) -> String {
+    let mut processes: BTreeMap<String, String> = BTreeMap::new();
+    let mut listeners: BTreeSet<(String, u16)> = BTreeSet::new();
+    // remote addr → (node id, label)
+    let mut endpoints: BTreeMap<Arc<str>, (String, String)> = BTreeMap::new();
+    let mut edges: BTreeMap<EdgeKey, (usize, BTreeSet<Tag>)> = BTreeMap::new();
+
+    for conn in conns {
+        let process = match (conn.pid, &conn.process_name) {
+            (Some(pid), name) => {
+                let id = format!("p{}", pid);
+                let label = match name {
+                    Some(name) => format!("{} ({})", name, pid),
+                    None => format!("pid {}", pid),
+                };
+                processes.entry(id.clone()).or_insert(label);
+                id
+            }
+            (None, _) => "host".to_string(),
+        };
+        if conn.state == ConnectionState::Listen {
+            listeners.insert((process, conn.local_port));
+            continue;
+        }
+        let count = endpoints.len();
+        endpoints
+            .entry(conn.remote_addr.clone())
+            .or_insert_with(|| {
+                let label = match conn.labels.get("asset") {
+                    Some(asset) => format!("{} ({})", asset, conn.remote_addr),
+                    None => conn.remote_addr.to_string(),
+                };
+                (format!("e{}", count), label)
+            });
+        let edge = edges
+            .entry((
+                process,
+                conn.remote_addr.clone(),
+                conn.remote_port,
+                conn.state.name(),
+                conn.direction == Some(ConnectionDirection::Inbound),
+            ))
+            .or_default();
+        edge.0 += 1;
+        edge.1.extend(tags.get(conn));
+    }
+
+    let mut out = String::from("flowchart LR\n");
+    let _ = writeln!(out, "    host[\"🖥️ {}\"]", escape(host));
+    for (id, label) in &processes {
+        let _ = writeln!(out, "    {}[\"{}\"]", id, escape(label));
+        let _ = writeln!(out, "    host --- {}", id);
+    }
+    for (process, port) in &listeners {
+        let id = format!("{}_l{}", process, port);
+        let _ = writeln!(out, "    {}((\"🕯️ :{}\"))", id, port);
+        let _ = writeln!(out, "    {} -.- {}", process, id);
+    }
+    for (id, label) in endpoints.values() {
+        let _ = writeln!(out, "    {}[\"{}\"]", id, escape(label));
+    }
+    for ((process, addr, port, state, inbound), (count, edge_tags)) in &edges {
+        let endpoint = &endpoints[addr].0;
+        let mut label = format!("{} {}", port, state);
+        if *count > 1 {
+            let _ = write!(label, " ×{}", count);
+        }
+        for tag in edge_tags {
+            let _ = write!(label, " {}", tag.label());
+        }
+        let (from, to) = if *inbound {
+            (endpoint.as_str(), process.as_str())
+        } else {
+            (process.as_str(), endpoint.as_str())
+        };
+        let _ = writeln!(out, "    {} -->|\"{}\"| {}", from, label, to);
+    }
+    out
+}
+
+/// Make `text` safe inside a quoted Mermaid label
+fn escape(text: &str) -> String {
+    text.replace('"', "#quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_flowchart() {
+        let curl = |local_port: u16| Connection {
+            local_addr: "10.0.0.5".into(),
+            local_port,
+            remote_addr: "93.184.216.34".into(),
+            remote_port: 443,
+            state: ConnectionState::Established,
+            pid: Some(812),
+            process_name: Some("curl".into()),
+            direction: Some(ConnectionDirection::Outbound),
+            ..Default::default()
+        };
+        let sshd = Connection {
+            local_addr: "0.0.0.0".into(),
+            local_port: 22,
+            remote_addr: "0.0.0.0".into(),
+            state: ConnectionState::Listen,
+            pid: Some(1),
+            process_name: Some("sshd".into()),
+            ..Default::default()
+        };
+        let peer = Connection {
+            local_addr: "10.0.0.5".into(),
+            local_port: 22,
+            remote_addr: "203.0.113.9".into(),
+            remote_port: 51234,
+            state: ConnectionState::Established,
+            direction: Some(ConnectionDirection::Inbound),
+            labels: [("asset", "bastion".into())].into(),
+            ..Default::default()
+        };
+        let mut tags = ConnectionTags::default();
+        tags.set(&curl(51001), Tag::FollowUp, true);
+        let chart = flowchart("web\"1", &[curl(51000), curl(51001), sshd, peer], &tags);
+        assert_eq!(
+            chart,
+            "flowchart LR\n\
+             \x20   host[\"🖥️ web#quot;1\"]\n\
+             \x20   p1[\"sshd (1)\"]\n\
+             \x20   host --- p1\n\
+             \x20   p812[\"curl (812)\"]\n\
+             \x20   host --- p812\n\
+             \x20   p1_l22((\"🕯️ :22\"))\n\
+             \x20   p1 -.- p1_l22\n\
+             \x20   e1[\"bastion (203.0.113.9)\"]\n\
+             \x20   e0[\"93.184.216.34\"]\n\
+             \x20   e1 -->|\"51234 ESTABLISHED\"| host\n\
+             \x20   p812 -->|\"443 ESTABLISHED ×2 follow-up\"| e0\n"
+        );
+    }
+}
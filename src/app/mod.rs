@@ -3,8 +3,72 @@
 // This module contains the main AppState struct and re-exports
 // configuration types from the config submodule.
 
+pub mod actions;
+pub mod activity;
+pub mod alarm;
+pub mod ambiance;
+pub mod anomaly;
+pub mod anonymizers;
+pub mod asn;
+pub mod autosave;
+pub mod clipboard;
+pub mod cloud_ranges;
+pub mod collector;
 pub mod config;
+pub mod config_file;
+pub mod config_watch;
+pub mod direction;
+pub mod dns;
+pub mod endpoint_detail;
+pub mod endpoint_rules;
+pub mod enrich;
+pub mod enrich_cache;
 pub mod event;
+pub mod event_feed;
+pub mod events;
+pub mod filter;
+pub mod filter_expr;
+pub mod forward;
+pub mod grouping;
+pub mod health;
+pub mod heavy_talkers;
+pub mod history;
+pub mod inspector;
+pub mod interfaces;
+pub mod inventory;
+pub mod jump;
+pub mod keymap;
+pub mod mermaid;
+pub mod navigation;
+pub mod notify;
+pub mod orphans;
+pub mod otlp;
+pub mod peers;
+pub mod perf;
+pub mod ports;
+pub mod probes;
+pub mod profiles;
+pub mod quality;
+pub mod quic;
+pub mod rdap;
+pub mod rows;
+pub mod scripting;
+pub mod seance;
+pub mod settings;
+pub mod snapshot;
+pub mod state_timeline;
+pub mod statusline;
+pub mod stream;
+pub mod syn_storm;
+pub mod tags;
+pub mod theme_schedule;
+pub mod threat_intel;
+pub mod toast;
+pub mod tombstones;
+pub mod trend;
+pub mod undo;
+pub mod watch;
+pub mod watchlist;
 
 // Re-export config types for convenience
 pub use config::{
@@ -12,13 +76,100 @@ pub use config::{
     CHANGE_HIGHLIGHT_DURATION,
 };
 
-use crate::net::{self, Connection};
+use crate::net::scope::{address_scope, AddressScope};
+use crate::net::sockinfo::{read_socket_info, SocketInfo};
+use crate::net::{self, Connection, DataSource, StringInterner};
+use crate::procfs::forensics::{ForensicsReader, ProcessForensics};
+use crate::procfs::package::{PackageOwner, PackageResolver};
+use actions::ActionsPopup;
+use activity::{activity_score, ActivityHistory, ActivityScope};
+use alarm::Alarm;
+use ambiance::Ambiance;
+use anomaly::AnomalyDetector;
+use anonymizers::{Anonymizer, Anonymizers};
+use asn::{AsInfo, AsnDatabase};
+use autosave::SnapshotAutosave;
+use cloud_ranges::{CloudRange, CloudRanges};
+use collector::Collector;
 use config::{
-    BLINK_INTERVAL_MS, FRAME_TIME_THRESHOLD_MS, LOG_ENTRY_COUNT, SLOW_FRAME_COUNT_THRESHOLD,
-    TICK_INTERVAL_MS,
+    BLINK_INTERVAL_MS, DATA_LATE_INTERVALS, DATA_STALE_INTERVALS, FRAME_TIME_THRESHOLD_MS,
+    HISTORY_SNAPSHOTS, LOG_ENTRY_COUNT, SCREENSAVER_FRAME_TICKS, SLOW_FRAME_COUNT_THRESHOLD,
+    TICK_INTERVAL_MS, TREND_WINDOW,
 };
+use config_file::ConfigFile;
+use config_watch::ConfigWatcher;
+use direction::DirectionHistory;
+use dns::{ReverseDns, ReverseName};
+use endpoint_detail::EndpointDetail;
+use endpoint_rules::{EndpointRule, EnvironmentAlert};
+use enrich::EnrichmentPipeline;
+use enrich_cache::{CacheKind, EnrichCache};
+use event_feed::{EventFeed, EventSubject};
+use events::{EventLog, EventSeverity, GrimoireEvent};
+use filter::ConnectionFilter;
+use filter_expr::{FilterExpr, FilterParseError};
+use grouping::{EndpointGroup, EndpointGrouping};
+use health::{BackendHealth, DiagnosticKind};
+use heavy_talkers::{SettingsPopup, SettingsRow};
+use inspector::{InspectorTab, SocketList};
+use inventory::{Inventory, InventoryEntry};
+use jump::Jump;
+use keymap::KeymapProfile;
+use navigation::{FocusHistory, FocusStep};
+use orphans::OrphanTracker;
+use peers::PeerStore;
+use perf::{PerfStats, Reduction};
+use ports::PortHeatmap;
+use probes::{ProbeKind, ProbeLimiter};
+use profiles::Profile;
+use quality::AnimationQuality;
+use quic::QuicTracker;
 use ratatui::widgets::ListState;
-use std::time::Instant;
+use rdap::{Rdap, RdapLookup, RdapRecord};
+use rows::{ConnectionRow, PoolKey, RowKind};
+use seance::SeanceCell;
+use settings::Settings;
+use snapshot::{connection_key, ConnectionKey, DiffChange, Snapshot, SnapshotHistory};
+use state_timeline::StateTimelines;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime};
+use syn_storm::{SynStormChange, SynStormMonitor};
+use tags::{ConnectionTags, Tag, TagPopup};
+use theme_schedule::ThemeSchedule;
+use threat_intel::{ThreatIntel, ThreatIntelUpdate};
+use toast::{ToastQueue, ToastSeverity};
+use tombstones::Tombstones;
+use trend::Trend;
+use undo::{UndoStack, ViewState};
+use watch::{Watch, WatchChange};
+use watchlist::WatchlistEntry;
+
+/// What the text typed at the bottom prompt is for
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PromptKind {
+    /// '/' filter expression
+    #[default]
+    Filter,
+    /// 'w' watch expression
+    Watch,
+    /// '#' PID or port to jump to
+    Jump,
+    /// '/' text searched for in the focused event feed
+    EventSearch,
+}
+
+/// How old the snapshot on screen is, relative to the data interval
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DataFreshness {
+    /// About as old as expected
+    Fresh,
+    /// Over DATA_LATE_INTERVALS data intervals old
+    Late,
+    /// Over DATA_STALE_INTERVALS data intervals old
+    Stale,
+}
 
 /// Main application state
 pub struct AppState {
@@ -33,8 +184,8 @@ pub struct AppState {
     #[allow(dead_code)]
     pub selected_log: usize,
 
-    /// Traffic history data (last 60 samples)
-    pub traffic_history: Vec<u64>,
+    /// Activity samples of the host, processes and endpoints
+    pub activity: ActivityHistory,
 
     /// Pulse phase for neon animation (0.0 ~ 1.0)
     pub pulse_phase: f32,
@@ -48,30 +199,236 @@ pub struct AppState {
     /// Last blink time for zombie animation
     pub last_blink: Instant,
 
-    /// Tick counter for generating varied traffic data
-    tick_counter: u64,
+    /// Drive animation from the tick counter alone (`--deterministic`):
+    /// every on_tick call is one tick, so the same input replays the same
+    /// frames
+    pub deterministic: bool,
+
+    /// When the state was created; the deterministic clock counts from here
+    started_at: Instant,
 
-    /// Active network connections from /proc/net/tcp
+    /// Tick counter for generating varied traffic data; also drives the
+    /// overdrive ambiance
+    pub tick_counter: u64,
+
+    /// Active network connections visible in the UI (collected snapshot
+    /// with `connection_filter` applied)
     pub connections: Vec<Connection>,
 
+    /// Unfiltered connections from the last collection
+    pub raw_connections: Vec<Connection>,
+
+    /// Filters narrowing `raw_connections` down to `connections`
+    pub connection_filter: ConnectionFilter,
+
+    /// Text being typed at the '/' filter prompt (None while closed)
+    pub filter_prompt: Option<String>,
+
+    /// Parse error for the expression last submitted at the prompt
+    pub filter_error: Option<String>,
+
+    /// Whether the open prompt edits the filter or adds a watch
+    pub prompt_kind: PromptKind,
+
+    /// Worker thread that collects connection snapshots from the backend
+    collector: Collector,
+
+    /// Whether the watchdog reported the running collection as hung
+    collection_hung: bool,
+
+    /// Shared storage for snapshot addresses and process names
+    interner: StringInterner,
+
+    /// Backend name, timing and recent errors (status bar + debug popup)
+    pub backend_health: BackendHealth,
+
+    /// Whether the backend debug popup is open (toggle with 'b' key)
+    pub show_backend_popup: bool,
+
+    /// Whether the diagnostics panel is open (toggle with '!' key)
+    pub show_diagnostics: bool,
+
+    /// Which page of endpoints the Graveyard shows ('{' / '}'); wraps
+    /// around, so it is only reduced modulo the page count when drawn
+    pub endpoint_page: isize,
+
+    /// Overdrive lightning flash state
+    pub ambiance: Ambiance,
+
+    /// Bell and banner flash for alerts (`[alarm]`)
+    pub alarm: Alarm,
+
+    /// Date/time theme schedule from `[theme]`; None leaves the theme
+    /// entirely to the `H` key
+    pub theme_schedule: Option<ThemeSchedule>,
+
+    /// Idle time before the screensaver starts; None never starts it
+    pub screensaver_after: Option<Duration>,
+
+    /// When the last key was pressed
+    pub last_input: Instant,
+
+    /// Whether the screensaver is showing
+    pub screensaver: bool,
+
+    /// Whether the palette is dimmed for the night
+    pub night_dim: bool,
+
+    /// Saved settings (per-terminal emoji width)
+    pub settings: Settings,
+
+    /// Where settings are saved; None keeps changes for this session only
+    pub settings_path: Option<std::path::PathBuf>,
+
+    /// Whether the next drawn frame should be saved as a screenshot ('>')
+    pub screenshot_requested: bool,
+
+    /// Whether the legend popup is open (toggle with '?' key)
+    pub show_legend: bool,
+
+    /// Whether the performance overlay is open (toggle with '%' key)
+    pub show_perf_overlay: bool,
+
+    /// Whether the port heatmap is shown below the Graveyard (toggle with 'R' key)
+    pub show_port_heatmap: bool,
+
+    /// The kernel's ephemeral port range, for the port heatmap
+    pub ephemeral_ports: std::ops::RangeInclusive<u16>,
+
+    /// Frame and collection times from the last minute, for the overlay
+    pub perf: PerfStats,
+
+    /// Recent data refreshes, replayed by the timeline
+    pub history: SnapshotHistory,
+
+    /// State transitions of every current connection, for the detail popup
+    pub state_timelines: StateTimelines,
+
+    /// History index shown while paused (Space); None follows live data
+    pub timeline: Option<usize>,
+
+    /// Snapshot marked with 'm' for the diff view
+    pub baseline: Option<Snapshot>,
+
+    /// Second snapshot frozen with 'M'; the diff compares against it
+    /// instead of the live data when set
+    pub comparison: Option<Snapshot>,
+
+    /// Whether the connection list shows only changes since the baseline
+    /// (toggle with 'v' key)
+    pub diff_mode: bool,
+
+    /// Change of each connection shown in the diff view
+    diff_changes: HashMap<ConnectionKey, DiffChange>,
+
+    /// Investigation commands for the selected connection (toggle with 'x')
+    pub actions_popup: Option<ActionsPopup>,
+
+    /// Triage tags put on connections this session
+    pub tags: ConnectionTags,
+
+    /// Open tag popup for the selected connection ('n')
+    pub tag_popup: Option<TagPopup>,
+
+    /// Only show connections with this tag (cycle with 'N' key)
+    pub tag_filter: Option<Tag>,
+
+    /// Open settings popup (toggle with ',')
+    pub settings_popup: Option<SettingsPopup>,
+
+    /// Profile preset last applied (`--profile` or the ',' popup)
+    pub profile: Option<Profile>,
+
+    /// Remote addresses pinned to the watchlist ('o')
+    pub pinned_endpoints: BTreeSet<Arc<str>>,
+
+    /// Connections marked with '.' for bulk tagging, pinning and export
+    pub marked: HashSet<ConnectionKey>,
+
+    /// Views before recent view-state changes, restored with 'u'
+    pub undo_stack: UndoStack,
+
+    /// Open endpoint detail popup (Enter), refreshed with the data
+    pub endpoint_detail: Option<EndpointDetail>,
+
+    /// Transient feedback drawn over the status bar
+    pub toasts: ToastQueue,
+
     /// Last time connections were refreshed
     last_conn_refresh: Instant,
 
+    /// When the last snapshot came in (on the animation clock, so the
+    /// age replays with `--deterministic`)
+    last_snapshot_at: Option<Instant>,
+
+    /// Data age in whole seconds as last drawn in the status bar
+    shown_data_age: u64,
+
     /// Connection refresh error message (if any)
     pub conn_error: Option<String>,
 
+    /// Grimoire event feed (newest last)
+    pub events: EventLog,
+
+    /// Scroll position, focus and search of the event feed panel
+    pub event_feed: EventFeed,
+
+    /// Root-owned wildcard listeners already reported, keyed by (addr, port)
+    seen_exposed_listeners: HashSet<(Arc<str>, u16)>,
+
     /// Graveyard view mode
     pub graveyard_mode: GraveyardMode,
 
     /// Selected process PID in Process mode
     pub selected_process_pid: Option<i32>,
 
+    /// Include descendant processes of the selected PID in Process mode
+    /// (toggle with 'c' key)
+    pub include_child_processes: bool,
+
+    /// PIDs covered by the Process-mode focus: the selected PID, plus its
+    /// descendants when `include_child_processes` is enabled
+    pub focus_pids: HashSet<i32>,
+
+    /// Back/forward history of Process-mode focus (Backspace / ')')
+    pub focus_history: FocusHistory,
+
+    /// Enrichers run on every collected snapshot (see enrich.rs)
+    enrichers: EnrichmentPipeline,
+
+    /// Package ownership resolver (dpkg/rpm lookups, cached per executable)
+    package_resolver: PackageResolver,
+
+    /// Package owning the focused process's executable (Process mode)
+    pub focused_package: Option<PackageOwner>,
+
+    /// Soul Inspector tab (cycle with 'i' key)
+    pub inspector_tab: InspectorTab,
+
+    /// Focus and selection of the Soul Inspector's socket list
+    pub inspector_sockets: SocketList,
+
+    /// /proc reader for the Forensics tab (caches executable hashes)
+    forensics_reader: ForensicsReader,
+
+    /// Forensics of the inspected process, read while the Forensics tab
+    /// is shown
+    pub forensics: Option<ProcessForensics>,
+
+    /// Socket options of the highlighted connection, read with its key
+    /// while the Forensics tab is shown (None inside when ss failed)
+    pub socket_info: Option<(ConnectionKey, Option<SocketInfo>)>,
+
     /// Currently selected connection index (Active Connections list)
     pub selected_connection: Option<usize>,
 
-    /// List state for Active Connections (enables scrolling)
+    /// List state for Active Connections (enables scrolling); selects a
+    /// row of `connection_rows()`, not a connection index
     pub connection_list_state: ListState,
 
+    /// Connection pools shown as their individual sockets
+    pub expanded_pools: HashSet<PoolKey>,
+
     /// Refresh interval configuration
     pub refresh_config: RefreshConfig,
 
@@ -81,58 +438,291 @@ pub struct AppState {
     /// Latency bucket configuration for ring positioning
     pub latency_config: LatencyConfig,
 
+    /// Name in the Host-mode coffin (see `set_host_name`)
+    pub host_name: String,
+
+    /// User-defined CIDR classification rules (`[[endpoint_rules]]`)
+    pub endpoint_rules: Vec<Arc<EndpointRule>>,
+
+    /// "process X talked to env=Y" alerts (`[[environment_alerts]]`)
+    pub environment_alerts: Vec<EnvironmentAlert>,
+
+    /// (process, remote addr) pairs already reported by environment alerts
+    seen_environment_contacts: HashSet<(Option<Arc<str>>, Arc<str>)>,
+
+    /// Blocklist for remote addresses (`[threat_intel]`, None when disabled)
+    pub threat_intel: Option<ThreatIntel>,
+
+    /// Remote addresses of the current snapshot found on the blocklist,
+    /// with the list's note for the matching entry
+    pub haunted_endpoints: HashMap<Arc<str>, String>,
+
+    /// Haunted remote addresses already reported in the Grimoire
+    seen_haunted_endpoints: HashSet<Arc<str>>,
+
+    /// Published cloud provider ranges (`[cloud_ranges]`)
+    pub cloud_ranges: CloudRanges,
+
+    /// Owner of each remote address in the current snapshot that lies in a
+    /// cloud provider range
+    cloud_endpoints: HashMap<Arc<str>, Arc<CloudRange>>,
+
+    /// Address range → AS/organization database (`[asn]`)
+    pub asn_database: Arc<AsnDatabase>,
+
+    /// CMDB asset annotations by address (`[inventory]`)
+    pub inventory: Arc<Inventory>,
+
+    /// AS announcing each remote address of the current snapshot
+    asn_endpoints: HashMap<Arc<str>, Arc<AsInfo>>,
+
+    /// Inbound/outbound direction of every socket seen so far
+    direction_history: DirectionHistory,
+
+    /// How long each UDP flow has been seen, for the QUIC heuristic
+    quic: QuicTracker,
+
+    /// Last owner of every connection, to spot sockets outliving it
+    orphans: OrphanTracker,
+
+    /// Per-process baselines and anomaly scores (`[anomaly]`)
+    pub anomaly: AnomalyDetector,
+
+    /// Background resolver for remote host names (`[dns] reverse`)
+    pub reverse_dns: Option<ReverseDns>,
+
+    /// Budget shared by everything that sends packets (`[probes]`)
+    pub probes: ProbeLimiter,
+
+    /// First/last-seen times of every remote address (`[peers]`)
+    pub peers: Option<PeerStore>,
+
+    /// Periodic snapshot files (`[autosave]`)
+    pub autosave: Option<SnapshotAutosave>,
+
+    /// Connections closed between two snapshots (`[tombstones]`)
+    pub tombstones: Option<Tombstones>,
+
+    /// DNS, RDAP and package lookups kept across restarts (`[cache]`)
+    pub enrich_cache: Option<EnrichCache>,
+
+    /// RDAP client, started by the first `K` lookup
+    rdap: Option<Rdap>,
+
+    /// Address whose RDAP popup is shown (`K`)
+    pub rdap_popup: Option<Arc<str>>,
+
+    /// How the Graveyard folds endpoints (AS organization, domain)
+    pub grouping: EndpointGrouping,
+
+    /// Show services and outbound connections side by side instead of
+    /// the Graveyard
+    pub split_view: bool,
+
+    /// Tile the Graveyard into per-process mini-graveyards (Séance view)
+    pub seance: bool,
+
+    /// Active key bindings (`[keys] profile`)
+    pub keymap: KeymapProfile,
+
+    /// Groups shown as their individual endpoints again
+    pub expanded_groups: HashSet<Arc<str>>,
+
+    /// Tor exit nodes and VPN ranges (`[anonymizers]`)
+    pub anonymizers: Anonymizers,
+
+    /// Remote addresses of the current snapshot that are Tor exits or VPN
+    /// endpoints
+    anonymous_endpoints: HashMap<Arc<str>, Anonymizer>,
+
+    /// Anonymizer addresses already reported in the Grimoire
+    seen_anonymous_endpoints: HashSet<Arc<str>>,
+
+    /// Half-open socket rates and SYN storm state (`[syn_storm]`)
+    pub syn_storm: SynStormMonitor,
+
+    /// Connection count watches (`[[watches]]` and the 'w' prompt)
+    pub watches: Vec<Watch>,
+
+    /// The config file as last applied, compared against on hot-reload
+    pub config: ConfigFile,
+
+    /// Watches config.toml for edits (None when the file can't be watched)
+    pub config_watcher: Option<ConfigWatcher>,
+
+    /// OTLP metrics exporter (`[otlp]`, built with `--features otlp`)
+    #[cfg(feature = "otlp")]
+    pub otlp: Option<otlp::OtlpExporter>,
+
     /// Frame time tracking for performance monitoring (Requirements 6.5)
     /// Stores the timestamp of the last frame render
     last_frame_time: Instant,
 
     /// Counter for consecutive slow frames (frame time > 100ms)
-    /// Used to trigger automatic animation complexity reduction
+    /// Each run of SLOW_FRAME_COUNT_THRESHOLD steps the animation quality down
     slow_frame_count: u32,
 
-    /// Whether animation complexity has been auto-reduced due to performance
-    /// When true, particle rendering uses reduced particle count
-    pub animation_reduced: bool,
+    /// Whether anything visible changed since the last draw
+    /// (snapshot, selection, settings, or animation phase)
+    dirty: bool,
 }
 
 impl AppState {
     /// Create a new AppState with default values
     pub fn new() -> Self {
+        Self::with_data_source(net::default_source())
+    }
+
+    /// Create a new AppState collecting connections from `data_source`
+    pub fn with_data_source(data_source: Box<dyn DataSource>) -> Self {
         let now = Instant::now();
         
         // Get detected emoji width offset from the emoji_width module
         let detected_offset = crate::ui::emoji_width::get_detected_offset();
         
-        let mut graveyard_settings = GraveyardSettings::default();
-        graveyard_settings.emoji_width_offset = detected_offset;
+        let graveyard_settings = GraveyardSettings {
+            emoji_width_offset: detected_offset,
+            ..Default::default()
+        };
         
         let mut state = Self {
             running: true,
             selected_node: 0,
             selected_log: 0,
             // Initialize with empty traffic history (will fill with real data)
-            traffic_history: vec![0; 60],
+            activity: ActivityHistory::default(),
             pulse_phase: 0.0,
             zombie_blink: true,
             last_tick: now,
             last_blink: now,
+            deterministic: false,
+            started_at: now,
             tick_counter: 0,
             connections: Vec::new(),
+            raw_connections: Vec::new(),
+            connection_filter: ConnectionFilter::default(),
+            filter_prompt: None,
+            filter_error: None,
+            prompt_kind: PromptKind::Filter,
+            collector: Collector::new(data_source),
+            collection_hung: false,
+            interner: StringInterner::new(),
+            backend_health: BackendHealth::default(),
+            show_backend_popup: false,
+            show_diagnostics: false,
+            endpoint_page: 0,
+            ambiance: Ambiance::default(),
+            alarm: Alarm::default(),
+            theme_schedule: None,
+            screensaver_after: None,
+            last_input: now,
+            screensaver: false,
+            night_dim: false,
+            settings: Settings::default(),
+            settings_path: None,
+            screenshot_requested: false,
+            show_legend: false,
+            show_port_heatmap: false,
+            ephemeral_ports: ports::IANA_EPHEMERAL,
+            show_perf_overlay: false,
+            perf: PerfStats::default(),
+            history: SnapshotHistory::new(HISTORY_SNAPSHOTS),
+            state_timelines: StateTimelines::default(),
+            timeline: None,
+            baseline: None,
+            comparison: None,
+            diff_mode: false,
+            diff_changes: HashMap::new(),
+            actions_popup: None,
+            tags: ConnectionTags::default(),
+            tag_popup: None,
+            settings_popup: None,
+            profile: None,
+            tag_filter: None,
+            pinned_endpoints: BTreeSet::new(),
+            marked: HashSet::new(),
+            undo_stack: UndoStack::default(),
+            endpoint_detail: None,
+            toasts: ToastQueue::default(),
             last_conn_refresh: now,
+            last_snapshot_at: None,
+            shown_data_age: 0,
             conn_error: None,
+            events: EventLog::default(),
+            event_feed: EventFeed::default(),
+            seen_exposed_listeners: HashSet::new(),
             graveyard_mode: GraveyardMode::default(),
             selected_process_pid: None,
+            include_child_processes: false,
+            focus_pids: HashSet::new(),
+            focus_history: FocusHistory::default(),
+            enrichers: EnrichmentPipeline::default(),
+            package_resolver: PackageResolver::new(),
+            focused_package: None,
+            inspector_tab: InspectorTab::default(),
+            inspector_sockets: SocketList::default(),
+            forensics_reader: ForensicsReader::default(),
+            forensics: None,
+            socket_info: None,
             selected_connection: None,
             connection_list_state: ListState::default(),
+            expanded_pools: HashSet::new(),
             refresh_config: RefreshConfig::new(),
             graveyard_settings,
             latency_config: LatencyConfig::default(),
+            host_name: "HOST".to_string(),
+            endpoint_rules: Vec::new(),
+            environment_alerts: Vec::new(),
+            seen_environment_contacts: HashSet::new(),
+            threat_intel: None,
+            haunted_endpoints: HashMap::new(),
+            seen_haunted_endpoints: HashSet::new(),
+            cloud_ranges: CloudRanges::default(),
+            cloud_endpoints: HashMap::new(),
+            asn_database: Arc::default(),
+            inventory: Arc::default(),
+            asn_endpoints: HashMap::new(),
+            direction_history: DirectionHistory::default(),
+            quic: QuicTracker::default(),
+            orphans: OrphanTracker::default(),
+            anomaly: AnomalyDetector::default(),
+            reverse_dns: None,
+            probes: ProbeLimiter::default(),
+            peers: None,
+            autosave: None,
+            tombstones: None,
+            enrich_cache: None,
+            rdap: None,
+            rdap_popup: None,
+            grouping: EndpointGrouping::Off,
+            split_view: false,
+            seance: false,
+            keymap: KeymapProfile::Default,
+            expanded_groups: HashSet::new(),
+            anonymizers: Anonymizers::default(),
+            anonymous_endpoints: HashMap::new(),
+            seen_anonymous_endpoints: HashSet::new(),
+            syn_storm: SynStormMonitor::default(),
+            watches: Vec::new(),
+            config: ConfigFile::default(),
+            config_watcher: None,
+            #[cfg(feature = "otlp")]
+            otlp: None,
             last_frame_time: now,
             slow_frame_count: 0,
-            animation_reduced: false,
+            dirty: true,
         };
 
         // Perform initial data load immediately on startup
+        state.rebuild_enrichers();
         state.refresh_connections();
+        state.events.push(GrimoireEvent::new(
+            EventSeverity::Info,
+            format!(
+                "Graveyard opened: {} connections",
+                state.raw_connections.len()
+            ),
+        ));
 
         state
     }
@@ -141,153 +731,2571 @@ impl AppState {
     pub fn on_tick(&mut self) {
         let now = Instant::now();
 
-        // Update pulse phase every tick (~100ms)
+        // Update pulse phase every tick (~100ms); in deterministic mode
+        // every call is a tick
         let elapsed_tick = now.duration_since(self.last_tick).as_millis();
-        if elapsed_tick >= TICK_INTERVAL_MS {
+        let ticked = self.deterministic || elapsed_tick >= TICK_INTERVAL_MS;
+        if ticked {
             self.last_tick = now;
             self.tick_counter += 1;
 
-            // Increment pulse phase (0.0 ~ 1.0)
-            self.pulse_phase += 0.05;
-            if self.pulse_phase >= 1.0 {
-                self.pulse_phase = 0.0;
+            // The pulse only moves while animations are enabled, so a
+            // static graveyard needs no redraws; lower quality tiers move
+            // it in bigger, rarer steps at the same speed
+            let pulse_every = self.pulse_every();
+            if let Some(every) = pulse_every.filter(|every| self.tick_counter % every == 0) {
+                // Increment pulse phase (0.0 ~ 1.0)
+                self.pulse_phase += 0.05 * every as f32;
+                if self.pulse_phase >= 1.0 {
+                    self.pulse_phase = 0.0;
+                }
+                self.dirty = true;
+            }
+
+            // Sample activity per scope, with a heartbeat variation
+            self.update_activity();
+
+            let ambiance_now = self.animation_instant(now);
+            if self.ambiance_active() && self.ambiance.observe(&self.events, ambiance_now) {
+                self.dirty = true;
+            }
+            if self.alarm.observe(&self.events, ambiance_now) {
+                self.dirty = true;
             }
 
-            // Update traffic history with sine wave + some randomness
-            self.update_traffic_history();
+            self.update_screensaver(now);
+            self.update_forensics();
+
+            // The status bar counts the data age in seconds
+            if let Some((age, _)) = self.data_age() {
+                let secs = age.as_secs();
+                if secs != std::mem::replace(&mut self.shown_data_age, secs) {
+                    self.dirty = true;
+                }
+            }
         }
 
-        // Toggle zombie blink every 500ms
-        let elapsed_blink = now.duration_since(self.last_blink).as_millis();
-        if elapsed_blink >= BLINK_INTERVAL_MS {
+        // Toggle zombie blink every 500ms (every 5 ticks when deterministic)
+        let blink_due = if self.deterministic {
+            ticked && self.tick_counter % (BLINK_INTERVAL_MS / TICK_INTERVAL_MS) as u64 == 0
+        } else {
+            now.duration_since(self.last_blink).as_millis() >= BLINK_INTERVAL_MS
+        };
+        if blink_due {
             self.last_blink = now;
             self.zombie_blink = !self.zombie_blink;
         }
 
-        // Refresh connections based on dynamic data refresh interval
-        let elapsed_conn = now.duration_since(self.last_conn_refresh);
-        if elapsed_conn >= self.refresh_config.data_interval() {
+        // Keep redrawing until the refresh-interval highlight has faded
+        if let Some(last_change) = self.refresh_config.last_change {
+            if last_change.elapsed() < CHANGE_HIGHLIGHT_DURATION + self.refresh_config.ui_interval()
+            {
+                self.dirty = true;
+            }
+        }
+
+        self.apply_theme_schedule(now);
+
+        if self.config_watcher.as_mut().is_some_and(|w| w.poll(now)) {
+            self.reload_config();
+        }
+
+        // Reverse DNS, RDAP and package answers arrive between data refreshes
+        self.poll_lookups();
+
+        // Expired toasts disappear on the next frame
+        if self.toasts.expire(now) {
+            self.dirty = true;
+        }
+
+        // Refresh connections based on dynamic data refresh interval; in
+        // deterministic mode every data_multiplier ticks
+        let refresh_due = if self.deterministic {
+            ticked && self.tick_counter % self.refresh_config.data_multiplier.max(1) == 0
+        } else {
+            now.duration_since(self.last_conn_refresh) >= self.refresh_config.data_interval()
+        };
+        if refresh_due {
             self.refresh_connections();
         }
     }
 
+    /// The instant animation timers compare against: the wall clock, or in
+    /// deterministic mode one TICK_INTERVAL_MS per tick since startup
+    fn animation_instant(&self, now: Instant) -> Instant {
+        if self.deterministic {
+            self.started_at + Duration::from_millis(self.tick_counter * TICK_INTERVAL_MS as u64)
+        } else {
+            now
+        }
+    }
+
+    /// The terminal is now `width` x `height`: redraw, and don't count
+    /// the full repaint against the animation quality
+    pub fn on_resize(&mut self, width: u16, height: u16) {
+        tracing::debug!(width, height, "Terminal resized");
+        self.slow_frame_count = 0;
+        self.last_frame_time = Instant::now();
+        self.dirty = true;
+    }
+
+    /// Request a redraw on the next frame
+    pub fn mark_dirty(&mut self) {
+        self.dirty = true;
+    }
+
+    /// Whether a redraw is needed; clears the flag
+    pub fn take_dirty(&mut self) -> bool {
+        std::mem::take(&mut self.dirty)
+    }
+
     /// Refresh network connections from /proc/net/tcp
     /// Read-only operation following security-domain guidelines
     pub fn refresh_connections(&mut self) {
         self.last_conn_refresh = Instant::now();
 
-        match net::collect_connections() {
+        let Some(collected) = self.collector.collect(self.refresh_config.collect_timeout) else {
+            self.report_hung_collection();
+            return;
+        };
+        if std::mem::take(&mut self.collection_hung) {
+            self.events.push(GrimoireEvent::new(
+                EventSeverity::Info,
+                format!(
+                    "Watchdog: {} collection returned after {:.1}s",
+                    collected.backend,
+                    collected.took.as_secs_f64()
+                ),
+            ));
+        }
+        // A backend that failed while another took over is only a warning
+        let kind = if collected.result.is_ok() {
+            DiagnosticKind::Warning
+        } else {
+            DiagnosticKind::Error
+        };
+        for (backend, message) in collected.errors {
+            self.backend_health.record(kind, backend, message);
+        }
+        // Process mapping is optional: the connections are shown anyway
+        if let Some(e) = collected.process_error {
+            tracing::warn!(error = %e, "Failed to attach process info to connections");
+            self.backend_health
+                .record(DiagnosticKind::of(&e), "procfs", e.to_string());
+        }
+
+        match collected.result {
             Ok(conns) => {
-                // On Linux, attach process information to connections
-                // This is a best-effort operation - failures are logged but don't prevent
-                // the connections from being displayed
-                #[cfg(target_os = "linux")]
-                let conns = {
-                    let mut conns = conns;
-                    if let Err(e) = crate::procfs::attach_process_info(&mut conns) {
-                        // Log the error but continue - process mapping is optional
-                        tracing::warn!(error = %e, "Failed to attach process info to connections");
+                self.last_snapshot_at = Some(self.animation_instant(Instant::now()));
+                // Share repeated addresses/process names across the snapshot
+                // (and with the previous one) instead of owning copies
+                let mut conns = conns;
+                self.interner.intern_connections(&mut conns);
+                self.direction_history.classify(&mut conns);
+                self.quic.classify(&mut conns);
+                let departures = self.orphans.classify(&mut conns, orphans::process_alive);
+                self.record_departures(departures);
+                // Service names depend on the direction
+                self.enrichers.run(&mut conns);
+
+                // Keep the paused timeline on the same snapshot when the
+                // oldest one is dropped
+                if self.history.push(Snapshot::capture(&conns)) {
+                    if let Some(index) = self.timeline.as_mut() {
+                        *index = index.saturating_sub(1);
                     }
-                    conns
-                };
+                }
+                self.state_timelines.observe(&conns, SystemTime::now());
+                // Timers tick on; re-read the socket options with the data
+                self.socket_info = None;
+
+                let previous_backend = self.backend_health.backend;
+                self.backend_health
+                    .record_snapshot(collected.backend, collected.took, &conns);
+
+                // Unchanged snapshots (same sockets, same backend) don't
+                // need a redraw; the collection time alone is not worth one
+                if conns != self.raw_connections
+                    || previous_backend != self.backend_health.backend
+                    || self.conn_error.is_some()
+                {
+                    self.dirty = true;
+                }
+                #[cfg(feature = "scripting")]
+                self.run_connection_hooks(&conns);
+                self.record_tombstones(&conns);
+                self.raw_connections = conns;
+                self.autosave_snapshot();
+                self.record_syn_storm();
+                self.record_exposed_listeners();
+                self.record_new_peers();
+                self.record_environment_contacts();
+                self.update_haunted_endpoints();
+                self.update_cloud_endpoints();
+                self.update_asn_endpoints();
+                self.record_anomalies();
+                self.update_reverse_dns();
+                self.update_anonymous_endpoints();
+                self.record_watches();
+                #[cfg(feature = "otlp")]
+                self.export_metrics();
+                self.apply_filters();
+                self.interner.purge_unused();
+                self.conn_error = None;
+
+                // Worker processes come and go, so the descendant set is
+                // rebuilt on every data refresh
+                self.update_focus_pids();
+                self.refresh_endpoint_detail();
+            }
+            Err(e) => {
+                self.backend_health.record(
+                    DiagnosticKind::of(&e),
+                    collected.backend,
+                    e.to_string(),
+                );
+                self.dirty = true;
+                // Gracefully handle errors - don't panic
+                // Following security-domain: calm, informative tone
+                let error = format!("Cannot read /proc/net/tcp: {} (permission or OS issue)", e);
+                // Toast once per distinct failure, not on every refresh
+                if self.conn_error.as_ref() != Some(&error) {
+                    self.toast(error.clone(), ToastSeverity::Error);
+                }
+                self.conn_error = Some(error);
+                // Keep existing connections if refresh fails
+            }
+        }
+        if self.show_diagnostics {
+            self.backend_health.mark_seen();
+        }
+        self.perf
+            .collections
+            .push(Instant::now(), self.last_conn_refresh.elapsed());
+    }
+
+    /// Log each process found gone with sockets still lingering
+    fn record_departures(&mut self, departures: Vec<orphans::Departure>) {
+        for departure in departures {
+            let message = format!(
+                "Process departed: {} left {} socket{} lingering",
+                departure.label(),
+                departure.lingering,
+                if departure.lingering == 1 { "" } else { "s" }
+            );
+            self.events.push(
+                GrimoireEvent::new(EventSeverity::Info, message).with_pid(Some(departure.pid)),
+            );
+        }
+    }
+
+    /// The collection is past its timeout: keep the last snapshot and
+    /// have the watchdog report it once
+    fn report_hung_collection(&mut self) {
+        self.dirty = true;
+        if std::mem::replace(&mut self.collection_hung, true) {
+            return;
+        }
+        let backend = self.collector.backend();
+        let message = format!(
+            "Watchdog: {} collection hung for over {:.1}s, keeping the last snapshot",
+            backend,
+            self.refresh_config.collect_timeout.as_secs_f64()
+        );
+        tracing::warn!(backend, "Collection timed out");
+        self.backend_health
+            .record(DiagnosticKind::Error, backend, message.clone());
+        self.events
+            .push(GrimoireEvent::new(EventSeverity::Warning, message));
+    }
+
+    /// How long the running collection has been hung, while the snapshot
+    /// on screen is stale
+    pub fn collection_overdue(&self) -> Option<Duration> {
+        self.collector
+            .overdue(Instant::now(), self.refresh_config.collect_timeout)
+    }
+
+    /// Age of the snapshot on screen and how it compares to the data
+    /// interval; None before the first snapshot
+    pub fn data_age(&self) -> Option<(Duration, DataFreshness)> {
+        let now = self.animation_instant(Instant::now());
+        let age = now.saturating_duration_since(self.last_snapshot_at?);
+        let expected = if self.deterministic {
+            Duration::from_millis(TICK_INTERVAL_MS as u64 * self.refresh_config.data_multiplier)
+        } else {
+            self.refresh_config.data_interval()
+        };
+        let freshness = if age > expected.mul_f64(DATA_STALE_INTERVALS) {
+            DataFreshness::Stale
+        } else if age > expected.mul_f64(DATA_LATE_INTERVALS) {
+            DataFreshness::Late
+        } else {
+            DataFreshness::Fresh
+        };
+        Some((age, freshness))
+    }
+
+    /// Toggle the backend debug popup
+    pub fn toggle_backend_popup(&mut self) {
+        self.show_backend_popup = !self.show_backend_popup;
+    }
+
+    /// Log more verbosely (wrapping back to errors only after trace)
+    pub fn cycle_log_level(&mut self) {
+        match crate::logging::cycle_level() {
+            Some(level) => self.toast(format!("📜 Log level: {}", level), ToastSeverity::Info),
+            None => self.toast(
+                "Start with --log-file to write logs",
+                ToastSeverity::Warning,
+            ),
+        }
+    }
+
+    /// Toggle the diagnostics panel; opening it marks everything as seen
+    pub fn toggle_diagnostics(&mut self) {
+        self.show_diagnostics = !self.show_diagnostics;
+        if self.show_diagnostics {
+            self.backend_health.mark_seen();
+        }
+    }
+
+    /// Switch Overdrive and night dimming when the theme schedule says
+    /// so; parts the schedule didn't change keep any manual toggle
+    pub fn apply_theme_schedule(&mut self, now: Instant) {
+        let Some(schedule) = self.theme_schedule.as_mut() else {
+            return;
+        };
+        let change = schedule.poll(now, SystemTime::now());
+        if let Some(overdrive) = change.overdrive {
+            self.graveyard_settings.overdrive_enabled = overdrive;
+            self.dirty = true;
+        }
+        if let Some(dim) = change.dim {
+            self.night_dim = dim;
+            self.dirty = true;
+        }
+    }
+
+    /// Name the Host-mode coffin: `configured` (`[graveyard] host_name`)
+    /// when set, else the machine's hostname without its domain; with
+    /// `--deterministic` the name stays "HOST" so output is reproducible
+    pub fn set_host_name(&mut self, configured: Option<&str>) {
+        self.host_name = match configured {
+            Some(name) => name.to_string(),
+            None if self.deterministic => "HOST".to_string(),
+            None => {
+                let name = mermaid::local_host_name();
+                name.split('.').next().unwrap_or(&name).to_string()
+            }
+        };
+    }
+
+    /// Re-read the config file after an edit and apply the sections that
+    /// can change while running
+    ///
+    /// A file that fails to parse or validate leaves every setting as it
+    /// was. Either way the outcome is logged in the Grimoire.
+    fn reload_config(&mut self) {
+        let Some(path) = self.config_watcher.as_ref().map(|w| w.path().to_path_buf()) else {
+            return;
+        };
+        let config = match ConfigFile::load(Some(&path)) {
+            Ok(config) => config,
+            Err(e) => {
+                self.events.push(GrimoireEvent::new(
+                    EventSeverity::Warning,
+                    format!("Config reload failed: {}", e),
+                ));
+                self.dirty = true;
+                return;
+            }
+        };
+        let changed = config_watch::changed_sections(&self.config, &config);
+        if changed.is_empty() {
+            return;
+        }
+        if let Err(e) = self.apply_live_config(&config, &changed) {
+            self.events.push(GrimoireEvent::new(
+                EventSeverity::Warning,
+                format!("Config reload failed: {}; keeping previous settings", e),
+            ));
+            self.dirty = true;
+            return;
+        }
+
+        let (live, restart): (Vec<&str>, Vec<&str>) = changed
+            .iter()
+            .partition(|section| config_watch::LIVE_SECTIONS.contains(section));
+        let mut message = if live.is_empty() {
+            "Config changed".to_string()
+        } else {
+            format!("Config reloaded: {}", live.join(", "))
+        };
+        if !restart.is_empty() {
+            message.push_str(&format!(" (restart to apply {})", restart.join(", ")));
+        }
+        self.events
+            .push(GrimoireEvent::new(EventSeverity::Info, message));
+        self.config = config;
+        self.dirty = true;
+    }
+
+    /// Apply the live `changed` sections of `config`
+    ///
+    /// Everything that can fail is built before anything is replaced, so
+    /// an error leaves the running settings untouched.
+    fn apply_live_config(&mut self, config: &ConfigFile, changed: &[&str]) -> Result<(), String> {
+        let has = |section: &str| changed.contains(&section);
+        let endpoint_rules = if has("endpoint_rules") {
+            Some(
+                endpoint_rules::build_rules(&config.endpoint_rules)
+                    .map_err(|e| format!("[[endpoint_rules]]: {}", e))?,
+            )
+        } else {
+            None
+        };
+        let environment_alerts = if has("environment_alerts") {
+            Some(
+                endpoint_rules::build_environment_alerts(&config.environment_alerts)
+                    .map_err(|e| format!("[[environment_alerts]]: {}", e))?,
+            )
+        } else {
+            None
+        };
+        let cloud_ranges = if has("cloud_ranges") {
+            Some(
+                CloudRanges::load(&config.cloud_ranges)
+                    .map_err(|e| format!("[cloud_ranges]: {}", e))?,
+            )
+        } else {
+            None
+        };
+        let anonymizers = if has("anonymizers") {
+            Some(
+                Anonymizers::load(&config.anonymizers)
+                    .map_err(|e| format!("[anonymizers]: {}", e))?,
+            )
+        } else {
+            None
+        };
+        let inventory = if has("inventory") {
+            Some(Inventory::load(&config.inventory).map_err(|e| format!("[inventory]: {}", e))?)
+        } else {
+            None
+        };
+        let alarm = if has("alarm") {
+            Some(Alarm::from_config(&config.alarm).map_err(|e| format!("[alarm]: {}", e))?)
+        } else {
+            None
+        };
+        let watches = if has("watches") {
+            Some(watch::build_watches(&config.watches).map_err(|e| format!("[[watches]]: {}", e))?)
+        } else {
+            None
+        };
+
+        if let Some(rules) = endpoint_rules {
+            self.endpoint_rules = rules;
+        }
+        if let Some(inventory) = inventory {
+            self.inventory = Arc::new(inventory);
+        }
+        if has("endpoint_rules") || has("inventory") {
+            self.rebuild_enrichers();
+        }
+        if let Some(alerts) = environment_alerts {
+            self.environment_alerts = alerts;
+        }
+        if has("threat_intel") {
+            self.threat_intel = ThreatIntel::from_config(&config.threat_intel);
+            self.haunted_endpoints.clear();
+        }
+        // Cached lookups were made against the old ranges
+        if let Some(ranges) = cloud_ranges {
+            self.cloud_ranges = ranges;
+            self.cloud_endpoints.clear();
+        }
+        if let Some(anonymizers) = anonymizers {
+            self.anonymizers = anonymizers;
+            self.anonymous_endpoints.clear();
+        }
+        if has("syn_storm") {
+            self.syn_storm = SynStormMonitor::new(&config.syn_storm);
+        }
+        // Watches added at the 'w' prompt stay; the old config's are replaced
+        if let Some(watches) = watches {
+            let old: Vec<&str> = self
+                .config
+                .watches
+                .iter()
+                .map(|w| w.expr.as_str())
+                .collect();
+            self.watches.retain(|w| !old.contains(&w.source.as_str()));
+            self.watches.splice(0..0, watches);
+        }
+        if let Some(alarm) = alarm {
+            self.alarm.reconfigure(alarm);
+        }
+        if has("probes") {
+            self.probes
+                .reconfigure(ProbeLimiter::from_config(&config.probes));
+        }
+        if has("keys") {
+            self.keymap = config.keys.profile;
+        }
+        if has("animation") {
+            self.graveyard_settings.animation_quality = config.animation.quality;
+            self.graveyard_settings.ambiance_enabled = config.animation.ambiance;
+        }
+        if has("graveyard") {
+            let defaults = GraveyardSettings::default();
+            self.graveyard_settings.max_visible_endpoints = config
+                .graveyard
+                .max_endpoints
+                .map_or(defaults.max_visible_endpoints, |max| max.max(1));
+            self.graveyard_settings.heavy_talkers = config
+                .graveyard
+                .heavy_talkers
+                .map_or(defaults.heavy_talkers, |threshold| threshold.adjusted(0));
+            self.set_host_name(config.graveyard.host_name.as_deref());
+        }
+        if has("theme") && !self.deterministic {
+            self.theme_schedule = Some(ThemeSchedule::new(config.theme.clone()));
+            self.apply_theme_schedule(Instant::now());
+        }
+        if has("screensaver") {
+            self.screensaver_after = config
+                .screensaver
+                .idle_secs
+                .filter(|&secs| secs > 0)
+                .map(Duration::from_secs);
+        }
+        Ok(())
+    }
+
+    /// Whether the overdrive ambient layer is drawn: overdrive on,
+    /// animations on, ambiance not disabled in the config, and a quality
+    /// tier above Off
+    pub fn ambiance_active(&self) -> bool {
+        let settings = &self.graveyard_settings;
+        settings.overdrive_enabled
+            && settings.animations_enabled
+            && settings.ambiance_enabled
+            && settings.animation_quality > AnimationQuality::Off
+    }
+
+    /// Start the screensaver once the keyboard has been idle long enough,
+    /// and keep its orbit moving while it shows
+    fn update_screensaver(&mut self, now: Instant) {
+        if self.screensaver {
+            if self.tick_counter % SCREENSAVER_FRAME_TICKS == 0 {
+                self.dirty = true;
+            }
+        } else if self
+            .screensaver_after
+            .is_some_and(|after| now.duration_since(self.last_input) >= after)
+        {
+            self.screensaver = true;
+            self.dirty = true;
+        }
+    }
+
+    /// Record a keypress; returns true when it woke the screensaver, in
+    /// which case the key should do nothing else
+    pub fn note_input(&mut self) -> bool {
+        self.last_input = Instant::now();
+        let woke = self.screensaver;
+        self.screensaver = false;
+        woke
+    }
+
+    /// Toggle the frame/collection time overlay
+    pub fn toggle_perf_overlay(&mut self) {
+        self.show_perf_overlay = !self.show_perf_overlay;
+    }
+
+    /// Show `text` as a toast for its severity's default lifetime
+    pub fn toast(&mut self, text: impl Into<String>, severity: ToastSeverity) {
+        self.toasts.push(text, severity, severity.ttl());
+        self.dirty = true;
+    }
+
+    /// Copy the selected connection's details to the system clipboard,
+    /// or one line per marked connection
+    pub fn yank_selected_connection(&mut self) {
+        let marked = self.marked_connections();
+        if !marked.is_empty() {
+            let lines: Vec<String> = marked.iter().map(|conn| self.yank_line(conn)).collect();
+            self.copy_to_clipboard(
+                &lines.join("\n"),
+                &format!("Yanked {} marked connections", marked.len()),
+            );
+            return;
+        }
+        let Some(conn) = self
+            .selected_connection
+            .and_then(|idx| self.connections.get(idx))
+        else {
+            self.toast(
+                "Nothing to yank: select a connection with ↑↓",
+                ToastSeverity::Warning,
+            );
+            return;
+        };
+        let text = self.yank_line(conn);
+        let summary = format!(
+            "{}:{} → {}:{}",
+            conn.local_addr, conn.local_port, conn.remote_addr, conn.remote_port
+        );
+        self.copy_to_clipboard(&text, &format!("Yanked {}", summary));
+    }
+
+    /// Clipboard line for `conn`, with its tags
+    fn yank_line(&self, conn: &Connection) -> String {
+        let mut text = clipboard::format_connection(conn);
+        if let Some(labels) = self.tags.labels(conn) {
+            text.push_str(&format!(" tags={}", labels));
+        }
+        text
+    }
+
+    /// Mark the connections of the selected row (all sockets of a folded
+    /// pool) for bulk actions, or unmark them, and move down a row
+    pub fn toggle_mark(&mut self) {
+        let rows = self.connection_rows();
+        let Some(row) = self.selected_row(&rows) else {
+            self.toast(
+                "Nothing to mark: select a connection with ↑↓",
+                ToastSeverity::Warning,
+            );
+            return;
+        };
+        let keys: Vec<ConnectionKey> = match rows[row].kind {
+            RowKind::Pool { ref members, .. } => members
+                .iter()
+                .map(|&idx| connection_key(&self.connections[idx]))
+                .collect(),
+            _ => vec![connection_key(&self.connections[rows[row].index])],
+        };
+        if keys.iter().all(|key| self.marked.contains(key)) {
+            for key in &keys {
+                self.marked.remove(key);
+            }
+        } else {
+            self.marked.extend(keys);
+        }
+        self.move_connection_selection(1);
+        self.dirty = true;
+    }
+
+    /// Unmark every connection
+    pub fn clear_marks(&mut self) {
+        self.marked.clear();
+        self.dirty = true;
+    }
+
+    /// Whether `conn` is marked for bulk actions
+    pub fn is_marked(&self, conn: &Connection) -> bool {
+        !self.marked.is_empty() && self.marked.contains(&connection_key(conn))
+    }
+
+    /// Marked connections in the list on screen
+    pub fn marked_connections(&self) -> Vec<Connection> {
+        if self.marked.is_empty() {
+            return Vec::new();
+        }
+        self.connections
+            .iter()
+            .filter(|conn| self.is_marked(conn))
+            .cloned()
+            .collect()
+    }
+
+    /// Connections exports cover: the marked ones, or everything on screen
+    fn export_connections(&self) -> Vec<Connection> {
+        let marked = self.marked_connections();
+        if marked.is_empty() {
+            self.connections.clone()
+        } else {
+            marked
+        }
+    }
+
+    /// Open the investigation commands popup for the selected connection
+    pub fn open_actions_popup(&mut self) {
+        match self
+            .selected_connection
+            .and_then(|idx| self.connections.get(idx))
+        {
+            Some(conn) => {
+                self.actions_popup = Some(ActionsPopup::for_connection(conn));
+                self.dirty = true;
+            }
+            None => self.toast(
+                "No actions: select a connection with ↑↓",
+                ToastSeverity::Warning,
+            ),
+        }
+    }
+
+    /// Close the actions popup without copying anything
+    pub fn close_actions_popup(&mut self) {
+        self.actions_popup = None;
+        self.dirty = true;
+    }
+
+    /// Copy the highlighted command and close the popup
+    pub fn copy_selected_action(&mut self) {
+        let Some(command) = self
+            .actions_popup
+            .take()
+            .and_then(|popup| popup.current().map(|action| action.command.clone()))
+        else {
+            return;
+        };
+        self.copy_to_clipboard(&command, &format!("Copied `{}`", command));
+    }
+
+    /// Open the tag popup for the marked connections, or else the
+    /// selected one
+    pub fn open_tag_popup(&mut self) {
+        let marked = self.marked_connections();
+        if !marked.is_empty() {
+            self.tag_popup = Some(TagPopup::for_marked(marked));
+            self.dirty = true;
+            return;
+        }
+        match self
+            .selected_connection
+            .and_then(|idx| self.connections.get(idx))
+        {
+            Some(conn) => {
+                self.tag_popup = Some(TagPopup::new(conn));
+                self.dirty = true;
+            }
+            None => self.toast(
+                "Nothing to tag: select a connection with ↑↓",
+                ToastSeverity::Warning,
+            ),
+        }
+    }
+
+    /// Close the tag popup without changing any tag
+    pub fn close_tag_popup(&mut self) {
+        self.tag_popup = None;
+        self.dirty = true;
+    }
+
+    /// Open or close the settings popup
+    pub fn toggle_settings_popup(&mut self) {
+        self.settings_popup = match self.settings_popup {
+            Some(_) => None,
+            None => Some(SettingsPopup::default()),
+        };
+        self.dirty = true;
+    }
+
+    /// Change the highlighted setting one step left (-1) or right (+1);
+    /// crown badges follow on the next frame
+    pub fn adjust_setting(&mut self, delta: isize) {
+        let Some(row) = self.settings_popup.as_ref().map(SettingsPopup::current) else {
+            return;
+        };
+        let heavy_talkers = &mut self.graveyard_settings.heavy_talkers;
+        match row {
+            SettingsRow::HeavyTalkerRule => *heavy_talkers = heavy_talkers.toggled(),
+            SettingsRow::HeavyTalkerCount => *heavy_talkers = heavy_talkers.adjusted(delta),
+            SettingsRow::Profile => {
+                let profile = match self.profile {
+                    Some(profile) => profile.cycled(delta.signum()),
+                    None if delta < 0 => Profile::ALL[Profile::ALL.len() - 1],
+                    None => Profile::ALL[0],
+                };
+                self.apply_profile(profile);
+            }
+        }
+        self.dirty = true;
+    }
+
+    /// Switch filter, panels, refresh rate and look to a profile's preset
+    pub fn apply_profile(&mut self, profile: Profile) {
+        let settings = profile.settings();
+        self.refresh_config.refresh_ms = settings.refresh_ms;
+        self.refresh_config.data_multiplier = settings.data_multiplier;
+        self.refresh_config.last_change = Some(Instant::now());
+        let graveyard = &mut self.graveyard_settings;
+        graveyard.animation_quality = settings.animation_quality;
+        graveyard.animations_enabled = settings.animations_enabled;
+        graveyard.ambiance_enabled = settings.ambiance_enabled;
+        graveyard.overdrive_enabled = settings.overdrive_enabled;
+        self.perf.reduction = None;
+        self.show_port_heatmap = settings.show_port_heatmap;
+        if self.grouping != settings.grouping {
+            self.grouping = settings.grouping;
+            self.expanded_groups.clear();
+        }
+        self.inspector_tab = settings.inspector_tab;
+        self.connection_filter.expression = profile.filter();
+        self.profile = Some(profile);
+        self.apply_filters();
+        self.toast(format!("🎛 Profile: {}", profile), ToastSeverity::Info);
+    }
+
+    /// Put the highlighted tag on the popup's connections, or take it off,
+    /// and close the popup
+    pub fn toggle_selected_tag(&mut self) {
+        let Some(popup) = self.tag_popup.take() else {
+            return;
+        };
+        let tag = popup.current();
+        let summary = if self.tags.toggle_all(&popup.conns, tag) {
+            format!("🏷 Tagged {} {}", popup.target, tag.label())
+        } else {
+            format!("🏷 Removed {} from {}", tag.label(), popup.target)
+        };
+        self.toast(summary, ToastSeverity::Info);
+        if self.tag_filter.is_some() {
+            self.apply_filters();
+        }
+    }
+
+    /// Cycle the tag filter through every tag and back to none
+    pub fn cycle_tag_filter(&mut self) {
+        if self.tag_filter.is_none() && self.tags.is_empty() {
+            self.toast(
+                "Nothing tagged yet: tag a connection with 'n'",
+                ToastSeverity::Warning,
+            );
+            return;
+        }
+        self.tag_filter = tags::next_tag_filter(self.tag_filter);
+        self.apply_filters();
+        self.dirty = true;
+    }
+
+    /// Pin the selected connection's remote endpoint to the watchlist, or
+    /// unpin it; with connections marked, pin all of their endpoints
+    pub fn toggle_pin(&mut self) {
+        let marked = self.marked_connections();
+        if !marked.is_empty() {
+            let before = self.pinned_endpoints.len();
+            self.pinned_endpoints.extend(
+                marked
+                    .iter()
+                    .filter(|conn| self.endpoint_key(conn).is_some())
+                    .map(|conn| conn.remote_addr.clone())
+                    .collect::<Vec<_>>(),
+            );
+            let pinned = self.pinned_endpoints.len() - before;
+            self.toast(
+                format!("📌 Pinned {} endpoints of the marked connections", pinned),
+                ToastSeverity::Info,
+            );
+            return;
+        }
+        let addr = self
+            .selected_connection
+            .and_then(|idx| self.connections.get(idx))
+            .filter(|conn| self.endpoint_key(conn).is_some())
+            .map(|conn| conn.remote_addr.clone());
+        let Some(addr) = addr else {
+            self.toast(
+                "Select a connection to a remote endpoint with ↑↓",
+                ToastSeverity::Warning,
+            );
+            return;
+        };
+        if self.pinned_endpoints.remove(&addr) {
+            self.toast(format!("📌 Unpinned {}", addr), ToastSeverity::Info);
+        } else {
+            self.toast(format!("📌 Pinned {}", addr), ToastSeverity::Info);
+            self.pinned_endpoints.insert(addr);
+        }
+    }
+
+    /// Whether `remote_addr` is pinned to the watchlist
+    pub fn is_pinned(&self, remote_addr: &str) -> bool {
+        self.pinned_endpoints.contains(remote_addr)
+    }
+
+    /// Pinned endpoints, tagged connections and watches with their status
+    /// in the snapshot on screen
+    pub fn watchlist(&self) -> Vec<WatchlistEntry> {
+        let connections = match self.timeline_snapshot() {
+            Some(snapshot) => &snapshot.connections[..],
+            None => &self.raw_connections[..],
+        };
+        watchlist::build(
+            &self.pinned_endpoints,
+            &self.tags,
+            &self.watches,
+            connections,
+        )
+    }
+
+    /// The part of the state `u` restores
+    pub fn view_state(&self) -> ViewState {
+        ViewState {
+            connection_filter: self.connection_filter.clone(),
+            tag_filter: self.tag_filter,
+            graveyard_mode: self.graveyard_mode,
+            selected_process_pid: self.selected_process_pid,
+            include_child_processes: self.include_child_processes,
+            grouping: self.grouping,
+            split_view: self.split_view,
+            seance: self.seance,
+            diff_mode: self.diff_mode,
+            endpoint_page: self.endpoint_page,
+        }
+    }
+
+    /// Push `before` on the undo stack if the view has changed since
+    pub fn record_view_change(&mut self, before: ViewState) {
+        if before != self.view_state() {
+            self.undo_stack.push(before);
+        }
+    }
+
+    /// Go back to the view before the last view-state change
+    pub fn undo_view_change(&mut self) {
+        let Some(view) = self.undo_stack.pop() else {
+            self.toast("Nothing to undo", ToastSeverity::Warning);
+            return;
+        };
+        let pid_changed = view.selected_process_pid != self.selected_process_pid;
+        if view.grouping != self.grouping {
+            self.expanded_groups.clear();
+        }
+        self.connection_filter = view.connection_filter;
+        self.tag_filter = view.tag_filter;
+        self.graveyard_mode = view.graveyard_mode;
+        self.selected_process_pid = view.selected_process_pid;
+        self.include_child_processes = view.include_child_processes;
+        self.grouping = view.grouping;
+        self.split_view = view.split_view;
+        self.seance = view.seance;
+        self.diff_mode = view.diff_mode;
+        self.endpoint_page = view.endpoint_page;
+
+        self.update_focus_pids();
+        self.focus_history.visit(self.focus_step());
+        if pid_changed {
+            self.focused_package = self
+                .selected_process_pid
+                .filter(|_| self.package_resolver.is_available())
+                .map(|pid| self.package_resolver.owner_of_pid(pid));
+        }
+        self.apply_filters();
+        self.toast(
+            format!("↶ View restored ({} more to undo)", self.undo_stack.len()),
+            ToastSeverity::Info,
+        );
+    }
+
+    /// Whether a filter hides part of the snapshot
+    pub fn filter_active(&self) -> bool {
+        self.connection_filter.is_active() || self.tag_filter.is_some()
+    }
+
+    /// Copy the connections on screen (only the marked ones, if any) as a
+    /// Mermaid flowchart
+    pub fn copy_mermaid(&mut self) {
+        let conns = self.export_connections();
+        let chart = mermaid::flowchart(&mermaid::local_host_name(), &conns, &self.tags);
+        self.copy_to_clipboard(&chart, "Copied Mermaid flowchart");
+    }
+
+    /// Write the connections on screen (only the marked ones, if any) as a
+    /// Mermaid flowchart to ntomb-<unix time>.mmd in the working directory
+    pub fn export_mermaid(&mut self) {
+        let conns = self.export_connections();
+        let chart = mermaid::flowchart(&mermaid::local_host_name(), &conns, &self.tags);
+        let secs = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        let path = format!("ntomb-{}.mmd", secs);
+        match std::fs::write(&path, chart) {
+            Ok(()) => self.toast(
+                format!("🧜 Wrote Mermaid flowchart to {}", path),
+                ToastSeverity::Success,
+            ),
+            Err(e) => self.toast(
+                format!("Cannot write {}: {}", path, e),
+                ToastSeverity::Error,
+            ),
+        }
+    }
+
+    /// Load saved settings from `path` and remember it for saving; a saved
+    /// emoji width offset for this $TERM replaces the detected one unless
+    /// NTOMB_EMOJI_WIDTH_OFFSET is set
+    pub fn load_settings(&mut self, path: std::path::PathBuf) {
+        self.settings = Settings::load(&path);
+        self.settings_path = Some(path);
+        let saved = self.settings.emoji_width.get(&settings::terminal_name());
+        if let Some(&offset) = saved {
+            if std::env::var_os("NTOMB_EMOJI_WIDTH_OFFSET").is_none() {
+                self.graveyard_settings.emoji_width_offset = offset;
+            }
+        }
+    }
+
+    /// Use emoji width `offset` and save it for this terminal
+    pub fn set_emoji_width_offset(&mut self, offset: i32) {
+        self.graveyard_settings.emoji_width_offset = offset;
+        let term = settings::terminal_name();
+        self.settings.emoji_width.insert(term.clone(), offset);
+        self.save_settings(format!("Emoji width offset {:+} for {}", offset, term));
+    }
+
+    /// Step the emoji width offset through -1, 0 and +1
+    pub fn cycle_emoji_width_offset(&mut self) {
+        let offset = match self.graveyard_settings.emoji_width_offset {
+            offset if offset >= 1 => -1,
+            offset if offset < -1 => -1,
+            offset => offset + 1,
+        };
+        self.set_emoji_width_offset(offset);
+    }
+
+    /// Go back to the detected emoji width offset and forget the saved one
+    pub fn reset_emoji_width_offset(&mut self) {
+        let offset = crate::ui::emoji_width::get_detected_offset();
+        self.graveyard_settings.emoji_width_offset = offset;
+        self.settings.emoji_width.remove(&settings::terminal_name());
+        self.save_settings(format!("Emoji width offset {:+} (detected)", offset));
+    }
+
+    /// Write settings to disk, confirming with `summary`
+    fn save_settings(&mut self, summary: String) {
+        let Some(path) = self.settings_path.clone() else {
+            self.toast(summary, ToastSeverity::Info);
+            return;
+        };
+        match self.settings.save(&path) {
+            Ok(()) => self.toast(format!("💾 {} (saved)", summary), ToastSeverity::Success),
+            Err(e) => self.toast(
+                format!("Cannot save {}: {}", path.display(), e),
+                ToastSeverity::Error,
+            ),
+        }
+    }
+
+    /// Save the next drawn frame as a screenshot (see `save_screenshot`)
+    pub fn request_screenshot(&mut self) {
+        self.screenshot_requested = true;
+        self.dirty = true;
+    }
+
+    /// Write `buffer`, a frame just drawn, to ntomb-<unix time>.ans (ANSI
+    /// text) and ntomb-<unix time>.html in the working directory
+    pub fn save_screenshot(&mut self, buffer: &ratatui::buffer::Buffer) {
+        self.screenshot_requested = false;
+        let secs = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        let ansi_path = format!("ntomb-{}.ans", secs);
+        let html_path = format!("ntomb-{}.html", secs);
+        let written = std::fs::write(&ansi_path, crate::ui::screenshot::to_ansi(buffer))
+            .map_err(|e| (ansi_path.clone(), e))
+            .and_then(|()| {
+                std::fs::write(&html_path, crate::ui::screenshot::to_html(buffer))
+                    .map_err(|e| (html_path.clone(), e))
+            });
+        match written {
+            Ok(()) => self.toast(
+                format!("📸 Saved screenshot to {} and {}", ansi_path, html_path),
+                ToastSeverity::Success,
+            ),
+            Err((path, e)) => self.toast(
+                format!("Cannot write {}: {}", path, e),
+                ToastSeverity::Error,
+            ),
+        }
+    }
+
+    /// Put `text` on the clipboard and confirm with `summary` in the status bar
+    fn copy_to_clipboard(&mut self, text: &str, summary: &str) {
+        match clipboard::copy(text) {
+            Ok(method) => self.toast(
+                format!("📋 {} ({})", summary, method),
+                ToastSeverity::Success,
+            ),
+            Err(e) => self.toast(format!("Copy failed: {}", e), ToastSeverity::Error),
+        }
+    }
+
+    /// Register the enrichers for the current config: containers (Linux),
+    /// interfaces and service names always, endpoint rules, GeoIP and the
+    /// asset inventory when configured
+    ///
+    /// Called again when a config reload changes the rules or inventory.
+    pub fn rebuild_enrichers(&mut self) {
+        let mut enrichers = EnrichmentPipeline::default();
+        #[cfg(target_os = "linux")]
+        enrichers.register(enrich::ContainerEnricher::default());
+        #[cfg(target_os = "linux")]
+        enrichers.register(enrich::TransportEnricher::default());
+        enrichers.register(enrich::InterfaceEnricher);
+        enrichers.register(enrich::ServiceEnricher);
+        if !self.endpoint_rules.is_empty() {
+            enrichers.register(enrich::CidrRuleEnricher::new(self.endpoint_rules.clone()));
+        }
+        if !self.asn_database.is_empty() {
+            enrichers.register(enrich::GeoIpEnricher::new(self.asn_database.clone()));
+        }
+        if !self.inventory.is_empty() {
+            enrichers.register(enrich::InventoryEnricher::new(self.inventory.clone()));
+        }
+        tracing::debug!(enrichers = ?enrichers, "Enrichment pipeline");
+        self.enrichers = enrichers;
+    }
+
+    /// Follow socket destroy events for `[tombstones]`; a failure to start
+    /// is reported in the diagnostics panel
+    pub fn set_tombstones(&mut self, mut tombstones: Tombstones) {
+        if let Err(e) = tombstones.start() {
+            tracing::warn!(error = %e, "Cannot follow socket destroy events");
+            self.backend_health
+                .record(DiagnosticKind::of(&e), "tombstones", e.to_string());
+        }
+        self.tombstones = Some(tombstones);
+    }
+
+    /// Record the connections destroyed since the last refresh that
+    /// neither it nor `conns` saw, logging the first one to each address
+    fn record_tombstones(&mut self, conns: &[Connection]) {
+        let Some(ref mut tombstones) = self.tombstones else {
+            return;
+        };
+        let (destroyed, stopped) = tombstones.drain();
+        if let Some(status) = stopped {
+            self.backend_health.record(
+                DiagnosticKind::Permission,
+                "tombstones",
+                format!("ss -E stopped ({}); destroy events need root", status),
+            );
+        }
+        if destroyed.is_empty() {
+            return;
+        }
+        let seen: HashSet<ConnectionKey> = conns
+            .iter()
+            .chain(&self.raw_connections)
+            .map(connection_key)
+            .collect();
+        let first = tombstones.record(
+            destroyed,
+            |conn| seen.contains(&connection_key(conn)),
+            SystemTime::now(),
+        );
+        for tombstone in first {
+            let conn = tombstone.conn;
+            self.events.push(
+                GrimoireEvent::new(
+                    EventSeverity::Info,
+                    format!(
+                        "Short-lived connection to {}:{} opened and closed between refreshes",
+                        conn.remote_addr, conn.remote_port
+                    ),
+                )
+                .with_remote(conn.remote_addr.to_string()),
+            );
+        }
+    }
+
+    /// Write the snapshot to the `[autosave]` directory when a save is due
+    fn autosave_snapshot(&mut self) {
+        let Some(ref mut autosave) = self.autosave else {
+            return;
+        };
+        if !autosave.due(Instant::now()) {
+            return;
+        }
+        if let Err(e) = autosave.save(&self.raw_connections, SystemTime::now()) {
+            tracing::warn!(error = %e, "Snapshot autosave failed");
+            self.backend_health
+                .record(DiagnosticKind::of(&e), "autosave", e.to_string());
+        }
+    }
+
+    /// Remember the snapshot's peers and log a Grimoire event for each
+    /// address the host never talked to before
+    fn record_new_peers(&mut self) {
+        let Some(ref mut peers) = self.peers else {
+            return;
+        };
+        let new_peers = peers.observe(&self.raw_connections, SystemTime::now());
+        peers.save_if_due(Instant::now());
+        for addr in new_peers {
+            let Some(conn) = self.raw_connections.iter().find(|c| c.remote_addr == addr) else {
+                continue;
+            };
+            let owner = match (&conn.process_name, conn.pid) {
+                (Some(name), Some(pid)) => format!(" ({}/{})", name, pid),
+                _ => String::new(),
+            };
+            self.events.push(
+                GrimoireEvent::new(
+                    EventSeverity::Info,
+                    format!("New peer {}:{}{}", addr, conn.remote_port, owner),
+                )
+                .with_pid(conn.pid)
+                .with_remote(addr.to_string()),
+            );
+        }
+    }
+
+    /// Whether `addr` showed up for the first time within the last hour
+    pub fn is_new_peer(&self, addr: &str) -> bool {
+        self.peers
+            .as_ref()
+            .is_some_and(|peers| peers.is_new(addr, SystemTime::now()))
+    }
+
+    /// Log a Grimoire event the first time each root-owned wildcard
+    /// listener is seen
+    fn record_exposed_listeners(&mut self) {
+        for conn in &self.raw_connections {
+            if !conn.is_root_wildcard_listener() {
+                continue;
+            }
+            let key = (conn.local_addr.clone(), conn.local_port);
+            if self.seen_exposed_listeners.insert(key) {
+                let owner = match (&conn.process_name, conn.pid) {
+                    (Some(name), Some(pid)) => format!(" ({}/{})", name, pid),
+                    _ => String::new(),
+                };
+                self.events.push(
+                    GrimoireEvent::new(
+                        EventSeverity::Warning,
+                        format!(
+                            "Root listener exposed on {}:{}{}",
+                            conn.local_addr, conn.local_port, owner
+                        ),
+                    )
+                    .with_pid(conn.pid),
+                );
+            }
+        }
+    }
+
+    /// Log a Grimoire event the first time a process matching an
+    /// environment alert talks to an endpoint in that environment
+    ///
+    /// Each (process, remote address) pair is reported once per session.
+    fn record_environment_contacts(&mut self) {
+        if self.environment_alerts.is_empty() {
+            return;
+        }
+        for conn in &self.raw_connections {
+            if conn.state == net::ConnectionState::Listen {
+                continue;
+            }
+            let Some(environment) =
+                endpoint_rules::environment_of(&self.endpoint_rules, &conn.remote_addr)
+            else {
+                continue;
+            };
+            let Some(alert) = self
+                .environment_alerts
+                .iter()
+                .find(|alert| alert.matches(conn, environment))
+            else {
+                continue;
+            };
+
+            let key = (conn.process_name.clone(), conn.remote_addr.clone());
+            if !self.seen_environment_contacts.insert(key) {
+                continue;
+            }
+            let process = match (&conn.process_name, conn.pid) {
+                (Some(name), Some(pid)) => format!("{}({})", name, pid),
+                (Some(name), None) => name.to_string(),
+                _ => "Unknown process".to_string(),
+            };
+            self.events.push(
+                GrimoireEvent::new(
+                    alert.severity,
+                    format!(
+                        "{} talked to env={} ({}:{})",
+                        process, environment, conn.remote_addr, conn.remote_port
+                    ),
+                )
+                .with_pid(conn.pid)
+                .with_remote(conn.remote_addr.to_string()),
+            );
+        }
+    }
+
+    /// Update half-open socket rates and log storm start/end
+    fn record_syn_storm(&mut self) {
+        let change = self
+            .syn_storm
+            .observe(&self.raw_connections, Instant::now());
+        let event = match change {
+            Some(SynStormChange::Started) => GrimoireEvent::new(
+                EventSeverity::Critical,
+                format!("SYN storm: {}", self.syn_storm.status.summary()),
+            ),
+            Some(SynStormChange::Ended) => {
+                GrimoireEvent::new(EventSeverity::Info, "SYN storm subsided")
+            }
+            None => return,
+        };
+        self.events.push(event);
+        self.dirty = true;
+    }
+
+    /// Evaluate every watch against the snapshot and log threshold crossings
+    fn record_watches(&mut self) {
+        for watch in &mut self.watches {
+            let event = match watch.evaluate(&self.raw_connections) {
+                Some(WatchChange::Fired) => GrimoireEvent::new(
+                    watch.severity,
+                    format!("Watch {} fired: {}", watch.name, watch.condition()),
+                ),
+                Some(WatchChange::Cleared) => GrimoireEvent::new(
+                    EventSeverity::Info,
+                    format!("Watch {} cleared: {}", watch.name, watch.condition()),
+                ),
+                None => continue,
+            };
+            if watch.notify && watch.firing {
+                notify::desktop_notify(
+                    &format!("ntomb watch {}", watch.name),
+                    &format!("{} ({})", watch.condition(), watch.source),
+                );
+            }
+            self.events.push(event);
+            self.dirty = true;
+        }
+    }
+
+    /// Show the user script every connection that opened, closed or
+    /// changed state since the previous snapshot
+    ///
+    /// The first snapshot is skipped: every socket would look new.
+    #[cfg(feature = "scripting")]
+    fn run_connection_hooks(&mut self, conns: &[Connection]) {
+        let Some(hooks) = self.events.script_hooks() else {
+            return;
+        };
+        if self.history.len() < 2 {
+            return;
+        }
+        for (event, conn) in stream::connection_events(&self.raw_connections, conns) {
+            match hooks.on_connection(event, conn) {
+                Ok(verdict) => {
+                    if let Some(event) = verdict.connection_event(event, conn) {
+                        self.events.record(event);
+                        self.dirty = true;
+                    }
+                }
+                Err(e) => self.events.script_error(e),
+            }
+        }
+    }
+
+    /// Publish the snapshot's metrics to the OTLP collector
+    #[cfg(feature = "otlp")]
+    fn export_metrics(&mut self) {
+        let Some(ref mut exporter) = self.otlp else {
+            return;
+        };
+        let event = match exporter.record(&self.raw_connections, &self.latency_config) {
+            Some(Ok(message)) => GrimoireEvent::new(EventSeverity::Info, message),
+            Some(Err(e)) => GrimoireEvent::new(EventSeverity::Warning, e),
+            None => return,
+        };
+        self.events.push(event);
+        self.dirty = true;
+    }
+
+    /// Refresh the blocklist and match the snapshot's remote addresses
+    ///
+    /// The first connection to each haunted address is logged as a
+    /// Critical event; lookups never leave the machine.
+    fn update_haunted_endpoints(&mut self) {
+        let Some(ref mut intel) = self.threat_intel else {
+            return;
+        };
+        for update in intel.poll(&mut self.probes) {
+            let event = match update {
+                ThreatIntelUpdate::Loaded { entries, skipped } => {
+                    let mut message = format!(
+                        "Threat list loaded: {} entries from {}",
+                        entries,
+                        intel.path().display()
+                    );
+                    if skipped > 0 {
+                        message.push_str(&format!(" ({} lines skipped)", skipped));
+                    }
+                    GrimoireEvent::new(EventSeverity::Info, message)
+                }
+                ThreatIntelUpdate::Error(e) => {
+                    GrimoireEvent::new(EventSeverity::Warning, format!("Threat list: {}", e))
+                }
+            };
+            self.events.push(event);
+        }
+
+        let mut haunted = HashMap::new();
+        for conn in &self.raw_connections {
+            if haunted.contains_key(&conn.remote_addr) {
+                continue;
+            }
+            let Some(note) = intel.lookup(&conn.remote_addr) else {
+                continue;
+            };
+            haunted.insert(conn.remote_addr.clone(), note.to_string());
+
+            if self.seen_haunted_endpoints.insert(conn.remote_addr.clone()) {
+                let owner = contact_owner(conn);
+                let note = if note.is_empty() {
+                    String::new()
+                } else {
+                    format!(" [{}]", note)
+                };
+                self.events.push(
+                    GrimoireEvent::new(
+                        EventSeverity::Critical,
+                        format!(
+                            "Haunted endpoint {}:{} contacted{}{}",
+                            conn.remote_addr, conn.remote_port, owner, note
+                        ),
+                    )
+                    .with_pid(conn.pid)
+                    .with_remote(conn.remote_addr.to_string()),
+                );
+            }
+        }
+        if haunted != self.haunted_endpoints {
+            self.haunted_endpoints = haunted;
+            self.dirty = true;
+        }
+    }
+
+    /// Match the snapshot's remote addresses against the cloud ranges
+    ///
+    /// Addresses already seen in the previous snapshot keep their result,
+    /// so only new endpoints are looked up.
+    fn update_cloud_endpoints(&mut self) {
+        if self.cloud_ranges.is_empty() {
+            return;
+        }
+        let mut cloud = HashMap::new();
+        for conn in &self.raw_connections {
+            if cloud.contains_key(&conn.remote_addr) {
+                continue;
+            }
+            let range = match self.cloud_endpoints.get(&conn.remote_addr) {
+                Some(range) => Some(range.clone()),
+                None => self.cloud_ranges.lookup(&conn.remote_addr).cloned(),
+            };
+            if let Some(range) = range {
+                cloud.insert(conn.remote_addr.clone(), range);
+            }
+        }
+        if cloud != self.cloud_endpoints {
+            self.cloud_endpoints = cloud;
+            self.dirty = true;
+        }
+    }
+
+    /// Look up the AS of the snapshot's remote addresses
+    ///
+    /// Like the cloud ranges, known addresses keep their previous result.
+    fn update_asn_endpoints(&mut self) {
+        if self.asn_database.is_empty() {
+            return;
+        }
+        let mut asn = HashMap::new();
+        for conn in &self.raw_connections {
+            if asn.contains_key(&conn.remote_addr) {
+                continue;
+            }
+            let info = match self.asn_endpoints.get(&conn.remote_addr) {
+                Some(info) => Some(info.clone()),
+                None => self.asn_database.lookup(&conn.remote_addr).cloned(),
+            };
+            if let Some(info) = info {
+                asn.insert(conn.remote_addr.clone(), info);
+            }
+        }
+        if asn != self.asn_endpoints {
+            self.asn_endpoints = asn;
+            self.dirty = true;
+        }
+    }
+
+    /// Score the snapshot's connections against their processes'
+    /// baselines and log a Grimoire warning for each new one scoring
+    /// `[anomaly] alert_score` or more
+    fn record_anomalies(&mut self) {
+        let asn_endpoints = &self.asn_endpoints;
+        let alerts = self.anomaly.observe(
+            &self.raw_connections,
+            |addr| asn_endpoints.get(addr).map(|info| info.number),
+            SystemTime::now(),
+        );
+        for (conn, anomaly) in alerts {
+            self.events.push(
+                GrimoireEvent::new(
+                    EventSeverity::Warning,
+                    format!(
+                        "Anomaly {} for {} → {}:{} ({})",
+                        anomaly.score,
+                        conn.process_name.as_deref().unwrap_or("?"),
+                        conn.remote_addr,
+                        conn.remote_port,
+                        anomaly.reasons.join(", ")
+                    ),
+                )
+                .with_pid(conn.pid)
+                .with_remote(conn.remote_addr.to_string()),
+            );
+        }
+    }
+
+    /// AS announcing `remote_addr`, if known
+    pub fn asn(&self, remote_addr: &str) -> Option<&Arc<AsInfo>> {
+        self.asn_endpoints.get(remote_addr)
+    }
+
+    /// Queue the snapshot's remote addresses for reverse lookups
+    /// within the probe budget; the rest wait for the next refresh
+    fn update_reverse_dns(&mut self) {
+        let Some(ref mut resolver) = self.reverse_dns else {
+            return;
+        };
+        if self.probes.passive_only() {
+            return;
+        }
+        let now = Instant::now();
+        for conn in &self.raw_connections {
+            if resolver.wants(&conn.remote_addr) && self.probes.allow(ProbeKind::Dns, now) {
+                resolver.request(&conn.remote_addr);
+            }
+        }
+    }
+
+    /// Collect finished DNS, RDAP and package lookups, keeping them in the
+    /// `[cache]`
+    fn poll_lookups(&mut self) {
+        let now = SystemTime::now();
+        if let Some(ref mut resolver) = self.reverse_dns {
+            let answered = resolver.poll();
+            self.dirty |= !answered.is_empty();
+            if let Some(ref mut cache) = self.enrich_cache {
+                for addr in answered {
+                    let name = resolver.name(&addr).map(|n| n.name.to_string());
+                    cache.put(CacheKind::Dns, &addr, name.into_iter().collect(), now);
+                }
+            }
+        }
+        if let Some(ref mut rdap) = self.rdap {
+            let answered = rdap.poll();
+            self.dirty |= !answered.is_empty();
+            if let Some(ref mut cache) = self.enrich_cache {
+                for addr in answered {
+                    if let Some(RdapLookup::Done(record)) = rdap.lookup(&addr) {
+                        cache.put(CacheKind::Rdap, &addr, record.cache_fields(), now);
+                    }
+                }
+            }
+        }
+        if !self.package_resolver.poll().is_empty() {
+            if let Some(PackageOwner::Pending) = self.focused_package {
+                self.focused_package = self
+                    .selected_process_pid
+                    .map(|pid| self.package_resolver.owner_of_pid(pid));
+                self.dirty = true;
+            }
+        }
+        let packages = self.package_resolver.take_resolved();
+        if let Some(ref mut cache) = self.enrich_cache {
+            for (path, owner) in packages {
+                if let Some(fields) = owner.cache_fields() {
+                    let path: Arc<str> = Arc::from(path.to_string_lossy());
+                    cache.put(CacheKind::Package, &path, fields, now);
+                }
+            }
+            cache.save_if_due(Instant::now());
+        }
+    }
+
+    /// Start using the on-disk enrichment cache, seeding the resolvers
+    /// with what it holds; call after `reverse_dns` is set up
+    pub fn set_enrich_cache(&mut self, cache: EnrichCache) {
+        let now = SystemTime::now();
+        if let Some(ref mut resolver) = self.reverse_dns {
+            for (addr, entry) in cache.entries(CacheKind::Dns) {
+                let stale = cache.age_if_stale(entry, now).is_some();
+                resolver.seed(
+                    addr.clone(),
+                    entry.fields.first().map(String::as_str),
+                    stale,
+                );
+            }
+        }
+        for (addr, entry) in cache.entries(CacheKind::Rdap) {
+            let record = RdapRecord::from_cache_fields(&entry.fields);
+            self.rdap
+                .get_or_insert_with(Rdap::spawn)
+                .seed(addr.clone(), record);
+        }
+        for (path, entry) in cache.entries(CacheKind::Package) {
+            // Stale owners are cheap to query again
+            if cache.age_if_stale(entry, now).is_some() {
+                continue;
+            }
+            if let Some(owner) = PackageOwner::from_cache_fields(&entry.fields) {
+                self.package_resolver.seed(PathBuf::from(&**path), owner);
+            }
+        }
+        self.enrich_cache = Some(cache);
+    }
+
+    /// Age of the cached `kind` lookup of `key` when it is past the
+    /// `[cache]` TTL, so it can be shown as stale
+    pub fn stale_enrichment(&self, kind: CacheKind, key: &str) -> Option<Duration> {
+        self.enrich_cache
+            .as_ref()?
+            .stale_age(kind, key, SystemTime::now())
+    }
+
+    /// Show who owns the highlighted connection's remote address (`K`),
+    /// looking it up unless it already was; failed lookups are retried
+    pub fn open_rdap_lookup(&mut self) {
+        if self.rdap_popup.take().is_some() {
+            self.dirty = true;
+            return;
+        }
+        let Some(addr) = self.highlighted_connection().map(|c| c.remote_addr.clone()) else {
+            self.toast("Select a connection with ↑↓", ToastSeverity::Warning);
+            return;
+        };
+        if address_scope(&addr) != Some(AddressScope::Public) {
+            self.toast(
+                format!("{} is not a public address", addr),
+                ToastSeverity::Warning,
+            );
+            return;
+        }
+        let known = self
+            .rdap
+            .as_ref()
+            .and_then(|rdap| rdap.lookup(&addr))
+            .is_some_and(|lookup| !matches!(lookup, RdapLookup::Failed(_)))
+            && self.stale_enrichment(CacheKind::Rdap, &addr).is_none();
+        if !known {
+            if self.probes.passive_only() {
+                self.toast(
+                    "Passive only: RDAP lookups are off ([probes])",
+                    ToastSeverity::Warning,
+                );
+                return;
+            }
+            if !self.probes.allow(ProbeKind::Rdap, Instant::now()) {
+                self.toast(
+                    "Probe budget spent, try again in a moment",
+                    ToastSeverity::Warning,
+                );
+                return;
+            }
+            if !self.rdap.get_or_insert_with(Rdap::spawn).request(&addr) {
+                self.toast("RDAP lookup thread is gone", ToastSeverity::Error);
+                return;
+            }
+        }
+        self.rdap_popup = Some(addr);
+        self.dirty = true;
+    }
+
+    /// RDAP lookup of `addr`, if one was requested
+    pub fn rdap_lookup(&self, addr: &str) -> Option<&RdapLookup> {
+        self.rdap.as_ref()?.lookup(addr)
+    }
+
+    /// Reverse DNS name of `remote_addr`, once resolved
+    pub fn reverse_name(&self, remote_addr: &str) -> Option<&ReverseName> {
+        self.reverse_dns.as_ref()?.name(remote_addr)
+    }
+
+    /// Graveyard node `conn` is drawn under, and whether it is a group:
+    /// its collapsed group, else its remote address; None for listeners
+    pub fn endpoint_key(&self, conn: &Connection) -> Option<(Arc<str>, bool)> {
+        if conn.state == net::ConnectionState::Listen || &*conn.remote_addr == "0.0.0.0" {
+            return None;
+        }
+        let group = self
+            .endpoint_group(conn)
+            .filter(|group| !self.expanded_groups.contains(group));
+        Some(match group {
+            Some(group) => (group, true),
+            None => (Arc::clone(&conn.remote_addr), false),
+        })
+    }
+
+    /// Open the detail popup for the selected connection's Graveyard node
+    pub fn open_endpoint_detail(&mut self) {
+        let key = self
+            .selected_connection
+            .and_then(|idx| self.connections.get(idx))
+            .and_then(|conn| self.endpoint_key(conn));
+        match key {
+            Some((key, is_group)) => {
+                self.endpoint_detail = Some(self.endpoint_detail_for(key, is_group));
+                self.dirty = true;
+            }
+            None => self.toast(
+                "Select a connection to a remote endpoint with ↑↓",
+                ToastSeverity::Warning,
+            ),
+        }
+    }
+
+    /// Narrow the Grimoire event feed to the selected connection's process
+    /// and remote address
+    pub fn narrow_events_to_selected(&mut self) {
+        let Some(conn) = self
+            .selected_connection
+            .and_then(|idx| self.connections.get(idx))
+        else {
+            return;
+        };
+        // Listeners have no remote end to speak of
+        let remote_addr = self
+            .endpoint_key(conn)
+            .map(|_| conn.remote_addr.to_string());
+        let subject = EventSubject {
+            pid: conn.pid,
+            remote_addr,
+        };
+        if subject.pid.is_some() || subject.remote_addr.is_some() {
+            self.event_feed.set_subject(Some(subject));
+            self.dirty = true;
+        }
+    }
+
+    /// Close the endpoint detail popup
+    pub fn close_endpoint_detail(&mut self) {
+        self.endpoint_detail = None;
+        self.dirty = true;
+    }
+
+    /// Rebuild the open detail popup from the current connections
+    fn refresh_endpoint_detail(&mut self) {
+        if let Some(detail) = self.endpoint_detail.take() {
+            self.endpoint_detail = Some(self.endpoint_detail_for(detail.key, detail.is_group));
+        }
+    }
+
+    fn endpoint_detail_for(&self, key: Arc<str>, is_group: bool) -> EndpointDetail {
+        let connections = self
+            .connections
+            .iter()
+            .filter(|conn| self.endpoint_key(conn).is_some_and(|(k, _)| k == key))
+            .cloned()
+            .collect();
+        EndpointDetail::new(
+            key,
+            is_group,
+            connections,
+            &self.history,
+            &self.state_timelines,
+        )
+    }
+
+    /// Group `conn`'s remote end is folded into under the current grouping
+    ///
+    /// Endpoints named by an endpoint rule are never grouped.
+    pub fn endpoint_group(&self, conn: &Connection) -> Option<Arc<str>> {
+        let remote_addr = &*conn.remote_addr;
+        let group = match self.grouping {
+            EndpointGrouping::Off => return None,
+            EndpointGrouping::Subnet => grouping::subnet(remote_addr)?.into(),
+            EndpointGrouping::Asn => self.asn(remote_addr)?.org.clone(),
+            EndpointGrouping::Process => conn.process_name.clone()?,
+            EndpointGrouping::Country => {
+                let country = &self.asn(remote_addr)?.country;
+                if country.is_empty() {
+                    return None;
+                }
+                country.clone()
+            }
+            EndpointGrouping::Domain => self.reverse_name(remote_addr)?.domain.clone(),
+        };
+        if endpoint_rules::match_rule(&self.endpoint_rules, remote_addr).is_some() {
+            return None;
+        }
+        Some(group)
+    }
+
+    /// Whether the detail popup's node is a group, or an expanded group's member
+    pub fn detail_in_group(&self, detail: &EndpointDetail) -> bool {
+        detail.is_group
+            || detail
+                .rows
+                .first()
+                .is_some_and(|row| self.endpoint_group(&row.conn).is_some())
+    }
+
+    /// Visible connections per group, busiest first
+    pub fn endpoint_groups(&self) -> Vec<EndpointGroup> {
+        grouping::aggregate(&self.connections, |conn| {
+            let group = self.endpoint_group(conn)?;
+            let tag = match self.grouping {
+                EndpointGrouping::Asn | EndpointGrouping::Country => self
+                    .asn(&conn.remote_addr)
+                    .map(|a| format!("AS{}", a.number)),
+                EndpointGrouping::Process => conn.pid.map(|pid| format!("pid {}", pid)),
+                _ => None,
+            };
+            Some((group, tag))
+        })
+    }
+
+    /// Cycle the Graveyard grouping through the modes with data available
+    pub fn cycle_grouping(&mut self) {
+        let available = |mode: EndpointGrouping| match mode {
+            EndpointGrouping::Off | EndpointGrouping::Subnet | EndpointGrouping::Process => true,
+            EndpointGrouping::Asn | EndpointGrouping::Country => !self.asn_database.is_empty(),
+            EndpointGrouping::Domain => self.reverse_dns.is_some(),
+        };
+        let mut next = self.grouping.next();
+        while !available(next) {
+            next = next.next();
+        }
+        self.grouping = next;
+        self.expanded_groups.clear();
+        self.dirty = true;
+    }
+
+    /// Switch between the Graveyard and the services/outbound split view
+    pub fn toggle_split_view(&mut self) {
+        self.split_view = !self.split_view;
+        self.seance = false;
+        self.dirty = true;
+    }
+
+    /// Show or hide the port heatmap
+    pub fn toggle_port_heatmap(&mut self) {
+        self.show_port_heatmap = !self.show_port_heatmap;
+        self.dirty = true;
+    }
+
+    /// Local port usage of the connections on screen, with the port space
+    /// cut into `buckets` slices
+    pub fn port_heatmap(&self, buckets: usize) -> PortHeatmap {
+        PortHeatmap::build(&self.connections, &self.ephemeral_ports, buckets)
+    }
+
+    /// Switch between the Graveyard and the Séance grid
+    pub fn toggle_seance(&mut self) {
+        self.seance = !self.seance;
+        self.split_view = false;
+        self.dirty = true;
+    }
+
+    /// Mini-graveyards of the busiest processes for the Séance grid
+    pub fn seance_cells(&self) -> Vec<SeanceCell> {
+        seance::busiest_processes(&self.connections, config::SEANCE_PROCESSES, |conn| {
+            self.endpoint_key(conn).map(|(key, _)| key)
+        })
+    }
+
+    /// Expand or collapse the group of the selected connection
+    pub fn toggle_group_expansion(&mut self) {
+        let group = self
+            .selected_connection
+            .and_then(|idx| self.connections.get(idx))
+            .and_then(|conn| self.endpoint_group(conn));
+        let Some(group) = group else {
+            self.toast(
+                "Select a connection in a group with ↑↓",
+                ToastSeverity::Warning,
+            );
+            return;
+        };
+        if !self.expanded_groups.remove(&group) {
+            self.expanded_groups.insert(group);
+        }
+        self.dirty = true;
+    }
+
+    /// Flag remote addresses that are Tor exits or VPN endpoints
+    ///
+    /// The first connection to each one is logged as a Warning.
+    fn update_anonymous_endpoints(&mut self) {
+        if self.anonymizers.is_empty() {
+            return;
+        }
+        let mut anonymous = HashMap::new();
+        for conn in &self.raw_connections {
+            if anonymous.contains_key(&conn.remote_addr) {
+                continue;
+            }
+            let Some(anonymizer) = self.anonymizers.lookup(&conn.remote_addr) else {
+                continue;
+            };
+            if self
+                .seen_anonymous_endpoints
+                .insert(conn.remote_addr.clone())
+            {
+                self.events.push(
+                    GrimoireEvent::new(
+                        EventSeverity::Warning,
+                        format!(
+                            "Anonymizer ({}) {}:{} contacted{}",
+                            anonymizer.label(),
+                            conn.remote_addr,
+                            conn.remote_port,
+                            contact_owner(conn)
+                        ),
+                    )
+                    .with_pid(conn.pid)
+                    .with_remote(conn.remote_addr.to_string()),
+                );
+            }
+            anonymous.insert(conn.remote_addr.clone(), anonymizer);
+        }
+        if anonymous != self.anonymous_endpoints {
+            self.anonymous_endpoints = anonymous;
+            self.dirty = true;
+        }
+    }
+
+    /// Tor exit or VPN behind `remote_addr`, if any
+    pub fn anonymizer(&self, remote_addr: &str) -> Option<&Anonymizer> {
+        self.anonymous_endpoints.get(remote_addr)
+    }
+
+    /// Cloud provider range containing `remote_addr`, if any
+    pub fn cloud_range(&self, remote_addr: &str) -> Option<&Arc<CloudRange>> {
+        self.cloud_endpoints.get(remote_addr)
+    }
+
+    /// `[inventory]` asset containing `remote_addr`, if any
+    pub fn inventory_entry(&self, remote_addr: &str) -> Option<&InventoryEntry> {
+        self.inventory.lookup(remote_addr).map(|entry| &**entry)
+    }
+
+    /// Whether `remote_addr` is on the threat-intel blocklist
+    pub fn is_haunted(&self, remote_addr: &str) -> bool {
+        self.haunted_endpoints.contains_key(remote_addr)
+    }
+
+    /// Ticks between pulse steps; None while the pulse stands still
+    /// (animations off or quality Off)
+    fn pulse_every(&self) -> Option<u64> {
+        self.graveyard_settings
+            .animation_quality
+            .pulse_every()
+            .filter(|_| self.graveyard_settings.animations_enabled)
+    }
+
+    /// Whether the Graveyard pulse is moving
+    pub fn pulse_running(&self) -> bool {
+        self.pulse_every().is_some()
+    }
+
+    /// Whether an alert rule points at `conn`: a firing watch counts it,
+    /// or its process's contact with the endpoint set off an environment
+    /// alert
+    pub fn is_alerting(&self, conn: &Connection) -> bool {
+        self.watches.iter().any(|watch| watch.flags(conn))
+            || self
+                .seen_environment_contacts
+                .contains(&(conn.process_name.clone(), conn.remote_addr.clone()))
+    }
+
+    /// Whether `conn` passes the connection and tag filters
+    fn passes_filters(&self, conn: &Connection) -> bool {
+        self.connection_filter.matches(conn) && self.matches_tag_filter(conn)
+    }
+
+    /// Up to TREND_WINDOW recorded snapshots ending with the one on screen,
+    /// oldest first
+    fn trend_window(&self) -> impl Iterator<Item = &Snapshot> {
+        let end = match self.timeline {
+            Some(index) => index + 1,
+            None => self.history.len(),
+        };
+        (end.saturating_sub(TREND_WINDOW)..end).filter_map(|index| self.history.get(index))
+    }
+
+    /// Trend of the number of connections on screen
+    pub fn connection_trend(&self) -> Trend {
+        let counts: Vec<usize> = self
+            .trend_window()
+            .map(|snapshot| {
+                snapshot
+                    .connections
+                    .iter()
+                    .filter(|c| self.passes_filters(c))
+                    .count()
+            })
+            .collect();
+        Trend::of(&counts)
+    }
+
+    /// Trend of each process's connections on screen
+    pub fn process_trends(&self) -> HashMap<i32, Trend> {
+        let window: Vec<HashMap<i32, usize>> = self
+            .trend_window()
+            .map(|snapshot| {
+                let mut counts = HashMap::new();
+                for conn in snapshot
+                    .connections
+                    .iter()
+                    .filter(|c| self.passes_filters(c))
+                {
+                    if let Some(pid) = conn.pid {
+                        *counts.entry(pid).or_default() += 1;
+                    }
+                }
+                counts
+            })
+            .collect();
+        let pids: HashSet<i32> = window
+            .iter()
+            .flat_map(|counts| counts.keys().copied())
+            .collect();
+        pids.into_iter()
+            .map(|pid| {
+                let counts: Vec<usize> = window
+                    .iter()
+                    .map(|counts| counts.get(&pid).copied().unwrap_or(0))
+                    .collect();
+                (pid, Trend::of(&counts))
+            })
+            .collect()
+    }
+
+    /// Rebuild the visible connection list from the last collection
+    ///
+    /// Keeps the selection index within bounds when the list shrinks.
+    pub fn apply_filters(&mut self) {
+        // A paused timeline shows a recorded snapshot instead of live data
+        let source = match self.timeline.and_then(|index| self.history.get(index)) {
+            Some(snapshot) => &snapshot.connections[..],
+            None => &self.raw_connections[..],
+        };
+
+        // The diff view replaces the snapshot with its added/removed sockets
+        let changes = match self.baseline {
+            Some(ref baseline) if self.diff_mode => {
+                let current = self
+                    .comparison
+                    .as_ref()
+                    .map_or(source, |s| &s.connections[..]);
+                Some(snapshot::diff(&baseline.connections, current))
+            }
+            _ => None,
+        };
+
+        match changes {
+            Some(changes) => {
+                self.diff_changes = changes
+                    .iter()
+                    .map(|(change, conn)| (connection_key(conn), *change))
+                    .collect();
+                self.connections = changes
+                    .into_iter()
+                    .map(|(_, conn)| conn)
+                    .filter(|c| self.passes_filters(c))
+                    .collect();
+            }
+            None => {
+                self.diff_changes.clear();
+                self.connections = source
+                    .iter()
+                    .filter(|c| self.passes_filters(c))
+                    .cloned()
+                    .collect();
+            }
+        }
+
+        if let Some(idx) = self.selected_connection {
+            let clamped = if self.connections.is_empty() {
+                None
+            } else {
+                Some(idx.min(self.connections.len() - 1))
+            };
+            self.selected_connection = clamped;
+            let rows = self.connection_rows();
+            let row = clamped.and_then(|idx| rows.iter().position(|r| r.covers(idx)));
+            self.connection_list_state.select(row);
+        }
+    }
+
+    /// Whether `conn` carries the tag the list is narrowed to (if any)
+    fn matches_tag_filter(&self, conn: &Connection) -> bool {
+        self.tag_filter.map_or(true, |tag| self.tags.has(conn, tag))
+    }
+
+    /// Pause on the latest recorded snapshot, or resume live data
+    pub fn toggle_pause(&mut self) {
+        if self.timeline.is_some() {
+            self.timeline = None;
+        } else if self.history.is_empty() {
+            self.toast("Nothing recorded yet", ToastSeverity::Warning);
+            return;
+        } else {
+            self.timeline = Some(self.history.len() - 1);
+        }
+        self.apply_filters();
+        self.dirty = true;
+    }
+
+    /// Step the paused timeline by `delta` refreshes (negative = back)
+    pub fn step_timeline(&mut self, delta: isize) {
+        let Some(index) = self.timeline else {
+            return;
+        };
+        let last = self.history.len().saturating_sub(1);
+        let target = index.saturating_add_signed(delta).min(last);
+        if target != index {
+            self.timeline = Some(target);
+            self.apply_filters();
+            self.dirty = true;
+        }
+    }
+
+    /// Snapshot on screen: the paused timeline's, or the live data
+    fn current_snapshot(&self) -> Snapshot {
+        self.timeline_snapshot()
+            .cloned()
+            .unwrap_or_else(|| Snapshot::capture(&self.raw_connections))
+    }
+
+    /// Snapshot shown by the paused timeline
+    pub fn timeline_snapshot(&self) -> Option<&Snapshot> {
+        self.timeline.and_then(|index| self.history.get(index))
+    }
+
+    /// Mark the current snapshot as the diff baseline
+    ///
+    /// While paused the snapshot on screen is marked, so two points of the
+    /// timeline can be compared. A previously frozen comparison snapshot is dropped, so the diff is
+    /// against live data again.
+    pub fn mark_baseline(&mut self) {
+        let baseline = self.current_snapshot();
+        self.events.push(GrimoireEvent::new(
+            EventSeverity::Info,
+            format!(
+                "Baseline marked at {}: {} connections",
+                baseline.time_label(),
+                baseline.connections.len()
+            ),
+        ));
+        self.baseline = Some(baseline);
+        self.comparison = None;
+        self.apply_filters();
+        self.dirty = true;
+    }
+
+    /// Freeze the current snapshot as the second point of the diff
+    pub fn mark_comparison(&mut self) {
+        let Some(ref baseline) = self.baseline else {
+            self.toast("Mark a baseline with 'm' first", ToastSeverity::Warning);
+            return;
+        };
+        let comparison = self.current_snapshot();
+        self.events.push(GrimoireEvent::new(
+            EventSeverity::Info,
+            format!(
+                "Comparing {} with {}",
+                baseline.time_label(),
+                comparison.time_label()
+            ),
+        ));
+        self.comparison = Some(comparison);
+        self.diff_mode = true;
+        self.apply_filters();
+        self.dirty = true;
+    }
+
+    /// Switch between the full snapshot and the diff against the baseline
+    pub fn toggle_diff_mode(&mut self) {
+        if self.baseline.is_none() {
+            self.toast("Mark a baseline with 'm' first", ToastSeverity::Warning);
+            return;
+        }
+        self.diff_mode = !self.diff_mode;
+        self.apply_filters();
+        self.dirty = true;
+    }
+
+    /// How `conn` differs from the baseline while the diff view is shown
+    pub fn diff_change(&self, conn: &Connection) -> Option<DiffChange> {
+        if self.diff_changes.is_empty() {
+            return None;
+        }
+        self.diff_changes.get(&connection_key(conn)).copied()
+    }
+
+    /// (added, removed) counts of the diff view
+    pub fn diff_counts(&self) -> (usize, usize) {
+        let added = self
+            .diff_changes
+            .values()
+            .filter(|c| **c == DiffChange::Added)
+            .count();
+        (added, self.diff_changes.len() - added)
+    }
+
+    /// Cycle the user filter through the users owning collected sockets
+    pub fn cycle_user_filter(&mut self) {
+        self.connection_filter.user = filter::next_user_filter(
+            self.connection_filter.user.as_deref(),
+            &self.raw_connections,
+        );
+        self.apply_filters();
+    }
+
+    /// Cycle the container filter through the containers seen in the snapshot
+    pub fn cycle_container_filter(&mut self) {
+        self.connection_filter.container = filter::next_container_filter(
+            self.connection_filter.container.as_deref(),
+            &self.raw_connections,
+        );
+        self.apply_filters();
+    }
+
+    /// Cycle the interface filter through the local interfaces in the snapshot
+    pub fn cycle_interface_filter(&mut self) {
+        self.connection_filter.interface = filter::next_interface_filter(
+            self.connection_filter.interface.as_deref(),
+            &self.raw_connections,
+        );
+        self.apply_filters();
+    }
+
+    /// Replace the filter expression; an empty string clears it
+    ///
+    /// On a parse error the current expression is kept.
+    pub fn set_filter_expression(&mut self, input: &str) -> Result<(), FilterParseError> {
+        self.connection_filter.expression = if input.trim().is_empty() {
+            None
+        } else {
+            Some(FilterExpr::parse(input)?)
+        };
+        self.apply_filters();
+        Ok(())
+    }
+
+    /// Open the '/' prompt, pre-filled with the active expression
+    pub fn open_filter_prompt(&mut self) {
+        let current = self
+            .connection_filter
+            .expression
+            .as_ref()
+            .map(|e| e.source().to_string())
+            .unwrap_or_default();
+        self.filter_prompt = Some(current);
+        self.filter_error = None;
+        self.prompt_kind = PromptKind::Filter;
+    }
+
+    /// Open the 'w' prompt for a new watch expression
+    pub fn open_watch_prompt(&mut self) {
+        self.filter_prompt = Some("count(".to_string());
+        self.filter_error = None;
+        self.prompt_kind = PromptKind::Watch;
+    }
+
+    /// Open the '#' prompt for a PID or port to jump to
+    pub fn open_jump_prompt(&mut self) {
+        self.filter_prompt = Some(String::new());
+        self.filter_error = None;
+        self.prompt_kind = PromptKind::Jump;
+    }
+
+    /// Open the '/' prompt of the focused event feed, pre-filled with the
+    /// active search
+    pub fn open_event_search_prompt(&mut self) {
+        self.filter_prompt = Some(self.event_feed.search().unwrap_or_default().to_string());
+        self.filter_error = None;
+        self.prompt_kind = PromptKind::EventSearch;
+    }
+
+    /// Show the next Soul Inspector tab
+    pub fn cycle_inspector_tab(&mut self) {
+        self.inspector_tab = self.inspector_tab.next();
+        self.inspector_sockets.focused &= self.inspector_tab.lists_sockets();
+        self.update_forensics();
+        self.dirty = true;
+    }
+
+    /// PID of the process the Soul Inspector describes: the focused one in
+    /// Process mode, else the selected connection's owner
+    pub fn inspected_pid(&self) -> Option<i32> {
+        match self.graveyard_mode {
+            GraveyardMode::Process => self.selected_process_pid,
+            GraveyardMode::Host => self
+                .selected_connection
+                .and_then(|idx| self.connections.get(idx))
+                .and_then(|conn| conn.pid),
+        }
+    }
+
+    /// Whether `conn` belongs to the inspected target: the focused process
+    /// (and its children), the selected connection's endpoint (a listener
+    /// and the connections accepted on it), or with nothing selected the
+    /// whole host
+    pub fn is_inspected(&self, conn: &Connection) -> bool {
+        match self.graveyard_mode {
+            GraveyardMode::Process => conn.pid.is_some_and(|p| self.focus_pids.contains(&p)),
+            GraveyardMode::Host => {
+                let Some(selected) = self
+                    .selected_connection
+                    .and_then(|idx| self.connections.get(idx))
+                else {
+                    return true;
+                };
+                if selected.state == net::ConnectionState::Listen {
+                    conn.local_port == selected.local_port
+                        && (selected.is_wildcard_listener()
+                            || conn.local_addr == selected.local_addr)
+                } else {
+                    conn.remote_addr == selected.remote_addr
+                }
+            }
+        }
+    }
+
+    /// Indices of the inspected target's connections, as listed by the
+    /// inspector's socket list
+    pub fn inspected_connections(&self) -> Vec<usize> {
+        (0..self.connections.len())
+            .filter(|&idx| self.is_inspected(&self.connections[idx]))
+            .collect()
+    }
+
+    /// Connection whose endpoint the Graveyard highlights: the inspector's
+    /// selected socket while its list is focused, else the selected one
+    pub fn highlighted_connection(&self) -> Option<&Connection> {
+        let idx = if self.inspector_sockets.focused {
+            let inspected = self.inspected_connections();
+            self.inspector_sockets
+                .selected(inspected.len())
+                .map(|i| inspected[i])
+        } else {
+            self.selected_connection
+        };
+        idx.and_then(|idx| self.connections.get(idx))
+    }
+
+    /// Select the inspector's selected socket in the connection list
+    pub fn select_inspected_socket(&mut self) {
+        let inspected = self.inspected_connections();
+        let Some(idx) = self
+            .inspector_sockets
+            .selected(inspected.len())
+            .map(|i| inspected[i])
+        else {
+            return;
+        };
+        let rows = self.connection_rows();
+        if let Some(row) = rows.iter().position(|r| r.covers(idx)) {
+            self.select_row(&rows, row);
+            self.inspector_sockets.focused = false;
+            self.dirty = true;
+        }
+    }
+
+    /// Re-read forensics when the Forensics tab shows another process, and
+    /// socket options when it shows another connection
+    /// than last time; nothing is read while another tab is shown
+    fn update_forensics(&mut self) {
+        if self.inspector_tab != InspectorTab::Forensics {
+            return;
+        }
+        let conn = self
+            .highlighted_connection()
+            .filter(|c| c.state != net::ConnectionState::Listen);
+        let key = conn.map(connection_key);
+        if self.socket_info.as_ref().map(|(k, _)| k) != key.as_ref() {
+            let info = conn.map(read_socket_info);
+            self.socket_info = key.zip(info);
+            self.dirty = true;
+        }
+        let pid = self.inspected_pid();
+        if self.forensics.as_ref().map(|f| f.pid) == pid {
+            return;
+        }
+        self.forensics = pid.map(|pid| self.forensics_reader.read(pid));
+        self.dirty = true;
+    }
+
+    /// Move keyboard focus on: connection list → event feed → inspector
+    /// socket list (when the tab shows one) → connection list; backwards
+    /// with `forward` false
+    pub fn cycle_focus(&mut self, forward: bool) {
+        let current = if self.event_feed.focused {
+            1
+        } else if self.inspector_sockets.focused {
+            2
+        } else {
+            0
+        };
+        let panes = if self.inspector_tab.lists_sockets() {
+            3
+        } else {
+            2
+        };
+        let next = if forward {
+            (current + 1) % panes
+        } else {
+            (current + panes - 1) % panes
+        };
+        self.event_feed.focused = next == 1;
+        self.inspector_sockets.focused = next == 2;
+        self.dirty = true;
+    }
+
+    /// Select the first connection of the PID or on the port typed at the
+    /// '#' prompt (see `jump.rs`), entering Process mode when asked to
+    pub fn jump_to(&mut self, input: &str) -> Result<(), String> {
+        let jump = Jump::parse(input)?;
+        let idx = jump
+            .find(&self.connections)
+            .ok_or_else(|| format!("no connection matches '{}'", input.trim()))?;
+        let rows = self.connection_rows();
+        if let Some(row) = rows.iter().position(|r| r.covers(idx)) {
+            self.select_row(&rows, row);
+        }
+        // A folded pool row shows its first member; point at the socket found
+        self.selected_connection = Some(idx);
+        if jump.focus {
+            self.focus_process_of_selected_connection();
+        }
+        self.dirty = true;
+        Ok(())
+    }
+
+    /// Add a watch typed as `count(<filter>) <op> <number>`
+    ///
+    /// It is evaluated right away so the watch bar shows a value before the
+    /// next data refresh.
+    pub fn add_watch(&mut self, input: &str) -> Result<(), String> {
+        let mut watch = Watch::parse(input)?;
+        if let Some(WatchChange::Fired) = watch.evaluate(&self.raw_connections) {
+            self.events.push(GrimoireEvent::new(
+                watch.severity,
+                format!("Watch {} fired: {}", watch.name, watch.condition()),
+            ));
+        }
+        self.watches.push(watch);
+        self.dirty = true;
+        Ok(())
+    }
+
+    /// Remove every watch
+    pub fn clear_watches(&mut self) {
+        if !self.watches.is_empty() {
+            self.watches.clear();
+            self.toast("👁 Watches cleared", ToastSeverity::Info);
+        }
+    }
+
+    /// Close the '/' prompt without changing the filter
+    pub fn cancel_filter_prompt(&mut self) {
+        self.filter_prompt = None;
+        self.filter_error = None;
+    }
 
-                self.connections = conns;
-                self.conn_error = None;
-            }
+    /// Apply the expression typed at the prompt (filter, new watch or jump)
+    ///
+    /// Invalid expressions keep the prompt open with the parse error shown
+    /// in the status bar so the input can be corrected.
+    pub fn submit_filter_prompt(&mut self) {
+        let Some(input) = self.filter_prompt.take() else {
+            return;
+        };
+        let result = match self.prompt_kind {
+            PromptKind::Filter => self
+                .set_filter_expression(&input)
+                .map_err(|e| e.to_string()),
+            PromptKind::Watch => self.add_watch(&input),
+            PromptKind::Jump => self.jump_to(&input),
+            PromptKind::EventSearch => self.event_feed.set_search(&self.events, &input),
+        };
+        match result {
+            Ok(()) => self.filter_error = None,
             Err(e) => {
-                // Gracefully handle errors - don't panic
-                // Following security-domain: calm, informative tone
-                self.conn_error = Some(format!(
-                    "Cannot read /proc/net/tcp: {} (permission or OS issue)",
-                    e
-                ));
-                // Keep existing connections if refresh fails
+                self.filter_error = Some(e);
+                self.filter_prompt = Some(input);
             }
         }
     }
 
-    /// Update traffic history based on real connection activity
-    ///
-    /// Tracks actual connection activity metrics with natural variation:
-    /// - Number of ESTABLISHED connections (weighted heavily)
-    /// - Number of LISTEN sockets (weighted moderately)
-    /// - Active state connections (SYN, FIN, etc.)
-    /// - Adds subtle pulse variation for visual interest
-    ///
-    /// This provides meaningful visualization without requiring BPF/eBPF
-    /// infrastructure for actual byte-level traffic monitoring.
-    fn update_traffic_history(&mut self) {
-        // Remove oldest value
-        self.traffic_history.remove(0);
-
-        // Get connections to analyze based on current mode
-        let conns_to_analyze: Vec<&Connection> = match self.graveyard_mode {
-            GraveyardMode::Process => {
-                // In Process mode, only count connections for selected process
-                if let Some(pid) = self.selected_process_pid {
-                    self.connections
-                        .iter()
-                        .filter(|c| c.pid == Some(pid))
-                        .collect()
-                } else {
-                    self.connections.iter().collect()
-                }
+    /// Sample the activity of the host, of every process and remote
+    /// address with connections, and of every pinned endpoint
+    fn update_activity(&mut self) {
+        let tick = self.tick_counter;
+        // In Process mode the focused process counts its children too
+        let focus = match self.graveyard_mode {
+            GraveyardMode::Process => self.selected_process_pid,
+            GraveyardMode::Host => None,
+        };
+        let mut processes: HashMap<i32, Vec<&Connection>> = HashMap::new();
+        let mut endpoints: HashMap<Arc<str>, Vec<&Connection>> = HashMap::new();
+        for addr in &self.pinned_endpoints {
+            endpoints.entry(addr.clone()).or_default();
+        }
+        if let Some(pid) = focus {
+            processes.entry(pid).or_default();
+        }
+        for conn in &self.connections {
+            if let Some(pid) = conn.pid.filter(|&pid| Some(pid) != focus) {
+                processes.entry(pid).or_default().push(conn);
             }
-            GraveyardMode::Host => {
-                // In Host mode, count all connections
-                self.connections.iter().collect()
+            if let Some(pid) = focus.filter(|_| self.is_focused_pid(conn.pid)) {
+                processes.entry(pid).or_default().push(conn);
             }
-        };
-
-        // Calculate activity score based on real connection data
-        let established_count = conns_to_analyze
-            .iter()
-            .filter(|c| c.state == crate::net::ConnectionState::Established)
-            .count();
-
-        let listen_count = conns_to_analyze
-            .iter()
-            .filter(|c| c.state == crate::net::ConnectionState::Listen)
-            .count();
-
-        let active_states = conns_to_analyze
-            .iter()
-            .filter(|c| {
-                matches!(
-                    c.state,
-                    crate::net::ConnectionState::SynSent
-                        | crate::net::ConnectionState::SynRecv
-                        | crate::net::ConnectionState::FinWait1
-                        | crate::net::ConnectionState::FinWait2
-                        | crate::net::ConnectionState::Closing
-                )
-            })
-            .count();
-
-        // Calculate base activity score (0-100 scale)
-        // - Each ESTABLISHED connection contributes 5 points (max 50)
-        // - Each LISTEN socket contributes 2 points (max 20)
-        // - Each active state connection contributes 10 points (max 30)
-        let established_score = (established_count * 5).min(50) as i64;
-        let listen_score = (listen_count * 2).min(20) as i64;
-        let active_score = (active_states * 10).min(30) as i64;
-
-        // Base activity level (minimum visibility)
-        let base_activity: i64 = if conns_to_analyze.is_empty() { 5 } else { 10 };
-
-        // Calculate base value
-        let base_value = base_activity + established_score + listen_score + active_score;
-
-        // Add natural variation using tick_counter for visual interest
-        // This creates a subtle "heartbeat" effect even when connections are stable
-        let t = self.tick_counter as f32 * 0.15;
-        let variation = ((t.sin() * 8.0) + (t * 1.7).cos() * 4.0) as i64;
+            if conn.state != net::ConnectionState::Listen && conn.remote_port != 0 {
+                endpoints
+                    .entry(conn.remote_addr.clone())
+                    .or_default()
+                    .push(conn);
+            }
+        }
 
-        // Total score clamped to 5-100 (never fully empty for visibility)
-        let new_value = (base_value + variation).clamp(5, 100) as u64;
+        let all: Vec<&Connection> = self.connections.iter().collect();
+        let mut samples = HashMap::with_capacity(processes.len() + endpoints.len() + 1);
+        samples.insert(ActivityScope::Host, activity_score(&all, tick));
+        for (pid, conns) in processes {
+            samples.insert(ActivityScope::Process(pid), activity_score(&conns, tick));
+        }
+        for (addr, conns) in endpoints {
+            samples.insert(ActivityScope::Endpoint(addr), activity_score(&conns, tick));
+        }
+        self.activity.record(samples);
+    }
 
-        // Add to history
-        self.traffic_history.push(new_value);
+    /// Scope of the activity sparkline: the focused process in Process
+    /// mode, else the selected connection's endpoint, else the host
+    pub fn activity_scope(&self) -> ActivityScope {
+        if let (GraveyardMode::Process, Some(pid)) =
+            (self.graveyard_mode, self.selected_process_pid)
+        {
+            return ActivityScope::Process(pid);
+        }
+        let selected = self
+            .selected_connection
+            .and_then(|idx| self.connections.get(idx))
+            .filter(|conn| conn.state != net::ConnectionState::Listen && conn.remote_port != 0);
+        match selected {
+            Some(conn) => ActivityScope::Endpoint(conn.remote_addr.clone()),
+            None => ActivityScope::Host,
+        }
     }
 
     /// Move log selection up (decrease index)
@@ -308,68 +3316,193 @@ impl AppState {
 
 
 
-    /// Move connection selection up (decrease index)
+    /// Rows of the connection list, with connection pools folded
+    pub fn connection_rows(&self) -> Vec<ConnectionRow> {
+        rows::build_rows(&self.connections, &self.expanded_pools)
+    }
+
+    /// Row showing the selected connection
+    pub fn selected_row(&self, rows: &[ConnectionRow]) -> Option<usize> {
+        let idx = self.selected_connection?;
+        self.connection_list_state
+            .selected()
+            .filter(|&row| rows.get(row).is_some_and(|r| r.index == idx))
+            .or_else(|| rows.iter().position(|r| r.covers(idx)))
+    }
+
+    /// Select row `row` of `rows`
+    fn select_row(&mut self, rows: &[ConnectionRow], row: usize) {
+        self.selected_connection = Some(rows[row].index);
+        self.connection_list_state.select(Some(row));
+    }
+
+    /// Move connection selection up one row
     pub fn select_previous_connection(&mut self) {
-        if self.connections.is_empty() {
+        self.move_connection_selection(-1);
+    }
+
+    /// Move connection selection down one row
+    pub fn select_next_connection(&mut self) {
+        self.move_connection_selection(1);
+    }
+
+    /// Move connection selection by `delta` rows, stopping at either end;
+    /// without a selection, moving up starts at the last row and moving
+    /// down at the first
+    pub fn move_connection_selection(&mut self, delta: isize) {
+        let rows = self.connection_rows();
+        if rows.is_empty() {
             self.selected_connection = None;
             self.connection_list_state.select(None);
             return;
         }
 
-        match self.selected_connection {
-            None => {
-                // Start at the last connection
-                let idx = self.connections.len() - 1;
-                self.selected_connection = Some(idx);
-                self.connection_list_state.select(Some(idx));
-            }
-            Some(idx) => {
-                if idx > 0 {
-                    self.selected_connection = Some(idx - 1);
-                    self.connection_list_state.select(Some(idx - 1));
-                }
-            }
-        }
+        let last = rows.len() - 1;
+        let row = match self.selected_row(&rows) {
+            None if delta < 0 => last,
+            None => 0,
+            Some(row) => row.saturating_add_signed(delta).min(last),
+        };
+        self.select_row(&rows, row);
     }
 
-    /// Move connection selection down (increase index)
-    pub fn select_next_connection(&mut self) {
-        if self.connections.is_empty() {
-            self.selected_connection = None;
-            self.connection_list_state.select(None);
+    /// Select the first (or, with `last`, the final) connection row
+    pub fn select_edge_connection(&mut self, last: bool) {
+        let rows = self.connection_rows();
+        if rows.is_empty() {
             return;
         }
+        let row = if last { rows.len() - 1 } else { 0 };
+        self.select_row(&rows, row);
+    }
 
-        match self.selected_connection {
-            None => {
-                // Start at the first connection
-                self.selected_connection = Some(0);
-                self.connection_list_state.select(Some(0));
-            }
-            Some(idx) => {
-                if idx < self.connections.len() - 1 {
-                    self.selected_connection = Some(idx + 1);
-                    self.connection_list_state.select(Some(idx + 1));
-                }
-            }
+    /// Expand the selected connection pool into its sockets, or fold it back
+    pub fn toggle_pool_expansion(&mut self) {
+        let rows = self.connection_rows();
+        let Some(row) = self.selected_row(&rows) else {
+            self.toast("Select a pooled connection with ↑↓", ToastSeverity::Warning);
+            return;
+        };
+        let selected = rows[row].index;
+        let key = match rows[row].kind {
+            RowKind::Single => None,
+            _ => rows::pool_key(&self.connections[selected]),
+        };
+        let Some(key) = key else {
+            self.toast(
+                "Selected connection has no pooled siblings",
+                ToastSeverity::Warning,
+            );
+            return;
+        };
+        if !self.expanded_pools.remove(&key) {
+            self.expanded_pools.insert(key);
+        }
+        // Keep the cursor on the pool's own row
+        let rows = self.connection_rows();
+        if let Some(pool_row) = rows
+            .iter()
+            .position(|r| matches!(r.kind, RowKind::Pool { .. }) && r.covers(selected))
+        {
+            self.select_row(&rows, pool_row);
         }
+        self.dirty = true;
     }
 
     /// Focus on the process of the selected connection
     pub fn focus_process_of_selected_connection(&mut self) {
         if let Some(conn_idx) = self.selected_connection {
-            if let Some(conn) = self.connections.get(conn_idx) {
-                // Switch to Process mode even if PID is unknown (macOS)
-                self.graveyard_mode = GraveyardMode::Process;
-                self.selected_process_pid = conn.pid;
+            if let Some(pid) = self.connections.get(conn_idx).map(|c| c.pid) {
+                self.set_process_focus(pid);
+                self.focus_history.visit(self.focus_step());
             }
         }
     }
 
     /// Clear process focus, return to Host mode
     pub fn clear_process_focus(&mut self) {
+        self.set_host_focus();
+        self.focus_history.visit(FocusStep::Host);
+    }
+
+    /// Enter Process mode on `pid`, without recording it in the history
+    fn set_process_focus(&mut self, pid: Option<i32>) {
+        // Switch to Process mode even if PID is unknown (macOS)
+        self.graveyard_mode = GraveyardMode::Process;
+        self.selected_process_pid = pid;
+        self.update_focus_pids();
+        // Package lookup is skipped entirely (no PKG line) when no
+        // package manager is available or lookups are disabled
+        self.focused_package = pid
+            .filter(|_| self.package_resolver.is_available())
+            .map(|pid| self.package_resolver.owner_of_pid(pid));
+        self.inspector_sockets.reset();
+    }
+
+    /// Return to Host mode, without recording it in the history
+    fn set_host_focus(&mut self) {
         self.graveyard_mode = GraveyardMode::Host;
         self.selected_process_pid = None;
+        self.focused_package = None;
+        self.focus_pids.clear();
+        self.inspector_sockets.reset();
+    }
+
+    /// The current focus as a navigation history step
+    fn focus_step(&self) -> FocusStep {
+        match self.graveyard_mode {
+            GraveyardMode::Host => FocusStep::Host,
+            GraveyardMode::Process => {
+                let pid = self.selected_process_pid;
+                let name = self
+                    .raw_connections
+                    .iter()
+                    .filter(|c| pid.is_some() && c.pid == pid)
+                    .find_map(|c| c.process_name.clone());
+                FocusStep::Process { pid, name }
+            }
+        }
+    }
+
+    /// Go back (or forward again) through the Process-mode focus history
+    pub fn navigate_focus(&mut self, back: bool) {
+        let step = if back {
+            self.focus_history.back()
+        } else {
+            self.focus_history.forward()
+        };
+        match step.cloned() {
+            Some(FocusStep::Host) => self.set_host_focus(),
+            Some(FocusStep::Process { pid, .. }) => self.set_process_focus(pid),
+            None if back => self.toast("No earlier focus", ToastSeverity::Info),
+            None => self.toast("No later focus", ToastSeverity::Info),
+        }
+        self.dirty = true;
+    }
+
+    /// Toggle inclusion of descendant processes in Process mode
+    pub fn toggle_child_processes(&mut self) {
+        self.include_child_processes = !self.include_child_processes;
+        self.update_focus_pids();
+    }
+
+    /// Recompute `focus_pids` from the selected PID
+    ///
+    /// The ppid map is only built when child aggregation is enabled,
+    /// so the default Process mode costs no extra /proc scan.
+    fn update_focus_pids(&mut self) {
+        self.focus_pids = match self.selected_process_pid {
+            Some(pid) if self.include_child_processes => {
+                crate::procfs::descendant_pids(pid, &crate::procfs::build_ppid_map())
+            }
+            Some(pid) => HashSet::from([pid]),
+            None => HashSet::new(),
+        };
+    }
+
+    /// Check whether a connection's PID belongs to the Process-mode focus
+    pub fn is_focused_pid(&self, pid: Option<i32>) -> bool {
+        pid.is_some_and(|p| self.focus_pids.contains(&p))
     }
 
     /// Toggle focus based on current mode
@@ -380,8 +3513,20 @@ impl AppState {
                 self.focus_process_of_selected_connection();
             }
             GraveyardMode::Process => {
-                // Return to Host mode
-                self.clear_process_focus();
+                // Drill into a child process of an aggregated focus ('c'),
+                // or return to Host mode
+                let child_pid = self
+                    .selected_connection
+                    .and_then(|idx| self.connections.get(idx))
+                    .and_then(|conn| conn.pid)
+                    .filter(|pid| {
+                        Some(*pid) != self.selected_process_pid && self.focus_pids.contains(pid)
+                    });
+                if child_pid.is_some() {
+                    self.focus_process_of_selected_connection();
+                } else {
+                    self.clear_process_focus();
+                }
             }
         }
     }
@@ -406,49 +3551,97 @@ impl AppState {
         self.refresh_config.last_change = Some(Instant::now());
     }
 
+    /// Refresh data less often (data multiplier +1, clamp to MAX_DATA_MULTIPLIER)
+    pub fn increase_data_multiplier(&mut self) {
+        self.refresh_config.data_multiplier =
+            (self.refresh_config.data_multiplier + 1).min(config::MAX_DATA_MULTIPLIER);
+        self.refresh_config.last_change = Some(Instant::now());
+    }
+
+    /// Refresh data more often (data multiplier -1, clamp to MIN_DATA_MULTIPLIER)
+    pub fn decrease_data_multiplier(&mut self) {
+        self.refresh_config.data_multiplier = self
+            .refresh_config
+            .data_multiplier
+            .saturating_sub(1)
+            .max(config::MIN_DATA_MULTIPLIER);
+        self.refresh_config.last_change = Some(Instant::now());
+    }
+
     /// Update frame time tracking and auto-reduce animation complexity if needed
     ///
     /// This method should be called at the start of each frame render.
-    /// It monitors frame time and automatically reduces animation complexity
-    /// if frame time consistently exceeds FRAME_TIME_THRESHOLD_MS (100ms).
+    /// It monitors frame time and steps the animation quality down one tier
+    /// each time SLOW_FRAME_COUNT_THRESHOLD consecutive frames exceed
+    /// FRAME_TIME_THRESHOLD_MS (100ms).
     ///
     /// Requirements: 6.5 - Auto-reduce animation complexity when CPU usage is high
     pub fn update_frame_time(&mut self) {
         let now = Instant::now();
-        let frame_time = now.duration_since(self.last_frame_time).as_millis();
+        let elapsed = now.duration_since(self.last_frame_time);
+        let frame_time = elapsed.as_millis();
         self.last_frame_time = now;
+        self.perf.frames.push(now, elapsed);
 
-        // Check if frame time exceeds threshold
-        if frame_time > FRAME_TIME_THRESHOLD_MS {
-            self.slow_frame_count += 1;
-
-            // If we've had enough consecutive slow frames, reduce animation complexity
-            if self.slow_frame_count >= SLOW_FRAME_COUNT_THRESHOLD && !self.animation_reduced {
-                self.animation_reduced = true;
-                // Log the auto-reduction for debugging
-                tracing::info!(
-                    frame_time_ms = frame_time,
-                    slow_frame_count = self.slow_frame_count,
-                    "Auto-reducing animation complexity due to slow frame times"
-                );
-            }
-        } else {
+        // Check if frame time exceeds threshold; recordings keep their
+        // quality tier whatever the machine's speed
+        if self.deterministic || frame_time <= FRAME_TIME_THRESHOLD_MS {
             // Reset slow frame counter on a fast frame
-            // Only reset if we haven't already reduced complexity
-            if !self.animation_reduced {
-                self.slow_frame_count = 0;
-            }
+            self.slow_frame_count = 0;
+            return;
+        }
+        self.slow_frame_count += 1;
+        if self.slow_frame_count < SLOW_FRAME_COUNT_THRESHOLD {
+            return;
         }
+
+        // Enough consecutive slow frames: drop one tier, and start counting
+        // again before dropping the next
+        let quality = self.graveyard_settings.animation_quality;
+        if let Some(reduced) = quality.reduced() {
+            self.graveyard_settings.animation_quality = reduced;
+            self.perf.reduction = Some(Reduction {
+                at: SystemTime::now(),
+                from: self.perf.reduction.map_or(quality, |r| r.from),
+                to: reduced,
+                frame_ms: frame_time as u64,
+                slow_frames: self.slow_frame_count,
+            });
+            // Log the auto-reduction for debugging
+            tracing::info!(
+                frame_time_ms = frame_time,
+                slow_frame_count = self.slow_frame_count,
+                quality = reduced.label(),
+                "Auto-reducing animation quality due to slow frame times"
+            );
+            self.dirty = true;
+        }
+        self.slow_frame_count = 0;
     }
 
-    /// Reset animation complexity reduction
+    /// Undo automatic quality reduction
     ///
-    /// Called when user manually toggles animations or when performance improves.
-    /// This allows the system to try full animation complexity again.
+    /// Called when the user re-enables animations, restoring the tier that
+    /// was active before slow frames stepped it down.
     pub fn reset_animation_reduction(&mut self) {
-        self.animation_reduced = false;
+        if let Some(reduction) = self.perf.reduction.take() {
+            self.graveyard_settings.animation_quality = reduction.from;
+        }
         self.slow_frame_count = 0;
     }
+
+    /// Switch to the next animation quality tier (Off → Low → Normal → Lush)
+    pub fn cycle_animation_quality(&mut self) {
+        let quality = self.graveyard_settings.animation_quality.next();
+        self.graveyard_settings.animation_quality = quality;
+        // A tier picked by hand replaces any automatic reduction
+        self.perf.reduction = None;
+        self.slow_frame_count = 0;
+        self.toast(
+            format!("✨ Animation quality: {}", quality.label()),
+            ToastSeverity::Info,
+        );
+    }
 }
 
 impl Default for AppState {
@@ -457,6 +3650,14 @@ impl Default for AppState {
     }
 }
 
+/// " by name(pid)" suffix for endpoint contact events ("" when unknown)
+fn contact_owner(conn: &Connection) -> String {
+    match (&conn.process_name, conn.pid) {
+        (Some(name), Some(pid)) => format!(" by {}({})", name, pid),
+        _ => String::new(),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -479,14 +3680,15 @@ mod tests {
         ) {
             // Create a test connection with the generated pid
             let test_conn = Connection {
-                local_addr: "127.0.0.1".to_string(),
+                local_addr: "127.0.0.1".into(),
                 local_port: 8080,
-                remote_addr: "192.168.1.1".to_string(),
+                remote_addr: "192.168.1.1".into(),
                 remote_port: 443,
                 state: crate::net::ConnectionState::Established,
                 inode: Some(12345),
                 pid: Some(pid),
-                process_name: Some("test_process".to_string()),
+                process_name: Some("test_process".into()),
+                ..Default::default()
             };
 
             // Create app state with the test connection
@@ -657,14 +3859,15 @@ mod tests {
 
         // Add test connections
         let test_conn = Connection {
-            local_addr: "127.0.0.1".to_string(),
+            local_addr: "127.0.0.1".into(),
             local_port: 8080,
-            remote_addr: "192.168.1.1".to_string(),
+            remote_addr: "192.168.1.1".into(),
             remote_port: 443,
             state: crate::net::ConnectionState::Established,
             inode: Some(12345),
             pid: Some(1234),
-            process_name: Some("test_process".to_string()),
+            process_name: Some("test_process".into()),
+            ..Default::default()
         };
         app.connections = vec![test_conn];
 
@@ -691,14 +3894,15 @@ mod tests {
 
         // Add test connection and select it
         let test_conn = Connection {
-            local_addr: "127.0.0.1".to_string(),
+            local_addr: "127.0.0.1".into(),
             local_port: 8080,
-            remote_addr: "192.168.1.1".to_string(),
+            remote_addr: "192.168.1.1".into(),
             remote_port: 443,
             state: crate::net::ConnectionState::Established,
             inode: Some(12345),
             pid: Some(5678),
-            process_name: Some("test_process".to_string()),
+            process_name: Some("test_process".into()),
+            ..Default::default()
         };
         app.connections = vec![test_conn];
         app.selected_connection = Some(0);
@@ -740,24 +3944,26 @@ mod tests {
         // Add test connections
         let test_conns = vec![
             Connection {
-                local_addr: "127.0.0.1".to_string(),
+                local_addr: "127.0.0.1".into(),
                 local_port: 8080,
-                remote_addr: "192.168.1.1".to_string(),
+                remote_addr: "192.168.1.1".into(),
                 remote_port: 443,
                 state: crate::net::ConnectionState::Established,
                 inode: Some(1),
                 pid: Some(100),
-                process_name: Some("proc1".to_string()),
+                process_name: Some("proc1".into()),
+                ..Default::default()
             },
             Connection {
-                local_addr: "127.0.0.1".to_string(),
+                local_addr: "127.0.0.1".into(),
                 local_port: 8081,
-                remote_addr: "10.0.0.1".to_string(),
+                remote_addr: "10.0.0.1".into(),
                 remote_port: 80,
                 state: crate::net::ConnectionState::Listen,
                 inode: Some(2),
                 pid: Some(200),
-                process_name: Some("proc2".to_string()),
+                process_name: Some("proc2".into()),
+                ..Default::default()
             },
         ];
         app.connections = test_conns;
@@ -794,14 +4000,15 @@ mod tests {
 
         // Add test connection
         let test_conn = Connection {
-            local_addr: "127.0.0.1".to_string(),
+            local_addr: "127.0.0.1".into(),
             local_port: 8080,
-            remote_addr: "192.168.1.1".to_string(),
+            remote_addr: "192.168.1.1".into(),
             remote_port: 443,
             state: crate::net::ConnectionState::Established,
             inode: Some(12345),
             pid: Some(9999),
-            process_name: Some("test_process".to_string()),
+            process_name: Some("test_process".into()),
+            ..Default::default()
         };
         app.connections = vec![test_conn];
         app.selected_connection = Some(0);
@@ -841,34 +4048,37 @@ mod tests {
         // Add some test connections
         let test_conns = vec![
             Connection {
-                local_addr: "127.0.0.1".to_string(),
+                local_addr: "127.0.0.1".into(),
                 local_port: 8080,
-                remote_addr: "192.168.1.1".to_string(),
+                remote_addr: "192.168.1.1".into(),
                 remote_port: 443,
                 state: crate::net::ConnectionState::Established,
                 inode: Some(1),
                 pid: Some(100),
-                process_name: Some("proc1".to_string()),
+                process_name: Some("proc1".into()),
+                ..Default::default()
             },
             Connection {
-                local_addr: "127.0.0.1".to_string(),
+                local_addr: "127.0.0.1".into(),
                 local_port: 8081,
-                remote_addr: "192.168.1.2".to_string(),
+                remote_addr: "192.168.1.2".into(),
                 remote_port: 443,
                 state: crate::net::ConnectionState::Established,
                 inode: Some(2),
                 pid: Some(200),
-                process_name: Some("proc2".to_string()),
+                process_name: Some("proc2".into()),
+                ..Default::default()
             },
             Connection {
-                local_addr: "127.0.0.1".to_string(),
+                local_addr: "127.0.0.1".into(),
                 local_port: 8082,
-                remote_addr: "192.168.1.3".to_string(),
+                remote_addr: "192.168.1.3".into(),
                 remote_port: 443,
                 state: crate::net::ConnectionState::Established,
                 inode: Some(3),
                 pid: Some(300),
-                process_name: Some("proc3".to_string()),
+                process_name: Some("proc3".into()),
+                ..Default::default()
             },
         ];
         app.connections = test_conns;
@@ -904,4 +4114,472 @@ mod tests {
         app.select_previous_connection();
         assert_eq!(app.selected_connection, Some(2)); // Should wrap to last
     }
+
+    #[test]
+    fn test_user_filter_applies_and_clamps_selection() {
+        let mut app = AppState::new();
+        app.raw_connections = vec![
+            Connection {
+                local_port: 5432,
+                user: Some("postgres".to_string()),
+                ..Default::default()
+            },
+            Connection {
+                local_port: 22,
+                user: Some("root".to_string()),
+                ..Default::default()
+            },
+            Connection {
+                local_port: 80,
+                user: Some("root".to_string()),
+                ..Default::default()
+            },
+        ];
+        app.apply_filters();
+        app.selected_connection = Some(2);
+
+        // First cycle selects "postgres" (sorted), leaving one connection
+        app.cycle_user_filter();
+        assert_eq!(app.connection_filter.user.as_deref(), Some("postgres"));
+        assert_eq!(app.connections.len(), 1);
+        assert_eq!(app.selected_connection, Some(0));
+
+        // Then "root", then back to no filter
+        app.cycle_user_filter();
+        assert_eq!(app.connections.len(), 2);
+        app.cycle_user_filter();
+        assert_eq!(app.connection_filter.user, None);
+        assert_eq!(app.connections.len(), 3);
+    }
+
+    #[test]
+    fn test_static_state_skips_redraw() {
+        let mut app = AppState::new();
+        // Initial frame is always drawn
+        assert!(app.take_dirty());
+        assert!(!app.take_dirty());
+
+        // Animations off, data refresh far away: ticks change nothing visible
+        app.graveyard_settings.animations_enabled = false;
+        app.refresh_config.data_multiplier = config::MAX_DATA_MULTIPLIER;
+        app.last_tick = Instant::now() - std::time::Duration::from_millis(200);
+        let phase = app.pulse_phase;
+        app.on_tick();
+        assert_eq!(app.pulse_phase, phase);
+        assert!(!app.take_dirty());
+
+        // Animations on: each tick advances the pulse and needs a frame
+        app.graveyard_settings.animations_enabled = true;
+        app.last_tick = Instant::now() - std::time::Duration::from_millis(200);
+        app.on_tick();
+        assert!(app.take_dirty());
+    }
+
+    #[test]
+    fn test_resize_marks_dirty() {
+        let mut app = AppState::new();
+        assert!(app.take_dirty());
+        app.slow_frame_count = SLOW_FRAME_COUNT_THRESHOLD - 1;
+        app.last_frame_time = Instant::now() - std::time::Duration::from_secs(1);
+
+        app.on_resize(120, 40);
+        assert!(app.take_dirty());
+        assert_eq!(app.slow_frame_count, 0);
+
+        // The pause while resizing does not count as a slow frame
+        app.update_frame_time();
+        assert_eq!(app.slow_frame_count, 0);
+    }
+
+    #[test]
+    fn test_environment_contact_alert_fires_once() {
+        use endpoint_rules::{EndpointRuleConfig, EnvironmentAlertConfig};
+
+        let mut app = AppState::new();
+        app.endpoint_rules = endpoint_rules::build_rules(&[EndpointRuleConfig {
+            cidr: "10.1.0.0/16".to_string(),
+            label: "prod-db".to_string(),
+            environment: Some("prod".to_string()),
+            ..Default::default()
+        }])
+        .unwrap();
+        app.environment_alerts =
+            endpoint_rules::build_environment_alerts(&[EnvironmentAlertConfig {
+                environment: "prod".to_string(),
+                filter: Some("proc ~ staging".to_string()),
+                severity: Some("critical".to_string()),
+            }])
+            .unwrap();
+
+        let staging_to_prod = Connection {
+            remote_addr: "10.1.0.9".into(),
+            remote_port: 5432,
+            state: net::ConnectionState::Established,
+            pid: Some(77),
+            process_name: Some("staging-api".into()),
+            ..Default::default()
+        };
+        let billing_to_prod = Connection {
+            process_name: Some("billing".into()),
+            ..staging_to_prod.clone()
+        };
+        app.raw_connections = vec![staging_to_prod, billing_to_prod];
+
+        let before = app.events.len();
+        app.record_environment_contacts();
+        app.record_environment_contacts();
+        assert_eq!(app.events.len(), before + 1);
+
+        let event = app.events.iter().last().unwrap();
+        assert_eq!(event.severity, EventSeverity::Critical);
+        assert_eq!(
+            event.message,
+            "staging-api(77) talked to env=prod (10.1.0.9:5432)"
+        );
+        assert_eq!(event.pid, Some(77));
+    }
+
+    #[test]
+    fn test_haunted_endpoint_logged_once() {
+        let path = std::env::temp_dir().join(format!("ntomb-haunted-{}.txt", std::process::id()));
+        std::fs::write(&path, "198.51.100.0/24 ; SBL42\n").unwrap();
+
+        let mut app = AppState::new();
+        app.threat_intel = ThreatIntel::from_config(&threat_intel::ThreatIntelConfig {
+            blocklist: Some(path.clone()),
+            ..Default::default()
+        });
+        app.raw_connections = vec![
+            Connection {
+                remote_addr: "198.51.100.23".into(),
+                remote_port: 443,
+                pid: Some(9),
+                process_name: Some("curl".into()),
+                ..Default::default()
+            },
+            Connection {
+                remote_addr: "8.8.8.8".into(),
+                ..Default::default()
+            },
+        ];
+
+        let before = app.events.len();
+        app.update_haunted_endpoints();
+        app.update_haunted_endpoints();
+        assert!(app.is_haunted("198.51.100.23"));
+        assert!(!app.is_haunted("8.8.8.8"));
+
+        // "Threat list loaded" + one Critical event for the endpoint
+        let events: Vec<_> = app.events.iter().skip(before).collect();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[1].severity, EventSeverity::Critical);
+        assert_eq!(
+            events[1].message,
+            "Haunted endpoint 198.51.100.23:443 contacted by curl(9) [SBL42]"
+        );
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_anonymizer_contact_logged_once() {
+        let path = std::env::temp_dir().join(format!("ntomb-tor-{}.txt", std::process::id()));
+        std::fs::write(&path, "203.0.113.5\n").unwrap();
+
+        let mut app = AppState::new();
+        app.anonymizers = Anonymizers::load(&anonymizers::AnonymizersConfig {
+            tor_exits: Some(path.clone()),
+            ..Default::default()
+        })
+        .unwrap();
+        app.raw_connections = vec![Connection {
+            remote_addr: "203.0.113.5".into(),
+            remote_port: 9001,
+            pid: Some(7),
+            process_name: Some("rsync".into()),
+            ..Default::default()
+        }];
+
+        let before = app.events.len();
+        app.update_anonymous_endpoints();
+        app.update_anonymous_endpoints();
+        assert_eq!(app.anonymizer("203.0.113.5"), Some(&Anonymizer::TorExit));
+
+        let events: Vec<_> = app.events.iter().skip(before).collect();
+        assert_eq!(events.len(), 1);
+        assert_eq!(
+            events[0].message,
+            "Anonymizer (tor exit) 203.0.113.5:9001 contacted by rsync(7)"
+        );
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_yank_without_selection_reports_error() {
+        let mut app = AppState::new();
+        app.selected_connection = None;
+        app.yank_selected_connection();
+        let toast = app.toasts.newest_first().next().unwrap();
+        assert_eq!(toast.severity, ToastSeverity::Warning);
+        assert!(toast.text.starts_with("Nothing to yank"));
+    }
+
+    #[test]
+    fn test_asn_grouping_and_expansion() {
+        let mut app = AppState::new();
+
+        app.asn_database = Arc::new(AsnDatabase::parse(
+            "8.8.8.0\t8.8.8.255\t15169\tUS\tGOOGLE\n8.8.4.0\t8.8.4.255\t15169\tUS\tGOOGLE\n",
+        ));
+        let conn = |remote: &str| Connection {
+            remote_addr: remote.into(),
+            ..Default::default()
+        };
+        app.raw_connections = vec![conn("8.8.8.8"), conn("8.8.4.4"), conn("10.0.0.9")];
+        app.update_asn_endpoints();
+        app.apply_filters();
+
+        // Subnets need no data
+        app.cycle_grouping();
+        assert_eq!(app.grouping, EndpointGrouping::Subnet);
+        assert_eq!(app.endpoint_groups().len(), 3);
+
+        app.cycle_grouping();
+        assert_eq!(app.grouping, EndpointGrouping::Asn);
+        let groups = app.endpoint_groups();
+        assert_eq!(groups.len(), 1);
+        assert_eq!((groups[0].connections, groups[0].endpoints), (2, 2));
+        assert_eq!(groups[0].tags, vec!["AS15169"]);
+
+        let idx = app
+            .connections
+            .iter()
+            .position(|c| &*c.remote_addr == "8.8.4.4")
+            .unwrap();
+        app.selected_connection = Some(idx);
+        app.toggle_group_expansion();
+        assert!(app.expanded_groups.contains("GOOGLE"));
+        app.toggle_group_expansion();
+        assert!(app.expanded_groups.is_empty());
+
+        app.cycle_grouping();
+        assert_eq!(app.grouping, EndpointGrouping::Process);
+        app.cycle_grouping();
+        assert_eq!(app.grouping, EndpointGrouping::Country);
+        let groups = app.endpoint_groups();
+        assert_eq!((&*groups[0].name, groups[0].connections), ("US", 2));
+
+        // Without reverse DNS the cycle skips the domain mode
+        app.cycle_grouping();
+        assert_eq!(app.grouping, EndpointGrouping::Off);
+        assert_eq!(app.endpoint_group(&conn("8.8.8.8")), None);
+    }
+
+    #[test]
+    fn test_quality_auto_reduction() {
+        use quality::AnimationQuality;
+        use std::time::Duration;
+
+        let mut app = AppState::new();
+        app.graveyard_settings.animation_quality = AnimationQuality::Lush;
+        let slow_frames = |app: &mut AppState, count: u32| {
+            for _ in 0..count {
+                app.last_frame_time = Instant::now() - Duration::from_millis(200);
+                app.update_frame_time();
+            }
+        };
+
+        slow_frames(&mut app, SLOW_FRAME_COUNT_THRESHOLD - 1);
+        assert_eq!(
+            app.graveyard_settings.animation_quality,
+            AnimationQuality::Lush
+        );
+        slow_frames(&mut app, 1);
+        assert_eq!(
+            app.graveyard_settings.animation_quality,
+            AnimationQuality::Normal
+        );
+        // Each further step needs another run of slow frames, down to Low
+        slow_frames(&mut app, SLOW_FRAME_COUNT_THRESHOLD * 2);
+        assert_eq!(
+            app.graveyard_settings.animation_quality,
+            AnimationQuality::Low
+        );
+        let reduction = app.perf.reduction.unwrap();
+        assert_eq!(
+            (reduction.from, reduction.to),
+            (AnimationQuality::Lush, AnimationQuality::Low)
+        );
+
+        // Re-enabling animations restores the tier from before the reduction
+        app.reset_animation_reduction();
+        assert_eq!(
+            app.graveyard_settings.animation_quality,
+            AnimationQuality::Lush
+        );
+        assert!(app.perf.reduction.is_none());
+    }
+
+    #[test]
+    fn test_activity_follows_the_selection() {
+        use crate::app::activity::{ActivityScope, ACTIVITY_SAMPLES};
+
+        let mut app = AppState::new();
+        app.connections = vec![Connection {
+            remote_addr: "93.184.216.34".into(),
+            remote_port: 443,
+            state: net::ConnectionState::Established,
+            pid: Some(812),
+            ..Default::default()
+        }];
+        app.selected_connection = None;
+        assert_eq!(app.activity_scope(), ActivityScope::Host);
+
+        app.selected_connection = Some(0);
+        let endpoint = ActivityScope::Endpoint("93.184.216.34".into());
+        assert_eq!(app.activity_scope(), endpoint);
+
+        app.graveyard_mode = GraveyardMode::Process;
+        app.selected_process_pid = Some(812);
+        assert_eq!(app.activity_scope(), ActivityScope::Process(812));
+
+        app.update_activity();
+        for scope in [ActivityScope::Host, ActivityScope::Process(812), endpoint] {
+            assert_eq!(app.activity.samples(&scope).len(), ACTIVITY_SAMPLES);
+        }
+    }
+
+    #[test]
+    fn test_data_age() {
+        let mut app = AppState::new();
+        app.deterministic = true;
+        app.refresh_config.data_multiplier = 10;
+        app.last_snapshot_at = Some(app.started_at);
+        let freshness = |app: &mut AppState, ticks| {
+            app.tick_counter = ticks;
+            app.data_age().unwrap()
+        };
+        // One tick is 100ms, so the data interval is 1s
+        assert_eq!(
+            freshness(&mut app, 10),
+            (Duration::from_secs(1), DataFreshness::Fresh)
+        );
+        assert_eq!(freshness(&mut app, 20).1, DataFreshness::Late);
+        assert_eq!(freshness(&mut app, 31).1, DataFreshness::Stale);
+    }
+
+    #[test]
+    fn test_deterministic_ticks() {
+        use std::time::Duration;
+
+        // Every call is a tick, however little time passed, so two runs
+        // produce the same animation frames
+        let run = || {
+            let mut app = AppState::new();
+            app.deterministic = true;
+            let blink = app.zombie_blink;
+            let frames: Vec<(u32, bool)> = (0..12)
+                .map(|_| {
+                    app.on_tick();
+                    (
+                        (app.pulse_phase * 100.0).round() as u32,
+                        app.zombie_blink != blink,
+                    )
+                })
+                .collect();
+            (app, frames)
+        };
+        let (mut app, frames) = run();
+        assert_eq!(frames, run().1);
+        assert_eq!(frames[0], (5, false));
+        // The blink flips every fifth tick
+        assert_eq!(frames[4], (25, true));
+        assert_eq!(frames[9], (50, false));
+
+        // Slow frames don't lower the quality tier
+        let quality = app.graveyard_settings.animation_quality;
+        for _ in 0..SLOW_FRAME_COUNT_THRESHOLD * 2 {
+            app.last_frame_time = Instant::now() - Duration::from_millis(200);
+            app.update_frame_time();
+        }
+        assert_eq!(app.graveyard_settings.animation_quality, quality);
+    }
+
+    #[test]
+    fn test_connection_trends() {
+        let conn = |pid: i32, local_port: u16| Connection {
+            local_port,
+            pid: Some(pid),
+            ..Default::default()
+        };
+        let mut app = AppState::new();
+        // pid 1 keeps 3 connections while pid 2 grows from 1 to 6
+        for n in 1..=6u16 {
+            let mut conns = vec![conn(1, 1), conn(1, 2), conn(1, 3)];
+            conns.extend((0..n).map(|port| conn(2, 100 + port)));
+            app.history.push(Snapshot::capture(&conns));
+        }
+        assert_eq!(app.connection_trend(), Trend::Rising);
+        let trends = app.process_trends();
+        assert_eq!(trends[&1], Trend::Stable);
+        assert_eq!(trends[&2], Trend::Rising);
+
+        // Filtered-out connections don't count
+        app.connection_filter.container = Some("db".to_string());
+        assert_eq!(app.connection_trend(), Trend::Stable);
+
+        // A paused timeline looks back from the snapshot on screen
+        app.connection_filter.container = None;
+        app.timeline = Some(0);
+        assert_eq!(app.connection_trend(), Trend::Stable);
+    }
+
+    #[test]
+    fn test_config_reload() {
+        let dir = std::env::temp_dir().join(format!("ntomb-reload-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("config.toml");
+        std::fs::write(&path, "").unwrap();
+
+        let mut app = AppState::new();
+        app.config_watcher = Some(ConfigWatcher::spawn(&path).unwrap());
+        app.watches
+            .push(Watch::parse("count(port == 22) > 1").unwrap());
+
+        std::fs::write(
+            &path,
+            "[graveyard]\nmax_endpoints = 5\nhost_name = \"db-primary\"\n\n\
+             [[watches]]\nexpr = 'count(port == 443) > 9'\n\n[dns]\nreverse = true\n",
+        )
+        .unwrap();
+        let before = app.events.len();
+        app.reload_config();
+        assert_eq!(app.graveyard_settings.max_visible_endpoints, 5);
+        assert_eq!(app.host_name, "db-primary");
+        let sources: Vec<&str> = app.watches.iter().map(|w| w.source.as_str()).collect();
+        assert_eq!(sources, ["count(port == 443) > 9", "count(port == 22) > 1"]);
+        let event = app.events.iter().last().unwrap();
+        assert_eq!(event.severity, EventSeverity::Info);
+        assert_eq!(
+            event.message,
+            "Config reloaded: watches, graveyard (restart to apply dns)"
+        );
+
+        // An invalid rule keeps every running setting
+        std::fs::write(
+            &path,
+            "[graveyard]\nmax_endpoints = 9\n\n[[endpoint_rules]]\ncidr = \"nope\"\nlabel = \"x\"\n",
+        )
+        .unwrap();
+        app.reload_config();
+        assert_eq!(app.graveyard_settings.max_visible_endpoints, 5);
+        assert!(app.endpoint_rules.is_empty());
+        let event = app.events.iter().last().unwrap();
+        assert_eq!(event.severity, EventSeverity::Warning);
+        assert!(event
+            .message
+            .starts_with("Config reload failed: [[endpoint_rules]]"));
+        assert_eq!(app.events.len(), before + 2);
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
 }
@@ -0,0 +1,170 @@
+// Process-mode navigation history
+//
+// Every change of Process-mode focus (`P` on a connection, `#<pid>!`, `P`
+// back to Host) is recorded like a browser history: Backspace or `(` steps
+// back, `)` steps forward again, and focusing something new drops the
+// forward steps. The Graveyard title shows the trail since the last visit
+// to Host as a breadcrumb ("HOST › nginx (1234) › worker (1250)").
+
+use std::sync::Arc;
+
+/// Steps kept before the oldest is dropped
+const MAX_STEPS: usize = 50;
+
+/// One place in the navigation history
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FocusStep {
+    Host,
+    /// Process mode on `pid` (None when the backend reports no PIDs)
+    Process {
+        pid: Option<i32>,
+        name: Option<Arc<str>>,
+    },
+}
+
+impl FocusStep {
+    /// Breadcrumb label: "HOST", "nginx (1234)", "PID 1234"
+    pub fn label(&self) -> String {
+        match self {
+            FocusStep::Host => "HOST".to_string(),
+            FocusStep::Process {
+                pid: Some(pid),
+                name: Some(name),
+            } => format!("{} ({})", name, pid),
+            FocusStep::Process {
+                pid: Some(pid),
+                name: None,
+            } => format!("PID {}", pid),
+            FocusStep::Process { pid: None, .. } => "unknown process".to_string(),
+        }
+    }
+
+    /// Whether both steps focus the same thing (names may differ as
+    /// processes are renamed)
+    fn same_place(&self, other: &FocusStep) -> bool {
+        match (self, other) {
+            (FocusStep::Host, FocusStep::Host) => true,
+            (FocusStep::Process { pid: a, .. }, FocusStep::Process { pid: b, .. }) => a == b,
+            _ => false,
+        }
+    }
+}
+
+/// Back/forward history of Process-mode focus
+#[derive(Debug, Clone)]
+pub struct FocusHistory {
+    steps: Vec<FocusStep>,
+    /// Index of the current step
+    cursor: usize,
+}
+
+impl Default for FocusHistory {
+    fn default() -> Self {
+        Self {
+            steps: vec![FocusStep::Host],
+            cursor: 0,
+        }
+    }
+}
+
+impl FocusHistory {
+    /// Record a focus change, dropping the steps ahead of the current one
+    pub fn visit(&mut self, step: FocusStep) {
+        if self.steps[self.cursor].same_place(&step) {
+            self.steps[self.cursor] = step;
+            return;
+        }
+        self.steps.truncate(self.cursor + 1);
+        self.steps.push(step);
+        if self.steps.len() > MAX_STEPS {
+            self.steps.remove(0);
+        }
+        self.cursor = self.steps.len() - 1;
+    }
+
+    /// Step back; None at the oldest step
+    pub fn back(&mut self) -> Option<&FocusStep> {
+        self.cursor = self.cursor.checked_sub(1)?;
+        Some(&self.steps[self.cursor])
+    }
+
+    /// Step forward again; None at the newest step
+    pub fn forward(&mut self) -> Option<&FocusStep> {
+        if self.cursor + 1 >= self.steps.len() {
+            return None;
+        }
+        self.cursor += 1;
+        Some(&self.steps[self.cursor])
+    }
+
+    /// Steps from the last Host visit up to the current one
+    pub fn breadcrumb(&self) -> &[FocusStep] {
+        let start = self.steps[..=self.cursor]
+            .iter()
+            .rposition(|step| *step == FocusStep::Host)
+            .unwrap_or(0);
+        &self.steps[start..=self.cursor]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn process(pid: i32, name: &str) -> FocusStep {
+        FocusStep::Process {
+            pid: Some(pid),
+            name: Some(name.into()),
+        }
+    }
+
+    fn labels(history: &FocusHistory) -> Vec<String> {
+        history.breadcrumb().iter().map(FocusStep::label).collect()
+    }
+
+    #[test]
+    fn test_back_and_forward() {
+        let mut history = FocusHistory::default();
+        history.visit(process(1234, "nginx"));
+        history.visit(process(1250, "worker"));
+        assert_eq!(labels(&history), ["HOST", "nginx (1234)", "worker (1250)"]);
+
+        assert_eq!(history.back(), Some(&process(1234, "nginx")));
+        assert_eq!(history.back(), Some(&FocusStep::Host));
+        assert_eq!(history.back(), None);
+        assert_eq!(history.forward(), Some(&process(1234, "nginx")));
+        assert_eq!(labels(&history), ["HOST", "nginx (1234)"]);
+
+        // Focusing something new drops the forward steps
+        history.visit(process(99, "sshd"));
+        assert_eq!(history.forward(), None);
+        assert_eq!(labels(&history), ["HOST", "nginx (1234)", "sshd (99)"]);
+    }
+
+    #[test]
+    fn test_breadcrumb_starts_at_last_host() {
+        let mut history = FocusHistory::default();
+        history.visit(process(1234, "nginx"));
+        history.visit(FocusStep::Host);
+        history.visit(process(99, "sshd"));
+        assert_eq!(labels(&history), ["HOST", "sshd (99)"]);
+
+        // Revisiting the current place records nothing
+        history.visit(process(99, "sshd-session"));
+        history.back();
+        assert_eq!(labels(&history), ["HOST"]);
+    }
+
+    #[test]
+    fn test_history_is_bounded() {
+        let mut history = FocusHistory::default();
+        for pid in 0..(MAX_STEPS as i32 * 2) {
+            history.visit(process(pid, "p"));
+        }
+        let mut steps = 0;
+        while history.back().is_some() {
+            steps += 1;
+        }
+        assert_eq!(steps, MAX_STEPS - 1);
+    }
+}
@@ -0,0 +1,55 @@
+// Desktop notifications
+//
+// Alerts that opt in (`notify = true`) are also sent to the desktop via
+// notify-send or, on macOS, osascript. The command runs on its own thread so
+// a slow notification daemon never stalls the UI; failures are ignored
+// because the Grimoire event is already logged.
+
+use std::process::{Command, Stdio};
+use std::thread;
+
+/// Show a desktop notification without waiting for it
+pub fn desktop_notify(title: &str, body: &str) {
+    let mut command = if cfg!(target_os = "macos") {
+        let script = format!(
+            "display notification \"{}\" with title \"{}\"",
+            applescript_escape(body),
+            applescript_escape(title)
+        );
+        let mut command = Command::new("osascript");
+        command.arg("-e").arg(script);
+        command
+    } else {
+        let mut command = Command::new("notify-send");
+        command.arg("--app-name=ntomb").arg(title).arg(body);
+        command
+    };
+    command
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+
+    thread::spawn(move || {
+        if let Ok(mut child) = command.spawn() {
+            let _ = child.wait();
+        }
+    });
+}
+
+/// Escape `text` for a double-quoted AppleScript string
+fn applescript_escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_applescript_escape() {
+        assert_eq!(
+            applescript_escape(r#"proc == "pg" \ ok"#),
+            r#"proc == \"pg\" \\ ok"#
+        );
+    }
+}
@@ -0,0 +1,184 @@
+// Departed processes and the sockets they leave behind
+//
+// A socket can outlive its process while it finishes closing: TIME_WAIT
+// for a minute or two, FIN_WAIT2 or LAST_ACK while the peer is slow to
+// answer. The kernel owns such sockets, so the backends report them
+// without a process. ntomb remembers who owned each connection across
+// refreshes; once an unowned connection's last owner is gone (no socket
+// of its PID in the snapshot, and no such process either), the connection
+// is labeled with the departed owner ("departed" = "curl(4242)"), shown
+// with a 👻 badge, and the departure is logged once per process.
+//
+// This compares snapshots rather than listening to the kernel's proc
+// connector, which needs root and is Linux only; a process is noticed
+// gone at the first refresh after it exits.
+
+use super::snapshot::{connection_key, ConnectionKey};
+use crate::net::Connection;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::Arc;
+use sysinfo::{Pid, ProcessesToUpdate, System};
+
+/// Label key of orphaned connections; the value names the departed owner
+pub const DEPARTED_LABEL: &str = "departed";
+
+/// Process that owned a connection
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Owner {
+    pid: i32,
+    name: Option<Arc<str>>,
+}
+
+/// A process found gone, with the sockets it left behind
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Departure {
+    pub pid: i32,
+    pub process_name: Option<Arc<str>>,
+    /// Connections of the process still open
+    pub lingering: usize,
+}
+
+impl Departure {
+    /// "curl(4242)", or "pid 4242" when the name is unknown
+    pub fn label(&self) -> String {
+        match self.process_name {
+            Some(ref name) => format!("{}({})", name, self.pid),
+            None => format!("pid {}", self.pid),
+        }
+    }
+}
+
+/// Last known owner of every connection
+#[derive(Debug, Clone, Default)]
+pub struct OrphanTracker {
+    owners: HashMap<ConnectionKey, Owner>,
+    /// PIDs already reported departed, while their sockets linger
+    departed: HashSet<i32>,
+}
+
+impl OrphanTracker {
+    /// Label the connections of a fresh snapshot whose owner has exited,
+    /// `alive` telling whether a PID with no socket in the snapshot still
+    /// runs; returns the processes newly found gone
+    pub fn classify(
+        &mut self,
+        conns: &mut [Connection],
+        alive: impl Fn(i32) -> bool,
+    ) -> Vec<Departure> {
+        let owning: HashSet<i32> = conns.iter().filter_map(|conn| conn.pid).collect();
+        let mut checked: HashMap<i32, bool> = HashMap::new();
+        let mut owners = HashMap::new();
+        let mut departures: BTreeMap<i32, Departure> = BTreeMap::new();
+        let mut departed = HashSet::new();
+        for conn in conns.iter_mut() {
+            let key = connection_key(conn);
+            if let Some(pid) = conn.pid {
+                let name = conn.process_name.clone();
+                owners.insert(key, Owner { pid, name });
+                continue;
+            }
+            let Some(owner) = self.owners.remove(&key) else {
+                continue;
+            };
+            let gone = !owning.contains(&owner.pid)
+                && !*checked.entry(owner.pid).or_insert_with(|| alive(owner.pid));
+            if gone {
+                let departure = Departure {
+                    pid: owner.pid,
+                    process_name: owner.name.clone(),
+                    lingering: 0,
+                };
+                conn.labels.insert(DEPARTED_LABEL, departure.label().into());
+                departed.insert(owner.pid);
+                if !self.departed.contains(&owner.pid) {
+                    departures.entry(owner.pid).or_insert(departure).lingering += 1;
+                }
+            }
+            // Still running (TIME_WAIT sockets have no owner either) or
+            // gone: either way the socket's owner is remembered
+            owners.insert(key, owner);
+        }
+        self.owners = owners;
+        self.departed = departed;
+        departures.into_values().collect()
+    }
+}
+
+/// Whether a process with `pid` is running
+pub fn process_alive(pid: i32) -> bool {
+    let Ok(pid) = u32::try_from(pid).map(Pid::from_u32) else {
+        return false;
+    };
+    let mut sys = System::new();
+    sys.refresh_processes(ProcessesToUpdate::Some(&[pid]), false);
+    sys.process(pid).is_some()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::net::ConnectionState;
+
+    fn conn(remote_port: u16, state: ConnectionState, owner: Option<(i32, &str)>) -> Connection {
+        Connection {
+            local_addr: "10.0.0.5".into(),
+            local_port: 50000,
+            remote_addr: "93.184.216.34".into(),
+            remote_port,
+            state,
+            pid: owner.map(|(pid, _)| pid),
+            process_name: owner.map(|(_, name)| name.into()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_lingering_sockets_of_a_departed_process() {
+        use ConnectionState::*;
+        let mut tracker = OrphanTracker::default();
+        let running = |pid| pid == 7;
+
+        let mut before = vec![
+            conn(443, Established, Some((4242, "curl"))),
+            conn(80, Established, Some((4242, "curl"))),
+            conn(22, Established, Some((7, "ssh"))),
+        ];
+        assert!(tracker.classify(&mut before, running).is_empty());
+
+        // curl exits; its sockets close without an owner. ssh's socket
+        // goes to TIME_WAIT while ssh still runs
+        let mut after = vec![
+            conn(443, TimeWait, None),
+            conn(80, FinWait2, None),
+            conn(22, TimeWait, None),
+        ];
+        let departures = tracker.classify(&mut after, running);
+        assert_eq!(
+            departures,
+            [Departure {
+                pid: 4242,
+                process_name: Some("curl".into()),
+                lingering: 2,
+            }]
+        );
+        let departed = |conns: &[Connection]| -> Vec<Option<String>> {
+            conns
+                .iter()
+                .map(|c| c.labels.get(DEPARTED_LABEL).map(|l| l.to_string()))
+                .collect()
+        };
+        assert_eq!(
+            departed(&after),
+            [
+                Some("curl(4242)".to_string()),
+                Some("curl(4242)".to_string()),
+                None
+            ]
+        );
+
+        // Reported once; the sockets stay labeled until they are gone
+        let mut later = vec![conn(443, TimeWait, None)];
+        assert!(tracker.classify(&mut later, running).is_empty());
+        assert_eq!(departed(&later), [Some("curl(4242)".to_string())]);
+    }
+}
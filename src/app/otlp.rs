@@ -0,0 +1,446 @@
+// OpenTelemetry metrics export (`--features otlp`)
+//
+// On every data refresh the current snapshot is summarized into OTLP
+// metrics and POSTed as OTLP/HTTP JSON to a collector, so ntomb can double
+// as a lightweight network telemetry agent:
+//
+// ```toml
+// [otlp]
+// endpoint = "http://localhost:4318/v1/metrics"
+// headers = { authorization = "Bearer s3cr3t" }   # optional
+// ```
+//
+// | Metric                     | Type              | Attributes                                  |
+// |----------------------------|-------------------|---------------------------------------------|
+// | `ntomb.connections`        | gauge             | process.executable.name, process.pid, tcp.state |
+// | `ntomb.connections.opened` | cumulative sum    |                                             |
+// | `ntomb.connections.closed` | cumulative sum    |                                             |
+// | `ntomb.endpoints`          | gauge             | latency.bucket                              |
+//
+// The resource carries `service.name = "ntomb"` and `host.name`. Requests
+// are sent with curl(1) from a background thread; a collector that is down
+// is reported once in the Grimoire and never slows the UI. The headers
+// are handed to curl in a file only the user can read (`-H @file`), so
+// credentials never show up in its command line.
+
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+#[cfg(feature = "otlp")]
+pub use exporter::OtlpExporter;
+
+/// `[otlp]` section of config.toml
+#[derive(Debug, Clone, Default, Deserialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct OtlpConfig {
+    /// OTLP/HTTP metrics URL; export is off when unset
+    pub endpoint: Option<String>,
+    /// Extra HTTP headers (authentication for hosted collectors)
+    pub headers: BTreeMap<String, String>,
+}
+
+#[cfg(feature = "otlp")]
+mod exporter {
+    use super::OtlpConfig;
+    use crate::app::config::{LatencyBucket, LatencyConfig};
+    use crate::app::mermaid::local_host_name;
+    use crate::app::stream::{connection_events, json_string, StreamEvent};
+    use crate::net::Connection;
+    use std::collections::{BTreeMap, HashSet};
+    use std::fs::{self, OpenOptions};
+    use std::io::{self, Write as _};
+    use std::path::{Path, PathBuf};
+    use std::process::{self, Command, Stdio};
+    use std::sync::mpsc::{self, Receiver, Sender};
+    use std::thread;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    /// Per-process connection counts: (process name, pid, state) → count
+    type ConnectionCounts<'a> = BTreeMap<(Option<&'a str>, Option<i32>, &'static str), u64>;
+
+    /// Summarizes snapshots and ships them to a collector
+    #[derive(Debug)]
+    pub struct OtlpExporter {
+        requests: Sender<String>,
+        errors: Receiver<Result<(), String>>,
+        host: String,
+        start_nanos: u128,
+        /// Last snapshot, for churn; None before the first one
+        previous: Option<Vec<Connection>>,
+        opened: u64,
+        closed: u64,
+        failing: bool,
+        /// Removed along with the exporter
+        #[allow(dead_code)]
+        header_file: Option<HeaderFile>,
+    }
+
+    impl OtlpExporter {
+        /// Start the sender thread for `config`; None when no endpoint is set
+        pub fn spawn(config: &OtlpConfig) -> Result<Option<Self>, String> {
+            let Some(endpoint) = config.endpoint.clone() else {
+                return Ok(None);
+            };
+            let mut headers = Vec::new();
+            for (name, value) in &config.headers {
+                if name.contains(['\n', '\r']) || value.contains(['\n', '\r']) {
+                    return Err(format!("header {} contains a line break", name));
+                }
+                headers.push(format!("{}: {}", name, value));
+            }
+            let header_file = if headers.is_empty() {
+                None
+            } else {
+                let file = HeaderFile::create(&headers)
+                    .map_err(|e| format!("cannot write the headers for curl: {}", e))?;
+                Some(file)
+            };
+            let header_path = header_file.as_ref().map(|file| file.path.clone());
+            let (requests, queue) = mpsc::channel::<String>();
+            let (results, errors) = mpsc::channel();
+            thread::spawn(move || {
+                for body in queue {
+                    let outcome = post(&endpoint, header_path.as_deref(), &body);
+                    if results.send(outcome).is_err() {
+                        break;
+                    }
+                }
+            });
+            Ok(Some(Self {
+                requests,
+                errors,
+                host: local_host_name(),
+                start_nanos: now_nanos(),
+                previous: None,
+                opened: 0,
+                closed: 0,
+                failing: false,
+                header_file,
+            }))
+        }
+
+        /// Export metrics for a fresh snapshot
+        ///
+        /// Returns a message when the collector starts or stops failing,
+        /// for the Grimoire.
+        pub fn record(
+            &mut self,
+            conns: &[Connection],
+            latency_config: &LatencyConfig,
+        ) -> Option<Result<String, String>> {
+            // Sockets already open at startup are not counted as opened
+            if let Some(previous) = &self.previous {
+                for (event, _) in connection_events(previous, conns) {
+                    match event {
+                        StreamEvent::Open => self.opened += 1,
+                        StreamEvent::Close => self.closed += 1,
+                        StreamEvent::StateChange(_) => {}
+                    }
+                }
+            }
+            self.previous = Some(conns.to_vec());
+            let body = self.payload(conns, latency_config, now_nanos());
+            let _ = self.requests.send(body);
+
+            // Only the latest outcome matters
+            let outcome = self.errors.try_iter().last()?;
+            match outcome {
+                Err(e) if !self.failing => {
+                    self.failing = true;
+                    Some(Err(e))
+                }
+                Ok(()) if self.failing => {
+                    self.failing = false;
+                    Some(Ok("OTLP export recovered".to_string()))
+                }
+                _ => None,
+            }
+        }
+
+        /// OTLP/HTTP JSON request body for `conns` at `time` (Unix nanoseconds)
+        pub(super) fn payload(
+            &self,
+            conns: &[Connection],
+            latency_config: &LatencyConfig,
+            time: u128,
+        ) -> String {
+            let mut counts = ConnectionCounts::new();
+            for conn in conns {
+                *counts
+                    .entry((conn.process_name.as_deref(), conn.pid, conn.state.name()))
+                    .or_default() += 1;
+            }
+            let connection_points: Vec<String> = counts
+                .iter()
+                .map(|((name, pid, state), count)| {
+                    let mut attributes = Vec::new();
+                    if let Some(name) = name {
+                        attributes.push(string_attribute("process.executable.name", name));
+                    }
+                    if let Some(pid) = pid {
+                        attributes.push(format!(
+                            "{{\"key\":\"process.pid\",\"value\":{{\"intValue\":\"{}\"}}}}",
+                            pid
+                        ));
+                    }
+                    attributes.push(string_attribute("tcp.state", state));
+                    data_point(&attributes, None, time, *count)
+                })
+                .collect();
+
+            // Endpoints per latency ring, as the Graveyard places them
+            let endpoints: HashSet<&str> = conns
+                .iter()
+                .filter(|c| c.remote_port != 0)
+                .map(|c| &*c.remote_addr)
+                .collect();
+            let mut buckets: BTreeMap<&str, u64> = BTreeMap::new();
+            for _ in &endpoints {
+                let bucket = crate::ui::classify_latency(None, latency_config);
+                *buckets.entry(bucket_name(bucket)).or_default() += 1;
+            }
+            let endpoint_points: Vec<String> = buckets
+                .iter()
+                .map(|(bucket, count)| {
+                    data_point(
+                        &[string_attribute("latency.bucket", bucket)],
+                        None,
+                        time,
+                        *count,
+                    )
+                })
+                .collect();
+
+            let metrics = [
+                gauge("ntomb.connections", "{connection}", &connection_points),
+                sum(
+                    "ntomb.connections.opened",
+                    &data_point(&[], Some(self.start_nanos), time, self.opened),
+                ),
+                sum(
+                    "ntomb.connections.closed",
+                    &data_point(&[], Some(self.start_nanos), time, self.closed),
+                ),
+                gauge("ntomb.endpoints", "{endpoint}", &endpoint_points),
+            ];
+            format!(
+                "{{\"resourceMetrics\":[{{\"resource\":{{\"attributes\":[{},{}]}},\
+                 \"scopeMetrics\":[{{\"scope\":{{\"name\":\"ntomb\",\"version\":\"{}\"}},\
+                 \"metrics\":[{}]}}]}}]}}",
+                string_attribute("service.name", "ntomb"),
+                string_attribute("host.name", &self.host),
+                env!("CARGO_PKG_VERSION"),
+                metrics.join(",")
+            )
+        }
+    }
+
+    fn bucket_name(bucket: LatencyBucket) -> &'static str {
+        match bucket {
+            LatencyBucket::Low => "low",
+            LatencyBucket::Medium => "medium",
+            LatencyBucket::High => "high",
+            LatencyBucket::Unknown => "unknown",
+        }
+    }
+
+    fn string_attribute(key: &str, value: &str) -> String {
+        format!(
+            "{{\"key\":\"{}\",\"value\":{{\"stringValue\":{}}}}}",
+            key,
+            json_string(value)
+        )
+    }
+
+    /// Integer data point; `start` is set for cumulative sums
+    fn data_point(attributes: &[String], start: Option<u128>, time: u128, value: u64) -> String {
+        let start = start
+            .map(|start| format!("\"startTimeUnixNano\":\"{}\",", start))
+            .unwrap_or_default();
+        format!(
+            "{{\"attributes\":[{}],{}\"timeUnixNano\":\"{}\",\"asInt\":\"{}\"}}",
+            attributes.join(","),
+            start,
+            time,
+            value
+        )
+    }
+
+    fn gauge(name: &str, unit: &str, points: &[String]) -> String {
+        format!(
+            "{{\"name\":\"{}\",\"unit\":\"{}\",\"gauge\":{{\"dataPoints\":[{}]}}}}",
+            name,
+            unit,
+            points.join(",")
+        )
+    }
+
+    /// Monotonic cumulative counter (aggregationTemporality 2)
+    fn sum(name: &str, point: &str) -> String {
+        format!(
+            "{{\"name\":\"{}\",\"unit\":\"{{connection}}\",\"sum\":{{\"aggregationTemporality\":2,\
+             \"isMonotonic\":true,\"dataPoints\":[{}]}}}}",
+            name, point
+        )
+    }
+
+    fn now_nanos() -> u128 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0)
+    }
+
+    /// Extra headers in a file readable by the user alone, one per line
+    #[derive(Debug)]
+    struct HeaderFile {
+        path: PathBuf,
+    }
+
+    impl HeaderFile {
+        fn create(headers: &[String]) -> io::Result<Self> {
+            let path = std::env::temp_dir().join(format!("ntomb-otlp-headers-{}", process::id()));
+            // Left over by an earlier run with the same PID
+            let _ = fs::remove_file(&path);
+            let mut options = OpenOptions::new();
+            options.write(true).create_new(true);
+            #[cfg(unix)]
+            std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+            let mut file = options.open(&path)?;
+            let header_file = Self { path };
+            for header in headers {
+                writeln!(file, "{}", header)?;
+            }
+            Ok(header_file)
+        }
+    }
+
+    impl Drop for HeaderFile {
+        fn drop(&mut self) {
+            let _ = fs::remove_file(&self.path);
+        }
+    }
+
+    /// POST `body` to `endpoint` with curl, adding the headers in
+    /// `header_file`
+    fn post(endpoint: &str, header_file: Option<&Path>, body: &str) -> Result<(), String> {
+        let mut command = Command::new("curl");
+        command.args([
+            "-fsS",
+            "--max-time",
+            "10",
+            "-o",
+            "/dev/null",
+            "-H",
+            "Content-Type: application/json",
+        ]);
+        if let Some(path) = header_file {
+            let mut arg = std::ffi::OsString::from("@");
+            arg.push(path);
+            command.arg("-H").arg(arg);
+        }
+        let mut child = command
+            .args(["--data-binary", "@-", endpoint])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| format!("cannot run curl: {}", e))?;
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin
+                .write_all(body.as_bytes())
+                .map_err(|e| format!("cannot send metrics: {}", e))?;
+        }
+        let output = child
+            .wait_with_output()
+            .map_err(|e| format!("curl failed: {}", e))?;
+        if output.status.success() {
+            Ok(())
+        } else {
+            Err(format!(
+                "OTLP export to {} failed: {}",
+                endpoint,
+                String::from_utf8_lossy(&output.stderr).trim()
+            ))
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use crate::net::ConnectionState;
+
+        #[test]
+        fn test_payload() {
+            let mut exporter = OtlpExporter::spawn(&OtlpConfig {
+                endpoint: Some("http://127.0.0.1:9/v1/metrics".to_string()),
+                ..Default::default()
+            })
+            .unwrap()
+            .unwrap();
+            exporter.host = "web-1".to_string();
+            exporter.start_nanos = 1;
+            exporter.opened = 3;
+            let conn = Connection {
+                remote_addr: "93.184.216.34".into(),
+                remote_port: 443,
+                state: ConnectionState::Established,
+                pid: Some(812),
+                process_name: Some("curl".into()),
+                ..Default::default()
+            };
+            let body = exporter.payload(&[conn.clone(), conn], &LatencyConfig::default(), 5);
+            assert!(body.starts_with(
+                "{\"resourceMetrics\":[{\"resource\":{\"attributes\":[\
+                 {\"key\":\"service.name\",\"value\":{\"stringValue\":\"ntomb\"}},\
+                 {\"key\":\"host.name\",\"value\":{\"stringValue\":\"web-1\"}}]}"
+            ));
+            assert!(body.contains(
+                "{\"name\":\"ntomb.connections\",\"unit\":\"{connection}\",\"gauge\":{\"dataPoints\":[\
+                 {\"attributes\":[{\"key\":\"process.executable.name\",\"value\":{\"stringValue\":\"curl\"}},\
+                 {\"key\":\"process.pid\",\"value\":{\"intValue\":\"812\"}},\
+                 {\"key\":\"tcp.state\",\"value\":{\"stringValue\":\"ESTABLISHED\"}}],\
+                 \"timeUnixNano\":\"5\",\"asInt\":\"2\"}]}}"
+            ));
+            assert!(body.contains(
+                "\"name\":\"ntomb.connections.opened\",\"unit\":\"{connection}\",\"sum\":{\
+                 \"aggregationTemporality\":2,\"isMonotonic\":true,\"dataPoints\":[{\"attributes\":[],\
+                 \"startTimeUnixNano\":\"1\",\"timeUnixNano\":\"5\",\"asInt\":\"3\"}]}"
+            ));
+            assert!(body.contains(
+                "{\"attributes\":[{\"key\":\"latency.bucket\",\"value\":{\"stringValue\":\"unknown\"}}],\
+                 \"timeUnixNano\":\"5\",\"asInt\":\"1\"}"
+            ));
+        }
+
+        #[test]
+        fn test_headers_stay_off_the_command_line() {
+            let mut config = OtlpConfig {
+                endpoint: Some("http://127.0.0.1:9/v1/metrics".to_string()),
+                ..Default::default()
+            };
+            config
+                .headers
+                .insert("authorization".to_string(), "Bearer s3cr3t".to_string());
+            let exporter = OtlpExporter::spawn(&config).unwrap().unwrap();
+            let path = exporter.header_file.as_ref().unwrap().path.clone();
+            assert_eq!(
+                fs::read_to_string(&path).unwrap(),
+                "authorization: Bearer s3cr3t\n"
+            );
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::PermissionsExt;
+                let mode = fs::metadata(&path).unwrap().permissions().mode();
+                assert_eq!(mode & 0o777, 0o600);
+            }
+            drop(exporter);
+            assert!(!path.exists());
+
+            config
+                .headers
+                .insert("x-scope".to_string(), "a\r\nx-injected: 1".to_string());
+            assert!(OtlpExporter::spawn(&config).is_err());
+        }
+    }
+}
@@ -0,0 +1,283 @@
+// First-seen / last-seen endpoint database
+//
+// Every remote address ntomb sees is remembered across restarts, with when
+// it was first and last seen. A peer the host never talked to before is
+// one of the strongest hints that something changed, so the first time
+// one shows up a Grimoire event names it and its Graveyard label wears a
+// NEW badge for an hour:
+//
+// ```toml
+// [peers]
+// enabled = true                      # default
+// file = "/var/lib/ntomb/peers.tsv"   # default $XDG_STATE_HOME/ntomb/peers.tsv
+// ```
+//
+// Each line is "address<TAB>first seen<TAB>last seen" in Unix seconds;
+// loopback and unspecified addresses are left out. The file is rewritten
+// at most once a minute while it changes, and when ntomb exits. When
+// there is no file yet, the peers of the first snapshot are the baseline
+// and none of them is NEW; the baseline time is kept in the header line.
+
+use crate::net::scope::{address_scope, AddressScope};
+use crate::net::{Connection, ConnectionState};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// How long a new peer wears the NEW badge
+pub const NEW_PEER_FOR: Duration = Duration::from_secs(60 * 60);
+
+/// Minimum time between two writes of the database
+const SAVE_EVERY: Duration = Duration::from_secs(60);
+
+/// Header line of the file, followed by the baseline time
+const HEADER: &str = "# ntomb peers: address, first seen, last seen; baseline";
+
+/// `[peers]` section of config.toml
+#[derive(Debug, Clone, Default, Deserialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct PeersConfig {
+    /// Keep the database (default true)
+    pub enabled: Option<bool>,
+    /// Where it is kept (default $XDG_STATE_HOME/ntomb/peers.tsv)
+    pub file: Option<PathBuf>,
+}
+
+/// When a peer was first and last seen (Unix seconds)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PeerSeen {
+    pub first: u64,
+    pub last: u64,
+}
+
+/// Every remote address seen so far, kept in a file
+#[derive(Debug)]
+pub struct PeerStore {
+    path: PathBuf,
+    peers: HashMap<Arc<str>, PeerSeen>,
+    /// Peers first seen at or before this time are never NEW; None until
+    /// the first snapshot of a fresh database
+    baseline: Option<u64>,
+    /// Changed since the last write
+    changed: bool,
+    saved_at: Option<Instant>,
+}
+
+impl PeerStore {
+    /// The configured database, read from disk; None when disabled or
+    /// there is nowhere to keep it
+    pub fn from_config(config: &PeersConfig) -> Option<Self> {
+        if !config.enabled.unwrap_or(true) {
+            return None;
+        }
+        let path = config.file.clone().or_else(default_path)?;
+        let store = match fs::read_to_string(&path) {
+            Ok(content) => Self::parse(path, &content),
+            Err(e) => {
+                if e.kind() != io::ErrorKind::NotFound {
+                    tracing::warn!(path = %path.display(), error = %e, "Cannot read peer database, starting over");
+                }
+                Self::parse(path, "")
+            }
+        };
+        Some(store)
+    }
+
+    /// A database holding the lines of `content`; malformed lines are
+    /// skipped
+    fn parse(path: PathBuf, content: &str) -> Self {
+        let mut baseline = None;
+        let mut peers = HashMap::new();
+        for line in content.lines() {
+            if let Some(header) = line.strip_prefix(HEADER) {
+                baseline = header.trim().parse().ok();
+                continue;
+            }
+            let mut fields = line.split('\t');
+            let (Some(addr), Some(first), Some(last)) =
+                (fields.next(), fields.next(), fields.next())
+            else {
+                continue;
+            };
+            if let (Ok(first), Ok(last)) = (first.parse(), last.parse()) {
+                peers.insert(addr.into(), PeerSeen { first, last });
+            }
+        }
+        Self {
+            path,
+            peers,
+            baseline,
+            changed: false,
+            saved_at: None,
+        }
+    }
+
+    /// Record the remote addresses of a snapshot taken at `now`; returns
+    /// the ones never seen before (none for the baseline snapshot)
+    pub fn observe(&mut self, conns: &[Connection], now: SystemTime) -> Vec<Arc<str>> {
+        let now = unix_secs(now);
+        let baseline = *self.baseline.get_or_insert(now);
+        let mut new_peers = Vec::new();
+        for conn in conns {
+            if conn.state == ConnectionState::Listen
+                || !matches!(
+                    address_scope(&conn.remote_addr),
+                    Some(AddressScope::Private | AddressScope::Public)
+                )
+            {
+                continue;
+            }
+            match self.peers.get_mut(&conn.remote_addr) {
+                Some(seen) => seen.last = seen.last.max(now),
+                None => {
+                    self.peers.insert(
+                        conn.remote_addr.clone(),
+                        PeerSeen {
+                            first: now,
+                            last: now,
+                        },
+                    );
+                    if now > baseline {
+                        new_peers.push(conn.remote_addr.clone());
+                    }
+                }
+            }
+        }
+        self.changed = true;
+        new_peers
+    }
+
+    /// When `addr` was first and last seen
+    pub fn seen(&self, addr: &str) -> Option<PeerSeen> {
+        self.peers.get(addr).copied()
+    }
+
+    /// Whether `addr` first appeared after the baseline, less than
+    /// NEW_PEER_FOR before `now`
+    pub fn is_new(&self, addr: &str, now: SystemTime) -> bool {
+        let (Some(seen), Some(baseline)) = (self.peers.get(addr), self.baseline) else {
+            return false;
+        };
+        seen.first > baseline && seen.first + NEW_PEER_FOR.as_secs() > unix_secs(now)
+    }
+
+    /// Write the database if it changed and the last write is a while ago
+    pub fn save_if_due(&mut self, now: Instant) {
+        let due = self
+            .saved_at
+            .map_or(true, |at| now.duration_since(at) >= SAVE_EVERY);
+        if self.changed && due {
+            self.saved_at = Some(now);
+            self.save();
+        }
+    }
+
+    /// Write the database now if it changed; failures are logged
+    pub fn save(&mut self) {
+        if !self.changed {
+            return;
+        }
+        match write_peers(&self.path, self.baseline, &self.peers) {
+            Ok(()) => self.changed = false,
+            Err(e) => {
+                tracing::warn!(path = %self.path.display(), error = %e, "Cannot save peer database")
+            }
+        }
+    }
+}
+
+/// Write `peers` to `path` via a temporary file, so a crash never leaves
+/// half a database
+fn write_peers(
+    path: &Path,
+    baseline: Option<u64>,
+    peers: &HashMap<Arc<str>, PeerSeen>,
+) -> io::Result<()> {
+    if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+        fs::create_dir_all(dir)?;
+    }
+    let mut sorted: Vec<_> = peers.iter().collect();
+    sorted.sort_by(|a, b| a.0.cmp(b.0));
+    let mut content = format!("{} {}\n", HEADER, baseline.unwrap_or(0));
+    for (addr, seen) in sorted {
+        content.push_str(&format!("{}\t{}\t{}\n", addr, seen.first, seen.last));
+    }
+    let tmp = path.with_extension("tmp");
+    fs::write(&tmp, content)?;
+    fs::rename(&tmp, path)
+}
+
+/// Whole seconds since the epoch (0 before it)
+pub(super) fn unix_secs(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs())
+}
+
+/// $XDG_STATE_HOME/ntomb/peers.tsv or ~/.local/state/ntomb/peers.tsv
+fn default_path() -> Option<PathBuf> {
+    let base = std::env::var_os("XDG_STATE_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| {
+            std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".local").join("state"))
+        })?;
+    Some(base.join("ntomb").join("peers.tsv"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn conn(remote_addr: &str) -> Connection {
+        Connection {
+            local_addr: "10.0.0.5".into(),
+            local_port: 50000,
+            remote_addr: remote_addr.into(),
+            remote_port: 443,
+            state: ConnectionState::Established,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_new_peers_after_the_baseline() {
+        let dir = std::env::temp_dir().join(format!("ntomb-peers-{}", std::process::id()));
+        let path = dir.join("peers.tsv");
+        let _ = fs::remove_file(&path);
+        let config = PeersConfig {
+            file: Some(path.clone()),
+            ..Default::default()
+        };
+        let start = UNIX_EPOCH + Duration::from_secs(1_760_000_000);
+
+        // The first snapshot of a fresh database is the baseline
+        let mut store = PeerStore::from_config(&config).unwrap();
+        let new = store.observe(&[conn("93.184.216.34"), conn("127.0.0.1")], start);
+        assert!(new.is_empty());
+        assert!(!store.is_new("93.184.216.34", start));
+        assert_eq!(store.seen("127.0.0.1"), None);
+
+        let later = start + Duration::from_secs(60);
+        let new = store.observe(&[conn("93.184.216.34"), conn("10.1.0.1")], later);
+        assert_eq!(new, [Arc::<str>::from("10.1.0.1")]);
+        assert!(store.is_new("10.1.0.1", later));
+        assert!(!store.is_new("10.1.0.1", later + NEW_PEER_FOR));
+        store.save();
+
+        // Reloaded: the baseline and timestamps survive
+        let store = PeerStore::from_config(&config).unwrap();
+        assert_eq!(
+            store.seen("93.184.216.34"),
+            Some(PeerSeen {
+                first: 1_760_000_000,
+                last: 1_760_000_060
+            })
+        );
+        assert!(!store.is_new("93.184.216.34", later));
+        assert!(store.is_new("10.1.0.1", later));
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
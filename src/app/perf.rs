@@ -0,0 +1,97 @@
+// Performance overlay data
+//
+// Frame times and per-refresh collection durations from the last minute,
+// kept so the performance overlay (`%`) can graph them next to the
+// slow-frame threshold and explain why the animation quality dropped.
+
+use super::config::PERF_WINDOW;
+use super::quality::AnimationQuality;
+use std::collections::VecDeque;
+use std::time::{Duration, Instant, SystemTime};
+
+/// Timed samples no older than PERF_WINDOW, oldest first
+#[derive(Debug, Clone, Default)]
+pub struct Samples {
+    samples: VecDeque<(Instant, Duration)>,
+}
+
+impl Samples {
+    /// Add a sample taken at `now` and drop the ones that left the window
+    pub fn push(&mut self, now: Instant, duration: Duration) {
+        self.samples.push_back((now, duration));
+        while let Some(&(at, _)) = self.samples.front() {
+            if now.duration_since(at) <= PERF_WINDOW {
+                break;
+            }
+            self.samples.pop_front();
+        }
+    }
+
+    /// Durations in milliseconds, oldest first
+    pub fn millis(&self) -> Vec<u64> {
+        self.samples
+            .iter()
+            .map(|(_, duration)| duration.as_millis() as u64)
+            .collect()
+    }
+
+    /// Mean duration in milliseconds (0 when empty)
+    pub fn average_ms(&self) -> u64 {
+        if self.samples.is_empty() {
+            return 0;
+        }
+        let total: u128 = self.samples.iter().map(|(_, d)| d.as_millis()).sum();
+        (total / self.samples.len() as u128) as u64
+    }
+
+    /// Longest duration in milliseconds (0 when empty)
+    pub fn peak_ms(&self) -> u64 {
+        self.samples
+            .iter()
+            .map(|(_, duration)| duration.as_millis() as u64)
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+/// When and why the animation quality was last stepped down
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Reduction {
+    pub at: SystemTime,
+    /// Tier before the first automatic step
+    pub from: AnimationQuality,
+    /// Tier after the latest step
+    pub to: AnimationQuality,
+    /// The frame that crossed the slow-frame count
+    pub frame_ms: u64,
+    /// Consecutive slow frames at that point
+    pub slow_frames: u32,
+}
+
+/// Everything the performance overlay shows
+#[derive(Debug, Clone, Default)]
+pub struct PerfStats {
+    pub frames: Samples,
+    pub collections: Samples,
+    pub reduction: Option<Reduction>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_samples_window() {
+        let start = Instant::now();
+        let mut samples = Samples::default();
+        samples.push(start, Duration::from_millis(40));
+        samples.push(start + Duration::from_secs(30), Duration::from_millis(120));
+        assert_eq!(samples.millis(), vec![40, 120]);
+        assert_eq!(samples.average_ms(), 80);
+        assert_eq!(samples.peak_ms(), 120);
+
+        // The first sample is now more than a minute old
+        samples.push(start + Duration::from_secs(61), Duration::from_millis(10));
+        assert_eq!(samples.millis(), vec![120, 10]);
+    }
+}
@@ -0,0 +1,266 @@
+// Local port heatmap
+//
+// `R` shows a panel below the Graveyard with how the local port space is
+// used: one row per range (well-known, registered, ephemeral) and a strip
+// of the whole 0-65535 space shaded by connection count and colored by
+// the dominant state. A crowded ephemeral range warns of port exhaustion
+// (typically a pile of TIME_WAIT sockets), and listeners in the ephemeral
+// range stand out as unusual service placement.
+//
+// The ephemeral range is the kernel's (/proc/sys/net/ipv4/ip_local_port_range
+// on Linux), falling back to the IANA dynamic range 49152-65535.
+
+use crate::net::{Connection, ConnectionState};
+use std::collections::HashSet;
+use std::ops::RangeInclusive;
+
+/// IANA dynamic/private ports, used when the kernel's range is unknown
+pub const IANA_EPHEMERAL: RangeInclusive<u16> = 49152..=65535;
+
+/// Where a local port lies
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PortRange {
+    /// 0-1023: system services
+    WellKnown,
+    /// 1024 and up outside the ephemeral range: user services
+    Registered,
+    /// Ports the kernel hands out to outgoing connections
+    Ephemeral,
+}
+
+impl PortRange {
+    pub const ALL: [PortRange; 3] = [
+        PortRange::WellKnown,
+        PortRange::Registered,
+        PortRange::Ephemeral,
+    ];
+
+    /// Range of `port` given the kernel's `ephemeral` range
+    pub fn of(port: u16, ephemeral: &RangeInclusive<u16>) -> Self {
+        if port < 1024 {
+            PortRange::WellKnown
+        } else if ephemeral.contains(&port) {
+            PortRange::Ephemeral
+        } else {
+            PortRange::Registered
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            PortRange::WellKnown => "well-known",
+            PortRange::Registered => "registered",
+            PortRange::Ephemeral => "ephemeral",
+        }
+    }
+}
+
+/// Connections by kind of state
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StateMix {
+    pub listen: usize,
+    pub established: usize,
+    /// Closing down: TIME_WAIT, CLOSE_WAIT, FIN_WAIT*, LAST_ACK, CLOSING
+    pub closing: usize,
+    /// Handshakes and anything else
+    pub other: usize,
+}
+
+impl StateMix {
+    fn add(&mut self, state: ConnectionState) {
+        use ConnectionState::*;
+        match state {
+            Listen => self.listen += 1,
+            Established => self.established += 1,
+            TimeWait | CloseWait | FinWait1 | FinWait2 | LastAck | Closing | Close => {
+                self.closing += 1
+            }
+            SynSent | SynRecv | Unknown => self.other += 1,
+        }
+    }
+
+    pub fn total(&self) -> usize {
+        self.listen + self.established + self.closing + self.other
+    }
+
+    /// The kind with the most connections (ties go to the first of
+    /// closing, established, listen, other); None when empty
+    pub fn dominant(&self) -> Option<ConnectionState> {
+        let kinds = [
+            (self.closing, ConnectionState::TimeWait),
+            (self.established, ConnectionState::Established),
+            (self.listen, ConnectionState::Listen),
+            (self.other, ConnectionState::Unknown),
+        ];
+        kinds
+            .iter()
+            .rev()
+            .max_by_key(|(count, _)| *count)
+            .filter(|(count, _)| *count > 0)
+            .map(|&(_, state)| state)
+    }
+}
+
+/// Usage of one port range
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RangeUsage {
+    pub range: PortRange,
+    /// Ports in the range
+    pub size: usize,
+    /// Distinct local ports in use
+    pub ports: usize,
+    pub mix: StateMix,
+}
+
+impl RangeUsage {
+    /// Share of the range in use, in percent
+    pub fn percent_used(&self) -> f64 {
+        self.ports as f64 * 100.0 / self.size.max(1) as f64
+    }
+}
+
+/// Local port usage of a snapshot
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PortHeatmap {
+    /// One per `PortRange::ALL`
+    pub ranges: Vec<RangeUsage>,
+    /// The 0-65535 space cut into equal slices, lowest ports first
+    pub buckets: Vec<StateMix>,
+    /// LISTEN sockets on ephemeral ports
+    pub ephemeral_listeners: usize,
+}
+
+impl PortHeatmap {
+    /// Usage of `conns`' local ports, with the port space cut into
+    /// `buckets` slices for the strip
+    pub fn build(conns: &[Connection], ephemeral: &RangeInclusive<u16>, buckets: usize) -> Self {
+        let buckets = buckets.max(1);
+        let mut ranges: Vec<RangeUsage> = PortRange::ALL
+            .iter()
+            .map(|&range| RangeUsage {
+                range,
+                size: range_size(range, ephemeral),
+                ports: 0,
+                mix: StateMix::default(),
+            })
+            .collect();
+        let mut strip = vec![StateMix::default(); buckets];
+        let mut seen: HashSet<u16> = HashSet::new();
+        let mut ephemeral_listeners = 0;
+
+        for conn in conns {
+            let port = conn.local_port;
+            let range = PortRange::of(port, ephemeral);
+            let usage = &mut ranges[range as usize];
+            usage.mix.add(conn.state);
+            if seen.insert(port) {
+                usage.ports += 1;
+            }
+            if range == PortRange::Ephemeral && conn.state == ConnectionState::Listen {
+                ephemeral_listeners += 1;
+            }
+            strip[port as usize * buckets / 65536].add(conn.state);
+        }
+
+        PortHeatmap {
+            ranges,
+            buckets: strip,
+            ephemeral_listeners,
+        }
+    }
+}
+
+fn range_size(range: PortRange, ephemeral: &RangeInclusive<u16>) -> usize {
+    // Part of the ephemeral range below 1024 still counts as well-known
+    let low = (*ephemeral.start()).max(1024) as usize;
+    let high = *ephemeral.end() as usize;
+    let ephemeral_size = (high + 1).saturating_sub(low);
+    match range {
+        PortRange::WellKnown => 1024,
+        PortRange::Ephemeral => ephemeral_size,
+        PortRange::Registered => 65536 - 1024 - ephemeral_size,
+    }
+}
+
+/// "32768\t60999" as written in ip_local_port_range
+pub fn parse_port_range(content: &str) -> Option<RangeInclusive<u16>> {
+    let mut parts = content.split_whitespace().map(str::parse::<u16>);
+    match (parts.next(), parts.next()) {
+        (Some(Ok(low)), Some(Ok(high))) if low <= high => Some(low..=high),
+        _ => None,
+    }
+}
+
+/// The kernel's ephemeral port range, or the IANA range elsewhere
+pub fn local_port_range() -> RangeInclusive<u16> {
+    #[cfg(target_os = "linux")]
+    {
+        std::fs::read_to_string("/proc/sys/net/ipv4/ip_local_port_range")
+            .ok()
+            .and_then(|content| parse_port_range(&content))
+            .unwrap_or(IANA_EPHEMERAL)
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    {
+        IANA_EPHEMERAL
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn conn(local_port: u16, state: ConnectionState) -> Connection {
+        Connection {
+            local_port,
+            state,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_port_range() {
+        let ephemeral = 32768..=60999;
+        assert_eq!(PortRange::of(22, &ephemeral), PortRange::WellKnown);
+        assert_eq!(PortRange::of(5432, &ephemeral), PortRange::Registered);
+        assert_eq!(PortRange::of(40000, &ephemeral), PortRange::Ephemeral);
+        assert_eq!(PortRange::of(62000, &ephemeral), PortRange::Registered);
+        assert_eq!(parse_port_range("32768\t60999\n"), Some(ephemeral));
+        assert_eq!(parse_port_range("60999 32768"), None);
+        assert_eq!(parse_port_range(""), None);
+    }
+
+    #[test]
+    fn test_build_heatmap() {
+        use ConnectionState::*;
+        let conns = [
+            conn(22, Listen),
+            conn(22, Established),
+            conn(40000, Listen),
+            conn(50001, TimeWait),
+            conn(50002, TimeWait),
+            conn(50003, Established),
+        ];
+        let heatmap = PortHeatmap::build(&conns, &(32768..=60999), 4);
+
+        let well_known = &heatmap.ranges[PortRange::WellKnown as usize];
+        assert_eq!((well_known.ports, well_known.mix.total()), (1, 2));
+        let ephemeral = &heatmap.ranges[PortRange::Ephemeral as usize];
+        assert_eq!(ephemeral.size, 60999 - 32768 + 1);
+        assert_eq!(ephemeral.ports, 4);
+        assert_eq!(ephemeral.mix.dominant(), Some(TimeWait));
+        assert_eq!(
+            heatmap.ranges[PortRange::Registered as usize].size,
+            65536 - 1024 - 28232
+        );
+        assert_eq!(heatmap.ephemeral_listeners, 1);
+
+        // 16384 ports per bucket
+        assert_eq!(heatmap.buckets[0].total(), 2);
+        assert_eq!(heatmap.buckets[1].total(), 0);
+        assert_eq!(heatmap.buckets[2].total(), 1);
+        assert_eq!(heatmap.buckets[3].total(), 3);
+        assert_eq!(heatmap.buckets[1].dominant(), None);
+    }
+}
@@ -0,0 +1,194 @@
+// Network probe budget
+//
+// Reverse DNS lookups, RDAP queries and threat-intel feed downloads put
+// packets on the network ntomb is observing. Every such probe takes a
+// token from one shared bucket, so turning more features on never makes
+// ntomb noisy, and `passive_only` turns them all off (cached blocklists
+// are still read):
+//
+// ```toml
+// [probes]
+// passive_only = false
+// rate = 5     # probes per second (default 5)
+// burst = 10   # probes allowed at once after a quiet spell (default 10)
+// ```
+//
+// Lookups and downloads that find the bucket empty are retried with the
+// next refresh; an RDAP query waits for another keypress.
+// The performance overlay (`%`) shows how many probes were sent.
+
+use serde::Deserialize;
+use std::time::Instant;
+
+/// Default probes per second
+pub const DEFAULT_PROBE_RATE: u32 = 5;
+
+/// Default bucket size
+pub const DEFAULT_PROBE_BURST: u32 = 10;
+
+/// `[probes]` section of config.toml
+#[derive(Debug, Clone, Default, Deserialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct ProbesConfig {
+    /// Never send anything to the network
+    pub passive_only: bool,
+    /// Probes per second (default 5)
+    pub rate: Option<u32>,
+    /// Probes allowed at once (default 10)
+    pub burst: Option<u32>,
+}
+
+/// What a probe is for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProbeKind {
+    /// Reverse DNS lookup
+    Dns,
+    /// Threat-intel feed download
+    Feed,
+    /// RDAP ("whois") query
+    Rdap,
+}
+
+/// Token bucket shared by every probing feature
+#[derive(Debug, Clone)]
+pub struct ProbeLimiter {
+    passive_only: bool,
+    rate: f64,
+    burst: f64,
+    tokens: f64,
+    refilled: Option<Instant>,
+    /// Probes sent, by kind
+    dns: u64,
+    feeds: u64,
+    rdap: u64,
+    /// Probes that found the bucket empty
+    deferred: u64,
+}
+
+impl Default for ProbeLimiter {
+    fn default() -> Self {
+        Self::from_config(&ProbesConfig::default())
+    }
+}
+
+impl ProbeLimiter {
+    pub fn from_config(config: &ProbesConfig) -> Self {
+        let burst = f64::from(config.burst.unwrap_or(DEFAULT_PROBE_BURST).max(1));
+        Self {
+            passive_only: config.passive_only,
+            rate: f64::from(config.rate.unwrap_or(DEFAULT_PROBE_RATE).max(1)),
+            burst,
+            tokens: burst,
+            refilled: None,
+            dns: 0,
+            feeds: 0,
+            rdap: 0,
+            deferred: 0,
+        }
+    }
+
+    /// Take over the settings of `other` (a reloaded config), keeping the
+    /// counts
+    pub fn reconfigure(&mut self, other: ProbeLimiter) {
+        self.passive_only = other.passive_only;
+        self.rate = other.rate;
+        self.burst = other.burst;
+        self.tokens = self.tokens.min(self.burst);
+    }
+
+    /// Whether nothing may be sent at all
+    pub fn passive_only(&self) -> bool {
+        self.passive_only
+    }
+
+    /// Take a token for a `kind` probe; false when passive-only or the
+    /// bucket is empty (try again later)
+    pub fn allow(&mut self, kind: ProbeKind, now: Instant) -> bool {
+        if self.passive_only {
+            return false;
+        }
+        if let Some(last) = self.refilled {
+            let elapsed = now.saturating_duration_since(last).as_secs_f64();
+            self.tokens = (self.tokens + elapsed * self.rate).min(self.burst);
+        }
+        self.refilled = Some(now);
+        if self.tokens < 1.0 {
+            self.deferred += 1;
+            return false;
+        }
+        self.tokens -= 1.0;
+        match kind {
+            ProbeKind::Dns => self.dns += 1,
+            ProbeKind::Feed => self.feeds += 1,
+            ProbeKind::Rdap => self.rdap += 1,
+        }
+        true
+    }
+
+    /// Probes sent of `kind`
+    pub fn sent(&self, kind: ProbeKind) -> u64 {
+        match kind {
+            ProbeKind::Dns => self.dns,
+            ProbeKind::Feed => self.feeds,
+            ProbeKind::Rdap => self.rdap,
+        }
+    }
+
+    /// Probes held back by the rate limit
+    pub fn deferred(&self) -> u64 {
+        self.deferred
+    }
+
+    /// "5/s, burst 10"
+    pub fn limit_label(&self) -> String {
+        format!("{}/s, burst {}", self.rate, self.burst)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_bucket_refills_at_rate() {
+        let mut limiter = ProbeLimiter::from_config(&ProbesConfig {
+            rate: Some(2),
+            burst: Some(3),
+            ..Default::default()
+        });
+        let now = Instant::now();
+        let allowed = (0..5)
+            .filter(|_| limiter.allow(ProbeKind::Dns, now))
+            .count();
+        assert_eq!(allowed, 3);
+        assert_eq!(limiter.deferred(), 2);
+
+        // Half a second at 2/s is one more token
+        let later = now + Duration::from_millis(500);
+        assert!(limiter.allow(ProbeKind::Feed, later));
+        assert!(!limiter.allow(ProbeKind::Dns, later));
+        assert_eq!(limiter.sent(ProbeKind::Dns), 3);
+        assert_eq!(limiter.sent(ProbeKind::Feed), 1);
+
+        // Never more than the burst, however long the pause
+        let much_later = later + Duration::from_secs(60);
+        let allowed = (0..10)
+            .filter(|_| limiter.allow(ProbeKind::Dns, much_later))
+            .count();
+        assert_eq!(allowed, 3);
+    }
+
+    #[test]
+    fn test_passive_only_sends_nothing() {
+        let mut limiter = ProbeLimiter::from_config(&ProbesConfig {
+            passive_only: true,
+            ..Default::default()
+        });
+        assert!(!limiter.allow(ProbeKind::Dns, Instant::now()));
+        assert_eq!(limiter.sent(ProbeKind::Dns), 0);
+
+        limiter.reconfigure(ProbeLimiter::default());
+        assert!(limiter.allow(ProbeKind::Dns, Instant::now()));
+    }
+}
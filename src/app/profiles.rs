@@ -0,0 +1,175 @@
+// Profile presets
+//
+// A profile sets several display settings at once for a recurring task:
+// the filter, panels, refresh rate and look. Pick one at startup with
+// `--profile NAME` or from the `,` settings popup; anything changed
+// afterwards stays changed until another profile is picked.
+//
+//   security-audit   non-loopback connections, port heatmap, grouped by
+//                    process, Forensics tab
+//   latency          established connections, fast refresh, Sockets tab
+//   minimal          slow refresh, no animations or ambiance (low CPU)
+//   demo             lush animations in overdrive, for screenshots and talks
+
+use super::filter_expr::FilterExpr;
+use super::grouping::EndpointGrouping;
+use super::inspector::InspectorTab;
+use super::quality::AnimationQuality;
+use std::fmt;
+use std::str::FromStr;
+
+/// A bundle of display settings for one kind of task
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Profile {
+    SecurityAudit,
+    Latency,
+    Minimal,
+    Demo,
+}
+
+/// What a profile sets
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProfileSettings {
+    /// Filter expression; None clears the filter
+    pub filter: Option<&'static str>,
+    pub refresh_ms: u64,
+    pub data_multiplier: u64,
+    pub animation_quality: AnimationQuality,
+    pub animations_enabled: bool,
+    pub ambiance_enabled: bool,
+    pub overdrive_enabled: bool,
+    pub show_port_heatmap: bool,
+    pub grouping: EndpointGrouping,
+    pub inspector_tab: InspectorTab,
+}
+
+impl Profile {
+    /// Every profile, in `--profile` help and popup order
+    pub const ALL: [Profile; 4] = [
+        Profile::SecurityAudit,
+        Profile::Latency,
+        Profile::Minimal,
+        Profile::Demo,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Profile::SecurityAudit => "security-audit",
+            Profile::Latency => "latency",
+            Profile::Minimal => "minimal",
+            Profile::Demo => "demo",
+        }
+    }
+
+    /// The profile `delta` steps after this one, wrapping around
+    pub fn cycled(self, delta: isize) -> Self {
+        let len = Self::ALL.len() as isize;
+        let idx = Self::ALL.iter().position(|&p| p == self).unwrap_or(0) as isize;
+        Self::ALL[(idx + delta).rem_euclid(len) as usize]
+    }
+
+    pub fn settings(self) -> ProfileSettings {
+        match self {
+            Profile::SecurityAudit => ProfileSettings {
+                filter: Some("!remote in 127.0.0.0/8 && !remote in ::1/128"),
+                refresh_ms: 500,
+                data_multiplier: 4,
+                animation_quality: AnimationQuality::Low,
+                animations_enabled: true,
+                ambiance_enabled: false,
+                overdrive_enabled: false,
+                show_port_heatmap: true,
+                grouping: EndpointGrouping::Process,
+                inspector_tab: InspectorTab::Forensics,
+            },
+            Profile::Latency => ProfileSettings {
+                filter: Some("state == established"),
+                refresh_ms: 250,
+                data_multiplier: 2,
+                animation_quality: AnimationQuality::Low,
+                animations_enabled: true,
+                ambiance_enabled: false,
+                overdrive_enabled: false,
+                show_port_heatmap: false,
+                grouping: EndpointGrouping::Off,
+                inspector_tab: InspectorTab::Sockets,
+            },
+            Profile::Minimal => ProfileSettings {
+                filter: None,
+                refresh_ms: 2000,
+                data_multiplier: 5,
+                animation_quality: AnimationQuality::Off,
+                animations_enabled: false,
+                ambiance_enabled: false,
+                overdrive_enabled: false,
+                show_port_heatmap: false,
+                grouping: EndpointGrouping::Off,
+                inspector_tab: InspectorTab::Overview,
+            },
+            Profile::Demo => ProfileSettings {
+                filter: None,
+                refresh_ms: 100,
+                data_multiplier: 10,
+                animation_quality: AnimationQuality::Lush,
+                animations_enabled: true,
+                ambiance_enabled: true,
+                overdrive_enabled: true,
+                show_port_heatmap: false,
+                grouping: EndpointGrouping::Off,
+                inspector_tab: InspectorTab::Overview,
+            },
+        }
+    }
+
+    /// The profile's filter, parsed
+    pub fn filter(self) -> Option<FilterExpr> {
+        let source = self.settings().filter?;
+        // The sources above are constants, checked by the tests
+        FilterExpr::parse(source).ok()
+    }
+}
+
+impl fmt::Display for Profile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Profile {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|profile| profile.as_str().eq_ignore_ascii_case(s))
+            .ok_or_else(|| {
+                let names: Vec<&str> = Self::ALL.iter().map(|p| p.as_str()).collect();
+                format!(
+                    "unknown profile '{}' (expected one of: {})",
+                    s,
+                    names.join(", ")
+                )
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_profiles() {
+        assert_eq!("Latency".parse(), Ok(Profile::Latency));
+        assert_eq!("security-audit".parse(), Ok(Profile::SecurityAudit));
+        assert!("fast".parse::<Profile>().is_err());
+
+        assert_eq!(Profile::SecurityAudit.cycled(-1), Profile::Demo);
+        assert_eq!(Profile::Demo.cycled(1), Profile::SecurityAudit);
+
+        for profile in Profile::ALL {
+            if let Some(source) = profile.settings().filter {
+                assert!(FilterExpr::parse(source).is_ok(), "{}", source);
+            }
+        }
+    }
+}
@@ -0,0 +1,147 @@
+// Animation quality tiers
+//
+// How much the graveyard animates, from a static map (Off) to dense
+// particle streams (Lush). The tier sets particles per edge, how densely
+// the latency rings are dotted and how often the pulse advances. It
+// starts from `[animation] quality` in config.toml, `*` cycles it, and
+// sustained slow frames step it down one tier at a time.
+
+use serde::Deserialize;
+
+/// Animation detail, least expensive first
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum AnimationQuality {
+    Off,
+    Low,
+    #[default]
+    Normal,
+    Lush,
+}
+
+/// `[animation]` section of config.toml
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct AnimationConfig {
+    /// "off", "low", "normal" or "lush"
+    pub quality: AnimationQuality,
+    /// Fog, bats and lightning in overdrive mode
+    pub ambiance: bool,
+}
+
+impl Default for AnimationConfig {
+    fn default() -> Self {
+        Self {
+            quality: AnimationQuality::default(),
+            ambiance: true,
+        }
+    }
+}
+
+impl AnimationQuality {
+    /// The next tier for the cycle key, wrapping from Lush to Off
+    pub fn next(self) -> Self {
+        match self {
+            AnimationQuality::Off => AnimationQuality::Low,
+            AnimationQuality::Low => AnimationQuality::Normal,
+            AnimationQuality::Normal => AnimationQuality::Lush,
+            AnimationQuality::Lush => AnimationQuality::Off,
+        }
+    }
+
+    /// One tier cheaper for auto-reduction; never below Low, so slow
+    /// frames thin the animation out without stopping it
+    pub fn reduced(self) -> Option<Self> {
+        match self {
+            AnimationQuality::Off | AnimationQuality::Low => None,
+            AnimationQuality::Normal => Some(AnimationQuality::Low),
+            AnimationQuality::Lush => Some(AnimationQuality::Normal),
+        }
+    }
+
+    /// Particle positions along each edge (fractions of its length)
+    pub fn particle_offsets(self) -> &'static [f32] {
+        match self {
+            AnimationQuality::Off => &[],
+            AnimationQuality::Low => &[0.33],
+            AnimationQuality::Normal => &[0.0, 0.33, 0.66],
+            AnimationQuality::Lush => &[0.0, 0.2, 0.4, 0.6, 0.8],
+        }
+    }
+
+    /// Degrees between the dots of a latency ring
+    pub fn ring_step_degrees(self) -> usize {
+        match self {
+            AnimationQuality::Off => 30,
+            AnimationQuality::Low => 20,
+            AnimationQuality::Normal => 10,
+            AnimationQuality::Lush => 5,
+        }
+    }
+
+    /// Ticks between pulse updates; None keeps the pulse still
+    pub fn pulse_every(self) -> Option<u64> {
+        match self {
+            AnimationQuality::Off => None,
+            AnimationQuality::Low => Some(3),
+            AnimationQuality::Normal | AnimationQuality::Lush => Some(1),
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            AnimationQuality::Off => "Off",
+            AnimationQuality::Low => "Low",
+            AnimationQuality::Normal => "Normal",
+            AnimationQuality::Lush => "Lush",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quality_tiers() {
+        let mut quality = AnimationQuality::Off;
+        let mut seen = Vec::new();
+        for _ in 0..4 {
+            quality = quality.next();
+            seen.push(quality);
+        }
+        assert_eq!(
+            seen,
+            [
+                AnimationQuality::Low,
+                AnimationQuality::Normal,
+                AnimationQuality::Lush,
+                AnimationQuality::Off
+            ]
+        );
+
+        // Auto-reduction walks down one tier at a time and stops at Low
+        assert_eq!(
+            AnimationQuality::Lush.reduced(),
+            Some(AnimationQuality::Normal)
+        );
+        assert_eq!(
+            AnimationQuality::Normal.reduced(),
+            Some(AnimationQuality::Low)
+        );
+        assert_eq!(AnimationQuality::Low.reduced(), None);
+
+        // Each tier up draws at least as much
+        let tiers = [
+            AnimationQuality::Off,
+            AnimationQuality::Low,
+            AnimationQuality::Normal,
+            AnimationQuality::Lush,
+        ];
+        for pair in tiers.windows(2) {
+            assert!(pair[0].particle_offsets().len() < pair[1].particle_offsets().len());
+            assert!(pair[0].ring_step_degrees() > pair[1].ring_step_degrees());
+        }
+        assert_eq!(AnimationQuality::Off.pulse_every(), None);
+    }
+}
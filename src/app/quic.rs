@@ -0,0 +1,96 @@
+// QUIC (HTTP/3) heuristic
+//
+// A UDP flow on port 443 that stays connected is almost always QUIC: a
+// connected socket carries both directions of one peer's traffic, and
+// keeping it across several refreshes rules out one-off probes. Such flows
+// are labeled "QUIC (likely)"; the service enricher already names port 443
+// "https", so the endpoint is classified like any TLS peer. Without packet
+// capture there is no SNI to tell which site it is.
+
+use super::snapshot::{connection_key, ConnectionKey};
+use crate::net::{Connection, ConnectionDirection, Protocol, QUIC_PORT};
+use std::collections::HashMap;
+
+/// Consecutive refreshes a UDP flow must be seen in to count as QUIC
+const MIN_REFRESHES: u32 = 3;
+
+/// Label value of likely QUIC flows (under the "protocol" key)
+pub const QUIC_LABEL: &str = "QUIC (likely)";
+
+/// How long each UDP flow has been seen
+#[derive(Debug, Clone, Default)]
+pub struct QuicTracker {
+    streaks: HashMap<ConnectionKey, u32>,
+}
+
+impl QuicTracker {
+    /// Label the sustained UDP flows of a fresh snapshot and set their
+    /// direction (inbound when our end is the 443 side); flows that are
+    /// gone are forgotten
+    pub fn classify(&mut self, conns: &mut [Connection]) {
+        let mut streaks = HashMap::new();
+        for conn in conns.iter_mut() {
+            if conn.protocol != Protocol::Udp {
+                continue;
+            }
+            let key = connection_key(conn);
+            let streak = self.streaks.get(&key).copied().unwrap_or(0) + 1;
+            conn.direction = Some(
+                if conn.local_port == QUIC_PORT && conn.remote_port != QUIC_PORT {
+                    ConnectionDirection::Inbound
+                } else {
+                    ConnectionDirection::Outbound
+                },
+            );
+            if streak >= MIN_REFRESHES {
+                conn.labels.insert("protocol", QUIC_LABEL.into());
+            }
+            streaks.insert(key, streak);
+        }
+        self.streaks = streaks;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn flow(local_port: u16, remote_port: u16, protocol: Protocol) -> Connection {
+        Connection {
+            local_addr: "10.0.0.5".into(),
+            local_port,
+            remote_addr: "93.184.216.34".into(),
+            remote_port,
+            protocol,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_sustained_udp_flows_are_labeled() {
+        let mut tracker = QuicTracker::default();
+        let label = |conn: &Connection| conn.labels.get("protocol").map(|l| l.to_string());
+        for refresh in 1..=MIN_REFRESHES {
+            let mut conns = vec![
+                flow(51000, 443, Protocol::Udp),
+                flow(51001, 443, Protocol::Tcp),
+            ];
+            tracker.classify(&mut conns);
+            let expected = (refresh >= MIN_REFRESHES).then(|| QUIC_LABEL.to_string());
+            assert_eq!(label(&conns[0]), expected);
+            assert_eq!(conns[0].direction, Some(ConnectionDirection::Outbound));
+            assert_eq!(label(&conns[1]), None);
+        }
+
+        // A flow that went away starts over
+        tracker.classify(&mut []);
+        let mut conns = vec![flow(51000, 443, Protocol::Udp)];
+        tracker.classify(&mut conns);
+        assert_eq!(label(&conns[0]), None);
+
+        // Our end on 443: a client reached our QUIC server
+        let mut conns = vec![flow(443, 62000, Protocol::Udp)];
+        tracker.classify(&mut conns);
+        assert_eq!(conns[0].direction, Some(ConnectionDirection::Inbound));
+    }
+}
@@ -0,0 +1,315 @@
+// RDAP ("whois") lookups for remote endpoints
+//
+// `K` asks who owns the selected connection's public address: the network
+// name and range, the owning organization, its country and the abuse
+// contact, from the registry's RDAP service (rdap.org redirects to the
+// right RIR). Lookups only happen on that keypress, count against the
+// `[probes]` budget (see probes.rs), run through curl(1) on a background
+// thread and are cached for the rest of the session (failed ones are
+// retried on the next keypress) and across sessions by the `[cache]` (see
+// enrich_cache.rs).
+
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::process::{Command, Stdio};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::Arc;
+use std::thread;
+
+/// RDAP bootstrap service that redirects to the registry of an address
+const RDAP_URL: &str = "https://rdap.org/ip/";
+
+/// What the registry says about an address
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RdapRecord {
+    /// Network name ("EDGECAST-NETBLK-03")
+    pub network: Option<String>,
+    /// Netblock, as CIDRs ("93.184.216.0/24") or "first - last"
+    pub range: Option<String>,
+    /// Registrant organization
+    pub org: Option<String>,
+    pub country: Option<String>,
+    /// Abuse contact e-mail (or name when there is no e-mail)
+    pub abuse: Option<String>,
+}
+
+impl RdapRecord {
+    /// The record as `[cache]` fields, missing ones empty
+    pub fn cache_fields(&self) -> Vec<String> {
+        [
+            &self.network,
+            &self.range,
+            &self.org,
+            &self.country,
+            &self.abuse,
+        ]
+        .into_iter()
+        .map(|field| field.clone().unwrap_or_default())
+        .collect()
+    }
+
+    /// The record stored as `fields` by `cache_fields`
+    pub fn from_cache_fields(fields: &[String]) -> Self {
+        let field = |idx: usize| fields.get(idx).filter(|f| !f.is_empty()).cloned();
+        Self {
+            network: field(0),
+            range: field(1),
+            org: field(2),
+            country: field(3),
+            abuse: field(4),
+        }
+    }
+}
+
+/// State of one address's lookup
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RdapLookup {
+    Pending,
+    Done(RdapRecord),
+    Failed(String),
+}
+
+/// Background RDAP client with a session cache
+#[derive(Debug)]
+pub struct Rdap {
+    requests: Sender<Arc<str>>,
+    results: Receiver<(Arc<str>, Result<RdapRecord, String>)>,
+    lookups: HashMap<Arc<str>, RdapLookup>,
+}
+
+impl Rdap {
+    /// Start the lookup thread; it exits when the client is dropped
+    pub fn spawn() -> Self {
+        let (requests, queue) = mpsc::channel::<Arc<str>>();
+        let (answers, results) = mpsc::channel();
+        thread::spawn(move || {
+            for addr in queue {
+                let record = fetch(&addr);
+                if answers.send((addr, record)).is_err() {
+                    break;
+                }
+            }
+        });
+        Self {
+            requests,
+            results,
+            lookups: HashMap::new(),
+        }
+    }
+
+    /// Take the record of `addr` from the on-disk cache
+    pub fn seed(&mut self, addr: Arc<str>, record: RdapRecord) {
+        self.lookups.insert(addr, RdapLookup::Done(record));
+    }
+
+    /// Queue a lookup of `addr`; false when the thread is gone. A record
+    /// already known stays on show until the new answer arrives
+    pub fn request(&mut self, addr: &Arc<str>) -> bool {
+        if self.requests.send(addr.clone()).is_err() {
+            return false;
+        }
+        if !matches!(self.lookups.get(addr), Some(RdapLookup::Done(_))) {
+            self.lookups.insert(addr.clone(), RdapLookup::Pending);
+        }
+        true
+    }
+
+    /// Collect finished lookups; returns the addresses whose lookup
+    /// changed. A failed lookup keeps the record already known, if any
+    pub fn poll(&mut self) -> Vec<Arc<str>> {
+        let mut answered = Vec::new();
+        while let Ok((addr, result)) = self.results.try_recv() {
+            match result {
+                Ok(record) => {
+                    self.lookups.insert(addr.clone(), RdapLookup::Done(record));
+                    answered.push(addr);
+                }
+                Err(e) => {
+                    if !matches!(self.lookups.get(&addr), Some(RdapLookup::Done(_))) {
+                        self.lookups.insert(addr.clone(), RdapLookup::Failed(e));
+                        answered.push(addr);
+                    }
+                }
+            }
+        }
+        answered
+    }
+
+    /// Lookup of `addr`, if one was requested
+    pub fn lookup(&self, addr: &str) -> Option<&RdapLookup> {
+        self.lookups.get(addr)
+    }
+}
+
+/// Query the registry for `addr` with curl
+fn fetch(addr: &str) -> Result<RdapRecord, String> {
+    let output = Command::new("curl")
+        .args(["-fsSL", "--max-time", "15", "-H"])
+        .arg("Accept: application/rdap+json")
+        .arg(format!("{}{}", RDAP_URL, addr))
+        .stdin(Stdio::null())
+        .output()
+        .map_err(|e| format!("cannot run curl: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "lookup failed ({}): {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    parse_rdap(&String::from_utf8_lossy(&output.stdout))
+        .ok_or_else(|| "unreadable RDAP answer".to_string())
+}
+
+/// The parts of an RDAP IP network answer shown in the popup
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct IpNetwork {
+    name: Option<String>,
+    country: Option<String>,
+    start_address: Option<String>,
+    end_address: Option<String>,
+    #[serde(rename = "cidr0_cidrs")]
+    cidrs: Vec<Cidr>,
+    entities: Vec<Entity>,
+}
+
+/// A netblock of the cidr0 extension
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct Cidr {
+    v4prefix: Option<String>,
+    v6prefix: Option<String>,
+    length: Option<u8>,
+}
+
+/// A contact of the network, with the contacts nested under it
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct Entity {
+    roles: Vec<String>,
+    /// jCard: `["vcard", [["fn", {}, "text", "Example Inc."], ...]]`
+    vcard_array: Option<(String, Vec<Vec<serde_json::Value>>)>,
+    entities: Vec<Entity>,
+}
+
+impl Entity {
+    /// Value of the `field` property of the jCard
+    fn vcard(&self, field: &str) -> Option<&str> {
+        let (_, properties) = self.vcard_array.as_ref()?;
+        properties
+            .iter()
+            .find_map(|property| match property.as_slice() {
+                [name, _, _, value, ..] if name.as_str() == Some(field) => value.as_str(),
+                _ => None,
+            })
+    }
+}
+
+/// The fields of an RDAP IP network answer shown in the popup
+fn parse_rdap(text: &str) -> Option<RdapRecord> {
+    let network: IpNetwork = serde_json::from_str(text).ok()?;
+
+    let cidrs: Vec<String> = network
+        .cidrs
+        .iter()
+        .filter_map(|cidr| {
+            let prefix = cidr.v4prefix.as_ref().or(cidr.v6prefix.as_ref())?;
+            Some(format!("{}/{}", prefix, cidr.length?))
+        })
+        .collect();
+    let range = if cidrs.is_empty() {
+        match (&network.start_address, &network.end_address) {
+            (Some(start), Some(end)) => Some(format!("{} - {}", start, end)),
+            _ => None,
+        }
+    } else {
+        Some(cidrs.join(", "))
+    };
+
+    let mut entities = Vec::new();
+    collect_entities(&network.entities, &mut entities);
+    let with_role = |role: &str| {
+        entities
+            .iter()
+            .copied()
+            .find(|entity| entity.roles.iter().any(|r| r == role))
+    };
+    let org = with_role("registrant").and_then(|entity| entity.vcard("fn"));
+    let abuse =
+        with_role("abuse").and_then(|entity| entity.vcard("email").or_else(|| entity.vcard("fn")));
+
+    Some(RdapRecord {
+        network: network.name.clone(),
+        range,
+        org: org.map(str::to_string),
+        country: network.country.clone(),
+        abuse: abuse.map(str::to_string),
+    })
+}
+
+/// Every entity of `entities` and below, depth first (abuse contacts are
+/// often nested in the registrant)
+fn collect_entities<'a>(entities: &'a [Entity], out: &mut Vec<&'a Entity>) {
+    for entity in entities {
+        out.push(entity);
+        collect_entities(&entity.entities, out);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ARIN: &str = r#"{
+  "objectClassName": "ip network",
+  "handle": "NET-93-184-216-0-1",
+  "startAddress": "93.184.216.0",
+  "endAddress": "93.184.216.255",
+  "name": "EDGECAST-NETBLK-03",
+  "country": "US",
+  "cidr0_cidrs": [{ "v4prefix": "93.184.216.0", "length": 24 }],
+  "entities": [{
+    "handle": "EDGEC-25",
+    "roles": ["registrant"],
+    "vcardArray": ["vcard", [
+      ["version", {}, "text", "4.0"],
+      ["fn", {}, "text", "Edgecast Inc."],
+      ["kind", {}, "text", "org"]
+    ]],
+    "entities": [{
+      "roles": ["abuse"],
+      "vcardArray": ["vcard", [
+        ["fn", {}, "text", "Abuse Desk"],
+        ["email", {}, "text", "abuse@edgecast.example"]
+      ]]
+    }]
+  }],
+  "remarks": [{ "description": ["Escaped \"quotes\" and é"] }],
+  "port43": null,
+  "secure": false
+}"#;
+
+    #[test]
+    fn test_parse_rdap() {
+        assert_eq!(
+            parse_rdap(ARIN),
+            Some(RdapRecord {
+                network: Some("EDGECAST-NETBLK-03".to_string()),
+                range: Some("93.184.216.0/24".to_string()),
+                org: Some("Edgecast Inc.".to_string()),
+                country: Some("US".to_string()),
+                abuse: Some("abuse@edgecast.example".to_string()),
+            })
+        );
+
+        // Without cidr0 the range comes from the first and last address
+        let plain = r#"{"startAddress": "10.0.0.0", "endAddress": "10.0.0.255"}"#;
+        assert_eq!(
+            parse_rdap(plain).and_then(|r| r.range).as_deref(),
+            Some("10.0.0.0 - 10.0.0.255")
+        );
+        assert_eq!(parse_rdap("<html>rate limited</html>"), None);
+        assert_eq!(parse_rdap(&"[".repeat(1000)), None);
+    }
+}
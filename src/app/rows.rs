@@ -0,0 +1,213 @@
+// Connection list rows
+//
+// Clients with connection pools open dozens of sockets that differ only in
+// their local ephemeral port. Those are shown as one pooled row with a
+// count ("10.0.0.5:* → 10.1.0.1:5432 ×12"), which `e` expands into the
+// individual sockets and collapses again.
+//
+// The subflows of one Multipath TCP connection are pooled the same way,
+// by their shared MPTCP token, since they are one logical connection
+// whatever their addresses.
+
+use crate::net::{Connection, ConnectionState, Protocol};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+/// Identity shared by the sockets of one pool
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum PoolKey {
+    /// Sockets differing only in local port:
+    /// (protocol, pid, process, local addr, remote addr, remote port, state)
+    Ephemeral(
+        Protocol,
+        Option<i32>,
+        Option<Arc<str>>,
+        Arc<str>,
+        Arc<str>,
+        u16,
+        ConnectionState,
+    ),
+    /// Subflows of one MPTCP connection, by token
+    Mptcp(Arc<str>),
+}
+
+/// Pool identity of `conn`; listeners are never pooled
+pub fn pool_key(conn: &Connection) -> Option<PoolKey> {
+    if conn.state == ConnectionState::Listen || conn.remote_port == 0 {
+        return None;
+    }
+    if let Some(ref token) = conn.mptcp {
+        return Some(PoolKey::Mptcp(token.clone()));
+    }
+    Some(PoolKey::Ephemeral(
+        conn.protocol,
+        conn.pid,
+        conn.process_name.clone(),
+        conn.local_addr.clone(),
+        conn.remote_addr.clone(),
+        conn.remote_port,
+        conn.state,
+    ))
+}
+
+/// What a list row shows
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RowKind {
+    /// A connection with no siblings
+    Single,
+    /// Several sockets folded together; `members` are connection indices
+    Pool { members: Vec<usize>, expanded: bool },
+    /// One socket listed under its expanded pool
+    Member,
+}
+
+/// One line of the connection list
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConnectionRow {
+    /// Connection shown (the first member for pools)
+    pub index: usize,
+    pub kind: RowKind,
+}
+
+impl ConnectionRow {
+    /// Whether the row stands for connection `index`
+    pub fn covers(&self, index: usize) -> bool {
+        match self.kind {
+            RowKind::Pool { ref members, .. } => members.contains(&index),
+            _ => self.index == index,
+        }
+    }
+}
+
+/// Rows for `conns` in list order, pooling sockets that share a key;
+/// pools in `expanded` are followed by one row per member
+pub fn build_rows(conns: &[Connection], expanded: &HashSet<PoolKey>) -> Vec<ConnectionRow> {
+    let mut pools: HashMap<PoolKey, Vec<usize>> = HashMap::new();
+    for (idx, conn) in conns.iter().enumerate() {
+        if let Some(key) = pool_key(conn) {
+            pools.entry(key).or_default().push(idx);
+        }
+    }
+
+    let mut rows = Vec::with_capacity(conns.len());
+    for (idx, conn) in conns.iter().enumerate() {
+        let key = pool_key(conn);
+        let members = match key.as_ref().and_then(|k| pools.get(k)) {
+            Some(members) if members.len() > 1 => members,
+            _ => {
+                rows.push(ConnectionRow {
+                    index: idx,
+                    kind: RowKind::Single,
+                });
+                continue;
+            }
+        };
+        // The pool is listed where its first socket appears
+        if members[0] != idx {
+            continue;
+        }
+        let expanded = key.is_some_and(|k| expanded.contains(&k));
+        rows.push(ConnectionRow {
+            index: idx,
+            kind: RowKind::Pool {
+                members: members.clone(),
+                expanded,
+            },
+        });
+        if expanded {
+            rows.extend(members.iter().map(|&index| ConnectionRow {
+                index,
+                kind: RowKind::Member,
+            }));
+        }
+    }
+    rows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn conn(local_port: u16, remote: &str, remote_port: u16) -> Connection {
+        Connection {
+            local_addr: "10.0.0.5".into(),
+            local_port,
+            remote_addr: remote.into(),
+            remote_port,
+            state: ConnectionState::Established,
+            pid: Some(42),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_build_rows_pools_ephemeral_ports() {
+        let conns = vec![
+            conn(51000, "10.1.0.1", 5432),
+            conn(443, "203.0.113.9", 51234),
+            conn(51001, "10.1.0.1", 5432),
+            conn(51002, "10.1.0.1", 5432),
+        ];
+        let rows = build_rows(&conns, &HashSet::new());
+        assert_eq!(rows.len(), 2);
+        assert_eq!(
+            rows[0].kind,
+            RowKind::Pool {
+                members: vec![0, 2, 3],
+                expanded: false
+            }
+        );
+        assert!(rows[0].covers(3));
+        assert_eq!(rows[1].kind, RowKind::Single);
+
+        let expanded = HashSet::from([pool_key(&conns[0]).unwrap()]);
+        let rows = build_rows(&conns, &expanded);
+        let indices: Vec<_> = rows.iter().map(|r| r.index).collect();
+        assert_eq!(indices, vec![0, 0, 2, 3, 1]);
+        assert_eq!(rows[2].kind, RowKind::Member);
+    }
+
+    #[test]
+    fn test_mptcp_subflows_are_pooled_by_token() {
+        let subflow = |local: &str, remote: &str| Connection {
+            local_addr: local.into(),
+            remote_addr: remote.into(),
+            mptcp: Some("0000/5f2a1c3b".into()),
+            ..conn(40000, "", 443)
+        };
+        let conns = vec![
+            subflow("10.0.0.5", "93.184.216.34"),
+            conn(40002, "93.184.216.34", 443),
+            subflow("192.168.1.5", "93.184.216.35"),
+        ];
+        let rows = build_rows(&conns, &HashSet::new());
+        assert_eq!(
+            rows[0].kind,
+            RowKind::Pool {
+                members: vec![0, 2],
+                expanded: false
+            }
+        );
+        // A plain TCP socket to the same peer is not a subflow
+        assert_eq!(rows[1].kind, RowKind::Single);
+    }
+
+    #[test]
+    fn test_listeners_and_other_owners_are_not_pooled() {
+        let mut other = conn(51001, "10.1.0.1", 5432);
+        other.pid = Some(43);
+        let listener = Connection {
+            local_port: 22,
+            state: ConnectionState::Listen,
+            ..Default::default()
+        };
+        let conns = vec![
+            conn(51000, "10.1.0.1", 5432),
+            other,
+            listener.clone(),
+            listener,
+        ];
+        let rows = build_rows(&conns, &HashSet::new());
+        assert!(rows.iter().all(|r| r.kind == RowKind::Single));
+    }
+}
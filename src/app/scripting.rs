@@ -0,0 +1,384 @@
+// Event scripting hooks (`--features scripting`)
+//
+// A user Rhai script sees every connection open/close/state change and
+// every Grimoire alert, and can annotate it, change its severity or
+// suppress it, without recompiling ntomb:
+//
+// ```toml
+// [scripting]
+// script = "/etc/ntomb/hooks.rhai"
+// ```
+//
+// ```rhai
+// fn on_event(ev) {
+//     if ev.kind == "open" && ev.remote_port == 6667 {
+//         return #{ severity: "critical", annotation: "IRC?" };
+//     }
+//     if ev.kind == "alert" && ev.message.contains("ci-runner") {
+//         return #{ suppress: true };
+//     }
+// }
+// ```
+//
+// `ev.kind` is "open", "close", "state_change" or "alert". Connection
+// events carry the connection's fields (local_addr, local_port,
+// remote_addr, remote_port, state, prev_state, direction, pid, process,
+// user, container, interface, labels); alerts carry severity, message, pid and
+// remote_addr. Missing values are `()`.
+//
+// `on_event` returns `()` for no opinion, a string to annotate the event,
+// or a map with any of `annotation`, `severity` (info, warning, critical)
+// and `suppress`. Connection events only reach the Grimoire when the
+// script annotates or rates them; alerts are logged unless suppressed.
+
+use serde::Deserialize;
+use std::path::PathBuf;
+
+#[cfg(feature = "scripting")]
+pub use engine::ScriptHooks;
+
+/// `[scripting]` section of config.toml
+#[derive(Debug, Clone, Default, Deserialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct ScriptingConfig {
+    /// Rhai script defining `on_event(ev)`; scripting is off when unset
+    pub script: Option<PathBuf>,
+}
+
+#[cfg(feature = "scripting")]
+mod engine {
+    use super::ScriptingConfig;
+    use crate::app::events::{EventSeverity, GrimoireEvent};
+    use crate::app::stream::StreamEvent;
+    use crate::net::{Connection, ConnectionDirection};
+    use rhai::{Dynamic, Engine, Map, Scope, AST};
+    use std::fmt;
+    use std::fs;
+
+    /// Operations one `on_event` call may run before it is aborted, so a
+    /// runaway loop can't freeze the UI
+    const MAX_OPERATIONS: u64 = 100_000;
+
+    /// Name of the hook function scripts must define
+    const HOOK: &str = "on_event";
+
+    /// A compiled user script
+    pub struct ScriptHooks {
+        engine: Engine,
+        ast: AST,
+    }
+
+    impl fmt::Debug for ScriptHooks {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.debug_struct("ScriptHooks").finish_non_exhaustive()
+        }
+    }
+
+    /// What the script decided about one event
+    #[derive(Debug, Clone, Default, PartialEq, Eq)]
+    pub struct ScriptVerdict {
+        pub suppress: bool,
+        pub severity: Option<EventSeverity>,
+        pub annotation: Option<String>,
+    }
+
+    impl ScriptVerdict {
+        /// Apply the verdict to an alert; None when it is suppressed
+        pub fn apply(self, mut event: GrimoireEvent) -> Option<GrimoireEvent> {
+            if self.suppress {
+                return None;
+            }
+            if let Some(severity) = self.severity {
+                event.severity = severity;
+            }
+            if let Some(annotation) = self.annotation {
+                event.message = format!("{} [{}]", event.message, annotation);
+            }
+            Some(event)
+        }
+
+        /// The Grimoire event for a connection event the script annotated
+        /// or rated; None when it had no opinion or suppressed it
+        pub fn connection_event(
+            self,
+            event: StreamEvent,
+            conn: &Connection,
+        ) -> Option<GrimoireEvent> {
+            if self.annotation.is_none() && self.severity.is_none() {
+                return None;
+            }
+            let mut message = format!(
+                "Connection {} {}:{} → {}:{}",
+                event.name(),
+                conn.local_addr,
+                conn.local_port,
+                conn.remote_addr,
+                conn.remote_port
+            );
+            if let Some(ref name) = conn.process_name {
+                message.push_str(&format!(" by {}({})", name, conn.pid.unwrap_or(0)));
+            }
+            let event = GrimoireEvent::new(EventSeverity::Info, message)
+                .with_pid(conn.pid)
+                .with_remote(conn.remote_addr.to_string());
+            self.apply(event)
+        }
+    }
+
+    impl ScriptHooks {
+        /// Compile the configured script; Ok(None) when scripting is off
+        pub fn load(config: &ScriptingConfig) -> Result<Option<Self>, String> {
+            let Some(ref path) = config.script else {
+                return Ok(None);
+            };
+            let source = fs::read_to_string(path)
+                .map_err(|e| format!("cannot read {}: {}", path.display(), e))?;
+            Self::compile(&source)
+                .map(Some)
+                .map_err(|e| format!("{}: {}", path.display(), e))
+        }
+
+        /// Compile script source that defines `on_event(ev)`
+        pub fn compile(source: &str) -> Result<Self, String> {
+            let mut engine = Engine::new();
+            engine.set_max_operations(MAX_OPERATIONS);
+            let ast = engine.compile(source).map_err(|e| e.to_string())?;
+            if !ast
+                .iter_functions()
+                .any(|f| f.name == HOOK && f.params.len() == 1)
+            {
+                return Err(format!("script defines no {}(ev) function", HOOK));
+            }
+            Ok(Self { engine, ast })
+        }
+
+        /// Run the hook for a connection opening, closing or changing state
+        pub fn on_connection(
+            &self,
+            event: StreamEvent,
+            conn: &Connection,
+        ) -> Result<ScriptVerdict, String> {
+            let mut ev = connection_map(conn);
+            ev.insert("kind".into(), event.name().into());
+            ev.insert(
+                "prev_state".into(),
+                match event {
+                    StreamEvent::StateChange(previous) => previous.name().into(),
+                    _ => Dynamic::UNIT,
+                },
+            );
+            self.call(ev)
+        }
+
+        /// Run the hook for a Grimoire alert
+        pub fn on_alert(&self, event: &GrimoireEvent) -> Result<ScriptVerdict, String> {
+            let mut ev = Map::new();
+            ev.insert("kind".into(), "alert".into());
+            let severity = match event.severity {
+                EventSeverity::Info => "info",
+                EventSeverity::Warning => "warning",
+                EventSeverity::Critical => "critical",
+            };
+            ev.insert("severity".into(), severity.into());
+            ev.insert("message".into(), event.message.clone().into());
+            ev.insert("pid".into(), optional(event.pid.map(i64::from)));
+            ev.insert("remote_addr".into(), optional(event.remote_addr.clone()));
+            self.call(ev)
+        }
+
+        fn call(&self, ev: Map) -> Result<ScriptVerdict, String> {
+            let result: Dynamic = self
+                .engine
+                .call_fn(&mut Scope::new(), &self.ast, HOOK, (ev,))
+                .map_err(|e| format!("{}: {}", HOOK, e))?;
+            verdict(result)
+        }
+    }
+
+    /// The fields of `conn` as a Rhai object map
+    fn connection_map(conn: &Connection) -> Map {
+        let direction = conn.direction.map(|d| match d {
+            ConnectionDirection::Inbound => "inbound",
+            ConnectionDirection::Outbound => "outbound",
+        });
+        let mut map = Map::new();
+        map.insert("local_addr".into(), conn.local_addr.to_string().into());
+        map.insert("local_port".into(), i64::from(conn.local_port).into());
+        map.insert("remote_addr".into(), conn.remote_addr.to_string().into());
+        map.insert("remote_port".into(), i64::from(conn.remote_port).into());
+        map.insert("state".into(), conn.state.name().into());
+        map.insert("protocol".into(), conn.protocol.name().into());
+        map.insert("direction".into(), optional(direction));
+        map.insert("pid".into(), optional(conn.pid.map(i64::from)));
+        map.insert(
+            "process".into(),
+            optional(conn.process_name.as_deref().map(str::to_string)),
+        );
+        map.insert("user".into(), optional(conn.user.clone()));
+        map.insert("container".into(), optional(conn.container.clone()));
+        map.insert(
+            "interface".into(),
+            optional(conn.interface.as_deref().map(str::to_string)),
+        );
+        let labels: Map = conn
+            .labels
+            .iter()
+            .map(|(key, value)| ((*key).into(), value.to_string().into()))
+            .collect();
+        map.insert("labels".into(), labels.into());
+        map
+    }
+
+    fn optional<T: Into<Dynamic>>(value: Option<T>) -> Dynamic {
+        value.map_or(Dynamic::UNIT, Into::into)
+    }
+
+    /// Interpret what `on_event` returned
+    fn verdict(result: Dynamic) -> Result<ScriptVerdict, String> {
+        if result.is_unit() {
+            return Ok(ScriptVerdict::default());
+        }
+        if result.is_string() {
+            return Ok(ScriptVerdict {
+                annotation: result.into_string().ok(),
+                ..Default::default()
+            });
+        }
+        let type_name = result.type_name();
+        let Some(map) = result.try_cast::<Map>() else {
+            return Err(format!(
+                "{} must return (), a string or a map, not {}",
+                HOOK, type_name
+            ));
+        };
+        let mut verdict = ScriptVerdict::default();
+        for (key, value) in map {
+            match key.as_str() {
+                "suppress" => {
+                    verdict.suppress = value
+                        .as_bool()
+                        .map_err(|_| "suppress must be true or false".to_string())?;
+                }
+                "severity" => {
+                    let name = value
+                        .into_string()
+                        .map_err(|_| "severity must be a string".to_string())?;
+                    verdict.severity = Some(
+                        EventSeverity::from_name(&name)
+                            .ok_or_else(|| format!("unknown severity '{}'", name))?,
+                    );
+                }
+                "annotation" => verdict.annotation = Some(value.to_string()),
+                other => {
+                    return Err(format!(
+                        "unknown key '{}' (annotation, severity, suppress)",
+                        other
+                    ))
+                }
+            }
+        }
+        Ok(verdict)
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use crate::net::ConnectionState;
+
+        const SCRIPT: &str = r#"
+            fn on_event(ev) {
+                if ev.kind == "open" && ev.remote_port == 6667 {
+                    return #{ severity: "critical", annotation: "IRC?" };
+                }
+                if ev.kind == "open" && ev.labels.service == "https" {
+                    return "tls";
+                }
+                if ev.kind == "close" {
+                    return "closed by " + ev.process;
+                }
+                if ev.kind == "alert" && ev.message.contains("noisy") {
+                    return #{ suppress: true };
+                }
+                if ev.kind == "alert" && ev.severity == "info" {
+                    return #{ severity: "warning" };
+                }
+            }
+        "#;
+
+        fn irc() -> Connection {
+            Connection {
+                local_addr: "10.0.0.5".into(),
+                local_port: 50000,
+                remote_addr: "198.51.100.7".into(),
+                remote_port: 6667,
+                state: ConnectionState::Established,
+                pid: Some(42),
+                process_name: Some("bot".into()),
+                ..Default::default()
+            }
+        }
+
+        #[test]
+        fn test_connection_hooks() {
+            let hooks = ScriptHooks::compile(SCRIPT).unwrap();
+            let verdict = hooks.on_connection(StreamEvent::Open, &irc()).unwrap();
+            assert_eq!(verdict.severity, Some(EventSeverity::Critical));
+            let event = verdict.connection_event(StreamEvent::Open, &irc()).unwrap();
+            assert_eq!(event.severity, EventSeverity::Critical);
+            assert_eq!(
+                event.message,
+                "Connection open 10.0.0.5:50000 → 198.51.100.7:6667 by bot(42) [IRC?]"
+            );
+
+            let mut tls = Connection {
+                remote_port: 443,
+                ..irc()
+            };
+            tls.labels.insert("service", "https".into());
+            let verdict = hooks.on_connection(StreamEvent::Open, &tls).unwrap();
+            assert_eq!(verdict.annotation.as_deref(), Some("tls"));
+
+            let verdict = hooks.on_connection(StreamEvent::Close, &irc()).unwrap();
+            assert_eq!(verdict.annotation.as_deref(), Some("closed by bot"));
+
+            // No opinion: nothing is logged
+            let quiet = Connection {
+                remote_port: 443,
+                ..irc()
+            };
+            let verdict = hooks.on_connection(StreamEvent::Open, &quiet).unwrap();
+            assert_eq!(verdict, ScriptVerdict::default());
+            assert!(verdict
+                .connection_event(StreamEvent::Open, &quiet)
+                .is_none());
+        }
+
+        #[test]
+        fn test_alert_hooks() {
+            let hooks = ScriptHooks::compile(SCRIPT).unwrap();
+            let noisy = GrimoireEvent::new(EventSeverity::Warning, "noisy watch fired");
+            assert!(hooks.on_alert(&noisy).unwrap().apply(noisy).is_none());
+
+            let info = GrimoireEvent::new(EventSeverity::Info, "Graveyard opened");
+            let event = hooks.on_alert(&info).unwrap().apply(info).unwrap();
+            assert_eq!(event.severity, EventSeverity::Warning);
+            assert_eq!(event.message, "Graveyard opened");
+        }
+
+        #[test]
+        fn test_script_errors() {
+            assert!(ScriptHooks::compile("fn other(x) {}").is_err());
+            assert!(ScriptHooks::compile("fn on_event(ev) {").is_err());
+
+            let bad = ScriptHooks::compile("fn on_event(ev) { #{ severity: \"loud\" } }").unwrap();
+            let event = GrimoireEvent::new(EventSeverity::Info, "x");
+            assert!(bad
+                .on_alert(&event)
+                .unwrap_err()
+                .contains("unknown severity"));
+
+            let endless = ScriptHooks::compile("fn on_event(ev) { loop {} }").unwrap();
+            assert!(endless.on_alert(&event).is_err());
+        }
+    }
+}
@@ -0,0 +1,166 @@
+// Séance view
+//
+// `O` replaces the Graveyard with a grid of mini-graveyards, one per
+// process among the SEANCE_PROCESSES busiest (by connections to remote
+// endpoints), each with the process in its own coffin and its busiest
+// endpoints around it. It answers "who talks to what" at a glance instead
+// of entering Process mode for one process after another.
+
+use super::config::SEANCE_ENDPOINTS;
+use crate::net::{Connection, ConnectionState};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// An endpoint around a process coffin
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SeanceEndpoint {
+    /// Graveyard node name (address or collapsed group)
+    pub key: Arc<str>,
+    pub connections: usize,
+    /// Most common state of the connections
+    pub state: ConnectionState,
+}
+
+/// One mini-graveyard of the grid
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SeanceCell {
+    pub pid: i32,
+    pub name: String,
+    /// Connections to remote endpoints
+    pub connections: usize,
+    /// Busiest endpoints first, at most SEANCE_ENDPOINTS
+    pub endpoints: Vec<SeanceEndpoint>,
+    /// Endpoints left out of `endpoints`
+    pub hidden_endpoints: usize,
+}
+
+/// The `limit` processes with the most connections to remote endpoints,
+/// busiest first; `endpoint_key` names the Graveyard node of a connection
+/// (None for listeners)
+pub fn busiest_processes(
+    conns: &[Connection],
+    limit: usize,
+    endpoint_key: impl Fn(&Connection) -> Option<Arc<str>>,
+) -> Vec<SeanceCell> {
+    let mut by_pid: HashMap<i32, Vec<(&Connection, Arc<str>)>> = HashMap::new();
+    for conn in conns {
+        let (Some(pid), Some(key)) = (conn.pid, endpoint_key(conn)) else {
+            continue;
+        };
+        by_pid.entry(pid).or_default().push((conn, key));
+    }
+
+    let mut cells: Vec<SeanceCell> = by_pid
+        .into_iter()
+        .map(|(pid, conns)| {
+            let mut states: HashMap<&Arc<str>, HashMap<ConnectionState, usize>> = HashMap::new();
+            for (conn, key) in &conns {
+                *states
+                    .entry(key)
+                    .or_default()
+                    .entry(conn.state)
+                    .or_default() += 1;
+            }
+            let mut endpoints: Vec<SeanceEndpoint> = states
+                .into_iter()
+                .map(|(key, states)| SeanceEndpoint {
+                    key: key.clone(),
+                    connections: states.values().sum(),
+                    state: states
+                        .into_iter()
+                        .max_by_key(|&(state, count)| (count, std::cmp::Reverse(state.name())))
+                        .map(|(state, _)| state)
+                        .unwrap_or(ConnectionState::Unknown),
+                })
+                .collect();
+            endpoints.sort_by(|a, b| b.connections.cmp(&a.connections).then(a.key.cmp(&b.key)));
+            let hidden_endpoints = endpoints.len().saturating_sub(SEANCE_ENDPOINTS);
+            endpoints.truncate(SEANCE_ENDPOINTS);
+            SeanceCell {
+                pid,
+                name: conns[0]
+                    .0
+                    .process_name
+                    .as_deref()
+                    .unwrap_or("unknown")
+                    .to_string(),
+                connections: conns.len(),
+                endpoints,
+                hidden_endpoints,
+            }
+        })
+        .collect();
+    cells.sort_by(|a, b| b.connections.cmp(&a.connections).then(a.pid.cmp(&b.pid)));
+    cells.truncate(limit);
+    cells
+}
+
+/// Columns and rows of a grid holding `cells` tiles, as square as
+/// possible with the extra column going to the width
+pub fn grid_size(cells: usize) -> (usize, usize) {
+    if cells == 0 {
+        return (0, 0);
+    }
+    let mut cols = 1;
+    while cols * cols < cells {
+        cols += 1;
+    }
+    (cols, cells.div_ceil(cols))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn conn(pid: i32, name: &str, remote_addr: &str, state: ConnectionState) -> Connection {
+        Connection {
+            remote_addr: remote_addr.into(),
+            remote_port: 443,
+            state,
+            pid: Some(pid),
+            process_name: Some(name.into()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_busiest_processes() {
+        use ConnectionState::*;
+        let conns = [
+            conn(812, "curl", "93.184.216.34", Established),
+            conn(1, "sshd", "0.0.0.0", Listen),
+            conn(900, "nginx", "10.1.0.1", Established),
+            conn(900, "nginx", "10.1.0.1", TimeWait),
+            conn(900, "nginx", "10.1.0.1", TimeWait),
+            conn(900, "nginx", "10.1.0.2", Established),
+        ];
+        let key = |c: &Connection| (c.state != Listen).then(|| c.remote_addr.clone());
+
+        let cells = busiest_processes(&conns, 6, key);
+        // Listener-only processes have nothing to draw
+        assert_eq!(cells.len(), 2);
+        assert_eq!((cells[0].pid, cells[0].name.as_str()), (900, "nginx"));
+        assert_eq!(cells[0].connections, 4);
+        assert_eq!(
+            cells[0].endpoints[0],
+            SeanceEndpoint {
+                key: "10.1.0.1".into(),
+                connections: 3,
+                state: TimeWait,
+            }
+        );
+        assert_eq!(cells[1].pid, 812);
+
+        assert_eq!(busiest_processes(&conns, 1, key).len(), 1);
+    }
+
+    #[test]
+    fn test_grid_size() {
+        assert_eq!(grid_size(0), (0, 0));
+        assert_eq!(grid_size(1), (1, 1));
+        assert_eq!(grid_size(2), (2, 1));
+        assert_eq!(grid_size(4), (2, 2));
+        assert_eq!(grid_size(5), (3, 2));
+        assert_eq!(grid_size(6), (3, 2));
+    }
+}
@@ -0,0 +1,86 @@
+// Saved settings
+//
+// Choices made at runtime that should outlive the session, kept in
+// settings.toml next to config.toml. Unlike config.toml, which ntomb only
+// reads, this file is rewritten by the app. For now it holds the emoji
+// width offset calibrated with `[` / `]` / `|`, keyed by $TERM since the
+// right value depends on the terminal:
+//
+// ```toml
+// [emoji_width]
+// xterm-256color = -1
+// xterm-kitty = 0
+// ```
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Contents of settings.toml
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct Settings {
+    /// Emoji width offset per $TERM value
+    pub emoji_width: BTreeMap<String, i32>,
+}
+
+impl Settings {
+    /// Read `path`; a missing or unreadable file gives empty settings,
+    /// since anything saved there can be chosen again
+    pub fn load(path: &Path) -> Self {
+        let Ok(content) = fs::read_to_string(path) else {
+            return Self::default();
+        };
+        toml::from_str(&content).unwrap_or_else(|e| {
+            tracing::warn!(path = %path.display(), error = %e, "Ignoring invalid settings file");
+            Self::default()
+        })
+    }
+
+    /// Write to `path`, creating its directory if needed
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let content =
+            toml::to_string(self).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        fs::write(path, content)
+    }
+}
+
+/// settings.toml in the same directory as the default config.toml
+pub fn default_path() -> Option<PathBuf> {
+    super::config_file::default_path().map(|config| config.with_file_name("settings.toml"))
+}
+
+/// The terminal the offset is saved under: $TERM, or "unknown"
+pub fn terminal_name() -> String {
+    std::env::var("TERM")
+        .ok()
+        .filter(|term| !term.is_empty())
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_settings_round_trip() {
+        let dir = std::env::temp_dir().join(format!("ntomb-settings-{}", std::process::id()));
+        let path = dir.join("nested").join("settings.toml");
+        assert_eq!(Settings::load(&path), Settings::default());
+
+        let mut settings = Settings::default();
+        settings.emoji_width.insert("xterm-256color".into(), -1);
+        settings.emoji_width.insert("xterm-kitty".into(), 0);
+        settings.save(&path).unwrap();
+        assert_eq!(Settings::load(&path), settings);
+
+        fs::write(&path, "emoji_width = 3").unwrap();
+        assert_eq!(Settings::load(&path), Settings::default());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
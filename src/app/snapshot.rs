@@ -0,0 +1,174 @@
+// Recorded snapshots and snapshot diffs
+//
+// `m` marks the current snapshot as a baseline; the diff view (`v`) then
+// shows only connections added or removed since that point, which answers
+// "what changed when the deploy happened?". `M` freezes a second snapshot
+// so two recorded points can be compared instead of baseline vs live.
+//
+// Every data refresh is also kept in a bounded `SnapshotHistory`, which the
+// timeline (Space to pause, Left/Right to step) replays.
+
+use super::events::time_of_day_label;
+use crate::net::Connection;
+use std::collections::{HashSet, VecDeque};
+use std::sync::Arc;
+use std::time::SystemTime;
+
+/// Connection identity across snapshots: (local addr, local port, remote addr, remote port)
+pub type ConnectionKey = (Arc<str>, u16, Arc<str>, u16);
+
+/// Identity of `conn` for diffing
+pub fn connection_key(conn: &Connection) -> ConnectionKey {
+    (
+        conn.local_addr.clone(),
+        conn.local_port,
+        conn.remote_addr.clone(),
+        conn.remote_port,
+    )
+}
+
+/// A connection list captured at one point in time
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Snapshot {
+    pub taken_at: SystemTime,
+    pub connections: Vec<Connection>,
+}
+
+impl Snapshot {
+    /// Capture `connections` as of now
+    pub fn capture(connections: &[Connection]) -> Self {
+        Self {
+            taken_at: SystemTime::now(),
+            connections: connections.to_vec(),
+        }
+    }
+
+    /// "HH:MM:SS" of the capture
+    pub fn time_label(&self) -> String {
+        time_of_day_label(self.taken_at)
+    }
+}
+
+/// The most recent snapshots, oldest first
+#[derive(Debug, Clone)]
+pub struct SnapshotHistory {
+    snapshots: VecDeque<Snapshot>,
+    capacity: usize,
+}
+
+impl SnapshotHistory {
+    /// Keep at most `capacity` snapshots (at least one)
+    pub fn new(capacity: usize) -> Self {
+        let capacity = capacity.max(1);
+        Self {
+            snapshots: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    /// Record a snapshot; returns true when the oldest one was dropped
+    pub fn push(&mut self, snapshot: Snapshot) -> bool {
+        let evicted = self.snapshots.len() == self.capacity;
+        if evicted {
+            self.snapshots.pop_front();
+        }
+        self.snapshots.push_back(snapshot);
+        evicted
+    }
+
+    pub fn len(&self) -> usize {
+        self.snapshots.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.snapshots.is_empty()
+    }
+
+    /// Snapshot at `index` (0 = oldest)
+    pub fn get(&self, index: usize) -> Option<&Snapshot> {
+        self.snapshots.get(index)
+    }
+}
+
+/// How a connection differs from the baseline
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DiffChange {
+    Added,
+    Removed,
+}
+
+impl DiffChange {
+    /// "+" or "-" marker for list rows
+    pub fn marker(&self) -> &'static str {
+        match self {
+            DiffChange::Added => "+",
+            DiffChange::Removed => "-",
+        }
+    }
+}
+
+/// Connections in `current` but not `baseline` (Added), followed by those
+/// only in `baseline` (Removed); state changes of a kept socket are not
+/// reported
+pub fn diff(baseline: &[Connection], current: &[Connection]) -> Vec<(DiffChange, Connection)> {
+    let before: HashSet<ConnectionKey> = baseline.iter().map(connection_key).collect();
+    let after: HashSet<ConnectionKey> = current.iter().map(connection_key).collect();
+
+    let added = current
+        .iter()
+        .filter(|c| !before.contains(&connection_key(c)))
+        .map(|c| (DiffChange::Added, c.clone()));
+    let removed = baseline
+        .iter()
+        .filter(|c| !after.contains(&connection_key(c)))
+        .map(|c| (DiffChange::Removed, c.clone()));
+    added.chain(removed).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn conn(remote: &str, port: u16) -> Connection {
+        Connection {
+            local_addr: "10.0.0.5".into(),
+            local_port: 40000 + port,
+            remote_addr: remote.into(),
+            remote_port: port,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_diff_added_and_removed() {
+        let baseline = vec![conn("10.1.0.1", 5432), conn("10.1.0.2", 6379)];
+        let current = vec![conn("10.1.0.1", 5432), conn("10.1.0.3", 443)];
+        let changes = diff(&baseline, &current);
+        assert_eq!(changes.len(), 2);
+        assert_eq!(changes[0].0, DiffChange::Added);
+        assert_eq!(&*changes[0].1.remote_addr, "10.1.0.3");
+        assert_eq!(changes[1].0, DiffChange::Removed);
+        assert_eq!(&*changes[1].1.remote_addr, "10.1.0.2");
+    }
+
+    #[test]
+    fn test_history_drops_oldest() {
+        let mut history = SnapshotHistory::new(2);
+        assert!(!history.push(Snapshot::capture(&[conn("10.1.0.1", 1)])));
+        assert!(!history.push(Snapshot::capture(&[conn("10.1.0.2", 2)])));
+        assert!(history.push(Snapshot::capture(&[conn("10.1.0.3", 3)])));
+        assert_eq!(history.len(), 2);
+        assert_eq!(
+            &*history.get(0).unwrap().connections[0].remote_addr,
+            "10.1.0.2"
+        );
+    }
+
+    #[test]
+    fn test_diff_ignores_state_changes() {
+        let baseline = vec![conn("10.1.0.1", 5432)];
+        let mut current = baseline.clone();
+        current[0].state = crate::net::ConnectionState::TimeWait;
+        assert!(diff(&baseline, &current).is_empty());
+    }
+}
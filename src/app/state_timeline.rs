@@ -0,0 +1,117 @@
+// Per-connection TCP state timelines
+//
+// Every data refresh records a connection's state when it differs from the
+// one recorded last, so the endpoint detail popup can show how each
+// connection got where it is ("SYN_SENT 12:00:01 → ESTABLISHED +2s →
+// FIN_WAIT1 +5m03s") for debugging handshakes and teardowns. A connection
+// that disappears is forgotten; the states are only as fine-grained as the
+// refresh interval.
+
+use super::snapshot::{connection_key, ConnectionKey};
+use crate::net::{Connection, ConnectionState};
+use std::collections::HashMap;
+use std::time::SystemTime;
+
+/// Steps kept per connection before the oldest is dropped
+const MAX_STEPS: usize = 8;
+
+/// A state a connection was first seen in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StateStep {
+    pub at: SystemTime,
+    pub state: ConnectionState,
+}
+
+/// State history of every current connection
+#[derive(Debug, Clone, Default)]
+pub struct StateTimelines {
+    tracks: HashMap<ConnectionKey, Vec<StateStep>>,
+}
+
+impl StateTimelines {
+    /// Record the states of `conns` seen at `at`, forgetting connections
+    /// that are gone; LISTEN sockets have no handshake to follow
+    pub fn observe(&mut self, conns: &[Connection], at: SystemTime) {
+        let mut tracks = HashMap::with_capacity(conns.len());
+        for conn in conns {
+            if conn.state == ConnectionState::Listen {
+                continue;
+            }
+            let key = connection_key(conn);
+            let mut steps = self.tracks.remove(&key).unwrap_or_default();
+            if steps.last().map(|step| step.state) != Some(conn.state) {
+                if steps.len() >= MAX_STEPS {
+                    steps.remove(0);
+                }
+                steps.push(StateStep {
+                    at,
+                    state: conn.state,
+                });
+            }
+            tracks.insert(key, steps);
+        }
+        self.tracks = tracks;
+    }
+
+    /// Recorded states of `conn`, oldest first
+    pub fn steps(&self, conn: &Connection) -> &[StateStep] {
+        self.tracks
+            .get(&connection_key(conn))
+            .map_or(&[], Vec::as_slice)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    fn conn(remote_port: u16, state: ConnectionState) -> Connection {
+        Connection {
+            remote_addr: "93.184.216.34".into(),
+            remote_port,
+            state,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_records_transitions() {
+        use ConnectionState::*;
+        let at = |secs| UNIX_EPOCH + Duration::from_secs(secs);
+        let mut timelines = StateTimelines::default();
+        for (secs, state) in [
+            (1, SynSent),
+            (2, Established),
+            (3, Established),
+            (9, FinWait1),
+        ] {
+            timelines.observe(&[conn(443, state), conn(80, Listen)], at(secs));
+        }
+        let steps: Vec<(SystemTime, ConnectionState)> = timelines
+            .steps(&conn(443, Established))
+            .iter()
+            .map(|step| (step.at, step.state))
+            .collect();
+        assert_eq!(
+            steps,
+            [(at(1), SynSent), (at(2), Established), (at(9), FinWait1)]
+        );
+        assert!(timelines.steps(&conn(80, Listen)).is_empty());
+
+        // Gone connections are forgotten
+        timelines.observe(&[], at(10));
+        assert!(timelines.steps(&conn(443, FinWait1)).is_empty());
+    }
+
+    #[test]
+    fn test_steps_are_bounded() {
+        use ConnectionState::*;
+        let mut timelines = StateTimelines::default();
+        for i in 0..20 {
+            let state = if i % 2 == 0 { Established } else { CloseWait };
+            timelines.observe(&[conn(443, state)], UNIX_EPOCH);
+        }
+        assert_eq!(timelines.steps(&conn(443, Established)).len(), MAX_STEPS);
+    }
+}
@@ -0,0 +1,59 @@
+// One-line summary for tmux and desktop bars
+//
+// `ntomb --statusline` prints a compact count and exits, which suits
+// tmux's `#(ntomb --statusline)`; `--interval N` keeps printing a fresh
+// line every N seconds for bars that read a long-running command (waybar,
+// i3blocks, polybar's tail mode):
+//
+// ```text
+// 🕸 42 est | 7 listen | ⚠2 alerts
+// ```
+//
+// Alerts are the warning and critical entries currently in the Grimoire.
+
+use super::events::{EventSeverity, GrimoireEvent};
+use crate::net::{Connection, ConnectionState};
+
+/// The summary line for `conns` and the Grimoire `events`
+pub fn summary<'a>(
+    conns: &[Connection],
+    events: impl IntoIterator<Item = &'a GrimoireEvent>,
+) -> String {
+    let count = |state| conns.iter().filter(|c| c.state == state).count();
+    let alerts = events
+        .into_iter()
+        .filter(|event| event.severity >= EventSeverity::Warning)
+        .count();
+    format!(
+        "🕸 {} est | {} listen | ⚠{} alerts",
+        count(ConnectionState::Established),
+        count(ConnectionState::Listen),
+        alerts
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_summary() {
+        let conn = |state| Connection {
+            state,
+            ..Default::default()
+        };
+        let conns = vec![
+            conn(ConnectionState::Established),
+            conn(ConnectionState::Established),
+            conn(ConnectionState::Listen),
+            conn(ConnectionState::TimeWait),
+        ];
+        let events = vec![
+            GrimoireEvent::new(EventSeverity::Info, "Graveyard opened"),
+            GrimoireEvent::new(EventSeverity::Warning, "Root listener exposed"),
+            GrimoireEvent::new(EventSeverity::Critical, "Haunted endpoint"),
+        ];
+        assert_eq!(summary(&conns, &events), "🕸 2 est | 1 listen | ⚠2 alerts");
+        assert_eq!(summary(&[], &[]), "🕸 0 est | 0 listen | ⚠0 alerts");
+    }
+}
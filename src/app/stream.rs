@@ -0,0 +1,229 @@
+// NDJSON output for scripts and log shippers
+//
+// `ntomb --json` prints the current connections as one JSON object per
+// line and exits. With `--watch` it keeps polling instead of starting the
+// TUI and prints one line per connection event as it is detected:
+//
+// ```text
+// {"ts":1760000000123,"event":"open","local_addr":"10.0.0.5","local_port":51000,...}
+// {"ts":1760000002130,"event":"state_change","prev_state":"ESTABLISHED","state":"TIME_WAIT",...}
+// {"ts":1760000004127,"event":"close",...}
+// ```
+//
+// The first poll reports every existing connection as "open", so a
+// consumer starting from nothing ends up with the full picture.
+
+use super::snapshot::{connection_key, ConnectionKey};
+use crate::net::{Connection, ConnectionDirection, ConnectionState};
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// What happened to a connection between two polls
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StreamEvent {
+    Open,
+    Close,
+    /// The socket is still there in a different state (the previous one)
+    StateChange(ConnectionState),
+}
+
+impl StreamEvent {
+    /// Value of the "event" field
+    pub fn name(&self) -> &'static str {
+        match self {
+            StreamEvent::Open => "open",
+            StreamEvent::Close => "close",
+            StreamEvent::StateChange(_) => "state_change",
+        }
+    }
+}
+
+/// Events turning `previous` into `current`: opens and state changes in
+/// `current` order, then closes in `previous` order
+pub fn connection_events<'a>(
+    previous: &'a [Connection],
+    current: &'a [Connection],
+) -> Vec<(StreamEvent, &'a Connection)> {
+    let before: HashMap<ConnectionKey, &Connection> =
+        previous.iter().map(|c| (connection_key(c), c)).collect();
+    let after: HashMap<ConnectionKey, &Connection> =
+        current.iter().map(|c| (connection_key(c), c)).collect();
+
+    let mut events = Vec::new();
+    for conn in current {
+        match before.get(&connection_key(conn)) {
+            None => events.push((StreamEvent::Open, conn)),
+            Some(old) if old.state != conn.state => {
+                events.push((StreamEvent::StateChange(old.state), conn))
+            }
+            Some(_) => {}
+        }
+    }
+    for conn in previous {
+        if !after.contains_key(&connection_key(conn)) {
+            events.push((StreamEvent::Close, conn));
+        }
+    }
+    events
+}
+
+/// One NDJSON line (without the newline) for `conn`, stamped with the
+/// current time; `event` is omitted for plain `--json` listings
+pub fn json_line(event: Option<StreamEvent>, conn: &Connection) -> String {
+    let ts = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or(0);
+    let mut out = format!("{{\"ts\":{}", ts);
+    if let Some(event) = event {
+        let _ = write!(out, ",{}", event_fields(event));
+    }
+    let _ = write!(out, ",{}}}", connection_fields(conn));
+    out
+}
+
+/// `event` on `conn` as a JSON object, without a timestamp
+pub fn event_json(event: StreamEvent, conn: &Connection) -> String {
+    format!("{{{},{}}}", event_fields(event), connection_fields(conn))
+}
+
+/// The "event" member, plus "prev_state" for state changes
+fn event_fields(event: StreamEvent) -> String {
+    let mut out = format!("\"event\":\"{}\"", event.name());
+    if let StreamEvent::StateChange(previous) = event {
+        let _ = write!(out, ",\"prev_state\":\"{}\"", previous.name());
+    }
+    out
+}
+
+/// `conn` as a JSON object
+pub fn connection_json(conn: &Connection) -> String {
+    format!("{{{}}}", connection_fields(conn))
+}
+
+/// The JSON members describing `conn`, without the enclosing braces
+fn connection_fields(conn: &Connection) -> String {
+    let direction = conn.direction.map(|d| match d {
+        ConnectionDirection::Inbound => "inbound",
+        ConnectionDirection::Outbound => "outbound",
+    });
+    format!(
+        "\"local_addr\":{},\"local_port\":{},\"remote_addr\":{},\"remote_port\":{},\"state\":\"{}\",\
+         \"direction\":{},\"pid\":{},\"process\":{},\"uid\":{},\"user\":{},\"container\":{},\"interface\":{},\"labels\":{}",
+        json_string(&conn.local_addr),
+        conn.local_port,
+        json_string(&conn.remote_addr),
+        conn.remote_port,
+        conn.state.name(),
+        json_option(direction),
+        json_number(conn.pid),
+        json_option(conn.process_name.as_deref()),
+        json_number(conn.uid),
+        json_option(conn.user.as_deref()),
+        json_option(conn.container.as_deref()),
+        json_option(conn.interface.as_deref()),
+        labels_json(conn),
+    )
+}
+
+/// Enricher labels as a JSON object
+fn labels_json(conn: &Connection) -> String {
+    let members: Vec<String> = conn
+        .labels
+        .iter()
+        .map(|(key, value)| format!("{}:{}", json_string(key), json_string(value)))
+        .collect();
+    format!("{{{}}}", members.join(","))
+}
+
+/// `text` as a JSON string literal
+pub fn json_string(text: &str) -> String {
+    let mut out = String::with_capacity(text.len() + 2);
+    out.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// A JSON string, or null
+pub fn json_option(text: Option<&str>) -> String {
+    text.map_or("null".to_string(), json_string)
+}
+
+/// A JSON number, or null
+pub fn json_number(value: Option<impl std::fmt::Display>) -> String {
+    value.map_or("null".to_string(), |value| value.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn conn(remote_port: u16, state: ConnectionState) -> Connection {
+        Connection {
+            local_addr: "10.0.0.5".into(),
+            local_port: 51000,
+            remote_addr: "93.184.216.34".into(),
+            remote_port,
+            state,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_connection_events() {
+        use ConnectionState::*;
+        let previous = vec![conn(443, Established), conn(80, Established)];
+        let current = vec![conn(443, TimeWait), conn(8443, SynSent)];
+        let events: Vec<_> = connection_events(&previous, &current)
+            .into_iter()
+            .map(|(event, c)| (event, c.remote_port))
+            .collect();
+        assert_eq!(
+            events,
+            vec![
+                (StreamEvent::StateChange(Established), 443),
+                (StreamEvent::Open, 8443),
+                (StreamEvent::Close, 80),
+            ]
+        );
+        assert!(connection_events(&current, &current).is_empty());
+    }
+
+    #[test]
+    fn test_json_line() {
+        let mut c = conn(443, ConnectionState::TimeWait);
+        c.pid = Some(812);
+        c.process_name = Some("cu\"rl".into());
+        c.direction = Some(ConnectionDirection::Outbound);
+        c.labels.insert("service", "https".into());
+        let line = json_line(
+            Some(StreamEvent::StateChange(ConnectionState::Established)),
+            &c,
+        );
+        let (ts, rest) = line.split_once(',').unwrap();
+        assert!(ts.starts_with("{\"ts\":"));
+        assert_eq!(
+            rest,
+            "\"event\":\"state_change\",\"prev_state\":\"ESTABLISHED\",\
+             \"local_addr\":\"10.0.0.5\",\"local_port\":51000,\
+             \"remote_addr\":\"93.184.216.34\",\"remote_port\":443,\"state\":\"TIME_WAIT\",\
+             \"direction\":\"outbound\",\"pid\":812,\"process\":\"cu\\\"rl\",\
+             \"uid\":null,\"user\":null,\"container\":null,\"interface\":null,\"labels\":{\"service\":\"https\"}}"
+        );
+        assert_eq!(json_string("a\u{1}\n"), "\"a\\u0001\\n\"");
+    }
+}
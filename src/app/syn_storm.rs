@@ -0,0 +1,226 @@
+// SYN flood / connect-storm detection
+//
+// Watches half-open sockets across data refreshes: new SYN_RECV/SYN_SENT
+// sockets per second, and SYN_RECV sockets piled up on each listener. When
+// either crosses its threshold a storm is declared, which shows a warning
+// banner and logs a Grimoire event, until both fall back below.
+//
+// ```toml
+// [syn_storm]
+// rate_per_sec = 50             # new half-open sockets per second
+// half_open_per_listener = 100  # SYN_RECV sockets on one local port
+// ```
+
+use crate::net::{Connection, ConnectionState};
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::Instant;
+
+/// Default new half-open sockets per second that count as a storm
+pub const DEFAULT_RATE_PER_SEC: u32 = 50;
+
+/// Default SYN_RECV sockets on one listener that count as a storm
+pub const DEFAULT_HALF_OPEN_PER_LISTENER: u32 = 100;
+
+/// `[syn_storm]` section of config.toml
+#[derive(Debug, Clone, Default, Deserialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct SynStormConfig {
+    /// New SYN_RECV/SYN_SENT sockets per second (default 50)
+    pub rate_per_sec: Option<u32>,
+    /// SYN_RECV sockets on a single local port (default 100)
+    pub half_open_per_listener: Option<u32>,
+}
+
+/// Half-open socket identity: (local addr, local port, remote addr, remote port)
+type HalfOpenKey = (Arc<str>, u16, Arc<str>, u16);
+
+/// Measurements from the latest snapshot
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SynStormStatus {
+    /// Sockets in SYN_RECV (inbound handshakes)
+    pub syn_recv: usize,
+    /// Sockets in SYN_SENT (outbound handshakes)
+    pub syn_sent: usize,
+    /// New half-open sockets per second since the previous snapshot
+    pub rate: f64,
+    /// Local port with the most SYN_RECV sockets, and their count
+    pub busiest_listener: Option<(u16, usize)>,
+    /// Whether a storm is in progress
+    pub active: bool,
+}
+
+impl SynStormStatus {
+    /// One-line summary for the banner and events
+    pub fn summary(&self) -> String {
+        let mut text = format!(
+            "{:.0} half-open/s ({} SYN_RECV, {} SYN_SENT)",
+            self.rate, self.syn_recv, self.syn_sent
+        );
+        if let Some((port, count)) = self.busiest_listener {
+            text.push_str(&format!(", :{} has {} pending", port, count));
+        }
+        text
+    }
+}
+
+/// Storm start/end reported by `observe`
+#[derive(Debug, Clone, PartialEq)]
+pub enum SynStormChange {
+    Started,
+    Ended,
+}
+
+/// Tracks half-open sockets between snapshots
+#[derive(Debug, Clone)]
+pub struct SynStormMonitor {
+    rate_threshold: f64,
+    listener_threshold: usize,
+    previous: HashSet<HalfOpenKey>,
+    last_observed: Option<Instant>,
+    pub status: SynStormStatus,
+}
+
+impl SynStormMonitor {
+    /// Create a monitor; thresholds of 0 fall back to the defaults
+    pub fn new(config: &SynStormConfig) -> Self {
+        let rate = config
+            .rate_per_sec
+            .filter(|r| *r > 0)
+            .unwrap_or(DEFAULT_RATE_PER_SEC);
+        let listener = config
+            .half_open_per_listener
+            .filter(|n| *n > 0)
+            .unwrap_or(DEFAULT_HALF_OPEN_PER_LISTENER);
+        Self {
+            rate_threshold: rate as f64,
+            listener_threshold: listener as usize,
+            previous: HashSet::new(),
+            last_observed: None,
+            status: SynStormStatus::default(),
+        }
+    }
+
+    /// Measure a new snapshot taken at `now`
+    pub fn observe(&mut self, conns: &[Connection], now: Instant) -> Option<SynStormChange> {
+        let mut current = HashSet::new();
+        let mut per_listener: HashMap<u16, usize> = HashMap::new();
+        let (mut syn_recv, mut syn_sent) = (0, 0);
+        for conn in conns {
+            match conn.state {
+                ConnectionState::SynRecv => {
+                    syn_recv += 1;
+                    *per_listener.entry(conn.local_port).or_insert(0) += 1;
+                }
+                ConnectionState::SynSent => syn_sent += 1,
+                _ => continue,
+            }
+            current.insert((
+                conn.local_addr.clone(),
+                conn.local_port,
+                conn.remote_addr.clone(),
+                conn.remote_port,
+            ));
+        }
+
+        // The first snapshot has no baseline, so it never reports a rate
+        let rate = match self.last_observed {
+            Some(last) => {
+                let elapsed = now.duration_since(last).as_secs_f64().max(0.001);
+                current.difference(&self.previous).count() as f64 / elapsed
+            }
+            None => 0.0,
+        };
+        self.previous = current;
+        self.last_observed = Some(now);
+
+        let busiest_listener = per_listener
+            .into_iter()
+            .max_by_key(|(port, count)| (*count, std::cmp::Reverse(*port)));
+        let active = rate >= self.rate_threshold
+            || busiest_listener.is_some_and(|(_, count)| count >= self.listener_threshold);
+
+        let was_active = self.status.active;
+        self.status = SynStormStatus {
+            syn_recv,
+            syn_sent,
+            rate,
+            busiest_listener,
+            active,
+        };
+        match (was_active, active) {
+            (false, true) => Some(SynStormChange::Started),
+            (true, false) => Some(SynStormChange::Ended),
+            _ => None,
+        }
+    }
+}
+
+impl Default for SynStormMonitor {
+    fn default() -> Self {
+        Self::new(&SynStormConfig::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn half_open(state: ConnectionState, local_port: u16, remote_port: u16) -> Connection {
+        Connection {
+            local_addr: "10.0.0.1".into(),
+            local_port,
+            remote_addr: "203.0.113.9".into(),
+            remote_port,
+            state,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_rate_triggers_storm() {
+        let mut monitor = SynStormMonitor::new(&SynStormConfig {
+            rate_per_sec: Some(10),
+            ..Default::default()
+        });
+        let start = Instant::now();
+        assert_eq!(monitor.observe(&[], start), None);
+
+        // 30 new handshakes in 2 seconds = 15/s
+        let burst: Vec<_> = (0..30)
+            .map(|i| half_open(ConnectionState::SynRecv, 443, 40000 + i))
+            .collect();
+        let change = monitor.observe(&burst, start + Duration::from_secs(2));
+        assert_eq!(change, Some(SynStormChange::Started));
+        assert_eq!(monitor.status.rate, 15.0);
+        assert_eq!(monitor.status.busiest_listener, Some((443, 30)));
+
+        // Same sockets still pending: no new arrivals, below both thresholds
+        let change = monitor.observe(&burst, start + Duration::from_secs(4));
+        assert_eq!(change, Some(SynStormChange::Ended));
+        assert_eq!(monitor.status.rate, 0.0);
+    }
+
+    #[test]
+    fn test_half_open_pileup_triggers_storm() {
+        let mut monitor = SynStormMonitor::new(&SynStormConfig {
+            half_open_per_listener: Some(5),
+            ..Default::default()
+        });
+        let pending: Vec<_> = (0..5)
+            .map(|i| half_open(ConnectionState::SynRecv, 22, 50000 + i))
+            .chain([half_open(ConnectionState::SynSent, 41000, 443)])
+            .collect();
+        assert_eq!(
+            monitor.observe(&pending, Instant::now()),
+            Some(SynStormChange::Started)
+        );
+        assert_eq!(monitor.status.syn_sent, 1);
+        assert_eq!(
+            monitor.status.summary(),
+            "0 half-open/s (5 SYN_RECV, 1 SYN_SENT), :22 has 5 pending"
+        );
+    }
+}
@@ -0,0 +1,227 @@
+// Connection tags
+//
+// Quick triage labels for the current session: `n` opens a popup to put
+// `!suspicious`, `ok` or `follow-up` on the selected connection (or on
+// every connection marked with `.`), the
+// Grimoire shows them as colored markers, `N` narrows the list to one tag
+// and yanked lines and Mermaid exports carry them. Tags are keyed by the
+// connection's addresses and ports, so they follow it across refreshes,
+// and are forgotten when ntomb exits.
+
+use super::snapshot::{connection_key, ConnectionKey};
+use crate::net::Connection;
+use crate::theme::{BLOOD_RED, PUMPKIN_ORANGE, TOXIC_GREEN};
+use ratatui::style::Color;
+use std::collections::{BTreeSet, HashMap};
+
+/// A triage label
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Tag {
+    Suspicious,
+    Ok,
+    FollowUp,
+}
+
+impl Tag {
+    /// Every tag, in popup order
+    pub const ALL: [Tag; 3] = [Tag::Suspicious, Tag::Ok, Tag::FollowUp];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Tag::Suspicious => "!suspicious",
+            Tag::Ok => "ok",
+            Tag::FollowUp => "follow-up",
+        }
+    }
+
+    /// Marker color in the connection list and the popup
+    pub fn color(&self) -> Color {
+        match self {
+            Tag::Suspicious => BLOOD_RED,
+            Tag::Ok => TOXIC_GREEN,
+            Tag::FollowUp => PUMPKIN_ORANGE,
+        }
+    }
+}
+
+/// Tags of the connections tagged this session
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConnectionTags {
+    tags: HashMap<ConnectionKey, BTreeSet<Tag>>,
+}
+
+impl ConnectionTags {
+    /// Tags on `conn`, in `Tag::ALL` order (empty when untagged)
+    pub fn get(&self, conn: &Connection) -> Vec<Tag> {
+        self.tags
+            .get(&connection_key(conn))
+            .map(|tags| tags.iter().copied().collect())
+            .unwrap_or_default()
+    }
+
+    pub fn has(&self, conn: &Connection, tag: Tag) -> bool {
+        self.tags
+            .get(&connection_key(conn))
+            .is_some_and(|tags| tags.contains(&tag))
+    }
+
+    /// Add `tag` to `conn`, or remove it when `tagged` is false
+    pub fn set(&mut self, conn: &Connection, tag: Tag, tagged: bool) {
+        let key = connection_key(conn);
+        if tagged {
+            self.tags.entry(key).or_default().insert(tag);
+        } else if let Some(tags) = self.tags.get_mut(&key) {
+            tags.remove(&tag);
+            if tags.is_empty() {
+                self.tags.remove(&key);
+            }
+        }
+    }
+
+    /// Toggle `tag` on all of `conns` at once: added to every one unless
+    /// they all have it already, in which case it is removed from all;
+    /// returns whether the connections have the tag now
+    pub fn toggle_all(&mut self, conns: &[Connection], tag: Tag) -> bool {
+        let tagged = !conns.iter().all(|conn| self.has(conn, tag));
+        for conn in conns {
+            self.set(conn, tag, tagged);
+        }
+        tagged
+    }
+
+    /// "!suspicious,follow-up", or None when `conn` is untagged
+    pub fn labels(&self, conn: &Connection) -> Option<String> {
+        let tags = self.get(conn);
+        if tags.is_empty() {
+            return None;
+        }
+        let labels: Vec<&str> = tags.iter().map(Tag::label).collect();
+        Some(labels.join(","))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tags.is_empty()
+    }
+
+    /// Every tagged connection with its tags
+    pub fn iter(&self) -> impl Iterator<Item = (&ConnectionKey, &BTreeSet<Tag>)> {
+        self.tags.iter()
+    }
+}
+
+/// State of the open tag popup
+#[derive(Debug, Clone, PartialEq)]
+pub struct TagPopup {
+    /// Connections being tagged, kept so a refresh reordering the list
+    /// while the popup is open doesn't move the tag to another socket
+    pub conns: Vec<Connection>,
+    /// What is being tagged ("1.2.3.4:443", "3 marked connections")
+    pub target: String,
+    /// Highlighted row of `Tag::ALL`
+    pub selected: usize,
+}
+
+impl TagPopup {
+    /// Popup for the single connection `conn`
+    pub fn new(conn: &Connection) -> Self {
+        Self {
+            conns: vec![conn.clone()],
+            target: format!("{}:{}", conn.remote_addr, conn.remote_port),
+            selected: 0,
+        }
+    }
+
+    /// Popup tagging all of the marked `conns`
+    pub fn for_marked(conns: Vec<Connection>) -> Self {
+        Self {
+            target: format!("{} marked connections", conns.len()),
+            conns,
+            selected: 0,
+        }
+    }
+
+    pub fn select_previous(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+
+    pub fn select_next(&mut self) {
+        if self.selected + 1 < Tag::ALL.len() {
+            self.selected += 1;
+        }
+    }
+
+    /// Currently highlighted tag
+    pub fn current(&self) -> Tag {
+        Tag::ALL[self.selected.min(Tag::ALL.len() - 1)]
+    }
+}
+
+/// Pick the next tag filter when cycling with the 'N' key:
+/// no filter → each tag in turn → no filter
+pub fn next_tag_filter(current: Option<Tag>) -> Option<Tag> {
+    match current {
+        None => Some(Tag::ALL[0]),
+        Some(tag) => {
+            let idx = Tag::ALL.iter().position(|t| *t == tag).unwrap_or(0);
+            Tag::ALL.get(idx + 1).copied()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn conn(remote_port: u16) -> Connection {
+        Connection {
+            local_addr: "10.0.0.5".into(),
+            local_port: 51000,
+            remote_addr: "93.184.216.34".into(),
+            remote_port,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_toggle_tags() {
+        let mut tags = ConnectionTags::default();
+        let https = conn(443);
+        let toggle = |tags: &mut ConnectionTags, tag| tags.toggle_all(&[conn(443)], tag);
+        assert!(toggle(&mut tags, Tag::FollowUp));
+        assert!(toggle(&mut tags, Tag::Suspicious));
+        assert_eq!(tags.get(&https), vec![Tag::Suspicious, Tag::FollowUp]);
+        assert_eq!(
+            tags.labels(&https).as_deref(),
+            Some("!suspicious,follow-up")
+        );
+        assert!(tags.has(&https, Tag::FollowUp));
+        assert!(!tags.has(&conn(80), Tag::FollowUp));
+        assert_eq!(tags.labels(&conn(80)), None);
+
+        // Toggling again removes the tag, and the entry once it is empty
+        assert!(!toggle(&mut tags, Tag::Suspicious));
+        assert!(!toggle(&mut tags, Tag::FollowUp));
+        assert!(tags.is_empty());
+    }
+
+    #[test]
+    fn test_toggle_all() {
+        let mut tags = ConnectionTags::default();
+        let conns = [conn(443), conn(80)];
+        tags.set(&conns[0], Tag::Ok, true);
+
+        // Added to the ones missing it first, then removed from all
+        assert!(tags.toggle_all(&conns, Tag::Ok));
+        assert!(tags.has(&conns[1], Tag::Ok));
+        assert!(!tags.toggle_all(&conns, Tag::Ok));
+        assert!(tags.is_empty());
+    }
+
+    #[test]
+    fn test_next_tag_filter() {
+        assert_eq!(next_tag_filter(None), Some(Tag::Suspicious));
+        assert_eq!(next_tag_filter(Some(Tag::Suspicious)), Some(Tag::Ok));
+        assert_eq!(next_tag_filter(Some(Tag::Ok)), Some(Tag::FollowUp));
+        assert_eq!(next_tag_filter(Some(Tag::FollowUp)), None);
+    }
+}
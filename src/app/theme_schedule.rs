@@ -0,0 +1,268 @@
+// Theme schedule
+//
+// Picks the theme from the calendar and the clock: the Kiroween Overdrive
+// (pumpkin) palette from mid-October until just after Halloween and the
+// plain one the rest of the year, and a dimmed palette at night. `[theme]`
+// in config.toml can pin either choice. A choice is only applied when the
+// schedule changes its mind, so toggling Overdrive with `H` sticks until
+// the next transition.
+
+use super::config::THEME_CHECK_INTERVAL;
+use super::events::civil_date;
+use serde::Deserialize;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+/// Which palette to use: by date, or pinned
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Season {
+    /// Overdrive from October 17 to November 2, plain otherwise
+    #[default]
+    Auto,
+    /// Always start in Overdrive
+    Halloween,
+    /// Never switch to Overdrive
+    Plain,
+}
+
+/// Whether to dim the palette: by time of day, or pinned
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Night {
+    /// Dim between `night_start` and `night_end`
+    #[default]
+    Auto,
+    /// Always dim
+    Dim,
+    /// Never dim
+    Bright,
+}
+
+/// `[theme]` section of config.toml
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct ThemeConfig {
+    /// "auto", "halloween" or "plain"
+    pub season: Season,
+    /// "auto", "dim" or "bright"
+    pub night: Night,
+    /// Hour (0-23) the night starts
+    pub night_start: u8,
+    /// Hour (0-23) the night ends
+    pub night_end: u8,
+    /// Local time offset from UTC in minutes (e.g. 540 for UTC+9); the
+    /// date and hour above are read on that clock
+    pub utc_offset_minutes: i32,
+}
+
+impl Default for ThemeConfig {
+    fn default() -> Self {
+        Self {
+            season: Season::default(),
+            night: Night::default(),
+            night_start: 20,
+            night_end: 7,
+            utc_offset_minutes: 0,
+        }
+    }
+}
+
+/// The theme the schedule wants
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ThemeChoice {
+    pub overdrive: bool,
+    pub dim: bool,
+}
+
+impl ThemeConfig {
+    /// The theme for wall-clock time `now`
+    pub fn pick(&self, now: SystemTime) -> ThemeChoice {
+        let secs = now
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs() as i64)
+            .unwrap_or(0)
+            + i64::from(self.utc_offset_minutes) * 60;
+        let (_, month, day) = civil_date(secs.div_euclid(86_400));
+        let hour = (secs.rem_euclid(86_400) / 3600) as u8;
+
+        let overdrive = match self.season {
+            Season::Auto => near_halloween(month, day),
+            Season::Halloween => true,
+            Season::Plain => false,
+        };
+        let dim = match self.night {
+            Night::Auto => is_night(hour, self.night_start % 24, self.night_end % 24),
+            Night::Dim => true,
+            Night::Bright => false,
+        };
+        ThemeChoice { overdrive, dim }
+    }
+}
+
+/// Two weeks before Halloween through the Day of the Dead
+fn near_halloween(month: i64, day: i64) -> bool {
+    matches!((month, day), (10, 17..=31) | (11, 1..=2))
+}
+
+/// Whether `hour` falls in [start, end), wrapping past midnight
+fn is_night(hour: u8, start: u8, end: u8) -> bool {
+    if start <= end {
+        (start..end).contains(&hour)
+    } else {
+        hour >= start || hour < end
+    }
+}
+
+/// Parts of the theme the schedule changed; None leaves a part alone
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ThemeChange {
+    pub overdrive: Option<bool>,
+    pub dim: Option<bool>,
+}
+
+/// Re-checks the schedule every THEME_CHECK_INTERVAL
+#[derive(Debug, Clone)]
+pub struct ThemeSchedule {
+    config: ThemeConfig,
+    last: Option<ThemeChoice>,
+    checked_at: Option<Instant>,
+}
+
+impl ThemeSchedule {
+    pub fn new(config: ThemeConfig) -> Self {
+        Self {
+            config,
+            last: None,
+            checked_at: None,
+        }
+    }
+
+    /// What changed since the previous check; the first check reports
+    /// every part
+    pub fn poll(&mut self, now: Instant, wall: SystemTime) -> ThemeChange {
+        if self
+            .checked_at
+            .is_some_and(|at| now.duration_since(at) < THEME_CHECK_INTERVAL)
+        {
+            return ThemeChange::default();
+        }
+        self.checked_at = Some(now);
+
+        let choice = self.config.pick(wall);
+        let last = self.last.replace(choice);
+        let changed = |part: fn(&ThemeChoice) -> bool| {
+            let value = part(&choice);
+            (last.as_ref().map(part) != Some(value)).then_some(value)
+        };
+        ThemeChange {
+            overdrive: changed(|c| c.overdrive),
+            dim: changed(|c| c.dim),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    /// 2025-10-31 00:00:00 UTC
+    const HALLOWEEN: u64 = 1_761_868_800;
+
+    fn at(secs: u64) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(secs)
+    }
+
+    #[test]
+    fn test_pick() {
+        let config = ThemeConfig::default();
+        let noon = 12 * 3600;
+        assert_eq!(
+            config.pick(at(HALLOWEEN + noon)),
+            ThemeChoice {
+                overdrive: true,
+                dim: false
+            }
+        );
+        // Mid-summer at 23:00 is plain but dim
+        let july = HALLOWEEN - 100 * 86_400;
+        assert_eq!(
+            config.pick(at(july + 23 * 3600)),
+            ThemeChoice {
+                overdrive: false,
+                dim: true
+            }
+        );
+        // Season edges: Oct 16 plain, Oct 17 and Nov 2 pumpkin, Nov 3 plain
+        let overdrive_on = |days: i64| {
+            let secs = HALLOWEEN as i64 + days * 86_400 + noon as i64;
+            config.pick(at(secs as u64)).overdrive
+        };
+        assert_eq!(
+            [-15, -14, 2, 3].map(overdrive_on),
+            [false, true, true, false]
+        );
+
+        // 23:00 UTC on Nov 2 is already Nov 3 in UTC+9, and morning there
+        let local = ThemeConfig {
+            utc_offset_minutes: 540,
+            ..ThemeConfig::default()
+        };
+        let choice = local.pick(at(HALLOWEEN + 2 * 86_400 + 23 * 3600));
+        assert!(!choice.overdrive);
+        assert!(!choice.dim);
+
+        let pinned = ThemeConfig {
+            season: Season::Plain,
+            night: Night::Dim,
+            ..ThemeConfig::default()
+        };
+        assert_eq!(
+            pinned.pick(at(HALLOWEEN + noon)),
+            ThemeChoice {
+                overdrive: false,
+                dim: true
+            }
+        );
+    }
+
+    #[test]
+    fn test_is_night() {
+        assert!(is_night(20, 20, 7));
+        assert!(is_night(3, 20, 7));
+        assert!(!is_night(7, 20, 7));
+        assert!(is_night(1, 0, 6));
+        assert!(!is_night(6, 0, 6));
+        assert!(!is_night(5, 5, 5));
+    }
+
+    #[test]
+    fn test_schedule_reports_changes() {
+        let start = Instant::now();
+        let mut schedule = ThemeSchedule::new(ThemeConfig::default());
+        let evening = HALLOWEEN + 19 * 3600;
+
+        assert_eq!(
+            schedule.poll(start, at(evening)),
+            ThemeChange {
+                overdrive: Some(true),
+                dim: Some(false)
+            }
+        );
+        // Checked again too soon
+        let soon = start + THEME_CHECK_INTERVAL / 2;
+        assert_eq!(
+            schedule.poll(soon, at(evening + 3600)),
+            ThemeChange::default()
+        );
+        // Night fell; the season is unchanged so Overdrive is left alone
+        let later = start + THEME_CHECK_INTERVAL;
+        assert_eq!(
+            schedule.poll(later, at(evening + 3600)),
+            ThemeChange {
+                overdrive: None,
+                dim: Some(true)
+            }
+        );
+    }
+}
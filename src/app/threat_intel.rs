@@ -0,0 +1,265 @@
+// Threat-intel blocklist lookups
+//
+// Optional module enabled by the `[threat_intel]` config section. Remote
+// addresses are matched against a local blocklist (one IP/CIDR per line,
+// e.g. Spamhaus DROP or FireHOL netsets); matches are shown as "haunted"
+// endpoints and logged as Critical Grimoire events.
+//
+// When `feed_url` is set the list is downloaded with curl(1) in a background
+// thread every `refresh_hours` (as the `[probes]` budget allows, see
+// probes.rs) and cached at the blocklist path. All lookups
+// are done offline against the cached file, which is reloaded whenever its
+// modification time changes.
+
+use super::probes::{ProbeKind, ProbeLimiter};
+use crate::net::cidr::{parse_range_list, Cidr};
+use serde::Deserialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant, SystemTime};
+
+/// Default feed refresh interval
+pub const DEFAULT_REFRESH_HOURS: u64 = 24;
+
+/// `[threat_intel]` section of config.toml
+#[derive(Debug, Clone, Default, Deserialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct ThreatIntelConfig {
+    /// Local blocklist file (also the cache for `feed_url`)
+    pub blocklist: Option<PathBuf>,
+    /// Feed downloaded periodically into the blocklist file
+    pub feed_url: Option<String>,
+    /// Hours between feed downloads (default 24)
+    pub refresh_hours: Option<u64>,
+}
+
+impl ThreatIntelConfig {
+    /// Whether any blocklist source is configured
+    pub fn is_enabled(&self) -> bool {
+        self.blocklist.is_some() || self.feed_url.is_some()
+    }
+}
+
+/// Something worth a Grimoire event that happened while polling
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ThreatIntelUpdate {
+    /// The blocklist was (re)loaded with this many entries
+    Loaded { entries: usize, skipped: usize },
+    /// Reading the list or downloading the feed failed
+    Error(String),
+}
+
+/// Loaded blocklist plus feed download state
+#[derive(Debug)]
+pub struct ThreatIntel {
+    path: PathBuf,
+    feed_url: Option<String>,
+    refresh_interval: Duration,
+    entries: Vec<(Cidr, String)>,
+    /// Modification time of the file the entries were loaded from
+    loaded_modified: Option<SystemTime>,
+    last_fetch: Option<Instant>,
+    fetch: Option<JoinHandle<Result<(), String>>>,
+}
+
+impl ThreatIntel {
+    /// Create from config; returns None when the section is not set
+    ///
+    /// Nothing is read until the first `poll`.
+    pub fn from_config(config: &ThreatIntelConfig) -> Option<Self> {
+        if !config.is_enabled() {
+            return None;
+        }
+        let path = config
+            .blocklist
+            .clone()
+            .or_else(default_cache_path)
+            .unwrap_or_else(|| PathBuf::from("ntomb-blocklist.txt"));
+        let hours = config.refresh_hours.unwrap_or(DEFAULT_REFRESH_HOURS).max(1);
+        Some(Self {
+            path,
+            feed_url: config.feed_url.clone(),
+            refresh_interval: Duration::from_secs(hours * 3600),
+            entries: Vec::new(),
+            loaded_modified: None,
+            last_fetch: None,
+            fetch: None,
+        })
+    }
+
+    /// Path of the blocklist file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Number of loaded entries
+    #[allow(dead_code)]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Note of the first entry containing `ip` ("" when the list has none)
+    pub fn lookup(&self, ip: &str) -> Option<&str> {
+        let ip = ip.parse().ok()?;
+        self.entries
+            .iter()
+            .find(|(cidr, _)| cidr.contains(&ip))
+            .map(|(_, note)| note.as_str())
+    }
+
+    /// Finish/start feed downloads and reload the file when it changed
+    ///
+    /// Called on every data refresh; cheap when nothing changed (one stat).
+    /// A due download waits until `probes` allows it.
+    pub fn poll(&mut self, probes: &mut ProbeLimiter) -> Vec<ThreatIntelUpdate> {
+        let mut updates = Vec::new();
+
+        if self.fetch.as_ref().is_some_and(|f| f.is_finished()) {
+            if let Some(Err(e)) = self.fetch.take().and_then(|f| f.join().ok()) {
+                updates.push(ThreatIntelUpdate::Error(e));
+            }
+        }
+
+        if let Some(ref url) = self.feed_url {
+            let due = self
+                .last_fetch
+                .map_or(true, |t| t.elapsed() >= self.refresh_interval);
+            if due && self.fetch.is_none() && probes.allow(ProbeKind::Feed, Instant::now()) {
+                self.last_fetch = Some(Instant::now());
+                let (url, path) = (url.clone(), self.path.clone());
+                self.fetch = Some(thread::spawn(move || download_feed(&url, &path)));
+            }
+        }
+
+        match fs::metadata(&self.path).and_then(|m| m.modified()) {
+            Ok(modified) if Some(modified) != self.loaded_modified => {
+                match fs::read_to_string(&self.path) {
+                    Ok(content) => {
+                        let (entries, skipped) = parse_range_list(&content);
+                        self.entries = entries;
+                        self.loaded_modified = Some(modified);
+                        updates.push(ThreatIntelUpdate::Loaded {
+                            entries: self.entries.len(),
+                            skipped,
+                        });
+                    }
+                    Err(e) => {
+                        self.loaded_modified = Some(modified);
+                        updates.push(ThreatIntelUpdate::Error(format!(
+                            "cannot read {}: {}",
+                            self.path.display(),
+                            e
+                        )));
+                    }
+                }
+            }
+            Ok(_) => {}
+            // A feed's cache file appears after the first download
+            Err(_) if self.feed_url.is_some() => {}
+            Err(e) if self.loaded_modified.is_none() && self.entries.is_empty() => {
+                // Report a missing file once, then stay quiet
+                self.loaded_modified = Some(SystemTime::UNIX_EPOCH);
+                updates.push(ThreatIntelUpdate::Error(format!(
+                    "cannot read {}: {}",
+                    self.path.display(),
+                    e
+                )));
+            }
+            Err(_) => {}
+        }
+
+        updates
+    }
+}
+
+/// $XDG_CACHE_HOME/ntomb/blocklist.txt or ~/.cache/ntomb/blocklist.txt
+fn default_cache_path() -> Option<PathBuf> {
+    let base = std::env::var_os("XDG_CACHE_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".cache")))?;
+    Some(base.join("ntomb").join("blocklist.txt"))
+}
+
+/// Download `url` into `path` via a temporary file, so readers never see
+/// a partial list
+fn download_feed(url: &str, path: &Path) -> Result<(), String> {
+    if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+        fs::create_dir_all(dir).map_err(|e| format!("cannot create {}: {}", dir.display(), e))?;
+    }
+    let tmp = path.with_extension("download");
+    let output = Command::new("curl")
+        .args(["-fsSL", "--max-time", "60", "-o"])
+        .arg(&tmp)
+        .arg(url)
+        .output()
+        .map_err(|e| format!("cannot run curl: {}", e))?;
+    if !output.status.success() {
+        let _ = fs::remove_file(&tmp);
+        return Err(format!(
+            "feed download failed ({}): {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    fs::rename(&tmp, path).map_err(|e| format!("cannot update {}: {}", path.display(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_list(name: &str, content: &str) -> PathBuf {
+        let path =
+            std::env::temp_dir().join(format!("ntomb-threat-{}-{}.txt", name, std::process::id()));
+        fs::write(&path, content).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_disabled_without_config() {
+        assert!(ThreatIntel::from_config(&ThreatIntelConfig::default()).is_none());
+    }
+
+    #[test]
+    fn test_load_and_lookup() {
+        let path = temp_list("lookup", "198.51.100.0/24 ; SBL1\n203.0.113.7\n");
+        let mut intel = ThreatIntel::from_config(&ThreatIntelConfig {
+            blocklist: Some(path.clone()),
+            ..Default::default()
+        })
+        .unwrap();
+
+        assert_eq!(
+            intel.poll(&mut ProbeLimiter::default()),
+            vec![ThreatIntelUpdate::Loaded {
+                entries: 2,
+                skipped: 0
+            }]
+        );
+        assert_eq!(intel.lookup("198.51.100.9"), Some("SBL1"));
+        assert_eq!(intel.lookup("203.0.113.7"), Some(""));
+        assert_eq!(intel.lookup("8.8.8.8"), None);
+
+        // Unchanged file: nothing to report
+        assert!(intel.poll(&mut ProbeLimiter::default()).is_empty());
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_missing_file_reported_once() {
+        let mut intel = ThreatIntel::from_config(&ThreatIntelConfig {
+            blocklist: Some(PathBuf::from("/nonexistent/ntomb/blocklist.txt")),
+            ..Default::default()
+        })
+        .unwrap();
+        assert!(matches!(
+            intel.poll(&mut ProbeLimiter::default()).as_slice(),
+            [ThreatIntelUpdate::Error(_)]
+        ));
+        assert!(intel.poll(&mut ProbeLimiter::default()).is_empty());
+        assert_eq!(intel.len(), 0);
+    }
+}
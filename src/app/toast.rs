@@ -0,0 +1,118 @@
+// Toast notifications
+//
+// Short-lived feedback drawn over the status bar: confirmations ("Copied
+// to clipboard"), hints for keys that need a selection, and problems such
+// as collection failures. Toasts stack newest first and each one expires
+// on its own; errors stay up longer than the rest.
+
+use super::config::{ERROR_TOAST_DURATION, MAX_TOASTS, TOAST_DURATION};
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// How a toast is colored, and how long it stays by default
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ToastSeverity {
+    Success,
+    Info,
+    Warning,
+    Error,
+}
+
+impl ToastSeverity {
+    /// Lifetime of a toast of this severity
+    pub fn ttl(self) -> Duration {
+        match self {
+            ToastSeverity::Error => ERROR_TOAST_DURATION,
+            _ => TOAST_DURATION,
+        }
+    }
+
+    /// Symbol shown before the text
+    pub fn icon(self) -> &'static str {
+        match self {
+            ToastSeverity::Success => "✔",
+            ToastSeverity::Info => "ℹ",
+            ToastSeverity::Warning => "⚠",
+            ToastSeverity::Error => "✖",
+        }
+    }
+}
+
+/// One notification
+#[derive(Debug, Clone)]
+pub struct Toast {
+    pub text: String,
+    pub severity: ToastSeverity,
+    pub expires_at: Instant,
+}
+
+/// Visible toasts, oldest first
+#[derive(Debug, Clone, Default)]
+pub struct ToastQueue {
+    toasts: VecDeque<Toast>,
+}
+
+impl ToastQueue {
+    /// Show `text` for `ttl`; repeating the newest toast restarts its
+    /// timer instead of stacking a copy, and the oldest is dropped when
+    /// MAX_TOASTS are up
+    pub fn push(&mut self, text: impl Into<String>, severity: ToastSeverity, ttl: Duration) {
+        let text = text.into();
+        let expires_at = Instant::now() + ttl;
+        if let Some(newest) = self
+            .toasts
+            .back_mut()
+            .filter(|t| t.text == text && t.severity == severity)
+        {
+            newest.expires_at = expires_at;
+            return;
+        }
+        if self.toasts.len() >= MAX_TOASTS {
+            self.toasts.pop_front();
+        }
+        self.toasts.push_back(Toast {
+            text,
+            severity,
+            expires_at,
+        });
+    }
+
+    /// Drop toasts expired at `now`; true when any was dropped
+    pub fn expire(&mut self, now: Instant) -> bool {
+        let before = self.toasts.len();
+        self.toasts.retain(|t| t.expires_at > now);
+        self.toasts.len() != before
+    }
+
+    /// Iterate toasts from newest to oldest
+    pub fn newest_first(&self) -> impl Iterator<Item = &Toast> {
+        self.toasts.iter().rev()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_toast_queue() {
+        let mut queue = ToastQueue::default();
+        let second = Duration::from_secs(1);
+        queue.push("Copied", ToastSeverity::Success, second);
+        queue.push("Copied", ToastSeverity::Success, second);
+        assert_eq!(queue.newest_first().count(), 1);
+
+        queue.push("Cannot write", ToastSeverity::Error, 10 * second);
+        queue.push("a", ToastSeverity::Info, second);
+        queue.push("b", ToastSeverity::Info, second);
+        let texts: Vec<_> = queue.newest_first().map(|t| t.text.as_str()).collect();
+        assert_eq!(texts, vec!["b", "a", "Cannot write"]);
+
+        // Short-lived toasts go first; the error outlives them
+        assert!(!queue.expire(Instant::now()));
+        assert!(queue.expire(Instant::now() + 2 * second));
+        assert_eq!(queue.newest_first().next().unwrap().text, "Cannot write");
+        assert!(queue.expire(Instant::now() + 11 * second));
+        assert_eq!(queue.newest_first().count(), 0);
+    }
+}
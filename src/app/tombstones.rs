@@ -0,0 +1,179 @@
+// Short-lived connection capture
+//
+// A connection that opens and closes within one data interval never shows
+// up in a snapshot. With `[tombstones]`, ntomb also follows the kernel's
+// socket destroy events (`ss -tnE`, root only) and records each destroyed
+// connection that no snapshot saw as a tombstone:
+//
+// ```toml
+// [tombstones]
+// enabled = true   # default false; needs root (CAP_NET_ADMIN)
+// keep = 500       # tombstones remembered (default 500)
+// ```
+//
+// The endpoint detail popup lists an endpoint's tombstones, and the first
+// short-lived connection to each remote address is logged in the Grimoire.
+// The kernel reports no owner for a destroyed socket, so tombstones carry
+// none.
+
+use crate::net::ss_events::DestroyWatcher;
+use crate::net::Connection;
+use serde::Deserialize;
+use std::collections::{HashSet, VecDeque};
+use std::io;
+use std::sync::Arc;
+use std::time::SystemTime;
+
+/// Tombstones kept unless `keep` says otherwise
+const DEFAULT_KEEP: usize = 500;
+
+/// `[tombstones]` section of config.toml
+#[derive(Debug, Clone, Default, Deserialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct TombstonesConfig {
+    /// Follow socket destroy events (default false)
+    pub enabled: Option<bool>,
+    /// Newest tombstones kept (default 500)
+    pub keep: Option<usize>,
+}
+
+/// A connection that closed between two snapshots
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tombstone {
+    /// When its destroy event came in
+    pub closed_at: SystemTime,
+    pub conn: Connection,
+}
+
+/// Destroy event watcher and the tombstones it produced, oldest first
+pub struct Tombstones {
+    watcher: Option<DestroyWatcher>,
+    log: VecDeque<Tombstone>,
+    keep: usize,
+    /// Remote addresses already logged in the Grimoire
+    reported: HashSet<Arc<str>>,
+}
+
+impl Tombstones {
+    /// Tombstones as configured, not yet following events; None when
+    /// disabled
+    pub fn from_config(config: &TombstonesConfig) -> Result<Option<Self>, String> {
+        if !config.enabled.unwrap_or(false) {
+            return Ok(None);
+        }
+        let keep = match config.keep.unwrap_or(DEFAULT_KEEP) {
+            0 => return Err("keep must be at least 1".to_string()),
+            keep => keep,
+        };
+        Ok(Some(Self::new(keep)))
+    }
+
+    fn new(keep: usize) -> Self {
+        Self {
+            watcher: None,
+            log: VecDeque::new(),
+            keep,
+            reported: HashSet::new(),
+        }
+    }
+
+    /// Start following socket destroy events
+    pub fn start(&mut self) -> io::Result<()> {
+        self.watcher = Some(DestroyWatcher::spawn()?);
+        Ok(())
+    }
+
+    /// Connections destroyed since the last call, and why the watcher
+    /// stopped if it just did
+    pub fn drain(&mut self) -> (Vec<Connection>, Option<String>) {
+        let Some(ref mut watcher) = self.watcher else {
+            return (Vec::new(), None);
+        };
+        let destroyed = watcher.drain();
+        let stopped = watcher.exit_status();
+        if stopped.is_some() {
+            self.watcher = None;
+        }
+        (destroyed, stopped)
+    }
+
+    /// Record the `destroyed` connections no snapshot `seen`, closed at
+    /// `now`; returns those to a remote address not logged before
+    pub fn record(
+        &mut self,
+        destroyed: Vec<Connection>,
+        seen: impl Fn(&Connection) -> bool,
+        now: SystemTime,
+    ) -> Vec<Tombstone> {
+        let mut first = Vec::new();
+        for conn in destroyed.into_iter().filter(|conn| !seen(conn)) {
+            let tombstone = Tombstone {
+                closed_at: now,
+                conn,
+            };
+            if self.reported.insert(tombstone.conn.remote_addr.clone()) {
+                first.push(tombstone.clone());
+            }
+            if self.log.len() >= self.keep {
+                self.log.pop_front();
+            }
+            self.log.push_back(tombstone);
+        }
+        first
+    }
+
+    /// Tombstones of connections to `remote_addr`, oldest first
+    pub fn to_remote<'a>(&'a self, remote_addr: &'a str) -> impl Iterator<Item = &'a Tombstone> {
+        self.log
+            .iter()
+            .filter(move |tombstone| &*tombstone.conn.remote_addr == remote_addr)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    fn conn(local_port: u16, remote_addr: &str) -> Connection {
+        Connection {
+            local_addr: "10.0.0.5".into(),
+            local_port,
+            remote_addr: remote_addr.into(),
+            remote_port: 443,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_records_unseen_connections() {
+        let config: TombstonesConfig = toml::from_str("enabled = true\nkeep = 2").unwrap();
+        let mut tombstones = Tombstones::from_config(&config).unwrap().unwrap();
+        let now = UNIX_EPOCH + Duration::from_secs(1_760_000_000);
+
+        // The connection on 50000 was in a snapshot: not a tombstone
+        let seen = |conn: &Connection| conn.local_port == 50000;
+        let destroyed = vec![
+            conn(50000, "93.184.216.34"),
+            conn(50001, "93.184.216.34"),
+            conn(50002, "93.184.216.34"),
+        ];
+        let first = tombstones.record(destroyed, seen, now);
+        assert_eq!(first.len(), 1);
+        assert_eq!(first[0].conn.local_port, 50001);
+
+        // Only the newest `keep` are kept; a known address isn't logged again
+        let first = tombstones.record(vec![conn(50003, "93.184.216.34")], seen, now);
+        assert!(first.is_empty());
+        let ports: Vec<u16> = tombstones
+            .to_remote("93.184.216.34")
+            .map(|t| t.conn.local_port)
+            .collect();
+        assert_eq!(ports, [50002, 50003]);
+
+        let off = TombstonesConfig::default();
+        assert!(Tombstones::from_config(&off).unwrap().is_none());
+        let bad: TombstonesConfig = toml::from_str("enabled = true\nkeep = 0").unwrap();
+        assert!(Tombstones::from_config(&bad).is_err());
+    }
+}
@@ -0,0 +1,73 @@
+// Connection count trends
+//
+// Whether a count is rising, falling or holding steady over the last few
+// recorded snapshots (TREND_WINDOW), shown as a small arrow next to the
+// total in the banner and next to each process in the Séance grid. The
+// direction comes from a least-squares line through the counts, so a
+// single noisy refresh doesn't flip it.
+
+use super::config::{TREND_MIN_CHANGE, TREND_MIN_CHANGE_RATIO};
+
+/// Direction of a count over the trend window
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Trend {
+    Rising,
+    Falling,
+    Stable,
+}
+
+impl Trend {
+    /// Direction of `counts` (oldest first): the change along the fitted
+    /// line must reach TREND_MIN_CHANGE and TREND_MIN_CHANGE_RATIO of the
+    /// mean count to count as rising or falling
+    pub fn of(counts: &[usize]) -> Self {
+        let n = counts.len();
+        if n < 2 {
+            return Trend::Stable;
+        }
+        let n_f = n as f64;
+        let mean_x = (n_f - 1.0) / 2.0;
+        let mean_y = counts.iter().sum::<usize>() as f64 / n_f;
+        let (mut cov, mut var) = (0.0, 0.0);
+        for (x, &y) in counts.iter().enumerate() {
+            let dx = x as f64 - mean_x;
+            cov += dx * (y as f64 - mean_y);
+            var += dx * dx;
+        }
+        let change = cov / var * (n_f - 1.0);
+        let threshold = (TREND_MIN_CHANGE as f64).max(mean_y * TREND_MIN_CHANGE_RATIO);
+        if change >= threshold {
+            Trend::Rising
+        } else if change <= -threshold {
+            Trend::Falling
+        } else {
+            Trend::Stable
+        }
+    }
+
+    pub fn arrow(self) -> &'static str {
+        match self {
+            Trend::Rising => "↑",
+            Trend::Falling => "↓",
+            Trend::Stable => "→",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trend() {
+        assert_eq!(Trend::of(&[]), Trend::Stable);
+        assert_eq!(Trend::of(&[40]), Trend::Stable);
+        assert_eq!(Trend::of(&[10, 12, 15, 18]), Trend::Rising);
+        assert_eq!(Trend::of(&[18, 15, 12, 10]), Trend::Falling);
+        // One blip in an otherwise flat series
+        assert_eq!(Trend::of(&[100, 100, 104, 100, 100]), Trend::Stable);
+        // Small counts need an absolute change too
+        assert_eq!(Trend::of(&[1, 1, 2]), Trend::Stable);
+        assert_eq!(Trend::of(&[0, 1, 2, 3]), Trend::Rising);
+    }
+}
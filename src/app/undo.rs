@@ -0,0 +1,91 @@
+// View-state undo
+//
+// Filters, the Graveyard mode, grouping, the split and diff views and the
+// endpoint page are "where am I looking" state that a single mistyped key
+// can throw away during an incident. Every key that changes any of them
+// records the previous view, and `u` steps back through the last
+// UNDO_DEPTH of them. Data (tags, pins, watches, baselines) is not part of
+// the view and is never undone.
+
+use super::config::{GraveyardMode, UNDO_DEPTH};
+use super::filter::ConnectionFilter;
+use super::grouping::EndpointGrouping;
+use super::tags::Tag;
+use std::collections::VecDeque;
+
+/// Everything `u` restores
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ViewState {
+    pub connection_filter: ConnectionFilter,
+    pub tag_filter: Option<Tag>,
+    pub graveyard_mode: GraveyardMode,
+    pub selected_process_pid: Option<i32>,
+    pub include_child_processes: bool,
+    pub grouping: EndpointGrouping,
+    pub split_view: bool,
+    pub seance: bool,
+    pub diff_mode: bool,
+    pub endpoint_page: isize,
+}
+
+/// Previous views, newest last
+#[derive(Debug, Clone, Default)]
+pub struct UndoStack {
+    views: VecDeque<ViewState>,
+}
+
+impl UndoStack {
+    /// Remember `view`, dropping the oldest once UNDO_DEPTH are kept
+    pub fn push(&mut self, view: ViewState) {
+        if self.views.len() == UNDO_DEPTH {
+            self.views.pop_front();
+        }
+        self.views.push_back(view);
+    }
+
+    /// The view before the most recent change
+    pub fn pop(&mut self) -> Option<ViewState> {
+        self.views.pop_back()
+    }
+
+    pub fn len(&self) -> usize {
+        self.views.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn view(endpoint_page: isize) -> ViewState {
+        ViewState {
+            connection_filter: ConnectionFilter::default(),
+            tag_filter: None,
+            graveyard_mode: GraveyardMode::Host,
+            selected_process_pid: None,
+            include_child_processes: false,
+            grouping: EndpointGrouping::Off,
+            split_view: false,
+            seance: false,
+            diff_mode: false,
+            endpoint_page,
+        }
+    }
+
+    #[test]
+    fn test_undo_stack_is_bounded() {
+        let mut stack = UndoStack::default();
+        for page in 0..UNDO_DEPTH as isize + 5 {
+            stack.push(view(page));
+        }
+        assert_eq!(stack.len(), UNDO_DEPTH);
+        assert_eq!(stack.pop(), Some(view(UNDO_DEPTH as isize + 4)));
+
+        // The oldest views were dropped
+        let mut last = None;
+        while let Some(view) = stack.pop() {
+            last = Some(view);
+        }
+        assert_eq!(last, Some(view(5)));
+    }
+}
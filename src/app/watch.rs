@@ -0,0 +1,309 @@
+// Watch expressions
+//
+// A watch counts the connections matching a filter expression and alerts
+// when the count crosses a threshold:
+//
+//   count(state == established && proc == "postgres") > 90
+//
+// Watches come from config.toml or the `w` prompt and are evaluated on every
+// data refresh. Crossing the threshold logs a Grimoire event (and optionally
+// a desktop notification); falling back logs an Info event.
+//
+// ```toml
+// [[watches]]
+// name = "pg-conns"
+// expr = 'count(state == established && proc == "postgres") > 90'
+// severity = "critical"   # info, warning (default) or critical
+// notify = true           # also send a desktop notification
+// ```
+
+use super::events::EventSeverity;
+use super::filter_expr::FilterExpr;
+use crate::net::Connection;
+use serde::Deserialize;
+use std::fmt;
+
+/// `[[watches]]` entry as written in config.toml
+#[derive(Debug, Clone, Default, Deserialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct WatchConfig {
+    /// Short name for the watch bar (default: the expression)
+    pub name: Option<String>,
+    /// `count(<filter>) <op> <number>`
+    pub expr: String,
+    /// info, warning (default) or critical
+    pub severity: Option<String>,
+    /// Send a desktop notification when the watch fires
+    pub notify: bool,
+}
+
+/// Comparison between the count and the threshold
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Comparison {
+    Greater,
+    GreaterEq,
+    Less,
+    LessEq,
+    Equal,
+    NotEqual,
+}
+
+impl Comparison {
+    fn holds(self, value: u64, threshold: u64) -> bool {
+        match self {
+            Comparison::Greater => value > threshold,
+            Comparison::GreaterEq => value >= threshold,
+            Comparison::Less => value < threshold,
+            Comparison::LessEq => value <= threshold,
+            Comparison::Equal => value == threshold,
+            Comparison::NotEqual => value != threshold,
+        }
+    }
+}
+
+impl fmt::Display for Comparison {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Comparison::Greater => ">",
+            Comparison::GreaterEq => ">=",
+            Comparison::Less => "<",
+            Comparison::LessEq => "<=",
+            Comparison::Equal => "==",
+            Comparison::NotEqual => "!=",
+        })
+    }
+}
+
+/// Threshold crossing reported by `evaluate`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WatchChange {
+    Fired,
+    Cleared,
+}
+
+/// A parsed watch and its latest value
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Watch {
+    pub name: String,
+    /// Expression as typed
+    pub source: String,
+    /// Connections counted (None = all)
+    filter: Option<FilterExpr>,
+    pub comparison: Comparison,
+    pub threshold: u64,
+    pub severity: EventSeverity,
+    pub notify: bool,
+    /// Count from the latest snapshot
+    pub value: u64,
+    /// Whether the condition currently holds
+    pub firing: bool,
+}
+
+impl Watch {
+    /// Parse `count(<filter>) <op> <number>`; `count()` and `count(*)`
+    /// count every connection
+    pub fn parse(input: &str) -> Result<Self, String> {
+        let source = input.trim();
+        let rest = source
+            .get(..5)
+            .filter(|head| head.eq_ignore_ascii_case("count"))
+            .map(|_| source[5..].trim_start())
+            .ok_or_else(|| "watch must start with count(...)".to_string())?;
+        let inner = rest
+            .strip_prefix('(')
+            .ok_or_else(|| "expected '(' after count".to_string())?;
+        let close = matching_paren(inner).ok_or_else(|| "unclosed count(".to_string())?;
+
+        let filter = match inner[..close].trim() {
+            "" | "*" => None,
+            expr => Some(FilterExpr::parse(expr).map_err(|e| format!("count(): {}", e))?),
+        };
+
+        let condition = inner[close + 1..].trim();
+        let (comparison, number) = [
+            (">=", Comparison::GreaterEq),
+            ("<=", Comparison::LessEq),
+            ("==", Comparison::Equal),
+            ("!=", Comparison::NotEqual),
+            (">", Comparison::Greater),
+            ("<", Comparison::Less),
+        ]
+        .iter()
+        .find_map(|(op, comparison)| condition.strip_prefix(op).map(|n| (*comparison, n)))
+        .ok_or_else(|| "expected >, >=, <, <=, == or != after count(...)".to_string())?;
+        let threshold = number
+            .trim()
+            .parse()
+            .map_err(|_| format!("invalid threshold '{}'", number.trim()))?;
+
+        Ok(Self {
+            name: source.to_string(),
+            source: source.to_string(),
+            filter,
+            comparison,
+            threshold,
+            severity: EventSeverity::Warning,
+            notify: false,
+            value: 0,
+            firing: false,
+        })
+    }
+
+    /// Validate a config entry, describing the first problem found
+    pub fn from_config(config: &WatchConfig) -> Result<Self, String> {
+        let label = config.name.as_deref().unwrap_or(&config.expr);
+        let mut watch =
+            Self::parse(&config.expr).map_err(|e| format!("watch '{}': {}", label, e))?;
+        if let Some(ref name) = config.name {
+            watch.name = name.clone();
+        }
+        if let Some(ref severity) = config.severity {
+            watch.severity = EventSeverity::from_name(severity)
+                .ok_or_else(|| format!("watch '{}': unknown severity '{}'", label, severity))?;
+        }
+        watch.notify = config.notify;
+        Ok(watch)
+    }
+
+    /// Count matching connections and report a threshold crossing
+    pub fn evaluate(&mut self, conns: &[Connection]) -> Option<WatchChange> {
+        self.value = conns
+            .iter()
+            .filter(|c| self.filter.as_ref().map_or(true, |f| f.matches(c)))
+            .count() as u64;
+        let firing = self.comparison.holds(self.value, self.threshold);
+        let change = match (self.firing, firing) {
+            (false, true) => Some(WatchChange::Fired),
+            (true, false) => Some(WatchChange::Cleared),
+            _ => None,
+        };
+        self.firing = firing;
+        change
+    }
+
+    /// Whether the watch is firing and counts `conn`; watches counting
+    /// every connection point at no connection in particular
+    pub fn flags(&self, conn: &Connection) -> bool {
+        self.firing && self.filter.as_ref().is_some_and(|f| f.matches(conn))
+    }
+
+    /// Current value against the threshold ("95 > 90")
+    pub fn condition(&self) -> String {
+        format!("{} {} {}", self.value, self.comparison, self.threshold)
+    }
+}
+
+/// Validate all configured watches
+pub fn build_watches(configs: &[WatchConfig]) -> Result<Vec<Watch>, String> {
+    configs.iter().map(Watch::from_config).collect()
+}
+
+/// Byte index of the ')' closing an already opened '(' in `s`
+fn matching_paren(s: &str) -> Option<usize> {
+    let mut depth = 0usize;
+    let mut quote: Option<char> = None;
+    for (i, c) in s.char_indices() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '"') | (None, '\'') => quote = Some(c),
+            (None, '(') => depth += 1,
+            (None, ')') if depth == 0 => return Some(i),
+            (None, ')') => depth -= 1,
+            _ => {}
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::net::ConnectionState;
+
+    fn conn(process: &str, state: ConnectionState) -> Connection {
+        Connection {
+            process_name: Some(process.into()),
+            state,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_parse_watch() {
+        let watch =
+            Watch::parse(r#"count(state==ESTABLISHED && proc=="postgres (main)") > 90"#).unwrap();
+        assert_eq!(watch.comparison, Comparison::Greater);
+        assert_eq!(watch.threshold, 90);
+        assert!(watch.filter.is_some());
+
+        let all = Watch::parse("COUNT(*) >= 1").unwrap();
+        assert_eq!(all.comparison, Comparison::GreaterEq);
+        assert!(all.filter.is_none());
+
+        assert!(Watch::parse("state == listen").is_err());
+        assert!(Watch::parse("count(state == listen > 3").is_err());
+        assert!(Watch::parse("count(state == listen) ~ 3").is_err());
+        assert!(Watch::parse("count(state == listen) > many").is_err());
+        assert!(Watch::parse("count(state ==) > 3").is_err());
+    }
+
+    #[test]
+    fn test_evaluate_fires_and_clears() {
+        let mut watch =
+            Watch::parse("count(proc == postgres && state == established) > 1").unwrap();
+        let mut conns = vec![
+            conn("postgres", ConnectionState::Established),
+            conn("postgres", ConnectionState::Listen),
+            conn("nginx", ConnectionState::Established),
+        ];
+        assert_eq!(watch.evaluate(&conns), None);
+        assert_eq!(watch.value, 1);
+
+        conns.push(conn("postgres", ConnectionState::Established));
+        assert_eq!(watch.evaluate(&conns), Some(WatchChange::Fired));
+        assert_eq!(watch.condition(), "2 > 1");
+        assert_eq!(watch.evaluate(&conns), None);
+
+        conns.pop();
+        assert_eq!(watch.evaluate(&conns), Some(WatchChange::Cleared));
+    }
+
+    #[test]
+    fn test_flags_counted_connections_while_firing() {
+        let mut watch = Watch::parse("count(proc == postgres) > 0").unwrap();
+        let conns = [conn("postgres", ConnectionState::Established)];
+        assert!(!watch.flags(&conns[0]));
+        watch.evaluate(&conns);
+        assert!(watch.flags(&conns[0]));
+        assert!(!watch.flags(&conn("nginx", ConnectionState::Established)));
+
+        // count(*) is about the total, not any one connection
+        let mut total = Watch::parse("count(*) > 0").unwrap();
+        total.evaluate(&conns);
+        assert!(total.firing && !total.flags(&conns[0]));
+    }
+
+    #[test]
+    fn test_from_config() {
+        let watch = Watch::from_config(&WatchConfig {
+            name: Some("pg-conns".to_string()),
+            expr: "count(proc == postgres) > 90".to_string(),
+            severity: Some("critical".to_string()),
+            notify: true,
+        })
+        .unwrap();
+        assert_eq!(watch.name, "pg-conns");
+        assert_eq!(watch.severity, EventSeverity::Critical);
+        assert!(watch.notify);
+
+        let err = Watch::from_config(&WatchConfig {
+            name: Some("bad".to_string()),
+            expr: "count() > 1".to_string(),
+            severity: Some("loud".to_string()),
+            notify: false,
+        })
+        .unwrap_err();
+        assert_eq!(err, "watch 'bad': unknown severity 'loud'");
+    }
+}
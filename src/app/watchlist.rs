@@ -0,0 +1,134 @@
+// Watchlist
+//
+// The things a user explicitly asked to keep an eye on, gathered in one
+// compact panel above the Grimoire: endpoints pinned with `o`, connections
+// tagged with `n` and the watch expressions. Entries stay listed after
+// their connections close, marked as gone, so nothing pinned silently
+// disappears from view.
+
+use super::snapshot::connection_key;
+use super::tags::{ConnectionTags, Tag};
+use super::watch::Watch;
+use crate::net::{Connection, ConnectionState};
+use std::collections::{BTreeSet, HashMap};
+use std::sync::Arc;
+
+/// One row of the watchlist panel
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WatchlistEntry {
+    /// Endpoint pinned with 'o' and its open connections (0 = gone)
+    Pinned { addr: Arc<str>, connections: usize },
+    /// Tagged connection ("1.2.3.4:443") and its state (None = gone)
+    Tagged {
+        target: String,
+        tags: Vec<Tag>,
+        state: Option<ConnectionState>,
+    },
+    /// Watch expression with its current value
+    Watch {
+        name: String,
+        condition: String,
+        firing: bool,
+    },
+}
+
+/// Watchlist rows for `connections`: pinned endpoints, then tagged
+/// connections, then watches
+pub fn build(
+    pinned: &BTreeSet<Arc<str>>,
+    tags: &ConnectionTags,
+    watches: &[Watch],
+    connections: &[Connection],
+) -> Vec<WatchlistEntry> {
+    let mut entries: Vec<WatchlistEntry> = pinned
+        .iter()
+        .map(|addr| WatchlistEntry::Pinned {
+            addr: addr.clone(),
+            connections: connections
+                .iter()
+                .filter(|c| c.state != ConnectionState::Listen && c.remote_addr == *addr)
+                .count(),
+        })
+        .collect();
+
+    let states: HashMap<_, _> = connections
+        .iter()
+        .map(|c| (connection_key(c), c.state))
+        .collect();
+    // Most alarming tags first; tags are stored in a HashMap, so sort to
+    // keep the panel from reshuffling
+    let mut tagged: Vec<_> = tags.iter().collect();
+    tagged.sort_by(|a, b| (a.1, a.0).cmp(&(b.1, b.0)));
+    let tagged = tagged
+        .into_iter()
+        .map(|(key, conn_tags)| WatchlistEntry::Tagged {
+            target: format!("{}:{}", key.2, key.3),
+            tags: conn_tags.iter().copied().collect(),
+            state: states.get(key).copied(),
+        });
+    entries.extend(tagged);
+
+    entries.extend(watches.iter().map(|watch| WatchlistEntry::Watch {
+        name: watch.name.clone(),
+        condition: watch.condition(),
+        firing: watch.firing,
+    }));
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn conn(remote_addr: &str, remote_port: u16) -> Connection {
+        Connection {
+            local_addr: "10.0.0.5".into(),
+            local_port: 51000,
+            remote_addr: remote_addr.into(),
+            remote_port,
+            state: ConnectionState::Established,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_build() {
+        let pinned = BTreeSet::from([Arc::from("93.184.216.34"), Arc::from("203.0.113.9")]);
+        let mut tags = ConnectionTags::default();
+        tags.set(&conn("93.184.216.34", 443), Tag::Suspicious, true);
+        tags.set(&conn("198.51.100.7", 22), Tag::FollowUp, true);
+        let mut watch = Watch::parse("count() > 1").unwrap();
+        let connections = [conn("93.184.216.34", 443), conn("93.184.216.34", 80)];
+        watch.evaluate(&connections);
+
+        let entries = build(&pinned, &tags, &[watch], &connections);
+        assert_eq!(
+            entries,
+            vec![
+                WatchlistEntry::Pinned {
+                    addr: "203.0.113.9".into(),
+                    connections: 0,
+                },
+                WatchlistEntry::Pinned {
+                    addr: "93.184.216.34".into(),
+                    connections: 2,
+                },
+                WatchlistEntry::Tagged {
+                    target: "93.184.216.34:443".into(),
+                    tags: vec![Tag::Suspicious],
+                    state: Some(ConnectionState::Established),
+                },
+                WatchlistEntry::Tagged {
+                    target: "198.51.100.7:22".into(),
+                    tags: vec![Tag::FollowUp],
+                    state: None,
+                },
+                WatchlistEntry::Watch {
+                    name: "count() > 1".into(),
+                    condition: "2 > 1".into(),
+                    firing: true,
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,243 @@
+// Command-line interface
+//
+// Options are parsed once in main and applied to AppState at startup.
+
+use crate::app::filter_expr::FilterExpr;
+use crate::app::profiles::Profile;
+use crate::net::BackendKind;
+use clap::{Parser, Subcommand};
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Network Tomb: Process-centric network visualization
+#[derive(Debug, Parser)]
+#[command(name = "ntomb", version, about)]
+pub struct Cli {
+    /// Connection collection backend: auto, netstat2, sysctl, ss, lsof
+    ///
+    /// `auto` uses the platform default (netstat2, or sysctl on BSD) and
+    /// falls back to ss, then lsof, when it fails or reports no PIDs.
+    #[arg(long, default_value_t = BackendKind::Auto)]
+    pub backend: BackendKind,
+
+    /// Config file (default: ~/.config/ntomb/config.toml)
+    #[arg(long, value_name = "PATH")]
+    pub config: Option<PathBuf>,
+
+    /// UI refresh interval in milliseconds (50-10000)
+    #[arg(long, value_name = "MS")]
+    pub refresh_ms: Option<u64>,
+
+    /// Collect connection data every N UI refreshes (1-100, default 10)
+    #[arg(long, value_name = "N")]
+    pub data_multiplier: Option<u64>,
+
+    /// Append logs to this file (rotated at 10 MiB, three old files kept)
+    #[arg(long, value_name = "PATH")]
+    pub log_file: Option<PathBuf>,
+
+    /// Most verbose level logged: error, warn, info, debug, trace
+    /// (`L` in the TUI cycles it while running)
+    #[arg(long, value_name = "LEVEL", default_value_t = tracing::Level::INFO, requires = "log_file")]
+    pub log_level: tracing::Level,
+
+    /// Only show connections matching a filter expression
+    ///
+    /// Example: "state == established && remote in 10.0.0.0/8 && port != 22"
+    #[arg(long, value_name = "EXPR")]
+    pub filter: Option<FilterExpr>,
+
+    /// Start with a preset of filter, panels, refresh rate and look:
+    /// security-audit, latency, minimal, demo (--filter, --refresh-ms and
+    /// --data-multiplier still win)
+    #[arg(long, value_name = "NAME")]
+    pub profile: Option<Profile>,
+
+    /// Drive animations from a frame counter instead of the clock, so
+    /// recordings and snapshot tests replay frame-for-frame (also keeps the
+    /// animation quality fixed and ignores the date/time theme schedule)
+    #[arg(long)]
+    pub deterministic: bool,
+
+    /// Print connections as JSON lines instead of starting the TUI
+    #[arg(long)]
+    pub json: bool,
+
+    /// With --json: keep polling and print one line per open, close or
+    /// state change
+    #[arg(long, requires = "json")]
+    pub watch: bool,
+
+    /// Print a one-line summary for tmux or desktop bars instead of
+    /// starting the TUI
+    #[arg(long, conflicts_with = "json")]
+    pub statusline: bool,
+
+    /// With --statusline: print a fresh line every N seconds
+    #[arg(
+        long,
+        value_name = "SECS",
+        requires = "statusline",
+        value_parser = clap::value_parser!(u64).range(1..=3600)
+    )]
+    pub interval: Option<u64>,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+/// Modes that replace the TUI
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Serve connections, processes and events as read-only JSON over HTTP
+    Serve {
+        /// Address to listen on (loopback by default)
+        #[arg(long, default_value = "127.0.0.1:8686")]
+        listen: SocketAddr,
+
+        /// Require this bearer token on every request (mandatory off
+        /// loopback); set it in the environment to keep it out of the
+        /// process list
+        #[arg(long, env = "NTOMB_API_TOKEN", hide_env_values = true)]
+        token: Option<String>,
+
+        /// Also accept WebSocket connections from pages of this origin
+        /// ("https://dash.example:8443"); repeatable
+        #[arg(long = "allow-origin", value_name = "ORIGIN")]
+        allow_origins: Vec<String>,
+    },
+    /// Print Grimoire events kept in the `[history]` file
+    Events {
+        /// Only events from the last "30m", "2h", "7d", ... (default: all)
+        #[arg(long, value_name = "AGE", value_parser = crate::app::history::parse_duration)]
+        since: Option<Duration>,
+
+        /// Print the stored JSON lines instead of one readable line each
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_backend() {
+        let cli = Cli::try_parse_from(["ntomb", "--backend", "lsof"]).unwrap();
+        assert_eq!(cli.backend, BackendKind::Lsof);
+
+        let cli = Cli::try_parse_from(["ntomb"]).unwrap();
+        assert_eq!(cli.backend, BackendKind::Auto);
+
+        assert!(Cli::try_parse_from(["ntomb", "--backend", "bogus"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_refresh_overrides() {
+        let cli = Cli::try_parse_from(["ntomb", "--refresh-ms", "250", "--data-multiplier", "4"])
+            .unwrap();
+        assert_eq!(cli.refresh_ms, Some(250));
+        assert_eq!(cli.data_multiplier, Some(4));
+    }
+
+    #[test]
+    fn test_parse_deterministic() {
+        assert!(
+            Cli::try_parse_from(["ntomb", "--deterministic"])
+                .unwrap()
+                .deterministic
+        );
+        assert!(!Cli::try_parse_from(["ntomb"]).unwrap().deterministic);
+    }
+
+    #[test]
+    fn test_parse_filter() {
+        let cli = Cli::try_parse_from(["ntomb", "--filter", "port == 22"]).unwrap();
+        assert_eq!(cli.filter.unwrap().source(), "port == 22");
+
+        assert!(Cli::try_parse_from(["ntomb", "--filter", "port =="]).is_err());
+    }
+
+    #[test]
+    fn test_parse_json_watch() {
+        let cli = Cli::try_parse_from(["ntomb", "--json", "--watch"]).unwrap();
+        assert!(cli.json && cli.watch);
+
+        assert!(Cli::try_parse_from(["ntomb", "--watch"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_logging() {
+        let cli = Cli::try_parse_from([
+            "ntomb",
+            "--log-file",
+            "/tmp/ntomb.log",
+            "--log-level",
+            "debug",
+        ])
+        .unwrap();
+        assert_eq!(
+            cli.log_file.as_deref(),
+            Some(std::path::Path::new("/tmp/ntomb.log"))
+        );
+        assert_eq!(cli.log_level, tracing::Level::DEBUG);
+
+        assert!(Cli::try_parse_from(["ntomb", "--log-level", "debug"]).is_err());
+        assert!(Cli::try_parse_from(["ntomb", "--log-file", "x", "--log-level", "loud"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_statusline() {
+        let cli = Cli::try_parse_from(["ntomb", "--statusline", "--interval", "5"]).unwrap();
+        assert!(cli.statusline);
+        assert_eq!(cli.interval, Some(5));
+
+        assert!(Cli::try_parse_from(["ntomb", "--interval", "5"]).is_err());
+        assert!(Cli::try_parse_from(["ntomb", "--statusline", "--interval", "0"]).is_err());
+        assert!(Cli::try_parse_from(["ntomb", "--statusline", "--json"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_serve() {
+        let cli = Cli::try_parse_from(["ntomb", "serve"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Some(Command::Serve { listen, token: None, .. }) if listen.to_string() == "127.0.0.1:8686"
+        ));
+        let cli = Cli::try_parse_from([
+            "ntomb",
+            "serve",
+            "--listen",
+            "0.0.0.0:9000",
+            "--token",
+            "s3cret",
+        ])
+        .unwrap();
+        assert!(matches!(
+            cli.command,
+            Some(Command::Serve { listen, token: Some(token), .. })
+                if listen.port() == 9000 && token == "s3cret"
+        ));
+        assert!(Cli::try_parse_from(["ntomb", "serve", "--listen", "localhost"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_events() {
+        let cli = Cli::try_parse_from(["ntomb", "events", "--since", "2h", "--json"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Some(Command::Events { since: Some(since), json: true }) if since.as_secs() == 7200
+        ));
+        let cli = Cli::try_parse_from(["ntomb", "events"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Some(Command::Events {
+                since: None,
+                json: false
+            })
+        ));
+        assert!(Cli::try_parse_from(["ntomb", "events", "--since", "soon"]).is_err());
+    }
+}
@@ -0,0 +1,250 @@
+// File logging
+//
+// `--log-file <path>` installs a `tracing` subscriber that appends one line
+// per event to a file, never to the terminal the TUI draws on:
+//
+// ```text
+// 2025-10-31T23:59:59Z WARN ntomb::procfs: Cannot read /proc directory error="Permission denied"
+// ```
+//
+// The file is rotated by size (ntomb.log → ntomb.log.1 → ... →
+// ntomb.log.3). `--log-level` sets the starting verbosity and `L` in the
+// TUI cycles it while running, so debug output can be switched on just
+// for the moment a problem shows up.
+
+use crate::app::events::rfc3339;
+use std::fmt::{self, Write as _};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::SystemTime;
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::subscriber::Interest;
+use tracing::{Event, Level, Metadata, Subscriber};
+
+/// Size at which the log file is rotated
+const MAX_LOG_BYTES: u64 = 10 * 1024 * 1024;
+
+/// Rotated files kept next to the active one
+const ROTATED_FILES: usize = 3;
+
+/// Levels `L` cycles through, least verbose first
+const LEVELS: [Level; 5] = [
+    Level::ERROR,
+    Level::WARN,
+    Level::INFO,
+    Level::DEBUG,
+    Level::TRACE,
+];
+
+/// Index into LEVELS of the most verbose level written
+static LEVEL: AtomicUsize = AtomicUsize::new(2);
+
+/// Whether a log file is installed (otherwise `L` has nothing to change)
+static INSTALLED: AtomicBool = AtomicBool::new(false);
+
+/// Start logging to `path` at `level`
+pub fn init(path: &Path, level: Level) -> io::Result<()> {
+    let file = LogFile::open(path.to_path_buf())?;
+    set_level(level);
+    let subscriber = FileSubscriber {
+        file: Mutex::new(file),
+        next_span: AtomicU64::new(1),
+    };
+    tracing::subscriber::set_global_default(subscriber).map_err(io::Error::other)?;
+    INSTALLED.store(true, Ordering::Relaxed);
+    Ok(())
+}
+
+fn set_level(level: Level) {
+    let index = LEVELS.iter().position(|l| *l == level).unwrap_or(2);
+    LEVEL.store(index, Ordering::Relaxed);
+}
+
+/// Most verbose level currently written
+pub fn level() -> Level {
+    LEVELS[LEVEL.load(Ordering::Relaxed)]
+}
+
+/// Switch to the next more verbose level, wrapping from TRACE to ERROR;
+/// None when no log file is installed
+pub fn cycle_level() -> Option<Level> {
+    if !INSTALLED.load(Ordering::Relaxed) {
+        return None;
+    }
+    let next = (LEVEL.load(Ordering::Relaxed) + 1) % LEVELS.len();
+    LEVEL.store(next, Ordering::Relaxed);
+    Some(LEVELS[next])
+}
+
+/// The active log file and its size so far
+struct LogFile {
+    path: PathBuf,
+    file: File,
+    size: u64,
+}
+
+impl LogFile {
+    fn open(path: PathBuf) -> io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let size = file.metadata()?.len();
+        Ok(Self { path, file, size })
+    }
+
+    /// Append `line`, rotating first when it would overflow the file
+    fn write_line(&mut self, line: &str) -> io::Result<()> {
+        if self.size > 0 && self.size + line.len() as u64 > MAX_LOG_BYTES {
+            self.rotate()?;
+        }
+        self.file.write_all(line.as_bytes())?;
+        self.size += line.len() as u64;
+        Ok(())
+    }
+
+    /// ntomb.log.2 → ntomb.log.3, ntomb.log.1 → ntomb.log.2,
+    /// ntomb.log → ntomb.log.1, then start an empty ntomb.log
+    fn rotate(&mut self) -> io::Result<()> {
+        let rotated = |n: usize| PathBuf::from(format!("{}.{}", self.path.display(), n));
+        for n in (1..ROTATED_FILES).rev() {
+            let _ = fs::rename(rotated(n), rotated(n + 1));
+        }
+        fs::rename(&self.path, rotated(1))?;
+        *self = Self::open(self.path.clone())?;
+        Ok(())
+    }
+}
+
+/// Writes every enabled event to the log file; spans are not tracked
+struct FileSubscriber {
+    file: Mutex<LogFile>,
+    next_span: AtomicU64,
+}
+
+impl Subscriber for FileSubscriber {
+    // The level changes at runtime, so callsites are asked every time
+    // instead of having their interest cached
+    fn register_callsite(&self, _metadata: &'static Metadata<'static>) -> Interest {
+        Interest::sometimes()
+    }
+
+    fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+        *metadata.level() <= level()
+    }
+
+    fn new_span(&self, _span: &Attributes<'_>) -> Id {
+        Id::from_u64(self.next_span.fetch_add(1, Ordering::Relaxed))
+    }
+
+    fn record(&self, _span: &Id, _values: &Record<'_>) {}
+
+    fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+
+    fn event(&self, event: &Event<'_>) {
+        let line = format_event(SystemTime::now(), event);
+        if let Ok(mut file) = self.file.lock() {
+            let _ = file.write_line(&line);
+        }
+    }
+
+    fn enter(&self, _span: &Id) {}
+
+    fn exit(&self, _span: &Id) {}
+}
+
+/// "<time> <LEVEL> <target>: <message> key=value ...\n"
+fn format_event(time: SystemTime, event: &Event<'_>) -> String {
+    let metadata = event.metadata();
+    let mut visitor = LineVisitor::default();
+    event.record(&mut visitor);
+    format!(
+        "{} {} {}: {}{}\n",
+        rfc3339(time),
+        metadata.level(),
+        metadata.target(),
+        visitor.message,
+        visitor.fields
+    )
+}
+
+/// Collects the message and, separately, the other fields as key=value
+#[derive(Default)]
+struct LineVisitor {
+    message: String,
+    fields: String,
+}
+
+impl Visit for LineVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message.push_str(value);
+        } else {
+            let _ = write!(self.fields, " {}={:?}", field.name(), value);
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == "message" {
+            let _ = write!(self.message, "{:?}", value);
+        } else {
+            let _ = write!(self.fields, " {}={:?}", field.name(), value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rotation() {
+        let dir = std::env::temp_dir().join(format!("ntomb-log-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("ntomb.log");
+        let mut file = LogFile::open(path.clone()).unwrap();
+
+        let line = "x".repeat(MAX_LOG_BYTES as usize / 2 + 1);
+        for _ in 0..5 {
+            file.write_line(&line).unwrap();
+        }
+        // One line per file: the active one plus three rotations
+        assert_eq!(fs::metadata(&path).unwrap().len(), line.len() as u64);
+        for n in 1..=ROTATED_FILES {
+            assert!(dir.join(format!("ntomb.log.{}", n)).exists());
+        }
+        assert!(!dir.join("ntomb.log.4").exists());
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_format_event() {
+        struct Capture(Mutex<Vec<String>>);
+        impl Subscriber for Capture {
+            fn enabled(&self, _: &Metadata<'_>) -> bool {
+                true
+            }
+            fn new_span(&self, _: &Attributes<'_>) -> Id {
+                Id::from_u64(1)
+            }
+            fn record(&self, _: &Id, _: &Record<'_>) {}
+            fn record_follows_from(&self, _: &Id, _: &Id) {}
+            fn event(&self, event: &Event<'_>) {
+                let line = format_event(SystemTime::UNIX_EPOCH, event);
+                self.0.lock().unwrap().push(line);
+            }
+            fn enter(&self, _: &Id) {}
+            fn exit(&self, _: &Id) {}
+        }
+
+        let capture = std::sync::Arc::new(Capture(Mutex::new(Vec::new())));
+        tracing::subscriber::with_default(capture.clone(), || {
+            tracing::warn!(pid = 812, error = %"denied", "Cannot read fd");
+        });
+        assert_eq!(
+            capture.0.lock().unwrap()[0],
+            "1970-01-01T00:00:00Z WARN ntomb::logging::tests: Cannot read fd pid=812 error=denied\n"
+        );
+    }
+}
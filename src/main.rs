@@ -1,27 +1,242 @@
 // ntomb - Network Tomb: Process-centric network visualization
 // A Halloween-themed TUI for the Kiroween hackathon
 
+mod api;
 mod app;
+mod cli;
+mod logging;
 mod net;
 mod procfs;
 mod theme;
 mod ui;
 
-use anyhow::Result;
-use app::{event::handle_key_event, AppState};
+use anyhow::{anyhow, Context, Result};
+use app::{
+    config_file::ConfigFile, endpoint_rules, event::handle_key, stream, AppState, RefreshConfig,
+};
+use clap::Parser;
 use crossterm::{
     event::{self, Event},
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
 use ratatui::{backend::CrosstermBackend, Terminal};
-use std::io;
+use std::io::{self, Write};
 
 fn main() -> Result<()> {
+    let cli = cli::Cli::parse();
+
+    // Logs go to a file only: stdout belongs to the TUI
+    if let Some(ref path) = cli.log_file {
+        logging::init(path, cli.log_level)
+            .with_context(|| format!("Cannot open log file {}", path.display()))?;
+    }
+
+    // Reading the event history needs neither a backend nor a terminal
+    if let Some(cli::Command::Events { since, json }) = cli.command {
+        let config = ConfigFile::load(cli.config.as_deref())?;
+        return print_history(&config.history, since, json);
+    }
+
+    // Resolve the backend before touching the terminal so errors print normally
+    let data_source = net::create_source(cli.backend)
+        .with_context(|| format!("Cannot use --backend {}", cli.backend))?;
+
+    // CLI flags override config file values
+    let config = ConfigFile::load(cli.config.as_deref())?;
+    let mut refresh_config = RefreshConfig::with_settings(
+        cli.refresh_ms.or(config.refresh.ui_interval_ms),
+        cli.data_multiplier.or(config.refresh.data_multiplier),
+    );
+    if let Some(ms) = config.refresh.collect_timeout_ms {
+        refresh_config.collect_timeout =
+            std::time::Duration::from_millis(ms.max(app::config::MIN_COLLECT_TIMEOUT_MS));
+    }
+    let ascii_mode = std::env::var_os("NTOMB_ASCII_MODE").is_some();
+    theme::glyphs::init(config.glyphs.build(ascii_mode));
+
+    let endpoint_rules = endpoint_rules::build_rules(&config.endpoint_rules)
+        .map_err(|e| anyhow!(e))
+        .context("Invalid [[endpoint_rules]] in config file")?;
+    let environment_alerts = endpoint_rules::build_environment_alerts(&config.environment_alerts)
+        .map_err(|e| anyhow!(e))
+        .context("Invalid [[environment_alerts]] in config file")?;
+    let cloud_ranges = app::cloud_ranges::CloudRanges::load(&config.cloud_ranges)
+        .map_err(|e| anyhow!(e))
+        .context("Invalid [cloud_ranges] in config file")?;
+    let asn_database = app::asn::AsnDatabase::load(&config.asn)
+        .map_err(|e| anyhow!(e))
+        .context("Invalid [asn] in config file")?;
+    let inventory = app::inventory::Inventory::load(&config.inventory)
+        .map(std::sync::Arc::new)
+        .map_err(|e| anyhow!(e))
+        .context("Invalid [inventory] in config file")?;
+    let anonymizers = app::anonymizers::Anonymizers::load(&config.anonymizers)
+        .map_err(|e| anyhow!(e))
+        .context("Invalid [anonymizers] in config file")?;
+    let watches = app::watch::build_watches(&config.watches)
+        .map_err(|e| anyhow!(e))
+        .context("Invalid [[watches]] in config file")?;
+    let alert_sources = AlertSources {
+        endpoint_rules,
+        environment_alerts,
+        watches,
+    };
+    let alarm = app::alarm::Alarm::from_config(&config.alarm)
+        .map_err(|e| anyhow!(e))
+        .context("Invalid [alarm] in config file")?;
+    let forwarder = app::forward::EventForwarder::from_config(&config.forward)
+        .map_err(|e| anyhow!(e))
+        .context("Invalid [forward] in config file")?;
+    let history = app::history::EventHistory::from_config(&config.history)
+        .map_err(|e| anyhow!(e))
+        .context("Invalid [history] in config file")?;
+    let autosave = app::autosave::SnapshotAutosave::from_config(&config.autosave)
+        .map_err(|e| anyhow!(e))
+        .context("Invalid [autosave] in config file")?;
+    let tombstones = app::tombstones::Tombstones::from_config(&config.tombstones)
+        .map_err(|e| anyhow!(e))
+        .context("Invalid [tombstones] in config file")?;
+    let enrich_cache = app::enrich_cache::EnrichCache::from_config(&config.cache)
+        .map_err(|e| anyhow!(e))
+        .context("Invalid [cache] in config file")?;
+    #[cfg(not(feature = "otlp"))]
+    if config.otlp.endpoint.is_some() {
+        return Err(anyhow!(
+            "[otlp] in config file needs ntomb built with --features otlp"
+        ));
+    }
+    #[cfg(not(feature = "scripting"))]
+    if config.scripting.script.is_some() {
+        return Err(anyhow!(
+            "[scripting] in config file needs ntomb built with --features scripting"
+        ));
+    }
+    #[cfg(feature = "scripting")]
+    let script_hooks = app::scripting::ScriptHooks::load(&config.scripting)
+        .map_err(|e| anyhow!(e))
+        .context("Invalid [scripting] in config file")?;
+
+    // Headless modes replace the TUI entirely
+    if let Some(cli::Command::Serve {
+        listen,
+        token,
+        allow_origins,
+    }) = cli.command
+    {
+        // The API serves the Grimoire, alerts included
+        let mut app = headless_app(data_source, refresh_config, inventory, cli.filter);
+        alert_sources.install(&mut app, &config);
+        if let Some(history) = history {
+            app.events.set_history(history);
+        }
+        return api::serve(&mut app, listen, token, allow_origins);
+    }
+    if cli.json {
+        let mut app = headless_app(data_source, refresh_config, inventory, cli.filter);
+        return run_stream(&mut app, cli.watch);
+    }
+    if cli.statusline {
+        // Alerts are counted from the Grimoire, so the alert sources are set
+        let mut app = headless_app(data_source, refresh_config, inventory, cli.filter);
+        alert_sources.install(&mut app, &config);
+        return run_statusline(&mut app, cli.interval);
+    }
+
     // Detect emoji width before entering alternate screen
     // This queries cursor position which requires the main terminal
     let _emoji_config = ui::emoji_width::init_emoji_width_detection();
-    
+
+    // AppState reads the detected emoji offset, so it is built afterwards
+    let mut app = AppState::with_data_source(data_source);
+    app.refresh_config = refresh_config;
+    alert_sources.install(&mut app, &config);
+    app.cloud_ranges = cloud_ranges;
+    app.asn_database = std::sync::Arc::new(asn_database);
+    app.inventory = inventory;
+    app.rebuild_enrichers();
+    app.probes = app::probes::ProbeLimiter::from_config(&config.probes);
+    app.peers = app::peers::PeerStore::from_config(&config.peers);
+    app.anomaly = app::anomaly::AnomalyDetector::from_config(&config.anomaly);
+    app.autosave = autosave;
+    if let Some(tombstones) = tombstones {
+        app.set_tombstones(tombstones);
+    }
+    if config.dns.reverse {
+        app.reverse_dns = Some(app::dns::ReverseDns::spawn());
+    }
+    if let Some(enrich_cache) = enrich_cache {
+        app.set_enrich_cache(enrich_cache);
+    }
+    app.anonymizers = anonymizers;
+    app.alarm = alarm;
+    app.keymap = config.keys.profile;
+    app.graveyard_settings.animation_quality = config.animation.quality;
+    app.graveyard_settings.ambiance_enabled = config.animation.ambiance;
+    if let Some(max) = config.graveyard.max_endpoints {
+        app.graveyard_settings.max_visible_endpoints = max.max(1);
+    }
+    if let Some(heavy_talkers) = config.graveyard.heavy_talkers {
+        // adjusted(0) raises N = 0 to 1
+        app.graveyard_settings.heavy_talkers = heavy_talkers.adjusted(0);
+    }
+    app.ephemeral_ports = app::ports::local_port_range();
+    if let Some(path) = app::settings::default_path() {
+        app.load_settings(path);
+    }
+    app.deterministic = cli.deterministic;
+    app.set_host_name(config.graveyard.host_name.as_deref());
+    if !cli.deterministic {
+        app.theme_schedule = Some(app::theme_schedule::ThemeSchedule::new(
+            config.theme.clone(),
+        ));
+        app.apply_theme_schedule(std::time::Instant::now());
+    }
+    app.screensaver_after = config
+        .screensaver
+        .idle_secs
+        .filter(|&secs| secs > 0)
+        .map(std::time::Duration::from_secs);
+    if let Some(forwarder) = forwarder {
+        app.events.set_forwarder(forwarder);
+    }
+    if let Some(history) = history {
+        app.events.set_history(history);
+    }
+    #[cfg(feature = "otlp")]
+    {
+        app.otlp = app::otlp::OtlpExporter::spawn(&config.otlp)
+            .map_err(|e| anyhow!(e))
+            .context("Invalid [otlp] in config file")?;
+    }
+    #[cfg(feature = "scripting")]
+    if let Some(hooks) = script_hooks {
+        app.events.set_script_hooks(hooks);
+    }
+    if let Some(profile) = cli.profile {
+        let chosen = app.refresh_config.clone();
+        app.apply_profile(profile);
+        if cli.refresh_ms.is_some() {
+            app.refresh_config.refresh_ms = chosen.refresh_ms;
+        }
+        if cli.data_multiplier.is_some() {
+            app.refresh_config.data_multiplier = chosen.data_multiplier;
+        }
+    }
+    if cli.filter.is_some() {
+        app.connection_filter.expression = cli.filter;
+        app.apply_filters();
+    }
+
+    // Apply edits to the config file without a restart
+    if let Some(path) = cli.config.clone().or_else(app::config_file::default_path) {
+        match app::config_watch::ConfigWatcher::spawn(&path) {
+            Ok(watcher) => app.config_watcher = Some(watcher),
+            Err(e) => tracing::warn!(error = %e, "Config hot-reload disabled"),
+        }
+    }
+    app.config = config;
+
     // Setup terminal
     enable_raw_mode()?;
     let mut stdout = io::stdout();
@@ -30,7 +245,17 @@ fn main() -> Result<()> {
     let mut terminal = Terminal::new(backend)?;
 
     // Run app
-    let res = run_app(&mut terminal);
+    let res = run_app(&mut terminal, &mut app);
+    app.events.push(app::events::GrimoireEvent::new(
+        app::events::EventSeverity::Info,
+        "Graveyard closed",
+    ));
+    if let Some(ref mut peers) = app.peers {
+        peers.save();
+    }
+    if let Some(ref mut enrich_cache) = app.enrich_cache {
+        enrich_cache.save();
+    }
 
     // Restore terminal
     disable_raw_mode()?;
@@ -43,20 +268,202 @@ fn main() -> Result<()> {
     Ok(())
 }
 
-fn run_app<B: ratatui::backend::Backend>(terminal: &mut Terminal<B>) -> Result<()> {
-    let mut app = AppState::new();
+/// Sources of the Grimoire's alerts, set up alike in every mode that
+/// reports them
+struct AlertSources {
+    endpoint_rules: Vec<std::sync::Arc<endpoint_rules::EndpointRule>>,
+    environment_alerts: Vec<endpoint_rules::EnvironmentAlert>,
+    watches: Vec<app::watch::Watch>,
+}
+
+impl AlertSources {
+    /// Arm `app` with these and the threat intel and SYN storm settings
+    /// of `config`
+    fn install(self, app: &mut AppState, config: &ConfigFile) {
+        app.endpoint_rules = self.endpoint_rules;
+        app.environment_alerts = self.environment_alerts;
+        app.threat_intel = app::threat_intel::ThreatIntel::from_config(&config.threat_intel);
+        app.syn_storm = app::syn_storm::SynStormMonitor::new(&config.syn_storm);
+        app.watches = self.watches;
+    }
+}
+
+/// App state for modes without a terminal: collection and filters only
+fn headless_app(
+    data_source: Box<dyn net::DataSource>,
+    refresh_config: RefreshConfig,
+    inventory: std::sync::Arc<app::inventory::Inventory>,
+    filter: Option<app::filter_expr::FilterExpr>,
+) -> AppState {
+    let mut app = AppState::with_data_source(data_source);
+    app.refresh_config = refresh_config;
+    // Asset labels are part of the exported records
+    app.inventory = inventory;
+    app.rebuild_enrichers();
+    if filter.is_some() {
+        app.connection_filter.expression = filter;
+        app.apply_filters();
+    }
+    app
+}
+
+/// Print the events kept in the `[history]` file, oldest first
+fn print_history(
+    config: &app::history::HistoryConfig,
+    since: Option<std::time::Duration>,
+    json: bool,
+) -> Result<()> {
+    let path = config
+        .file
+        .as_deref()
+        .ok_or_else(|| anyhow!("No event history: set [history] file in the config file"))?;
+    let since = since.and_then(|age| std::time::SystemTime::now().checked_sub(age));
+    let events = app::history::read_events(path, since)
+        .with_context(|| format!("Cannot read event history {}", path.display()))?;
+    let mut stdout = io::stdout().lock();
+    for event in &events {
+        let line = if json {
+            app::history::event_json(event)
+        } else {
+            format!(
+                "{} {} {}",
+                app::events::rfc3339(event.timestamp),
+                event.severity.label(),
+                event.message
+            )
+        };
+        if writeln!(stdout, "{}", line).is_err() {
+            // The reader went away (`ntomb events | head`)
+            break;
+        }
+    }
+    Ok(())
+}
+
+/// Print connections (or, with `watch`, connection events) as NDJSON
+///
+/// Stops quietly when the reader goes away (`ntomb --json --watch | head`).
+fn run_stream(app: &mut AppState, watch: bool) -> Result<()> {
+    let mut stdout = io::stdout().lock();
+    let mut previous = Vec::new();
     loop {
-        app.on_tick();
-        app.update_frame_time();
-        terminal.draw(|f| ui::draw(f, &mut app))?;
+        app.refresh_connections();
+        if let Some(error) = &app.conn_error {
+            if !watch {
+                return Err(anyhow!(error.clone()));
+            }
+            eprintln!("ntomb: {}", error);
+        }
+        let lines: Vec<String> = if watch {
+            stream::connection_events(&previous, &app.connections)
+                .into_iter()
+                .map(|(event, conn)| stream::json_line(Some(event), conn))
+                .collect()
+        } else {
+            app.connections
+                .iter()
+                .map(|conn| stream::json_line(None, conn))
+                .collect()
+        };
+        let written = lines
+            .iter()
+            .try_for_each(|line| writeln!(stdout, "{}", line))
+            .and_then(|()| stdout.flush());
+        match written {
+            Err(e) if e.kind() == io::ErrorKind::BrokenPipe => return Ok(()),
+            written => written?,
+        }
+        if !watch {
+            return Ok(());
+        }
+        previous = app.connections.clone();
+        std::thread::sleep(app.refresh_config.data_interval());
+    }
+}
+
+/// Print the status-line summary once, or every `interval` seconds
+fn run_statusline(app: &mut AppState, interval: Option<u64>) -> Result<()> {
+    let mut stdout = io::stdout().lock();
+    loop {
+        app.refresh_connections();
+        if let Some(error) = &app.conn_error {
+            if interval.is_none() {
+                return Err(anyhow!(error.clone()));
+            }
+            eprintln!("ntomb: {}", error);
+        }
+        let line = app::statusline::summary(&app.connections, app.events.iter());
+        match writeln!(stdout, "{}", line).and_then(|()| stdout.flush()) {
+            Err(e) if e.kind() == io::ErrorKind::BrokenPipe => return Ok(()),
+            written => written?,
+        }
+        let Some(secs) = interval else {
+            return Ok(());
+        };
+        std::thread::sleep(std::time::Duration::from_secs(secs));
+    }
+}
+
+fn run_app<B: ratatui::backend::Backend + Write>(
+    terminal: &mut Terminal<B>,
+    app: &mut AppState,
+) -> Result<()> {
+    // Set by a resize: draw the new layout before the next tick, which
+    // may be waiting on a collection
+    let mut resized = false;
+    loop {
+        if !std::mem::take(&mut resized) {
+            app.on_tick();
+        }
+
+        // Skip drawing entirely when nothing visible changed
+        if app.take_dirty() {
+            app.update_frame_time();
+            let frame = terminal.draw(|f| ui::draw(f, app))?;
+            if app.screenshot_requested {
+                app.save_screenshot(frame.buffer);
+            }
+        }
+        // BEL after the frame, so it never lands inside a drawing sequence
+        if app.alarm.take_bell() {
+            let backend = terminal.backend_mut();
+            write!(backend, "\x07")?;
+            Write::flush(backend)?;
+        }
 
         if !app.running {
             return Ok(());
         }
 
         if event::poll(app.refresh_config.ui_interval())? {
-            if let Event::Key(key) = event::read()? {
-                handle_key_event(&mut app, key.code);
+            let mut next = Some(event::read()?);
+            while let Some(current) = next.take() {
+                match current {
+                    Event::Key(key) => {
+                        handle_key(app, key);
+                        app.mark_dirty();
+                    }
+                    Event::Resize(mut width, mut height) => {
+                        // Dragging a window edge sends a burst of resizes;
+                        // only the last size is laid out
+                        while event::poll(std::time::Duration::ZERO)? {
+                            match event::read()? {
+                                Event::Resize(w, h) => (width, height) = (w, h),
+                                other => {
+                                    next = Some(other);
+                                    break;
+                                }
+                            }
+                        }
+                        // Resizing the buffers clears the screen, so the
+                        // next frame is drawn whole instead of diffed
+                        // against the old size
+                        terminal.resize(ratatui::layout::Rect::new(0, 0, width, height))?;
+                        app.on_resize(width, height);
+                        resized = true;
+                    }
+                    _ => {}
+                }
             }
         }
     }
@@ -0,0 +1,430 @@
+// BSD data source
+//
+// netstat2 has no BSD support, so FreeBSD and OpenBSD collect sockets
+// straight from the kernel with sysctl:
+// - FreeBSD: `net.inet.tcp.pcblist` lists every TCP PCB with its state;
+//   libprocstat walks open file descriptors to map PCBs to owning PIDs.
+// - OpenBSD: `kern.file` (KERN_FILE_BYPID) returns one record per open
+//   socket, already carrying addresses, TCP state and the owning process.
+//
+// Both paths are read-only queries of kernel state.
+
+use super::source::DataSource;
+use super::{Connection, ConnectionState};
+use std::io;
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+/// sysctl-based backend for FreeBSD and OpenBSD
+#[derive(Debug, Default)]
+pub struct SysctlSource;
+
+impl SysctlSource {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl DataSource for SysctlSource {
+    fn name(&self) -> &'static str {
+        "sysctl"
+    }
+
+    fn collect(&mut self) -> io::Result<Vec<Connection>> {
+        let mut connections = platform::collect_tcp()?;
+        super::fill_user_names(&mut connections);
+        Ok(connections)
+    }
+}
+
+/// Map a BSD `TCPS_*` state number to a ConnectionState
+/// (the numbering in netinet/tcp_fsm.h is shared by FreeBSD and OpenBSD)
+fn state_from_tcps(state: i32) -> ConnectionState {
+    match state {
+        0 => ConnectionState::Close,
+        1 => ConnectionState::Listen,
+        2 => ConnectionState::SynSent,
+        3 => ConnectionState::SynRecv,
+        4 => ConnectionState::Established,
+        5 => ConnectionState::CloseWait,
+        6 => ConnectionState::FinWait1,
+        7 => ConnectionState::Closing,
+        8 => ConnectionState::LastAck,
+        9 => ConnectionState::FinWait2,
+        10 => ConnectionState::TimeWait,
+        _ => ConnectionState::Unknown,
+    }
+}
+
+/// Convert a NUL-terminated C char array (e.g. `ki_comm`) to a String
+fn c_chars_to_string(chars: &[libc::c_char]) -> String {
+    let bytes: Vec<u8> = chars
+        .iter()
+        .take_while(|&&c| c != 0)
+        .map(|&c| c as u8)
+        .collect();
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+/// Owning process of a socket
+#[derive(Debug, Clone)]
+struct SocketOwner {
+    pid: i32,
+    uid: u32,
+    name: String,
+}
+
+#[cfg(target_os = "freebsd")]
+mod platform {
+    use super::*;
+    use std::collections::HashMap;
+    use std::ffi::CString;
+    use std::mem::size_of;
+    use std::ptr;
+
+    /// `in_conninfo.inc_flags` bit marking an IPv6 PCB
+    const INC_ISIPV6: u8 = 0x01;
+
+    /// Bytes between the end of `xt_inp` and `t_state` in `struct xtcpcb`:
+    /// xt_stack[32] + xt_logid[64] + xt_cc[16] + spare64[6] (FreeBSD 12+;
+    /// FreeBSD 12 has spare64[8] in place of xt_cc, which is the same size)
+    const XTCPCB_STATE_OFFSET_AFTER_INP: usize = 32 + 64 + 16 + 48;
+
+    pub(super) fn collect_tcp() -> io::Result<Vec<Connection>> {
+        let buf = read_sysctl("net.inet.tcp.pcblist")?;
+        let owners = socket_owners_by_pcb();
+
+        let mut connections = Vec::new();
+        for pcb in parse_pcblist(&buf) {
+            let owner = owners.get(&pcb.inpp);
+            connections.push(Connection {
+                local_addr: pcb.local_addr.into(),
+                local_port: pcb.local_port,
+                remote_addr: pcb.remote_addr.into(),
+                remote_port: pcb.remote_port,
+                state: state_from_tcps(pcb.state),
+                pid: owner.map(|o| o.pid),
+                process_name: owner.map(|o| o.name.as_str().into()),
+                uid: owner.map(|o| o.uid),
+                ..Default::default()
+            });
+        }
+        Ok(connections)
+    }
+
+    /// One TCP PCB from the pcblist sysctl
+    struct PcbEntry {
+        /// Kernel address of the inpcb (matches `sockstat.so_pcb`)
+        inpp: u64,
+        local_addr: String,
+        local_port: u16,
+        remote_addr: String,
+        remote_port: u16,
+        state: i32,
+    }
+
+    /// Read a variable-length sysctl into a byte buffer
+    fn read_sysctl(name: &str) -> io::Result<Vec<u8>> {
+        let cname = CString::new(name).map_err(io::Error::other)?;
+        let mut len: libc::size_t = 0;
+
+        // SAFETY: size query with a null buffer; `len` receives the size
+        let rc = unsafe {
+            libc::sysctlbyname(cname.as_ptr(), ptr::null_mut(), &mut len, ptr::null(), 0)
+        };
+        if rc != 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        // The PCB list can grow between the two calls, leave some headroom
+        len += len / 8;
+        let mut buf = vec![0u8; len];
+        // SAFETY: `buf` is valid for `len` bytes; the kernel updates `len`
+        let rc = unsafe {
+            libc::sysctlbyname(
+                cname.as_ptr(),
+                buf.as_mut_ptr().cast(),
+                &mut len,
+                ptr::null(),
+                0,
+            )
+        };
+        if rc != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        buf.truncate(len);
+        Ok(buf)
+    }
+
+    fn read_u64(buf: &[u8], offset: usize) -> Option<u64> {
+        let bytes = buf.get(offset..offset + 8)?;
+        Some(u64::from_ne_bytes(bytes.try_into().ok()?))
+    }
+
+    fn read_i32(buf: &[u8], offset: usize) -> Option<i32> {
+        let bytes = buf.get(offset..offset + 4)?;
+        Some(i32::from_ne_bytes(bytes.try_into().ok()?))
+    }
+
+    /// Walk the `xinpgen` header, the `xtcpcb` records and the trailing
+    /// `xinpgen`. Every record and nested struct is length-prefixed, so
+    /// only the fixed prefix of each layout is relied upon.
+    fn parse_pcblist(buf: &[u8]) -> Vec<PcbEntry> {
+        let mut entries = Vec::new();
+        let Some(header_len) = read_u64(buf, 0) else {
+            return entries;
+        };
+
+        let mut offset = header_len as usize;
+        while let Some(xt_len) = read_u64(buf, offset) {
+            let xt_len = xt_len as usize;
+            // The trailing xinpgen marks the end of the list
+            if xt_len <= size_of::<libc::xinpgen>() || offset + xt_len > buf.len() {
+                break;
+            }
+            if let Some(entry) = parse_xtcpcb(&buf[offset..offset + xt_len]) {
+                entries.push(entry);
+            }
+            offset += xt_len;
+        }
+        entries
+    }
+
+    /// Parse one `struct xtcpcb`:
+    /// xt_len, then `struct xinpcb` { xi_len, xi_inpp, xsocket, in_conninfo, ... }
+    fn parse_xtcpcb(record: &[u8]) -> Option<PcbEntry> {
+        let xinpcb = 8;
+        let xi_len = read_u64(record, xinpcb)? as usize;
+        let inpp = read_u64(record, xinpcb + 8)?;
+        let xsocket = xinpcb + 16;
+        let xso_len = read_u64(record, xsocket)? as usize;
+
+        let inc_offset = xsocket + xso_len;
+        if inc_offset + size_of::<libc::in_conninfo>() > record.len() {
+            return None;
+        }
+        // SAFETY: bounds checked above; in_conninfo is plain old data
+        let inc: libc::in_conninfo =
+            unsafe { ptr::read_unaligned(record[inc_offset..].as_ptr().cast()) };
+
+        let state = read_i32(record, xinpcb + xi_len + XTCPCB_STATE_OFFSET_AFTER_INP)?;
+
+        let ie = &inc.inc_ie;
+        // SAFETY: the union variant is selected by INC_ISIPV6
+        let (local_addr, remote_addr) = unsafe {
+            if inc.inc_flags & INC_ISIPV6 != 0 {
+                (
+                    Ipv6Addr::from(ie.ie_dependladdr.id6_addr.s6_addr).to_string(),
+                    Ipv6Addr::from(ie.ie_dependfaddr.id6_addr.s6_addr).to_string(),
+                )
+            } else {
+                (
+                    ipv4_string(ie.ie_dependladdr.id46_addr.ia46_addr4.s_addr),
+                    ipv4_string(ie.ie_dependfaddr.id46_addr.ia46_addr4.s_addr),
+                )
+            }
+        };
+
+        Some(PcbEntry {
+            inpp,
+            local_addr,
+            local_port: u16::from_be(ie.ie_lport),
+            remote_addr,
+            remote_port: u16::from_be(ie.ie_fport),
+            state,
+        })
+    }
+
+    /// Format an `in_addr.s_addr` (network byte order)
+    fn ipv4_string(s_addr: u32) -> String {
+        Ipv4Addr::from(u32::from_be(s_addr)).to_string()
+    }
+
+    /// Map inpcb kernel addresses to the processes holding the sockets,
+    /// using libprocstat to walk every process's open files
+    fn socket_owners_by_pcb() -> HashMap<u64, SocketOwner> {
+        let mut owners = HashMap::new();
+
+        // SAFETY: libprocstat handles are used and released within this
+        // function; list pointers are only dereferenced while valid
+        unsafe {
+            let ps = libc::procstat_open_sysctl();
+            if ps.is_null() {
+                return owners;
+            }
+
+            let mut count: libc::c_uint = 0;
+            let procs = libc::procstat_getprocs(ps, libc::KERN_PROC_PROC, 0, &mut count);
+            if procs.is_null() {
+                libc::procstat_close(ps);
+                return owners;
+            }
+
+            for i in 0..count as usize {
+                let kp = procs.add(i);
+                let files = libc::procstat_getfiles(ps, kp, 0);
+                if files.is_null() {
+                    continue;
+                }
+
+                let mut fst = (*files).stqh_first;
+                while !fst.is_null() {
+                    if (*fst).fs_type == libc::PS_FST_TYPE_SOCKET {
+                        let mut sock: libc::sockstat = std::mem::zeroed();
+                        let mut errbuf = [0 as libc::c_char; libc::_POSIX2_LINE_MAX as usize];
+                        let ok =
+                            libc::procstat_get_socket_info(ps, fst, &mut sock, errbuf.as_mut_ptr())
+                                == 0;
+                        if ok && sock.proto == libc::IPPROTO_TCP && sock.so_pcb != 0 {
+                            owners.entry(sock.so_pcb).or_insert_with(|| SocketOwner {
+                                pid: (*kp).ki_pid,
+                                uid: (*kp).ki_uid,
+                                name: c_chars_to_string(&(*kp).ki_comm),
+                            });
+                        }
+                    }
+                    fst = (*fst).next.stqe_next;
+                }
+                libc::procstat_freefiles(ps, files);
+            }
+
+            libc::procstat_freeprocs(ps, procs);
+            libc::procstat_close(ps);
+        }
+
+        owners
+    }
+}
+
+#[cfg(target_os = "openbsd")]
+mod platform {
+    use super::*;
+    use std::mem::size_of;
+    use std::ptr;
+
+    pub(super) fn collect_tcp() -> io::Result<Vec<Connection>> {
+        let files = read_kinfo_files()?;
+
+        let mut connections = Vec::new();
+        let mut seen_pcbs = std::collections::HashSet::new();
+        for kf in &files {
+            if kf.f_type != libc::DTYPE_SOCKET as u32 || kf.so_protocol != libc::IPPROTO_TCP as u32
+            {
+                continue;
+            }
+            let is_ipv6 = match kf.so_family as i32 {
+                libc::AF_INET => false,
+                libc::AF_INET6 => true,
+                _ => continue,
+            };
+            // Sockets shared between processes (fork, fd passing) appear
+            // once per descriptor; keep the first owner
+            if !seen_pcbs.insert(kf.so_pcb) {
+                continue;
+            }
+
+            let owner = SocketOwner {
+                pid: kf.p_pid as i32,
+                uid: kf.p_uid,
+                name: c_chars_to_string(&kf.p_comm),
+            };
+            connections.push(Connection {
+                local_addr: format_addr(&kf.inp_laddru, is_ipv6).into(),
+                local_port: u16::from_be(kf.inp_lport as u16),
+                remote_addr: format_addr(&kf.inp_faddru, is_ipv6).into(),
+                remote_port: u16::from_be(kf.inp_fport as u16),
+                state: state_from_tcps(kf.t_state as i32),
+                pid: Some(owner.pid),
+                process_name: Some(owner.name.into()),
+                uid: Some(owner.uid),
+                ..Default::default()
+            });
+        }
+        Ok(connections)
+    }
+
+    /// Read every open file of every process via `kern.file`
+    fn read_kinfo_files() -> io::Result<Vec<libc::kinfo_file>> {
+        let elem_size = size_of::<libc::kinfo_file>();
+        let mut mib = [
+            libc::CTL_KERN,
+            libc::KERN_FILE,
+            libc::KERN_FILE_BYPID,
+            -1,
+            elem_size as libc::c_int,
+            0,
+        ];
+        let mut len: libc::size_t = 0;
+
+        // SAFETY: size query with a null buffer; `len` receives the size
+        let rc = unsafe {
+            libc::sysctl(
+                mib.as_ptr(),
+                mib.len() as libc::c_uint,
+                ptr::null_mut(),
+                &mut len,
+                ptr::null_mut(),
+                0,
+            )
+        };
+        if rc != 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        // Files can be opened between the two calls, leave some headroom
+        let capacity = len / elem_size + len / elem_size / 8 + 1;
+        mib[5] = capacity as libc::c_int;
+        let mut files: Vec<libc::kinfo_file> = Vec::with_capacity(capacity);
+        len = capacity * elem_size;
+
+        // SAFETY: `files` has room for `capacity` records; the kernel
+        // writes at most `len` bytes and reports how many it wrote
+        let rc = unsafe {
+            libc::sysctl(
+                mib.as_ptr(),
+                mib.len() as libc::c_uint,
+                files.as_mut_ptr().cast(),
+                &mut len,
+                ptr::null_mut(),
+                0,
+            )
+        };
+        if rc != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        // SAFETY: the kernel initialized `len / elem_size` records
+        unsafe { files.set_len(len / elem_size) };
+        Ok(files)
+    }
+
+    /// Format `inp_laddru`/`inp_faddru` (network byte order words)
+    fn format_addr(words: &[u32; 4], is_ipv6: bool) -> String {
+        if is_ipv6 {
+            let mut bytes = [0u8; 16];
+            for (chunk, word) in bytes.chunks_exact_mut(4).zip(words) {
+                chunk.copy_from_slice(&word.to_ne_bytes());
+            }
+            Ipv6Addr::from(bytes).to_string()
+        } else {
+            Ipv4Addr::from(u32::from_be(words[0])).to_string()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_state_from_tcps() {
+        assert_eq!(state_from_tcps(1), ConnectionState::Listen);
+        assert_eq!(state_from_tcps(4), ConnectionState::Established);
+        assert_eq!(state_from_tcps(10), ConnectionState::TimeWait);
+        assert_eq!(state_from_tcps(42), ConnectionState::Unknown);
+    }
+
+    #[test]
+    fn test_c_chars_to_string() {
+        let comm: [libc::c_char; 8] = [b's' as _, b's' as _, b'h' as _, b'd' as _, 0, 0, 0, 0];
+        assert_eq!(c_chars_to_string(&comm), "sshd");
+    }
+}
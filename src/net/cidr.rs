@@ -0,0 +1,182 @@
+// CIDR address ranges
+//
+// Used by filter expressions (`remote in 10.0.0.0/8`) and anywhere else an
+// address needs to be matched against a network. Connection addresses are
+// stored as strings, so matching parses them on demand; IPv4-mapped IPv6
+// addresses (::ffff:a.b.c.d) match IPv4 ranges.
+
+use std::fmt;
+use std::net::IpAddr;
+use std::str::FromStr;
+
+/// A network such as `10.0.0.0/8` or `fd00::/8`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Cidr {
+    network: IpAddr,
+    prefix_len: u8,
+}
+
+impl Cidr {
+    /// Whether `addr` lies inside this network
+    pub fn contains(&self, addr: &IpAddr) -> bool {
+        let addr = normalize(*addr);
+        match (self.network, addr) {
+            (IpAddr::V4(net), IpAddr::V4(addr)) => {
+                let mask = prefix_mask_u32(self.prefix_len);
+                u32::from(net) & mask == u32::from(addr) & mask
+            }
+            (IpAddr::V6(net), IpAddr::V6(addr)) => {
+                let mask = prefix_mask_u128(self.prefix_len);
+                u128::from(net) & mask == u128::from(addr) & mask
+            }
+            _ => false,
+        }
+    }
+
+    /// Number of leading network bits
+    pub fn prefix_len(&self) -> u8 {
+        self.prefix_len
+    }
+
+    /// Whether the textual address `addr` lies inside this network
+    ///
+    /// Unparseable addresses never match.
+    pub fn contains_str(&self, addr: &str) -> bool {
+        addr.parse::<IpAddr>().is_ok_and(|ip| self.contains(&ip))
+    }
+}
+
+impl fmt::Display for Cidr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.network, self.prefix_len)
+    }
+}
+
+impl FromStr for Cidr {
+    type Err = String;
+
+    /// Parse `addr/len`; a bare address is a single-host network
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (addr, len) = match s.split_once('/') {
+            Some((addr, len)) => (addr, Some(len)),
+            None => (s, None),
+        };
+        let network = addr
+            .parse::<IpAddr>()
+            .map(normalize)
+            .map_err(|_| format!("invalid address '{}' in CIDR '{}'", addr, s))?;
+        let max_len = if network.is_ipv4() { 32 } else { 128 };
+        let prefix_len = match len {
+            Some(len) => len
+                .parse::<u8>()
+                .ok()
+                .filter(|len| *len <= max_len)
+                .ok_or_else(|| format!("invalid prefix length '/{}' (0-{})", len, max_len))?,
+            None => max_len,
+        };
+        Ok(Self {
+            network,
+            prefix_len,
+        })
+    }
+}
+
+/// Parse a range list: one address or CIDR per line, optionally followed
+/// by a note ("1.2.3.0/24 ; SBL123", "203.0.113.7 scanner")
+///
+/// Blank lines, `#` comments and `;` comment lines are ignored. Returns the entries and the
+/// number of lines that could not be parsed.
+pub fn parse_range_list(content: &str) -> (Vec<(Cidr, String)>, usize) {
+    let mut entries = Vec::new();
+    let mut skipped = 0;
+    for line in content.lines() {
+        let line = line.split('#').next().unwrap_or("").trim();
+        if line.is_empty() || line.starts_with(';') {
+            continue;
+        }
+        let (range, note) = match line.find(|c: char| c.is_whitespace() || c == ';' || c == ',') {
+            Some(idx) => (&line[..idx], &line[idx..]),
+            None => (line, ""),
+        };
+        match range.parse::<Cidr>() {
+            Ok(cidr) => {
+                let note = note.trim_matches(|c: char| c.is_whitespace() || c == ';' || c == ',');
+                entries.push((cidr, note.to_string()));
+            }
+            Err(_) => skipped += 1,
+        }
+    }
+    (entries, skipped)
+}
+
+/// Treat IPv4-mapped IPv6 addresses as plain IPv4
+pub(crate) fn normalize(addr: IpAddr) -> IpAddr {
+    match addr {
+        IpAddr::V6(v6) => v6
+            .to_ipv4_mapped()
+            .map(IpAddr::V4)
+            .unwrap_or(IpAddr::V6(v6)),
+        v4 => v4,
+    }
+}
+
+fn prefix_mask_u32(len: u8) -> u32 {
+    u32::MAX.checked_shl(32 - len as u32).unwrap_or(0)
+}
+
+fn prefix_mask_u128(len: u8) -> u128 {
+    u128::MAX.checked_shl(128 - len as u32).unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ipv4_contains() {
+        let cidr: Cidr = "10.0.0.0/8".parse().unwrap();
+        assert!(cidr.contains_str("10.1.2.3"));
+        assert!(!cidr.contains_str("11.0.0.1"));
+        assert!(cidr.contains_str("::ffff:10.9.9.9"));
+        assert!(!cidr.contains_str("not-an-ip"));
+
+        let all: Cidr = "0.0.0.0/0".parse().unwrap();
+        assert!(all.contains_str("203.0.113.7"));
+        assert!(!all.contains_str("2001:db8::1"));
+    }
+
+    #[test]
+    fn test_ipv6_contains() {
+        let cidr: Cidr = "fd00::/8".parse().unwrap();
+        assert!(cidr.contains_str("fd12:3456::1"));
+        assert!(!cidr.contains_str("fe80::1"));
+    }
+
+    #[test]
+    fn test_single_host() {
+        let cidr: Cidr = "192.168.1.10".parse().unwrap();
+        assert_eq!(cidr.to_string(), "192.168.1.10/32");
+        assert!(cidr.contains_str("192.168.1.10"));
+        assert!(!cidr.contains_str("192.168.1.11"));
+    }
+
+    #[test]
+    fn test_parse_range_list() {
+        let (entries, skipped) = parse_range_list(
+            "; Spamhaus DROP\n1.10.16.0/20 ; SBL256894\n\n203.0.113.7 scanner # seen 2x\n2001:db8::/32\nbogus line\n",
+        );
+        assert_eq!(skipped, 1);
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0].0.to_string(), "1.10.16.0/20");
+        assert_eq!(entries[0].1, "SBL256894");
+        assert_eq!(entries[1].1, "scanner");
+        assert_eq!(entries[2].1, "");
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!("10.0.0.0/33".parse::<Cidr>().is_err());
+        assert!("10.0.0/8".parse::<Cidr>().is_err());
+        assert!("fd00::/129".parse::<Cidr>().is_err());
+    }
+}
@@ -0,0 +1,100 @@
+// String interning for connection snapshots
+//
+// Addresses and process names repeat heavily within a snapshot (every
+// connection of a pool shares its remote address and process name) and
+// across refreshes. The interner keeps one shared `Arc<str>` per distinct
+// value, so snapshots hold pointers into a small set of strings and the UI
+// can clone connections freely.
+
+use super::Connection;
+use std::collections::HashSet;
+use std::sync::Arc;
+
+/// Deduplicating store of shared strings
+#[derive(Debug, Default)]
+pub struct StringInterner {
+    strings: HashSet<Arc<str>>,
+}
+
+impl StringInterner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Return the shared copy of `value`, storing it on first sight
+    pub fn intern(&mut self, value: &str) -> Arc<str> {
+        if let Some(existing) = self.strings.get(value) {
+            return Arc::clone(existing);
+        }
+        let shared: Arc<str> = Arc::from(value);
+        self.strings.insert(Arc::clone(&shared));
+        shared
+    }
+
+    /// Replace the strings of every connection with their shared copies
+    pub fn intern_connections(&mut self, connections: &mut [Connection]) {
+        for conn in connections.iter_mut() {
+            conn.local_addr = self.intern(&conn.local_addr);
+            conn.remote_addr = self.intern(&conn.remote_addr);
+            if let Some(name) = conn.process_name.take() {
+                conn.process_name = Some(self.intern(&name));
+            }
+        }
+    }
+
+    /// Drop strings no longer referenced outside the interner
+    ///
+    /// Called after each refresh so addresses of closed connections don't
+    /// accumulate for the lifetime of the session.
+    pub fn purge_unused(&mut self) {
+        self.strings.retain(|s| Arc::strong_count(s) > 1);
+    }
+
+    /// Number of distinct strings held
+    #[allow(dead_code)]
+    pub fn len(&self) -> usize {
+        self.strings.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_intern_shares_allocations() {
+        let mut interner = StringInterner::new();
+        let mut conns = vec![
+            Connection {
+                remote_addr: "10.0.0.1".into(),
+                process_name: Some("postgres".into()),
+                ..Default::default()
+            },
+            Connection {
+                remote_addr: "10.0.0.1".into(),
+                process_name: Some("postgres".into()),
+                ..Default::default()
+            },
+        ];
+        interner.intern_connections(&mut conns);
+
+        assert!(Arc::ptr_eq(&conns[0].remote_addr, &conns[1].remote_addr));
+        assert!(Arc::ptr_eq(
+            conns[0].process_name.as_ref().unwrap(),
+            conns[1].process_name.as_ref().unwrap()
+        ));
+        // "", "10.0.0.1", "postgres"
+        assert_eq!(interner.len(), 3);
+    }
+
+    #[test]
+    fn test_purge_unused() {
+        let mut interner = StringInterner::new();
+        let kept = interner.intern("kept");
+        drop(interner.intern("dropped"));
+
+        interner.purge_unused();
+        assert_eq!(interner.len(), 1);
+        assert!(Arc::ptr_eq(&kept, &interner.intern("kept")));
+    }
+}
@@ -0,0 +1,161 @@
+// lsof(8) data source
+//
+// Fallback backend for systems where neither netstat2 nor ss is usable.
+// Runs `lsof -nP -iTCP -FpcunT` and parses the field output, which is
+// stable across lsof versions and platforms (unlike its column layout).
+// Only TCP sockets are collected; on Linux, UDP flows come from
+// /proc/net/udp (see udp.rs) whichever backend is in use.
+
+use super::source::DataSource;
+use super::{Connection, ConnectionState};
+use std::io;
+use std::process::Command;
+use std::sync::Arc;
+
+/// Backend that shells out to `lsof`
+#[derive(Debug, Default)]
+pub struct LsofSource;
+
+impl LsofSource {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl DataSource for LsofSource {
+    fn name(&self) -> &'static str {
+        "lsof"
+    }
+
+    fn collect(&mut self) -> io::Result<Vec<Connection>> {
+        let output = Command::new("lsof")
+            .args(["-nP", "-iTCP", "-FpcunT"])
+            .output()
+            .map_err(|e| io::Error::other(format!("Cannot run lsof: {}", e)))?;
+        // lsof exits with 1 when some files could not be inspected (e.g.
+        // other users' processes); the output is still usable
+        if !output.status.success() && output.stdout.is_empty() {
+            return Err(io::Error::other(format!(
+                "lsof exited with {}",
+                output.status
+            )));
+        }
+        let mut connections = parse_lsof_output(&String::from_utf8_lossy(&output.stdout));
+        super::fill_user_names(&mut connections);
+        Ok(connections)
+    }
+}
+
+/// Parse `lsof -F pcunT` field output
+///
+/// Each line starts with a field tag: `p` (pid), `c` (command), `u` (uid)
+/// open a process set; `n` (addresses) opens a file within it, followed by
+/// `T` lines such as `TST=ESTABLISHED`.
+fn parse_lsof_output(output: &str) -> Vec<Connection> {
+    let mut connections: Vec<Connection> = Vec::new();
+    let mut pid: Option<i32> = None;
+    let mut command: Option<Arc<str>> = None;
+    let mut uid: Option<u32> = None;
+
+    for line in output.lines() {
+        let Some(tag) = line.chars().next() else {
+            continue;
+        };
+        let value = &line[tag.len_utf8()..];
+
+        match tag {
+            'p' => {
+                pid = value.parse().ok();
+                command = None;
+                uid = None;
+            }
+            'c' => command = Some(value.into()),
+            'u' => uid = value.parse().ok(),
+            'n' => {
+                let Some((local, remote)) = parse_lsof_name(value) else {
+                    continue;
+                };
+                connections.push(Connection {
+                    local_addr: local.0.into(),
+                    local_port: local.1,
+                    remote_addr: remote.0.into(),
+                    remote_port: remote.1,
+                    pid,
+                    process_name: command.clone(),
+                    uid,
+                    ..Default::default()
+                });
+            }
+            'T' => {
+                if let (Some(state), Some(conn)) =
+                    (value.strip_prefix("ST="), connections.last_mut())
+                {
+                    conn.state = parse_lsof_state(state);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    connections
+}
+
+/// Parse an lsof name: "127.0.0.1:5432->10.0.0.2:50000", "*:22", "[::1]:8080"
+fn parse_lsof_name(name: &str) -> Option<((String, u16), (String, u16))> {
+    match name.split_once("->") {
+        Some((local, remote)) => Some((parse_lsof_endpoint(local)?, parse_lsof_endpoint(remote)?)),
+        None => Some((parse_lsof_endpoint(name)?, ("0.0.0.0".to_string(), 0))),
+    }
+}
+
+fn parse_lsof_endpoint(endpoint: &str) -> Option<(String, u16)> {
+    let (addr, port) = endpoint.rsplit_once(':')?;
+    let port = if port == "*" { 0 } else { port.parse().ok()? };
+    let addr = addr.trim_start_matches('[').trim_end_matches(']');
+    let addr = if addr == "*" { "0.0.0.0" } else { addr };
+    Some((addr.to_string(), port))
+}
+
+fn parse_lsof_state(state: &str) -> ConnectionState {
+    match state {
+        "ESTABLISHED" => ConnectionState::Established,
+        "SYN_SENT" => ConnectionState::SynSent,
+        "SYN_RECEIVED" | "SYN_RCVD" => ConnectionState::SynRecv,
+        "FIN_WAIT1" | "FIN_WAIT_1" => ConnectionState::FinWait1,
+        "FIN_WAIT2" | "FIN_WAIT_2" => ConnectionState::FinWait2,
+        "TIME_WAIT" => ConnectionState::TimeWait,
+        "CLOSED" => ConnectionState::Close,
+        "CLOSE_WAIT" => ConnectionState::CloseWait,
+        "LAST_ACK" => ConnectionState::LastAck,
+        "LISTEN" => ConnectionState::Listen,
+        "CLOSING" => ConnectionState::Closing,
+        _ => ConnectionState::Unknown,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_lsof_output() {
+        let output = "p812\ncsshd\nu0\nn*:22\nTST=LISTEN\nTQR=0\n\
+                      n[::1]:8080->[::1]:40000\nTST=ESTABLISHED\n\
+                      p2001\ncpostgres\nu113\nn127.0.0.1:5432->127.0.0.1:50000\nTST=CLOSE_WAIT\n";
+        let conns = parse_lsof_output(output);
+        assert_eq!(conns.len(), 3);
+
+        assert_eq!(&*conns[0].local_addr, "0.0.0.0");
+        assert_eq!(conns[0].local_port, 22);
+        assert_eq!(conns[0].state, ConnectionState::Listen);
+        assert_eq!(conns[0].pid, Some(812));
+        assert_eq!(conns[0].uid, Some(0));
+
+        assert_eq!(&*conns[1].remote_addr, "::1");
+        assert_eq!(conns[1].remote_port, 40000);
+        assert_eq!(conns[1].process_name.as_deref(), Some("sshd"));
+
+        assert_eq!(conns[2].process_name.as_deref(), Some("postgres"));
+        assert_eq!(conns[2].state, ConnectionState::CloseWait);
+    }
+}
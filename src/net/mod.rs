@@ -1,10 +1,31 @@
 // Network connection scanning module
 // Read-only operations following ntomb security-domain guidelines
-// Uses netstat2 for cross-platform network socket information
+// Platform-specific collection lives behind the DataSource trait (see source.rs)
+
+#[cfg(any(target_os = "freebsd", target_os = "openbsd"))]
+mod bsd;
+pub mod cidr;
+mod intern;
+mod lsof;
+#[cfg(not(any(target_os = "freebsd", target_os = "openbsd")))]
+mod netstat;
+pub mod scope;
+pub mod sockinfo;
+pub mod source;
+mod ss;
+pub mod ss_events;
+pub mod transport;
+#[cfg(target_os = "linux")]
+pub mod udp;
+
+pub use intern::StringInterner;
+pub use source::{create_source, default_source, BackendKind, DataSource};
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
 
-use netstat2::{get_sockets_info, AddressFamilyFlags, ProtocolFlags, ProtocolSocketInfo, TcpState};
+#[cfg(target_os = "linux")]
 use std::io;
-use sysinfo::System;
 
 #[cfg(target_os = "linux")]
 use std::collections::HashMap;
@@ -14,7 +35,7 @@ use std::fs;
 use std::net::{Ipv4Addr, Ipv6Addr};
 
 /// TCP connection states
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
 pub enum ConnectionState {
     Established,
     SynSent,
@@ -27,34 +48,88 @@ pub enum ConnectionState {
     LastAck,
     Listen,
     Closing,
+    #[default]
     Unknown,
 }
 
-impl From<TcpState> for ConnectionState {
-    fn from(state: TcpState) -> Self {
-        match state {
-            TcpState::Established => ConnectionState::Established,
-            TcpState::SynSent => ConnectionState::SynSent,
-            TcpState::SynReceived => ConnectionState::SynRecv,
-            TcpState::FinWait1 => ConnectionState::FinWait1,
-            TcpState::FinWait2 => ConnectionState::FinWait2,
-            TcpState::TimeWait => ConnectionState::TimeWait,
-            TcpState::Closed => ConnectionState::Close,
-            TcpState::CloseWait => ConnectionState::CloseWait,
-            TcpState::LastAck => ConnectionState::LastAck,
-            TcpState::Listen => ConnectionState::Listen,
-            TcpState::Closing => ConnectionState::Closing,
-            _ => ConnectionState::Unknown,
+impl ConnectionState {
+    /// Kernel-style state name ("ESTABLISHED", "SYN_RECV", ...)
+    pub fn name(&self) -> &'static str {
+        match self {
+            ConnectionState::Established => "ESTABLISHED",
+            ConnectionState::SynSent => "SYN_SENT",
+            ConnectionState::SynRecv => "SYN_RECV",
+            ConnectionState::FinWait1 => "FIN_WAIT1",
+            ConnectionState::FinWait2 => "FIN_WAIT2",
+            ConnectionState::TimeWait => "TIME_WAIT",
+            ConnectionState::Close => "CLOSE",
+            ConnectionState::CloseWait => "CLOSE_WAIT",
+            ConnectionState::LastAck => "LAST_ACK",
+            ConnectionState::Listen => "LISTEN",
+            ConnectionState::Closing => "CLOSING",
+            ConnectionState::Unknown => "UNKNOWN",
+        }
+    }
+}
+
+/// UDP port of QUIC / HTTP/3
+pub const QUIC_PORT: u16 = 443;
+
+/// Transport protocol of a socket
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Protocol {
+    #[default]
+    Tcp,
+    /// Connected UDP flows on port 443 (see udp.rs)
+    Udp,
+}
+
+impl Protocol {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Protocol::Tcp => "TCP",
+            Protocol::Udp => "UDP",
+        }
+    }
+}
+
+/// Which side opened a connection
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ConnectionDirection {
+    /// A peer connected to one of our LISTEN sockets
+    Inbound,
+    /// We connected out to the peer
+    Outbound,
+}
+
+impl ConnectionDirection {
+    /// Arrow as seen from the local end ("←" in, "→" out)
+    pub fn arrow(&self) -> &'static str {
+        match self {
+            ConnectionDirection::Inbound => "←",
+            ConnectionDirection::Outbound => "→",
+        }
+    }
+
+    /// Short column label ("IN", "OUT")
+    pub fn label(&self) -> &'static str {
+        match self {
+            ConnectionDirection::Inbound => "IN",
+            ConnectionDirection::Outbound => "OUT",
         }
     }
 }
 
 /// Represents a single TCP connection
-#[derive(Debug, Clone)]
+///
+/// Addresses, process and interface names are shared `Arc<str>`s (see
+/// intern.rs), so cloning a connection or a whole snapshot never copies
+/// their string data.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
 pub struct Connection {
-    pub local_addr: String,
+    pub local_addr: Arc<str>,
     pub local_port: u16,
-    pub remote_addr: String,
+    pub remote_addr: Arc<str>,
     pub remote_port: u16,
     pub state: ConnectionState,
     #[allow(dead_code)]
@@ -62,68 +137,78 @@ pub struct Connection {
     /// Process ID that owns this connection (populated by procfs on Linux)
     pub pid: Option<i32>,
     /// Process name that owns this connection (populated by procfs on Linux)
-    pub process_name: Option<String>,
+    pub process_name: Option<Arc<str>>,
+    /// UID owning the socket (from /proc/net/tcp on Linux)
+    pub uid: Option<u32>,
+    /// User name resolved from `uid` via /etc/passwd (falls back to the numeric UID)
+    pub user: Option<String>,
+    /// Name of the container the owning process runs in (Docker/Podman)
+    pub container: Option<String>,
+    /// Local network interface the socket is bound to (eth0, wg0, ...);
+    /// None for wildcard binds and unknown addresses
+    pub interface: Option<Arc<str>>,
+    /// Labels added by enrichers ("service" → "https", "country" → "NL";
+    /// see app/enrich.rs)
+    pub labels: BTreeMap<&'static str, Arc<str>>,
+    /// Who opened the connection, inferred from our LISTEN sockets
+    /// (see app/direction.rs); None for listeners
+    pub direction: Option<ConnectionDirection>,
+    /// Token pair of the Multipath TCP connection this socket is a subflow
+    /// of, shared by all its subflows (see transport.rs); None for plain TCP
+    pub mptcp: Option<Arc<str>>,
+    /// Whether the handshake carried data (TCP Fast Open)
+    pub fast_open: bool,
+    /// TCP, or UDP for the flows of udp.rs (reported as ESTABLISHED)
+    pub protocol: Protocol,
 }
 
-/// Collect TCP connections using netstat2
-/// Cross-platform, read-only operation, never modifies system state
-///
-/// Uses netstat2's associated_pids for process information on all platforms,
-/// and sysinfo to resolve PID to process name.
-pub fn collect_connections() -> io::Result<Vec<Connection>> {
-    // Query both IPv4 and IPv6 TCP connections
-    let af_flags = AddressFamilyFlags::IPV4 | AddressFamilyFlags::IPV6;
-    let proto_flags = ProtocolFlags::TCP;
-
-    let sockets = get_sockets_info(af_flags, proto_flags).map_err(|e| {
-        // Gracefully handle errors
-        // Following security-domain: calm, informative tone
-        io::Error::other(format!("Cannot retrieve network sockets: {}", e))
-    })?;
-
-    // Initialize sysinfo for process name lookup
-    let mut sys = System::new();
-    sys.refresh_processes(sysinfo::ProcessesToUpdate::All, true);
-
-    let mut connections = Vec::new();
-
-    for socket_info in sockets {
-        if let ProtocolSocketInfo::Tcp(tcp_info) = socket_info.protocol_socket_info {
-            // Get PID from netstat2's associated_pids (cross-platform!)
-            let pid = socket_info.associated_pids.first().map(|&p| p as i32);
-
-            // Lookup process name using sysinfo
-            let process_name = pid.and_then(|p| {
-                let sysinfo_pid = sysinfo::Pid::from_u32(p as u32);
-                sys.process(sysinfo_pid)
-                    .map(|proc| proc.name().to_string_lossy().to_string())
-            });
-
-            connections.push(Connection {
-                local_addr: tcp_info.local_addr.to_string(),
-                local_port: tcp_info.local_port,
-                remote_addr: tcp_info.remote_addr.to_string(),
-                remote_port: tcp_info.remote_port,
-                state: ConnectionState::from(tcp_info.state),
-                inode: None,
-                pid,
-                process_name,
-            });
-        }
+impl Connection {
+    /// Whether this is a LISTEN socket bound to every interface (0.0.0.0 or ::)
+    pub fn is_wildcard_listener(&self) -> bool {
+        self.state == ConnectionState::Listen && matches!(&*self.local_addr, "0.0.0.0" | "::")
     }
 
-    // On Linux, populate inodes by reading /proc/net/tcp and /proc/net/tcp6
-    #[cfg(target_os = "linux")]
-    populate_inodes(&mut connections)?;
+    /// Whether this is a root-owned wildcard listener
+    ///
+    /// These are the highest-exposure services on the host: reachable from
+    /// any interface and running with full privileges.
+    pub fn is_root_wildcard_listener(&self) -> bool {
+        self.uid == Some(0) && self.is_wildcard_listener()
+    }
+}
+
+/// Socket metadata parsed from a /proc/net/tcp line
+#[cfg(target_os = "linux")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct ProcSocketEntry {
+    inode: u64,
+    uid: u32,
+}
+
+/// (local addr, local port, remote addr, remote port) of a /proc/net/tcp row
+#[cfg(target_os = "linux")]
+type SocketKey = (Arc<str>, u16, Arc<str>, u16);
 
-    Ok(connections)
+/// Resolve `uid` to `user` for connections whose backend reported a UID
+fn fill_user_names(connections: &mut [Connection]) {
+    let user_names = crate::procfs::users::read_user_names();
+    for conn in connections.iter_mut() {
+        if let Some(uid) = conn.uid {
+            conn.user = Some(
+                user_names
+                    .get(&uid)
+                    .cloned()
+                    .unwrap_or_else(|| uid.to_string()),
+            );
+        }
+    }
 }
 
-/// On Linux, read /proc/net/tcp and /proc/net/tcp6 to get socket inodes
-/// and match them to connections by local/remote address and port
+/// On Linux, read /proc/net/tcp and /proc/net/tcp6 to get socket inodes and
+/// owning UIDs, and match them to connections by local/remote address and port
 #[cfg(target_os = "linux")]
 fn populate_inodes(connections: &mut [Connection]) -> io::Result<()> {
-    // Build a map of (local_addr, local_port, remote_addr, remote_port) -> inode
+    // Build a map of (local_addr, local_port, remote_addr, remote_port) -> entry
     let mut inode_map = HashMap::new();
 
     // Parse /proc/net/tcp (IPv4)
@@ -136,6 +221,8 @@ fn populate_inodes(connections: &mut [Connection]) -> io::Result<()> {
         parse_proc_net_tcp(&content, &mut inode_map, true);
     }
 
+    let user_names = crate::procfs::users::read_user_names();
+
     // Match connections to inodes
     for conn in connections.iter_mut() {
         let key = (
@@ -144,8 +231,15 @@ fn populate_inodes(connections: &mut [Connection]) -> io::Result<()> {
             conn.remote_addr.clone(),
             conn.remote_port,
         );
-        if let Some(&inode) = inode_map.get(&key) {
-            conn.inode = Some(inode);
+        if let Some(entry) = inode_map.get(&key) {
+            conn.inode = Some(entry.inode);
+            conn.uid = Some(entry.uid);
+            conn.user = Some(
+                user_names
+                    .get(&entry.uid)
+                    .cloned()
+                    .unwrap_or_else(|| entry.uid.to_string()),
+            );
         }
     }
 
@@ -157,7 +251,7 @@ fn populate_inodes(connections: &mut [Connection]) -> io::Result<()> {
 #[cfg(target_os = "linux")]
 fn parse_proc_net_tcp(
     content: &str,
-    inode_map: &mut HashMap<(String, u16, String, u16), u64>,
+    inode_map: &mut HashMap<SocketKey, ProcSocketEntry>,
     is_ipv6: bool,
 ) {
     for line in content.lines().skip(1) {
@@ -183,9 +277,17 @@ fn parse_proc_net_tcp(
         let remote_addr = parse_hex_addr(remote_parts[0], is_ipv6);
         let remote_port = u16::from_str_radix(remote_parts[1], 16).unwrap_or(0);
 
-        // Parse inode (last field)
-        if let Ok(inode) = parts[9].parse::<u64>() {
-            inode_map.insert((local_addr, local_port, remote_addr, remote_port), inode);
+        // Parse uid and inode
+        if let (Ok(uid), Ok(inode)) = (parts[7].parse::<u32>(), parts[9].parse::<u64>()) {
+            inode_map.insert(
+                (
+                    local_addr.into(),
+                    local_port,
+                    remote_addr.into(),
+                    remote_port,
+                ),
+                ProcSocketEntry { inode, uid },
+            );
         }
     }
 }
@@ -238,36 +340,48 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_collect_connections() {
-        // This test will only pass if the system has network connections
-        // It's more of a smoke test to ensure the API works
-        match collect_connections() {
-            Ok(conns) => {
-                println!("Found {} connections", conns.len());
-                // Should have at least some connections on a typical system
-                assert!(!conns.is_empty());
-            }
-            Err(e) => {
-                // On some systems this might fail due to permissions
-                println!("Warning: Could not collect connections: {}", e);
-            }
-        }
+    fn test_is_root_wildcard_listener() {
+        let listener = Connection {
+            local_addr: "0.0.0.0".into(),
+            local_port: 22,
+            state: ConnectionState::Listen,
+            uid: Some(0),
+            ..Default::default()
+        };
+        assert!(listener.is_root_wildcard_listener());
+
+        let ipv6 = Connection {
+            local_addr: "::".into(),
+            ..listener.clone()
+        };
+        assert!(ipv6.is_root_wildcard_listener());
+
+        let loopback = Connection {
+            local_addr: "127.0.0.1".into(),
+            ..listener.clone()
+        };
+        assert!(!loopback.is_root_wildcard_listener());
+
+        let unprivileged = Connection {
+            uid: Some(1000),
+            ..listener.clone()
+        };
+        assert!(unprivileged.is_wildcard_listener());
+        assert!(!unprivileged.is_root_wildcard_listener());
     }
 
+    #[cfg(target_os = "linux")]
     #[test]
-    fn test_connection_state_conversion() {
-        assert_eq!(
-            ConnectionState::from(TcpState::Established),
-            ConnectionState::Established
-        );
-        assert_eq!(
-            ConnectionState::from(TcpState::Listen),
-            ConnectionState::Listen
-        );
-        assert_eq!(
-            ConnectionState::from(TcpState::TimeWait),
-            ConnectionState::TimeWait
-        );
+    fn test_parse_proc_net_tcp_uid_and_inode() {
+        let content = "  sl  local_address rem_address   st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode\n   0: 0100007F:1F90 00000000:0000 0A 00000000:00000000 00:00000000 00000000  1000        0 54321 1 0000000000000000 100 0 0 10 0\n";
+        let mut map = HashMap::new();
+        parse_proc_net_tcp(content, &mut map, false);
+
+        let entry = map
+            .get(&("127.0.0.1".into(), 8080, "0.0.0.0".into(), 0))
+            .expect("listen socket parsed");
+        assert_eq!(entry.uid, 1000);
+        assert_eq!(entry.inode, 54321);
     }
 
     #[cfg(target_os = "linux")]
@@ -0,0 +1,147 @@
+// netstat2 data source
+//
+// Default backend on Linux, macOS and Windows. netstat2 provides sockets and
+// owning PIDs, sysinfo resolves PIDs to process names, and on Linux the
+// socket inodes and owning UIDs are filled in from /proc/net/tcp{,6}.
+
+use super::source::DataSource;
+use super::{Connection, ConnectionState};
+use netstat2::{get_sockets_info, AddressFamilyFlags, ProtocolFlags, ProtocolSocketInfo, TcpState};
+use std::io;
+use sysinfo::System;
+
+impl From<TcpState> for ConnectionState {
+    fn from(state: TcpState) -> Self {
+        match state {
+            TcpState::Established => ConnectionState::Established,
+            TcpState::SynSent => ConnectionState::SynSent,
+            TcpState::SynReceived => ConnectionState::SynRecv,
+            TcpState::FinWait1 => ConnectionState::FinWait1,
+            TcpState::FinWait2 => ConnectionState::FinWait2,
+            TcpState::TimeWait => ConnectionState::TimeWait,
+            TcpState::Closed => ConnectionState::Close,
+            TcpState::CloseWait => ConnectionState::CloseWait,
+            TcpState::LastAck => ConnectionState::LastAck,
+            TcpState::Listen => ConnectionState::Listen,
+            TcpState::Closing => ConnectionState::Closing,
+            _ => ConnectionState::Unknown,
+        }
+    }
+}
+
+/// Cross-platform backend built on netstat2
+#[derive(Debug, Default)]
+pub struct Netstat2Source;
+
+impl Netstat2Source {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl DataSource for Netstat2Source {
+    fn name(&self) -> &'static str {
+        "netstat2"
+    }
+
+    /// Collect TCP connections using netstat2
+    /// Cross-platform, read-only operation, never modifies system state
+    ///
+    /// Uses netstat2's associated_pids for process information on all platforms,
+    /// and sysinfo to resolve PID to process name.
+    fn collect(&mut self) -> io::Result<Vec<Connection>> {
+        // Query both IPv4 and IPv6 TCP connections
+        let af_flags = AddressFamilyFlags::IPV4 | AddressFamilyFlags::IPV6;
+        let proto_flags = ProtocolFlags::TCP;
+
+        let sockets = get_sockets_info(af_flags, proto_flags).map_err(|e| {
+            // Gracefully handle errors
+            // Following security-domain: calm, informative tone
+            io::Error::other(format!("Cannot retrieve network sockets: {}", e))
+        })?;
+
+        // Initialize sysinfo for process name lookup
+        let mut sys = System::new();
+        sys.refresh_processes(sysinfo::ProcessesToUpdate::All, true);
+
+        let mut connections = Vec::new();
+
+        for socket_info in sockets {
+            if let ProtocolSocketInfo::Tcp(tcp_info) = socket_info.protocol_socket_info {
+                // Get PID from netstat2's associated_pids (cross-platform!)
+                let pid = socket_info.associated_pids.first().map(|&p| p as i32);
+
+                // Lookup process name using sysinfo
+                let process_name = pid.and_then(|p| {
+                    let sysinfo_pid = sysinfo::Pid::from_u32(p as u32);
+                    sys.process(sysinfo_pid)
+                        .map(|proc| proc.name().to_string_lossy().into())
+                });
+
+                connections.push(Connection {
+                    local_addr: tcp_info.local_addr.to_string().into(),
+                    local_port: tcp_info.local_port,
+                    remote_addr: tcp_info.remote_addr.to_string().into(),
+                    remote_port: tcp_info.remote_port,
+                    state: ConnectionState::from(tcp_info.state),
+                    inode: None,
+                    pid,
+                    process_name,
+                    uid: None,
+                    user: None,
+                    container: None,
+                    interface: None,
+                    labels: Default::default(),
+                    direction: None,
+                    mptcp: None,
+                    fast_open: false,
+                    protocol: Default::default(),
+                });
+            }
+        }
+
+        // On Linux, populate inodes and owning UIDs by reading /proc/net/tcp and /proc/net/tcp6
+        #[cfg(target_os = "linux")]
+        super::populate_inodes(&mut connections)?;
+
+        Ok(connections)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_collect_connections() {
+        // This test will only pass if the system has network connections
+        // It's more of a smoke test to ensure the API works
+        match Netstat2Source::new().collect() {
+            Ok(conns) => {
+                println!("Found {} connections", conns.len());
+                // Should have at least some connections on a typical system
+                assert!(!conns.is_empty());
+            }
+            Err(e) => {
+                // On some systems this might fail due to permissions
+                println!("Warning: Could not collect connections: {}", e);
+            }
+        }
+    }
+
+    #[test]
+    fn test_connection_state_conversion() {
+        assert_eq!(
+            ConnectionState::from(TcpState::Established),
+            ConnectionState::Established
+        );
+        assert_eq!(
+            ConnectionState::from(TcpState::Listen),
+            ConnectionState::Listen
+        );
+        assert_eq!(
+            ConnectionState::from(TcpState::TimeWait),
+            ConnectionState::TimeWait
+        );
+    }
+}
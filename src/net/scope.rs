@@ -0,0 +1,98 @@
+// Address scope
+//
+// Whether an address stays on this host, on a local network or goes out
+// to the internet. Shared by the Graveyard's endpoint icons, the Soul
+// Inspector's public count and the whois action, so IPv6 is handled the
+// same everywhere: unique local (fc00::/7) and link-local (fe80::/10)
+// addresses are private, and IPv4-mapped addresses (::ffff:a.b.c.d) are
+// classified by the IPv4 address they carry.
+
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+/// Where an address lives
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AddressScope {
+    /// This host: loopback (127.0.0.0/8, ::1) or unspecified (0.0.0.0, ::)
+    Loopback,
+    /// A local network: RFC1918, link-local (169.254.0.0/16, fe80::/10),
+    /// IPv6 unique local (fc00::/7) and the IPv4 broadcast address
+    Private,
+    /// Everything else
+    Public,
+}
+
+/// Scope of `addr`; None when it is not an IP address
+pub fn address_scope(addr: &str) -> Option<AddressScope> {
+    Some(match addr.parse::<IpAddr>().ok()? {
+        IpAddr::V4(v4) => ipv4_scope(v4),
+        IpAddr::V6(v6) => match v6.to_ipv4_mapped() {
+            Some(v4) => ipv4_scope(v4),
+            None => ipv6_scope(v6),
+        },
+    })
+}
+
+fn ipv4_scope(v4: Ipv4Addr) -> AddressScope {
+    if v4.is_loopback() || v4.is_unspecified() {
+        AddressScope::Loopback
+    } else if v4.is_private() || v4.is_link_local() || v4.is_broadcast() {
+        AddressScope::Private
+    } else {
+        AddressScope::Public
+    }
+}
+
+fn ipv6_scope(v6: Ipv6Addr) -> AddressScope {
+    let first = v6.segments()[0];
+    if v6.is_loopback() || v6.is_unspecified() {
+        AddressScope::Loopback
+    } else if first & 0xfe00 == 0xfc00 || first & 0xffc0 == 0xfe80 {
+        AddressScope::Private
+    } else {
+        AddressScope::Public
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ipv4_scope() {
+        assert_eq!(address_scope("127.0.0.53"), Some(AddressScope::Loopback));
+        assert_eq!(address_scope("0.0.0.0"), Some(AddressScope::Loopback));
+        assert_eq!(address_scope("172.16.0.1"), Some(AddressScope::Private));
+        assert_eq!(
+            address_scope("169.254.169.254"),
+            Some(AddressScope::Private)
+        );
+        assert_eq!(address_scope("8.8.8.8"), Some(AddressScope::Public));
+        assert_eq!(address_scope("not-an-ip"), None);
+    }
+
+    #[test]
+    fn test_ipv6_scope() {
+        assert_eq!(address_scope("::1"), Some(AddressScope::Loopback));
+        assert_eq!(address_scope("::"), Some(AddressScope::Loopback));
+        assert_eq!(address_scope("fd12:3456::1"), Some(AddressScope::Private));
+        assert_eq!(address_scope("fc00::1"), Some(AddressScope::Private));
+        assert_eq!(address_scope("fe80::1"), Some(AddressScope::Private));
+        assert_eq!(address_scope("febf::1"), Some(AddressScope::Private));
+        assert_eq!(address_scope("fec0::1"), Some(AddressScope::Public));
+        assert_eq!(
+            address_scope("2001:4860:4860::8888"),
+            Some(AddressScope::Public)
+        );
+
+        // IPv4-mapped addresses take the scope of the IPv4 address
+        assert_eq!(
+            address_scope("::ffff:127.0.0.1"),
+            Some(AddressScope::Loopback)
+        );
+        assert_eq!(
+            address_scope("::ffff:10.0.0.1"),
+            Some(AddressScope::Private)
+        );
+        assert_eq!(address_scope("::ffff:8.8.8.8"), Some(AddressScope::Public));
+    }
+}
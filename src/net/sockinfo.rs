@@ -0,0 +1,143 @@
+// TCP socket details for the Forensics tab
+//
+// The congestion control algorithm, keepalive timer and window scaling of
+// one connection, as the kernel's sock_diag interface reports them through
+// `ss -tino` (TCP, internal info, timers, numeric) filtered to the
+// connection's two endpoints. Read-only; only available on Linux.
+
+use super::Connection;
+
+#[cfg(target_os = "linux")]
+use crate::app::actions::ss_endpoint;
+#[cfg(target_os = "linux")]
+use std::process::Command;
+#[cfg(target_os = "linux")]
+use tracing::debug;
+
+/// Socket options of one TCP connection
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SocketInfo {
+    /// Congestion control algorithm ("cubic", "bbr")
+    pub congestion: Option<String>,
+    /// Time until the next keepalive probe; None when no keepalive timer
+    /// is armed (SO_KEEPALIVE off, or another timer is pending)
+    pub keepalive: Option<String>,
+    /// Window scale shifts (send, receive); None when not negotiated
+    pub wscale: Option<(u8, u8)>,
+}
+
+/// Read the socket options of `conn` with `ss`; None when ss fails or no
+/// longer lists the connection
+#[cfg(target_os = "linux")]
+pub fn read_socket_info(conn: &Connection) -> Option<SocketInfo> {
+    let output = Command::new("ss")
+        .args(["-tino", "state", "all", "src"])
+        .arg(ss_endpoint(&conn.local_addr, conn.local_port))
+        .arg("dst")
+        .arg(ss_endpoint(&conn.remote_addr, conn.remote_port))
+        .output();
+    match output {
+        Ok(output) if output.status.success() => {
+            parse_socket_info(&String::from_utf8_lossy(&output.stdout))
+        }
+        Ok(_) => None,
+        Err(e) => {
+            debug!(error = %e, "ss failed");
+            None
+        }
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn read_socket_info(_conn: &Connection) -> Option<SocketInfo> {
+    None
+}
+
+/// Parse `ss -tino` output for a single socket: the socket line (with the
+/// `timer:(...)` field) followed by an indented line of TCP info
+/// ("ts sack cubic wscale:7,7 rto:204 ...")
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_socket_info(output: &str) -> Option<SocketInfo> {
+    let mut lines = output
+        .lines()
+        .filter(|line| !line.trim().is_empty() && !line.starts_with("State"));
+    let socket = lines.next()?;
+    let mut info = SocketInfo {
+        keepalive: socket
+            .split_whitespace()
+            .find_map(|field| field.strip_prefix("timer:(keepalive,"))
+            .and_then(|timer| timer.split(',').next())
+            .map(str::to_string),
+        ..Default::default()
+    };
+
+    let Some(details) = lines
+        .next()
+        .filter(|line| line.starts_with(char::is_whitespace))
+    else {
+        return Some(info);
+    };
+    for field in details.split_whitespace() {
+        if let Some(scales) = field.strip_prefix("wscale:") {
+            info.wscale = scales
+                .split_once(',')
+                .and_then(|(snd, rcv)| Some((snd.parse().ok()?, rcv.parse().ok()?)));
+        } else if field.contains(':') {
+            // The algorithm comes before the first "key:value" field
+            break;
+        } else if !matches!(field, "ts" | "sack" | "ecn" | "ecnseen" | "fastopen") {
+            info.congestion = Some(field.to_string());
+        }
+    }
+    Some(info)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_socket_info() {
+        let output = "\
+State Recv-Q Send-Q Local Address:Port  Peer Address:Port Process
+ESTAB 0      0          10.0.0.5:42238 93.184.216.34:443 timer:(keepalive,22sec,0)
+\t ts sack cubic wscale:7,10 rto:204 rtt:0.082/0.01 ato:40 mss:1448 cwnd:10
+";
+        assert_eq!(
+            parse_socket_info(output),
+            Some(SocketInfo {
+                congestion: Some("cubic".to_string()),
+                keepalive: Some("22sec".to_string()),
+                wscale: Some((7, 10)),
+            })
+        );
+
+        // Retransmit timer pending, no window scaling, no header (-H)
+        let output = "\
+ESTAB 0 0 10.0.0.5:42240 93.184.216.34:443 timer:(on,200ms,0)
+\t sack bbr rto:204 rtt:0.5/0.25 mss:1448
+";
+        assert_eq!(
+            parse_socket_info(output),
+            Some(SocketInfo {
+                congestion: Some("bbr".to_string()),
+                ..Default::default()
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_socket_info_without_socket() {
+        assert_eq!(
+            parse_socket_info("State Recv-Q Send-Q Local Address:Port Peer Address:Port\n"),
+            None
+        );
+        // TIME-WAIT sockets have no TCP info line
+        assert_eq!(
+            parse_socket_info(
+                "TIME-WAIT 0 0 10.0.0.5:42238 93.184.216.34:443 timer:(timewait,59sec,0)\n"
+            ),
+            Some(SocketInfo::default())
+        );
+    }
+}
@@ -0,0 +1,266 @@
+// Connection data sources
+//
+// Platform-specific socket collection lives behind the `DataSource` trait so
+// the rest of ntomb only ever sees `Connection` snapshots. Each backend is a
+// read-only collector; `create_source()` builds the one selected with
+// `--backend`, and `auto` chains the platform default with the ss/lsof
+// fallbacks.
+
+use super::Connection;
+use std::fmt;
+use std::io;
+use std::str::FromStr;
+
+/// A read-only backend that produces TCP connection snapshots, on the
+/// collection worker thread
+pub trait DataSource: Send {
+    /// Short backend name (e.g. "netstat2", "sysctl")
+    fn name(&self) -> &'static str;
+
+    /// Collect a fresh snapshot of TCP connections
+    fn collect(&mut self) -> io::Result<Vec<Connection>>;
+
+    /// Errors from the last `collect` that did not make it fail, as
+    /// (backend, message) pairs (e.g. a fallback backend was used instead)
+    fn take_errors(&mut self) -> Vec<(&'static str, String)> {
+        Vec::new()
+    }
+}
+
+/// Collection backend selectable with `--backend`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BackendKind {
+    /// Platform default, falling back to ss and then lsof on failure
+    #[default]
+    Auto,
+    /// netstat2 crate (Linux, macOS, Windows)
+    Netstat2,
+    /// sysctl (FreeBSD, OpenBSD)
+    Sysctl,
+    /// `ss -tanp` (Linux)
+    Ss,
+    /// `lsof -iTCP` (most Unix systems)
+    Lsof,
+}
+
+impl BackendKind {
+    /// All backends, in `--backend` help order
+    pub const ALL: [BackendKind; 5] = [
+        BackendKind::Auto,
+        BackendKind::Netstat2,
+        BackendKind::Sysctl,
+        BackendKind::Ss,
+        BackendKind::Lsof,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Auto => "auto",
+            Self::Netstat2 => "netstat2",
+            Self::Sysctl => "sysctl",
+            Self::Ss => "ss",
+            Self::Lsof => "lsof",
+        }
+    }
+}
+
+impl fmt::Display for BackendKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for BackendKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|kind| kind.as_str().eq_ignore_ascii_case(s))
+            .ok_or_else(|| {
+                let names: Vec<&str> = Self::ALL.iter().map(|k| k.as_str()).collect();
+                format!(
+                    "unknown backend '{}' (expected one of: {})",
+                    s,
+                    names.join(", ")
+                )
+            })
+    }
+}
+
+/// Create the default data source for the current platform
+pub fn default_source() -> Box<dyn DataSource> {
+    #[cfg(any(target_os = "freebsd", target_os = "openbsd"))]
+    {
+        Box::new(super::bsd::SysctlSource::new())
+    }
+
+    #[cfg(not(any(target_os = "freebsd", target_os = "openbsd")))]
+    {
+        Box::new(super::netstat::Netstat2Source::new())
+    }
+}
+
+/// Create the data source for `kind`
+///
+/// Fails if the backend is not built for this platform.
+pub fn create_source(kind: BackendKind) -> io::Result<Box<dyn DataSource>> {
+    match kind {
+        BackendKind::Auto => Ok(Box::new(FallbackSource::new(vec![
+            default_source(),
+            Box::new(super::ss::SsSource::new()),
+            Box::new(super::lsof::LsofSource::new()),
+        ]))),
+        #[cfg(not(any(target_os = "freebsd", target_os = "openbsd")))]
+        BackendKind::Netstat2 => Ok(Box::new(super::netstat::Netstat2Source::new())),
+        #[cfg(any(target_os = "freebsd", target_os = "openbsd"))]
+        BackendKind::Sysctl => Ok(Box::new(super::bsd::SysctlSource::new())),
+        BackendKind::Ss => Ok(Box::new(super::ss::SsSource::new())),
+        BackendKind::Lsof => Ok(Box::new(super::lsof::LsofSource::new())),
+        #[allow(unreachable_patterns)]
+        other => Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            format!("backend '{}' is not available on this platform", other),
+        )),
+    }
+}
+
+/// Tries each source in order until one succeeds
+///
+/// A source that succeeds without attributing a single socket to a
+/// process is only used if no later source does better.
+pub struct FallbackSource {
+    sources: Vec<Box<dyn DataSource>>,
+    /// Index of the source that produced the last snapshot
+    active: usize,
+    /// Failures of skipped sources during the last collect
+    errors: Vec<(&'static str, String)>,
+}
+
+impl FallbackSource {
+    pub fn new(sources: Vec<Box<dyn DataSource>>) -> Self {
+        Self {
+            sources,
+            active: 0,
+            errors: Vec::new(),
+        }
+    }
+}
+
+impl DataSource for FallbackSource {
+    fn name(&self) -> &'static str {
+        self.sources
+            .get(self.active)
+            .map(|source| source.name())
+            .unwrap_or("none")
+    }
+
+    fn collect(&mut self) -> io::Result<Vec<Connection>> {
+        self.errors.clear();
+        let mut last_error = None;
+        let mut without_pids: Option<(usize, Vec<Connection>)> = None;
+
+        for (idx, source) in self.sources.iter_mut().enumerate() {
+            match source.collect() {
+                Ok(conns) if conns.is_empty() || conns.iter().any(|c| c.pid.is_some()) => {
+                    self.active = idx;
+                    return Ok(conns);
+                }
+                Ok(conns) => {
+                    tracing::debug!(backend = source.name(), "Backend returned no PID data");
+                    without_pids.get_or_insert((idx, conns));
+                }
+                Err(e) => {
+                    tracing::debug!(backend = source.name(), error = %e, "Backend failed");
+                    self.errors.push((source.name(), e.to_string()));
+                    last_error = Some(e);
+                }
+            }
+        }
+
+        if let Some((idx, conns)) = without_pids {
+            self.active = idx;
+            return Ok(conns);
+        }
+        Err(last_error.unwrap_or_else(|| io::Error::other("No collection backend configured")))
+    }
+
+    fn take_errors(&mut self) -> Vec<(&'static str, String)> {
+        std::mem::take(&mut self.errors)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test source returning a canned result
+    struct StubSource {
+        name: &'static str,
+        result: Option<Vec<Connection>>,
+    }
+
+    impl DataSource for StubSource {
+        fn name(&self) -> &'static str {
+            self.name
+        }
+
+        fn collect(&mut self) -> io::Result<Vec<Connection>> {
+            self.result
+                .clone()
+                .ok_or_else(|| io::Error::other("stub failure"))
+        }
+    }
+
+    fn stub(name: &'static str, result: Option<Vec<Connection>>) -> Box<dyn DataSource> {
+        Box::new(StubSource { name, result })
+    }
+
+    #[test]
+    fn test_backend_kind_parse() {
+        assert_eq!("ss".parse::<BackendKind>(), Ok(BackendKind::Ss));
+        assert_eq!("LSOF".parse::<BackendKind>(), Ok(BackendKind::Lsof));
+        assert!("bogus".parse::<BackendKind>().is_err());
+    }
+
+    #[test]
+    fn test_fallback_skips_failing_source() {
+        let with_pid = Connection {
+            pid: Some(42),
+            ..Default::default()
+        };
+        let mut source = FallbackSource::new(vec![
+            stub("broken", None),
+            stub("working", Some(vec![with_pid])),
+        ]);
+        assert_eq!(source.collect().unwrap().len(), 1);
+        assert_eq!(source.name(), "working");
+        assert_eq!(
+            source.take_errors(),
+            vec![("broken", "stub failure".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_fallback_prefers_source_with_pids() {
+        let no_pid = Connection::default();
+        let with_pid = Connection {
+            pid: Some(42),
+            ..Default::default()
+        };
+        let mut source = FallbackSource::new(vec![
+            stub("blind", Some(vec![no_pid.clone()])),
+            stub("sighted", Some(vec![with_pid])),
+        ]);
+        assert_eq!(source.collect().unwrap()[0].pid, Some(42));
+        assert_eq!(source.name(), "sighted");
+
+        // Nothing better available: keep the PID-less snapshot
+        let mut source = FallbackSource::new(vec![
+            stub("blind", Some(vec![no_pid])),
+            stub("broken", None),
+        ]);
+        assert_eq!(source.collect().unwrap().len(), 1);
+        assert_eq!(source.name(), "blind");
+    }
+}
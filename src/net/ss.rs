@@ -0,0 +1,172 @@
+// ss(8) data source
+//
+// Fallback backend for Linux environments where netstat2 cannot read
+// sockets or owning processes (restricted containers, unusual kernels).
+// Runs `ss -tanp` (TCP only, all states, numeric, with processes) and
+// parses its text output. Like the other backends it collects TCP only;
+// on Linux, the UDP flows ntomb shows come from /proc/net/udp (see
+// udp.rs), whichever backend is in use.
+
+use super::source::DataSource;
+use super::{Connection, ConnectionState};
+use std::io;
+use std::process::Command;
+
+/// Backend that shells out to `ss`
+#[derive(Debug, Default)]
+pub struct SsSource;
+
+impl SsSource {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl DataSource for SsSource {
+    fn name(&self) -> &'static str {
+        "ss"
+    }
+
+    fn collect(&mut self) -> io::Result<Vec<Connection>> {
+        let output = Command::new("ss")
+            .arg("-tanp")
+            .output()
+            .map_err(|e| io::Error::other(format!("Cannot run ss: {}", e)))?;
+        if !output.status.success() {
+            return Err(io::Error::other(format!(
+                "ss exited with {}",
+                output.status
+            )));
+        }
+        #[allow(unused_mut)]
+        let mut connections = parse_ss_output(&String::from_utf8_lossy(&output.stdout));
+
+        // ss only prints UIDs with -e; /proc/net/tcp gives them (and the
+        // inodes used for process mapping) more cheaply
+        #[cfg(target_os = "linux")]
+        super::populate_inodes(&mut connections)?;
+
+        Ok(connections)
+    }
+}
+
+/// Parse `ss -tanp` output
+/// Format: State Recv-Q Send-Q Local:Port Peer:Port [users:(("name",pid=N,fd=N),...)]
+fn parse_ss_output(output: &str) -> Vec<Connection> {
+    let mut connections = Vec::new();
+
+    for line in output.lines().skip(1) {
+        // Skip header line
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        if parts.len() < 5 {
+            continue;
+        }
+
+        let (Some((local_addr, local_port)), Some((remote_addr, remote_port))) =
+            (parse_ss_endpoint(parts[3]), parse_ss_endpoint(parts[4]))
+        else {
+            continue;
+        };
+
+        let process = parts.get(5).and_then(|users| parse_ss_process(users));
+
+        connections.push(Connection {
+            local_addr: local_addr.into(),
+            local_port,
+            remote_addr: remote_addr.into(),
+            remote_port,
+            state: parse_ss_state(parts[0]),
+            pid: process.as_ref().map(|(_, pid)| *pid),
+            process_name: process.map(|(name, _)| name.into()),
+            ..Default::default()
+        });
+    }
+
+    connections
+}
+
+/// Parse an ss endpoint: "127.0.0.1:22", "[::1]:22", "*:22", "0.0.0.0:*",
+/// "10.0.0.1%eth0:53"
+pub(super) fn parse_ss_endpoint(endpoint: &str) -> Option<(String, u16)> {
+    let (addr, port) = endpoint.rsplit_once(':')?;
+    let port = if port == "*" { 0 } else { port.parse().ok()? };
+
+    let addr = addr.trim_start_matches('[').trim_end_matches(']');
+    // Drop the interface scope ("fe80::1%eth0")
+    let addr = addr.split('%').next().unwrap_or(addr);
+    let addr = if addr == "*" { "0.0.0.0" } else { addr };
+
+    Some((addr.to_string(), port))
+}
+
+/// Extract the first process from `users:(("sshd",pid=1234,fd=3),...)`
+fn parse_ss_process(users: &str) -> Option<(String, i32)> {
+    let start = users.find("((\"")? + 3;
+    let rest = &users[start..];
+    let name_end = rest.find('"')?;
+    let name = &rest[..name_end];
+
+    let pid_start = rest.find("pid=")? + 4;
+    let pid_str: String = rest[pid_start..]
+        .chars()
+        .take_while(|c| c.is_ascii_digit())
+        .collect();
+    Some((name.to_string(), pid_str.parse().ok()?))
+}
+
+fn parse_ss_state(state: &str) -> ConnectionState {
+    match state {
+        "ESTAB" => ConnectionState::Established,
+        "SYN-SENT" => ConnectionState::SynSent,
+        "SYN-RECV" => ConnectionState::SynRecv,
+        "FIN-WAIT-1" => ConnectionState::FinWait1,
+        "FIN-WAIT-2" => ConnectionState::FinWait2,
+        "TIME-WAIT" => ConnectionState::TimeWait,
+        "CLOSE" | "UNCONN" => ConnectionState::Close,
+        "CLOSE-WAIT" => ConnectionState::CloseWait,
+        "LAST-ACK" => ConnectionState::LastAck,
+        "LISTEN" => ConnectionState::Listen,
+        "CLOSING" => ConnectionState::Closing,
+        _ => ConnectionState::Unknown,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_ss_output() {
+        let output = "\
+State  Recv-Q Send-Q Local Address:Port  Peer Address:Port Process
+LISTEN 0      128          0.0.0.0:22         0.0.0.0:*     users:((\"sshd\",pid=812,fd=3))
+ESTAB  0      0          10.0.0.5:22         10.0.0.9:51514 users:((\"sshd\",pid=2001,fd=4),(\"sshd\",pid=2040,fd=4))
+TIME-WAIT 0   0      [::1]:8080            [::1]:40000
+";
+        let conns = parse_ss_output(output);
+        assert_eq!(conns.len(), 3);
+
+        assert_eq!(conns[0].state, ConnectionState::Listen);
+        assert_eq!(conns[0].local_port, 22);
+        assert_eq!(conns[0].remote_port, 0);
+        assert_eq!(conns[0].process_name.as_deref(), Some("sshd"));
+        assert_eq!(conns[0].pid, Some(812));
+
+        assert_eq!(&*conns[1].remote_addr, "10.0.0.9");
+        assert_eq!(conns[1].pid, Some(2001));
+
+        assert_eq!(&*conns[2].local_addr, "::1");
+        assert_eq!(conns[2].state, ConnectionState::TimeWait);
+        assert_eq!(conns[2].pid, None);
+    }
+
+    #[test]
+    fn test_parse_ss_endpoint() {
+        assert_eq!(parse_ss_endpoint("*:22"), Some(("0.0.0.0".to_string(), 22)));
+        assert_eq!(
+            parse_ss_endpoint("[fe80::1%eth0]:546"),
+            Some(("fe80::1".to_string(), 546))
+        );
+        assert_eq!(parse_ss_endpoint("garbage"), None);
+    }
+}
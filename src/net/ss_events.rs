@@ -0,0 +1,132 @@
+// Socket destroy events from ss(8)
+//
+// `ss -tnE` subscribes to the kernel's sock_diag destroy notifications and
+// prints every TCP socket as it is destroyed, including the ones that
+// opened and closed between two collections and so never appear in a
+// snapshot. The subscription needs CAP_NET_ADMIN (Linux only); without
+// it ss exits right away. ss buffers its output when piped, so it runs
+// under `stdbuf -oL` when that is installed.
+
+use super::ss::parse_ss_endpoint;
+use super::{Connection, ConnectionState};
+use std::io::{self, BufRead, BufReader};
+use std::process::{Child, Command, Stdio};
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+
+/// Running `ss -tnE` and the sockets it reported destroyed
+pub struct DestroyWatcher {
+    child: Child,
+    destroyed: Receiver<Connection>,
+}
+
+impl DestroyWatcher {
+    /// Start ss and a thread reading its output
+    pub fn spawn() -> io::Result<Self> {
+        let mut child = match command(true).spawn() {
+            Err(e) if e.kind() == io::ErrorKind::NotFound => command(false).spawn(),
+            spawned => spawned,
+        }
+        .map_err(|e| io::Error::new(e.kind(), format!("Cannot run ss -E: {}", e)))?;
+        let stdout = child.stdout.take().expect("ss stdout is piped");
+        let (tx, destroyed) = mpsc::channel();
+        thread::spawn(move || {
+            for line in BufReader::new(stdout).lines() {
+                let Ok(line) = line else {
+                    break;
+                };
+                if let Some(conn) = parse_destroyed(&line) {
+                    if tx.send(conn).is_err() {
+                        break;
+                    }
+                }
+            }
+        });
+        Ok(Self { child, destroyed })
+    }
+
+    /// Sockets destroyed since the last call
+    pub fn drain(&self) -> Vec<Connection> {
+        self.destroyed.try_iter().collect()
+    }
+
+    /// Why ss stopped, once it has
+    pub fn exit_status(&mut self) -> Option<String> {
+        match self.child.try_wait() {
+            Ok(Some(status)) => Some(status.to_string()),
+            Ok(None) => None,
+            Err(e) => Some(e.to_string()),
+        }
+    }
+}
+
+impl Drop for DestroyWatcher {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+/// `ss -tnE`, line-buffered through stdbuf or not
+fn command(line_buffered: bool) -> Command {
+    let mut command = if line_buffered {
+        let mut command = Command::new("stdbuf");
+        command.args(["-oL", "ss"]);
+        command
+    } else {
+        Command::new("ss")
+    };
+    command
+        .arg("-tnE")
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null());
+    command
+}
+
+/// Parse one line of `ss -tnE` output; None for the header and for
+/// listeners, which have no peer
+/// Format: State Recv-Q Send-Q Local:Port Peer:Port
+fn parse_destroyed(line: &str) -> Option<Connection> {
+    let parts: Vec<&str> = line.split_whitespace().collect();
+    if parts.len() < 5 || parts[0] == "State" {
+        return None;
+    }
+    let (local_addr, local_port) = parse_ss_endpoint(parts[3])?;
+    let (remote_addr, remote_port) = parse_ss_endpoint(parts[4])?;
+    if remote_port == 0 {
+        return None;
+    }
+    Some(Connection {
+        local_addr: local_addr.into(),
+        local_port,
+        remote_addr: remote_addr.into(),
+        remote_port,
+        state: ConnectionState::Close,
+        ..Default::default()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_destroyed() {
+        assert!(parse_destroyed(
+            "State  Recv-Q Send-Q Local Address:Port  Peer Address:Port Process"
+        )
+        .is_none());
+        assert!(
+            parse_destroyed("UNCONN 0      0          127.0.0.1:33267      0.0.0.0:*").is_none()
+        );
+
+        let conn = parse_destroyed("UNCONN 1      0          10.0.0.5:50000    93.184.216.34:443")
+            .unwrap();
+        assert_eq!(&*conn.local_addr, "10.0.0.5");
+        assert_eq!(conn.local_port, 50000);
+        assert_eq!(&*conn.remote_addr, "93.184.216.34");
+        assert_eq!(conn.remote_port, 443);
+        assert_eq!(conn.state, ConnectionState::Close);
+    }
+}
@@ -0,0 +1,174 @@
+// Multipath TCP subflows and TCP Fast Open
+//
+// An MPTCP connection shows up in the socket tables as one plain TCP socket
+// per subflow, which would otherwise look like duplicate connections to the
+// same peer. The kernel reports each subflow's MPTCP token pair (shared by
+// all subflows of one connection) and whether a handshake carried data
+// (Fast Open) through sock_diag; `ss -tin state connected` prints both:
+//
+//   ESTAB 0 0 10.0.0.5:40000 93.184.216.34:443
+//        ts sack fastopen cubic wscale:7,7 ... tcp-ulp-mptcp flags:Mec token:0000(id:0)/5f2a1c3b(id:0) ...
+//
+// Linux only; elsewhere nothing is attached.
+
+use super::Connection;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+#[cfg(target_os = "linux")]
+use std::process::Command;
+#[cfg(target_os = "linux")]
+use tracing::debug;
+
+/// (local addr, local port, remote addr, remote port) of an ss socket line
+type SocketKey = (String, u16, String, u16);
+
+/// What ss tells about one socket beyond plain TCP
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TransportFeatures {
+    /// MPTCP token pair without the subflow ids ("0000/5f2a1c3b")
+    pub mptcp: Option<Arc<str>>,
+    pub fast_open: bool,
+}
+
+/// Read the MPTCP and Fast Open features of every connected TCP socket;
+/// Err when ss cannot be run
+#[cfg(target_os = "linux")]
+pub fn read_transport_features() -> std::io::Result<HashMap<SocketKey, TransportFeatures>> {
+    let output = Command::new("ss")
+        .args(["-tin", "state", "connected"])
+        .output()?;
+    if !output.status.success() {
+        debug!(status = %output.status, "ss -tin failed");
+        return Ok(HashMap::new());
+    }
+    Ok(parse_transport_features(&String::from_utf8_lossy(
+        &output.stdout,
+    )))
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn read_transport_features() -> std::io::Result<HashMap<SocketKey, TransportFeatures>> {
+    Ok(HashMap::new())
+}
+
+/// Set `mptcp` and `fast_open` on the connections listed in `features`
+pub fn attach_transport_features(
+    conns: &mut [Connection],
+    features: &HashMap<SocketKey, TransportFeatures>,
+) {
+    if features.is_empty() {
+        return;
+    }
+    for conn in conns.iter_mut() {
+        let key = (
+            conn.local_addr.to_string(),
+            conn.local_port,
+            conn.remote_addr.to_string(),
+            conn.remote_port,
+        );
+        if let Some(found) = features.get(&key) {
+            conn.mptcp = found.mptcp.clone();
+            conn.fast_open = found.fast_open;
+        }
+    }
+}
+
+/// Parse `ss -tin` output, keeping only the sockets with an MPTCP token
+/// or Fast Open
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_transport_features(output: &str) -> HashMap<SocketKey, TransportFeatures> {
+    let mut features = HashMap::new();
+    let mut socket: Option<SocketKey> = None;
+    for line in output.lines() {
+        if !line.starts_with(char::is_whitespace) {
+            // A socket line; its TCP info follows on an indented line
+            socket = socket_key(line);
+            continue;
+        }
+        let Some(key) = socket.take() else {
+            continue;
+        };
+        let found = TransportFeatures {
+            mptcp: line
+                .split_whitespace()
+                .find_map(|field| field.strip_prefix("token:"))
+                .map(|token| strip_subflow_ids(token).into()),
+            fast_open: line.split_whitespace().any(|field| field == "fastopen"),
+        };
+        if found != TransportFeatures::default() {
+            features.insert(key, found);
+        }
+    }
+    features
+}
+
+/// Endpoints of an ss socket line ("ESTAB 0 0 10.0.0.5:40000 10.1.0.1:80")
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn socket_key(line: &str) -> Option<SocketKey> {
+    let mut parts = line.split_whitespace().skip(3);
+    let (local_addr, local_port) = super::ss::parse_ss_endpoint(parts.next()?)?;
+    let (remote_addr, remote_port) = super::ss::parse_ss_endpoint(parts.next()?)?;
+    Some((local_addr, local_port, remote_addr, remote_port))
+}
+
+/// "0000(id:0)/5f2a1c3b(id:2)" -> "0000/5f2a1c3b"
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn strip_subflow_ids(token: &str) -> String {
+    token
+        .split('/')
+        .map(|part| part.split('(').next().unwrap_or(part))
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OUTPUT: &str = "\
+State Recv-Q Send-Q Local Address:Port  Peer Address:Port Process
+ESTAB 0      0          10.0.0.5:40000 93.184.216.34:443
+\t ts sack cubic wscale:7,7 rto:204 tcp-ulp-mptcp flags:Mec token:0000(id:0)/5f2a1c3b(id:0) seq:1
+ESTAB 0      0      192.168.1.5:40002 93.184.216.35:443
+\t ts sack cubic wscale:7,7 rto:204 tcp-ulp-mptcp flags:Jjec token:0000(id:2)/5f2a1c3b(id:1) seq:1
+ESTAB 0      0          10.0.0.5:40004     10.1.0.1:80
+\t ts sack fastopen cubic wscale:7,7 rto:204
+ESTAB 0      0          10.0.0.5:40006     10.1.0.1:22
+\t ts sack cubic wscale:7,7 rto:204
+";
+
+    #[test]
+    fn test_parse_transport_features() {
+        let features = parse_transport_features(OUTPUT);
+        assert_eq!(features.len(), 3);
+        let key = |la: &str, lp, ra: &str, rp| (la.to_string(), lp, ra.to_string(), rp);
+
+        let first = &features[&key("10.0.0.5", 40000, "93.184.216.34", 443)];
+        let second = &features[&key("192.168.1.5", 40002, "93.184.216.35", 443)];
+        assert_eq!(first.mptcp.as_deref(), Some("0000/5f2a1c3b"));
+        // Subflows of one connection share the token whatever their ids
+        assert_eq!(first.mptcp, second.mptcp);
+        assert!(!first.fast_open);
+
+        let tfo = &features[&key("10.0.0.5", 40004, "10.1.0.1", 80)];
+        assert_eq!(tfo.mptcp, None);
+        assert!(tfo.fast_open);
+    }
+
+    #[test]
+    fn test_attach_transport_features() {
+        let conn = |local_port: u16| Connection {
+            local_addr: "10.0.0.5".into(),
+            local_port,
+            remote_addr: "10.1.0.1".into(),
+            remote_port: if local_port == 40004 { 80 } else { 22 },
+            ..Default::default()
+        };
+        let mut conns = vec![conn(40004), conn(40006)];
+        attach_transport_features(&mut conns, &parse_transport_features(OUTPUT));
+        assert!(conns[0].fast_open);
+        assert!(!conns[1].fast_open);
+        assert_eq!(conns[1].mptcp, None);
+    }
+}
@@ -0,0 +1,87 @@
+// Connected UDP flows on port 443
+//
+// QUIC (HTTP/3) runs over UDP, which the TCP backends never report, so a
+// growing share of web traffic would not show up at all. Connected UDP
+// sockets, which QUIC clients (and servers using a socket per connection)
+// talk through, are read from /proc/net/udp and /proc/net/udp6 when either
+// end is port 443; app/quic.rs decides which of them are likely QUIC.
+//
+// UDP has no states: a connected socket is reported as ESTABLISHED, the
+// way the kernel lists it.
+
+use super::{Connection, ConnectionState, ProcSocketEntry, Protocol, SocketKey, QUIC_PORT};
+use std::collections::HashMap;
+use std::fs;
+
+/// Connected UDP sockets with either end on QUIC_PORT
+pub fn read_udp_flows() -> Vec<Connection> {
+    let mut entries = HashMap::new();
+    for (path, is_ipv6) in [("/proc/net/udp", false), ("/proc/net/udp6", true)] {
+        if let Ok(content) = fs::read_to_string(path) {
+            super::parse_proc_net_tcp(&content, &mut entries, is_ipv6);
+        }
+    }
+    let mut flows = udp_flows(entries);
+    super::fill_user_names(&mut flows);
+    flows
+}
+
+/// Flows among the parsed /proc/net/udp rows, in address order
+fn udp_flows(entries: HashMap<SocketKey, ProcSocketEntry>) -> Vec<Connection> {
+    let mut flows: Vec<Connection> = entries
+        .into_iter()
+        .filter(|((_, local_port, _, remote_port), _)| {
+            *remote_port != 0 && (*local_port == QUIC_PORT || *remote_port == QUIC_PORT)
+        })
+        .map(
+            |((local_addr, local_port, remote_addr, remote_port), entry)| Connection {
+                local_addr,
+                local_port,
+                remote_addr,
+                remote_port,
+                state: ConnectionState::Established,
+                inode: Some(entry.inode),
+                uid: Some(entry.uid),
+                protocol: Protocol::Udp,
+                ..Default::default()
+            },
+        )
+        .collect();
+    flows.sort_by(|a, b| {
+        (&a.local_addr, a.local_port, &a.remote_addr, a.remote_port).cmp(&(
+            &b.local_addr,
+            b.local_port,
+            &b.remote_addr,
+            b.remote_port,
+        ))
+    });
+    flows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_udp_flows_keep_connected_port_443() {
+        // 10.0.0.5:51000 -> 93.184.216.34:443 (connected), a DNS flow and
+        // an unconnected socket bound to 443
+        let content = "   sl  local_address rem_address   st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode ref pointer drops
+  100: 0500000A:C738 22D8B85D:01BB 01 00000000:00000000 00:00000000 00000000  1000        0 4242 2 0000000000000000 0
+  101: 0500000A:C739 08080808:0035 01 00000000:00000000 00:00000000 00000000  1000        0 4243 2 0000000000000000 0
+  102: 00000000:01BB 00000000:0000 07 00000000:00000000 00:00000000 00000000     0        0 4244 2 0000000000000000 0
+";
+        let mut entries = HashMap::new();
+        super::super::parse_proc_net_tcp(content, &mut entries, false);
+        let flows = udp_flows(entries);
+        assert_eq!(flows.len(), 1);
+        let flow = &flows[0];
+        assert_eq!((&*flow.local_addr, flow.local_port), ("10.0.0.5", 51000));
+        assert_eq!(
+            (&*flow.remote_addr, flow.remote_port),
+            ("93.184.216.34", 443)
+        );
+        assert_eq!(flow.protocol, Protocol::Udp);
+        assert_eq!(flow.inode, Some(4242));
+    }
+}
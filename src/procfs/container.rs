@@ -0,0 +1,187 @@
+// Container name resolution
+//
+// Maps processes to the containers they run in. The container ID is taken
+// from /proc/<pid>/cgroup, then resolved to a human-readable name with a
+// read-only `GET /containers/<id>/json` call against the Docker or Podman
+// API socket. Names are cached per container ID for the whole session.
+
+use std::collections::HashMap;
+
+#[cfg(target_os = "linux")]
+use std::fs;
+#[cfg(target_os = "linux")]
+use std::io::{Read, Write};
+#[cfg(target_os = "linux")]
+use std::os::unix::net::UnixStream;
+#[cfg(target_os = "linux")]
+use std::time::Duration;
+#[cfg(target_os = "linux")]
+use tracing::debug;
+
+/// Container engine API sockets probed in order (Docker, then Podman)
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+const CONTAINER_API_SOCKETS: [&str; 3] = [
+    "/var/run/docker.sock",
+    "/run/podman/podman.sock",
+    "/run/user/0/podman/podman.sock",
+];
+
+/// Read/write timeout for container API calls
+#[cfg(target_os = "linux")]
+const CONTAINER_API_TIMEOUT: Duration = Duration::from_millis(200);
+
+/// Length of the short container ID used when no name can be resolved
+const SHORT_ID_LEN: usize = 12;
+
+/// Cached container resolver
+#[derive(Debug, Clone, Default)]
+pub struct ContainerResolver {
+    /// Container ID -> display name (name, or short ID if the API is unreachable)
+    names: HashMap<String, String>,
+}
+
+impl ContainerResolver {
+    /// Create an empty resolver
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Resolve the container name for `pid`, or None if it is not containerized
+    pub fn name_for_pid(&mut self, pid: i32) -> Option<String> {
+        let id = read_container_id(pid)?;
+        if let Some(name) = self.names.get(&id) {
+            return Some(name.clone());
+        }
+
+        let name =
+            query_container_name(&id).unwrap_or_else(|| id.chars().take(SHORT_ID_LEN).collect());
+        self.names.insert(id, name.clone());
+        Some(name)
+    }
+}
+
+/// Read the container ID for `pid` from /proc/<pid>/cgroup
+#[cfg(target_os = "linux")]
+fn read_container_id(pid: i32) -> Option<String> {
+    let content = fs::read_to_string(format!("/proc/{}/cgroup", pid)).ok()?;
+    parse_container_id(&content)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn read_container_id(_pid: i32) -> Option<String> {
+    None
+}
+
+/// Extract a 64-hex-digit container ID from cgroup file contents
+///
+/// Handles the common layouts:
+/// - cgroup v1 Docker: `.../docker/<id>`
+/// - systemd driver: `.../docker-<id>.scope`, `libpod-<id>.scope`,
+///   `cri-containerd-<id>.scope`
+/// - Kubernetes: `/kubepods/.../<id>`
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_container_id(cgroup: &str) -> Option<String> {
+    for line in cgroup.lines() {
+        // Format: hierarchy-ID:controller-list:cgroup-path
+        let path = line.splitn(3, ':').nth(2).unwrap_or(line);
+        for segment in path.rsplit('/') {
+            let segment = segment.strip_suffix(".scope").unwrap_or(segment);
+            let candidate = segment.rsplit('-').next().unwrap_or(segment);
+            if candidate.len() == 64 && candidate.chars().all(|c| c.is_ascii_hexdigit()) {
+                return Some(candidate.to_string());
+            }
+        }
+    }
+    None
+}
+
+/// Ask the container engine for the container's name
+#[cfg(target_os = "linux")]
+fn query_container_name(id: &str) -> Option<String> {
+    for socket in CONTAINER_API_SOCKETS {
+        let Ok(mut stream) = UnixStream::connect(socket) else {
+            continue;
+        };
+        let _ = stream.set_read_timeout(Some(CONTAINER_API_TIMEOUT));
+        let _ = stream.set_write_timeout(Some(CONTAINER_API_TIMEOUT));
+
+        // HTTP/1.0 so the engine closes the connection after responding
+        let request = format!(
+            "GET /containers/{}/json HTTP/1.0\r\nHost: localhost\r\n\r\n",
+            id
+        );
+        if stream.write_all(request.as_bytes()).is_err() {
+            continue;
+        }
+
+        let mut response = String::new();
+        if let Err(e) = stream.read_to_string(&mut response) {
+            debug!(socket = socket, error = %e, "Container API read failed");
+            continue;
+        }
+        if let Some(name) = parse_container_name(&response) {
+            return Some(name);
+        }
+    }
+    None
+}
+
+#[cfg(not(target_os = "linux"))]
+fn query_container_name(_id: &str) -> Option<String> {
+    None
+}
+
+/// Extract the `"Name"` field from a container inspect response
+///
+/// Docker reports names with a leading slash ("/cache-1").
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_container_name(response: &str) -> Option<String> {
+    let body = response.split("\r\n\r\n").nth(1).unwrap_or(response);
+    let start = body.find("\"Name\":\"")? + "\"Name\":\"".len();
+    let end = body[start..].find('"')? + start;
+    let name = body[start..end].trim_start_matches('/');
+    if name.is_empty() {
+        None
+    } else {
+        Some(name.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ID: &str = "3f4e5d6c7b8a99887766554433221100ffeeddccbbaa00112233445566778899";
+
+    #[test]
+    fn test_parse_container_id_layouts() {
+        let cgroup_v1 = format!("12:memory:/docker/{}\n", ID);
+        assert_eq!(parse_container_id(&cgroup_v1), Some(ID.to_string()));
+
+        let systemd = format!("0::/system.slice/docker-{}.scope\n", ID);
+        assert_eq!(parse_container_id(&systemd), Some(ID.to_string()));
+
+        let podman = format!("0::/machine.slice/libpod-{}.scope/container\n", ID);
+        assert_eq!(parse_container_id(&podman), Some(ID.to_string()));
+
+        let kube = format!("0::/kubepods/burstable/pod1234/{}\n", ID);
+        assert_eq!(parse_container_id(&kube), Some(ID.to_string()));
+    }
+
+    #[test]
+    fn test_parse_container_id_host_process() {
+        assert_eq!(parse_container_id("0::/user.slice/user-1000.slice\n"), None);
+        assert_eq!(parse_container_id(""), None);
+    }
+
+    #[test]
+    fn test_parse_container_name() {
+        let response = "HTTP/1.0 200 OK\r\nContent-Type: application/json\r\n\r\n\
+                        {\"Id\":\"abc\",\"Name\":\"/cache-1\",\"State\":{}}";
+        assert_eq!(parse_container_name(response), Some("cache-1".to_string()));
+        assert_eq!(
+            parse_container_name("HTTP/1.0 404 Not Found\r\n\r\n{}"),
+            None
+        );
+    }
+}
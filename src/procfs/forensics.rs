@@ -0,0 +1,185 @@
+// Process forensics for the Soul Inspector
+//
+// Collects what an incident responder asks about a process first: which
+// binary it runs (and whether that was deleted from disk since), its
+// command line, parent, cgroup and the SHA-256 of the executable.
+//
+// Read-only: /proc is read and `sha256sum` is run on /proc/<pid>/exe,
+// which still works after the binary was deleted. Hashes are cached per
+// executable path, so each binary is hashed at most once.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+#[cfg(target_os = "linux")]
+use std::fs;
+#[cfg(target_os = "linux")]
+use std::process::Command;
+#[cfg(target_os = "linux")]
+use tracing::debug;
+
+/// What /proc tells about one process
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProcessForensics {
+    pub pid: i32,
+    pub ppid: Option<i32>,
+    /// Executable path, without the kernel's " (deleted)" suffix
+    pub exe: Option<PathBuf>,
+    /// Whether the executable was removed from disk after the process started
+    pub exe_deleted: bool,
+    /// Arguments joined with spaces
+    pub cmdline: Option<String>,
+    /// cgroup path (the unified hierarchy's, when there is one)
+    pub cgroup: Option<String>,
+    /// Hex SHA-256 of the executable
+    pub sha256: Option<String>,
+}
+
+/// Reads process forensics, caching executable hashes
+#[derive(Debug, Clone, Default)]
+pub struct ForensicsReader {
+    /// Executable path -> SHA-256 (None when hashing failed)
+    hashes: HashMap<PathBuf, Option<String>>,
+}
+
+impl ForensicsReader {
+    /// Forensics of `pid`; fields that cannot be read (process gone,
+    /// permission denied, not Linux) are left empty
+    pub fn read(&mut self, pid: i32) -> ProcessForensics {
+        let mut forensics = read_proc(pid);
+        if let Some(ref exe) = forensics.exe {
+            // A deleted binary's path may be reused by a new one
+            forensics.sha256 = if forensics.exe_deleted {
+                hash_exe(pid)
+            } else {
+                self.hashes
+                    .entry(exe.clone())
+                    .or_insert_with(|| hash_exe(pid))
+                    .clone()
+            };
+        }
+        forensics
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn read_proc(pid: i32) -> ProcessForensics {
+    let proc_file = |name: &str| fs::read_to_string(format!("/proc/{}/{}", pid, name)).ok();
+    let exe = fs::read_link(format!("/proc/{}/exe", pid))
+        .ok()
+        .map(|target| target.to_string_lossy().into_owned());
+    let exe_deleted = exe.as_ref().is_some_and(|e| e.ends_with(" (deleted)"));
+    ProcessForensics {
+        pid,
+        ppid: proc_file("stat").and_then(|stat| super::parse_stat_ppid(&stat)),
+        exe: exe.map(|e| PathBuf::from(e.trim_end_matches(" (deleted)"))),
+        exe_deleted,
+        cmdline: proc_file("cmdline").and_then(|c| parse_cmdline(&c)),
+        cgroup: proc_file("cgroup").and_then(|c| parse_cgroup(&c)),
+        sha256: None,
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn read_proc(pid: i32) -> ProcessForensics {
+    ProcessForensics {
+        pid,
+        ..Default::default()
+    }
+}
+
+/// Hash the executable of `pid` with `sha256sum`
+#[cfg(target_os = "linux")]
+fn hash_exe(pid: i32) -> Option<String> {
+    let output = Command::new("sha256sum")
+        .arg(format!("/proc/{}/exe", pid))
+        .output();
+    match output {
+        Ok(output) if output.status.success() => {
+            parse_sha256sum(&String::from_utf8_lossy(&output.stdout))
+        }
+        Ok(_) => None,
+        Err(e) => {
+            debug!(error = %e, "sha256sum failed");
+            None
+        }
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn hash_exe(_pid: i32) -> Option<String> {
+    None
+}
+
+/// /proc/<pid>/cmdline is NUL-separated; None for kernel threads
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_cmdline(raw: &str) -> Option<String> {
+    let args: Vec<&str> = raw.split('\0').filter(|a| !a.is_empty()).collect();
+    (!args.is_empty()).then(|| args.join(" "))
+}
+
+/// Pick the unified ("0::/path") hierarchy from /proc/<pid>/cgroup, falling
+/// back to the first cgroup v1 line
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_cgroup(raw: &str) -> Option<String> {
+    let path = |line: &str| line.splitn(3, ':').nth(2).map(str::to_string);
+    raw.lines()
+        .find(|line| line.starts_with("0::"))
+        .or_else(|| raw.lines().next())
+        .and_then(path)
+        .filter(|p| !p.is_empty())
+}
+
+/// First field of `sha256sum` output ("<hex>  <path>")
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_sha256sum(output: &str) -> Option<String> {
+    let hash = output.split_whitespace().next()?;
+    (hash.len() == 64 && hash.chars().all(|c| c.is_ascii_hexdigit())).then(|| hash.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_cmdline() {
+        assert_eq!(
+            parse_cmdline("/usr/sbin/sshd\0-D\0").as_deref(),
+            Some("/usr/sbin/sshd -D")
+        );
+        assert_eq!(parse_cmdline(""), None);
+    }
+
+    #[test]
+    fn test_parse_cgroup() {
+        assert_eq!(
+            parse_cgroup("0::/system.slice/ssh.service\n").as_deref(),
+            Some("/system.slice/ssh.service")
+        );
+        assert_eq!(
+            parse_cgroup("12:pids:/docker/abc\n11:cpu:/docker/abc\n").as_deref(),
+            Some("/docker/abc")
+        );
+        assert_eq!(parse_cgroup(""), None);
+    }
+
+    #[test]
+    fn test_parse_sha256sum() {
+        let hash = "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855";
+        assert_eq!(
+            parse_sha256sum(&format!("{}  /proc/1/exe\n", hash)).as_deref(),
+            Some(hash)
+        );
+        assert_eq!(parse_sha256sum("sha256sum: permission denied"), None);
+    }
+
+    #[test]
+    fn test_read_own_process() {
+        let mut reader = ForensicsReader::default();
+        let forensics = reader.read(std::process::id() as i32);
+        if cfg!(target_os = "linux") {
+            assert!(forensics.exe.is_some());
+            assert!(forensics.ppid.is_some());
+        }
+    }
+}
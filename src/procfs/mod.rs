@@ -2,16 +2,23 @@
 // Read-only operations following ntomb security-domain guidelines
 // Maps network connections to their owning processes using socket inodes
 
+pub mod container;
+pub mod forensics;
+pub mod package;
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+pub mod users;
+
 use crate::net::Connection;
+use std::collections::{HashMap, HashSet};
 use std::io;
 
-#[cfg(target_os = "linux")]
-use std::collections::HashMap;
 #[cfg(target_os = "linux")]
 use std::fs;
 #[cfg(target_os = "linux")]
 use std::path::Path;
 #[cfg(target_os = "linux")]
+use std::sync::Arc;
+#[cfg(target_os = "linux")]
 use tracing::{debug, warn};
 
 /// Map process information to Connections using /proc on Linux
@@ -46,7 +53,7 @@ pub fn attach_process_info(conns: &mut [Connection]) -> io::Result<()> {
             if let Some(inode) = conn.inode {
                 if let Some((pid, name)) = inode_map.get(&inode) {
                     conn.pid = Some(*pid);
-                    conn.process_name = Some(name.clone());
+                    conn.process_name = Some(Arc::clone(name));
                 }
             }
         }
@@ -63,7 +70,7 @@ pub fn attach_process_info(conns: &mut [Connection]) -> io::Result<()> {
 /// Extract socket inodes from /proc/<pid>/fd/* and build a map
 /// Returns HashMap<inode, (pid, process_name)>
 #[cfg(target_os = "linux")]
-fn build_inode_pid_map() -> io::Result<HashMap<u64, (i32, String)>> {
+fn build_inode_pid_map() -> io::Result<HashMap<u64, (i32, Arc<str>)>> {
     let mut map = HashMap::new();
     let proc_path = Path::new("/proc");
 
@@ -90,7 +97,7 @@ fn build_inode_pid_map() -> io::Result<HashMap<u64, (i32, String)>> {
             if let Some(pid_str) = filename.to_str() {
                 if let Ok(pid) = pid_str.parse::<i32>() {
                     // Read process name from /proc/<pid>/comm
-                    let process_name = read_process_name(pid);
+                    let process_name: Arc<str> = read_process_name(pid).into();
 
                     // Scan /proc/<pid>/fd/* for socket inodes
                     let fd_path = path.join("fd");
@@ -107,7 +114,7 @@ fn build_inode_pid_map() -> io::Result<HashMap<u64, (i32, String)>> {
                                             // Extract inode number
                                             let inode_str = &target_str[8..target_str.len() - 1];
                                             if let Ok(inode) = inode_str.parse::<u64>() {
-                                                map.insert(inode, (pid, process_name.clone()));
+                                                map.insert(inode, (pid, Arc::clone(&process_name)));
                                             }
                                         }
                                     }
@@ -148,6 +155,81 @@ fn read_process_name(pid: i32) -> String {
     }
 }
 
+/// Build a map of pid -> parent pid from /proc/<pid>/stat
+/// Returns an empty map on non-Linux systems
+///
+/// Processes that exit or cannot be read during the scan are skipped.
+pub fn build_ppid_map() -> HashMap<i32, i32> {
+    #[cfg(not(target_os = "linux"))]
+    {
+        HashMap::new()
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        let mut map = HashMap::new();
+        let entries = match fs::read_dir("/proc") {
+            Ok(entries) => entries,
+            Err(e) => {
+                warn!(error = %e, "Cannot read /proc directory");
+                return map;
+            }
+        };
+
+        for entry in entries.flatten() {
+            let Some(pid) = entry
+                .file_name()
+                .to_str()
+                .and_then(|s| s.parse::<i32>().ok())
+            else {
+                continue;
+            };
+            if let Ok(stat) = fs::read_to_string(entry.path().join("stat")) {
+                if let Some(ppid) = parse_stat_ppid(&stat) {
+                    map.insert(pid, ppid);
+                }
+            }
+        }
+
+        debug!("build_ppid_map: Found {} processes", map.len());
+        map
+    }
+}
+
+/// Parse the parent pid (field 4) from a /proc/<pid>/stat line
+///
+/// The comm field (2) is wrapped in parentheses and may itself contain
+/// spaces or ')' characters, so fields are counted from the last ')'.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_stat_ppid(stat: &str) -> Option<i32> {
+    let after_comm = &stat[stat.rfind(')')? + 1..];
+    // Remaining fields: state ppid ...
+    after_comm.split_whitespace().nth(1)?.parse().ok()
+}
+
+/// Collect `root` and all of its descendant pids using a pid -> ppid map
+pub fn descendant_pids(root: i32, ppid_map: &HashMap<i32, i32>) -> HashSet<i32> {
+    // Invert the map once so the walk is O(n)
+    let mut children: HashMap<i32, Vec<i32>> = HashMap::new();
+    for (&pid, &ppid) in ppid_map {
+        children.entry(ppid).or_default().push(pid);
+    }
+
+    let mut result = HashSet::from([root]);
+    let mut stack = vec![root];
+    while let Some(pid) = stack.pop() {
+        if let Some(kids) = children.get(&pid) {
+            for &kid in kids {
+                // insert() guards against cycles in a racy snapshot
+                if result.insert(kid) {
+                    stack.push(kid);
+                }
+            }
+        }
+    }
+    result
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -162,14 +244,15 @@ mod tests {
     #[test]
     fn test_attach_process_info_no_inode() {
         let mut conns = vec![Connection {
-            local_addr: "127.0.0.1".to_string(),
+            local_addr: "127.0.0.1".into(),
             local_port: 8080,
-            remote_addr: "127.0.0.1".to_string(),
+            remote_addr: "127.0.0.1".into(),
             remote_port: 9090,
             state: crate::net::ConnectionState::Established,
             inode: None,
             pid: None,
             process_name: None,
+            ..Default::default()
         }];
 
         let result = attach_process_info(&mut conns);
@@ -178,6 +261,26 @@ mod tests {
         assert!(conns[0].pid.is_none());
     }
 
+    #[test]
+    fn test_parse_stat_ppid() {
+        assert_eq!(parse_stat_ppid("1234 (nginx) S 1 1234 1234 0 -1"), Some(1));
+        // comm containing spaces and parentheses
+        assert_eq!(parse_stat_ppid("42 (my (odd) proc) R 7 42 42"), Some(7));
+        assert_eq!(parse_stat_ppid("garbage"), None);
+    }
+
+    #[test]
+    fn test_descendant_pids() {
+        // 1 -> 100 -> {101, 102}, 102 -> 103; 200 is unrelated
+        let ppid_map = HashMap::from([(100, 1), (101, 100), (102, 100), (103, 102), (200, 1)]);
+
+        let tree = descendant_pids(100, &ppid_map);
+        assert_eq!(tree, HashSet::from([100, 101, 102, 103]));
+
+        // Leaf process only contains itself
+        assert_eq!(descendant_pids(103, &ppid_map), HashSet::from([103]));
+    }
+
     #[cfg(target_os = "linux")]
     #[test]
     fn test_build_inode_pid_map() {
@@ -0,0 +1,420 @@
+// Package ownership lookup
+//
+// Resolves which distro package owns a process executable using the
+// system package manager (`dpkg -S` on Debian/Ubuntu, `rpm -qf` on
+// Fedora/RHEL). Executables that no package claims are reported as
+// UNPACKAGED, which quickly surfaces dropped or hand-built binaries.
+//
+// Read-only: only query commands are executed, and results are cached
+// per executable path so each binary is looked up at most once. `dpkg -S`
+// scans every package's file list and can take seconds, so queries run on
+// a background thread; the owner reads "resolving…" until it answers.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+
+#[cfg(target_os = "linux")]
+use std::fs;
+#[cfg(target_os = "linux")]
+use std::process::{Command, Stdio};
+#[cfg(target_os = "linux")]
+use tracing::debug;
+
+/// Environment variable to disable package lookups ("0", "false", "off")
+pub const PACKAGE_LOOKUP_ENV: &str = "NTOMB_PACKAGE_LOOKUP";
+
+/// Supported package managers
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PackageManager {
+    /// Debian/Ubuntu (`dpkg -S <path>`)
+    Dpkg,
+    /// Fedora/RHEL/SUSE (`rpm -qf <path>`)
+    Rpm,
+}
+
+/// Result of a package ownership lookup
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PackageOwner {
+    /// Executable belongs to the named package
+    Package(String),
+    /// Package manager knows nothing about the executable
+    Unpackaged,
+    /// Lookup not possible (exe unreadable, no package manager, disabled)
+    Unknown,
+    /// Query still running
+    Pending,
+}
+
+impl PackageOwner {
+    /// Short display text for the inspector
+    pub fn display_text(&self) -> String {
+        match self {
+            Self::Package(name) => format!("owned by: {}", name),
+            Self::Unpackaged => "UNPACKAGED".to_string(),
+            Self::Unknown => "unknown".to_string(),
+            Self::Pending => "resolving…".to_string(),
+        }
+    }
+
+    /// The owner as `[cache]` fields; None for `Unknown` and `Pending`,
+    /// which are not worth keeping
+    pub fn cache_fields(&self) -> Option<Vec<String>> {
+        match self {
+            Self::Package(name) => Some(vec!["package".to_string(), name.clone()]),
+            Self::Unpackaged => Some(vec!["unpackaged".to_string()]),
+            Self::Unknown | Self::Pending => None,
+        }
+    }
+
+    /// The owner stored as `fields` by `cache_fields`
+    pub fn from_cache_fields(fields: &[String]) -> Option<Self> {
+        match fields {
+            [kind, name] if kind == "package" => Some(Self::Package(name.clone())),
+            [kind] if kind == "unpackaged" => Some(Self::Unpackaged),
+            _ => None,
+        }
+    }
+}
+
+/// Cached package ownership resolver
+///
+/// Detects the available package manager once and caches lookups by
+/// executable path. Disabled resolvers always return `Unknown`.
+#[derive(Debug)]
+pub struct PackageResolver {
+    /// Detected package manager (None if unavailable or disabled)
+    manager: Option<PackageManager>,
+    /// Cache of executable path -> owner, `Pending` while queried
+    cache: HashMap<PathBuf, PackageOwner>,
+    /// Lookups not yet handed to the on-disk cache
+    resolved: Vec<(PathBuf, PackageOwner)>,
+    /// Query thread, started on the first lookup
+    worker: Option<Worker>,
+}
+
+/// Channels to the query thread
+#[derive(Debug)]
+struct Worker {
+    requests: Sender<PathBuf>,
+    answers: Receiver<(PathBuf, PackageOwner)>,
+}
+
+impl Worker {
+    /// Start the query thread; it exits when the resolver is dropped
+    fn spawn(manager: PackageManager) -> Self {
+        let (requests, queue) = mpsc::channel::<PathBuf>();
+        let (results, answers) = mpsc::channel();
+        thread::spawn(move || {
+            for path in queue {
+                let owner = query_owner(manager, &path);
+                if results.send((path, owner)).is_err() {
+                    break;
+                }
+            }
+        });
+        Self { requests, answers }
+    }
+}
+
+impl PackageResolver {
+    /// Create a resolver, detecting the package manager unless disabled
+    /// via `NTOMB_PACKAGE_LOOKUP=0`
+    pub fn new() -> Self {
+        let enabled = std::env::var(PACKAGE_LOOKUP_ENV)
+            .map(|v| !matches!(v.to_lowercase().as_str(), "0" | "false" | "off"))
+            .unwrap_or(true);
+
+        Self {
+            manager: if enabled {
+                detect_package_manager()
+            } else {
+                None
+            },
+            cache: HashMap::new(),
+            resolved: Vec::new(),
+            worker: None,
+        }
+    }
+
+    /// Whether lookups can be performed at all
+    pub fn is_available(&self) -> bool {
+        self.manager.is_some()
+    }
+
+    /// Owner of the executable of `pid`; `Pending` while it is looked up
+    pub fn owner_of_pid(&mut self, pid: i32) -> PackageOwner {
+        if self.manager.is_none() {
+            return PackageOwner::Unknown;
+        }
+        match read_exe_path(pid) {
+            Some(path) => self.owner_of_path(&path),
+            None => PackageOwner::Unknown,
+        }
+    }
+
+    /// Owner of `path` from the cache, or `Pending` after queueing a
+    /// lookup for `poll` to collect
+    pub fn owner_of_path(&mut self, path: &Path) -> PackageOwner {
+        let Some(manager) = self.manager else {
+            return PackageOwner::Unknown;
+        };
+
+        if let Some(owner) = self.cache.get(path) {
+            return owner.clone();
+        }
+
+        let worker = self.worker.get_or_insert_with(|| Worker::spawn(manager));
+        let owner = match worker.requests.send(path.to_path_buf()) {
+            Ok(()) => PackageOwner::Pending,
+            Err(_) => PackageOwner::Unknown,
+        };
+        self.cache.insert(path.to_path_buf(), owner.clone());
+        owner
+    }
+
+    /// Collect finished lookups; returns the paths answered
+    pub fn poll(&mut self) -> Vec<PathBuf> {
+        let Some(ref worker) = self.worker else {
+            return Vec::new();
+        };
+        let mut answered = Vec::new();
+        while let Ok((path, owner)) = worker.answers.try_recv() {
+            if owner != PackageOwner::Unknown {
+                self.resolved.push((path.clone(), owner.clone()));
+            }
+            self.cache.insert(path.clone(), owner);
+            answered.push(path);
+        }
+        answered
+    }
+
+    /// Take the owner of `path` from the on-disk cache
+    pub fn seed(&mut self, path: PathBuf, owner: PackageOwner) {
+        self.cache.insert(path, owner);
+    }
+
+    /// Lookups made since the last call, for the on-disk cache
+    pub fn take_resolved(&mut self) -> Vec<(PathBuf, PackageOwner)> {
+        std::mem::take(&mut self.resolved)
+    }
+}
+
+impl Default for PackageResolver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Find an installed package manager by probing its query command
+#[cfg(target_os = "linux")]
+fn detect_package_manager() -> Option<PackageManager> {
+    let probe = |cmd: &str| {
+        Command::new(cmd)
+            .arg("--version")
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .map(|s| s.success())
+            .unwrap_or(false)
+    };
+
+    if probe("dpkg") {
+        Some(PackageManager::Dpkg)
+    } else if probe("rpm") {
+        Some(PackageManager::Rpm)
+    } else {
+        None
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn detect_package_manager() -> Option<PackageManager> {
+    None
+}
+
+/// Read the executable path from /proc/<pid>/exe
+///
+/// Strips the " (deleted)" suffix the kernel appends when the binary was
+/// removed from disk after the process started.
+#[cfg(target_os = "linux")]
+fn read_exe_path(pid: i32) -> Option<PathBuf> {
+    let target = fs::read_link(format!("/proc/{}/exe", pid)).ok()?;
+    let target_str = target.to_string_lossy();
+    match target_str.strip_suffix(" (deleted)") {
+        Some(stripped) => Some(PathBuf::from(stripped)),
+        None => Some(target),
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn read_exe_path(_pid: i32) -> Option<PathBuf> {
+    None
+}
+
+/// Run the package manager query for `path`
+///
+/// On usr-merged systems the package database may record `/bin/foo`
+/// while the kernel reports `/usr/bin/foo`, so the unmerged path is
+/// tried as a fallback.
+#[cfg(target_os = "linux")]
+fn query_owner(manager: PackageManager, path: &Path) -> PackageOwner {
+    let mut candidates = vec![path.to_path_buf()];
+    if let Ok(unmerged) = path.strip_prefix("/usr") {
+        candidates.push(Path::new("/").join(unmerged));
+    }
+
+    for candidate in &candidates {
+        let output = match manager {
+            PackageManager::Dpkg => Command::new("dpkg").arg("-S").arg(candidate).output(),
+            PackageManager::Rpm => Command::new("rpm")
+                .args(["-qf", "--queryformat", "%{NAME}\\n"])
+                .arg(candidate)
+                .output(),
+        };
+
+        let output = match output {
+            Ok(output) => output,
+            Err(e) => {
+                debug!(error = %e, "Package manager query failed");
+                return PackageOwner::Unknown;
+            }
+        };
+
+        if !output.status.success() {
+            continue;
+        }
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let parsed = match manager {
+            PackageManager::Dpkg => parse_dpkg_output(&stdout),
+            PackageManager::Rpm => parse_rpm_output(&stdout),
+        };
+        if let Some(name) = parsed {
+            return PackageOwner::Package(name);
+        }
+    }
+
+    PackageOwner::Unpackaged
+}
+
+#[cfg(not(target_os = "linux"))]
+fn query_owner(_manager: PackageManager, _path: &Path) -> PackageOwner {
+    PackageOwner::Unknown
+}
+
+/// Parse `dpkg -S` output
+/// Format: "openssh-server: /usr/sbin/sshd" (possibly "pkg:amd64: /path",
+/// or "pkg1, pkg2: /path" for shared files)
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_dpkg_output(output: &str) -> Option<String> {
+    let line = output.lines().find(|l| !l.starts_with("diversion by"))?;
+    let (packages, _path) = line.rsplit_once(": ")?;
+    let first = packages.split(',').next()?.trim();
+    // Strip architecture qualifier ("libc6:amd64" -> "libc6")
+    let name = first.split(':').next()?.trim();
+    if name.is_empty() {
+        None
+    } else {
+        Some(name.to_string())
+    }
+}
+
+/// Parse `rpm -qf --queryformat '%{NAME}\n'` output
+/// Unowned files produce "file /path is not owned by any package"
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_rpm_output(output: &str) -> Option<String> {
+    let line = output.lines().next()?.trim();
+    if line.is_empty() || line.contains("not owned by any package") {
+        None
+    } else {
+        Some(line.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_dpkg_output() {
+        assert_eq!(
+            parse_dpkg_output("openssh-server: /usr/sbin/sshd\n"),
+            Some("openssh-server".to_string())
+        );
+        assert_eq!(
+            parse_dpkg_output("libc6:amd64: /usr/lib/x86_64-linux-gnu/libc.so.6\n"),
+            Some("libc6".to_string())
+        );
+        assert_eq!(
+            parse_dpkg_output("coreutils, busybox: /bin/ls\n"),
+            Some("coreutils".to_string())
+        );
+        assert_eq!(parse_dpkg_output(""), None);
+    }
+
+    #[test]
+    fn test_parse_rpm_output() {
+        assert_eq!(
+            parse_rpm_output("openssh-server\n"),
+            Some("openssh-server".to_string())
+        );
+        assert_eq!(
+            parse_rpm_output("file /opt/x is not owned by any package\n"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_package_owner_display_text() {
+        assert_eq!(
+            PackageOwner::Package("nginx".to_string()).display_text(),
+            "owned by: nginx"
+        );
+        assert_eq!(PackageOwner::Unpackaged.display_text(), "UNPACKAGED");
+    }
+
+    #[test]
+    fn test_disabled_resolver_returns_unknown() {
+        let mut resolver = PackageResolver {
+            manager: None,
+            cache: HashMap::new(),
+            resolved: Vec::new(),
+            worker: None,
+        };
+        assert!(!resolver.is_available());
+        assert_eq!(resolver.owner_of_pid(1), PackageOwner::Unknown);
+    }
+
+    #[test]
+    fn test_lookups_run_in_the_background() {
+        let mut resolver = PackageResolver {
+            manager: Some(PackageManager::Dpkg),
+            cache: HashMap::new(),
+            resolved: Vec::new(),
+            worker: None,
+        };
+        let seeded = PathBuf::from("/usr/sbin/sshd");
+        let owner = PackageOwner::Package("openssh-server".to_string());
+        resolver.seed(seeded.clone(), owner.clone());
+        assert_eq!(resolver.owner_of_path(&seeded), owner);
+        assert!(resolver.worker.is_none());
+
+        // Answered later, whatever the package manager says
+        let path = Path::new("/nonexistent/ntomb-package-test");
+        assert_eq!(resolver.owner_of_path(path), PackageOwner::Pending);
+        assert_eq!(resolver.owner_of_path(path), PackageOwner::Pending);
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(10);
+        while resolver.poll().is_empty() {
+            assert!(
+                std::time::Instant::now() < deadline,
+                "lookup never answered"
+            );
+            thread::sleep(std::time::Duration::from_millis(10));
+        }
+        assert_ne!(resolver.owner_of_path(path), PackageOwner::Pending);
+    }
+}
@@ -0,0 +1,46 @@
+// User name lookup
+//
+// Maps numeric UIDs (as reported by /proc/net/tcp) to user names by
+// reading /etc/passwd. Read-only; returns an empty map when the file is
+// unavailable so callers fall back to numeric UIDs.
+
+use std::collections::HashMap;
+use std::fs;
+
+/// Read /etc/passwd and build a map of uid -> user name
+pub fn read_user_names() -> HashMap<u32, String> {
+    fs::read_to_string("/etc/passwd")
+        .map(|content| parse_passwd(&content))
+        .unwrap_or_default()
+}
+
+/// Parse passwd(5) format: name:password:uid:gid:gecos:home:shell
+fn parse_passwd(content: &str) -> HashMap<u32, String> {
+    content
+        .lines()
+        .filter(|line| !line.starts_with('#'))
+        .filter_map(|line| {
+            let mut fields = line.split(':');
+            let name = fields.next()?;
+            let uid = fields.nth(1)?.parse::<u32>().ok()?;
+            Some((uid, name.to_string()))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_passwd() {
+        let content = "root:x:0:0:root:/root:/bin/bash\n\
+                       # comment line\n\
+                       www-data:x:33:33:www-data:/var/www:/usr/sbin/nologin\n\
+                       broken-line\n";
+        let users = parse_passwd(content);
+        assert_eq!(users.get(&0), Some(&"root".to_string()));
+        assert_eq!(users.get(&33), Some(&"www-data".to_string()));
+        assert_eq!(users.len(), 2);
+    }
+}
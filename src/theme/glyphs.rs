@@ -0,0 +1,240 @@
+// Glyph sets
+//
+// Every symbol the Graveyard draws with: endpoint icons, the Overdrive
+// state icons, the particle and the heavy-talker and blocklist badges.
+// `[glyphs]` in config.toml picks a preset (emoji, nerd-font or ascii) and
+// can override single glyphs, for terminals and fonts that lack emoji. The
+// set is fixed at startup, like the emoji width offset.
+
+use serde::Deserialize;
+use std::sync::OnceLock;
+
+/// The glyph set in use; `init` sets it once at startup
+static GLYPHS: OnceLock<GlyphSet> = OnceLock::new();
+
+/// Bundled glyph sets
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum GlyphPreset {
+    /// Halloween emoji (needs an emoji font)
+    #[default]
+    Emoji,
+    /// Font Awesome symbols from a Nerd Font
+    NerdFont,
+    /// Plain ASCII, for any terminal
+    Ascii,
+}
+
+/// One symbol per thing drawn
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GlyphSet {
+    /// Endpoint icons
+    pub localhost: String,
+    pub private: String,
+    pub public: String,
+    pub listen: String,
+    /// Badge after heavy talkers
+    pub crown: String,
+    /// Badge after blocklisted endpoints
+    pub haunted: String,
+    /// Particle flowing along edges
+    pub particle: String,
+    /// Overdrive icons: ESTABLISHED, high latency, CLOSE_WAIT/TIME_WAIT/CLOSE,
+    /// SYN_SENT/SYN_RECV, the other closing states, and anything else
+    pub overdrive_healthy: String,
+    pub overdrive_hot: String,
+    pub overdrive_dying: String,
+    pub overdrive_waiting: String,
+    pub overdrive_fading: String,
+    pub overdrive_unknown: String,
+    /// Country flags (regional indicator pairs) instead of two-letter codes
+    pub flags: bool,
+}
+
+impl GlyphSet {
+    pub fn preset(preset: GlyphPreset) -> Self {
+        match preset {
+            GlyphPreset::Emoji => Self {
+                localhost: "⚰️".into(),
+                private: "🪦".into(),
+                public: "🎃".into(),
+                listen: "🕯".into(),
+                crown: "👑".into(),
+                haunted: "☠️".into(),
+                particle: "●".into(),
+                overdrive_healthy: "🟢👻".into(),
+                overdrive_hot: "🔥🎃".into(),
+                overdrive_dying: "💀".into(),
+                overdrive_waiting: "⏳".into(),
+                overdrive_fading: "👻".into(),
+                overdrive_unknown: "❓".into(),
+                flags: true,
+            },
+            GlyphPreset::NerdFont => Self {
+                localhost: "\u{f108}".into(),
+                private: "\u{f0e8}".into(),
+                public: "\u{f0ac}".into(),
+                listen: "\u{f025}".into(),
+                crown: "\u{f005}".into(),
+                haunted: "\u{f071}".into(),
+                particle: "●".into(),
+                overdrive_healthy: "\u{f00c}".into(),
+                overdrive_hot: "\u{f06d}".into(),
+                overdrive_dying: "\u{f00d}".into(),
+                overdrive_waiting: "\u{f254}".into(),
+                overdrive_fading: "\u{f070}".into(),
+                overdrive_unknown: "\u{f128}".into(),
+                flags: false,
+            },
+            GlyphPreset::Ascii => Self {
+                localhost: "L".into(),
+                private: "P".into(),
+                public: "@".into(),
+                listen: "S".into(),
+                crown: "+".into(),
+                haunted: "!".into(),
+                particle: "*".into(),
+                overdrive_healthy: "o".into(),
+                overdrive_hot: "!!".into(),
+                overdrive_dying: "x".into(),
+                overdrive_waiting: "~".into(),
+                overdrive_fading: ".".into(),
+                overdrive_unknown: "?".into(),
+                flags: false,
+            },
+        }
+    }
+
+    /// A country as drawn next to endpoints: its flag ("NL" -> 🇳🇱) when
+    /// the set has flags, else the code itself
+    pub fn country(&self, code: &str) -> String {
+        if !self.flags || code.len() != 2 || !code.bytes().all(|b| b.is_ascii_alphabetic()) {
+            return code.to_string();
+        }
+        code.bytes()
+            .filter_map(|b| char::from_u32(0x1F1E6 + u32::from(b.to_ascii_uppercase() - b'A')))
+            .collect()
+    }
+}
+
+/// `[glyphs]` section of config.toml: a preset plus single-glyph overrides
+#[derive(Debug, Clone, Default, Deserialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct GlyphsConfig {
+    /// "emoji", "nerd-font" or "ascii"; unset means emoji, or ascii when
+    /// NTOMB_ASCII_MODE is set
+    pub preset: Option<GlyphPreset>,
+    pub localhost: Option<String>,
+    pub private: Option<String>,
+    pub public: Option<String>,
+    pub listen: Option<String>,
+    pub crown: Option<String>,
+    pub haunted: Option<String>,
+    pub particle: Option<String>,
+    pub overdrive_healthy: Option<String>,
+    pub overdrive_hot: Option<String>,
+    pub overdrive_dying: Option<String>,
+    pub overdrive_waiting: Option<String>,
+    pub overdrive_fading: Option<String>,
+    pub overdrive_unknown: Option<String>,
+    /// Country flags on or off (on with the emoji preset)
+    pub flags: Option<bool>,
+}
+
+impl GlyphsConfig {
+    /// The configured set; `ascii_mode` picks the ascii preset when none
+    /// is given
+    pub fn build(&self, ascii_mode: bool) -> GlyphSet {
+        let fallback = if ascii_mode {
+            GlyphPreset::Ascii
+        } else {
+            GlyphPreset::Emoji
+        };
+        let mut set = GlyphSet::preset(self.preset.unwrap_or(fallback));
+        let overrides = [
+            (&mut set.localhost, &self.localhost),
+            (&mut set.private, &self.private),
+            (&mut set.public, &self.public),
+            (&mut set.listen, &self.listen),
+            (&mut set.crown, &self.crown),
+            (&mut set.haunted, &self.haunted),
+            (&mut set.particle, &self.particle),
+            (&mut set.overdrive_healthy, &self.overdrive_healthy),
+            (&mut set.overdrive_hot, &self.overdrive_hot),
+            (&mut set.overdrive_dying, &self.overdrive_dying),
+            (&mut set.overdrive_waiting, &self.overdrive_waiting),
+            (&mut set.overdrive_fading, &self.overdrive_fading),
+            (&mut set.overdrive_unknown, &self.overdrive_unknown),
+        ];
+        for (glyph, custom) in overrides {
+            if let Some(custom) = custom {
+                *glyph = custom.clone();
+            }
+        }
+        if let Some(flags) = self.flags {
+            set.flags = flags;
+        }
+        set
+    }
+}
+
+/// Use `set` for the rest of the session; only the first call counts
+pub fn init(set: GlyphSet) {
+    let _ = GLYPHS.set(set);
+}
+
+/// The glyph set in use (the emoji preset until `init` is called)
+pub fn glyphs() -> &'static GlyphSet {
+    GLYPHS.get_or_init(|| GlyphSet::preset(GlyphPreset::Emoji))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_glyphs() {
+        let default = GlyphsConfig::default();
+        assert_eq!(default.build(false), GlyphSet::preset(GlyphPreset::Emoji));
+        assert_eq!(default.build(true).public, "@");
+
+        let config = GlyphsConfig {
+            preset: Some(GlyphPreset::NerdFont),
+            crown: Some("^".into()),
+            ..GlyphsConfig::default()
+        };
+        // An explicit preset wins over NTOMB_ASCII_MODE
+        let set = config.build(true);
+        assert_eq!(set.public, "\u{f0ac}");
+        assert_eq!(set.crown, "^");
+
+        // Every ascii glyph really is ASCII
+        let ascii = GlyphSet::preset(GlyphPreset::Ascii);
+        let all = [
+            &ascii.localhost,
+            &ascii.private,
+            &ascii.public,
+            &ascii.listen,
+            &ascii.crown,
+            &ascii.haunted,
+            &ascii.particle,
+            &ascii.overdrive_healthy,
+            &ascii.overdrive_hot,
+            &ascii.overdrive_dying,
+            &ascii.overdrive_waiting,
+            &ascii.overdrive_fading,
+            &ascii.overdrive_unknown,
+        ];
+        assert!(all.iter().all(|glyph| glyph.is_ascii()));
+    }
+
+    #[test]
+    fn test_country_glyph() {
+        let emoji = GlyphSet::preset(GlyphPreset::Emoji);
+        assert_eq!(emoji.country("NL"), "🇳🇱");
+        assert_eq!(emoji.country("us"), "🇺🇸");
+        // Not a country code: shown as is
+        assert_eq!(emoji.country("EU1"), "EU1");
+        assert_eq!(GlyphSet::preset(GlyphPreset::Ascii).country("NL"), "NL");
+    }
+}
@@ -34,6 +34,60 @@ pub const TOXIC_GREEN: Color = Color::Rgb(158, 206, 106);
 /// RGB: (169, 177, 214)
 pub const BONE_WHITE: Color = Color::Rgb(169, 177, 214);
 
+/// Look up a color by palette name (`blood_red`, `toxic_green`, ...),
+/// falling back to ratatui color names (`cyan`, `lightblue`) and `#rrggbb`
+///
+/// Used for colors given in the config file.
+pub fn color_by_name(name: &str) -> Option<Color> {
+    let normalized = name.trim().to_ascii_lowercase().replace('-', "_");
+    let color = match normalized.as_str() {
+        "neon_purple" => NEON_PURPLE,
+        "pumpkin_orange" => PUMPKIN_ORANGE,
+        "blood_red" => BLOOD_RED,
+        "toxic_green" => TOXIC_GREEN,
+        "bone_white" => BONE_WHITE,
+        _ => return name.trim().parse().ok(),
+    };
+    Some(color)
+}
+
+/// How bright the palette is at night (see `dim_color`)
+const NIGHT_DIM_FACTOR: f32 = 0.65;
+
+/// The night-time version of `color`: RGB colors are darkened, named
+/// terminal colors are left to the terminal's own palette
+pub fn dim_color(color: Color) -> Color {
+    match color {
+        Color::Rgb(r, g, b) => {
+            let dim = |c: u8| (c as f32 * NIGHT_DIM_FACTOR) as u8;
+            Color::Rgb(dim(r), dim(g), dim(b))
+        }
+        other => other,
+    }
+}
+
+/// `color` mixed toward white by `amount` (0.0 unchanged, 1.0 white);
+/// named terminal colors switch to their light variant instead
+pub fn brighten_color(color: Color, amount: f32) -> Color {
+    match color {
+        Color::Rgb(r, g, b) => {
+            let lift = |c: u8| c + ((255 - c) as f32 * amount.clamp(0.0, 1.0)) as u8;
+            Color::Rgb(lift(r), lift(g), lift(b))
+        }
+        _ if amount <= 0.0 => color,
+        Color::Red => Color::LightRed,
+        Color::Green => Color::LightGreen,
+        Color::Yellow => Color::LightYellow,
+        Color::Blue => Color::LightBlue,
+        Color::Magenta => Color::LightMagenta,
+        Color::Cyan => Color::LightCyan,
+        Color::Gray => Color::White,
+        other => other,
+    }
+}
+
+pub mod glyphs;
+
 // Re-export theme functions for convenient access
 pub use default::*;
 pub use overdrive::*;
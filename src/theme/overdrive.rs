@@ -10,6 +10,7 @@ use crate::app::LatencyBucket;
 use crate::net::ConnectionState;
 
 use super::get_normal_status_text;
+use super::glyphs::glyphs;
 
 /// Get overdrive-themed icon based on connection state and latency
 ///
@@ -31,35 +32,40 @@ use super::get_normal_status_text;
 ///
 /// Requirements: 4.2, 4.3, 4.4
 pub fn get_overdrive_icon(state: ConnectionState, latency_bucket: LatencyBucket) -> &'static str {
+    let glyphs = glyphs();
     // Priority: CLOSE_WAIT/TIME_WAIT > High latency > ESTABLISHED > Other
     match state {
         // Dying connections get skull icon (Requirement 4.4)
-        ConnectionState::CloseWait | ConnectionState::TimeWait => "💀",
+        ConnectionState::CloseWait | ConnectionState::TimeWait => &glyphs.overdrive_dying,
 
         // Established connections: check latency first
         ConnectionState::Established => {
             // High latency gets fire-pumpkin (Requirement 4.3)
             if latency_bucket == LatencyBucket::High {
-                "🔥🎃"
+                &glyphs.overdrive_hot
             } else {
                 // Healthy established connections get ghost (Requirement 4.2)
-                "🟢👻"
+                &glyphs.overdrive_healthy
             }
         }
 
         // Other states with high latency also get fire-pumpkin
         _ => {
             if latency_bucket == LatencyBucket::High {
-                "🔥🎃"
+                &glyphs.overdrive_hot
             } else {
                 // Default to standard state indicator
                 match state {
-                    ConnectionState::Listen => "🕯",
-                    ConnectionState::SynSent | ConnectionState::SynRecv => "⏳",
-                    ConnectionState::Close => "💀",
-                    ConnectionState::FinWait1 | ConnectionState::FinWait2 => "👻",
-                    ConnectionState::LastAck | ConnectionState::Closing => "👻",
-                    _ => "❓",
+                    ConnectionState::Listen => &glyphs.listen,
+                    ConnectionState::SynSent | ConnectionState::SynRecv => {
+                        &glyphs.overdrive_waiting
+                    }
+                    ConnectionState::Close => &glyphs.overdrive_dying,
+                    ConnectionState::FinWait1 | ConnectionState::FinWait2 => {
+                        &glyphs.overdrive_fading
+                    }
+                    ConnectionState::LastAck | ConnectionState::Closing => &glyphs.overdrive_fading,
+                    _ => &glyphs.overdrive_unknown,
                 }
             }
         }
@@ -3,7 +3,7 @@
 // Renders the top banner with ASCII art logo and global stats.
 
 use crate::app::AppState;
-use crate::theme::get_stats_label;
+use crate::theme::{get_stats_label, BLOOD_RED, BONE_WHITE};
 use ratatui::{
     layout::{Alignment, Rect},
     style::{Color, Modifier, Style},
@@ -17,10 +17,32 @@ pub fn render_banner(f: &mut Frame, area: Rect, app: &AppState) {
     // When overdrive is enabled, use "Spirits" instead of "Total Souls"
     let stats_label = get_stats_label(app.graveyard_settings.overdrive_enabled);
     let conn_count = app.connections.len();
-    let stats_text = format!(
-        "   [💀 {}: {}] [🩸 BPF Radar: TBD]",
-        stats_label, conn_count
+    let mut stats_text = format!(
+        "   [💀 {}: {} {}] [🩸 BPF Radar: TBD]",
+        stats_label,
+        conn_count,
+        app.connection_trend().arrow()
     );
+    // Paused timeline: which recorded refresh is on screen
+    if let (Some(index), Some(snapshot)) = (app.timeline, app.timeline_snapshot()) {
+        stats_text.push_str(&format!(
+            " [⏸ {} {}/{}]",
+            snapshot.time_label(),
+            index + 1,
+            app.history.len()
+        ));
+    }
+
+    // Hung backend: the snapshot on screen is the last one collected
+    if let Some(overdue) = app.collection_overdue() {
+        stats_text.push_str(&format!(" [⌛ STALE {}s]", overdue.as_secs()));
+    }
+
+    // Errors recorded since the diagnostics panel was last opened
+    let error_badge = match app.backend_health.unseen_errors() {
+        0 => String::new(),
+        n => format!(" [❗ {} (!)]", n),
+    };
 
     let banner_text = vec![
         Line::from(vec![Span::styled(
@@ -61,17 +83,51 @@ pub fn render_banner(f: &mut Frame, area: Rect, app: &AppState) {
                 Style::default().fg(Color::Rgb(178, 83, 255)),
             ),
             Span::styled(stats_text, Style::default().fg(Color::Red)),
+            Span::styled(
+                error_badge,
+                Style::default().fg(BLOOD_RED).add_modifier(Modifier::BOLD),
+            ),
         ]),
     ];
 
+    // An alarmed alert inverts the whole banner for a moment
+    let alarm = if app.alarm.flashing() {
+        Modifier::REVERSED
+    } else {
+        Modifier::empty()
+    };
     let banner = Paragraph::new(banner_text)
         .block(
             Block::default()
                 .borders(Borders::ALL)
                 .border_type(BorderType::Double)
-                .border_style(Style::default().fg(Color::Rgb(138, 43, 226))),
+                .border_style(
+                    Style::default()
+                        .fg(Color::Rgb(138, 43, 226))
+                        .add_modifier(alarm),
+                ),
         )
+        .style(Style::default().add_modifier(alarm))
         .alignment(Alignment::Left);
 
     f.render_widget(banner, area);
 }
+
+/// Full-width warning strip shown while a SYN storm is in progress
+pub fn render_storm_warning(f: &mut Frame, area: Rect, app: &AppState) {
+    // Alternate the background with the zombie blink so it catches the eye
+    let (fg, bg) = if app.zombie_blink {
+        (BONE_WHITE, BLOOD_RED)
+    } else {
+        (BLOOD_RED, Color::Black)
+    };
+    let text = format!(" 🚨 SYN STORM: {} ", app.syn_storm.status.summary());
+    let warning = Paragraph::new(Line::from(Span::styled(
+        text,
+        Style::default().fg(fg).add_modifier(Modifier::BOLD),
+    )))
+    .style(Style::default().bg(bg))
+    .alignment(Alignment::Center);
+
+    f.render_widget(warning, area);
+}
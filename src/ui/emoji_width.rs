@@ -30,7 +30,7 @@ const TEST_EMOJIS: &[&str] = &["🎃", "⚰️", "👻", "💀", "👑"];
 const EXPECTED_UNICODE_WIDTH: i32 = 2;
 
 /// Configuration for emoji width handling
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
 pub struct EmojiWidthConfig {
     /// Detected offset: actual_width - unicode_width_reported
     /// 0 = no correction needed (macOS typical)
@@ -44,16 +44,6 @@ pub struct EmojiWidthConfig {
     pub use_ascii_fallback: bool,
 }
 
-impl Default for EmojiWidthConfig {
-    fn default() -> Self {
-        Self {
-            offset: 0,
-            detected: false,
-            use_ascii_fallback: false,
-        }
-    }
-}
-
 impl EmojiWidthConfig {
     /// Calculate the actual display width of a string containing emoji
     /// 
@@ -3,11 +3,17 @@
 // Renders the main network topology visualization canvas with endpoints,
 // connections, latency rings, and particle animations.
 
+use crate::app::ambiance::{bat_position, fog_positions};
+use crate::app::endpoint_rules::{environment_color, match_rule, EndpointRule};
+use crate::app::heavy_talkers::HeavyTalkerThreshold;
+use crate::app::quality::AnimationQuality;
 use crate::app::{AppState, GraveyardMode, LatencyBucket, LatencyConfig};
-use crate::net::ConnectionState;
+use crate::net::scope::{address_scope, AddressScope};
+use crate::net::{ConnectionDirection, ConnectionState};
+use crate::theme::glyphs::{glyphs, GlyphSet};
 use crate::theme::{
-    get_overdrive_icon, interpolate_color, BLOOD_RED, BONE_WHITE, NEON_PURPLE, PUMPKIN_ORANGE,
-    TOXIC_GREEN,
+    brighten_color, get_overdrive_icon, interpolate_color, BLOOD_RED, BONE_WHITE, NEON_PURPLE,
+    PUMPKIN_ORANGE, TOXIC_GREEN,
 };
 use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
@@ -20,7 +26,9 @@ use ratatui::{
     },
     Frame,
 };
-use std::collections::HashMap;
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
 
 use super::emoji_width::{corrected_str_width_with_offset, emoji_centering_offset_with};
 
@@ -42,27 +50,18 @@ const MIN_EDGE_PADDING: f64 = 5.0;
 // Center point of the HOST node in virtual canvas space
 const HOST_CENTER: (f64, f64) = (50.0, 50.0);
 
-// Edge particle animation constants (Requirements 2.1, 2.2)
-// Offset positions for particles along the edge (0.0 to 1.0)
-// 3 particles evenly distributed: start, 1/3, 2/3 along the edge
-const PARTICLE_OFFSETS: [f32; 3] = [0.0, 0.33, 0.66];
-
-// Symbol used to render particles on edges
-const PARTICLE_SYMBOL: &str = "●";
+// Overdrive ambiance: drifting fog, the occasional bat, and the canvas
+// background while a lightning flash is on
+const FOG_SYMBOL: &str = "·";
+const FOG_COLOR: Color = Color::Rgb(86, 95, 137);
+const BAT_SYMBOL: &str = "🦇";
+const LIGHTNING_BACKGROUND: Color = Color::Rgb(52, 54, 78);
 
 // Performance optimization constants (Requirements 6.3, 6.4, 6.5)
-// Maximum number of endpoints to display in the Graveyard canvas
-// Limited to 8 for clean visualization around the central HOST
-const MAX_VISIBLE_ENDPOINTS: usize = 8;
-
 // Threshold for reducing particle count to maintain performance
-// When edge count exceeds this, reduce particles per edge
+// When edge count exceeds this, particles are capped at the Low tier
 const PARTICLE_REDUCTION_THRESHOLD: usize = 50;
 
-// Reduced particle offsets for high edge count scenarios
-// Uses 1 particle instead of 3 to reduce rendering load
-const REDUCED_PARTICLE_OFFSETS: [f32; 1] = [0.33];
-
 // ============================================================================
 // Adaptive Layout Configuration (Requirements 1.1, 1.2, 2.1)
 // ============================================================================
@@ -111,13 +110,14 @@ impl Default for LayoutConfig {
 /// based on their IP address characteristics.
 ///
 /// Requirements: 3.1, 3.2, 3.3, 3.5
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum EndpointType {
-    /// Local loopback connections (127.0.0.1, ::1, 0.0.0.0)
+    /// Local loopback connections (127.0.0.0/8, ::1, 0.0.0.0, ::)
     /// Icon: ⚰️, Color: Toxic Green
     Localhost,
 
-    /// RFC1918 private IP addresses (10.x, 172.16-31.x, 192.168.x)
+    /// Local network addresses: RFC1918 (10.x, 172.16-31.x, 192.168.x),
+    /// link-local and IPv6 unique local (fc00::/7)
     /// Icon: 🪦, Color: Bone White
     Private,
 
@@ -128,6 +128,10 @@ pub enum EndpointType {
     /// Local server sockets in LISTEN state (no remote connection)
     /// Icon: 🕯, Color: Neon Purple
     ListenOnly,
+
+    /// Address matched by a user-defined `[[endpoint_rules]]` entry
+    /// Icon, color and label come from the rule
+    Custom(Arc<EndpointRule>),
 }
 
 impl EndpointType {
@@ -136,12 +140,14 @@ impl EndpointType {
     /// Returns the appropriate Halloween-themed emoji icon based on endpoint classification.
     ///
     /// Requirements: 3.1, 3.2, 3.3, 3.5
-    pub fn icon(&self) -> &'static str {
+    pub fn icon(&self) -> &str {
+        let glyphs = glyphs();
         match self {
-            Self::Localhost => "⚰️",
-            Self::Private => "🪦",
-            Self::Public => "🎃",
-            Self::ListenOnly => "🕯",
+            Self::Localhost => &glyphs.localhost,
+            Self::Private => &glyphs.private,
+            Self::Public => &glyphs.public,
+            Self::ListenOnly => &glyphs.listen,
+            Self::Custom(rule) => &rule.icon,
         }
     }
 
@@ -156,16 +162,28 @@ impl EndpointType {
             Self::Private => BONE_WHITE,
             Self::Public => PUMPKIN_ORANGE,
             Self::ListenOnly => NEON_PURPLE,
+            Self::Custom(rule) => rule.color,
+        }
+    }
+
+    /// Short classification name for detail views
+    pub fn label(&self) -> &str {
+        match self {
+            Self::Localhost => "localhost",
+            Self::Private => "private",
+            Self::Public => "public",
+            Self::ListenOnly => "listener",
+            Self::Custom(rule) => &rule.label,
         }
     }
 
     /// Get the icon with optional heavy talker badge
     ///
-    /// Returns the endpoint type icon with "👑" appended if the endpoint
-    /// is a heavy talker (top 5 by connection count).
+    /// Returns the endpoint type icon with the crown glyph ("👑") appended
+    /// if the endpoint is a heavy talker (see `is_heavy_talker`).
     ///
     /// # Arguments
-    /// * `is_heavy_talker` - Whether this endpoint is in the top 5 by connection count
+    /// * `is_heavy_talker` - Whether this endpoint passes the heavy-talker threshold
     ///
     /// # Returns
     /// A String containing the icon, with "👑" badge appended for heavy talkers
@@ -174,7 +192,7 @@ impl EndpointType {
     pub fn icon_with_badge(&self, is_heavy_talker: bool) -> String {
         let base_icon = self.icon();
         if is_heavy_talker {
-            format!("{}👑", base_icon)
+            format!("{}{}", base_icon, glyphs().crown)
         } else {
             base_icon.to_string()
         }
@@ -184,118 +202,93 @@ impl EndpointType {
 /// Classify an endpoint IP address into an EndpointType
 ///
 /// Classification logic:
-/// 1. Localhost: 127.0.0.1, ::1, or 0.0.0.0
-/// 2. Private: RFC1918 ranges (10.x, 172.16-31.x, 192.168.x)
-/// 3. ListenOnly: When remote address is 0.0.0.0:0 (LISTEN socket)
-/// 4. Public: All other IP addresses
+/// 1. ListenOnly: When remote address is 0.0.0.0:0 (LISTEN socket)
+/// 2. Custom: First user-defined rule whose CIDR contains the address
+/// 3. Localhost: loopback (127.0.0.0/8, ::1) or unspecified (0.0.0.0, ::)
+/// 4. Private: RFC1918, link-local (169.254.x, fe80::/10) and IPv6 unique
+///    local (fc00::/7) ranges
+/// 5. Public: All other IP addresses
 ///
 /// # Arguments
 /// * `ip` - The IP address string to classify
 /// * `is_listen_socket` - True if this is a LISTEN-only socket (remote = 0.0.0.0:0)
+/// * `rules` - Custom classification rules from the config file
 ///
 /// # Returns
 /// The appropriate EndpointType classification
 ///
 /// Requirements: 3.1, 3.2, 3.3, 3.5
-pub fn classify_endpoint(ip: &str, is_listen_socket: bool) -> EndpointType {
+pub fn classify_endpoint(
+    ip: &str,
+    is_listen_socket: bool,
+    rules: &[Arc<EndpointRule>],
+) -> EndpointType {
     // Check for LISTEN-only sockets first (remote = 0.0.0.0:0)
     // These are local server sockets waiting for connections
     if is_listen_socket {
         return EndpointType::ListenOnly;
     }
 
-    // Check for localhost addresses
-    // Includes IPv4 loopback (127.0.0.1), IPv6 loopback (::1), and wildcard (0.0.0.0)
-    if ip == "127.0.0.1" || ip == "::1" || ip == "0.0.0.0" {
-        return EndpointType::Localhost;
+    // User rules describe the actual network topology, so they take
+    // precedence over the generic ranges below
+    if let Some(rule) = match_rule(rules, ip) {
+        return EndpointType::Custom(Arc::clone(rule));
     }
 
-    // Check for RFC1918 private IP ranges
-    // Parse as IPv4 and check against private ranges
-    if let Some(endpoint_type) = classify_ipv4_private(ip) {
-        return endpoint_type;
+    // Loopback/unspecified and local network ranges, IPv4 and IPv6
+    // (IPv4-mapped addresses follow their IPv4 address); anything else,
+    // including strings that don't parse, is Public
+    match address_scope(ip) {
+        Some(AddressScope::Loopback) => EndpointType::Localhost,
+        Some(AddressScope::Private) => EndpointType::Private,
+        Some(AddressScope::Public) | None => EndpointType::Public,
     }
-
-    // Default to Public for all other addresses
-    EndpointType::Public
-}
-
-/// Helper function to classify IPv4 addresses against RFC1918 private ranges
-///
-/// RFC1918 private ranges:
-/// - 10.0.0.0/8 (10.0.0.0 - 10.255.255.255)
-/// - 172.16.0.0/12 (172.16.0.0 - 172.31.255.255)
-/// - 192.168.0.0/16 (192.168.0.0 - 192.168.255.255)
-///
-/// Returns Some(EndpointType::Private) if the IP is in a private range,
-/// None otherwise.
-fn classify_ipv4_private(ip: &str) -> Option<EndpointType> {
-    // Parse the IP address into octets
-    let parts: Vec<&str> = ip.split('.').collect();
-    if parts.len() != 4 {
-        return None; // Not a valid IPv4 address
-    }
-
-    // Parse each octet
-    let octets: Vec<u8> = parts.iter().filter_map(|p| p.parse::<u8>().ok()).collect();
-
-    if octets.len() != 4 {
-        return None; // Failed to parse all octets
-    }
-
-    // Check RFC1918 private ranges
-
-    // 10.0.0.0/8 - Class A private network
-    if octets[0] == 10 {
-        return Some(EndpointType::Private);
-    }
-
-    // 172.16.0.0/12 - Class B private network (172.16.x.x - 172.31.x.x)
-    if octets[0] == 172 && (16..=31).contains(&octets[1]) {
-        return Some(EndpointType::Private);
-    }
-
-    // 192.168.0.0/16 - Class C private network
-    if octets[0] == 192 && octets[1] == 168 {
-        return Some(EndpointType::Private);
-    }
-
-    None
 }
 
 /// Determine if an endpoint is a "heavy talker" based on connection count
 ///
 /// An endpoint is considered a heavy talker if its connection count is in the
-/// top 5 among all endpoints. This helps identify endpoints with unusually
-/// high activity that may warrant investigation.
+/// top N among all endpoints, or at least N, depending on `threshold`
+/// (`[graveyard] heavy_talkers`, top 5 by default). This helps identify
+/// endpoints with unusually high activity that may warrant investigation.
 ///
 /// # Arguments
 /// * `conn_count` - The connection count for the endpoint being checked
 /// * `all_counts` - A slice of all endpoint connection counts for comparison
+/// * `threshold` - Top N by connection count, or a minimum connection count
 ///
 /// # Returns
-/// `true` if the endpoint is in the top 5 by connection count, `false` otherwise
+/// `true` if the endpoint passes the threshold, `false` otherwise
 ///
 /// # Edge Cases
-/// - If there are fewer than 5 endpoints, all endpoints are considered heavy talkers
-/// - If multiple endpoints have the same count as the 5th highest, all are included
+/// - If there are fewer than N endpoints, all endpoints are top-N heavy talkers
+/// - If multiple endpoints have the same count as the Nth highest, all are included
 ///
 /// Requirements: 3.4
-pub fn is_heavy_talker(conn_count: usize, all_counts: &[usize]) -> bool {
+pub fn is_heavy_talker(
+    conn_count: usize,
+    all_counts: &[usize],
+    threshold: HeavyTalkerThreshold,
+) -> bool {
     if all_counts.is_empty() {
         return false;
     }
 
-    // Sort counts in descending order to find top 5
+    let top = match threshold {
+        HeavyTalkerThreshold::Top(top) => top.max(1),
+        HeavyTalkerThreshold::Min(min) => return conn_count >= min.max(1),
+    };
+
+    // Sort counts in descending order to find the top N
     let mut sorted = all_counts.to_vec();
     sorted.sort_by(|a, b| b.cmp(a));
 
-    // Determine the threshold for top 5
-    // If fewer than 5 endpoints, use the minimum count (all are heavy talkers)
-    let threshold = if sorted.len() >= 5 {
-        sorted[4] // 5th highest count (0-indexed)
+    // Determine the threshold for the top N
+    // If fewer than N endpoints, use the minimum count (all are heavy talkers)
+    let threshold = if sorted.len() >= top {
+        sorted[top - 1] // Nth highest count (0-indexed)
     } else {
-        // Fewer than 5 endpoints - use the lowest count
+        // Fewer than N endpoints - use the lowest count
         *sorted.last().unwrap_or(&0)
     };
 
@@ -327,38 +320,127 @@ pub fn classify_latency(latency_ms: Option<u64>, config: &LatencyConfig) -> Late
     }
 }
 
-/// Calculate particle position along an edge for spirit flow animation
-///
-/// Uses linear interpolation to position a particle along the line segment
-/// from `start` to `end`. The position is determined by combining the
-/// `pulse_phase` (animation time 0.0-1.0) with a particle `offset` to
-/// create multiple evenly-spaced particles moving along the edge.
-///
-/// # Arguments
-/// * `start` - Starting point (x, y) of the edge (typically HOST_CENTER)
-/// * `end` - Ending point (x, y) of the edge (endpoint position)
-/// * `pulse_phase` - Current animation phase (0.0 to 1.0, cycles over time)
-/// * `offset` - Particle offset along the edge (0.0, 0.33, 0.66 for 3 particles)
-///
-/// # Returns
-/// (x, y) coordinates of the particle position in canvas space
-///
-/// Requirements: 2.2
-pub fn particle_position(
-    start: (f64, f64),
-    end: (f64, f64),
-    pulse_phase: f32,
-    offset: f32,
-) -> (f64, f64) {
-    // Calculate parametric position t along the edge (0.0 to 1.0)
-    // Wrapping with modulo ensures smooth cycling animation
-    let t = ((pulse_phase + offset) % 1.0) as f64;
+/// Angle (degrees) within which edges leaving the host count as parallel
+const PARALLEL_EDGE_DEGREES: f64 = 8.0;
+
+/// Sideways bow (canvas units) between neighbouring parallel edges
+const EDGE_BEND_STEP: f64 = 3.0;
+
+/// Line segments a curved edge is sampled into
+const EDGE_CURVE_SAMPLES: usize = 12;
+
+/// An edge routed as a quadratic curve from the host to an endpoint;
+/// straight when its bend is zero
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EdgeCurve {
+    pub start: (f64, f64),
+    pub control: (f64, f64),
+    pub end: (f64, f64),
+}
+
+impl EdgeCurve {
+    /// Curve whose midpoint is pushed `bend` canvas units to the left of
+    /// the straight line (to the right when negative)
+    pub fn new(start: (f64, f64), end: (f64, f64), bend: f64) -> Self {
+        let (dx, dy) = (end.0 - start.0, end.1 - start.1);
+        let len = (dx * dx + dy * dy).sqrt();
+        let mid = ((start.0 + end.0) / 2.0, (start.1 + end.1) / 2.0);
+        // A quadratic curve passes halfway between the chord and its
+        // control point
+        let control = if len == 0.0 {
+            mid
+        } else {
+            (mid.0 - dy / len * bend * 2.0, mid.1 + dx / len * bend * 2.0)
+        };
+        Self {
+            start,
+            control,
+            end,
+        }
+    }
+
+    /// Point at `t` (0.0 at the host, 1.0 at the endpoint)
+    pub fn point(&self, t: f64) -> (f64, f64) {
+        let u = 1.0 - t;
+        let at = |s: f64, c: f64, e: f64| u * u * s + 2.0 * u * t * c + t * t * e;
+        (
+            at(self.start.0, self.control.0, self.end.0),
+            at(self.start.1, self.control.1, self.end.1),
+        )
+    }
 
-    // Linear interpolation between start and end points
-    let x = start.0 + (end.0 - start.0) * t;
-    let y = start.1 + (end.1 - start.1) * t;
+    /// Direction of travel (dx, dy) at `t`
+    pub fn tangent(&self, t: f64) -> (f64, f64) {
+        let u = 1.0 - t;
+        let at = |s: f64, c: f64, e: f64| 2.0 * u * (c - s) + 2.0 * t * (e - c);
+        (
+            at(self.start.0, self.control.0, self.end.0),
+            at(self.start.1, self.control.1, self.end.1),
+        )
+    }
 
-    (x, y)
+    /// Points to join with straight lines: just both ends for a straight
+    /// edge, `EDGE_CURVE_SAMPLES` segments otherwise
+    pub fn polyline(&self) -> Vec<(f64, f64)> {
+        let mid = (
+            (self.start.0 + self.end.0) / 2.0,
+            (self.start.1 + self.end.1) / 2.0,
+        );
+        if self.control == mid {
+            return vec![self.start, self.end];
+        }
+        (0..=EDGE_CURVE_SAMPLES)
+            .map(|i| self.point(i as f64 / EDGE_CURVE_SAMPLES as f64))
+            .collect()
+    }
+
+    /// The same curve travelled from the endpoint to the host
+    pub fn reversed(&self) -> Self {
+        Self {
+            start: self.end,
+            control: self.control,
+            end: self.start,
+        }
+    }
+
+    /// Calculate particle position along the edge for spirit flow animation
+    ///
+    /// The position is determined by combining the `pulse_phase` (animation
+    /// time 0.0-1.0) with a particle `offset` (0.0, 0.33, 0.66 for 3
+    /// particles) to create evenly-spaced particles moving along the edge;
+    /// wrapping with modulo keeps the flow cycling smoothly.
+    ///
+    /// Requirements: 2.2
+    pub fn particle_position(&self, pulse_phase: f32, offset: f32) -> (f64, f64) {
+        self.point(((pulse_phase + offset) % 1.0) as f64)
+    }
+}
+
+/// Bend of the edge from `center` to each of `targets`, so edges leaving
+/// at nearly the same angle fan out instead of drawing over each other;
+/// edges with no close neighbour stay straight
+pub fn edge_bends(center: (f64, f64), targets: &[(f64, f64)]) -> Vec<f64> {
+    let angle = |&(x, y): &(f64, f64)| (y - center.1).atan2(x - center.0).to_degrees();
+    let mut order: Vec<usize> = (0..targets.len()).collect();
+    order.sort_by(|&a, &b| angle(&targets[a]).total_cmp(&angle(&targets[b])));
+
+    let mut bends = vec![0.0; targets.len()];
+    let mut group_start = 0;
+    for i in 1..=order.len() {
+        let splits = i == order.len()
+            || angle(&targets[order[i]]) - angle(&targets[order[i - 1]]) > PARALLEL_EDGE_DEGREES;
+        if !splits {
+            continue;
+        }
+        // Fan the group out symmetrically, keeping its angular order
+        let group = &order[group_start..i];
+        let middle = (group.len() - 1) as f64 / 2.0;
+        for (rank, &idx) in group.iter().enumerate() {
+            bends[idx] = (rank as f64 - middle) * EDGE_BEND_STEP;
+        }
+        group_start = i;
+    }
+    bends
 }
 
 // ============================================================================
@@ -432,6 +514,10 @@ const MID_COFFIN_MAX_NAME: usize = 6;
 /// Placeholder string in template (exactly 6 chars)
 const MID_COFFIN_PLACEHOLDER: &str = "HOST__";
 
+/// Longest name shown in the caption under a coffin whose placeholder
+/// could not hold it
+const COFFIN_CAPTION_MAX_NAME: usize = 24;
+
 /// Coffin variant enumeration
 ///
 /// Determines which coffin template to use based on available space.
@@ -461,6 +547,13 @@ pub struct CoffinRender {
     pub width: usize,
     /// Height in lines
     pub height: usize,
+    /// Name printed under the coffin when it was truncated inside
+    pub caption: Option<String>,
+}
+
+/// Caption for a coffin whose `max_name` placeholder truncates `host`
+fn coffin_caption(host: &str, max_name: usize) -> Option<String> {
+    (host.chars().count() > max_name).then(|| truncate_host_name(host, COFFIN_CAPTION_MAX_NAME))
 }
 
 /// Truncate host name to fit within max_len, adding ".." suffix if needed
@@ -469,7 +562,7 @@ pub struct CoffinRender {
 /// - "HOST" with max_len=10 → "HOST"
 /// - "kafka-broker-1" with max_len=6 → "kafk.."
 /// - "AB" with max_len=2 → "AB"
-fn truncate_host_name(host: &str, max_len: usize) -> String {
+pub(super) fn truncate_host_name(host: &str, max_len: usize) -> String {
     let char_count = host.chars().count();
     if char_count <= max_len {
         host.to_string()
@@ -531,6 +624,7 @@ pub fn build_large_coffin(host: &str) -> CoffinRender {
         variant: CoffinVariant::Large,
         width: LARGE_COFFIN_WIDTH,
         height: LARGE_COFFIN_HEIGHT,
+        caption: coffin_caption(host, LARGE_COFFIN_MAX_NAME),
     }
 }
 
@@ -566,6 +660,7 @@ pub fn build_mid_coffin(host: &str) -> CoffinRender {
         variant: CoffinVariant::Mid,
         width: MID_COFFIN_WIDTH,
         height: MID_COFFIN_HEIGHT,
+        caption: coffin_caption(host, MID_COFFIN_MAX_NAME),
     }
 }
 
@@ -593,6 +688,7 @@ pub fn build_label_coffin(host: &str, max_width: usize) -> CoffinRender {
         variant: CoffinVariant::Label,
         width,
         height: 1,
+        caption: None,
     }
 }
 
@@ -624,15 +720,21 @@ pub fn choose_coffin_variant(area_width: f64, area_height: f64, host: &str) -> C
     let char_height = (area_height / 4.0) as usize;
 
     // Try Large coffin first (5 lines, 14 chars wide)
-    // Requires: width >= 14, height >= 5
-    if char_width >= LARGE_COFFIN_WIDTH && char_height >= LARGE_COFFIN_HEIGHT {
-        return build_large_coffin(host);
+    // Requires: width >= 14, height >= 5 (one more for a caption)
+    let large = build_large_coffin(host);
+    if char_width >= LARGE_COFFIN_WIDTH
+        && char_height >= LARGE_COFFIN_HEIGHT + usize::from(large.caption.is_some())
+    {
+        return large;
     }
 
     // Try Mid coffin (3 lines, 11 chars wide)
-    // Requires: width >= 11, height >= 3
-    if char_width >= MID_COFFIN_WIDTH && char_height >= MID_COFFIN_HEIGHT {
-        return build_mid_coffin(host);
+    // Requires: width >= 11, height >= 3 (one more for a caption)
+    let mid = build_mid_coffin(host);
+    if char_width >= MID_COFFIN_WIDTH
+        && char_height >= MID_COFFIN_HEIGHT + usize::from(mid.caption.is_some())
+    {
+        return mid;
     }
 
     // Fallback to Label (1 line, minimum 10 chars for readability)
@@ -730,6 +832,17 @@ pub fn draw_coffin_block(
         ctx.print(x, y, Span::styled(line.clone(), style));
     }
 
+    // The full name under a coffin too small to hold it
+    if let Some(ref caption) = coffin.caption {
+        let x = cx - caption.chars().count() as f64 * cell_width / 2.0;
+        let y = start_y - (coffin.lines.len() as f64 * line_spacing);
+        ctx.print(
+            x,
+            y,
+            Span::styled(caption.clone(), Style::default().fg(coffin_color)),
+        );
+    }
+
     variant
 }
 
@@ -747,6 +860,16 @@ pub fn get_coffin_variant_for_canvas(canvas_height: f64, host_name: &str) -> Cof
     choose_coffin_variant(100.0, canvas_height, host_name).variant
 }
 
+/// Color of latency ring `ring_idx` (0 = inner): inner ring is
+/// brightest, outer rings fade
+pub fn ring_color(ring_idx: usize) -> Color {
+    let opacity_factor = 1.0 - (ring_idx as f32 * 0.25);
+    let r = (169.0 * opacity_factor) as u8;
+    let g = (177.0 * opacity_factor) as u8;
+    let b = (214.0 * opacity_factor) as u8;
+    Color::Rgb(r, g, b)
+}
+
 /// Draw latency rings on the canvas around the HOST center
 ///
 /// Draws 3 concentric dotted circles using Braille markers:
@@ -755,12 +878,14 @@ pub fn get_coffin_variant_for_canvas(canvas_height: f64, host_name: &str) -> Cof
 /// - Outer ring: High latency endpoints (> 200ms)
 ///
 /// Ring radii are determined by the provided LayoutConfig, enabling adaptive
-/// scaling based on canvas dimensions.
+/// scaling based on canvas dimensions; `step_degrees` (from the animation
+/// quality tier) sets how far apart the dots are.
 ///
 /// Requirements: 1.1, 2.1
 pub fn draw_latency_rings<F>(
     ctx: &mut ratatui::widgets::canvas::Context<'_>,
     layout: &LayoutConfig,
+    step_degrees: usize,
     draw_point: F,
 ) where
     F: Fn(&mut ratatui::widgets::canvas::Context<'_>, f64, f64, Style),
@@ -771,16 +896,10 @@ pub fn draw_latency_rings<F>(
     let ring_radii = [layout.ring_low, layout.ring_medium, layout.ring_high];
 
     for (ring_idx, radius) in ring_radii.iter().enumerate() {
-        // Calculate opacity: inner ring is brightest, outer rings fade
-        let opacity_factor = 1.0 - (ring_idx as f32 * 0.25);
-        let r = (169.0 * opacity_factor) as u8;
-        let g = (177.0 * opacity_factor) as u8;
-        let b = (214.0 * opacity_factor) as u8;
-        let ring_color = Color::Rgb(r, g, b);
-        let ring_style = Style::default().fg(ring_color);
-
-        // Draw ring as series of dotted points (every 10 degrees for dotted effect)
-        for angle_deg in (0..360).step_by(10) {
+        let ring_style = Style::default().fg(ring_color(ring_idx));
+
+        // Draw ring as series of dotted points (every 10 degrees at Normal quality)
+        for angle_deg in (0..360).step_by(step_degrees) {
             let angle_rad = (angle_deg as f64).to_radians();
             let x = cx + radius * angle_rad.cos();
             let y = cy + radius * angle_rad.sin();
@@ -795,6 +914,74 @@ pub fn draw_latency_rings<F>(
     }
 }
 
+/// Band each latency ring stands for, innermost first: "<50ms",
+/// "50–200ms", ">200ms" with the default thresholds
+pub fn ring_band_labels(config: &LatencyConfig) -> [String; 3] {
+    [
+        format!("<{}ms", config.low_threshold_ms),
+        format!("{}–{}ms", config.low_threshold_ms, config.high_threshold_ms),
+        format!(">{}ms", config.high_threshold_ms),
+    ]
+}
+
+/// Print each ring's band label centered on its top arc, so the rings
+/// explain themselves; labels that would leave the padded canvas are
+/// skipped. `cell_width` is the width of one terminal cell in canvas units.
+pub fn draw_ring_labels(
+    ctx: &mut ratatui::widgets::canvas::Context<'_>,
+    layout: &LayoutConfig,
+    labels: &[String; 3],
+    cell_width: f64,
+) {
+    let (cx, cy) = HOST_CENTER;
+    let ring_radii = [layout.ring_low, layout.ring_medium, layout.ring_high];
+
+    for (ring_idx, (radius, label)) in ring_radii.iter().zip(labels).enumerate() {
+        let y = cy + radius;
+        let half_width = label.chars().count() as f64 * cell_width / 2.0;
+        if y > 100.0 - layout.edge_padding || cx - half_width < layout.edge_padding {
+            continue;
+        }
+        ctx.print(
+            cx - half_width,
+            y,
+            Span::styled(label.clone(), Style::default().fg(ring_color(ring_idx))),
+        );
+    }
+}
+
+/// Listening ports shown as candles before the rest are summed up as "+N"
+const MAX_LISTEN_CANDLES: usize = 8;
+
+/// Gap (canvas units) between the coffin's exclusion zone and the candle
+/// ring
+const LISTEN_RING_GAP: f64 = 3.0;
+
+/// Candles for a process's listening `ports` on a small ring of `radius`
+/// around `center`, clockwise from the top: (position, label) with labels
+/// ":8080", the last one "+N" when there are too many to show
+pub fn listen_candles(ports: &[u16], center: (f64, f64), radius: f64) -> Vec<((f64, f64), String)> {
+    let mut labels: Vec<String> = ports.iter().map(|port| format!(":{}", port)).collect();
+    if labels.len() > MAX_LISTEN_CANDLES {
+        let hidden = labels.len() - (MAX_LISTEN_CANDLES - 1);
+        labels.truncate(MAX_LISTEN_CANDLES - 1);
+        labels.push(format!("+{}", hidden));
+    }
+    let count = labels.len() as f64;
+    labels
+        .into_iter()
+        .enumerate()
+        .map(|(idx, label)| {
+            let angle = std::f64::consts::FRAC_PI_2 - idx as f64 / count * std::f64::consts::TAU;
+            let position = (
+                center.0 + radius * angle.cos(),
+                center.1 + radius * angle.sin(),
+            );
+            (position, label)
+        })
+        .collect()
+}
+
 /// Check if any endpoint has known latency data
 ///
 /// Returns true if at least one endpoint has a latency bucket other than Unknown.
@@ -875,8 +1062,411 @@ pub struct EndpointNode {
     pub latency_bucket: LatencyBucket,
     /// Endpoint type classification for icon and color selection
     pub endpoint_type: EndpointType,
-    /// Whether this endpoint is a heavy talker (top 5 by connection count)
+    /// Whether this endpoint passes the heavy-talker threshold
     pub is_heavy_talker: bool,
+    /// Whether the address is on the threat-intel blocklist
+    pub is_haunted: bool,
+    /// Whether an alert rule matches a connection to this endpoint
+    pub is_alerting: bool,
+    /// Badge for Tor exit / VPN endpoints
+    pub anonymizer_icon: Option<&'static str>,
+    /// Whether the address was never seen before the last hour
+    pub is_new: bool,
+    /// Who opened the connections to this endpoint; None when mixed
+    pub direction: Option<ConnectionDirection>,
+    /// Who opened most of them; particles flow from that side
+    pub flow: Option<ConnectionDirection>,
+}
+
+/// Color of the edge to an endpoint in `state`; states without a color
+/// of their own pulse (`pulse`)
+pub fn edge_color(state: ConnectionState, pulse: Color) -> Color {
+    match state {
+        ConnectionState::Established => TOXIC_GREEN,
+        ConnectionState::TimeWait | ConnectionState::CloseWait => PUMPKIN_ORANGE,
+        ConnectionState::SynSent | ConnectionState::SynRecv => Color::Yellow,
+        ConnectionState::Close => BLOOD_RED,
+        _ => pulse,
+    }
+}
+
+/// Number of parallel strands drawn for the edge to an endpoint with
+/// `conn_count` connections, so busy endpoints stand out on the map
+pub fn edge_strands(conn_count: usize) -> usize {
+    match conn_count {
+        0..=2 => 1,
+        3..=9 => 2,
+        _ => 3,
+    }
+}
+
+/// Edge color lifted toward white for every strand beyond the first
+pub fn edge_intensity(color: Color, strands: usize) -> Color {
+    brighten_color(color, strands.saturating_sub(1) as f32 * 0.2)
+}
+
+/// Segments of an edge drawn as `strands` parallel lines `spacing` apart,
+/// the first one on the edge itself and the rest alternating to either side
+pub fn edge_segments(
+    start: (f64, f64),
+    end: (f64, f64),
+    strands: usize,
+    spacing: f64,
+) -> Vec<((f64, f64), (f64, f64))> {
+    let (dx, dy) = (end.0 - start.0, end.1 - start.1);
+    let len = (dx * dx + dy * dy).sqrt();
+    if len == 0.0 {
+        return vec![(start, end)];
+    }
+    // Unit normal of the edge
+    let (nx, ny) = (-dy / len, dx / len);
+    (0..strands.max(1))
+        .map(|i| {
+            let side = if i % 2 == 1 { 1.0 } else { -1.0 };
+            let offset = side * i.div_ceil(2) as f64 * spacing;
+            let shift = |(x, y): (f64, f64)| (x + nx * offset, y + ny * offset);
+            (shift(start), shift(end))
+        })
+        .collect()
+}
+
+/// Color of an alerting endpoint's node and edge: swelling from `base` to
+/// blood red and back over one pulse cycle, steady red when the pulse is
+/// stopped (None)
+pub fn alert_color(base: Color, pulse_phase: Option<f32>) -> Color {
+    let Some(phase) = pulse_phase else {
+        return BLOOD_RED;
+    };
+    let ratio = 1.0 - (2.0 * phase - 1.0).abs();
+    match (base, BLOOD_RED) {
+        (Color::Rgb(r, g, b), Color::Rgb(red_r, red_g, red_b)) => {
+            interpolate_color((r, g, b), (red_r, red_g, red_b), ratio)
+        }
+        _ if ratio >= 0.5 => BLOOD_RED,
+        _ => base,
+    }
+}
+
+pub fn particle_color(state: ConnectionState, latency_bucket: LatencyBucket) -> Color {
+    match state {
+        ConnectionState::TimeWait | ConnectionState::CloseWait => PUMPKIN_ORANGE,
+        ConnectionState::Established if latency_bucket == LatencyBucket::High => PUMPKIN_ORANGE,
+        ConnectionState::Established => TOXIC_GREEN,
+        _ => NEON_PURPLE,
+    }
+}
+
+/// Color of an endpoint's icon and label; closing states and blocklisted
+/// addresses override the classification color
+pub fn node_color(state: ConnectionState, endpoint_type: &EndpointType, is_haunted: bool) -> Color {
+    match state {
+        _ if is_haunted => BLOOD_RED,
+        ConnectionState::TimeWait | ConnectionState::CloseWait => PUMPKIN_ORANGE,
+        ConnectionState::Close => BLOOD_RED,
+        _ => endpoint_type.color(),
+    }
+}
+
+/// Arrow pointing from the initiator to the acceptor along an edge from
+/// the host to an endpoint (`dx`, `dy` with y growing upwards)
+pub fn edge_arrow(dx: f64, dy: f64, direction: ConnectionDirection) -> &'static str {
+    const ARROWS: [&str; 8] = ["→", "↗", "↑", "↖", "←", "↙", "↓", "↘"];
+    let (dx, dy) = match direction {
+        ConnectionDirection::Outbound => (dx, dy),
+        ConnectionDirection::Inbound => (-dx, -dy),
+    };
+    let octant = (dy.atan2(dx) / std::f64::consts::FRAC_PI_4).round() as i64;
+    ARROWS[octant.rem_euclid(8) as usize]
+}
+
+/// Icon drawn for an endpoint node, with its heavy-talker, blocklist and
+/// anonymizer badges
+fn node_icon(node: &EndpointNode, overdrive_enabled: bool, glyphs: &GlyphSet) -> String {
+    let icon = if overdrive_enabled {
+        let overdrive_icon = get_overdrive_icon(node.state, node.latency_bucket);
+        if node.is_heavy_talker {
+            format!("{}{}", overdrive_icon, glyphs.crown)
+        } else {
+            overdrive_icon.to_string()
+        }
+    } else {
+        node.endpoint_type.icon_with_badge(node.is_heavy_talker)
+    };
+    // Blocklisted and anonymizer endpoints carry their mark in every theme
+    let icon = if node.is_haunted {
+        format!("{}{}", icon, glyphs.haunted)
+    } else {
+        icon
+    };
+    match node.anonymizer_icon {
+        Some(badge) => format!("{}{}", icon, badge),
+        None => icon,
+    }
+}
+
+/// Label under an endpoint node ("10.0.0.5 (3)"), with its badges and
+/// their colors: NEW for a first-time peer and the environment of the
+/// matching endpoint rule ("[prod] ")
+fn endpoint_label(node: &EndpointNode) -> (Vec<(String, Color)>, String) {
+    let mut badges = Vec::new();
+    if node.is_new {
+        badges.push(("NEW ".to_string(), TOXIC_GREEN));
+    }
+    if let EndpointType::Custom(ref rule) = node.endpoint_type {
+        if let Some(env) = rule.environment.as_deref() {
+            badges.push((format!("[{}] ", env), environment_color(env)));
+        }
+    }
+    (badges, format!("{} ({})", node.label, node.conn_count))
+}
+
+/// Node icon and label of one endpoint, as sized for the label layout
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LabelRequest {
+    /// Node position (canvas units)
+    pub node: (f64, f64),
+    /// Width of the node icon (canvas units)
+    pub icon_width: f64,
+    /// Width of the label (canvas units)
+    pub label_width: f64,
+}
+
+/// Canvas geometry the label layout works in (canvas units)
+#[derive(Debug, Clone, PartialEq)]
+pub struct LabelSpace {
+    /// Width of one terminal cell
+    pub cell_width: f64,
+    /// Height of one terminal row
+    pub row_height: f64,
+    /// Canvas width; the height is always 100
+    pub x_range: f64,
+    /// Coffin center and radius; labels never cover it
+    pub coffin: ((f64, f64), f64),
+    /// Drawn latency rings (center, radius); labels avoid them when they can
+    pub rings: Vec<((f64, f64), f64)>,
+}
+
+/// Screen area taken by a label or icon
+#[derive(Debug, Clone, Copy)]
+struct Extent {
+    x0: f64,
+    y0: f64,
+    x1: f64,
+    y1: f64,
+}
+
+impl Extent {
+    fn overlaps(&self, other: &Extent) -> bool {
+        self.x0 < other.x1 && other.x0 < self.x1 && self.y0 < other.y1 && other.y0 < self.y1
+    }
+
+    /// Nearest and farthest distance from `point` to the extent
+    fn distances(&self, point: (f64, f64)) -> (f64, f64) {
+        let gap = |p: f64, lo: f64, hi: f64| (lo - p).max(p - hi).max(0.0);
+        let far = |p: f64, lo: f64, hi: f64| (p - lo).abs().max((hi - p).abs());
+        (
+            gap(point.0, self.x0, self.x1).hypot(gap(point.1, self.y0, self.y1)),
+            far(point.0, self.x0, self.x1).hypot(far(point.1, self.y0, self.y1)),
+        )
+    }
+}
+
+/// Where each endpoint label goes (the `ctx.print` position), placing
+/// them in `priority` order: below its node, else above, right or left of
+/// it, whichever first clears the canvas edge, the coffin, every node icon
+/// and the labels placed so far, preferring spots off the latency rings.
+/// Labels that fit nowhere are elided (None).
+pub fn layout_labels(
+    requests: &[LabelRequest],
+    priority: &[usize],
+    space: &LabelSpace,
+) -> Vec<Option<(f64, f64)>> {
+    let row = space.row_height;
+    let icons: Vec<Extent> = requests
+        .iter()
+        .map(|r| Extent {
+            x0: r.node.0 - r.icon_width / 2.0,
+            y0: r.node.1,
+            x1: r.node.0 + r.icon_width / 2.0,
+            y1: r.node.1 + row,
+        })
+        .collect();
+    let mut placed: Vec<Extent> = Vec::new();
+    let mut positions = vec![None; requests.len()];
+
+    for &idx in priority {
+        let Some(request) = requests.get(idx) else {
+            continue;
+        };
+        let (nx, ny) = request.node;
+        let (w, gap) = (
+            request.label_width,
+            request.icon_width / 2.0 + space.cell_width,
+        );
+        let candidates = [
+            (nx - w / 2.0, ny - row),
+            (nx - w / 2.0, ny + row),
+            (nx + gap, ny),
+            (nx - gap - w, ny),
+        ];
+        let extent = |(x, y): (f64, f64)| Extent {
+            x0: x,
+            y0: y,
+            x1: x + w,
+            y1: y + row,
+        };
+        let fits = |at: &(f64, f64)| {
+            let e = extent(*at);
+            let (coffin_center, coffin_radius) = space.coffin;
+            e.x0 >= 0.0
+                && e.x1 <= space.x_range
+                && e.y0 >= 0.0
+                && e.y1 <= 100.0
+                && e.distances(coffin_center).0 > coffin_radius
+                && !icons.iter().any(|icon| icon.overlaps(&e))
+                && !placed.iter().any(|label| label.overlaps(&e))
+        };
+        let off_rings = |at: &(f64, f64)| {
+            let e = extent(*at);
+            space.rings.iter().all(|&(center, radius)| {
+                let (near, far) = e.distances(center);
+                radius < near || radius > far
+            })
+        };
+        let choice = candidates
+            .iter()
+            .find(|at| fits(at) && off_rings(at))
+            .or_else(|| candidates.iter().find(|at| fits(at)));
+        if let Some(&at) = choice {
+            placed.push(extent(at));
+            positions[idx] = Some(at);
+        }
+    }
+    positions
+}
+
+/// Panel title, with the Process-mode breadcrumb once a process was
+/// focused: "HOST › nginx (1234) › worker (1250)"
+fn graveyard_title(app: &AppState) -> Vec<Span<'static>> {
+    let bold = Style::default()
+        .fg(NEON_PURPLE)
+        .add_modifier(Modifier::BOLD);
+    let mut title = vec![Span::styled(
+        format!(
+            "━ 🕸️ The Graveyard (Network Topology · by {}) ━",
+            app.grouping.label()
+        ),
+        bold,
+    )];
+    let trail = app.focus_history.breadcrumb();
+    if trail.len() > 1 {
+        title.push(Span::raw(" "));
+        for (i, step) in trail.iter().enumerate() {
+            if i > 0 {
+                title.push(Span::styled(" › ", Style::default().fg(Color::DarkGray)));
+            }
+            let style = if i + 1 == trail.len() {
+                Style::default()
+                    .fg(PUMPKIN_ORANGE)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(BONE_WHITE)
+            };
+            title.push(Span::styled(step.label(), style));
+        }
+        title.push(Span::styled(" ━", bold));
+    }
+    title
+}
+
+/// The direction most of `directions` share, if any (None on a tie or
+/// when no direction is known)
+fn dominant_direction(
+    directions: impl Iterator<Item = Option<ConnectionDirection>>,
+) -> Option<ConnectionDirection> {
+    let (mut inbound, mut outbound) = (0, 0);
+    for direction in directions.flatten() {
+        match direction {
+            ConnectionDirection::Inbound => inbound += 1,
+            ConnectionDirection::Outbound => outbound += 1,
+        }
+    }
+    match inbound.cmp(&outbound) {
+        std::cmp::Ordering::Greater => Some(ConnectionDirection::Inbound),
+        std::cmp::Ordering::Less => Some(ConnectionDirection::Outbound),
+        std::cmp::Ordering::Equal => None,
+    }
+}
+
+/// The direction shared by all of `directions`, if any
+fn common_direction(
+    mut directions: impl Iterator<Item = Option<ConnectionDirection>>,
+) -> Option<ConnectionDirection> {
+    let first = directions.next()??;
+    directions.all(|d| d == Some(first)).then_some(first)
+}
+
+/// Height of the group summary below the Graveyard (including borders)
+pub const GROUP_SUMMARY_HEIGHT: u16 = 8;
+
+/// Render the per-group summary shown while grouping endpoints:
+/// "GOOGLE  14 conns  5 IPs  AS15169,AS396982", busiest first, with
+/// expanded groups marked "▾"
+pub fn render_group_summary(f: &mut Frame, area: Rect, app: &AppState) {
+    let groups = app.endpoint_groups();
+    let rows = area.height.saturating_sub(2) as usize;
+
+    let mut lines: Vec<Line> = groups
+        .iter()
+        .take(rows)
+        .map(|group| {
+            let marker = if app.expanded_groups.contains(&group.name) {
+                "▾"
+            } else {
+                "▸"
+            };
+            Line::from(vec![
+                Span::styled(format!(" {} ", marker), Style::default().fg(NEON_PURPLE)),
+                Span::styled(
+                    format!("{:<24.24} ", group.name),
+                    Style::default().fg(BONE_WHITE).add_modifier(Modifier::BOLD),
+                ),
+                Span::styled(
+                    format!("{:>4} conns ", group.connections),
+                    Style::default().fg(TOXIC_GREEN),
+                ),
+                Span::styled(
+                    format!("{:>3} IPs  ", group.endpoints),
+                    Style::default().fg(PUMPKIN_ORANGE),
+                ),
+                Span::styled(group.tags.join(","), Style::default().fg(Color::DarkGray)),
+            ])
+        })
+        .collect();
+    if lines.is_empty() {
+        lines.push(Line::from(Span::styled(
+            " No grouped endpoints yet",
+            Style::default().fg(Color::DarkGray),
+        )));
+    }
+
+    let summary = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(NEON_PURPLE))
+            .title(Span::styled(
+                format!(
+                    "━ 🏢 {} ({}) · Enter: expand ━",
+                    app.grouping.title(),
+                    groups.len()
+                ),
+                Style::default()
+                    .fg(NEON_PURPLE)
+                    .add_modifier(Modifier::BOLD),
+            )),
+    );
+    f.render_widget(summary, area);
 }
 
 pub fn render_network_map(f: &mut Frame, area: Rect, app: &AppState) {
@@ -890,10 +1480,10 @@ pub fn render_network_map(f: &mut Frame, area: Rect, app: &AppState) {
     let filtered_connections: Vec<&crate::net::Connection> = match app.graveyard_mode {
         GraveyardMode::Host => app.connections.iter().collect(),
         GraveyardMode::Process => {
-            if let Some(selected_pid) = app.selected_process_pid {
+            if app.selected_process_pid.is_some() {
                 app.connections
                     .iter()
-                    .filter(|conn| conn.pid == Some(selected_pid))
+                    .filter(|conn| app.is_focused_pid(conn.pid))
                     .collect()
             } else {
                 Vec::new()
@@ -902,46 +1492,58 @@ pub fn render_network_map(f: &mut Frame, area: Rect, app: &AppState) {
     };
 
     // Collect endpoint data from filtered connections
-    let mut endpoints_map: HashMap<String, Vec<&crate::net::Connection>> = HashMap::new();
+    let mut endpoints_map: HashMap<Arc<str>, Vec<&crate::net::Connection>> = HashMap::new();
     let mut listen_count = 0;
+    // Group names used as keys while grouping endpoints
+    let mut endpoint_groups: HashSet<Arc<str>> = HashSet::new();
+
+    // Ports the focused process listens on, drawn as candles around the coffin
+    let mut listen_ports: Vec<u16> = Vec::new();
 
     for conn in &filtered_connections {
         if conn.state == ConnectionState::Listen {
             listen_count += 1;
-        } else if conn.remote_addr != "0.0.0.0" {
-            endpoints_map
-                .entry(conn.remote_addr.clone())
-                .or_default()
-                .push(conn);
+            if app.graveyard_mode == GraveyardMode::Process {
+                listen_ports.push(conn.local_port);
+            }
+        } else if let Some((key, is_group)) = app.endpoint_key(conn) {
+            if is_group {
+                endpoint_groups.insert(key.clone());
+            }
+            endpoints_map.entry(key).or_default().push(conn);
         }
     }
 
     let endpoint_count = endpoints_map.len();
+    listen_ports.sort_unstable();
+    listen_ports.dedup();
 
     // Determine center node label based on mode
     let center_label = match app.graveyard_mode {
-        GraveyardMode::Host => "HOST".to_string(),
+        GraveyardMode::Host => app.host_name.clone(),
         GraveyardMode::Process => {
             if let Some(pid) = app.selected_process_pid {
                 let process_name = filtered_connections
                     .iter()
                     .find_map(|conn| {
                         if conn.pid == Some(pid) {
-                            conn.process_name.clone()
+                            conn.process_name.as_deref()
                         } else {
                             None
                         }
                     })
-                    .unwrap_or_else(|| "unknown".to_string());
+                    .unwrap_or("unknown");
 
-                let short_name = if process_name.len() > 8 {
-                    format!("{}...", &process_name[..5])
+                let short_name = truncate_host_name(process_name, 8);
+                // "+N" marks aggregated descendant processes
+                let child_count = app.focus_pids.len().saturating_sub(1);
+                if child_count > 0 {
+                    format!("{} ({}+{})", short_name, pid, child_count)
                 } else {
-                    process_name
-                };
-                format!("{} ({})", short_name, pid)
+                    format!("{} ({})", short_name, pid)
+                }
             } else {
-                "HOST".to_string()
+                app.host_name.clone()
             }
         }
     };
@@ -958,43 +1560,44 @@ pub fn render_network_map(f: &mut Frame, area: Rect, app: &AppState) {
             ),
             Style::default().fg(BONE_WHITE),
         ),
-        // Legend for icons
+        // The full legend lives in its own popup
         Span::styled("[", Style::default().fg(Color::DarkGray)),
-        Span::styled("⚰️ ", Style::default().fg(PUMPKIN_ORANGE)),
-        Span::styled("host ", Style::default().fg(Color::DarkGray)),
-        Span::styled("🏠 ", Style::default().fg(TOXIC_GREEN)),
-        Span::styled("local ", Style::default().fg(Color::DarkGray)),
-        Span::styled("🎃 ", Style::default().fg(PUMPKIN_ORANGE)),
-        Span::styled("ext ", Style::default().fg(Color::DarkGray)),
-        Span::styled("👑 ", Style::default().fg(Color::Yellow)),
-        Span::styled("hot", Style::default().fg(Color::DarkGray)),
-        Span::styled("]", Style::default().fg(Color::DarkGray)),
+        Span::styled("?", Style::default().fg(NEON_PURPLE)),
+        Span::styled(" legend]", Style::default().fg(Color::DarkGray)),
     ]))
     .block(
         Block::default()
             .borders(Borders::TOP | Borders::LEFT | Borders::RIGHT)
             .border_type(BorderType::Rounded)
             .border_style(Style::default().fg(NEON_PURPLE))
-            .title(vec![Span::styled(
-                "━ 🕸️ The Graveyard (Network Topology) ━",
-                Style::default()
-                    .fg(NEON_PURPLE)
-                    .add_modifier(Modifier::BOLD),
-            )]),
+            .title(graveyard_title(app)),
     );
     f.render_widget(summary, chunks[0]);
 
     // Prepare endpoint nodes with latency-based ring layout
     let mut sorted_endpoints: Vec<_> = endpoints_map.iter().collect();
-    sorted_endpoints.sort_by(|a, b| b.1.len().cmp(&a.1.len()));
+    sorted_endpoints.sort_by_key(|b| std::cmp::Reverse(b.1.len()));
 
-    let max_nodes = MAX_VISIBLE_ENDPOINTS;
+    let max_nodes = app.graveyard_settings.max_visible_endpoints.max(1);
     let latency_config = &app.latency_config;
-    let hidden_endpoint_count = sorted_endpoints.len().saturating_sub(max_nodes);
+    let page = endpoint_page(sorted_endpoints.len(), max_nodes, app.endpoint_page);
+
+    // The node of the selected connection is highlighted (Enter opens it),
+    // or that of the inspector's selected socket while its list is focused
+    let selected_key = app
+        .highlighted_connection()
+        .and_then(|conn| app.endpoint_key(conn))
+        .map(|(key, _)| key);
+    let selected_node = sorted_endpoints
+        .iter()
+        .skip(page.first)
+        .take(max_nodes)
+        .position(|(addr, _)| Some(*addr) == selected_key.as_ref());
 
-    // First pass: classify all endpoints
+    // First pass: classify the endpoints on the current page
     let endpoint_data: Vec<_> = sorted_endpoints
         .iter()
+        .skip(page.first)
         .take(max_nodes)
         .map(|(addr, conns)| {
             let state = conns
@@ -1011,25 +1614,94 @@ pub fn render_network_map(f: &mut Frame, area: Rect, app: &AppState) {
                 .map(|(state, _)| state)
                 .unwrap_or(ConnectionState::Unknown);
 
-            let label = if addr.len() > 15 {
-                format!("{}...", &addr[..12])
+            let latency_bucket = classify_latency(None, latency_config);
+            let direction = common_direction(conns.iter().map(|c| c.direction));
+            let flow = dominant_direction(conns.iter().map(|c| c.direction));
+
+            // A group node stands for all its addresses
+            if endpoint_groups.contains(*addr) {
+                let endpoint_type =
+                    classify_endpoint(&conns[0].remote_addr, false, &app.endpoint_rules);
+                let label = if addr.chars().count() > 15 {
+                    format!("{}...", addr.chars().take(12).collect::<String>())
+                } else {
+                    addr.to_string()
+                };
+                let is_haunted = conns.iter().any(|c| app.is_haunted(&c.remote_addr));
+                let is_alerting = conns.iter().any(|c| app.is_alerting(c));
+                let anonymizer_icon = conns
+                    .iter()
+                    .find_map(|c| app.anonymizer(&c.remote_addr))
+                    .map(|a| a.icon());
+                return (
+                    label,
+                    state,
+                    conns.len(),
+                    latency_bucket,
+                    endpoint_type,
+                    is_haunted,
+                    is_alerting,
+                    anonymizer_icon,
+                    false,
+                    direction,
+                    flow,
+                );
+            }
+
+            let is_listen_socket = addr.as_ref() == "0.0.0.0"
+                && conns.iter().all(|c| c.state == ConnectionState::Listen);
+            let endpoint_type = classify_endpoint(addr, is_listen_socket, &app.endpoint_rules);
+
+            // Rule-matched endpoints are shown by their configured name,
+            // inventory assets by their asset name, cloud endpoints by their
+            // provider/service, then the host name
+            let name: Cow<str> = match endpoint_type {
+                EndpointType::Custom(ref rule) => Cow::Borrowed(&rule.label),
+                _ => match (app.inventory_entry(addr), app.cloud_range(addr)) {
+                    (Some(asset), _) => Cow::Borrowed(&asset.name),
+                    (None, Some(range)) => Cow::Owned(range.short_label()),
+                    (None, None) => match app.reverse_name(addr) {
+                        Some(reverse) => Cow::Borrowed(&reverse.name),
+                        None => Cow::Borrowed(addr),
+                    },
+                },
+            };
+            let mut label = if name.chars().count() > 15 {
+                format!("{}...", name.chars().take(12).collect::<String>())
             } else {
-                (*addr).to_string()
+                name.to_string()
             };
+            // Registration country of public endpoints (GeoIP)
+            if let Some(country) = conns.iter().find_map(|c| c.labels.get("country")) {
+                label = format!("{} {}", label, glyphs().country(country));
+            }
 
-            let latency_bucket = classify_latency(None, latency_config);
-            let is_listen_socket =
-                *addr == "0.0.0.0" && conns.iter().all(|c| c.state == ConnectionState::Listen);
-            let endpoint_type = classify_endpoint(addr, is_listen_socket);
-
-            (label, state, conns.len(), latency_bucket, endpoint_type)
+            let is_haunted = app.is_haunted(addr);
+            let is_alerting = conns.iter().any(|c| app.is_alerting(c));
+            let anonymizer_icon = app.anonymizer(addr).map(|a| a.icon());
+            let is_new = app.is_new_peer(addr);
+
+            (
+                label,
+                state,
+                conns.len(),
+                latency_bucket,
+                endpoint_type,
+                is_haunted,
+                is_alerting,
+                anonymizer_icon,
+                is_new,
+                direction,
+                flow,
+            )
         })
         .collect();
 
     let all_conn_counts: Vec<usize> = endpoint_data
         .iter()
-        .map(|(_, _, count, _, _)| *count)
+        .map(|(_, _, count, _, _, _, _, _, _, _, _)| *count)
         .collect();
+    let heavy_talkers = app.graveyard_settings.heavy_talkers;
 
     // Calculate adaptive layout based on canvas size
     // Larger terminals get larger ring radii for better spacing
@@ -1056,7 +1728,7 @@ pub fn render_network_map(f: &mut Frame, area: Rect, app: &AppState) {
 
     // Count endpoints per latency bucket for position calculation
     let mut bucket_counts: HashMap<LatencyBucket, usize> = HashMap::new();
-    for (_, _, _, bucket, _) in &endpoint_data {
+    for (_, _, _, bucket, _, _, _, _, _, _, _) in &endpoint_data {
         *bucket_counts.entry(*bucket).or_insert(0) += 1;
     }
 
@@ -1066,7 +1738,19 @@ pub fn render_network_map(f: &mut Frame, area: Rect, app: &AppState) {
     let nodes: Vec<EndpointNode> = endpoint_data
         .into_iter()
         .map(
-            |(label, state, conn_count, latency_bucket, endpoint_type)| {
+            |(
+                label,
+                state,
+                conn_count,
+                latency_bucket,
+                endpoint_type,
+                is_haunted,
+                is_alerting,
+                anonymizer_icon,
+                is_new,
+                direction,
+                flow,
+            )| {
                 let idx_in_bucket = *bucket_indices.entry(latency_bucket).or_insert(0);
                 let total_in_bucket = *bucket_counts.get(&latency_bucket).unwrap_or(&1);
                 *bucket_indices.get_mut(&latency_bucket).unwrap() += 1;
@@ -1077,7 +1761,7 @@ pub fn render_network_map(f: &mut Frame, area: Rect, app: &AppState) {
                     latency_bucket,
                     &layout_config,
                 );
-                let is_heavy = is_heavy_talker(conn_count, &all_conn_counts);
+                let is_heavy = is_heavy_talker(conn_count, &all_conn_counts, heavy_talkers);
 
                 EndpointNode {
                     label,
@@ -1088,6 +1772,12 @@ pub fn render_network_map(f: &mut Frame, area: Rect, app: &AppState) {
                     latency_bucket,
                     endpoint_type,
                     is_heavy_talker: is_heavy,
+                    is_haunted,
+                    is_alerting,
+                    anonymizer_icon,
+                    is_new,
+                    direction,
+                    flow,
                 }
             },
         )
@@ -1101,12 +1791,18 @@ pub fn render_network_map(f: &mut Frame, area: Rect, app: &AppState) {
     let graveyard_mode = app.graveyard_mode;
     let should_draw_rings = has_latency_data(&nodes);
     let animations_enabled = app.graveyard_settings.animations_enabled;
+    // Alerting endpoints pulse while the pulse moves, else glow steady red
+    let alert_pulse = app.pulse_running().then_some(app.pulse_phase);
     let pulse_phase = app.pulse_phase;
     let edge_count = nodes.len();
-    let animation_reduced = app.animation_reduced;
+    let animation_quality = app.graveyard_settings.animation_quality;
     let labels_enabled = app.graveyard_settings.labels_enabled;
     let overdrive_enabled = app.graveyard_settings.overdrive_enabled;
     let emoji_width_offset = app.graveyard_settings.emoji_width_offset;
+    let glyphs = glyphs();
+    let ambiance_active = app.ambiance_active();
+    let tick = app.tick_counter;
+    let flashing = ambiance_active && app.ambiance.flashing();
 
     // Calculate canvas dimensions for proper aspect ratio
     // Braille markers: each cell is 2x4 dots, so we multiply accordingly
@@ -1142,6 +1838,28 @@ pub fn render_network_map(f: &mut Frame, area: Rect, app: &AppState) {
 
     // For closure capture
     let canvas_height = canvas_pixel_height;
+    let ring_labels = ring_band_labels(&app.latency_config);
+    let bends = edge_bends(
+        (x_center, 50.0),
+        &nodes
+            .iter()
+            .map(|node| (node.x, node.y))
+            .collect::<Vec<_>>(),
+    );
+    // One Braille dot in canvas units (the same along both axes)
+    let dot_spacing = 100.0 / canvas_pixel_height.max(1.0);
+    let cell_width = x_range / canvas_width_cells.max(1.0);
+    let row_height = 100.0 / canvas_height_cells.max(1.0);
+
+    // Lightning lights up the frame and the sky behind the map
+    let (border_style, background) = if flashing {
+        (
+            Style::default().fg(BONE_WHITE).add_modifier(Modifier::BOLD),
+            LIGHTNING_BACKGROUND,
+        )
+    } else {
+        (Style::default().fg(NEON_PURPLE), Color::Reset)
+    };
 
     // Canvas with Braille markers
     let canvas = Canvas::default()
@@ -1149,8 +1867,9 @@ pub fn render_network_map(f: &mut Frame, area: Rect, app: &AppState) {
             Block::default()
                 .borders(Borders::BOTTOM | Borders::LEFT | Borders::RIGHT)
                 .border_type(BorderType::Rounded)
-                .border_style(Style::default().fg(NEON_PURPLE)),
+                .border_style(border_style),
         )
+        .background_color(background)
         .marker(Marker::Braille)
         .x_bounds([0.0, x_range])
         .y_bounds([0.0, 100.0])
@@ -1159,26 +1878,40 @@ pub fn render_network_map(f: &mut Frame, area: Rect, app: &AppState) {
             let cx = x_center;
             let cy = 50.0;
 
-            // Draw latency rings first (behind everything else)
+            // Fog drifts behind everything else in overdrive
+            if ambiance_active {
+                for (x, y) in fog_positions(tick, animation_quality) {
+                    ctx.print(
+                        x * x_range,
+                        y * 100.0,
+                        Span::styled(FOG_SYMBOL, Style::default().fg(FOG_COLOR)),
+                    );
+                }
+            }
+
+            // Draw latency rings (behind nodes and edges)
             // Uses adaptive layout config for ring radii
             if should_draw_rings {
-                draw_latency_rings(ctx, &layout_config, |ctx, x, y, style| {
+                let step = animation_quality.ring_step_degrees();
+                draw_latency_rings(ctx, &layout_config, step, |ctx, x, y, style| {
                     ctx.print(x, y, Span::styled("·", style));
                 });
+                draw_ring_labels(ctx, &layout_config, &ring_labels, cell_width);
             }
 
             // Calculate coffin exclusion zone radius based on selected variant
             // This ensures connection lines don't overlap the coffin silhouette
             let coffin_variant = get_coffin_variant_for_canvas(canvas_height, &center_label);
             let coffin_radius = coffin_exclusion_radius(coffin_variant);
+            // Listening-socket candles circle just outside it
+            let candle_radius = coffin_radius + LISTEN_RING_GAP;
 
-            for node in &nodes {
-                let line_color = match node.state {
-                    ConnectionState::Established => TOXIC_GREEN,
-                    ConnectionState::TimeWait | ConnectionState::CloseWait => PUMPKIN_ORANGE,
-                    ConnectionState::SynSent | ConnectionState::SynRecv => Color::Yellow,
-                    ConnectionState::Close => BLOOD_RED,
-                    _ => pulse_color,
+            for (node, &bend) in nodes.iter().zip(&bends) {
+                let line_color = edge_color(node.state, pulse_color);
+                let line_color = if node.is_alerting {
+                    alert_color(line_color, alert_pulse)
+                } else {
+                    line_color
                 };
 
                 let dx = node.x - cx;
@@ -1192,14 +1925,38 @@ pub fn render_network_map(f: &mut Frame, area: Rect, app: &AppState) {
                     (cx, cy)
                 };
 
-                // Draw base edge line
-                ctx.draw(&CanvasLine {
-                    x1: start_x,
-                    y1: start_y,
-                    x2: node.x,
-                    y2: node.y,
-                    color: line_color,
-                });
+                // Draw base edge, heavier and brighter for busy endpoints and
+                // bowed aside when it runs parallel to its neighbours
+                let curve = EdgeCurve::new((start_x, start_y), (node.x, node.y), bend);
+                let strands = edge_strands(node.conn_count);
+                let strand_color = edge_intensity(line_color, strands);
+                for piece in curve.polyline().windows(2) {
+                    for ((x1, y1), (x2, y2)) in
+                        edge_segments(piece[0], piece[1], strands, dot_spacing)
+                    {
+                        ctx.draw(&CanvasLine {
+                            x1,
+                            y1,
+                            x2,
+                            y2,
+                            color: strand_color,
+                        });
+                    }
+                }
+
+                // Arrow from the side that opened the connections
+                if let Some(direction) = node.direction {
+                    let (ax, ay) = curve.point(0.6);
+                    let (tx, ty) = curve.tangent(0.6);
+                    ctx.print(
+                        ax,
+                        ay,
+                        Span::styled(
+                            edge_arrow(tx, ty, direction),
+                            Style::default().fg(line_color),
+                        ),
+                    );
+                }
 
                 // Draw particles if animations enabled
                 if animations_enabled {
@@ -1210,36 +1967,27 @@ pub fn render_network_map(f: &mut Frame, area: Rect, app: &AppState) {
                         continue;
                     }
 
-                    let particle_color = match node.state {
-                        ConnectionState::TimeWait | ConnectionState::CloseWait => PUMPKIN_ORANGE,
-                        ConnectionState::Established => {
-                            if node.latency_bucket == LatencyBucket::High {
-                                PUMPKIN_ORANGE
-                            } else {
-                                TOXIC_GREEN
-                            }
-                        }
-                        _ => NEON_PURPLE,
+                    let particle_color = particle_color(node.state, node.latency_bucket);
+
+                    let quality = if edge_count > PARTICLE_REDUCTION_THRESHOLD {
+                        animation_quality.min(AnimationQuality::Low)
+                    } else {
+                        animation_quality
                     };
 
-                    let particle_offsets: &[f32] =
-                        if animation_reduced || edge_count > PARTICLE_REDUCTION_THRESHOLD {
-                            &REDUCED_PARTICLE_OFFSETS
-                        } else {
-                            &PARTICLE_OFFSETS
-                        };
-
-                    for &offset in particle_offsets {
-                        let (px, py) = particle_position(
-                            (start_x, start_y),
-                            (node.x, node.y),
-                            pulse_phase,
-                            offset,
-                        );
+                    // Spirits flow from the side that opened most of the
+                    // connections: out to the endpoint, or in to the host
+                    let flow_curve = if node.flow == Some(ConnectionDirection::Inbound) {
+                        curve.reversed()
+                    } else {
+                        curve
+                    };
+                    for &offset in quality.particle_offsets() {
+                        let (px, py) = flow_curve.particle_position(pulse_phase, offset);
                         ctx.print(
                             px,
                             py,
-                            Span::styled(PARTICLE_SYMBOL, Style::default().fg(particle_color)),
+                            Span::styled(&glyphs.particle, Style::default().fg(particle_color)),
                         );
                     }
                 }
@@ -1248,28 +1996,95 @@ pub fn render_network_map(f: &mut Frame, area: Rect, app: &AppState) {
             // Draw coffin block at center
             draw_coffin_block(ctx, &center_label, overdrive_enabled, canvas_height, cx, cy);
 
-            // Draw endpoint nodes
-            for node in &nodes {
-                let icon = if overdrive_enabled {
-                    let overdrive_icon = get_overdrive_icon(node.state, node.latency_bucket);
-                    if node.is_heavy_talker {
-                        format!("{}👑", overdrive_icon)
-                    } else {
-                        overdrive_icon.to_string()
-                    }
+            // The focused process's own listening sockets, separate from
+            // the remote endpoints it talks to
+            for ((x, y), label) in listen_candles(&listen_ports, (cx, cy), candle_radius) {
+                let icon_width =
+                    corrected_str_width_with_offset(&glyphs.listen, emoji_width_offset) as f64;
+                let style = Style::default().fg(EndpointType::ListenOnly.color());
+                ctx.print(
+                    x - icon_width * cell_width / 2.0,
+                    y,
+                    Line::from(vec![
+                        Span::styled(glyphs.listen.clone(), style),
+                        Span::styled(label, style),
+                    ]),
+                );
+            }
+
+            // Icons and labels first, so the labels can be laid out around
+            // every node before anything is drawn
+            let icons: Vec<String> = nodes
+                .iter()
+                .map(|node| node_icon(node, overdrive_enabled, glyphs))
+                .collect();
+            let labels: Vec<(Vec<(String, Color)>, String)> =
+                nodes.iter().map(endpoint_label).collect();
+            let width = |text: &str| {
+                corrected_str_width_with_offset(text, emoji_width_offset) as f64 * cell_width
+            };
+            let label_positions = if labels_enabled {
+                let requests: Vec<LabelRequest> = nodes
+                    .iter()
+                    .zip(&icons)
+                    .zip(&labels)
+                    .map(|((node, icon), (badges, label))| LabelRequest {
+                        node: (node.x, node.y),
+                        icon_width: width(icon),
+                        label_width: width(label)
+                            + badges.iter().map(|(text, _)| width(text)).sum::<f64>(),
+                    })
+                    .collect();
+                // The selected node's label first, then the busiest
+                let mut priority: Vec<usize> = (0..nodes.len()).collect();
+                priority.sort_by_key(|&idx| {
+                    (
+                        selected_node != Some(idx),
+                        std::cmp::Reverse(nodes[idx].conn_count),
+                    )
+                });
+                let rings = if should_draw_rings {
+                    [
+                        layout_config.ring_low,
+                        layout_config.ring_medium,
+                        layout_config.ring_high,
+                    ]
+                    .iter()
+                    .map(|&radius| (HOST_CENTER, radius))
+                    .collect()
                 } else {
-                    node.endpoint_type.icon_with_badge(node.is_heavy_talker)
+                    Vec::new()
+                };
+                let space = LabelSpace {
+                    cell_width,
+                    row_height,
+                    x_range,
+                    // Labels keep clear of the candles too
+                    coffin: if listen_ports.is_empty() {
+                        ((cx, cy), coffin_radius)
+                    } else {
+                        ((cx, cy), candle_radius + LISTEN_RING_GAP)
+                    },
+                    rings,
                 };
+                layout_labels(&requests, &priority, &space)
+            } else {
+                vec![None; nodes.len()]
+            };
 
-                let color = match node.state {
-                    ConnectionState::TimeWait | ConnectionState::CloseWait => PUMPKIN_ORANGE,
-                    ConnectionState::Close => BLOOD_RED,
-                    _ => node.endpoint_type.color(),
+            // Draw endpoint nodes
+            for (idx, node) in nodes.iter().enumerate() {
+                let icon = &icons[idx];
+                let color = node_color(node.state, &node.endpoint_type, node.is_haunted);
+                let color = if node.is_alerting {
+                    alert_color(color, alert_pulse)
+                } else {
+                    color
                 };
 
                 // Center the icon using corrected width for accurate cross-platform positioning
                 // emoji_centering_offset_with() provides additional correction for emoji width differences
-                let icon_width = corrected_str_width_with_offset(&icon, emoji_width_offset) as f64;
+                let icon_width = corrected_str_width_with_offset(icon, emoji_width_offset) as f64;
                 let icon_offset = icon_width / 2.0 + emoji_centering_offset_with(emoji_width_offset);
                 ctx.print(
                     node.x - icon_offset,
@@ -1277,14 +2092,37 @@ pub fn render_network_map(f: &mut Frame, area: Rect, app: &AppState) {
                     Span::styled(icon.clone(), Style::default().fg(color)),
                 );
 
-                if labels_enabled {
-                    let label = format!("{} ({})", node.label, node.conn_count);
-                    // Use corrected width for accurate positioning with emoji
-                    let label_offset = corrected_str_width_with_offset(&label, emoji_width_offset) as f64 / 2.0;
+                // Labels that found no free spot are elided
+                if let Some((x, y)) = label_positions[idx] {
+                    let (badges, label) = labels[idx].clone();
+                    let mut spans: Vec<Span> = badges
+                        .into_iter()
+                        .map(|(text, badge_color)| {
+                            Span::styled(
+                                text,
+                                Style::default()
+                                    .fg(badge_color)
+                                    .add_modifier(Modifier::BOLD),
+                            )
+                        })
+                        .collect();
+                    let label_style = if selected_node == Some(idx) {
+                        Style::default().fg(color).add_modifier(Modifier::REVERSED)
+                    } else {
+                        Style::default().fg(color)
+                    };
+                    spans.push(Span::styled(label, label_style));
+                    ctx.print(x, y, Line::from(spans));
+                }
+            }
+
+            // A bat crosses in front of the map now and then
+            if ambiance_active {
+                if let Some((x, y)) = bat_position(tick, animation_quality) {
                     ctx.print(
-                        node.x - label_offset,
-                        node.y - 4.0,
-                        Span::styled(label, Style::default().fg(color)),
+                        x * x_range,
+                        y * 100.0,
+                        Span::styled(BAT_SYMBOL, Style::default().fg(BONE_WHITE)),
                     );
                 }
             }
@@ -1309,9 +2147,14 @@ pub fn render_network_map(f: &mut Frame, area: Rect, app: &AppState) {
                 );
             }
 
-            // Show "... and N more" indicator
-            if hidden_endpoint_count > 0 {
-                let more_text = format!("... and {} more", hidden_endpoint_count);
+            // Show which page of endpoints is on screen
+            if page.count > 1 {
+                let more_text = format!(
+                    "... {}-{} of {} ({{/}} page)",
+                    page.first + 1,
+                    page.first + page.shown,
+                    page.total
+                );
                 let text_offset = (corrected_str_width_with_offset(&more_text, emoji_width_offset) as f64 / 2.0) * 1.2;
                 ctx.print(
                     cx - text_offset,
@@ -1329,6 +2172,32 @@ pub fn render_network_map(f: &mut Frame, area: Rect, app: &AppState) {
     f.render_widget(canvas, chunks[1]);
 }
 
+/// The slice of the busiest-first endpoint list drawn on the canvas
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EndpointPage {
+    /// Index of the first endpoint shown
+    pub first: usize,
+    /// Endpoints shown on this page
+    pub shown: usize,
+    pub total: usize,
+    /// Number of pages
+    pub count: usize,
+}
+
+/// Page `requested` (wrapping in both directions) of `total` endpoints,
+/// `per_page` at a time
+pub fn endpoint_page(total: usize, per_page: usize, requested: isize) -> EndpointPage {
+    let count = total.div_ceil(per_page).max(1);
+    let index = requested.rem_euclid(count as isize) as usize;
+    let first = index * per_page;
+    EndpointPage {
+        first,
+        shown: total.saturating_sub(first).min(per_page),
+        total,
+        count,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1342,22 +2211,31 @@ mod tests {
     #[test]
     fn test_classify_endpoint_localhost() {
         assert_eq!(
-            classify_endpoint("127.0.0.1", false),
+            classify_endpoint("127.0.0.1", false, &[]),
+            EndpointType::Localhost
+        );
+        assert_eq!(
+            classify_endpoint("::1", false, &[]),
+            EndpointType::Localhost
+        );
+        assert_eq!(
+            classify_endpoint("0.0.0.0", false, &[]),
             EndpointType::Localhost
         );
-        assert_eq!(classify_endpoint("::1", false), EndpointType::Localhost);
-        assert_eq!(classify_endpoint("0.0.0.0", false), EndpointType::Localhost);
     }
 
     #[test]
     fn test_classify_endpoint_rfc1918_class_a() {
-        assert_eq!(classify_endpoint("10.0.0.1", false), EndpointType::Private);
         assert_eq!(
-            classify_endpoint("10.255.255.255", false),
+            classify_endpoint("10.0.0.1", false, &[]),
+            EndpointType::Private
+        );
+        assert_eq!(
+            classify_endpoint("10.255.255.255", false, &[]),
             EndpointType::Private
         );
         assert_eq!(
-            classify_endpoint("10.100.50.25", false),
+            classify_endpoint("10.100.50.25", false, &[]),
             EndpointType::Private
         );
     }
@@ -1365,68 +2243,138 @@ mod tests {
     #[test]
     fn test_classify_endpoint_rfc1918_class_b() {
         assert_eq!(
-            classify_endpoint("172.16.0.1", false),
+            classify_endpoint("172.16.0.1", false, &[]),
             EndpointType::Private
         );
         assert_eq!(
-            classify_endpoint("172.31.255.255", false),
+            classify_endpoint("172.31.255.255", false, &[]),
             EndpointType::Private
         );
         assert_eq!(
-            classify_endpoint("172.20.100.50", false),
+            classify_endpoint("172.20.100.50", false, &[]),
             EndpointType::Private
         );
-        assert_eq!(classify_endpoint("172.15.0.1", false), EndpointType::Public);
-        assert_eq!(classify_endpoint("172.32.0.1", false), EndpointType::Public);
+        assert_eq!(
+            classify_endpoint("172.15.0.1", false, &[]),
+            EndpointType::Public
+        );
+        assert_eq!(
+            classify_endpoint("172.32.0.1", false, &[]),
+            EndpointType::Public
+        );
     }
 
     #[test]
     fn test_classify_endpoint_rfc1918_class_c() {
         assert_eq!(
-            classify_endpoint("192.168.0.1", false),
+            classify_endpoint("192.168.0.1", false, &[]),
             EndpointType::Private
         );
         assert_eq!(
-            classify_endpoint("192.168.255.255", false),
+            classify_endpoint("192.168.255.255", false, &[]),
             EndpointType::Private
         );
         assert_eq!(
-            classify_endpoint("192.168.1.100", false),
+            classify_endpoint("192.168.1.100", false, &[]),
             EndpointType::Private
         );
         assert_eq!(
-            classify_endpoint("192.169.0.1", false),
+            classify_endpoint("192.169.0.1", false, &[]),
             EndpointType::Public
         );
         assert_eq!(
-            classify_endpoint("192.167.0.1", false),
+            classify_endpoint("192.167.0.1", false, &[]),
+            EndpointType::Public
+        );
+    }
+
+    #[test]
+    fn test_classify_endpoint_ipv6() {
+        assert_eq!(classify_endpoint("::", false, &[]), EndpointType::Localhost);
+        assert_eq!(
+            classify_endpoint("fd00:1234::5", false, &[]),
+            EndpointType::Private
+        );
+        assert_eq!(
+            classify_endpoint("fe80::1ff:fe23:4567:890a", false, &[]),
+            EndpointType::Private
+        );
+        assert_eq!(
+            classify_endpoint("::ffff:192.168.1.10", false, &[]),
+            EndpointType::Private
+        );
+        assert_eq!(
+            classify_endpoint("::ffff:127.0.0.1", false, &[]),
+            EndpointType::Localhost
+        );
+        assert_eq!(
+            classify_endpoint("2606:4700:4700::1111", false, &[]),
             EndpointType::Public
         );
     }
 
     #[test]
     fn test_classify_endpoint_public() {
-        assert_eq!(classify_endpoint("8.8.8.8", false), EndpointType::Public);
-        assert_eq!(classify_endpoint("1.1.1.1", false), EndpointType::Public);
         assert_eq!(
-            classify_endpoint("203.0.113.50", false),
+            classify_endpoint("8.8.8.8", false, &[]),
             EndpointType::Public
         );
         assert_eq!(
-            classify_endpoint("198.51.100.1", false),
+            classify_endpoint("1.1.1.1", false, &[]),
+            EndpointType::Public
+        );
+        assert_eq!(
+            classify_endpoint("203.0.113.50", false, &[]),
+            EndpointType::Public
+        );
+        assert_eq!(
+            classify_endpoint("198.51.100.1", false, &[]),
             EndpointType::Public
         );
     }
 
     #[test]
     fn test_classify_endpoint_listen_only() {
-        assert_eq!(classify_endpoint("0.0.0.0", true), EndpointType::ListenOnly);
         assert_eq!(
-            classify_endpoint("127.0.0.1", true),
+            classify_endpoint("0.0.0.0", true, &[]),
+            EndpointType::ListenOnly
+        );
+        assert_eq!(
+            classify_endpoint("127.0.0.1", true, &[]),
+            EndpointType::ListenOnly
+        );
+        assert_eq!(
+            classify_endpoint("192.168.1.1", true, &[]),
             EndpointType::ListenOnly
         );
+    }
+
+    #[test]
+    fn test_classify_endpoint_custom_rules_take_precedence() {
+        let rules = crate::app::endpoint_rules::build_rules(&[
+            crate::app::endpoint_rules::EndpointRuleConfig {
+                cidr: "10.1.0.0/16".to_string(),
+                label: "prod-db".to_string(),
+                color: Some("blood_red".to_string()),
+                icon: Some("🗄".to_string()),
+                ..Default::default()
+            },
+        ])
+        .unwrap();
+
+        let endpoint_type = classify_endpoint("10.1.2.3", false, &rules);
+        assert_eq!(endpoint_type, EndpointType::Custom(Arc::clone(&rules[0])));
+        assert_eq!(endpoint_type.icon(), "🗄");
+        assert_eq!(endpoint_type.color(), BLOOD_RED);
+
+        // Outside the rule: built-in classification still applies
+        assert_eq!(
+            classify_endpoint("10.2.0.1", false, &rules),
+            EndpointType::Private
+        );
+        // LISTEN sockets are never reclassified
         assert_eq!(
-            classify_endpoint("192.168.1.1", true),
+            classify_endpoint("10.1.2.3", true, &rules),
             EndpointType::ListenOnly
         );
     }
@@ -1504,55 +2452,230 @@ mod tests {
         assert_eq!(classify_latency(Some(501), &config), LatencyBucket::High);
     }
 
+    fn label_space(rings: Vec<((f64, f64), f64)>) -> LabelSpace {
+        LabelSpace {
+            cell_width: 1.0,
+            row_height: 4.0,
+            x_range: 100.0,
+            coffin: ((50.0, 50.0), 5.0),
+            rings,
+        }
+    }
+
+    fn label_request(x: f64, y: f64, label_width: f64) -> LabelRequest {
+        LabelRequest {
+            node: (x, y),
+            icon_width: 2.0,
+            label_width,
+        }
+    }
+
+    #[test]
+    fn test_layout_labels_dodge_each_other() {
+        // Two nodes side by side: the second label can't go below as well
+        let requests = [
+            label_request(20.0, 20.0, 10.0),
+            label_request(24.0, 20.0, 10.0),
+        ];
+        let positions = layout_labels(&requests, &[0, 1], &label_space(Vec::new()));
+        assert_eq!(positions[0], Some((15.0, 16.0)));
+        assert_eq!(positions[1], Some((19.0, 24.0)));
+
+        // Priority decides who keeps the spot below
+        let positions = layout_labels(&requests, &[1, 0], &label_space(Vec::new()));
+        assert_eq!(positions[1], Some((19.0, 16.0)));
+    }
+
+    #[test]
+    fn test_layout_labels_elide_and_avoid_rings() {
+        // Too wide for any side of a node in the corner
+        let requests = [label_request(1.0, 1.0, 120.0)];
+        assert_eq!(
+            layout_labels(&requests, &[0], &label_space(Vec::new())),
+            [None]
+        );
+
+        // Below would sit on the ring, above is clear of it
+        let ring = ((50.0, 50.0), 18.0);
+        let requests = [label_request(50.0, 70.0, 6.0)];
+        let positions = layout_labels(&requests, &[0], &label_space(vec![ring]));
+        assert_eq!(positions[0], Some((47.0, 74.0)));
+        // Covering the coffin is never allowed
+        let requests = [label_request(50.0, 58.0, 6.0)];
+        let positions = layout_labels(&requests, &[0], &label_space(Vec::new()));
+        assert_eq!(positions[0], Some((47.0, 62.0)));
+    }
+
+    #[test]
+    fn test_particles_flow_from_the_opening_side() {
+        use ConnectionDirection::*;
+        let flow = |dirs: &[Option<ConnectionDirection>]| dominant_direction(dirs.iter().copied());
+        assert_eq!(
+            flow(&[Some(Inbound), Some(Inbound), Some(Outbound)]),
+            Some(Inbound)
+        );
+        assert_eq!(flow(&[Some(Outbound), None, None]), Some(Outbound));
+        assert_eq!(flow(&[Some(Inbound), Some(Outbound)]), None);
+        assert_eq!(flow(&[]), None);
+
+        // Inbound spirits start at the endpoint
+        let curve = EdgeCurve::new((0.0, 0.0), (10.0, 0.0), 2.0);
+        assert_eq!(curve.reversed().particle_position(0.0, 0.0), (10.0, 0.0));
+        assert_eq!(curve.reversed().point(0.25), curve.point(0.75));
+    }
+
+    #[test]
+    fn test_edge_curve_bows_aside() {
+        let straight = EdgeCurve::new((0.0, 0.0), (10.0, 0.0), 0.0);
+        assert_eq!(straight.polyline(), [(0.0, 0.0), (10.0, 0.0)]);
+
+        // Positive bends bow to the left of the direction of travel
+        let curve = EdgeCurve::new((0.0, 0.0), (10.0, 0.0), 2.0);
+        let apex = curve.point(0.5);
+        assert!((apex.0 - 5.0).abs() < 1e-9 && (apex.1 - 2.0).abs() < 1e-9);
+        let points = curve.polyline();
+        assert_eq!(points.len(), EDGE_CURVE_SAMPLES + 1);
+        assert_eq!(
+            (points[0], points[EDGE_CURVE_SAMPLES]),
+            ((0.0, 0.0), (10.0, 0.0))
+        );
+        assert!(curve.tangent(0.0).1 > 0.0 && curve.tangent(1.0).1 < 0.0);
+    }
+
+    #[test]
+    fn test_edge_bends_fan_out_parallel_edges() {
+        let center = (50.0, 50.0);
+        // Two endpoints due east on different rings, one far away north
+        let bends = edge_bends(center, &[(90.0, 50.0), (50.0, 90.0), (70.0, 51.0)]);
+        assert_eq!(bends[1], 0.0);
+        assert_eq!(bends[0], -EDGE_BEND_STEP / 2.0);
+        assert_eq!(bends[2], EDGE_BEND_STEP / 2.0);
+
+        assert!(edge_bends(center, &[]).is_empty());
+        assert_eq!(edge_bends(center, &[(60.0, 60.0)]), [0.0]);
+    }
+
+    #[test]
+    fn test_edge_weight_grows_with_connections() {
+        assert_eq!(edge_strands(1), 1);
+        assert_eq!(edge_strands(3), 2);
+        assert_eq!(edge_strands(40), 3);
+        assert_eq!(edge_intensity(TOXIC_GREEN, 1), TOXIC_GREEN);
+        assert_ne!(edge_intensity(TOXIC_GREEN, 3), TOXIC_GREEN);
+
+        let segments = edge_segments((0.0, 0.0), (10.0, 0.0), 3, 1.0);
+        let offsets: Vec<f64> = segments.iter().map(|(start, _)| start.1).collect();
+        assert_eq!(offsets, [0.0, 1.0, -1.0]);
+        assert!(segments.iter().all(|(start, end)| start.1 == end.1));
+    }
+
+    #[test]
+    fn test_alert_color_pulses_to_red() {
+        assert_eq!(alert_color(TOXIC_GREEN, Some(0.0)), TOXIC_GREEN);
+        assert_eq!(alert_color(TOXIC_GREEN, Some(0.5)), BLOOD_RED);
+        assert_ne!(alert_color(TOXIC_GREEN, Some(0.25)), TOXIC_GREEN);
+        // Steady red with the pulse stopped, whatever the base
+        assert_eq!(alert_color(TOXIC_GREEN, None), BLOOD_RED);
+        assert_eq!(alert_color(Color::Yellow, Some(0.1)), Color::Yellow);
+        assert_eq!(alert_color(Color::Yellow, Some(0.6)), BLOOD_RED);
+    }
+
+    #[test]
+    fn test_listen_candles() {
+        let candles = listen_candles(&[22, 8080], (50.0, 50.0), 10.0);
+        let labels: Vec<&str> = candles.iter().map(|(_, label)| label.as_str()).collect();
+        assert_eq!(labels, [":22", ":8080"]);
+        // First at the top, the second opposite it
+        let (top, bottom) = (candles[0].0, candles[1].0);
+        assert!((top.0 - 50.0).abs() < 1e-9 && (top.1 - 60.0).abs() < 1e-9);
+        assert!((bottom.1 - 40.0).abs() < 1e-9);
+
+        let ports: Vec<u16> = (8000..8012).collect();
+        let candles = listen_candles(&ports, (50.0, 50.0), 10.0);
+        assert_eq!(candles.len(), MAX_LISTEN_CANDLES);
+        assert_eq!(candles[MAX_LISTEN_CANDLES - 1].1, "+5");
+        assert!(listen_candles(&[], (50.0, 50.0), 10.0).is_empty());
+    }
+
+    #[test]
+    fn test_ring_band_labels() {
+        assert_eq!(
+            ring_band_labels(&LatencyConfig::default()),
+            ["<50ms", "50–200ms", ">200ms"]
+        );
+        let config = LatencyConfig {
+            low_threshold_ms: 100,
+            high_threshold_ms: 500,
+        };
+        assert_eq!(ring_band_labels(&config)[1], "100–500ms");
+    }
+
     // ============================================================================
     // Test heavy talker detection
     // Requirements: 3.4
     // ============================================================================
 
+    const TOP_5: HeavyTalkerThreshold = HeavyTalkerThreshold::Top(5);
+
     #[test]
     fn test_is_heavy_talker_top_5() {
         let all_counts = vec![100, 80, 60, 40, 20, 10, 5];
 
-        assert!(is_heavy_talker(100, &all_counts));
-        assert!(is_heavy_talker(80, &all_counts));
-        assert!(is_heavy_talker(60, &all_counts));
-        assert!(is_heavy_talker(40, &all_counts));
-        assert!(is_heavy_talker(20, &all_counts));
-        assert!(!is_heavy_talker(10, &all_counts));
-        assert!(!is_heavy_talker(5, &all_counts));
+        assert!(is_heavy_talker(100, &all_counts, TOP_5));
+        assert!(is_heavy_talker(80, &all_counts, TOP_5));
+        assert!(is_heavy_talker(60, &all_counts, TOP_5));
+        assert!(is_heavy_talker(40, &all_counts, TOP_5));
+        assert!(is_heavy_talker(20, &all_counts, TOP_5));
+        assert!(!is_heavy_talker(10, &all_counts, TOP_5));
+        assert!(!is_heavy_talker(5, &all_counts, TOP_5));
     }
 
     #[test]
     fn test_is_heavy_talker_fewer_than_5() {
         let all_counts = vec![50, 30, 10];
 
-        assert!(is_heavy_talker(50, &all_counts));
-        assert!(is_heavy_talker(30, &all_counts));
-        assert!(is_heavy_talker(10, &all_counts));
+        assert!(is_heavy_talker(50, &all_counts, TOP_5));
+        assert!(is_heavy_talker(30, &all_counts, TOP_5));
+        assert!(is_heavy_talker(10, &all_counts, TOP_5));
     }
 
     #[test]
     fn test_is_heavy_talker_empty() {
         let all_counts: Vec<usize> = vec![];
-        assert!(!is_heavy_talker(10, &all_counts));
+        assert!(!is_heavy_talker(10, &all_counts, TOP_5));
     }
 
     #[test]
     fn test_is_heavy_talker_zero_count() {
         let all_counts = vec![10, 5, 0, 0, 0];
 
-        assert!(!is_heavy_talker(0, &all_counts));
-        assert!(is_heavy_talker(10, &all_counts));
-        assert!(is_heavy_talker(5, &all_counts));
+        assert!(!is_heavy_talker(0, &all_counts, TOP_5));
+        assert!(is_heavy_talker(10, &all_counts, TOP_5));
+        assert!(is_heavy_talker(5, &all_counts, TOP_5));
     }
 
     #[test]
     fn test_is_heavy_talker_ties() {
         let all_counts = vec![100, 50, 50, 50, 50, 10];
 
-        assert!(is_heavy_talker(100, &all_counts));
-        assert!(is_heavy_talker(50, &all_counts));
-        assert!(!is_heavy_talker(10, &all_counts));
+        assert!(is_heavy_talker(100, &all_counts, TOP_5));
+        assert!(is_heavy_talker(50, &all_counts, TOP_5));
+        assert!(!is_heavy_talker(10, &all_counts, TOP_5));
+    }
+
+    #[test]
+    fn test_is_heavy_talker_configured() {
+        let all_counts = vec![100, 80, 60, 40, 20, 10, 5];
+
+        // Top 2: only the two busiest endpoints
+        let top_2 = HeavyTalkerThreshold::Top(2);
+        assert!(is_heavy_talker(80, &all_counts, top_2));
+        assert!(!is_heavy_talker(60, &all_counts, top_2));
+
+        // At least 40 connections, however many endpoints that is
+        let min_40 = HeavyTalkerThreshold::Min(40);
+        assert!(is_heavy_talker(40, &all_counts, min_40));
+        assert!(!is_heavy_talker(20, &all_counts, min_40));
     }
 
     // ============================================================================
@@ -1565,7 +2688,7 @@ mod tests {
         let start = (50.0, 50.0);
         let end = (80.0, 30.0);
 
-        let pos = particle_position(start, end, 0.0, 0.0);
+        let pos = EdgeCurve::new(start, end, 0.0).particle_position(0.0, 0.0);
         assert!((pos.0 - 50.0).abs() < 0.001);
         assert!((pos.1 - 50.0).abs() < 0.001);
     }
@@ -1575,7 +2698,7 @@ mod tests {
         let start = (50.0, 50.0);
         let end = (80.0, 30.0);
 
-        let pos = particle_position(start, end, 0.5, 0.0);
+        let pos = EdgeCurve::new(start, end, 0.0).particle_position(0.5, 0.0);
         assert!((pos.0 - 65.0).abs() < 0.001);
         assert!((pos.1 - 40.0).abs() < 0.001);
     }
@@ -1585,7 +2708,7 @@ mod tests {
         let start = (50.0, 50.0);
         let end = (80.0, 30.0);
 
-        let pos = particle_position(start, end, 1.0, 0.0);
+        let pos = EdgeCurve::new(start, end, 0.0).particle_position(1.0, 0.0);
         assert!((pos.0 - 50.0).abs() < 0.001);
         assert!((pos.1 - 50.0).abs() < 0.001);
     }
@@ -1595,11 +2718,11 @@ mod tests {
         let start = (0.0, 0.0);
         let end = (100.0, 100.0);
 
-        let pos = particle_position(start, end, 0.0, 0.33);
+        let pos = EdgeCurve::new(start, end, 0.0).particle_position(0.0, 0.33);
         assert!((pos.0 - 33.0).abs() < 0.001);
         assert!((pos.1 - 33.0).abs() < 0.001);
 
-        let pos = particle_position(start, end, 0.0, 0.66);
+        let pos = EdgeCurve::new(start, end, 0.0).particle_position(0.0, 0.66);
         assert!((pos.0 - 66.0).abs() < 0.001);
         assert!((pos.1 - 66.0).abs() < 0.001);
     }
@@ -1609,7 +2732,7 @@ mod tests {
         let start = (0.0, 0.0);
         let end = (100.0, 0.0);
 
-        let pos = particle_position(start, end, 0.8, 0.33);
+        let pos = EdgeCurve::new(start, end, 0.0).particle_position(0.8, 0.33);
         let expected_t = (0.8 + 0.33) % 1.0;
         assert!((pos.0 - expected_t * 100.0).abs() < 0.001);
     }
@@ -1698,6 +2821,18 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_edge_arrow() {
+        use ConnectionDirection::*;
+        // Endpoint to the right of the host
+        assert_eq!(edge_arrow(10.0, 0.0, Outbound), "→");
+        assert_eq!(edge_arrow(10.0, 0.0, Inbound), "←");
+        // Endpoint above-left
+        assert_eq!(edge_arrow(-10.0, 10.0, Outbound), "↖");
+        assert_eq!(edge_arrow(-10.0, 10.0, Inbound), "↘");
+        assert_eq!(edge_arrow(0.0, -10.0, Outbound), "↓");
+    }
+
     #[test]
     fn test_has_latency_data() {
         let nodes_with_data = vec![EndpointNode {
@@ -1709,6 +2844,12 @@ mod tests {
             latency_bucket: LatencyBucket::Low,
             endpoint_type: EndpointType::Public,
             is_heavy_talker: false,
+            is_haunted: false,
+            is_alerting: false,
+            anonymizer_icon: None,
+            is_new: false,
+            direction: None,
+            flow: None,
         }];
         assert!(has_latency_data(&nodes_with_data));
 
@@ -1721,6 +2862,12 @@ mod tests {
             latency_bucket: LatencyBucket::Unknown,
             endpoint_type: EndpointType::Public,
             is_heavy_talker: false,
+            is_haunted: false,
+            is_alerting: false,
+            anonymizer_icon: None,
+            is_new: false,
+            direction: None,
+            flow: None,
         }];
         assert!(!has_latency_data(&nodes_without_data));
 
@@ -1991,6 +3138,23 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_coffin_caption_for_long_names() {
+        assert_eq!(build_large_coffin("KAFKA1").caption, None);
+        let coffin = build_mid_coffin("kafka-broker-1");
+        assert_eq!(coffin.caption.as_deref(), Some("kafka-broker-1"));
+        assert_eq!(coffin.lines.len(), MID_COFFIN_HEIGHT);
+        let caption = build_large_coffin(&"x".repeat(40)).caption.unwrap();
+        assert_eq!(caption.chars().count(), COFFIN_CAPTION_MAX_NAME);
+
+        // The caption needs a line of its own: 4 lines fit the Large
+        // coffin alone, but not with a caption
+        let large = choose_coffin_variant(100.0, 16.0, "HOST");
+        assert_eq!(large.variant, CoffinVariant::Large);
+        let mid = choose_coffin_variant(100.0, 16.0, "kafka-broker-1");
+        assert_eq!(mid.variant, CoffinVariant::Mid);
+    }
+
     #[test]
     fn test_label_coffin_width_constraint() {
         // Test that label coffin respects max_width
@@ -2004,4 +3168,18 @@ mod tests {
             "Wider constraint should show more of the name"
         );
     }
+
+    #[test]
+    fn test_endpoint_page() {
+        let page = endpoint_page(20, 8, 0);
+        assert_eq!((page.first, page.shown, page.count), (0, 8, 3));
+        // The last page holds the remainder
+        assert_eq!(endpoint_page(20, 8, 2).shown, 4);
+        // Paging wraps in both directions
+        assert_eq!(endpoint_page(20, 8, 3).first, 0);
+        assert_eq!(endpoint_page(20, 8, -1).first, 16);
+        // Everything fits: a single page, whatever was requested
+        assert_eq!(endpoint_page(5, 8, 7), endpoint_page(5, 8, 0));
+        assert_eq!(endpoint_page(0, 8, 0).count, 1);
+    }
 }
@@ -1,24 +1,56 @@
 // Grimoire (Connection List) rendering module
 //
 // Renders the scrollable list of active network connections with
-// state-based coloring and process information.
+// state-based coloring and process information, followed by the
+// Grimoire event feed.
 
+use crate::app::endpoint_rules::{environment_color, environment_of};
+use crate::app::events::EventSeverity;
+use crate::app::orphans::DEPARTED_LABEL;
+use crate::app::rows::RowKind;
+use crate::app::snapshot::DiffChange;
 use crate::app::AppState;
-use crate::net::ConnectionState;
-use crate::theme::{BLOOD_RED, BONE_WHITE, PUMPKIN_ORANGE, TOXIC_GREEN};
+use crate::net::{ConnectionDirection, ConnectionState, Protocol};
+use crate::theme::glyphs::glyphs;
+use crate::theme::{BLOOD_RED, BONE_WHITE, NEON_PURPLE, PUMPKIN_ORANGE, TOXIC_GREEN};
 use ratatui::{
-    layout::Rect,
+    layout::{Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, BorderType, Borders, List, ListItem},
+    widgets::{Block, BorderType, Borders, List, ListItem, Paragraph},
     Frame,
 };
 
+/// Height of the event feed below the connection list (including borders)
+const EVENT_FEED_HEIGHT: u16 = 6;
+
+/// Height of the event feed while it has keyboard focus
+const FOCUSED_EVENT_FEED_HEIGHT: u16 = 14;
+
 pub fn render_grimoire(f: &mut Frame, area: Rect, app: &mut AppState) {
+    // The focused feed grows, but leaves room for a few connections
+    let feed_height = if app.event_feed.focused {
+        FOCUSED_EVENT_FEED_HEIGHT.min(area.height.saturating_sub(5))
+    } else {
+        EVENT_FEED_HEIGHT
+    };
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(0), Constraint::Length(feed_height)])
+        .split(area);
+
+    render_connection_list(f, chunks[0], app);
+    render_event_feed(f, chunks[1], app);
+}
+
+fn render_connection_list(f: &mut Frame, area: Rect, app: &mut AppState) {
     let mut log_items = Vec::new();
 
-    // Show all connections (scrollable)
-    for (idx, conn) in app.connections.iter().enumerate() {
+    // Show all connections (scrollable), pools folded into one row
+    let rows = app.connection_rows();
+    let selected_row = app.selected_row(&rows);
+    for (idx, row) in rows.iter().enumerate() {
+        let conn = &app.connections[row.index];
         // Color based on connection state
         let state_color = match conn.state {
             ConnectionState::Established => TOXIC_GREEN,
@@ -29,26 +61,99 @@ pub fn render_grimoire(f: &mut Frame, area: Rect, app: &mut AppState) {
         };
 
         // Format: local:port -> remote:port [STATE]
-        let conn_line = if conn.remote_addr == "0.0.0.0" && conn.remote_port == 0 {
+        let conn_line = if &*conn.remote_addr == "0.0.0.0" && conn.remote_port == 0 {
             // Listening socket
             format!(" {}:{} [LISTEN]", conn.local_addr, conn.local_port)
         } else {
-            // Active connection
-            format!(
-                " {}:{} → {}:{} [{:?}]",
-                conn.local_addr, conn.local_port, conn.remote_addr, conn.remote_port, conn.state
-            )
+            match row.kind {
+                // Subflows of one MPTCP connection: the first one's endpoints
+                RowKind::Pool {
+                    ref members,
+                    expanded,
+                } if conn.mptcp.is_some() => format!(
+                    " {} {}:{} → {}:{} [{:?}] ×{} subflows",
+                    if expanded { "▾" } else { "▸" },
+                    conn.local_addr,
+                    conn.local_port,
+                    conn.remote_addr,
+                    conn.remote_port,
+                    conn.state,
+                    members.len()
+                ),
+                RowKind::Member if conn.mptcp.is_some() => format!(
+                    "   ↳ {}:{} → {}:{}",
+                    conn.local_addr, conn.local_port, conn.remote_addr, conn.remote_port
+                ),
+                // Sockets differing only in local port: "local:* → remote ×N"
+                RowKind::Pool {
+                    ref members,
+                    expanded,
+                } => format!(
+                    " {} {}:* → {}:{} [{:?}] ×{}",
+                    if expanded { "▾" } else { "▸" },
+                    conn.local_addr,
+                    conn.remote_addr,
+                    conn.remote_port,
+                    conn.state,
+                    members.len()
+                ),
+                RowKind::Member => format!("   ↳ {}:{}", conn.local_addr, conn.local_port),
+                // UDP flows have no state to show
+                RowKind::Single if conn.protocol == Protocol::Udp => format!(
+                    " {}:{} → {}:{} [{}]",
+                    conn.local_addr,
+                    conn.local_port,
+                    conn.remote_addr,
+                    conn.remote_port,
+                    conn.protocol.name()
+                ),
+                // Active connection
+                RowKind::Single => format!(
+                    " {}:{} → {}:{} [{:?}]",
+                    conn.local_addr,
+                    conn.local_port,
+                    conn.remote_addr,
+                    conn.remote_port,
+                    conn.state
+                ),
+            }
+        };
+
+        // Direction column: "← IN" accepted by a local listener, "→ OUT" opened by us
+        let (direction_column, direction_color) = match conn.direction {
+            Some(direction) => (
+                format!(" {} {:<3}", direction.arrow(), direction.label()),
+                match direction {
+                    ConnectionDirection::Inbound => Color::Cyan,
+                    ConnectionDirection::Outbound => PUMPKIN_ORANGE,
+                },
+            ),
+            None => (" ".repeat(6), Color::DarkGray),
         };
 
+        // USER column (socket owner), fixed width for alignment
+        let user_column = format!(" {:<8}", truncate_user(conn.user.as_deref().unwrap_or("-")));
+
         // Add process info tag if available
-        let process_tag = if let (Some(pid), Some(ref name)) = (conn.pid, &conn.process_name) {
-            format!(" [{}({})]", name, pid)
-        } else {
-            String::new()
+        let process_tag = match (conn.pid, &conn.process_name, &conn.container) {
+            (Some(pid), Some(name), Some(container)) => {
+                format!(" [{}({}) (container: {})]", name, pid, container)
+            }
+            (Some(pid), Some(name), None) => format!(" [{}({})]", name, pid),
+            _ => String::new(),
+        };
+
+        // Socket outliving its process (see app/orphans.rs)
+        let departed_badge = match conn.labels.get(DEPARTED_LABEL) {
+            Some(owner) => Span::styled(
+                format!(" [👻 {} departed]", owner),
+                Style::default().fg(Color::DarkGray),
+            ),
+            None => Span::raw(""),
         };
 
         // Check if this connection is selected
-        let is_selected = app.selected_connection == Some(idx);
+        let is_selected = selected_row == Some(idx);
 
         // Apply highlighting to selected connection
         let item_style = if is_selected {
@@ -57,20 +162,175 @@ pub fn render_grimoire(f: &mut Frame, area: Rect, app: &mut AppState) {
             Style::default()
         };
 
-        log_items.push(
-            ListItem::new(Line::from(vec![
+        // Root-owned wildcard listeners are the most exposed services
+        let exposure_badge = if conn.is_root_wildcard_listener() {
+            Span::styled(
+                " ⚠️",
+                Style::default().fg(BLOOD_RED).add_modifier(Modifier::BOLD),
+            )
+        } else {
+            Span::raw("")
+        };
+
+        // Environment badge when the remote address matches a tagged rule
+        let environment_badge = match environment_of(&app.endpoint_rules, &conn.remote_addr) {
+            Some(env) => Span::styled(
+                format!(" [{}]", env),
+                Style::default()
+                    .fg(environment_color(env))
+                    .add_modifier(Modifier::BOLD),
+            ),
+            None => Span::raw(""),
+        };
+
+        // Remote address found on the threat-intel blocklist
+        let haunted_badge = if app.is_haunted(&conn.remote_addr) {
+            Span::styled(
+                format!(" {} haunted", glyphs().haunted),
+                Style::default().fg(BLOOD_RED).add_modifier(Modifier::BOLD),
+            )
+        } else {
+            Span::raw("")
+        };
+
+        // Tor exit / VPN endpoint
+        let anonymizer_badge = match app.anonymizer(&conn.remote_addr) {
+            Some(anonymizer) => Span::styled(
+                format!(" {} {}", anonymizer.icon(), anonymizer.label()),
+                Style::default()
+                    .fg(PUMPKIN_ORANGE)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            None => Span::raw(""),
+        };
+
+        // Multipath TCP subflow / Fast Open handshake
+        let transport_badge = match (&conn.mptcp, conn.fast_open) {
+            (Some(_), _) => Span::styled(
+                " MPTCP",
+                Style::default()
+                    .fg(NEON_PURPLE)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            (None, true) => Span::styled(" TFO", Style::default().fg(NEON_PURPLE)),
+            (None, false) => Span::raw(""),
+        };
+
+        // UDP flow on 443 that kept going (see app/quic.rs)
+        let quic_badge = match conn.labels.get("protocol") {
+            Some(protocol) => Span::styled(
+                format!(" {}", protocol),
+                Style::default()
+                    .fg(NEON_PURPLE)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            None => Span::raw(""),
+        };
+
+        // Endpoint pinned to the watchlist with 'o'
+        let pin_marker = if app.is_pinned(&conn.remote_addr) {
+            Span::styled(" 📌", Style::default().fg(BONE_WHITE))
+        } else {
+            Span::raw("")
+        };
+
+        // Triage tags put on with 'n'
+        let tag_markers = app.tags.get(conn).into_iter().map(|tag| {
+            Span::styled(
+                format!(" [{}]", tag.label()),
+                Style::default()
+                    .fg(tag.color())
+                    .add_modifier(Modifier::BOLD),
+            )
+        });
+
+        // Marked with '.' for bulk actions (a pool is marked once all of
+        // its sockets are)
+        let marked = match row.kind {
+            RowKind::Pool { ref members, .. } => members
+                .iter()
+                .all(|&member| app.is_marked(&app.connections[member])),
+            _ => app.is_marked(conn),
+        };
+        let mark_marker = if marked {
+            Span::styled(
+                "●",
+                Style::default()
+                    .fg(NEON_PURPLE)
+                    .add_modifier(Modifier::BOLD),
+            )
+        } else {
+            Span::raw("")
+        };
+
+        // "+"/"-" marker while the diff view is shown
+        let diff_marker = match app.diff_change(conn) {
+            Some(change) => {
+                let color = match change {
+                    DiffChange::Added => TOXIC_GREEN,
+                    DiffChange::Removed => BLOOD_RED,
+                };
                 Span::styled(
-                    format!("{:2}.", idx + 1),
-                    Style::default().fg(Color::DarkGray),
-                ),
-                Span::styled(conn_line, Style::default().fg(state_color)),
-                Span::styled(process_tag, Style::default().fg(Color::Cyan)),
-            ]))
-            .style(item_style),
-        );
+                    change.marker(),
+                    Style::default().fg(color).add_modifier(Modifier::BOLD),
+                )
+            }
+            None => Span::raw(""),
+        };
+
+        let mut spans = vec![
+            mark_marker,
+            diff_marker,
+            Span::styled(
+                format!("{:2}.", idx + 1),
+                Style::default().fg(Color::DarkGray),
+            ),
+            Span::styled(user_column, Style::default().fg(Color::DarkGray)),
+            Span::styled(direction_column, Style::default().fg(direction_color)),
+            Span::styled(conn_line, Style::default().fg(state_color)),
+            environment_badge,
+            Span::styled(process_tag, Style::default().fg(Color::Cyan)),
+            departed_badge,
+            exposure_badge,
+            haunted_badge,
+            anonymizer_badge,
+            transport_badge,
+            quic_badge,
+            pin_marker,
+        ];
+        spans.extend(tag_markers);
+        log_items.push(ListItem::new(Line::from(spans)).style(item_style));
     }
 
-    let title = format!("━ 🌐 Active Connections ({}) ", app.connections.len());
+    // Show "visible/total" while a filter hides part of the snapshot
+    let mut title = if app.diff_mode {
+        let (added, removed) = app.diff_counts();
+        format!("━ 🌐 Changes (+{} -{}) ", added, removed)
+    } else if app.filter_active() {
+        format!(
+            "━ 🌐 Active Connections ({}/{}) ",
+            app.connections.len(),
+            app.raw_connections.len()
+        )
+    } else {
+        format!("━ 🌐 Active Connections ({}) ", app.connections.len())
+    };
+    if let Some(ref user) = app.connection_filter.user {
+        title.push_str(&format!("[user: {}] ", user));
+    }
+    if let Some(ref container) = app.connection_filter.container {
+        title.push_str(&format!("[container: {}] ", container));
+    }
+    if let Some(ref interface) = app.connection_filter.interface {
+        title.push_str(&format!("[iface: {}] ", interface));
+    }
+    let marked = app.marked_connections().len();
+    if marked > 0 {
+        title.push_str(&format!("[● {} marked] ", marked));
+    }
+    if let Some(tag) = app.tag_filter {
+        title.push_str(&format!("[tag: {}] ", tag.label()));
+    }
 
     let logs = List::new(log_items)
         .block(
@@ -92,3 +352,120 @@ pub fn render_grimoire(f: &mut Frame, area: Rect, app: &mut AppState) {
 
     f.render_stateful_widget(logs, area, &mut app.connection_list_state);
 }
+
+/// Render the Grimoire events in view (newest at the bottom): the latest
+/// ones while tailing, search matches highlighted
+fn render_event_feed(f: &mut Frame, area: Rect, app: &mut AppState) {
+    app.event_feed.rows = area.height.saturating_sub(2) as usize;
+    let feed = &app.event_feed;
+    let window = feed.window(&app.events);
+
+    let lines: Vec<Line> = if window.is_empty() {
+        let silence = if app.events.is_empty() {
+            " (the grimoire is silent)"
+        } else {
+            " (the grimoire is silent about this soul; Tab, Esc shows all)"
+        };
+        vec![Line::from(Span::styled(
+            silence,
+            Style::default()
+                .fg(Color::DarkGray)
+                .add_modifier(Modifier::ITALIC),
+        ))]
+    } else {
+        let first = app.events.first_seq();
+        app.events
+            .iter()
+            .enumerate()
+            .map(|(idx, event)| (first + idx as u64, event))
+            .filter(|(seq, _)| window.binary_search(seq).is_ok())
+            .map(|(seq, event)| {
+                let color = severity_color(event.severity);
+                let mut message = Style::default().fg(color);
+                if feed.is_current_match(seq) {
+                    message = message.add_modifier(Modifier::REVERSED);
+                } else if feed.matches(event) {
+                    message = message.add_modifier(Modifier::UNDERLINED);
+                }
+                Line::from(vec![
+                    Span::styled(
+                        format!(" {} ", event.time_label()),
+                        Style::default().fg(Color::DarkGray),
+                    ),
+                    Span::styled(
+                        format!("{} ", event.severity.label()),
+                        Style::default().fg(color).add_modifier(Modifier::BOLD),
+                    ),
+                    Span::styled(event.message.clone(), message),
+                ])
+            })
+            .collect()
+    };
+
+    // "📖 Grimoire (42) [⚲ pid 1234 · 93.184.216.34] [🔍 storm] [↓ 3 new]"
+    let mut title = vec![Span::styled(
+        format!(" 📖 Grimoire ({}) ", app.events.len()),
+        Style::default()
+            .fg(PUMPKIN_ORANGE)
+            .add_modifier(Modifier::BOLD),
+    )];
+    if let Some(subject) = feed.subject() {
+        title.push(Span::styled(
+            format!("[⚲ {}] ", subject.label()),
+            Style::default().fg(TOXIC_GREEN),
+        ));
+    }
+    if let Some(search) = feed.search() {
+        title.push(Span::styled(
+            format!("[🔍 {}] ", search),
+            Style::default().fg(NEON_PURPLE),
+        ));
+    }
+    if !feed.is_tailing() {
+        let scrolled = match feed.new_events(&app.events) {
+            0 => "[↑ scrolled back] ".to_string(),
+            new => format!("[↓ {} new] ", new),
+        };
+        title.push(Span::styled(
+            scrolled,
+            Style::default()
+                .fg(TOXIC_GREEN)
+                .add_modifier(Modifier::BOLD),
+        ));
+    }
+
+    let mut block = Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(PUMPKIN_ORANGE));
+    if feed.focused {
+        block = block
+            .border_type(BorderType::Thick)
+            .title_bottom(Span::styled(
+                " ↑↓ scroll  / search  n/N match  End tail  Tab back ",
+                Style::default().fg(Color::DarkGray),
+            ));
+    }
+
+    f.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+/// Map event severity to its palette color
+pub fn severity_color(severity: EventSeverity) -> Color {
+    match severity {
+        EventSeverity::Info => BONE_WHITE,
+        EventSeverity::Warning => PUMPKIN_ORANGE,
+        EventSeverity::Critical => BLOOD_RED,
+    }
+}
+
+/// Truncate a user name to the USER column width (8 chars)
+fn truncate_user(user: &str) -> String {
+    if user.chars().count() > 8 {
+        let truncated: String = user.chars().take(7).collect();
+        format!("{}…", truncated)
+    } else {
+        user.to_string()
+    }
+}
@@ -1,13 +1,22 @@
 // Soul Inspector rendering module
 //
 // Renders the detail panel showing selected process/connection information,
-// traffic sparkline, and socket list.
+// traffic sparkline, and socket list on the Overview tab, and the Sockets,
+// Forensics and History tabs (see app/inspector.rs).
 //
 // The Soul Inspector displays real-time data about the currently selected
 // target (process or connection) from AppState.
 
+use crate::app::anomaly::Anomaly;
+use crate::app::enrich::country_counts;
+use crate::app::events::time_of_day_label;
+use crate::app::inspector::{timeline, InspectorTab, TimelineChange};
+use crate::app::snapshot::connection_key;
 use crate::app::{AppState, GraveyardMode};
+use crate::net::scope::{address_scope, AddressScope};
 use crate::net::{Connection, ConnectionState};
+use crate::procfs::package::PackageOwner;
+use crate::theme::glyphs::glyphs;
 use crate::theme::{
     get_refresh_color, get_status_text, BLOOD_RED, BONE_WHITE, NEON_PURPLE, PUMPKIN_ORANGE,
     TOXIC_GREEN,
@@ -16,9 +25,15 @@ use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, BorderType, Borders, Paragraph, Sparkline},
+    widgets::{Block, BorderType, Borders, List, ListItem, Paragraph, Sparkline, Tabs, Wrap},
     Frame,
 };
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+/// Countries listed on the Overview's GEO line before "+N"
+const MAX_GEO_COUNTRIES: usize = 4;
 
 // ============================================================================
 // Soul Inspector View Model
@@ -36,6 +51,8 @@ pub struct SoulInspectorView {
     pub target_icon: String,
     /// Process ID if available
     pub pid: Option<i32>,
+    /// Number of descendant processes aggregated under `pid` (Process mode)
+    pub child_count: usize,
     /// Parent process ID (not available in current data model, reserved for future use)
     #[allow(dead_code)]
     pub ppid: Option<i32>,
@@ -50,6 +67,8 @@ pub struct SoulInspectorView {
     pub state_color: Color,
     /// Current UI refresh interval in milliseconds
     pub refresh_ms: u64,
+    /// Data refreshes every N UI intervals
+    pub data_multiplier: u64,
     /// Number of connections for this target
     pub conn_count: usize,
     /// Number of server (LISTEN) connections
@@ -58,8 +77,6 @@ pub struct SoulInspectorView {
     pub client_count: usize,
     /// Number of public/external connections
     pub public_count: usize,
-    /// List of connections/sockets for this target
-    pub sockets: Vec<SocketInfo>,
     /// Whether this target has suspicious activity
     pub suspicious: bool,
     /// Number of suspicious connections
@@ -68,6 +85,20 @@ pub struct SoulInspectorView {
     pub suspicious_reasons: Vec<String>,
     /// Tags for this target
     pub tags: Vec<String>,
+    /// Package owning the focused process's executable (Process mode only)
+    pub package: Option<PackageOwner>,
+    /// Cloud provider owning the selected connection's remote address
+    pub cloud: Option<String>,
+    /// Reverse DNS name of the selected connection's remote address
+    pub host_name: Option<String>,
+    /// AS and organization announcing the selected connection's remote address
+    pub asn: Option<String>,
+    /// Connections per registration country, busiest first (needs `[asn]`)
+    pub countries: Vec<(Arc<str>, usize)>,
+    /// Highest anomaly score among the target process's connections
+    pub anomaly: Option<Anomaly>,
+    /// Time left before anomalies are scored, while learning
+    pub learning_left: Option<Duration>,
     /// Whether a target is selected
     pub has_selection: bool,
 }
@@ -75,7 +106,7 @@ pub struct SoulInspectorView {
 /// Socket/connection info for display in the socket list
 #[derive(Debug, Clone)]
 pub struct SocketInfo {
-    /// Display string (e.g., "tcp://127.0.0.1:8080")
+    /// Display string (e.g., "tcp://127.0.0.1:8080 on lo")
     pub display: String,
     /// Remote endpoint if applicable
     pub remote: Option<String>,
@@ -89,21 +120,29 @@ impl Default for SoulInspectorView {
             target_name: "No target selected".to_string(),
             target_icon: "👻".to_string(),
             pid: None,
+            child_count: 0,
             ppid: None,
             user: None,
             state_icon: "⚪".to_string(),
             state_text: "Idle".to_string(),
             state_color: BONE_WHITE,
             refresh_ms: 500,
+            data_multiplier: crate::app::config::DATA_REFRESH_MULTIPLIER,
             conn_count: 0,
             server_count: 0,
             client_count: 0,
             public_count: 0,
-            sockets: Vec::new(),
             suspicious: false,
             suspicious_count: 0,
             suspicious_reasons: Vec::new(),
             tags: Vec::new(),
+            package: None,
+            cloud: None,
+            host_name: None,
+            asn: None,
+            countries: Vec::new(),
+            anomaly: None,
+            learning_left: None,
             has_selection: false,
         }
     }
@@ -118,6 +157,7 @@ impl Default for SoulInspectorView {
 pub fn build_soul_inspector_view(app: &AppState) -> SoulInspectorView {
     let mut view = SoulInspectorView {
         refresh_ms: app.refresh_config.refresh_ms,
+        data_multiplier: app.refresh_config.data_multiplier,
         ..Default::default()
     };
 
@@ -128,6 +168,12 @@ pub fn build_soul_inspector_view(app: &AppState) -> SoulInspectorView {
                 // A connection is selected - show its details
                 if let Some(conn) = app.connections.get(conn_idx) {
                     build_connection_view(&mut view, conn, &app.connections);
+                    view.cloud = app.cloud_range(&conn.remote_addr).map(|r| r.to_string());
+                    view.asn = app.asn(&conn.remote_addr).map(|a| a.to_string());
+                    view.host_name = app
+                        .reverse_name(&conn.remote_addr)
+                        .map(|r| r.name.to_string());
+                    set_anomaly(&mut view, app, conn.process_name.as_deref());
                 }
             } else {
                 // No selection - show host overview
@@ -137,7 +183,14 @@ pub fn build_soul_inspector_view(app: &AppState) -> SoulInspectorView {
         GraveyardMode::Process => {
             // Process mode - show selected process details
             if let Some(pid) = app.selected_process_pid {
-                build_process_view(&mut view, pid, &app.connections);
+                build_process_view(&mut view, pid, &app.focus_pids, &app.connections);
+                view.package = app.focused_package.clone();
+                let process = app
+                    .connections
+                    .iter()
+                    .filter(|c| c.pid == Some(pid))
+                    .find_map(|c| c.process_name.as_deref());
+                set_anomaly(&mut view, app, process);
             } else {
                 // Process mode but no PID (shouldn't happen normally)
                 view.target_name = "No process selected".to_string();
@@ -149,6 +202,22 @@ pub fn build_soul_inspector_view(app: &AppState) -> SoulInspectorView {
     view
 }
 
+/// Anomaly meter of `process`: its score, or the learning time left
+fn set_anomaly(view: &mut SoulInspectorView, app: &AppState, process: Option<&str>) {
+    let Some(process) = process else {
+        return;
+    };
+    view.learning_left = app.anomaly.learning_left(SystemTime::now());
+    if view.learning_left.is_none() {
+        view.anomaly = Some(
+            app.anomaly
+                .process_anomaly(process)
+                .cloned()
+                .unwrap_or_default(),
+        );
+    }
+}
+
 /// Build view for Host mode (no specific selection)
 fn build_host_view(view: &mut SoulInspectorView, connections: &[Connection]) {
     view.target_name = "HOST".to_string();
@@ -166,7 +235,7 @@ fn build_host_view(view: &mut SoulInspectorView, connections: &[Connection]) {
         .count();
     let other = connections.len() - established - listening;
 
-    // Count public/external connections (non-RFC1918, non-localhost)
+    // Count public/external connections (not loopback, not a local network)
     let public_count = connections
         .iter()
         .filter(|c| is_public_ip(&c.remote_addr))
@@ -176,6 +245,7 @@ fn build_host_view(view: &mut SoulInspectorView, connections: &[Connection]) {
     view.server_count = listening;
     view.client_count = established;
     view.public_count = public_count;
+    view.countries = country_counts(connections);
 
     // Check for suspicious patterns across all connections
     let mut suspicious_count = 0;
@@ -227,13 +297,6 @@ fn build_host_view(view: &mut SoulInspectorView, connections: &[Connection]) {
         view.state_color = PUMPKIN_ORANGE;
     }
 
-    // Build socket list (show first few connections)
-    view.sockets = connections
-        .iter()
-        .take(5)
-        .map(connection_to_socket_info)
-        .collect();
-
     // Add tags
     if listening > 0 {
         view.tags.push(format!("server ({})", listening));
@@ -283,12 +346,14 @@ fn build_connection_view(
         view.conn_count = 1;
     }
 
-    // Socket info
-    view.sockets = vec![connection_to_socket_info(conn)];
-
     // Add process name as tag if available
     if let Some(ref name) = conn.process_name {
-        view.tags.push(name.clone());
+        view.tags.push(name.to_string());
+    }
+
+    // Enricher labels (service, country, rule, ...)
+    for (key, value) in &conn.labels {
+        view.tags.push(format!("{}={}", key, value));
     }
 
     // Check for suspicious patterns
@@ -296,19 +361,31 @@ fn build_connection_view(
 }
 
 /// Build view for a selected process
-fn build_process_view(view: &mut SoulInspectorView, pid: i32, connections: &[Connection]) {
+///
+/// `focus_pids` contains the selected PID and, when child aggregation is
+/// enabled, all of its descendants.
+fn build_process_view(
+    view: &mut SoulInspectorView,
+    pid: i32,
+    focus_pids: &HashSet<i32>,
+    connections: &[Connection],
+) {
     view.has_selection = true;
     view.target_icon = "⚰️".to_string();
     view.pid = Some(pid);
 
-    // Find connections for this process
-    let process_conns: Vec<&Connection> =
-        connections.iter().filter(|c| c.pid == Some(pid)).collect();
+    // Find connections for this process (and its descendants)
+    let process_conns: Vec<&Connection> = connections
+        .iter()
+        .filter(|c| c.pid.is_some_and(|p| focus_pids.contains(&p)))
+        .collect();
 
-    // Get process name from first connection
+    // Get process name from the selected process's own connections
     let process_name = process_conns
         .iter()
-        .find_map(|c| c.process_name.clone())
+        .filter(|c| c.pid == Some(pid))
+        .find_map(|c| c.process_name.as_deref())
+        .map(str::to_string)
         .unwrap_or_else(|| format!("PID {}", pid));
 
     view.target_name = if process_name.len() > 15 {
@@ -318,6 +395,7 @@ fn build_process_view(view: &mut SoulInspectorView, pid: i32, connections: &[Con
     };
 
     view.conn_count = process_conns.len();
+    view.countries = country_counts(process_conns.iter().copied());
 
     // Determine state based on connections
     let established = process_conns
@@ -360,15 +438,9 @@ fn build_process_view(view: &mut SoulInspectorView, pid: i32, connections: &[Con
         view.state_color = BONE_WHITE;
     }
 
-    // Build socket list
-    view.sockets = process_conns
-        .iter()
-        .take(5)
-        .map(|c| connection_to_socket_info(c))
-        .collect();
-
     // Tags
     view.tags.push(process_name);
+    view.child_count = focus_pids.len().saturating_sub(1);
     if listening > 0 {
         view.tags.push("server".to_string());
     }
@@ -379,8 +451,11 @@ fn build_process_view(view: &mut SoulInspectorView, pid: i32, connections: &[Con
 
 /// Convert Connection to SocketInfo for display
 fn connection_to_socket_info(conn: &Connection) -> SocketInfo {
-    let display = format!("tcp://{}:{}", conn.local_addr, conn.local_port);
-    let remote = if conn.state == ConnectionState::Listen || conn.remote_addr == "0.0.0.0" {
+    let mut display = format!("tcp://{}:{}", conn.local_addr, conn.local_port);
+    if let Some(ref interface) = conn.interface {
+        display.push_str(&format!(" on {}", interface));
+    }
+    let remote = if conn.state == ConnectionState::Listen || &*conn.remote_addr == "0.0.0.0" {
         None
     } else {
         Some(format!("{}:{}", conn.remote_addr, conn.remote_port))
@@ -462,38 +537,370 @@ fn check_suspicious_patterns(view: &mut SoulInspectorView, conn: &Connection) {
     }
 }
 
-/// Check if an IP address is public (not localhost, not RFC1918 private)
+/// Check if an IP address is public (not loopback, not a local network range)
 fn is_public_ip(addr: &str) -> bool {
-    // Localhost
-    if addr == "127.0.0.1" || addr == "::1" || addr == "0.0.0.0" || addr.starts_with("127.") {
-        return false;
+    !matches!(
+        address_scope(addr),
+        Some(AddressScope::Loopback | AddressScope::Private)
+    )
+}
+
+/// Color of a socket's state in socket lists
+fn socket_state_color(state: ConnectionState) -> Color {
+    match state {
+        ConnectionState::Established => TOXIC_GREEN,
+        ConnectionState::Listen => PUMPKIN_ORANGE,
+        ConnectionState::TimeWait | ConnectionState::CloseWait => PUMPKIN_ORANGE,
+        ConnectionState::Close => BLOOD_RED,
+        _ => BONE_WHITE,
     }
+}
+
+/// Bordered block for the Sockets, Forensics and History tabs
+fn tab_block(title: String) -> Block<'static> {
+    Block::default()
+        .title(vec![Span::styled(
+            title,
+            Style::default()
+                .fg(NEON_PURPLE)
+                .add_modifier(Modifier::BOLD),
+        )])
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(NEON_PURPLE))
+}
+
+/// Dimmed italic placeholder line
+fn placeholder(text: &str) -> Line<'static> {
+    Line::from(Span::styled(
+        format!("  {}", text),
+        Style::default()
+            .fg(Color::DarkGray)
+            .add_modifier(Modifier::ITALIC),
+    ))
+}
+
+pub fn render_soul_inspector(f: &mut Frame, area: Rect, app: &mut AppState) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(1), Constraint::Min(0)])
+        .split(area);
 
-    // RFC1918 private ranges
-    if addr.starts_with("10.") || addr.starts_with("192.168.") {
-        return false;
+    let titles = InspectorTab::ALL.iter().map(|tab| tab.label());
+    let selected = InspectorTab::ALL
+        .iter()
+        .position(|tab| *tab == app.inspector_tab)
+        .unwrap_or(0);
+    let tabs = Tabs::new(titles)
+        .select(selected)
+        .style(Style::default().fg(Color::DarkGray))
+        .highlight_style(
+            Style::default()
+                .fg(NEON_PURPLE)
+                .add_modifier(Modifier::BOLD | Modifier::REVERSED),
+        )
+        .divider(Span::styled("│", Style::default().fg(NEON_PURPLE)));
+    f.render_widget(tabs, chunks[0]);
+
+    match app.inspector_tab {
+        InspectorTab::Overview => render_overview(f, chunks[1], app),
+        InspectorTab::Sockets => render_sockets(f, chunks[1], app),
+        InspectorTab::Forensics => render_forensics(f, chunks[1], app),
+        InspectorTab::History => render_history(f, chunks[1], app),
     }
+}
 
-    // 172.16.0.0 - 172.31.255.255
-    if addr.starts_with("172.") {
-        if let Some(second_octet) = addr.split('.').nth(1) {
-            if let Ok(octet) = second_octet.parse::<u8>() {
-                if (16..=31).contains(&octet) {
-                    return false;
-                }
+/// Every socket of the inspected target, one row each
+fn render_sockets(f: &mut Frame, area: Rect, app: &mut AppState) {
+    let header = Line::from(Span::styled(
+        format!(
+            "  {:<11} {:<24} {:<24} {}",
+            "STATE", "LOCAL", "REMOTE", "PROCESS"
+        ),
+        Style::default()
+            .fg(Color::DarkGray)
+            .add_modifier(Modifier::BOLD),
+    ));
+    let items: Vec<ListItem> = app
+        .inspected_connections()
+        .into_iter()
+        .map(|idx| {
+            let conn = &app.connections[idx];
+            let remote = if conn.state == ConnectionState::Listen {
+                "*".to_string()
+            } else {
+                format!("{}:{}", conn.remote_addr, conn.remote_port)
+            };
+            let process = match (conn.pid, conn.process_name.as_deref()) {
+                (Some(pid), Some(name)) => format!("{}/{}", pid, name),
+                (Some(pid), None) => pid.to_string(),
+                _ => "-".to_string(),
+            };
+            ListItem::new(Line::from(vec![
+                Span::styled(
+                    format!("  {:<11} ", conn.state.name()),
+                    Style::default().fg(socket_state_color(conn.state)),
+                ),
+                Span::styled(
+                    format!(
+                        "{:<24} ",
+                        format!("{}:{}", conn.local_addr, conn.local_port)
+                    ),
+                    Style::default().fg(Color::Cyan),
+                ),
+                Span::styled(format!("{:<24} ", remote), Style::default().fg(Color::Blue)),
+                Span::styled(process, Style::default().fg(BONE_WHITE)),
+            ]))
+        })
+        .collect();
+    let title = format!(" 📜 Sockets ({}) ", items.len());
+    render_socket_list(f, area, app, title, Some(header), items);
+}
+
+/// Socket list shared by the Overview and Sockets tabs: scrolls with its
+/// own selection while focused (`Tab`), and says so when it overflows
+fn render_socket_list(
+    f: &mut Frame,
+    area: Rect,
+    app: &mut AppState,
+    title: String,
+    header: Option<Line<'static>>,
+    items: Vec<ListItem<'static>>,
+) {
+    let focused = app.inspector_sockets.focused;
+    let mut block = Block::default()
+        .title(vec![Span::styled(
+            title,
+            Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+        )])
+        .borders(Borders::ALL)
+        .border_type(if focused {
+            BorderType::Thick
+        } else {
+            BorderType::Rounded
+        })
+        .border_style(Style::default().fg(NEON_PURPLE));
+    let inner = block.inner(area);
+    let list_area = match header {
+        Some(header) if inner.height > 1 => {
+            f.render_widget(Paragraph::new(header), Rect { height: 1, ..inner });
+            Rect {
+                y: inner.y + 1,
+                height: inner.height - 1,
+                ..inner
             }
         }
+        _ => inner,
+    };
+    let rows = list_area.height as usize;
+    app.inspector_sockets.rows = rows;
+
+    let hint = if focused {
+        Some(" ↑↓ select · Enter: in list · Esc ")
+    } else if items.len() > rows {
+        Some(" Shift-Tab: scroll ")
+    } else {
+        None
+    };
+    if let Some(hint) = hint {
+        block = block.title_bottom(Line::from(Span::styled(
+            hint,
+            Style::default().fg(Color::DarkGray),
+        )));
+    }
+    f.render_widget(block, area);
+
+    if items.is_empty() {
+        f.render_widget(Paragraph::new(placeholder("(no sockets)")), list_area);
+        return;
+    }
+    // A list shrunk by a refresh keeps a valid selection
+    let selected = app.inspector_sockets.selected(items.len());
+    app.inspector_sockets.state.select(selected);
+    let mut list = List::new(items);
+    if focused {
+        list = list.highlight_style(Style::default().bg(Color::Rgb(47, 51, 77)));
     }
+    f.render_stateful_widget(list, list_area, &mut app.inspector_sockets.state);
+}
+
+/// Executable, identity and containment of the inspected process
+fn render_forensics(f: &mut Frame, area: Rect, app: &AppState) {
+    let label = |text: &'static str| Span::styled(text, Style::default().fg(Color::DarkGray));
+    let value = |text: String| Span::styled(text, Style::default().fg(BONE_WHITE));
+    let unknown = || Span::styled("unknown", Style::default().fg(Color::DarkGray));
+
+    let forensics = app
+        .forensics
+        .as_ref()
+        .filter(|f| Some(f.pid) == app.inspected_pid());
+    let mut lines = match forensics {
+        None if app.inspected_pid().is_none() => vec![placeholder(
+            "(no process: select a connection with a known PID, or press P)",
+        )],
+        None => vec![placeholder("(reading /proc...)")],
+        Some(forensics) => {
+            let owned: Vec<&Connection> = app
+                .connections
+                .iter()
+                .filter(|c| c.pid == Some(forensics.pid))
+                .collect();
+            let user = owned.iter().find_map(|c| c.user.clone());
+            let container = owned.iter().find_map(|c| c.container.clone());
+
+            let mut pid_line = vec![label("  PID:    "), value(forensics.pid.to_string())];
+            if let Some(ppid) = forensics.ppid {
+                pid_line.push(label("  PPID: "));
+                pid_line.push(value(ppid.to_string()));
+            }
+            let mut exe_line = vec![label("  EXE:    ")];
+            match forensics.exe {
+                Some(ref exe) => exe_line.push(value(exe.display().to_string())),
+                None => exe_line.push(unknown()),
+            }
+            if forensics.exe_deleted {
+                exe_line.push(Span::styled(
+                    " (deleted)",
+                    Style::default().fg(BLOOD_RED).add_modifier(Modifier::BOLD),
+                ));
+            }
+            let optional = |text: &Option<String>| match text {
+                Some(text) => value(text.clone()),
+                None => unknown(),
+            };
 
-    // Link-local
-    if addr.starts_with("169.254.") || addr.starts_with("fe80:") {
-        return false;
+            let mut lines = vec![
+                Line::from(pid_line),
+                Line::from(exe_line),
+                Line::from(vec![label("  CMD:    "), optional(&forensics.cmdline)]),
+                Line::from(vec![label("  USER:   "), optional(&user)]),
+            ];
+            if let Some(container) = container {
+                lines.push(Line::from(vec![label("  CTR:    "), value(container)]));
+            }
+            lines.push(Line::from(vec![
+                label("  CGROUP: "),
+                optional(&forensics.cgroup),
+            ]));
+            lines.push(Line::from(vec![
+                label("  SHA256: "),
+                optional(&forensics.sha256),
+            ]));
+            if let Some(package) = app.focused_package.as_ref().filter(|_| {
+                app.graveyard_mode == GraveyardMode::Process
+                    && app.selected_process_pid == Some(forensics.pid)
+            }) {
+                lines.push(Line::from(vec![
+                    label("  PKG:    "),
+                    value(package.display_text()),
+                ]));
+            }
+            lines
+        }
+    };
+    if let Some(conn) = app
+        .highlighted_connection()
+        .filter(|c| c.state != ConnectionState::Listen)
+    {
+        lines.push(Line::from(""));
+        lines.push(Line::from(vec![
+            label("  SOCKET: "),
+            value(format!(
+                "{}:{} → {}:{}",
+                conn.local_addr, conn.local_port, conn.remote_addr, conn.remote_port
+            )),
+        ]));
+        let info = app
+            .socket_info
+            .as_ref()
+            .filter(|(key, _)| *key == connection_key(conn));
+        match info {
+            None => lines.push(placeholder("(reading socket options...)")),
+            Some((_, None)) => lines.push(placeholder("(socket options unavailable)")),
+            Some((_, Some(info))) => {
+                let congestion = match info.congestion {
+                    Some(ref algorithm) => value(algorithm.clone()),
+                    None => unknown(),
+                };
+                let keepalive = match info.keepalive {
+                    Some(ref next) => value(format!("on, next probe in {}", next)),
+                    None => Span::styled("not armed", Style::default().fg(Color::DarkGray)),
+                };
+                let wscale = match info.wscale {
+                    Some((snd, rcv)) => value(format!("snd {} · rcv {}", snd, rcv)),
+                    None => Span::styled("not negotiated", Style::default().fg(Color::DarkGray)),
+                };
+                lines.push(Line::from(vec![label("  CC:     "), congestion]));
+                lines.push(Line::from(vec![label("  KEEP:   "), keepalive]));
+                lines.push(Line::from(vec![label("  WSCALE: "), wscale]));
+            }
+        }
     }
 
-    true
+    let paragraph = Paragraph::new(lines)
+        .wrap(Wrap { trim: false })
+        .block(tab_block(" 🔬 Forensics ".to_string()));
+    f.render_widget(paragraph, area);
+}
+
+/// The inspected target's connections opening, closing and changing state
+/// across the recorded refreshes, newest first
+fn render_history(f: &mut Frame, area: Rect, app: &AppState) {
+    let rows = (area.height as usize).saturating_sub(2);
+    let entries = timeline(&app.history, |c| app.is_inspected(c), rows);
+
+    let lines: Vec<Line> = if entries.is_empty() {
+        vec![placeholder(&format!(
+            "(no changes in the last {} refreshes)",
+            app.history.len()
+        ))]
+    } else {
+        entries
+            .iter()
+            .map(|entry| {
+                let (marker, color, state) = match entry.change {
+                    TimelineChange::Opened => {
+                        ("+", TOXIC_GREEN, entry.conn.state.name().to_string())
+                    }
+                    TimelineChange::Closed => ("-", BLOOD_RED, "closed".to_string()),
+                    TimelineChange::StateChange(prev) => (
+                        "~",
+                        PUMPKIN_ORANGE,
+                        format!("{} → {}", prev.name(), entry.conn.state.name()),
+                    ),
+                };
+                let endpoint = if entry.conn.state == ConnectionState::Listen {
+                    format!("{}:{}", entry.conn.local_addr, entry.conn.local_port)
+                } else {
+                    format!("{}:{}", entry.conn.remote_addr, entry.conn.remote_port)
+                };
+                Line::from(vec![
+                    Span::styled(
+                        format!("  {} ", time_of_day_label(entry.at)),
+                        Style::default().fg(Color::DarkGray),
+                    ),
+                    Span::styled(
+                        format!("{} ", marker),
+                        Style::default().fg(color).add_modifier(Modifier::BOLD),
+                    ),
+                    Span::styled(
+                        format!("{:<24} ", endpoint),
+                        Style::default().fg(Color::Cyan),
+                    ),
+                    Span::styled(state, Style::default().fg(color)),
+                ])
+            })
+            .collect()
+    };
+
+    let paragraph = Paragraph::new(lines).block(tab_block(" 🕯️ History ".to_string()));
+    f.render_widget(paragraph, area);
 }
 
-pub fn render_soul_inspector(f: &mut Frame, area: Rect, app: &AppState) {
+/// Target basics, activity sparkline and the target's sockets
+fn render_overview(f: &mut Frame, area: Rect, app: &mut AppState) {
     // Build view model from app state
     let view = build_soul_inspector_view(app);
 
@@ -601,16 +1008,103 @@ pub fn render_soul_inspector(f: &mut Frame, area: Rect, app: &AppState) {
                 Style::default().fg(BONE_WHITE),
             ),
             if let Some(pid) = view.pid {
-                Span::styled(
-                    format!("  (PID: {})", pid),
-                    Style::default().fg(Color::Cyan),
-                )
+                let pid_text = if view.child_count > 0 {
+                    format!("  (PID: {} +{} children)", pid, view.child_count)
+                } else {
+                    format!("  (PID: {})", pid)
+                };
+                Span::styled(pid_text, Style::default().fg(Color::Cyan))
             } else {
                 Span::raw("")
             },
         ]),
     ];
 
+    // PKG line - package ownership of the focused process's executable
+    if let Some(ref package) = view.package {
+        let package_style = match package {
+            PackageOwner::Package(_) => Style::default().fg(BONE_WHITE),
+            PackageOwner::Unpackaged => Style::default().fg(BLOOD_RED).add_modifier(Modifier::BOLD),
+            PackageOwner::Unknown | PackageOwner::Pending => Style::default().fg(Color::DarkGray),
+        };
+        top_content.push(Line::from(vec![
+            Span::styled("  PKG:    ", Style::default().fg(Color::DarkGray)),
+            Span::styled(package.display_text(), package_style),
+        ]));
+    }
+
+    // CLOUD line - provider range of the selected connection's endpoint
+    if let Some(ref cloud) = view.cloud {
+        top_content.push(Line::from(vec![
+            Span::styled("  CLOUD:  ", Style::default().fg(Color::DarkGray)),
+            Span::styled(format!("☁️ {}", cloud), Style::default().fg(NEON_PURPLE)),
+        ]));
+    }
+
+    // NAME line - reverse DNS name of the selected connection's endpoint
+    if let Some(ref host_name) = view.host_name {
+        top_content.push(Line::from(vec![
+            Span::styled("  NAME:   ", Style::default().fg(Color::DarkGray)),
+            Span::styled(format!("🔖 {}", host_name), Style::default().fg(BONE_WHITE)),
+        ]));
+    }
+
+    // ASN line - AS/organization of the selected connection's endpoint
+    if let Some(ref asn) = view.asn {
+        top_content.push(Line::from(vec![
+            Span::styled("  ASN:    ", Style::default().fg(Color::DarkGray)),
+            Span::styled(format!("🏢 {}", asn), Style::default().fg(BONE_WHITE)),
+        ]));
+    }
+
+    // GEO line - where the target's public peers are registered
+    if !view.countries.is_empty() {
+        let mut countries: Vec<String> = view
+            .countries
+            .iter()
+            .take(MAX_GEO_COUNTRIES)
+            .map(|(country, count)| format!("{} {}", glyphs().country(country), count))
+            .collect();
+        if view.countries.len() > MAX_GEO_COUNTRIES {
+            countries.push(format!("+{}", view.countries.len() - MAX_GEO_COUNTRIES));
+        }
+        top_content.push(Line::from(vec![
+            Span::styled("  GEO:    ", Style::default().fg(Color::DarkGray)),
+            Span::styled(countries.join(" · "), Style::default().fg(BONE_WHITE)),
+        ]));
+    }
+
+    // ANOM line - how far the target process strays from its baseline
+    if let Some(left) = view.learning_left {
+        top_content.push(Line::from(vec![
+            Span::styled("  ANOM:   ", Style::default().fg(Color::DarkGray)),
+            Span::styled(
+                format!("learning ({}m left)", left.as_secs().div_ceil(60)),
+                Style::default().fg(Color::DarkGray),
+            ),
+        ]));
+    } else if let Some(ref anomaly) = view.anomaly {
+        let color = match anomaly.score {
+            0..=19 => TOXIC_GREEN,
+            20..=59 => PUMPKIN_ORANGE,
+            _ => BLOOD_RED,
+        };
+        let filled = (anomaly.score as usize).div_ceil(10);
+        top_content.push(Line::from(vec![
+            Span::styled("  ANOM:   ", Style::default().fg(Color::DarkGray)),
+            Span::styled("▰".repeat(filled), Style::default().fg(color)),
+            Span::styled(
+                "▱".repeat(10 - filled),
+                Style::default().fg(Color::DarkGray),
+            ),
+            Span::styled(
+                format!(" {:>3} ", anomaly.score),
+                Style::default().fg(color).add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(anomaly.reasons.join(", "), Style::default().fg(BONE_WHITE)),
+        ]));
+    }
+
     // RISK line - only show if suspicious activity detected
     if view.suspicious {
         let reasons = if view.suspicious_reasons.is_empty() {
@@ -628,11 +1122,19 @@ pub fn render_soul_inspector(f: &mut Frame, area: Rect, app: &AppState) {
         ]));
     }
 
-    // Scan interval line
+    // Scan interval line: UI interval, then data interval (UI x multiplier)
     top_content.push(Line::from(vec![
         Span::styled("  SCAN:   ", Style::default().fg(Color::DarkGray)),
         Span::styled(format!("{}ms", view.refresh_ms), refresh_style),
-        Span::styled(" interval", Style::default().fg(Color::DarkGray)),
+        Span::styled(" ui · ", Style::default().fg(Color::DarkGray)),
+        Span::styled(
+            format!("{}ms", view.refresh_ms * view.data_multiplier),
+            refresh_style,
+        ),
+        Span::styled(
+            format!(" data (x{})", view.data_multiplier),
+            Style::default().fg(Color::DarkGray),
+        ),
     ]));
 
     // Title with suspicious warning if applicable
@@ -676,15 +1178,17 @@ pub fn render_soul_inspector(f: &mut Frame, area: Rect, app: &AppState) {
 
     f.render_widget(top_paragraph, inspector_chunks[0]);
 
-    // Calculate traffic history statistics
-    let traffic_avg = if app.traffic_history.is_empty() {
+    // Activity history of the current selection's scope
+    let scope = app.activity_scope();
+    let samples = app.activity.samples(&scope);
+    let traffic_avg = if samples.is_empty() {
         0.0
     } else {
-        app.traffic_history.iter().sum::<u64>() as f64 / app.traffic_history.len() as f64
+        samples.iter().sum::<u64>() as f64 / samples.len() as f64
     };
-    let traffic_peak = app.traffic_history.iter().max().copied().unwrap_or(0);
+    let traffic_peak = samples.iter().max().copied().unwrap_or(0);
 
-    // Sparkline for traffic history with Avg/Peak stats in title
+    // Sparkline for the scope's activity with Avg/Peak stats in title
     let sparkline = Sparkline::default()
         .block(
             Block::default()
@@ -695,6 +1199,7 @@ pub fn render_soul_inspector(f: &mut Frame, area: Rect, app: &AppState) {
                             .fg(Color::Cyan)
                             .add_modifier(Modifier::BOLD),
                     ),
+                    Span::styled(format!("· {} ", scope), Style::default().fg(NEON_PURPLE)),
                     Span::styled(
                         format!("Avg:{:.0} ", traffic_avg),
                         Style::default().fg(BONE_WHITE),
@@ -708,84 +1213,35 @@ pub fn render_soul_inspector(f: &mut Frame, area: Rect, app: &AppState) {
                 .border_type(BorderType::Rounded)
                 .border_style(Style::default().fg(NEON_PURPLE)),
         )
-        .data(&app.traffic_history)
+        .data(samples)
         .style(Style::default().fg(TOXIC_GREEN))
         .max(100);
 
     f.render_widget(sparkline, inspector_chunks[1]);
 
-    // Bottom section with socket list - now using real data
-    let mut socket_lines = vec![Line::from("")];
-
-    if view.sockets.is_empty() {
-        socket_lines.push(Line::from(vec![Span::styled(
-            "  (no sockets)",
-            Style::default()
-                .fg(Color::DarkGray)
-                .add_modifier(Modifier::ITALIC),
-        )]));
-    } else {
-        for socket in &view.sockets {
-            let state_color = match socket.state {
-                ConnectionState::Established => TOXIC_GREEN,
-                ConnectionState::Listen => PUMPKIN_ORANGE,
-                ConnectionState::TimeWait | ConnectionState::CloseWait => PUMPKIN_ORANGE,
-                ConnectionState::Close => BLOOD_RED,
-                _ => BONE_WHITE,
-            };
-
-            let state_str = match socket.state {
-                ConnectionState::Established => "ESTABLISHED",
-                ConnectionState::Listen => "LISTEN",
-                ConnectionState::TimeWait => "TIME_WAIT",
-                ConnectionState::CloseWait => "CLOSE_WAIT",
-                ConnectionState::Close => "CLOSED",
-                ConnectionState::SynSent => "SYN_SENT",
-                _ => "OTHER",
-            };
-
-            if let Some(ref remote) = socket.remote {
-                socket_lines.push(Line::from(vec![
-                    Span::raw("  > "),
-                    Span::styled(&socket.display, Style::default().fg(Color::Cyan)),
-                    Span::raw(" → "),
-                    Span::styled(remote, Style::default().fg(Color::Blue)),
-                ]));
-            } else {
-                socket_lines.push(Line::from(vec![
-                    Span::raw("  > "),
-                    Span::styled(&socket.display, Style::default().fg(Color::Cyan)),
-                    Span::styled(
-                        format!(" ({})", state_str),
-                        Style::default().fg(state_color),
-                    ),
-                ]));
+    // Bottom section: every socket of the target, scrollable while focused
+    let items: Vec<ListItem> = app
+        .inspected_connections()
+        .into_iter()
+        .map(|idx| {
+            let socket = connection_to_socket_info(&app.connections[idx]);
+            let mut spans = vec![
+                Span::raw("  > "),
+                Span::styled(socket.display, Style::default().fg(Color::Cyan)),
+            ];
+            match socket.remote {
+                Some(remote) => {
+                    spans.push(Span::raw(" → "));
+                    spans.push(Span::styled(remote, Style::default().fg(Color::Blue)));
+                }
+                None => spans.push(Span::styled(
+                    format!(" ({})", socket.state.name()),
+                    Style::default().fg(socket_state_color(socket.state)),
+                )),
             }
-        }
-
-        // Show "and N more" if there are more sockets
-        if view.conn_count > view.sockets.len() {
-            socket_lines.push(Line::from(vec![Span::styled(
-                format!("  ... and {} more", view.conn_count - view.sockets.len()),
-                Style::default()
-                    .fg(Color::DarkGray)
-                    .add_modifier(Modifier::ITALIC),
-            )]));
-        }
-    }
-
-    let socket_paragraph = Paragraph::new(socket_lines).block(
-        Block::default()
-            .title(vec![Span::styled(
-                format!(" 📜 Open Sockets ({}) ", view.sockets.len()),
-                Style::default()
-                    .fg(Color::Yellow)
-                    .add_modifier(Modifier::BOLD),
-            )])
-            .borders(Borders::ALL)
-            .border_type(BorderType::Rounded)
-            .border_style(Style::default().fg(NEON_PURPLE)),
-    );
-
-    f.render_widget(socket_paragraph, inspector_chunks[2]);
+            ListItem::new(Line::from(spans))
+        })
+        .collect();
+    let title = format!(" 📜 Open Sockets ({}) ", items.len());
+    render_socket_list(f, inspector_chunks[2], app, title, None, items);
 }
@@ -0,0 +1,256 @@
+// Legend popup
+//
+// Every icon, color and ring on the Graveyard explained in one place (`?`).
+// Entries are produced by the same functions the map draws with, so the
+// legend can't drift from what is actually on screen.
+
+use super::graveyard::{
+    alert_color, edge_arrow, edge_color, edge_intensity, edge_strands, node_color, particle_color,
+    ring_color, EndpointType,
+};
+use super::popup::centered_rect;
+use crate::app::anonymizers::Anonymizer;
+use crate::app::{AppState, LatencyBucket};
+use crate::net::{ConnectionDirection, ConnectionState};
+use crate::theme::glyphs::glyphs;
+use crate::theme::{
+    get_normal_status_text, get_overdrive_icon, get_overdrive_status_text, BONE_WHITE, NEON_PURPLE,
+    TOXIC_GREEN,
+};
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+
+/// Every TCP state, in the order the legend lists them
+const STATES: [ConnectionState; 11] = [
+    ConnectionState::Established,
+    ConnectionState::SynSent,
+    ConnectionState::SynRecv,
+    ConnectionState::TimeWait,
+    ConnectionState::CloseWait,
+    ConnectionState::FinWait1,
+    ConnectionState::FinWait2,
+    ConnectionState::LastAck,
+    ConnectionState::Closing,
+    ConnectionState::Close,
+    ConnectionState::Listen,
+];
+
+/// Render the legend popup over `area`
+pub fn render_legend_popup(f: &mut Frame, area: Rect, app: &AppState) {
+    let popup_area = centered_rect(70, 80, area);
+    let popup = Paragraph::new(legend_lines(app))
+        .block(
+            Block::default()
+                .title(" 📖 Legend (?/Esc to close) ")
+                .borders(Borders::ALL)
+                .border_type(BorderType::Double)
+                .border_style(Style::default().fg(NEON_PURPLE)),
+        )
+        .wrap(Wrap { trim: false });
+
+    f.render_widget(Clear, popup_area);
+    f.render_widget(popup, popup_area);
+}
+
+fn heading(text: &'static str) -> Line<'static> {
+    Line::from(Span::styled(
+        text,
+        Style::default()
+            .fg(NEON_PURPLE)
+            .add_modifier(Modifier::BOLD),
+    ))
+}
+
+/// "  <symbol> <description>" with the symbol in `color`
+fn entry(symbol: impl Into<String>, color: Color, text: impl Into<String>) -> Line<'static> {
+    Line::from(vec![
+        Span::styled(format!("  {} ", symbol.into()), Style::default().fg(color)),
+        Span::styled(text.into(), Style::default().fg(BONE_WHITE)),
+    ])
+}
+
+/// State names sharing each color, in first-seen order
+fn states_by_color(color_of: impl Fn(ConnectionState) -> Color) -> Vec<(Color, Vec<&'static str>)> {
+    let mut groups: Vec<(Color, Vec<&'static str>)> = Vec::new();
+    for state in STATES {
+        let color = color_of(state);
+        match groups.iter_mut().find(|(c, _)| *c == color) {
+            Some((_, names)) => names.push(state.name()),
+            None => groups.push((color, vec![state.name()])),
+        }
+    }
+    groups
+}
+
+/// The legend, section by section
+pub fn legend_lines(app: &AppState) -> Vec<Line<'static>> {
+    let mut lines = vec![heading("Endpoints")];
+    let builtin = [
+        (EndpointType::Localhost, "loopback"),
+        (
+            EndpointType::Private,
+            "private network (RFC 1918, link-local, IPv6 ULA)",
+        ),
+        (EndpointType::Public, "public internet"),
+        (EndpointType::ListenOnly, "listening socket"),
+    ];
+    for (endpoint_type, text) in builtin {
+        let color = node_color(ConnectionState::Established, &endpoint_type, false);
+        lines.push(entry(endpoint_type.icon(), color, text));
+    }
+    for rule in &app.endpoint_rules {
+        let endpoint_type = EndpointType::Custom(rule.clone());
+        let text = match rule.environment {
+            Some(ref env) => format!("{} [{}] ({})", rule.label, env, rule.cidr),
+            None => format!("{} ({})", rule.label, rule.cidr),
+        };
+        let color = node_color(ConnectionState::Established, &endpoint_type, false);
+        lines.push(entry(endpoint_type.icon(), color, text));
+    }
+    // Shown on a private endpoint, whose own color differs from both
+    let private = EndpointType::Private;
+    for (state, text) in [
+        (ConnectionState::TimeWait, "closing (TIME_WAIT, CLOSE_WAIT)"),
+        (ConnectionState::Close, "closed"),
+    ] {
+        lines.push(entry(
+            private.icon(),
+            node_color(state, &private, false),
+            text,
+        ));
+    }
+    let haunted = node_color(ConnectionState::Established, &private, true);
+    lines.push(entry(
+        glyphs().haunted.as_str(),
+        haunted,
+        "on the threat-intel blocklist",
+    ));
+    lines.push(entry(
+        private.icon(),
+        alert_color(BONE_WHITE, None),
+        "pulsing red: a firing watch or environment alert matches it",
+    ));
+    lines.push(entry(
+        glyphs().crown.as_str(),
+        BONE_WHITE,
+        "heavy talker (top 5 by connections)",
+    ));
+    for anonymizer in [Anonymizer::TorExit, Anonymizer::Vpn("".into())] {
+        lines.push(entry(anonymizer.icon(), BONE_WHITE, anonymizer.label()));
+    }
+
+    lines.push(Line::from(""));
+    lines.push(heading("Edges"));
+    let pulse = Color::Reset;
+    for (color, names) in states_by_color(|state| edge_color(state, pulse)) {
+        if color == pulse {
+            lines.push(entry(
+                "━━",
+                NEON_PURPLE,
+                format!("{} (pulsing)", names.join(" ")),
+            ));
+        } else {
+            lines.push(entry("━━", color, names.join(" ")));
+        }
+    }
+    lines.push(entry(
+        "══",
+        edge_intensity(TOXIC_GREEN, edge_strands(10)),
+        "doubled and brighter: 3+ connections, tripled: 10+",
+    ));
+    lines.push(entry(
+        edge_arrow(1.0, 0.0, ConnectionDirection::Outbound),
+        BONE_WHITE,
+        "we opened the connections",
+    ));
+    lines.push(entry(
+        edge_arrow(1.0, 0.0, ConnectionDirection::Inbound),
+        BONE_WHITE,
+        "the endpoint connected to us",
+    ));
+    let slow = particle_color(ConnectionState::Established, LatencyBucket::High);
+    lines.push(entry(
+        glyphs().particle.as_str(),
+        slow,
+        "particles: slow (high latency)",
+    ));
+    lines.push(entry(
+        glyphs().particle.as_str(),
+        BONE_WHITE,
+        "particles flow away from whoever opened most of the connections",
+    ));
+    for (color, names) in states_by_color(|state| particle_color(state, LatencyBucket::Unknown)) {
+        lines.push(entry(
+            glyphs().particle.as_str(),
+            color,
+            format!("particles: {}", names.join(" ")),
+        ));
+    }
+
+    lines.push(Line::from(""));
+    lines.push(heading("Latency rings (drawn once latency is known)"));
+    let latency = &app.latency_config;
+    let rings = [
+        format!("inner: under {} ms", latency.low_threshold_ms),
+        format!(
+            "middle: {}-{} ms",
+            latency.low_threshold_ms, latency.high_threshold_ms
+        ),
+        format!("outer: over {} ms", latency.high_threshold_ms),
+    ];
+    for (ring_idx, text) in rings.into_iter().enumerate() {
+        lines.push(entry("·", ring_color(ring_idx), text));
+    }
+
+    lines.push(Line::from(""));
+    lines.push(heading("Kiroween Overdrive (h)"));
+    let mut overdrive: Vec<(&str, String)> = Vec::new();
+    for state in STATES {
+        let icon = get_overdrive_icon(state, LatencyBucket::Unknown);
+        let text = format!(
+            "{} → {}",
+            get_normal_status_text(state),
+            get_overdrive_status_text(state)
+        );
+        if !overdrive.iter().any(|(i, t)| *i == icon && *t == text) {
+            overdrive.push((icon, text));
+        }
+    }
+    overdrive.push((
+        get_overdrive_icon(ConnectionState::Established, LatencyBucket::High),
+        "high latency".to_string(),
+    ));
+    for (icon, text) in overdrive {
+        lines.push(entry(icon, BONE_WHITE, text));
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_legend_lines() {
+        let app = AppState::new();
+        let text: Vec<String> = legend_lines(&app)
+            .iter()
+            .map(|line| line.spans.iter().map(|s| s.content.as_ref()).collect())
+            .collect();
+        let has = |needle: &str| text.iter().any(|line| line.contains(needle));
+
+        assert!(has("🎃 public internet"));
+        assert!(has("inner: under 50 ms"));
+        assert!(has("SYN_SENT SYN_RECV"));
+        assert!(has("🔥🎃 high latency"));
+        // Every state shares its edge color with a group, none is dropped
+        for state in STATES {
+            assert!(has(state.name()), "{} missing", state.name());
+        }
+    }
+}
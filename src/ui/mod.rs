@@ -8,32 +8,58 @@ pub mod emoji_width;
 mod graveyard;
 mod grimoire;
 mod inspector;
+mod legend;
+mod popup;
+mod port_heatmap;
+mod screensaver;
+pub mod screenshot;
+mod seance;
+mod split_view;
 mod status_bar;
+mod watchlist;
 
 // Re-export graveyard types for external use (may be used by tests or future modules)
 #[allow(unused_imports)]
 pub use graveyard::{
     calculate_endpoint_position, classify_endpoint, classify_latency, draw_coffin_block,
-    draw_latency_rings, has_latency_data, is_heavy_talker, particle_position, EndpointNode,
-    EndpointType,
+    draw_latency_rings, has_latency_data, is_heavy_talker, EdgeCurve, EndpointNode, EndpointType,
 };
 
+use crate::app::grouping::EndpointGrouping;
 use crate::app::AppState;
+use crate::theme::dim_color;
 use ratatui::{
     layout::{Constraint, Direction, Layout},
     Frame,
 };
 
-use banner::render_banner;
-use graveyard::render_network_map;
+use banner::{render_banner, render_storm_warning};
+use graveyard::{render_group_summary, render_network_map, GROUP_SUMMARY_HEIGHT};
 use grimoire::render_grimoire;
 use inspector::render_soul_inspector;
-use status_bar::render_status_bar;
+use legend::render_legend_popup;
+use popup::{
+    render_actions_popup, render_backend_popup, render_diagnostics_popup,
+    render_endpoint_detail_popup, render_perf_overlay, render_rdap_popup, render_settings_popup,
+    render_tag_popup,
+};
+use port_heatmap::{render_port_heatmap, PORT_HEATMAP_HEIGHT};
+use screensaver::render_screensaver;
+use seance::render_seance;
+use split_view::render_split_view;
+use status_bar::{render_status_bar, render_toasts, render_watch_bar};
+use watchlist::{render_watchlist, watchlist_height};
 
 /// Main UI drawing function
 pub fn draw(f: &mut Frame, app: &mut AppState) {
     let size = f.area();
 
+    // The screensaver replaces the whole UI until a key is pressed
+    if app.screensaver {
+        render_screensaver(f, size, app);
+        return;
+    }
+
     // Main layout: banner, body, status bar
     let chunks = Layout::default()
         .direction(Direction::Vertical)
@@ -47,6 +73,30 @@ pub fn draw(f: &mut Frame, app: &mut AppState) {
     // Banner
     render_banner(f, chunks[0], app);
 
+    // SYN storm warning takes the first body row while the storm lasts
+    let body_area = if app.syn_storm.status.active {
+        let storm_chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(1), Constraint::Min(0)])
+            .split(chunks[1]);
+        render_storm_warning(f, storm_chunks[0], app);
+        storm_chunks[1]
+    } else {
+        chunks[1]
+    };
+
+    // Watch bar takes the last body row while any watch is defined
+    let body_area = if app.watches.is_empty() {
+        body_area
+    } else {
+        let watch_chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(0), Constraint::Length(1)])
+            .split(body_area);
+        render_watch_bar(f, watch_chunks[1], app);
+        watch_chunks[0]
+    };
+
     // Body: Network map + right panels
     let body_chunks = Layout::default()
         .direction(Direction::Horizontal)
@@ -54,22 +104,92 @@ pub fn draw(f: &mut Frame, app: &mut AppState) {
             Constraint::Percentage(65), // Network map
             Constraint::Percentage(35), // Right panels
         ])
-        .split(chunks[1]);
+        .split(body_area);
 
-    render_network_map(f, body_chunks[0], app);
+    // The port heatmap sits below whatever fills the map area
+    let map_area = if app.show_port_heatmap {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(0), Constraint::Length(PORT_HEATMAP_HEIGHT)])
+            .split(body_chunks[0]);
+        render_port_heatmap(f, chunks[1], app);
+        chunks[0]
+    } else {
+        body_chunks[0]
+    };
 
-    // Right side: Soul Inspector + Grimoire
+    // Split view and the Séance grid replace the map; otherwise the group
+    // summary sits below the map while endpoints are grouped
+    if app.split_view {
+        render_split_view(f, map_area, app);
+    } else if app.seance {
+        render_seance(f, map_area, app);
+    } else if app.grouping != EndpointGrouping::Off {
+        let map_chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(0), Constraint::Length(GROUP_SUMMARY_HEIGHT)])
+            .split(map_area);
+        render_network_map(f, map_chunks[0], app);
+        render_group_summary(f, map_chunks[1], app);
+    } else {
+        render_network_map(f, map_area, app);
+    }
+
+    // Right side: Soul Inspector + Watchlist (while it has entries) + Grimoire
+    let watchlist = app.watchlist();
     let right_chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
-            Constraint::Percentage(60), // Soul Inspector
-            Constraint::Percentage(40), // Grimoire
+            Constraint::Percentage(60),                       // Soul Inspector
+            Constraint::Length(watchlist_height(&watchlist)), // Watchlist
+            Constraint::Percentage(40),                       // Grimoire
         ])
         .split(body_chunks[1]);
 
     render_soul_inspector(f, right_chunks[0], app);
-    render_grimoire(f, right_chunks[1], app);
+    if !watchlist.is_empty() {
+        render_watchlist(f, right_chunks[1], &watchlist);
+    }
+    render_grimoire(f, right_chunks[2], app);
 
     // Status bar
     render_status_bar(f, chunks[2], app);
+    render_toasts(f, chunks[2], app);
+
+    // Overlays
+    if app.show_backend_popup {
+        render_backend_popup(f, size, app);
+    }
+    if app.show_diagnostics {
+        render_diagnostics_popup(f, size, app);
+    }
+    if app.show_perf_overlay {
+        render_perf_overlay(f, size, app);
+    }
+    if app.show_legend {
+        render_legend_popup(f, size, app);
+    }
+    if app.endpoint_detail.is_some() {
+        render_endpoint_detail_popup(f, size, app);
+    }
+    if app.actions_popup.is_some() {
+        render_actions_popup(f, size, app);
+    }
+    if app.tag_popup.is_some() {
+        render_tag_popup(f, size, app);
+    }
+    if app.settings_popup.is_some() {
+        render_settings_popup(f, size, app);
+    }
+    if app.rdap_popup.is_some() {
+        render_rdap_popup(f, size, app);
+    }
+
+    // Night palette: darken everything drawn this frame
+    if app.night_dim {
+        for cell in f.buffer_mut().content.iter_mut() {
+            cell.fg = dim_color(cell.fg);
+            cell.bg = dim_color(cell.bg);
+        }
+    }
 }
@@ -0,0 +1,803 @@
+// Popup rendering module
+//
+// Renders modal overlays drawn on top of the main layout.
+
+use crate::app::config::{LatencyBucket, FRAME_TIME_THRESHOLD_MS};
+use crate::app::endpoint_detail::{age_label, timeline_strip};
+use crate::app::enrich_cache::CacheKind;
+use crate::app::events::{rfc3339, time_of_day_label};
+use crate::app::health::DiagnosticKind;
+use crate::app::heavy_talkers::{HeavyTalkerThreshold, SettingsRow};
+use crate::app::inventory::Criticality;
+use crate::app::perf::Samples;
+use crate::app::probes::ProbeKind;
+use crate::app::rdap::RdapLookup;
+use crate::app::tags::Tag;
+use crate::app::AppState;
+use crate::net::ConnectionState;
+use crate::theme::glyphs::glyphs;
+use crate::theme::{BLOOD_RED, BONE_WHITE, NEON_PURPLE, PUMPKIN_ORANGE, TOXIC_GREEN};
+use crate::ui::graveyard::{classify_endpoint, classify_latency, is_heavy_talker};
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Borders, Clear, Paragraph, Sparkline, Wrap},
+    Frame,
+};
+use std::collections::HashMap;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Compute a rectangle centered in `area` taking the given percentages
+pub fn centered_rect(percent_x: u16, percent_y: u16, area: Rect) -> Rect {
+    let vertical = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Percentage((100 - percent_y) / 2),
+            Constraint::Percentage(percent_y),
+            Constraint::Percentage((100 - percent_y) / 2),
+        ])
+        .split(area);
+
+    Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Percentage((100 - percent_x) / 2),
+            Constraint::Percentage(percent_x),
+            Constraint::Percentage((100 - percent_x) / 2),
+        ])
+        .split(vertical[1])[1]
+}
+
+/// Render the backend debug popup: active backend, timing, attribution
+/// and the errors collected during recent refreshes (newest first)
+pub fn render_backend_popup(f: &mut Frame, area: Rect, app: &AppState) {
+    let popup_area = centered_rect(70, 60, area);
+    let health = &app.backend_health;
+
+    let label = Style::default()
+        .fg(NEON_PURPLE)
+        .add_modifier(Modifier::BOLD);
+    let value = Style::default().fg(BONE_WHITE);
+
+    let duration = health
+        .last_duration
+        .map(|d| format!("{} ms", d.as_millis()))
+        .unwrap_or_else(|| "-".to_string());
+
+    let mut lines = vec![
+        Line::from(vec![
+            Span::styled("Backend:      ", label),
+            Span::styled(
+                health.backend,
+                Style::default()
+                    .fg(TOXIC_GREEN)
+                    .add_modifier(Modifier::BOLD),
+            ),
+        ]),
+        Line::from(vec![
+            Span::styled("Last collect: ", label),
+            Span::styled(duration, value),
+        ]),
+        Line::from(vec![
+            Span::styled("No process:   ", label),
+            Span::styled(
+                format!("{} of {} sockets", health.unattributed, health.total),
+                value,
+            ),
+        ]),
+        Line::from(""),
+        Line::from(Span::styled("Recent errors:", label)),
+    ];
+
+    if health.error_count() == 0 {
+        lines.push(Line::from(Span::styled(
+            "  No backend errors",
+            Style::default().fg(TOXIC_GREEN),
+        )));
+    } else {
+        for error in health.errors().rev() {
+            lines.push(Line::from(vec![
+                Span::styled(
+                    format!("  {} ", error.time_label()),
+                    Style::default().fg(BONE_WHITE),
+                ),
+                Span::styled(
+                    format!("[{}] ", error.source),
+                    Style::default().fg(PUMPKIN_ORANGE),
+                ),
+                Span::styled(error.message.clone(), Style::default().fg(BLOOD_RED)),
+            ]));
+        }
+    }
+
+    let popup = Paragraph::new(lines)
+        .block(
+            Block::default()
+                .title(" 🔧 Backend Health (b/Esc to close) ")
+                .borders(Borders::ALL)
+                .border_type(BorderType::Double)
+                .border_style(Style::default().fg(NEON_PURPLE)),
+        )
+        .wrap(Wrap { trim: false });
+
+    f.render_widget(Clear, popup_area);
+    f.render_widget(popup, popup_area);
+}
+
+/// Render the diagnostics panel: the current collection state, then every
+/// recorded error, permission problem and backend warning (newest first)
+pub fn render_diagnostics_popup(f: &mut Frame, area: Rect, app: &AppState) {
+    let popup_area = centered_rect(80, 70, area);
+    let label = Style::default()
+        .fg(NEON_PURPLE)
+        .add_modifier(Modifier::BOLD);
+
+    let collection = match app.conn_error {
+        Some(ref error) => Span::styled(format!("✖ {}", error), Style::default().fg(BLOOD_RED)),
+        None => Span::styled(
+            format!("✔ OK via {}", app.backend_health.backend),
+            Style::default().fg(TOXIC_GREEN),
+        ),
+    };
+    let mut lines = vec![
+        Line::from(vec![Span::styled("Collection: ", label), collection]),
+        Line::from(""),
+    ];
+
+    if app.backend_health.error_count() == 0 {
+        lines.push(Line::from(Span::styled(
+            "  Nothing to report",
+            Style::default().fg(TOXIC_GREEN),
+        )));
+    }
+    for diagnostic in app.backend_health.errors().rev() {
+        let color = match diagnostic.kind {
+            DiagnosticKind::Error => BLOOD_RED,
+            DiagnosticKind::Permission | DiagnosticKind::Warning => PUMPKIN_ORANGE,
+        };
+        lines.push(Line::from(vec![
+            Span::styled(
+                format!("  {} ", diagnostic.time_label()),
+                Style::default().fg(BONE_WHITE),
+            ),
+            Span::styled(
+                format!("{:<5} ", diagnostic.kind.label()),
+                Style::default().fg(color).add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(
+                format!("[{}] ", diagnostic.source),
+                Style::default().fg(NEON_PURPLE),
+            ),
+            Span::styled(diagnostic.message.clone(), Style::default().fg(color)),
+        ]));
+    }
+
+    let popup = Paragraph::new(lines)
+        .block(
+            Block::default()
+                .title(" ❗ Diagnostics (!/Esc to close) ")
+                .borders(Borders::ALL)
+                .border_type(BorderType::Double)
+                .border_style(Style::default().fg(NEON_PURPLE)),
+        )
+        .wrap(Wrap { trim: false });
+
+    f.render_widget(Clear, popup_area);
+    f.render_widget(popup, popup_area);
+}
+
+/// Render the RDAP popup: who the registry says owns the selected
+/// endpoint's address
+pub fn render_rdap_popup(f: &mut Frame, area: Rect, app: &AppState) {
+    let Some(addr) = app.rdap_popup.as_deref() else {
+        return;
+    };
+    let popup_area = centered_rect(60, 40, area);
+    let label = Style::default()
+        .fg(NEON_PURPLE)
+        .add_modifier(Modifier::BOLD);
+
+    let lines = match app.rdap_lookup(addr) {
+        Some(RdapLookup::Done(record)) => {
+            let mut lines: Vec<Line> = [
+                ("NETWORK", &record.network),
+                ("RANGE  ", &record.range),
+                ("ORG    ", &record.org),
+                ("COUNTRY", &record.country),
+                ("ABUSE  ", &record.abuse),
+            ]
+            .into_iter()
+            .map(|(name, value)| {
+                let value = match value {
+                    Some(value) => Span::styled(value.clone(), Style::default().fg(BONE_WHITE)),
+                    None => Span::styled("-", Style::default().fg(Color::DarkGray)),
+                };
+                Line::from(vec![Span::styled(format!("{} ", name), label), value])
+            })
+            .collect();
+            if let Some(age) = app.stale_enrichment(CacheKind::Rdap, addr) {
+                lines.push(Line::from(""));
+                lines.push(Line::from(Span::styled(
+                    format!("⌛ cached {} ago, may be out of date", age_label(age)),
+                    Style::default().fg(PUMPKIN_ORANGE),
+                )));
+            }
+            lines
+        }
+        Some(RdapLookup::Failed(error)) => vec![Line::from(Span::styled(
+            format!("✖ {}", error),
+            Style::default().fg(BLOOD_RED),
+        ))],
+        Some(RdapLookup::Pending) | None => vec![Line::from(Span::styled(
+            "(asking the registry...)",
+            Style::default().fg(Color::DarkGray),
+        ))],
+    };
+
+    let popup = Paragraph::new(lines)
+        .block(
+            Block::default()
+                .title(format!(" 🔎 Who owns {} (K/Esc to close) ", addr))
+                .borders(Borders::ALL)
+                .border_type(BorderType::Double)
+                .border_style(Style::default().fg(NEON_PURPLE)),
+        )
+        .wrap(Wrap { trim: false });
+
+    f.render_widget(Clear, popup_area);
+    f.render_widget(popup, popup_area);
+}
+
+/// Render the performance overlay: frame times against the slow-frame
+/// threshold and collection time per refresh over the last minute, plus
+/// the animation quality tier and why it was reduced if it was, and the
+/// network probes sent so far
+pub fn render_perf_overlay(f: &mut Frame, area: Rect, app: &AppState) {
+    let popup_area = centered_rect(70, 60, area);
+    let block = Block::default()
+        .title(" ⏱ Performance, last minute (%/Esc to close) ")
+        .borders(Borders::ALL)
+        .border_type(BorderType::Double)
+        .border_style(Style::default().fg(NEON_PURPLE));
+    let inner = block.inner(popup_area);
+    f.render_widget(Clear, popup_area);
+    f.render_widget(block, popup_area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3),
+            Constraint::Percentage(50),
+            Constraint::Percentage(50),
+        ])
+        .split(inner);
+
+    let label = Style::default()
+        .fg(NEON_PURPLE)
+        .add_modifier(Modifier::BOLD);
+    let quality = app.graveyard_settings.animation_quality;
+    let animation = match app.perf.reduction {
+        Some(reduction) => Span::styled(
+            format!(
+                "{} (reduced from {} at {}: {} frames over {} ms in a row, last {} ms)",
+                reduction.to.label(),
+                reduction.from.label(),
+                time_of_day_label(reduction.at),
+                reduction.slow_frames,
+                FRAME_TIME_THRESHOLD_MS,
+                reduction.frame_ms
+            ),
+            Style::default().fg(PUMPKIN_ORANGE),
+        ),
+        None => Span::styled(quality.label(), Style::default().fg(TOXIC_GREEN)),
+    };
+    let probes = if app.probes.passive_only() {
+        Span::styled("passive only", Style::default().fg(TOXIC_GREEN))
+    } else {
+        let mut text = format!(
+            "{} DNS, {} feed, {} RDAP ({})",
+            app.probes.sent(ProbeKind::Dns),
+            app.probes.sent(ProbeKind::Feed),
+            app.probes.sent(ProbeKind::Rdap),
+            app.probes.limit_label()
+        );
+        if app.probes.deferred() > 0 {
+            text.push_str(&format!(", {} deferred", app.probes.deferred()));
+        }
+        Span::styled(text, Style::default().fg(BONE_WHITE))
+    };
+    let summary = Paragraph::new(vec![
+        Line::from(vec![Span::styled("Animation: ", label), animation]),
+        Line::from(vec![Span::styled("Probes: ", label), probes]),
+    ])
+    .wrap(Wrap { trim: false });
+    f.render_widget(summary, chunks[0]);
+
+    let frame_color = if app.perf.frames.peak_ms() > FRAME_TIME_THRESHOLD_MS as u64 {
+        BLOOD_RED
+    } else {
+        TOXIC_GREEN
+    };
+    render_samples(
+        f,
+        chunks[1],
+        &app.perf.frames,
+        format!("Frame time (threshold {} ms)", FRAME_TIME_THRESHOLD_MS),
+        // Keep the threshold at half height so slow frames stand out
+        FRAME_TIME_THRESHOLD_MS as u64 * 2,
+        frame_color,
+    );
+    render_samples(
+        f,
+        chunks[2],
+        &app.perf.collections,
+        format!("Collection per refresh ({})", app.backend_health.backend),
+        1,
+        NEON_PURPLE,
+    );
+}
+
+/// One titled sparkline of the newest samples that fit the width, scaled
+/// to at least `min_max` ms
+fn render_samples(
+    f: &mut Frame,
+    area: Rect,
+    samples: &Samples,
+    title: String,
+    min_max: u64,
+    color: Color,
+) {
+    let block = Block::default()
+        .title(vec![
+            Span::styled(format!(" {} ", title), Style::default().fg(BONE_WHITE)),
+            Span::styled(
+                format!("avg {} ms ", samples.average_ms()),
+                Style::default().fg(BONE_WHITE),
+            ),
+            Span::styled(
+                format!("peak {} ms ", samples.peak_ms()),
+                Style::default().fg(PUMPKIN_ORANGE),
+            ),
+        ])
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(NEON_PURPLE));
+    let width = block.inner(area).width as usize;
+    let millis = samples.millis();
+    let visible = &millis[millis.len().saturating_sub(width)..];
+    let sparkline = Sparkline::default()
+        .block(block)
+        .data(visible)
+        .style(Style::default().fg(color))
+        .max(samples.peak_ms().max(min_max));
+    f.render_widget(sparkline, area);
+}
+
+/// Color of a TCP state in the endpoint detail popup
+fn state_color(state: ConnectionState) -> Color {
+    match state {
+        ConnectionState::Established => TOXIC_GREEN,
+        ConnectionState::TimeWait | ConnectionState::CloseWait => PUMPKIN_ORANGE,
+        ConnectionState::Close => BLOOD_RED,
+        _ => BONE_WHITE,
+    }
+}
+
+/// Render the endpoint detail popup: what the selected Graveyard node is,
+/// then every connection to it with ports, state, process and age, and
+/// the states it went through when it changed state while watched
+pub fn render_endpoint_detail_popup(f: &mut Frame, area: Rect, app: &AppState) {
+    let Some(ref detail) = app.endpoint_detail else {
+        return;
+    };
+    let popup_area = centered_rect(80, 70, area);
+    let label = Style::default()
+        .fg(NEON_PURPLE)
+        .add_modifier(Modifier::BOLD);
+    let value = Style::default().fg(BONE_WHITE);
+
+    // Heavy talkers are ranked among all nodes, as on the map
+    let mut counts: HashMap<_, usize> = HashMap::new();
+    for conn in &app.connections {
+        if let Some((key, _)) = app.endpoint_key(conn) {
+            *counts.entry(key).or_default() += 1;
+        }
+    }
+    let all_counts: Vec<usize> = counts.values().copied().collect();
+    let heavy = is_heavy_talker(
+        detail.rows.len(),
+        &all_counts,
+        app.graveyard_settings.heavy_talkers,
+    );
+
+    let mut lines = Vec::new();
+    let kind = match detail.rows.first() {
+        Some(_) if detail.is_group => "group of endpoints".to_string(),
+        Some(row) => {
+            let endpoint_type =
+                classify_endpoint(&row.conn.remote_addr, false, &app.endpoint_rules);
+            format!("{} {}", endpoint_type.icon(), endpoint_type.label())
+        }
+        None => "no longer connected".to_string(),
+    };
+    lines.push(Line::from(vec![
+        Span::styled("Type:        ", label),
+        Span::styled(kind, value),
+    ]));
+    if !detail.is_group {
+        let addr = &*detail.key;
+        let mut notes = Vec::new();
+        if let Some(range) = app.cloud_range(addr) {
+            notes.push(range.short_label());
+        }
+        if let Some(asn) = app.asn(addr) {
+            notes.push(asn.to_string());
+        }
+        if let Some(reverse) = app.reverse_name(addr) {
+            match app.stale_enrichment(CacheKind::Dns, addr) {
+                Some(age) => {
+                    notes.push(format!("{} (cached {} ago)", reverse.name, age_label(age)))
+                }
+                None => notes.push(reverse.name.to_string()),
+            }
+        }
+        if let Some(anonymizer) = app.anonymizer(addr) {
+            notes.push(anonymizer.label());
+        }
+        if !notes.is_empty() {
+            lines.push(Line::from(vec![
+                Span::styled("Known as:    ", label),
+                Span::styled(notes.join(" · "), value),
+            ]));
+        }
+        if let Some(asset) = app.inventory_entry(addr) {
+            let style = if asset.criticality == Some(Criticality::Critical) {
+                Style::default().fg(BLOOD_RED).add_modifier(Modifier::BOLD)
+            } else {
+                value
+            };
+            lines.push(Line::from(vec![
+                Span::styled("Asset:       ", label),
+                Span::styled(asset.to_string(), style),
+            ]));
+        }
+        if let Some(seen) = app.peers.as_ref().and_then(|peers| peers.seen(addr)) {
+            let at = |secs| rfc3339(UNIX_EPOCH + Duration::from_secs(secs));
+            lines.push(Line::from(vec![
+                Span::styled("Peer:        ", label),
+                Span::styled(
+                    format!(
+                        "first seen {} · last seen {}",
+                        at(seen.first),
+                        at(seen.last)
+                    ),
+                    value,
+                ),
+                Span::styled(
+                    if app.is_new_peer(addr) { "  NEW" } else { "" },
+                    Style::default()
+                        .fg(TOXIC_GREEN)
+                        .add_modifier(Modifier::BOLD),
+                ),
+            ]));
+        }
+        let tombstones: Vec<_> = app
+            .tombstones
+            .iter()
+            .flat_map(|tombstones| tombstones.to_remote(addr))
+            .collect();
+        if let Some(last) = tombstones.last() {
+            let mut ports: Vec<u16> = tombstones.iter().map(|t| t.conn.remote_port).collect();
+            ports.sort_unstable();
+            ports.dedup();
+            let ports: Vec<String> = ports.iter().map(|port| format!(":{}", port)).collect();
+            lines.push(Line::from(vec![
+                Span::styled("Tombstones:  ", label),
+                Span::styled(
+                    format!(
+                        "{} short-lived (to {}) · last closed {}",
+                        tombstones.len(),
+                        ports.join(" "),
+                        time_of_day_label(last.closed_at)
+                    ),
+                    value,
+                ),
+            ]));
+        }
+        if app.is_haunted(addr) {
+            lines.push(Line::from(Span::styled(
+                "☠️ On the threat-intel blocklist",
+                Style::default().fg(BLOOD_RED).add_modifier(Modifier::BOLD),
+            )));
+        }
+    }
+    lines.push(Line::from(vec![
+        Span::styled("Connections: ", label),
+        Span::styled(detail.rows.len().to_string(), value),
+        Span::styled(
+            if heavy {
+                format!(
+                    "  {} heavy talker ({})",
+                    glyphs().crown,
+                    app.graveyard_settings.heavy_talkers.label()
+                )
+            } else {
+                String::new()
+            },
+            Style::default().fg(PUMPKIN_ORANGE),
+        ),
+    ]));
+    // Backends report no RTT yet, so every endpoint lands in Unknown
+    let latency = match classify_latency(None, &app.latency_config) {
+        LatencyBucket::Low => "low",
+        LatencyBucket::Medium => "medium",
+        LatencyBucket::High => "high",
+        LatencyBucket::Unknown => "unknown (no RTT data from this backend)",
+    };
+    lines.push(Line::from(vec![
+        Span::styled("Latency:     ", label),
+        Span::styled(latency, value),
+    ]));
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        format!(
+            "  {:<22} {:<22} {:<12} {:<20} {}",
+            "LOCAL", "REMOTE", "STATE", "PROCESS", "AGE"
+        ),
+        label,
+    )));
+
+    let now = SystemTime::now();
+    for row in &detail.rows {
+        let conn = &row.conn;
+        let process = match (conn.pid, conn.process_name.as_deref()) {
+            (Some(pid), Some(name)) => format!("{} ({})", name, pid),
+            (Some(pid), None) => pid.to_string(),
+            _ => "-".to_string(),
+        };
+        let age = match row.first_seen {
+            Some(at) => {
+                let age = age_label(now.duration_since(at).unwrap_or_default());
+                if row.before_history {
+                    format!("≥{}", age)
+                } else {
+                    age
+                }
+            }
+            None => "-".to_string(),
+        };
+        lines.push(Line::from(vec![
+            Span::styled(
+                format!(
+                    "  {:<22} {:<22} ",
+                    format!("{}:{}", conn.local_addr, conn.local_port),
+                    format!("{}:{}", conn.remote_addr, conn.remote_port)
+                ),
+                value,
+            ),
+            Span::styled(
+                format!("{:<12} ", conn.state.name()),
+                Style::default().fg(state_color(conn.state)),
+            ),
+            Span::styled(format!("{:<20} ", process), value),
+            Span::styled(age, Style::default().fg(NEON_PURPLE)),
+        ]));
+
+        // How it got there: "└ SYN_SENT 12:00:01 → ESTABLISHED +2s"
+        if row.steps.len() > 1 {
+            let mut strip = vec![Span::styled("    └ ", Style::default().fg(Color::DarkGray))];
+            for (i, (state, when)) in timeline_strip(&row.steps).into_iter().enumerate() {
+                if i > 0 {
+                    strip.push(Span::styled(" → ", Style::default().fg(Color::DarkGray)));
+                }
+                strip.push(Span::styled(
+                    state.name(),
+                    Style::default().fg(state_color(state)),
+                ));
+                strip.push(Span::styled(
+                    format!(" {}", when),
+                    Style::default().fg(Color::DarkGray),
+                ));
+            }
+            lines.push(Line::from(strip));
+        }
+    }
+
+    // An expanded group's member can be collapsed back from here
+    let title = if app.detail_in_group(detail) {
+        format!(
+            " 🔎 {} (Enter expand/collapse group, Esc close) ",
+            detail.key
+        )
+    } else {
+        format!(" 🔎 {} (Esc close) ", detail.key)
+    };
+    let popup = Paragraph::new(lines)
+        .block(
+            Block::default()
+                .title(title)
+                .borders(Borders::ALL)
+                .border_type(BorderType::Double)
+                .border_style(Style::default().fg(NEON_PURPLE)),
+        )
+        .wrap(Wrap { trim: false });
+
+    f.render_widget(Clear, popup_area);
+    f.render_widget(popup, popup_area);
+}
+
+/// Render the investigation commands popup for the selected connection
+///
+/// The highlighted command is copied with Enter; nothing is executed.
+pub fn render_actions_popup(f: &mut Frame, area: Rect, app: &AppState) {
+    let Some(ref popup) = app.actions_popup else {
+        return;
+    };
+    let popup_area = centered_rect(70, 40, area);
+
+    let mut lines = vec![
+        Line::from(vec![
+            Span::styled(
+                "Target: ",
+                Style::default()
+                    .fg(NEON_PURPLE)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(popup.target.clone(), Style::default().fg(PUMPKIN_ORANGE)),
+        ]),
+        Line::from(""),
+    ];
+    for (idx, action) in popup.actions.iter().enumerate() {
+        let style = if idx == popup.selected {
+            Style::default()
+                .fg(TOXIC_GREEN)
+                .add_modifier(Modifier::BOLD | Modifier::REVERSED)
+        } else {
+            Style::default().fg(BONE_WHITE)
+        };
+        lines.push(Line::from(vec![
+            Span::styled(format!(" {} ", idx + 1), Style::default().fg(NEON_PURPLE)),
+            Span::styled(
+                format!("{:<26}", action.label),
+                Style::default().fg(BONE_WHITE),
+            ),
+            Span::styled(format!(" {} ", action.command), style),
+        ]));
+    }
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        "Commands are copied to the clipboard, never run by ntomb",
+        Style::default()
+            .fg(BONE_WHITE)
+            .add_modifier(Modifier::ITALIC),
+    )));
+
+    let widget = Paragraph::new(lines)
+        .block(
+            Block::default()
+                .title(" 🔦 Investigate (↑↓ / 1-9, Enter copy, x/Esc close) ")
+                .borders(Borders::ALL)
+                .border_type(BorderType::Double)
+                .border_style(Style::default().fg(NEON_PURPLE)),
+        )
+        .wrap(Wrap { trim: false });
+
+    f.render_widget(Clear, popup_area);
+    f.render_widget(widget, popup_area);
+}
+
+/// Render the tag popup for the selected connection, with a check mark on
+/// the tags it already has
+pub fn render_tag_popup(f: &mut Frame, area: Rect, app: &AppState) {
+    let Some(ref popup) = app.tag_popup else {
+        return;
+    };
+    let popup_area = centered_rect(40, 30, area);
+
+    let mut lines = vec![
+        Line::from(vec![
+            Span::styled(
+                "Target: ",
+                Style::default()
+                    .fg(NEON_PURPLE)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(popup.target.clone(), Style::default().fg(PUMPKIN_ORANGE)),
+        ]),
+        Line::from(""),
+    ];
+    for (idx, tag) in Tag::ALL.iter().enumerate() {
+        let mut style = Style::default()
+            .fg(tag.color())
+            .add_modifier(Modifier::BOLD);
+        if idx == popup.selected {
+            style = style.add_modifier(Modifier::REVERSED);
+        }
+        let check = if popup.conns.iter().all(|conn| app.tags.has(conn, *tag)) {
+            "✓"
+        } else {
+            " "
+        };
+        lines.push(Line::from(vec![
+            Span::styled(format!(" {} ", idx + 1), Style::default().fg(NEON_PURPLE)),
+            Span::styled(format!("{} ", check), Style::default().fg(TOXIC_GREEN)),
+            Span::styled(format!(" {} ", tag.label()), style),
+        ]));
+    }
+
+    let widget = Paragraph::new(lines)
+        .block(
+            Block::default()
+                .title(" 🏷 Tag (↑↓ / 1-3, Enter toggle, n/Esc close) ")
+                .borders(Borders::ALL)
+                .border_type(BorderType::Double)
+                .border_style(Style::default().fg(NEON_PURPLE)),
+        )
+        .wrap(Wrap { trim: false });
+
+    f.render_widget(Clear, popup_area);
+    f.render_widget(widget, popup_area);
+}
+
+pub fn render_settings_popup(f: &mut Frame, area: Rect, app: &AppState) {
+    let Some(ref popup) = app.settings_popup else {
+        return;
+    };
+    let popup_area = centered_rect(40, 30, area);
+    let heavy_talkers = app.graveyard_settings.heavy_talkers;
+
+    let mut lines = vec![
+        Line::from(Span::styled(
+            format!("{} Heavy talkers", glyphs().crown),
+            Style::default()
+                .fg(NEON_PURPLE)
+                .add_modifier(Modifier::BOLD),
+        )),
+        Line::from(""),
+    ];
+    for (idx, row) in SettingsRow::ALL.iter().enumerate() {
+        let (name, value) = match row {
+            SettingsRow::HeavyTalkerRule => (
+                "Rule",
+                match heavy_talkers {
+                    HeavyTalkerThreshold::Top(_) => "top N busiest",
+                    HeavyTalkerThreshold::Min(_) => "at least N connections",
+                }
+                .to_string(),
+            ),
+            SettingsRow::HeavyTalkerCount => ("N", heavy_talkers.count().to_string()),
+            SettingsRow::Profile => (
+                "Profile",
+                app.profile
+                    .map_or_else(|| "custom".to_string(), |p| p.to_string()),
+            ),
+        };
+        let mut style = Style::default().fg(PUMPKIN_ORANGE);
+        if idx == popup.selected {
+            style = style.add_modifier(Modifier::REVERSED);
+        }
+        lines.push(Line::from(vec![
+            Span::styled(format!(" {:<7}", name), Style::default().fg(BONE_WHITE)),
+            Span::styled(format!(" ◂ {} ▸ ", value), style),
+        ]));
+    }
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        format!(" Crowning: {}", heavy_talkers.label()),
+        Style::default().fg(Color::DarkGray),
+    )));
+
+    let widget = Paragraph::new(lines)
+        .block(
+            Block::default()
+                .title(" ⚙ Settings (↑↓ select, ←→ change, ,/Esc close) ")
+                .borders(Borders::ALL)
+                .border_type(BorderType::Double)
+                .border_style(Style::default().fg(NEON_PURPLE)),
+        )
+        .wrap(Wrap { trim: false });
+
+    f.render_widget(Clear, popup_area);
+    f.render_widget(widget, popup_area);
+}
@@ -0,0 +1,199 @@
+// Port heatmap rendering module
+//
+// Panel below the Graveyard showing local port usage: a row per port
+// range with its state mix, and a strip of the whole port space shaded by
+// connection count and colored by the dominant state.
+
+use crate::app::config::PORT_EXHAUSTION_PERCENT;
+use crate::app::ports::{PortRange, RangeUsage, StateMix};
+use crate::app::AppState;
+use crate::net::ConnectionState;
+use crate::theme::{BLOOD_RED, BONE_WHITE, NEON_PURPLE, PUMPKIN_ORANGE, TOXIC_GREEN};
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Borders, Paragraph},
+    Frame,
+};
+
+/// Height of the port heatmap (including borders)
+pub const PORT_HEATMAP_HEIGHT: u16 = 7;
+
+/// Width of the state mix bar of each range row
+const MIX_BAR_WIDTH: usize = 20;
+
+/// Strip shades, empty to busiest
+const SHADES: [char; 5] = [' ', '░', '▒', '▓', '█'];
+
+pub fn render_port_heatmap(f: &mut Frame, area: Rect, app: &AppState) {
+    let width = area.width.saturating_sub(2).max(1) as usize;
+    let heatmap = app.port_heatmap(width);
+
+    let mut lines: Vec<Line> = heatmap.ranges.iter().map(range_line).collect();
+
+    // Strip: shade by connections relative to the busiest slice
+    let busiest = heatmap
+        .buckets
+        .iter()
+        .map(StateMix::total)
+        .max()
+        .unwrap_or(0);
+    lines.push(Line::from(
+        heatmap
+            .buckets
+            .iter()
+            .map(|mix| {
+                let total = mix.total();
+                let shade = if total == 0 {
+                    0
+                } else {
+                    (total * (SHADES.len() - 1)).div_ceil(busiest)
+                };
+                Span::styled(
+                    SHADES[shade].to_string(),
+                    Style::default().fg(mix.dominant().map_or(Color::DarkGray, mix_color)),
+                )
+            })
+            .collect::<Vec<_>>(),
+    ));
+    lines.push(Line::from(Span::styled(
+        axis(
+            width,
+            *app.ephemeral_ports.start(),
+            *app.ephemeral_ports.end(),
+        ),
+        Style::default().fg(Color::DarkGray),
+    )));
+
+    let mut title = vec![Span::styled(
+        format!(
+            "━ 🔥 Local ports (ephemeral {}-{}) ━",
+            app.ephemeral_ports.start(),
+            app.ephemeral_ports.end()
+        ),
+        Style::default()
+            .fg(NEON_PURPLE)
+            .add_modifier(Modifier::BOLD),
+    )];
+    if heatmap.ephemeral_listeners > 0 {
+        title.push(Span::styled(
+            format!(
+                " ⚠ {} listening on ephemeral ports ",
+                heatmap.ephemeral_listeners
+            ),
+            Style::default().fg(PUMPKIN_ORANGE),
+        ));
+    }
+
+    let panel = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(NEON_PURPLE))
+            .title(Line::from(title)),
+    );
+    f.render_widget(panel, area);
+}
+
+/// " ephemeral   1234 ports  4.4%  ████▒▒  est 12 · closing 3 ..."
+fn range_line(usage: &RangeUsage) -> Line<'static> {
+    let percent = usage.percent_used();
+    let exhausted = usage.range == PortRange::Ephemeral && percent >= PORT_EXHAUSTION_PERCENT;
+    let mut spans = vec![
+        Span::styled(
+            format!(" {:<11}", usage.range.label()),
+            Style::default().fg(BONE_WHITE).add_modifier(Modifier::BOLD),
+        ),
+        Span::styled(
+            format!("{:>5} ports ", usage.ports),
+            Style::default().fg(BONE_WHITE),
+        ),
+        Span::styled(
+            format!("{:>5.1}% ", percent),
+            if exhausted {
+                Style::default().fg(BLOOD_RED).add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(Color::DarkGray)
+            },
+        ),
+    ];
+
+    // State mix bar, each kind sized by its share of the range's connections
+    let mix = usage.mix;
+    let kinds = [
+        (mix.listen, ConnectionState::Listen, "listen"),
+        (mix.established, ConnectionState::Established, "est"),
+        (mix.closing, ConnectionState::TimeWait, "closing"),
+        (mix.other, ConnectionState::Unknown, "other"),
+    ];
+    let total = mix.total();
+    let mut drawn = 0;
+    for (count, state, _) in kinds {
+        if count == 0 {
+            continue;
+        }
+        let cells = (count * MIX_BAR_WIDTH)
+            .div_ceil(total)
+            .min(MIX_BAR_WIDTH - drawn);
+        drawn += cells;
+        spans.push(Span::styled(
+            "█".repeat(cells),
+            Style::default().fg(mix_color(state)),
+        ));
+    }
+    spans.push(Span::raw(" ".repeat(MIX_BAR_WIDTH - drawn + 1)));
+
+    let counts: Vec<String> = kinds
+        .iter()
+        .filter(|(count, _, _)| *count > 0)
+        .map(|(count, _, name)| format!("{} {}", name, count))
+        .collect();
+    spans.push(Span::styled(
+        counts.join(" · "),
+        Style::default().fg(Color::DarkGray),
+    ));
+    if exhausted {
+        spans.push(Span::styled(
+            "  ⚠ port exhaustion",
+            Style::default().fg(BLOOD_RED).add_modifier(Modifier::BOLD),
+        ));
+    }
+    Line::from(spans)
+}
+
+/// Color of a state kind in the mix bar and strip (`StateMix::dominant`)
+fn mix_color(state: ConnectionState) -> Color {
+    match state {
+        ConnectionState::Listen => NEON_PURPLE,
+        ConnectionState::Established => TOXIC_GREEN,
+        ConnectionState::TimeWait => PUMPKIN_ORANGE,
+        _ => Color::DarkGray,
+    }
+}
+
+/// Port numbers under the strip: 0, the ephemeral range and, when there
+/// is room after the range, 65535; later labels overwrite earlier ones
+fn axis(width: usize, ephemeral_start: u16, ephemeral_end: u16) -> String {
+    let mut axis = vec![' '; width];
+    let mut place = |pos: usize, text: &str| {
+        let pos = pos.min(width.saturating_sub(text.chars().count()));
+        for (i, c) in text.chars().enumerate() {
+            if let Some(cell) = axis.get_mut(pos + i) {
+                *cell = c;
+            }
+        }
+    };
+    place(0, "0");
+    let end = format!("{}┘", ephemeral_end);
+    let end_pos = (ephemeral_end as usize * width / 65536).saturating_sub(end.chars().count() - 1);
+    if end_pos + end.chars().count() + 1 < width.saturating_sub(5) {
+        place(width, "65535");
+    }
+    place(end_pos, &end);
+    place(
+        ephemeral_start as usize * width / 65536,
+        &format!("└{}", ephemeral_start),
+    );
+    axis.into_iter().collect()
+}
@@ -0,0 +1,162 @@
+// Screensaver
+//
+// After `[screensaver] idle_secs` without a keypress the whole screen turns
+// into a slow orbit of the live endpoints around the host, in dimmed
+// colors, so ntomb can stay up on a wall-mounted display without burning in
+// its panels and borders. Any key brings the normal UI back.
+
+use super::graveyard::{classify_endpoint, node_color};
+use crate::app::AppState;
+use crate::net::ConnectionState;
+use crate::theme::glyphs::glyphs;
+use crate::theme::{dim_color, BONE_WHITE, NEON_PURPLE};
+use ratatui::{
+    layout::Rect,
+    style::{Modifier, Style},
+    symbols::Marker,
+    text::Span,
+    widgets::canvas::Canvas,
+    Frame,
+};
+use std::collections::HashMap;
+use std::f64::consts::TAU;
+use std::sync::Arc;
+
+/// Endpoints in orbit at once, busiest first
+const MAX_ORBITING: usize = 24;
+
+/// Orbit radii (canvas y units), innermost first
+const ORBIT_RADII: [f64; 3] = [18.0, 30.0, 42.0];
+
+/// Angular speed per tick of each orbit; inner orbits turn faster
+const ORBIT_SPEEDS: [f64; 3] = [0.012, 0.008, 0.005];
+
+/// Where endpoint `index` is at `tick`, relative to the center: endpoints
+/// are dealt round-robin onto the orbits and spread evenly along each
+pub fn orbit_position(index: usize, tick: u64) -> (f64, f64) {
+    let orbit = index % ORBIT_RADII.len();
+    let slot = index / ORBIT_RADII.len();
+    let per_orbit = MAX_ORBITING.div_ceil(ORBIT_RADII.len());
+    let angle = slot as f64 * TAU / per_orbit as f64
+        + orbit as f64 * 0.4
+        + tick as f64 * ORBIT_SPEEDS[orbit];
+    let radius = ORBIT_RADII[orbit];
+    (radius * angle.cos(), radius * angle.sin())
+}
+
+/// An endpoint in orbit
+struct Orbiter {
+    key: Arc<str>,
+    count: usize,
+    state: ConnectionState,
+    remote_addr: Arc<str>,
+}
+
+/// Graveyard nodes of the visible connections, busiest first
+fn orbiters(app: &AppState) -> Vec<Orbiter> {
+    let mut by_key: HashMap<Arc<str>, Orbiter> = HashMap::new();
+    for conn in &app.connections {
+        let Some((key, _)) = app.endpoint_key(conn) else {
+            continue;
+        };
+        by_key
+            .entry(Arc::clone(&key))
+            .or_insert_with(|| Orbiter {
+                key,
+                count: 0,
+                state: conn.state,
+                remote_addr: Arc::clone(&conn.remote_addr),
+            })
+            .count += 1;
+    }
+    let mut orbiters: Vec<Orbiter> = by_key.into_values().collect();
+    orbiters.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.key.cmp(&b.key)));
+    orbiters.truncate(MAX_ORBITING);
+    orbiters
+}
+
+/// Render the screensaver over the whole of `area`
+pub fn render_screensaver(f: &mut Frame, area: Rect, app: &AppState) {
+    let orbiters = orbiters(app);
+    let tick = app.tick_counter;
+    let total = app.connections.len();
+
+    // Same square coordinate space as the Graveyard: braille cells are
+    // 2x4 dots, y runs 0-100 and x is widened to the aspect ratio
+    let aspect = (area.width as f64 * 2.0) / (area.height as f64 * 4.0).max(1.0);
+    let x_range = 100.0 * aspect;
+    let (cx, cy) = (x_range / 2.0, 50.0);
+    // Canvas x units per terminal cell, for placing text
+    let cell = x_range / (area.width as f64).max(1.0);
+
+    let canvas = Canvas::default()
+        .marker(Marker::Braille)
+        .x_bounds([0.0, x_range])
+        .y_bounds([0.0, 100.0])
+        .paint(move |ctx| {
+            let center = glyphs().localhost.as_str();
+            ctx.print(
+                cx - cell,
+                cy,
+                Span::styled(center, Style::default().fg(dim_color(NEON_PURPLE))),
+            );
+
+            for (index, orbiter) in orbiters.iter().enumerate() {
+                let (dx, dy) = orbit_position(index, tick);
+                let endpoint_type =
+                    classify_endpoint(&orbiter.remote_addr, false, &app.endpoint_rules);
+                let haunted = app.haunted_endpoints.contains_key(&orbiter.remote_addr);
+                let color = dim_color(node_color(orbiter.state, &endpoint_type, haunted));
+                ctx.print(
+                    cx + dx,
+                    cy + dy,
+                    Span::styled(endpoint_type.icon().to_string(), Style::default().fg(color)),
+                );
+                ctx.print(
+                    cx + dx + 3.0 * cell,
+                    cy + dy,
+                    Span::styled(
+                        format!("{} ({})", orbiter.key, orbiter.count),
+                        Style::default().fg(color),
+                    ),
+                );
+            }
+
+            let caption = format!("ntomb · {} connections · any key to wake", total);
+            ctx.print(
+                cx - caption.chars().count() as f64 / 2.0 * cell,
+                3.0,
+                Span::styled(
+                    caption,
+                    Style::default()
+                        .fg(dim_color(BONE_WHITE))
+                        .add_modifier(Modifier::ITALIC),
+                ),
+            );
+        });
+
+    f.render_widget(canvas, area);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_orbit_position() {
+        // Each endpoint stays on its orbit as it turns
+        for index in 0..MAX_ORBITING {
+            for tick in [0, 100, 5_000] {
+                let (dx, dy) = orbit_position(index, tick);
+                let radius = (dx * dx + dy * dy).sqrt();
+                assert!((radius - ORBIT_RADII[index % 3]).abs() < 1e-9);
+            }
+        }
+        // Neighbours on an orbit don't overlap
+        let (x0, y0) = orbit_position(0, 0);
+        let (x1, y1) = orbit_position(3, 0);
+        assert!((x0 - x1).hypot(y0 - y1) > 5.0);
+        // and it moves
+        assert_ne!(orbit_position(0, 0), orbit_position(0, 10));
+    }
+}
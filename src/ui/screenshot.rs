@@ -0,0 +1,295 @@
+// Screenshot export
+//
+// Serializes a drawn frame buffer for sharing the exact screen in chat or
+// docs: ANSI text (SGR escapes, for `cat` in a terminal) and a standalone
+// HTML page. Both walk the buffer cell by cell, emitting a new style only
+// where it changes and skipping the cells hidden under wide characters.
+
+use ratatui::{
+    buffer::{Buffer, Cell},
+    style::{Color, Modifier},
+};
+use unicode_width::UnicodeWidthStr;
+
+/// Page colors for cells left at the terminal default (Tokyo Night, the
+/// palette the theme is drawn from)
+const HTML_BACKGROUND: (u8, u8, u8) = (26, 27, 38);
+const HTML_FOREGROUND: (u8, u8, u8) = (169, 177, 214);
+
+/// The visible cells of each row: cells covered by the wide character
+/// before them, or marked skip, are left out
+fn rows(buffer: &Buffer) -> impl Iterator<Item = Vec<&Cell>> {
+    let width = buffer.area.width.max(1) as usize;
+    buffer.content.chunks(width).map(|row| {
+        let mut cells = Vec::with_capacity(row.len());
+        let mut covered = 0;
+        for cell in row {
+            if covered > 0 {
+                covered -= 1;
+                continue;
+            }
+            if cell.skip {
+                continue;
+            }
+            covered = cell.symbol().width().saturating_sub(1);
+            cells.push(cell);
+        }
+        cells
+    })
+}
+
+/// The frame as text with ANSI SGR color escapes, one line per row
+pub fn to_ansi(buffer: &Buffer) -> String {
+    let mut out = String::new();
+    for row in rows(buffer) {
+        let mut current: Option<(Color, Color, Modifier)> = None;
+        for cell in row {
+            let style = (cell.fg, cell.bg, cell.modifier);
+            if current != Some(style) {
+                out.push_str(&sgr(cell.fg, cell.bg, cell.modifier));
+                current = Some(style);
+            }
+            out.push_str(cell.symbol());
+        }
+        out.push_str("\x1b[0m\n");
+    }
+    out
+}
+
+/// One SGR sequence that resets, then sets the given colors and modifiers
+fn sgr(fg: Color, bg: Color, modifier: Modifier) -> String {
+    let mut codes = vec!["0".to_string()];
+    for (flag, code) in [
+        (Modifier::BOLD, "1"),
+        (Modifier::DIM, "2"),
+        (Modifier::ITALIC, "3"),
+        (Modifier::UNDERLINED, "4"),
+        (Modifier::REVERSED, "7"),
+        (Modifier::CROSSED_OUT, "9"),
+    ] {
+        if modifier.contains(flag) {
+            codes.push(code.to_string());
+        }
+    }
+    codes.extend(sgr_color(fg, 30));
+    codes.extend(sgr_color(bg, 40));
+    format!("\x1b[{}m", codes.join(";"))
+}
+
+/// SGR parameter for a color; `base` is 30 for foreground, 40 for
+/// background. None leaves the terminal default.
+fn sgr_color(color: Color, base: u8) -> Option<String> {
+    let named = |offset: u8| Some((base + offset).to_string());
+    let bright = |offset: u8| Some((base + 60 + offset).to_string());
+    match color {
+        Color::Reset => None,
+        Color::Black => named(0),
+        Color::Red => named(1),
+        Color::Green => named(2),
+        Color::Yellow => named(3),
+        Color::Blue => named(4),
+        Color::Magenta => named(5),
+        Color::Cyan => named(6),
+        Color::Gray => named(7),
+        Color::DarkGray => bright(0),
+        Color::LightRed => bright(1),
+        Color::LightGreen => bright(2),
+        Color::LightYellow => bright(3),
+        Color::LightBlue => bright(4),
+        Color::LightMagenta => bright(5),
+        Color::LightCyan => bright(6),
+        Color::White => bright(7),
+        Color::Indexed(index) => Some(format!("{};5;{}", base + 8, index)),
+        Color::Rgb(r, g, b) => Some(format!("{};2;{};{};{}", base + 8, r, g, b)),
+    }
+}
+
+/// The frame as a standalone HTML page
+pub fn to_html(buffer: &Buffer) -> String {
+    let mut body = String::new();
+    for row in rows(buffer) {
+        let mut run: Option<(String, String)> = None;
+        for cell in row {
+            let style = css(cell);
+            match run {
+                Some((ref run_style, ref mut text)) if *run_style == style => {
+                    push_escaped(text, cell.symbol());
+                }
+                _ => {
+                    if let Some((run_style, text)) = run.take() {
+                        push_span(&mut body, &run_style, &text);
+                    }
+                    let mut text = String::new();
+                    push_escaped(&mut text, cell.symbol());
+                    run = Some((style, text));
+                }
+            }
+        }
+        if let Some((run_style, text)) = run {
+            push_span(&mut body, &run_style, &text);
+        }
+        body.push('\n');
+    }
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>ntomb</title>\n\
+         </head>\n<body style=\"margin:0;background:{bg}\">\n\
+         <pre style=\"margin:0;padding:1em;color:{fg};background:{bg};\
+         font-family:monospace;line-height:1.2\">\n{body}</pre>\n</body>\n</html>\n",
+        bg = hex(HTML_BACKGROUND),
+        fg = hex(HTML_FOREGROUND),
+        body = body,
+    )
+}
+
+fn push_span(out: &mut String, style: &str, text: &str) {
+    if style.is_empty() {
+        out.push_str(text);
+    } else {
+        out.push_str(&format!("<span style=\"{}\">{}</span>", style, text));
+    }
+}
+
+fn push_escaped(out: &mut String, text: &str) {
+    for ch in text.chars() {
+        match ch {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            _ => out.push(ch),
+        }
+    }
+}
+
+/// Inline CSS for a cell; empty for the page defaults
+fn css(cell: &Cell) -> String {
+    let (mut fg, mut bg) = (rgb(cell.fg), rgb(cell.bg));
+    if cell.modifier.contains(Modifier::REVERSED) {
+        (fg, bg) = (
+            Some(bg.unwrap_or(HTML_BACKGROUND)),
+            Some(fg.unwrap_or(HTML_FOREGROUND)),
+        );
+    }
+    let mut style = String::new();
+    if let Some(fg) = fg {
+        style.push_str(&format!("color:{};", hex(fg)));
+    }
+    if let Some(bg) = bg {
+        style.push_str(&format!("background:{};", hex(bg)));
+    }
+    for (flag, rule) in [
+        (Modifier::BOLD, "font-weight:bold;"),
+        (Modifier::DIM, "opacity:0.6;"),
+        (Modifier::ITALIC, "font-style:italic;"),
+        (Modifier::UNDERLINED, "text-decoration:underline;"),
+        (Modifier::CROSSED_OUT, "text-decoration:line-through;"),
+    ] {
+        if cell.modifier.contains(flag) {
+            style.push_str(rule);
+        }
+    }
+    style
+}
+
+fn hex((r, g, b): (u8, u8, u8)) -> String {
+    format!("#{:02x}{:02x}{:02x}", r, g, b)
+}
+
+/// RGB for a color (xterm defaults for the named ones); None for Reset
+fn rgb(color: Color) -> Option<(u8, u8, u8)> {
+    const NAMED: [(u8, u8, u8); 16] = [
+        (0, 0, 0),
+        (205, 0, 0),
+        (0, 205, 0),
+        (205, 205, 0),
+        (0, 0, 238),
+        (205, 0, 205),
+        (0, 205, 205),
+        (229, 229, 229),
+        (127, 127, 127),
+        (255, 0, 0),
+        (0, 255, 0),
+        (255, 255, 0),
+        (92, 92, 255),
+        (255, 0, 255),
+        (0, 255, 255),
+        (255, 255, 255),
+    ];
+    let index = match color {
+        Color::Reset => return None,
+        Color::Rgb(r, g, b) => return Some((r, g, b)),
+        Color::Black => 0,
+        Color::Red => 1,
+        Color::Green => 2,
+        Color::Yellow => 3,
+        Color::Blue => 4,
+        Color::Magenta => 5,
+        Color::Cyan => 6,
+        Color::Gray => 7,
+        Color::DarkGray => 8,
+        Color::LightRed => 9,
+        Color::LightGreen => 10,
+        Color::LightYellow => 11,
+        Color::LightBlue => 12,
+        Color::LightMagenta => 13,
+        Color::LightCyan => 14,
+        Color::White => 15,
+        Color::Indexed(index) => index,
+    };
+    Some(match index {
+        0..=15 => NAMED[index as usize],
+        // 6x6x6 color cube
+        16..=231 => {
+            let level = |n: u8| if n == 0 { 0 } else { 55 + n * 40 };
+            let n = index - 16;
+            (level(n / 36), level(n / 6 % 6), level(n % 6))
+        }
+        // Grayscale ramp
+        _ => {
+            let gray = 8 + (index - 232) * 10;
+            (gray, gray, gray)
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::{layout::Rect, style::Style};
+
+    fn sample() -> Buffer {
+        let mut buffer = Buffer::empty(Rect::new(0, 0, 6, 2));
+        buffer.set_string(0, 0, "a<b", Style::default().fg(Color::Rgb(187, 154, 247)));
+        buffer.set_string(3, 0, "👻", Style::default().add_modifier(Modifier::BOLD));
+        buffer.set_string(0, 1, "ok", Style::default().bg(Color::Red));
+        buffer
+    }
+
+    #[test]
+    fn test_to_ansi() {
+        let ansi = to_ansi(&sample());
+        let lines: Vec<&str> = ansi.lines().collect();
+        assert_eq!(lines.len(), 2);
+        // The ghost covers two cells, so the row has one trailing space
+        assert_eq!(
+            lines[0],
+            "\x1b[0;38;2;187;154;247ma<b\x1b[0;1m👻\x1b[0m \x1b[0m"
+        );
+        assert_eq!(lines[1], "\x1b[0;41mok\x1b[0m    \x1b[0m");
+    }
+
+    #[test]
+    fn test_to_html() {
+        let html = to_html(&sample());
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<span style=\"color:#bb9af7;\">a&lt;b</span>"));
+        assert!(html.contains("<span style=\"font-weight:bold;\">👻</span> \n"));
+        assert!(html.contains("<span style=\"background:#cd0000;\">ok</span>    \n"));
+    }
+
+    #[test]
+    fn test_rgb() {
+        assert_eq!(rgb(Color::Reset), None);
+        assert_eq!(rgb(Color::Indexed(196)), Some((255, 0, 0)));
+        assert_eq!(rgb(Color::Indexed(244)), Some((128, 128, 128)));
+    }
+}
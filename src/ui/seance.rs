@@ -0,0 +1,190 @@
+// Séance view rendering module
+//
+// Replaces the Graveyard with a grid of mini-graveyards, one per busy
+// process: the process sits in a coffin label at the center of its tile
+// with its busiest endpoints around it.
+
+use super::graveyard::{build_label_coffin, edge_color, truncate_host_name};
+use crate::app::seance::{grid_size, SeanceCell};
+use crate::app::trend::Trend;
+use crate::app::AppState;
+use crate::theme::{interpolate_color, BONE_WHITE, NEON_PURPLE, PUMPKIN_ORANGE};
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    symbols::Marker,
+    text::Span,
+    widgets::{
+        canvas::{Canvas, Line as CanvasLine},
+        Block, BorderType, Borders, Paragraph,
+    },
+    Frame,
+};
+
+/// Distance of the endpoints from the coffin, in canvas units (y: 0-100)
+const ENDPOINT_RADIUS: f64 = 34.0;
+
+pub fn render_seance(f: &mut Frame, area: Rect, app: &AppState) {
+    let cells = app.seance_cells();
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(NEON_PURPLE))
+        .title(Span::styled(
+            format!("━ 🔮 Séance ({} processes, O to leave) ━", cells.len()),
+            Style::default()
+                .fg(NEON_PURPLE)
+                .add_modifier(Modifier::BOLD),
+        ));
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    if cells.is_empty() {
+        f.render_widget(
+            Paragraph::new(Span::styled(
+                " No process is talking to anyone",
+                Style::default().fg(Color::DarkGray),
+            )),
+            inner,
+        );
+        return;
+    }
+
+    let selected_pid = app
+        .selected_connection
+        .and_then(|idx| app.connections.get(idx))
+        .and_then(|conn| conn.pid);
+    let pulse = interpolate_color((138, 43, 226), (187, 154, 247), app.pulse_phase);
+    let trends = app.process_trends();
+
+    let (cols, rows) = grid_size(cells.len());
+    let row_areas = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(vec![Constraint::Ratio(1, rows as u32); rows])
+        .split(inner);
+    for (row, cells) in cells.chunks(cols).enumerate() {
+        let tiles = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints(vec![Constraint::Ratio(1, cols as u32); cols])
+            .split(row_areas[row]);
+        for (cell, tile) in cells.iter().zip(tiles.iter()) {
+            let trend = trends.get(&cell.pid).copied().unwrap_or(Trend::Stable);
+            render_cell(f, *tile, cell, trend, selected_pid == Some(cell.pid), pulse);
+        }
+    }
+}
+
+/// One mini-graveyard; the selected connection's process gets an orange frame
+fn render_cell(
+    f: &mut Frame,
+    area: Rect,
+    cell: &SeanceCell,
+    trend: Trend,
+    selected: bool,
+    pulse: Color,
+) {
+    let border = if selected {
+        PUMPKIN_ORANGE
+    } else {
+        Color::DarkGray
+    };
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(border))
+        .title(Span::styled(
+            format!(
+                " {} ({}) ×{} {} ",
+                cell.name,
+                cell.pid,
+                cell.connections,
+                trend.arrow()
+            ),
+            Style::default().fg(border),
+        ));
+
+    // Same square coordinate space as the Graveyard: y spans 0-100 and
+    // x is stretched to the tile's aspect ratio (Braille: 2x4 dots per cell)
+    let width_cells = area.width.saturating_sub(2).max(1) as f64;
+    let height_cells = area.height.saturating_sub(2).max(1) as f64;
+    let x_range = 100.0 * (width_cells * 2.0) / (height_cells * 4.0);
+    let (cx, cy) = (x_range / 2.0, 50.0);
+    // Canvas units per character cell, to center printed text
+    let unit_x = x_range / width_cells;
+    let unit_y = 100.0 / height_cells;
+
+    let label_width = (width_cells as usize / 3).max(6);
+    let coffin = build_label_coffin(&cell.name, label_width);
+    let coffin_radius = (coffin.width as f64 / 2.0 + 1.0) * unit_x;
+
+    let count = cell.endpoints.len() + usize::from(cell.hidden_endpoints > 0);
+    let canvas = Canvas::default()
+        .block(block)
+        .marker(Marker::Braille)
+        .x_bounds([0.0, x_range])
+        .y_bounds([0.0, 100.0])
+        .paint(move |ctx| {
+            for (i, endpoint) in cell.endpoints.iter().enumerate() {
+                // Start at the top and go clockwise
+                let angle =
+                    std::f64::consts::FRAC_PI_2 - i as f64 * std::f64::consts::TAU / count as f64;
+                let (dx, dy) = (angle.cos() * ENDPOINT_RADIUS, angle.sin() * ENDPOINT_RADIUS);
+                let color = edge_color(endpoint.state, pulse);
+                let dist = (dx * dx + dy * dy).sqrt();
+                let start = if dist > coffin_radius {
+                    coffin_radius / dist
+                } else {
+                    0.0
+                };
+                ctx.draw(&CanvasLine {
+                    x1: cx + dx * start,
+                    y1: cy + dy * start,
+                    x2: cx + dx,
+                    y2: cy + dy,
+                    color,
+                });
+
+                let label = truncate_host_name(&endpoint.key, label_width);
+                let label = if endpoint.connections > 1 {
+                    format!("{} ×{}", label, endpoint.connections)
+                } else {
+                    label
+                };
+                let offset = label.chars().count() as f64 / 2.0 * unit_x;
+                // Labels below the coffin hang under their endpoint
+                let y = if dy < 0.0 { cy + dy - unit_y } else { cy + dy };
+                ctx.print(
+                    (cx + dx - offset).max(0.0),
+                    y,
+                    Span::styled(label, Style::default().fg(color)),
+                );
+            }
+
+            if cell.hidden_endpoints > 0 {
+                let more = format!("+{} more", cell.hidden_endpoints);
+                let angle = std::f64::consts::FRAC_PI_2
+                    - cell.endpoints.len() as f64 * std::f64::consts::TAU / count as f64;
+                let offset = more.chars().count() as f64 / 2.0 * unit_x;
+                ctx.print(
+                    (cx + angle.cos() * ENDPOINT_RADIUS - offset).max(0.0),
+                    cy + angle.sin() * ENDPOINT_RADIUS,
+                    Span::styled(
+                        more,
+                        Style::default()
+                            .fg(Color::DarkGray)
+                            .add_modifier(Modifier::ITALIC),
+                    ),
+                );
+            }
+
+            ctx.print(
+                cx - coffin.width as f64 / 2.0 * unit_x,
+                cy,
+                Span::styled(
+                    coffin.lines[0].clone(),
+                    Style::default().fg(BONE_WHITE).add_modifier(Modifier::BOLD),
+                ),
+            );
+        });
+    f.render_widget(canvas, area);
+}
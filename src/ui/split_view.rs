@@ -0,0 +1,117 @@
+// Split view rendering module
+//
+// Replaces the Graveyard with two panels: "Souls we summon" (our LISTEN
+// sockets with their inbound peers) and "Souls we seek" (connections we
+// opened), so the direction of every connection is visible at a glance.
+
+use crate::app::direction;
+use crate::app::AppState;
+use crate::net::Connection;
+use crate::theme::{BONE_WHITE, NEON_PURPLE, PUMPKIN_ORANGE, TOXIC_GREEN};
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Borders, Paragraph},
+    Frame,
+};
+
+pub fn render_split_view(f: &mut Frame, area: Rect, app: &AppState) {
+    let chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(area);
+
+    let split = direction::split(&app.connections);
+
+    // Left: each listener followed by its inbound peers
+    let peer_count: usize = split.services.iter().map(|s| s.peers.len()).sum();
+    let mut summon = Vec::new();
+    for service in &split.services {
+        let listener = &service.listener;
+        summon.push(Line::from(vec![
+            Span::styled(
+                format!(" 🕯️ {}:{}", listener.local_addr, listener.local_port),
+                Style::default().fg(BONE_WHITE).add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(owner(listener), Style::default().fg(Color::Cyan)),
+            Span::styled(
+                format!("  ×{}", service.peers.len()),
+                Style::default().fg(Color::DarkGray),
+            ),
+        ]));
+        for peer in &service.peers {
+            summon.push(Line::from(Span::styled(
+                format!(
+                    "    ← {}:{} [{:?}]",
+                    peer.remote_addr, peer.remote_port, peer.state
+                ),
+                Style::default().fg(TOXIC_GREEN),
+            )));
+        }
+    }
+    render_panel(
+        f,
+        chunks[0],
+        format!(
+            "━ 🕯️ Souls we summon ({} services, {} peers) ━",
+            split.services.len(),
+            peer_count
+        ),
+        summon,
+    );
+
+    // Right: connections we opened
+    let seek = split
+        .outbound
+        .iter()
+        .map(|conn| {
+            Line::from(vec![
+                Span::styled(
+                    format!(
+                        " → {}:{} [{:?}]",
+                        conn.remote_addr, conn.remote_port, conn.state
+                    ),
+                    Style::default().fg(PUMPKIN_ORANGE),
+                ),
+                Span::styled(owner(conn), Style::default().fg(Color::Cyan)),
+            ])
+        })
+        .collect();
+    render_panel(
+        f,
+        chunks[1],
+        format!("━ 🔮 Souls we seek ({}) ━", split.outbound.len()),
+        seek,
+    );
+}
+
+/// " name(pid)" of the owning process, if known
+fn owner(conn: &Connection) -> String {
+    match (&conn.process_name, conn.pid) {
+        (Some(name), Some(pid)) => format!(" {}({})", name, pid),
+        _ => String::new(),
+    }
+}
+
+fn render_panel(f: &mut Frame, area: Rect, title: String, mut lines: Vec<Line>) {
+    if lines.is_empty() {
+        lines.push(Line::from(Span::styled(
+            " (none)",
+            Style::default().fg(Color::DarkGray),
+        )));
+    }
+    let panel = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(NEON_PURPLE))
+            .title(Span::styled(
+                title,
+                Style::default()
+                    .fg(NEON_PURPLE)
+                    .add_modifier(Modifier::BOLD),
+            )),
+    );
+    f.render_widget(panel, area);
+}
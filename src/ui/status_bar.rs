@@ -1,18 +1,56 @@
 // Status Bar rendering module
 //
-// Renders the bottom status bar with keyboard shortcuts and toggle indicators.
+// Renders the bottom status bar with keyboard shortcuts and toggle indicators,
+// and the toasts stacked over it.
 
-use crate::app::{AppState, GraveyardMode};
-use crate::theme::{BONE_WHITE, NEON_PURPLE, TOXIC_GREEN};
+use crate::app::endpoint_detail::age_label;
+use crate::app::keymap::KeymapProfile;
+use crate::app::toast::ToastSeverity;
+use crate::app::{AppState, DataFreshness, GraveyardMode, PromptKind};
+use crate::theme::{BLOOD_RED, BONE_WHITE, NEON_PURPLE, PUMPKIN_ORANGE, TOXIC_GREEN};
 use ratatui::{
     layout::{Alignment, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, BorderType, Borders, Paragraph},
+    widgets::{Block, BorderType, Borders, Clear, Paragraph},
     Frame,
 };
 
+/// Draw toasts right-aligned over the status bar: the newest on its text
+/// row, older ones stacked above it
+pub fn render_toasts(f: &mut Frame, area: Rect, app: &AppState) {
+    let text_row = area.y + 1;
+    for (i, toast) in app.toasts.newest_first().enumerate() {
+        let Some(y) = text_row.checked_sub(i as u16) else {
+            break;
+        };
+        let color = match toast.severity {
+            ToastSeverity::Success => TOXIC_GREEN,
+            ToastSeverity::Info => BONE_WHITE,
+            ToastSeverity::Warning => PUMPKIN_ORANGE,
+            ToastSeverity::Error => BLOOD_RED,
+        };
+        let line = Line::from(Span::styled(
+            format!(" {} {} ", toast.severity.icon(), toast.text),
+            Style::default()
+                .fg(color)
+                .bg(Color::Black)
+                .add_modifier(Modifier::BOLD),
+        ));
+        let width = (line.width() as u16).min(area.width.saturating_sub(2));
+        let toast_area = Rect::new(area.right().saturating_sub(width + 1), y, width, 1);
+        f.render_widget(Clear, toast_area);
+        f.render_widget(Paragraph::new(line), toast_area);
+    }
+}
+
 pub fn render_status_bar(f: &mut Frame, area: Rect, app: &AppState) {
+    // The filter prompt takes over the whole bar while it is open
+    if let Some(ref input) = app.filter_prompt {
+        render_filter_prompt(f, area, input, app.prompt_kind, app.filter_error.as_deref());
+        return;
+    }
+
     // Determine mode-specific hint text
     let mode_hint = match app.graveyard_mode {
         GraveyardMode::Host => "Focus Process | ",
@@ -22,6 +60,9 @@ pub fn render_status_bar(f: &mut Frame, area: Rect, app: &AppState) {
     // Calculate available width for hints (subtract borders and icon)
     let available_width = area.width.saturating_sub(4);
 
+    // The vim keymap moves navigation and grouping
+    let vim = app.keymap == KeymapProfile::Vim;
+
     // Define all hints with priority levels
     struct Hint {
         priority: u8,
@@ -39,7 +80,7 @@ pub fn render_status_bar(f: &mut Frame, area: Rect, app: &AppState) {
         },
         Hint {
             priority: 1,
-            key: "↑↓:",
+            key: if vim { "jk:" } else { "↑↓:" },
             desc: "Navigate | ".to_string(),
             color: NEON_PURPLE,
         },
@@ -51,7 +92,13 @@ pub fn render_status_bar(f: &mut Frame, area: Rect, app: &AppState) {
         },
         Hint {
             priority: 2,
-            key: "+/-:",
+            key: "/:",
+            desc: "Filter | ".to_string(),
+            color: NEON_PURPLE,
+        },
+        Hint {
+            priority: 2,
+            key: "=/-:",
             desc: "Speed | ".to_string(),
             color: NEON_PURPLE,
         },
@@ -73,6 +120,156 @@ pub fn render_status_bar(f: &mut Frame, area: Rect, app: &AppState) {
             desc: "Labels | ".to_string(),
             color: NEON_PURPLE,
         },
+        Hint {
+            priority: 3,
+            key: "Y:",
+            desc: "Yank | ".to_string(),
+            color: NEON_PURPLE,
+        },
+        Hint {
+            priority: 3,
+            key: "F:",
+            desc: "Mermaid | ".to_string(),
+            color: NEON_PURPLE,
+        },
+        Hint {
+            priority: 3,
+            key: ">:",
+            desc: "Screenshot | ".to_string(),
+            color: NEON_PURPLE,
+        },
+        Hint {
+            priority: 3,
+            key: "Space:",
+            desc: "Pause ←→ | ".to_string(),
+            color: NEON_PURPLE,
+        },
+        Hint {
+            priority: 3,
+            key: "M/V:",
+            desc: "Mark/Diff | ".to_string(),
+            color: NEON_PURPLE,
+        },
+        Hint {
+            priority: 3,
+            key: if vim { "Z:" } else { "G:" },
+            desc: "Group | ".to_string(),
+            color: NEON_PURPLE,
+        },
+        Hint {
+            priority: 3,
+            key: "S:",
+            desc: "Split | ".to_string(),
+            color: NEON_PURPLE,
+        },
+        Hint {
+            priority: 3,
+            key: ",:",
+            desc: "Settings | ".to_string(),
+            color: NEON_PURPLE,
+        },
+        Hint {
+            priority: 3,
+            key: "R:",
+            desc: "Ports | ".to_string(),
+            color: NEON_PURPLE,
+        },
+        Hint {
+            priority: 3,
+            key: "O:",
+            desc: "Séance | ".to_string(),
+            color: NEON_PURPLE,
+        },
+        Hint {
+            priority: 3,
+            key: "W:",
+            desc: "Watch | ".to_string(),
+            color: NEON_PURPLE,
+        },
+        Hint {
+            priority: 3,
+            key: "X:",
+            desc: "Actions | ".to_string(),
+            color: NEON_PURPLE,
+        },
+        Hint {
+            priority: 3,
+            key: "n/N:",
+            desc: "Tag | ".to_string(),
+            color: NEON_PURPLE,
+        },
+        Hint {
+            priority: 3,
+            key: "u:",
+            desc: "Undo | ".to_string(),
+            color: NEON_PURPLE,
+        },
+        Hint {
+            priority: 3,
+            key: ".:",
+            desc: "Mark | ".to_string(),
+            color: NEON_PURPLE,
+        },
+        Hint {
+            priority: 3,
+            key: "o:",
+            desc: "Pin | ".to_string(),
+            color: NEON_PURPLE,
+        },
+        Hint {
+            priority: 3,
+            key: "C:",
+            desc: "Children | ".to_string(),
+            color: NEON_PURPLE,
+        },
+        Hint {
+            priority: 3,
+            key: "U:",
+            desc: "User | ".to_string(),
+            color: NEON_PURPLE,
+        },
+        Hint {
+            priority: 3,
+            key: "D:",
+            desc: "Container | ".to_string(),
+            color: NEON_PURPLE,
+        },
+        Hint {
+            priority: 3,
+            key: "I:",
+            desc: "Iface | ".to_string(),
+            color: NEON_PURPLE,
+        },
+        Hint {
+            priority: 3,
+            key: "Tab:",
+            desc: "Events | ".to_string(),
+            color: NEON_PURPLE,
+        },
+        Hint {
+            priority: 3,
+            key: "B:",
+            desc: "Backend | ".to_string(),
+            color: NEON_PURPLE,
+        },
+        Hint {
+            priority: 3,
+            key: "!:",
+            desc: "Diagnostics | ".to_string(),
+            color: NEON_PURPLE,
+        },
+        Hint {
+            priority: 3,
+            key: "?:",
+            desc: "Legend | ".to_string(),
+            color: NEON_PURPLE,
+        },
+        Hint {
+            priority: 3,
+            key: "%:",
+            desc: "Perf | ".to_string(),
+            color: NEON_PURPLE,
+        },
         Hint {
             priority: 3,
             key: "F1:",
@@ -103,6 +300,49 @@ pub fn render_status_bar(f: &mut Frame, area: Rect, app: &AppState) {
         }
     }
 
+    // Diff baseline: "[Δ 14:02:11→live]" (highlighted while the diff view is on)
+    if let Some(ref baseline) = app.baseline {
+        let target = app
+            .comparison
+            .as_ref()
+            .map_or_else(|| "live".to_string(), |s| s.time_label());
+        let color = if app.diff_mode {
+            PUMPKIN_ORANGE
+        } else {
+            BONE_WHITE
+        };
+        spans.push(Span::styled(
+            format!("[Δ {}→{}] ", baseline.time_label(), target),
+            Style::default().fg(color).add_modifier(Modifier::BOLD),
+        ));
+    }
+
+    // Active filter expression
+    if let Some(ref expression) = app.connection_filter.expression {
+        spans.push(Span::styled("[/ ", Style::default().fg(BONE_WHITE)));
+        spans.push(Span::styled(
+            expression.source().to_string(),
+            Style::default()
+                .fg(TOXIC_GREEN)
+                .add_modifier(Modifier::BOLD),
+        ));
+        spans.push(Span::styled("] ", Style::default().fg(BONE_WHITE)));
+    }
+
+    // Data age: "[data: 2s ago]", Pumpkin Orange once the snapshot is
+    // overdue and Blood Red once it is well past due
+    if let Some((age, freshness)) = app.data_age() {
+        let color = match freshness {
+            DataFreshness::Fresh => BONE_WHITE,
+            DataFreshness::Late => PUMPKIN_ORANGE,
+            DataFreshness::Stale => BLOOD_RED,
+        };
+        spans.push(Span::styled(
+            format!("[data: {} ago] ", age_label(age)),
+            Style::default().fg(color),
+        ));
+    }
+
     // Add toggle status indicators (always show, they're important for debugging)
     let toggle_indicators = build_toggle_indicators(app);
     spans.push(Span::raw(" "));
@@ -122,22 +362,93 @@ pub fn render_status_bar(f: &mut Frame, area: Rect, app: &AppState) {
     f.render_widget(status_bar, area);
 }
 
+/// Render the '/' filter, 'w' watch, '#' jump or event search prompt: "/ <input>▏"
+/// plus the last parse error
+fn render_filter_prompt(
+    f: &mut Frame,
+    area: Rect,
+    input: &str,
+    kind: PromptKind,
+    error: Option<&str>,
+) {
+    let (marker, example) = match kind {
+        PromptKind::Filter => (" / ", "e.g. state == established && port != 22"),
+        PromptKind::Watch => (" 👁 ", "e.g. count(proc == postgres) > 90"),
+        PromptKind::Jump => (
+            " # ",
+            "PID or port, :port for ports only, ! to focus the process",
+        ),
+        PromptKind::EventSearch => (" 📖/ ", "text in event messages, empty clears"),
+    };
+    let mut spans = vec![
+        Span::styled(
+            marker,
+            Style::default()
+                .fg(NEON_PURPLE)
+                .add_modifier(Modifier::BOLD),
+        ),
+        Span::styled(input.to_string(), Style::default().fg(BONE_WHITE)),
+        Span::styled("▏", Style::default().fg(TOXIC_GREEN)),
+    ];
+    match error {
+        Some(error) => spans.push(Span::styled(
+            format!("  ✖ {}", error),
+            Style::default().fg(BLOOD_RED).add_modifier(Modifier::BOLD),
+        )),
+        None => spans.push(Span::styled(
+            format!("  Enter: apply  Esc: cancel  {}", example),
+            Style::default().fg(Color::DarkGray),
+        )),
+    }
+
+    let prompt = Paragraph::new(Line::from(spans)).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Double)
+            .border_style(Style::default().fg(NEON_PURPLE)),
+    );
+    f.render_widget(prompt, area);
+}
+
+/// Render the watch bar: "[👁 pg-conns 95 > 90]" per watch, firing ones in
+/// Blood Red
+pub fn render_watch_bar(f: &mut Frame, area: Rect, app: &AppState) {
+    let mut spans = vec![Span::styled(" 👁 ", Style::default().fg(NEON_PURPLE))];
+    for watch in &app.watches {
+        let color = if watch.firing { BLOOD_RED } else { TOXIC_GREEN };
+        spans.push(Span::styled("[", Style::default().fg(BONE_WHITE)));
+        spans.push(Span::styled(
+            format!("{} ", watch.name),
+            Style::default().fg(BONE_WHITE),
+        ));
+        spans.push(Span::styled(
+            watch.condition(),
+            Style::default().fg(color).add_modifier(Modifier::BOLD),
+        ));
+        spans.push(Span::styled("] ", Style::default().fg(BONE_WHITE)));
+    }
+    f.render_widget(Paragraph::new(Line::from(spans)), area);
+}
+
 /// Build toggle status indicator spans for the status bar
-/// Shows [A:ON/OFF] [H:ON/OFF] [t:ON/OFF] with appropriate colors
-/// Toxic Green for ON, Bone White for OFF
+/// Shows [A:<quality>/OFF] [H:ON/OFF] [t:ON/OFF] with appropriate colors
+/// Toxic Green for ON, Bone White for OFF; the animation tier turns
+/// Pumpkin Orange while slow frames have reduced it
 pub fn build_toggle_indicators(app: &AppState) -> Vec<Span<'static>> {
     let mut spans = Vec::new();
 
-    // Animation toggle [A:ON/OFF]
+    // Animation toggle [A:<quality>/OFF]
     let anim_state = if app.graveyard_settings.animations_enabled {
-        "ON"
+        app.graveyard_settings.animation_quality.label()
     } else {
         "OFF"
     };
-    let anim_color = if app.graveyard_settings.animations_enabled {
-        TOXIC_GREEN
-    } else {
+    let anim_color = if !app.graveyard_settings.animations_enabled {
         BONE_WHITE
+    } else if app.perf.reduction.is_some() {
+        PUMPKIN_ORANGE
+    } else {
+        TOXIC_GREEN
     };
     spans.push(Span::styled("[A:", Style::default().fg(BONE_WHITE)));
     spans.push(Span::styled(
@@ -197,9 +508,44 @@ pub fn build_toggle_indicators(app: &AppState) -> Vec<Span<'static>> {
     spans.push(Span::styled("[E:", Style::default().fg(BONE_WHITE)));
     spans.push(Span::styled(
         offset_str,
-        Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD),
+        Style::default()
+            .fg(Color::Cyan)
+            .add_modifier(Modifier::BOLD),
     ));
     spans.push(Span::styled("]", Style::default().fg(BONE_WHITE)));
 
+    spans.push(Span::raw(" "));
+    spans.extend(build_backend_indicator(app));
+
     spans
 }
+
+/// Build the backend health indicator: [netstat2 12ms ?3]
+/// "?N" counts sockets without an owning process; the indicator turns
+/// Pumpkin Orange when any socket is unattributed or errors were recorded
+pub fn build_backend_indicator(app: &AppState) -> Vec<Span<'static>> {
+    let health = &app.backend_health;
+    let degraded = health.unattributed > 0 || health.error_count() > 0;
+    let color = if degraded {
+        PUMPKIN_ORANGE
+    } else {
+        TOXIC_GREEN
+    };
+
+    let mut text = health.backend.to_string();
+    if let Some(duration) = health.last_duration {
+        text.push_str(&format!(" {}ms", duration.as_millis()));
+    }
+    if health.unattributed > 0 {
+        text.push_str(&format!(" ?{}", health.unattributed));
+    }
+
+    vec![
+        Span::styled("[", Style::default().fg(BONE_WHITE)),
+        Span::styled(
+            text,
+            Style::default().fg(color).add_modifier(Modifier::BOLD),
+        ),
+        Span::styled("]", Style::default().fg(BONE_WHITE)),
+    ]
+}
@@ -0,0 +1,101 @@
+// Watchlist panel rendering module
+//
+// Compact panel between the Soul Inspector and the Grimoire listing pinned
+// endpoints, tagged connections and watches with their current status.
+// It is only drawn while the watchlist has entries.
+
+use crate::app::watchlist::WatchlistEntry;
+use crate::theme::{BLOOD_RED, BONE_WHITE, NEON_PURPLE, TOXIC_GREEN};
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Borders, Paragraph},
+    Frame,
+};
+
+/// Most rows the panel takes (including borders); longer lists are cut
+pub const WATCHLIST_MAX_HEIGHT: u16 = 8;
+
+/// Rows the panel needs for `entries` (0 = not drawn)
+pub fn watchlist_height(entries: &[WatchlistEntry]) -> u16 {
+    if entries.is_empty() {
+        0
+    } else {
+        (entries.len() as u16 + 2).min(WATCHLIST_MAX_HEIGHT)
+    }
+}
+
+pub fn render_watchlist(f: &mut Frame, area: Rect, entries: &[WatchlistEntry]) {
+    let gone = Style::default().fg(Color::DarkGray);
+    let lines: Vec<Line> = entries
+        .iter()
+        .map(|entry| match entry {
+            WatchlistEntry::Pinned { addr, connections } => {
+                let status = if *connections == 0 {
+                    Span::styled(" gone", gone)
+                } else {
+                    Span::styled(
+                        format!(" ×{}", connections),
+                        Style::default().fg(TOXIC_GREEN),
+                    )
+                };
+                Line::from(vec![
+                    Span::styled(format!(" 📌 {}", addr), Style::default().fg(BONE_WHITE)),
+                    status,
+                ])
+            }
+            WatchlistEntry::Tagged {
+                target,
+                tags,
+                state,
+            } => {
+                let mut spans = vec![Span::styled(
+                    format!(" 🏷 {}", target),
+                    Style::default().fg(BONE_WHITE),
+                )];
+                for tag in tags {
+                    spans.push(Span::styled(
+                        format!(" [{}]", tag.label()),
+                        Style::default()
+                            .fg(tag.color())
+                            .add_modifier(Modifier::BOLD),
+                    ));
+                }
+                spans.push(match state {
+                    Some(state) => Span::styled(format!(" {}", state.name()), gone),
+                    None => Span::styled(" gone", gone),
+                });
+                Line::from(spans)
+            }
+            WatchlistEntry::Watch {
+                name,
+                condition,
+                firing,
+            } => {
+                let color = if *firing { BLOOD_RED } else { TOXIC_GREEN };
+                Line::from(vec![
+                    Span::styled(format!(" 👁 {} ", name), Style::default().fg(BONE_WHITE)),
+                    Span::styled(
+                        condition.clone(),
+                        Style::default().fg(color).add_modifier(Modifier::BOLD),
+                    ),
+                ])
+            }
+        })
+        .collect();
+
+    let panel = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(NEON_PURPLE))
+            .title(Span::styled(
+                format!(" 📌 Watchlist ({}) ", entries.len()),
+                Style::default()
+                    .fg(NEON_PURPLE)
+                    .add_modifier(Modifier::BOLD),
+            )),
+    );
+    f.render_widget(panel, area);
+}